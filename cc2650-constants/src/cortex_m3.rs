@@ -101,7 +101,8 @@ pub mod MPU {
     }
 
     impl TEX_C_B {
-        fn from_bits(tex: u8, c: u8, b: u8, s: bool) -> Self {
+        #[must_use]
+        pub fn from_bits(tex: u8, c: u8, b: u8, s: bool) -> Self {
             let (memory_type, sharability, cache_inner, cache_outer) = match (tex, c, b) {
                 (0b000, 0, 0) => (
                     MemoryType::StronglyOrdered,
@@ -163,6 +164,16 @@ pub mod MPU {
                 cache_outer,
             }
         }
+
+        #[must_use]
+        pub fn memory_type(&self) -> MemoryType {
+            self.memory_type
+        }
+
+        #[must_use]
+        pub fn inner_cache_policy(&self) -> CachePolicy {
+            self.cache_inner
+        }
     }
 
     // [ARM-TRM-G] Table 4-2 Memory region permissions
@@ -199,7 +210,8 @@ pub mod MPU {
 
     impl XN {
         /// [ARM-TRM-G] Note below Table 4-2
-        fn is_region_permanently_xn(region: CoreMemoryMap) -> bool {
+        #[must_use]
+        pub fn is_region_permanently_xn(region: CoreMemoryMap) -> bool {
             matches!(region, CoreMemoryMap::PPB(_) | CoreMemoryMap::Vendor)
         }
     }
@@ -226,6 +238,7 @@ pub mod MPU {
 pub mod CoreMap {
     use cmemu_common::{Address, address_match_range, address_match_range_exhaustive};
     use core::ops::{Range, RangeInclusive};
+    use serde::{Deserialize, Serialize};
 
     /// Memory map as seen by the Core
     /// [ARM-TRM-G] 4.1 About the memory map (right side)
@@ -329,7 +342,7 @@ pub mod CoreMap {
         }
     }
 
-    #[derive(Debug, Copy, PartialEq, Clone)]
+    #[derive(Debug, Copy, PartialEq, Clone, Serialize, Deserialize)]
     #[non_exhaustive]
     pub enum PPBRegion {
         ITM,
@@ -408,6 +421,8 @@ pub mod CoreMap {
 pub mod operation {
     use std::fmt;
 
+    use serde::{Deserialize, Serialize};
+
     /// [ARM-ARM] B1.3.1 Modes, privilege and stacks
     #[derive(Debug, Clone, Copy, Eq, PartialEq)]
     #[allow(clippy::exhaustive_enums)]
@@ -419,7 +434,7 @@ pub mod operation {
     /// [ARM-ARM] B1.3.1 Modes, privilege and stacks
     /// B1.4.1 The ARM core registers/ The SP registers
     /// Banked stack pointer.
-    #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+    #[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
     #[allow(clippy::exhaustive_enums)]
     pub enum StackPointer {
         /// Note: Handler mode always uses Main
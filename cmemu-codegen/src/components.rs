@@ -220,22 +220,22 @@ fn build_data_enums(
     features: &Vec<TokenStream>,
 ) -> TokenStream {
     let types = quote! {
-        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, enum_map::Enum, strum::IntoStaticStr)]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, enum_map::Enum, strum::IntoStaticStr, serde::Serialize, serde::Deserialize)]
         #[non_exhaustive]
         pub(crate) enum Components {
             #(#component_types,)*
         }
-        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, enum_map::Enum, strum::IntoStaticStr)]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, enum_map::Enum, strum::IntoStaticStr, serde::Serialize, serde::Deserialize)]
         #[non_exhaustive]
         pub(crate) enum ClockTreeNodes {
             #(#clock_types,)*
         }
-        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, enum_map::Enum, strum::IntoStaticStr)]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, enum_map::Enum, strum::IntoStaticStr, serde::Serialize, serde::Deserialize)]
         #[non_exhaustive]
         pub(crate) enum Oscillators {
             #(#oscillators,)*
         }
-        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, enum_map::Enum, strum::IntoStaticStr)]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, enum_map::Enum, strum::IntoStaticStr, serde::Serialize, serde::Deserialize)]
         #[non_exhaustive]
         pub(crate) enum EnergyEntity {
             Component(Components),
@@ -283,8 +283,13 @@ fn generate_component_mod(comp_idx: &BuildMetadata) -> String {
     );
     let not_special = |&c: &&ComponentDesc| -> bool { !c.is_special };
     let fields = format_components!(comp_idx(not_special), |c| format!(
-        "{} pub(crate) {}: {},",
+        "{} {} pub(crate) {}: {},",
         format_cfg_feature_if_present(&c.config),
+        if c.config.requires_feature.is_some() {
+            format!("#[serde(skip, default = \"{}::new\")]", c.config.mod_path)
+        } else {
+            String::new()
+        },
         c.config.field_name,
         c.config.mod_path
     ));
@@ -314,6 +319,7 @@ fn generate_component_mod(comp_idx: &BuildMetadata) -> String {
     format!(
         "// Auto generated by build script.
 
+        #[derive(Clone, serde::Serialize, serde::Deserialize)]
         pub(crate) struct Components {{ {fields} }}
 
         impl Components {{
@@ -332,8 +338,9 @@ fn generate_event_data_and_proxy_mod(comp_idx: &BuildMetadata) -> String {
         format_components_handlers!(comp_idx, |c: &ComponentDesc, h: &HandlerDesc| -> String {
             match h.generator {
                 HandlerKind::ClockTree | HandlerKind::Auto => format!(
-                    "{} {}(super::proxy_{}::{}),",
+                    "{} {} {}(super::proxy_{}::{}),",
                     format_cfg_feature_if_present(&c.config),
+                    format_serde_skip_if_feature_gated(&c.config),
                     event_data_variant_name(c, h),
                     c.config.field_name,
                     event_data_variant_payload_type_name(c, h)
@@ -353,7 +360,7 @@ fn generate_event_data_and_proxy_mod(comp_idx: &BuildMetadata) -> String {
         #[allow(dead_code)]
         pub(crate) const HANDLERS_COUNT: usize = {handlers_count};
 
-        #[derive(Debug)]
+        #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
         pub(crate) enum EventData {{
             #[allow(unused)]
             Nop,
@@ -414,7 +421,12 @@ fn format_proxy(component: &ComponentDesc) -> String {
     );
     let event_data_payload_structs = format_handlers!(component, |h| match h.generator {
         HandlerKind::ClockTree | HandlerKind::Auto => format!(
-            "#[derive(Debug)] pub(crate) struct {}({});",
+            "#[derive(Debug, Clone{})] pub(crate) struct {}({});",
+            if component.config.requires_feature.is_some() {
+                ""
+            } else {
+                ", serde::Serialize, serde::Deserialize"
+            },
             event_data_variant_payload_type_name(component, h),
             h.fields
                 .iter()
@@ -437,7 +449,7 @@ fn format_proxy(component: &ComponentDesc) -> String {
             #[allow(unused_imports)]
             use {mod_path};
 
-            #[derive(Default)]
+            #[derive(Default, Clone, serde::Serialize, serde::Deserialize)]
             pub(crate) struct {proxy_name};
 
             #[allow(clippy::used_underscore_binding)] // we use all bindings and use same names as user
@@ -538,6 +550,16 @@ fn format_cfg_feature_if_present(config: &ComponentConf) -> String {
     format_cfg_feature(config.requires_feature.as_ref())
 }
 
+/// Feature-gated components are debugging aids observing the emulation, so they are left out
+/// of a saved snapshot. Their events are always immediate, thus never pending between cycles.
+fn format_serde_skip_if_feature_gated(config: &ComponentConf) -> &'static str {
+    if config.requires_feature.is_some() {
+        "#[serde(skip)]"
+    } else {
+        ""
+    }
+}
+
 fn format_cfg_feature(required_feature: Option<&String>) -> String {
     if let Some(feature) = required_feature {
         format!("#[cfg(feature = \"{feature}\")]")
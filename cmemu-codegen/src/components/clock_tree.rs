@@ -271,7 +271,7 @@ pub(super) fn generate_clock_graph(comp_idx: &BuildMetadata) -> String {
         use crate::engine::*;
         use crate::build_data;
 
-        #[derive(Subcomponent, )]
+        #[derive(Subcomponent, Clone, serde::Serialize, serde::Deserialize)]
         #[subcomponent_1to1]
         pub(super) struct Nodes {{
             {nodes}
@@ -193,17 +193,22 @@ fn generate_subcomponent(
                 // and the rest will work!
                 // TODO: consider impling full Path-ed types
             } else if is_generic {
-                // We implement default here, to make it possible to derive Default
+                // We implement default (and clone) here, to make it possible to derive Default
                 result.extend(quote! {
+                #[derive(::serde::Serialize, ::serde::Deserialize)]
+                #[serde(bound = "")]
                 #its_vis struct #its_name #ty_generics(#(::std::marker::PhantomData<#phantom_generics>),*);
                 impl #impl_generics Default for #its_name #ty_generics {
                         fn default() -> Self {Self(#(::std::marker::PhantomData::<#phantom_generics>),*)}
                     }
+                impl #impl_generics Clone for #its_name #ty_generics {
+                        fn clone(&self) -> Self {Self(#(::std::marker::PhantomData::<#phantom_generics>),*)}
+                    }
                 impl #impl_generics crate::engine::PureSubcomponentMarker for #its_name #ty_generics {}
                     });
             } else {
                 result.extend(quote! {
-                #[derive(Default)]
+                #[derive(Default, Clone, ::serde::Serialize, ::serde::Deserialize)]
                 #its_vis struct #its_name;
                 impl crate::engine::PureSubcomponentMarker for #its_name {}
                     });
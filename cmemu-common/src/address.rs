@@ -1,8 +1,9 @@
 use core::ops::{Range, RangeBounds, RangeInclusive};
+use serde::{Deserialize, Serialize};
 use std::collections::Bound;
 use std::fmt;
 
-#[derive(Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct Address(u32);
 
 impl Address {
@@ -345,7 +346,8 @@ pub mod const_hax {
 
     product! {range_checker_product, [
     Range<Address>, RangeInclusive<Address>, RangeFrom<Address>, RangeTo<Address>, RangeToInclusive<Address>,
-    RangeUnion<'_, Range<Address>, Range<Address>>
+    RangeUnion<'_, Range<Address>, Range<Address>>,
+    RangeUnion<'_, RangeUnion<'_, Range<Address>, Range<Address>>, Range<Address>>
     ]}
 }
 
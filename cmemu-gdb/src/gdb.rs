@@ -10,7 +10,9 @@ use cmemu_lib::engine::{Emulator, EmulatorError};
 use flexi_logger::{LogSpecBuilder, LoggerHandle};
 use gdbstub::common::{Pid, Signal};
 use gdbstub::target::ext::base::BaseOps;
-use gdbstub::target::ext::base::reverse_exec::{ReverseContOps, ReverseStepOps};
+use gdbstub::target::ext::base::reverse_exec::{
+    ReverseCont, ReverseContOps, ReverseStep, ReverseStepOps,
+};
 use gdbstub::target::ext::base::single_register_access::{
     SingleRegisterAccess, SingleRegisterAccessOps,
 };
@@ -43,6 +45,7 @@ mod monitor;
 use monitor::MonitorOptions;
 #[cfg(feature = "flash-test-lib")]
 mod flash_test_lib;
+mod reverse;
 
 // TODO: rethink whether we should take mut ref to the old emulator
 pub(super) type EmulatorResetHook = dyn Fn(&DebugMonitor, &mut Emulator) -> DynResult<()>;
@@ -62,6 +65,8 @@ pub(crate) struct DebugMonitor {
     our_cycle_number: u64,
     options: MonitorOptions,
     exec_mode: ExecMode,
+    /// Snapshots for the reverse execution
+    checkpoints: reverse::Checkpoints,
 
     // breaks
     cycle_timout: Option<u64>,
@@ -88,6 +93,8 @@ enum ExecMode {
     StepInstruction,
     StepCycle,
     RangeStep(Range<Address>),
+    ReverseStep,
+    ReverseContinue,
 }
 
 // helper functions
@@ -117,6 +124,7 @@ impl DebugMonitor {
             cycle_timout,
             options: MonitorOptions::default(),
             exec_mode: ExecMode::Halted,
+            checkpoints: reverse::Checkpoints::default(),
             cycle_breakpoints: VecDeque::new(),
             breakpoints: HashSet::new(),
             traps: HashSet::new(),
@@ -147,6 +155,7 @@ impl DebugMonitor {
         // FIXME: uart lite / modem services
         // drop the stored panic if possible
         self.our_cycle_number = 0;
+        self.checkpoints.clear();
         self.post_mortem = None;
         Ok(())
     }
@@ -178,6 +187,12 @@ impl DebugMonitor {
         if self.ctrl_c_flag.load(Ordering::Relaxed) {
             self.ctrl_c_flag.store(false, Ordering::Relaxed);
             return Ok(Some(StopReason::Signal(Signal::SIGINT)));
+        } else if matches!(
+            self.exec_mode,
+            ExecMode::ReverseStep | ExecMode::ReverseContinue
+        ) {
+            // This may take a while, but we check Ctrl-C inside
+            return Ok(Some(self.run_backwards()));
         } else if self.post_mortem.is_some() {
             return Ok(Some(StopReason::Terminated(Signal::SIGKILL)));
         }
@@ -186,6 +201,8 @@ impl DebugMonitor {
             return Ok(Some(StopReason::DoneStep));
         }
 
+        self.checkpoints
+            .maybe_take(self.our_cycle_number, &self.emu);
         self.our_cycle_number += 1; // overflow in ages
         let step_error = std::panic::catch_unwind(AssertUnwindSafe(|| self.emu.step_cycle()));
         if let Err(panic) = step_error {
@@ -246,13 +263,8 @@ impl DebugMonitor {
                 return Ok(Some(StopReason::DoneStep));
             }
 
-            if self.breakpoints.contains(&instr_addr) {
-                trace!("Breakpoint hit for {instr_addr:#x?}");
-                return Ok(Some(StopReason::SwBreak(())));
-            }
-            if self.traps.contains(&instr_addr) {
-                trace!("Trap hit for {instr_addr:#x?}");
-                return Ok(Some(StopReason::Signal(Signal::SIGEMT)));
+            if let Some(reason) = self.check_code_breakpoints() {
+                return Ok(Some(reason));
             }
         }
 
@@ -277,6 +289,30 @@ impl DebugMonitor {
         // Note: watchpoints should probably be under "current_instruction_changed",
         // but that would miss accesses by LDM etc.
         // However, GDB does a single step after a watchpoint - is it our fault?
+        if let Some(reason) = self.check_watchpoints() {
+            return Ok(Some(reason));
+        }
+
+        // No event, call again
+        Ok(None)
+    }
+
+    /// Check the (software) breakpoints and traps. Call only if the instruction changed.
+    fn check_code_breakpoints(&self) -> Option<StopReason> {
+        let instr_addr = self.emu.get_current_instruction_address();
+        if self.breakpoints.contains(&instr_addr) {
+            trace!("Breakpoint hit for {instr_addr:#x?}");
+            return Some(StopReason::SwBreak(()));
+        }
+        if self.traps.contains(&instr_addr) {
+            trace!("Trap hit for {instr_addr:#x?}");
+            return Some(StopReason::Signal(Signal::SIGEMT));
+        }
+        None
+    }
+
+    #[cfg_attr(not(feature = "cdl"), allow(clippy::unused_self))]
+    fn check_watchpoints(&self) -> Option<StopReason> {
         #[cfg(feature = "cdl")]
         if let Some(lsu_transfer) = self.emu.peek_core_lsu_request().as_ref() {
             use log::info;
@@ -301,19 +337,17 @@ impl DebugMonitor {
                     // OR it cannot read the memory!
                     if *kind == WatchKind::Write && self.options.write_watchpoint_is_trap {
                         info!("Write Watchpoint upgraded to a trap!");
-                        return Ok(Some(StopReason::Signal(Signal::SIGTRAP)));
+                        return Some(StopReason::Signal(Signal::SIGTRAP));
                     }
-                    return Ok(Some(StopReason::Watch {
+                    return Some(StopReason::Watch {
                         tid: (),
                         kind: *kind,
                         addr: addr_intersect.start.into(),
-                    }));
+                    });
                 }
             }
         }
-
-        // No event, call again
-        Ok(None)
+        None
     }
 
     fn request_mode_change(
@@ -404,7 +438,11 @@ impl SingleThreadBase for DebugMonitor {
     fn write_addrs(&mut self, start_addr: EmuUsize, data: &[u8]) -> TargetResult<(), Self> {
         trace!("Writing memory at {start_addr:#x}/{}b", data.len());
         match self.emu.write_memory(start_addr.into(), data) {
-            Ok(()) => Ok(()),
+            Ok(()) => {
+                self.checkpoints
+                    .rewrite_from(self.our_cycle_number, &self.emu);
+                Ok(())
+            }
             Err(EmulatorError::InvalidAddress) => Err(TargetError::Errno(EFAULT as u8)),
             Err(e) => Err(TargetError::Fatal(e.into())),
         }
@@ -479,13 +517,26 @@ impl SingleThreadResume for DebugMonitor {
 
     #[inline(always)]
     fn support_reverse_step(&mut self) -> Option<ReverseStepOps<'_, (), Self>> {
-        // TODO: walk cdl frames
-        None
+        Some(self)
     }
 
     #[inline(always)]
     fn support_reverse_cont(&mut self) -> Option<ReverseContOps<'_, (), Self>> {
-        None
+        Some(self)
+    }
+}
+
+impl ReverseStep<()> for DebugMonitor {
+    fn reverse_step(&mut self, _tid: ()) -> Result<(), Self::Error> {
+        trace!("Reverse step");
+        self.request_mode_change(ExecMode::ReverseStep, None)
+    }
+}
+
+impl ReverseCont<()> for DebugMonitor {
+    fn reverse_cont(&mut self) -> Result<(), Self::Error> {
+        trace!("Reverse continue");
+        self.request_mode_change(ExecMode::ReverseContinue, None)
     }
 }
 
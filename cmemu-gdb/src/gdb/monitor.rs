@@ -51,6 +51,12 @@ enum Commands {
     #[command(visible_alias = "bc")]
     /// Add a breakpoint on a cycle number
     BreakCycle { cycle: u64 },
+    #[command(visible_alias = "cp")]
+    /// Show the checkpoints used for reverse execution
+    ///
+    /// The checkpoints are taken every `interval` cycles while running forward.
+    /// A smaller interval makes going back faster, but uses more memory.
+    Checkpoints { interval: Option<u64> },
 
     /// Get AGU value
     Agu,
@@ -339,6 +345,13 @@ impl DebugMonitor {
         out.flush();
         outputln!(out, "Cycle breakpoints: {:#?}", self.cycle_breakpoints);
         out.flush();
+        outputln!(
+            out,
+            "Checkpoints (every {} cycles): {:?}",
+            self.checkpoints.interval(),
+            self.checkpoints.cycles().collect::<Vec<_>>()
+        );
+        out.flush();
         outputln!(out, "Extra traps: {:#?}", self.traps);
         outputln!(out);
         out.flush();
@@ -371,6 +384,28 @@ impl DebugMonitor {
         }
     }
 
+    fn configure_checkpoints(
+        &mut self,
+        interval: Option<u64>,
+        out: &mut ConsoleOutput<'_>,
+    ) -> Result<(), MonitorError> {
+        match interval {
+            None => {
+                outputln!(out, "Interval: {} cycles", self.checkpoints.interval());
+                outputln!(
+                    out,
+                    "Taken at cycles: {:?}",
+                    self.checkpoints.cycles().collect::<Vec<_>>()
+                );
+            }
+            Some(0) => Err(MonitorError::Runtime(
+                "The interval must be positive".to_owned(),
+            ))?,
+            Some(interval) => self.checkpoints.set_interval(interval),
+        }
+        Ok(())
+    }
+
     fn set_timeout(
         &mut self,
         cycle: Option<u64>,
@@ -551,6 +586,9 @@ impl DebugMonitor {
             Commands::BreakCycle { cycle } => {
                 self.add_breakpoint_on_cycle(cycle, out)?;
             }
+            Commands::Checkpoints { interval } => {
+                self.configure_checkpoints(interval, out)?;
+            }
             Commands::Agu => {
                 outputln!(
                     out,
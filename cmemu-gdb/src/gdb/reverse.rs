@@ -0,0 +1,378 @@
+//! Reverse execution (`reverse-stepi`, `reverse-continue`, etc.).
+//!
+//! We don't record the history. Instead, snapshots of the emulator are taken periodically
+//! while running forward, and a past state is reached by restoring the nearest earlier snapshot
+//! and re-executing the emulator, which is deterministic.
+//!
+//! Keep in mind that host-side effects of the guest (e.g., UART output) are repeated
+//! during re-execution, and that memory writes done by the debugger become a part of the history
+//! only from the cycle they were done at.
+use super::{DebugMonitor, ExecMode};
+use crate::StopReason;
+use cmemu_lib::engine::{Emulator, EmulatorSnapshot};
+use gdbstub::common::Signal;
+use gdbstub::target::ext::base::reverse_exec::ReplayLogPosition;
+use log::{debug, trace};
+use std::mem;
+use std::sync::atomic::Ordering;
+
+/// Above this number of checkpoints, every other one is dropped and the interval is doubled.
+const MAX_CHECKPOINTS: usize = 64;
+const DEFAULT_INTERVAL: u64 = 100_000;
+
+/// Periodic snapshots of the emulator, sorted by their cycle number.
+pub(super) struct Checkpoints {
+    snapshots: Vec<(u64, EmulatorSnapshot)>,
+    interval: u64,
+}
+
+impl Default for Checkpoints {
+    fn default() -> Self {
+        Self {
+            snapshots: Vec::new(),
+            interval: DEFAULT_INTERVAL,
+        }
+    }
+}
+
+impl Checkpoints {
+    /// Take a snapshot if the last one is at least `interval` cycles old.
+    ///
+    /// It should be called before stepping the cycle `cycle + 1`.
+    pub(super) fn maybe_take(&mut self, cycle: u64, emu: &Emulator) {
+        if self
+            .snapshots
+            .last()
+            .is_some_and(|(last, _)| cycle < last + self.interval)
+        {
+            return;
+        }
+        trace!("Taking a checkpoint at cycle {cycle}");
+        self.snapshots.push((cycle, emu.snapshot()));
+
+        if self.snapshots.len() > MAX_CHECKPOINTS {
+            let mut idx = 0;
+            self.snapshots.retain(|_| {
+                idx += 1;
+                idx % 2 == 1
+            });
+            self.interval *= 2;
+            debug!(
+                "Too many checkpoints: thinned them out to every {} cycles",
+                self.interval
+            );
+        }
+    }
+
+    /// Replace the checkpoints at `cycle` and later, as the state was modified out of the history.
+    pub(super) fn rewrite_from(&mut self, cycle: u64, emu: &Emulator) {
+        self.snapshots.retain(|(c, _)| *c < cycle);
+        self.snapshots.push((cycle, emu.snapshot()));
+    }
+
+    pub(super) fn clear(&mut self) {
+        self.snapshots.clear();
+        self.interval = DEFAULT_INTERVAL;
+    }
+
+    pub(super) fn interval(&self) -> u64 {
+        self.interval
+    }
+
+    pub(super) fn set_interval(&mut self, interval: u64) {
+        self.interval = interval;
+    }
+
+    pub(super) fn cycles(&self) -> impl Iterator<Item = u64> + '_ {
+        self.snapshots.iter().map(|(c, _)| *c)
+    }
+
+    /// Index of the latest checkpoint with at least one cycle to execute before `cycle`.
+    fn latest_before(&self, cycle: u64) -> Option<usize> {
+        self.snapshots
+            .partition_point(|(c, _)| c + 1 < cycle)
+            .checked_sub(1)
+    }
+}
+
+impl DebugMonitor {
+    /// Go back to the latest past cycle at which the reverse execution mode should stop.
+    ///
+    /// The cycles are checked after being stepped, as in the forward execution.
+    /// If there is no such cycle, we stop at the first checkpoint.
+    pub(super) fn run_backwards(&mut self) -> StopReason {
+        let mode = mem::replace(&mut self.exec_mode, ExecMode::Halted);
+        let begin = StopReason::ReplayLog {
+            tid: None,
+            pos: ReplayLogPosition::Begin,
+        };
+        if self.checkpoints.snapshots.is_empty() {
+            return begin;
+        }
+        // We are going to restore a sane state.
+        self.post_mortem = None;
+
+        // Search for cycles in (checkpoint, upper), segment by segment.
+        let mut upper = self.cycle_number();
+        while let Some(idx) = self.checkpoints.latest_before(upper) {
+            self.restore_checkpoint(idx);
+            let mut found = None;
+            while self.cycle_number() + 1 < upper {
+                if self.ctrl_c_flag.swap(false, Ordering::Relaxed) {
+                    return StopReason::Signal(Signal::SIGINT);
+                }
+                self.replay_cycle();
+                if let Some(reason) = self.reverse_stop_reason(&mode) {
+                    found = Some((self.cycle_number(), reason));
+                }
+            }
+
+            if let Some((cycle, reason)) = found {
+                trace!("Going back to cycle {cycle} for {reason:?}");
+                self.restore_checkpoint(idx);
+                while self.cycle_number() < cycle {
+                    self.replay_cycle();
+                }
+                return reason;
+            }
+            upper = self.checkpoints.snapshots[idx].0 + 1;
+        }
+
+        self.restore_checkpoint(0);
+        begin
+    }
+
+    fn restore_checkpoint(&mut self, idx: usize) {
+        let (cycle, ref snapshot) = self.checkpoints.snapshots[idx];
+        self.emu.restore(snapshot);
+        self.our_cycle_number = cycle;
+    }
+
+    fn replay_cycle(&mut self) {
+        self.our_cycle_number += 1;
+        self.emu.step_cycle();
+    }
+
+    fn reverse_stop_reason(&self, mode: &ExecMode) -> Option<StopReason> {
+        match mode {
+            ExecMode::ReverseStep if self.options.step_cycle => Some(StopReason::DoneStep),
+            ExecMode::ReverseStep => self
+                .emu
+                .current_instruction_changed()
+                .then_some(StopReason::DoneStep),
+            ExecMode::ReverseContinue => self
+                .emu
+                .current_instruction_changed()
+                .then(|| self.check_code_breakpoints())
+                .flatten()
+                .or_else(|| self.check_watchpoints()),
+            _ => unreachable!("Not a reverse execution mode: {mode:?}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use cmemu_lib::common::{Address, RegisterID, Word};
+    use flexi_logger::{Logger, LoggerHandle};
+    use std::array;
+    use std::path::PathBuf;
+    use std::sync::OnceLock;
+
+    const LOOP_START: u32 = 0x102;
+    const LOOP_BRANCH: u32 = 0x104;
+
+    /// An emulator running an endless loop incrementing `r0`.
+    fn counting_emulator() -> Emulator {
+        let mut flash = vec![0u8; 0x200];
+        // Vector table: the initial SP and the reset handler.
+        flash[0..4].copy_from_slice(&0x2000_1000_u32.to_le_bytes());
+        flash[4..8].copy_from_slice(&0x101_u32.to_le_bytes());
+        // 0x100: movs r0, #0
+        // 0x102: adds r0, #1
+        // 0x104: b 0x102
+        for (i, instr) in [0x2000_u16, 0x3001, 0xe7fd].into_iter().enumerate() {
+            flash[0x100 + 2 * i..][..2].copy_from_slice(&instr.to_le_bytes());
+        }
+        Emulator::new(&flash, None)
+    }
+
+    fn monitor() -> DebugMonitor {
+        // The logger is not installed, but building it more than once makes noise.
+        static LOGGER: OnceLock<LoggerHandle> = OnceLock::new();
+        let logger = LOGGER.get_or_init(|| {
+            Logger::try_with_str("off")
+                .expect("Valid log spec")
+                .build()
+                .expect("Logger is built")
+                .1
+        });
+        DebugMonitor::new(counting_emulator(), None, PathBuf::new(), logger.clone())
+    }
+
+    fn checkpoints_with_interval(interval: u64) -> Checkpoints {
+        let mut checkpoints = Checkpoints::default();
+        checkpoints.set_interval(interval);
+        checkpoints
+    }
+
+    /// Run the monitor in the `mode` until it stops.
+    fn run(monitor: &mut DebugMonitor, mode: ExecMode) -> StopReason {
+        monitor.exec_mode = mode;
+        loop {
+            if let Some(reason) = monitor.step_check_event().expect("No errors") {
+                monitor.exec_mode = ExecMode::Halted;
+                return reason;
+            }
+        }
+    }
+
+    fn state(monitor: &DebugMonitor) -> (u64, Address, [Word; 16]) {
+        (
+            monitor.cycle_number(),
+            monitor.emu.get_current_instruction_address(),
+            array::from_fn(|i| monitor.emu.get_register(RegisterID::from_index(i))),
+        )
+    }
+
+    #[test]
+    fn too_many_checkpoints_are_thinned_out() {
+        let emu = counting_emulator();
+        let mut checkpoints = checkpoints_with_interval(1);
+        for cycle in 0..=MAX_CHECKPOINTS as u64 {
+            checkpoints.maybe_take(cycle, &emu);
+        }
+        assert_eq!(checkpoints.interval(), 2);
+        assert!(
+            checkpoints
+                .cycles()
+                .eq((0..=MAX_CHECKPOINTS as u64).step_by(2))
+        );
+
+        checkpoints.maybe_take(MAX_CHECKPOINTS as u64 + 1, &emu);
+        assert_eq!(checkpoints.cycles().last(), Some(MAX_CHECKPOINTS as u64));
+        checkpoints.maybe_take(MAX_CHECKPOINTS as u64 + 2, &emu);
+        assert_eq!(
+            checkpoints.cycles().last(),
+            Some(MAX_CHECKPOINTS as u64 + 2)
+        );
+    }
+
+    #[test]
+    fn latest_checkpoint_before_needs_a_cycle_to_execute() {
+        let emu = counting_emulator();
+        let mut checkpoints = checkpoints_with_interval(10);
+        for cycle in 0..30 {
+            checkpoints.maybe_take(cycle, &emu);
+        }
+        assert!(checkpoints.cycles().eq([0, 10, 20]));
+
+        assert_eq!(checkpoints.latest_before(0), None);
+        assert_eq!(checkpoints.latest_before(1), None);
+        assert_eq!(checkpoints.latest_before(2), Some(0));
+        assert_eq!(checkpoints.latest_before(11), Some(0));
+        assert_eq!(checkpoints.latest_before(12), Some(1));
+        assert_eq!(checkpoints.latest_before(100), Some(2));
+    }
+
+    #[test]
+    fn rewrite_replaces_later_checkpoints() {
+        let emu = counting_emulator();
+        let mut checkpoints = checkpoints_with_interval(10);
+        for cycle in 0..30 {
+            checkpoints.maybe_take(cycle, &emu);
+        }
+        checkpoints.rewrite_from(15, &emu);
+        assert!(checkpoints.cycles().eq([0, 10, 15]));
+        checkpoints.rewrite_from(10, &emu);
+        assert!(checkpoints.cycles().eq([0, 10]));
+    }
+
+    #[test]
+    fn run_backwards_without_checkpoints_stops_at_the_beginning() {
+        let mut monitor = monitor();
+        monitor.exec_mode = ExecMode::ReverseStep;
+        assert!(matches!(
+            monitor.run_backwards(),
+            StopReason::ReplayLog {
+                pos: ReplayLogPosition::Begin,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn reverse_stepi_then_stepi_lands_on_the_same_state() {
+        let mut monitor = monitor();
+        monitor.checkpoints.set_interval(7);
+
+        let mut history = vec![];
+        for _ in 0..20 {
+            assert!(matches!(
+                run(&mut monitor, ExecMode::StepInstruction),
+                StopReason::DoneStep
+            ));
+            history.push(state(&monitor));
+        }
+        assert!(
+            history.iter().any(|(_, _, regs)| regs[0] > Word::from(2)),
+            "The loop is running"
+        );
+
+        for expected in history.iter().rev().skip(1).take(5) {
+            assert!(matches!(
+                run(&mut monitor, ExecMode::ReverseStep),
+                StopReason::DoneStep
+            ));
+            assert_eq!(state(&monitor), *expected);
+        }
+        for expected in &history[history.len() - 5..] {
+            assert!(matches!(
+                run(&mut monitor, ExecMode::StepInstruction),
+                StopReason::DoneStep
+            ));
+            assert_eq!(state(&monitor), *expected);
+        }
+    }
+
+    #[test]
+    fn reverse_continue_stops_at_the_latest_breakpoint() {
+        let mut monitor = monitor();
+        monitor.checkpoints.set_interval(5);
+
+        let mut history = vec![];
+        for _ in 0..20 {
+            run(&mut monitor, ExecMode::StepInstruction);
+            history.push(state(&monitor));
+        }
+        monitor.breakpoints.insert(Address::from_const(LOOP_BRANCH));
+        let expected = history
+            .iter()
+            .rev()
+            .skip(1)
+            .find(|(_, pc, _)| *pc == Address::from_const(LOOP_BRANCH))
+            .expect("The branch was executed");
+
+        assert!(matches!(
+            run(&mut monitor, ExecMode::ReverseContinue),
+            StopReason::SwBreak(())
+        ));
+        assert_eq!(state(&monitor), *expected);
+
+        // Going back once more finds the previous iteration.
+        monitor.breakpoints.clear();
+        monitor.breakpoints.insert(Address::from_const(LOOP_START));
+        let expected = history
+            .iter()
+            .rev()
+            .filter(|(cycle, _, _)| *cycle < expected.0)
+            .find(|(_, pc, _)| *pc == Address::from_const(LOOP_START))
+            .expect("The loop was entered");
+        assert!(matches!(
+            run(&mut monitor, ExecMode::ReverseContinue),
+            StopReason::SwBreak(())
+        ));
+        assert_eq!(state(&monitor), *expected);
+    }
+}
@@ -18,7 +18,9 @@ pub(crate) mod pending;
 // TODO: maybe CoreRegisterID would be a better name for an export?
 pub use crate::component::core::{CoreCoupledRegisterId, RegisterID, SpecialPurposeRegisterId};
 pub use crate::component::rfc::command::CcaReq;
-pub use crate::component::rfc::{ModemImpl, ModemInterface, ModemOp};
+pub use crate::component::rfc::{
+    BleModemInterface, BleModemOp, BlePacket, ModemImpl, ModemInterface, ModemOp,
+};
 pub use crate::component::semi_hosting::RequestedExit;
 pub mod cmemu_hosting {
     pub use crate::component::semi_hosting::{
@@ -26,6 +28,10 @@ pub mod cmemu_hosting {
         OS_DATA_RANGE,
     };
 }
+pub use crate::component::gpio::{GpioInterface, GpioInterfaceImpl};
+pub use crate::component::i2c::{I2cBusInterface, I2cBusInterfaceImpl};
+pub use crate::component::ssi::{SpiDeviceInterface, SpiDeviceInterfaceImpl, SsiModule};
+pub use crate::component::uart::{UARTInterface, UARTInterfaceImpl};
 pub use crate::component::uart_lite::UARTLiteInterface;
 pub use bitstring::{Bitstring, BitstringUtils, Word};
 pub(crate) use shift::{SRType, Shift};
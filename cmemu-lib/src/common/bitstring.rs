@@ -3,6 +3,7 @@
 //! [ARM-ARM] A2.2.1 -- Integer arithmetic
 //! [ARM-ARM] D6.5 -- Operators and built-in functions
 
+use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::fmt::{Binary, Debug, Formatter};
 use std::marker::PhantomData;
@@ -29,7 +30,7 @@ pub const MAX_BITS: usize = 32;
 /// `N`: bits representation: u8, u16 or u32
 /// `T`: marker type "[bool; N]"
 /// `PartialEq`, `Eq`, `PartialOrd`, `Ord`: [ARM-ARM] D6.5.4 Comparisons
-#[derive(Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[derive(Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
 pub struct Bitstring<N, T>(N, PhantomData<T>);
 
 // Note(asserts):
@@ -7,6 +7,7 @@ use crate::engine::Subcomponent;
 use crate::engine::{BufferFlop, DisableableComponent, TickComponent, TickComponentExtra};
 use enum_map::{EnumArray, EnumMap};
 use log::trace;
+use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 use std::marker::PhantomData;
 
@@ -22,7 +23,16 @@ where
     fn get_addr_in_port(&self) -> Option<TAG>;
 }
 
-#[derive(Subcomponent, TickComponent, TickComponentExtra, DisableableComponent, Debug)]
+#[derive(
+    Clone,
+    Subcomponent,
+    TickComponent,
+    TickComponentExtra,
+    DisableableComponent,
+    Debug,
+    Serialize,
+    Deserialize,
+)]
 pub(crate) struct NoArbiter<TAG, ONLYPM>
 where
     TAG: PartialEq + Copy + Debug + FromMarker<ONLYPM>,
@@ -71,7 +81,16 @@ where
 
 /// Always returns None, panics at any request
 /// Or should we call it Unimplemented Arbiter?
-#[derive(Subcomponent, TickComponent, TickComponentExtra, DisableableComponent, Debug)]
+#[derive(
+    Clone,
+    Subcomponent,
+    TickComponent,
+    TickComponentExtra,
+    DisableableComponent,
+    Debug,
+    Serialize,
+    Deserialize,
+)]
 pub(crate) struct NullArbiter<TAG>
 where
     TAG: PartialEq + Copy + Debug,
@@ -116,7 +135,16 @@ where
 // Note: the default marker is implemented for all generated tags enum-struct-magic
 type AlwaysNoneMarker = DefaultSlave;
 #[allow(dead_code)]
-#[derive(Subcomponent, TickComponent, TickComponentExtra, DisableableComponent, Debug)]
+#[derive(
+    Clone,
+    Subcomponent,
+    TickComponent,
+    TickComponentExtra,
+    DisableableComponent,
+    Debug,
+    Serialize,
+    Deserialize,
+)]
 pub(crate) struct RoundRobinArbiter<TAG, DEFAULT = AlwaysNoneMarker>
 where
     TAG: PartialEq + Copy + EnumArray<bool> + Debug,
@@ -206,7 +234,16 @@ where
     }
 }
 
-#[derive(Subcomponent, TickComponent, TickComponentExtra, DisableableComponent, Debug)]
+#[derive(
+    Clone,
+    Subcomponent,
+    TickComponent,
+    TickComponentExtra,
+    DisableableComponent,
+    Debug,
+    Serialize,
+    Deserialize,
+)]
 pub(crate) struct FixedArbiter<TAG, DEFAULT = AlwaysNoneMarker>
 where
     TAG: PartialEq + Copy + EnumArray<bool>,
@@ -288,7 +325,16 @@ where
 
 /// Performs GRANTs in the same cycle
 /// It is expected to be called AFTER all input in known for the current cycle
-#[derive(Subcomponent, TickComponent, TickComponentExtra, DisableableComponent, Debug)]
+#[derive(
+    Clone,
+    Subcomponent,
+    TickComponent,
+    TickComponentExtra,
+    DisableableComponent,
+    Debug,
+    Serialize,
+    Deserialize,
+)]
 pub(crate) struct CombinatorialFixedArbiter<TAG>
 where
     TAG: PartialEq + Copy + EnumArray<bool>,
@@ -344,7 +390,16 @@ where
 }
 
 // XXX: TODO: it only reverses order
-#[derive(Subcomponent, TickComponent, TickComponentExtra, DisableableComponent, Debug)]
+#[derive(
+    Clone,
+    Subcomponent,
+    TickComponent,
+    TickComponentExtra,
+    DisableableComponent,
+    Debug,
+    Serialize,
+    Deserialize,
+)]
 pub(crate) struct ReversedCombFixedArbiter<TAG>
 where
     TAG: PartialEq + Copy + EnumArray<bool>,
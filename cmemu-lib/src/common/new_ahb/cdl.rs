@@ -407,3 +407,16 @@ impl CdlTag {
         self == other || self == &Self::DEFAULT_STR
     }
 }
+
+// The tags only decorate debug logs, so a transfer restored from a snapshot gets the default one.
+impl serde::Serialize for CdlTag {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_unit()
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for CdlTag {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        <()>::deserialize(deserializer).map(|()| Self::default())
+    }
+}
@@ -1,9 +1,10 @@
-/// A struct carrying sized data
 use crate::Bitstring;
 use crate::common::new_ahb::{MasterToSlaveWires, Size, SlaveToMasterWires};
 use crate::common::{BitstringUtils, Word};
 use crate::utils::IfExpr;
 use cmemu_common::Address;
+/// A struct carrying sized data
+use serde::{Deserialize, Serialize};
 use std::fmt::{Debug, Display, Formatter};
 use thiserror::Error;
 
@@ -17,7 +18,7 @@ use thiserror::Error;
 /// Many parts of the memory subsystem in CMEmu are specialized to interfaces using the `DataBus`,
 /// where a generic type-carrying argument may go, such as [`SlaveToMasterWires`].
 // NOTE: using uX instead of [u8; X/8] has a potential to over-assume little-endianness
-#[derive(PartialEq, Eq, Clone, Default)]
+#[derive(PartialEq, Eq, Clone, Default, Serialize, Deserialize)]
 #[non_exhaustive]
 pub enum DataBus {
     /// Represents a high-impedance lines state when not carrying any data.
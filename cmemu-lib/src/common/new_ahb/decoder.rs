@@ -9,6 +9,7 @@
 //! Because of that, special considerations are needed.
 //! Refer to the documentation of the [`Decoder`] struct for details.
 use log::trace;
+use serde::{Deserialize, Serialize};
 use std::fmt::{Debug, Display, Formatter};
 use std::marker::PhantomData;
 
@@ -42,7 +43,7 @@ make_port_struct!(pub(crate) AhbPort<SC, PM>);
 /// - data route must be kept until the transfer finishes,
 /// - if addr router is different from data one, decoder must either reflect HREADY to addr phase
 ///   or DENY access (not propagate the addr route).
-#[derive(Subcomponent, TickComponent, DisableableComponent)]
+#[derive(Clone, Subcomponent, TickComponent, DisableableComponent, Serialize, Deserialize)]
 pub(crate) struct Decoder<SC>
 where
     SC: Subcomponent<Member = Self>,
@@ -74,7 +75,7 @@ where
     stm: DecoderSTM,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 enum DecoderSTM {
     Tick,
     GotS2M,
@@ -121,7 +122,7 @@ where
 pub(crate) trait AhbDecode {
     fn decode(addr: Address) -> Self;
 }
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub(crate) struct DefaultSlave;
 
 impl<SC> TickComponentExtra for Decoder<SC>
@@ -433,9 +434,15 @@ where
 {
     fn send_ahb_output(
         comp: &mut Self::Component,
-        _ctx: &mut Context,
+        ctx: &mut Context,
         msg: MasterToSlaveWires<Self::Data>,
     ) {
+        // The default slave is never busy, so an address routed here is always granted.
+        if let Some(granter) = <Decoder<SC> as AhbDecoderTag>::GRANTER
+            && msg.addr_phase.meta.is_address_valid()
+        {
+            granter(comp, ctx, TrackedBool::true_::<Self>());
+        }
         let mut this = SubcomponentProxyMut::<SC>::from(comp);
         let (response, source_msg, data) = if let Some(err_msg) =
             this.default_slave_err_req.try_take()
@@ -6,6 +6,7 @@
 //! cannot actually proceed.
 //! The role of the [`InputStage`] is to remember the addr-phase request,
 //! and reply to the master with waitstates, while retrying the request on the slave side.
+use serde::{Deserialize, Serialize};
 use std::fmt::{Debug, Display, Formatter};
 use std::marker::PhantomData;
 
@@ -35,7 +36,7 @@ pub(crate) mod transparent;
 /// Main invariants upheld:
 /// - promises upholding main CMEmu AHB invariants
 /// - if the upstream transfer advanced, we inject waitstates
-#[derive(Subcomponent, TickComponent, DisableableComponent)]
+#[derive(Clone, Subcomponent, TickComponent, DisableableComponent, Serialize, Deserialize)]
 pub(crate) struct InputStage<SC>
 where
     SC: Subcomponent<Member = InputStage<SC>>,
@@ -53,7 +54,7 @@ where
     phantom_sc: PhantomData<SC>,
 }
 
-#[derive(Default, Debug, PartialEq, Clone, Copy)]
+#[derive(Default, Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
 enum InputState {
     /// No active transfer
     #[default]
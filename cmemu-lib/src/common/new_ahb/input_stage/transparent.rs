@@ -1,4 +1,5 @@
 //! Plug to the interconnect builder, which forwards the grant wire outside the interconnect.
+use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 use std::marker::PhantomData;
 
@@ -27,7 +28,7 @@ pub(crate) trait TransparentInputStageCfg: AHBPortConfig {
 ///
 /// Moreover, it has a check if we generated a response and receive one, they match.
 // TODO: Can this be eliminated altogether by a) allowing skipping replies to Idle, b) making them in Decoder?
-#[derive(Subcomponent, TickComponent)]
+#[derive(Clone, Subcomponent, TickComponent, Serialize, Deserialize)]
 pub(crate) struct TransparentInputStage<SC>
 where
     SC: Subcomponent<Member = Self>,
@@ -82,7 +82,7 @@ paste::paste!{
 
     (@component($is:path,$dec:path,$os:path,$dport:path,$oport:path) $vis:vis $name:ident [$($mtag:ident),+] [$($stag:ident),+]) => {
 paste::paste!{
-        #[derive($crate::engine::Subcomponent, $crate::engine::TickComponent, $crate::engine::TickComponentExtra, $crate::engine::DisableableComponent, Default)]
+        #[derive($crate::engine::Subcomponent, $crate::engine::TickComponent, $crate::engine::TickComponentExtra, $crate::engine::DisableableComponent, Default, Clone, ::serde::Serialize, ::serde::Deserialize)]
         #[subcomponent_1to1]
         $vis struct $name {
             $(
@@ -26,16 +26,29 @@
 //! Moreover, ATM the macro unhygienically assumes the presence of ``Input`` and ``Output`` ports.
 use crate::common::new_ahb::signals::MasterToSlaveWires;
 use enum_map::EnumArray;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
 use std::fmt::Debug;
 
 pub(crate) trait LiteWrapperCfg {
-    type Data: Debug + Clone + Default + 'static;
+    type Data: Debug + Clone + Default + Serialize + DeserializeOwned + 'static;
+    // Deserializing an EnumMap<_, V> requires an EnumMap<_, Option<V>>.
     type InputTag: EnumArray<Option<MasterToSlaveWires<Self::Data>>>
+        + EnumArray<Option<Option<MasterToSlaveWires<Self::Data>>>>
         + EnumArray<Option<bool>>
+        + EnumArray<Option<Option<bool>>>
         + EnumArray<bool>
         + Copy
-        + Debug;
-    type OutputTag: EnumArray<Option<bool>> + EnumArray<bool> + Copy + Debug;
+        + Debug
+        + Serialize
+        + DeserializeOwned;
+    type OutputTag: EnumArray<Option<bool>>
+        + EnumArray<Option<Option<bool>>>
+        + EnumArray<bool>
+        + Copy
+        + Debug
+        + Serialize
+        + DeserializeOwned;
 }
 // This exports LiteWrapper, LiteInput & LiteOutput
 // In the future the goal is to make it generic if possible.
@@ -182,7 +195,7 @@ where
     }
 }
 
-#[derive(Subcomponent, TickComponent)]
+#[derive(Subcomponent, TickComponent, Clone, ::serde::Serialize, ::serde::Deserialize)]
 #[subcomponent_1to1]
 $vis struct LiteWrapper {
     input_buffer: EnumMap<
@@ -17,6 +17,7 @@ use crate::engine::{
 use crate::utils::dife;
 use log::{debug, trace};
 use owo_colors::OwoColorize;
+use serde::{Deserialize, Serialize};
 use std::fmt::{Debug, Formatter};
 use std::marker::PhantomData;
 
@@ -54,7 +55,7 @@ pub(crate) trait LineBufferCfg: AHBPortConfig {
 /// ```
 ///
 /// **Note**: writing through the line buffer is not implemented!
-#[derive(Subcomponent, TickComponent)]
+#[derive(Clone, Subcomponent, TickComponent, Serialize, Deserialize)]
 pub(crate) struct LineBuffer<SC>
 where
     SC: Subcomponent<Member = Self>,
@@ -104,7 +105,7 @@ where
     }
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
 enum LBMode {
     /// Transfer not active
     _Idle,
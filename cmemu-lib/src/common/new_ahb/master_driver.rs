@@ -29,13 +29,15 @@ use crate::move_state_machine;
 #[cfg(debug_assertions)]
 use crate::static_downcast;
 use log::trace;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 use std::fmt::{Debug, Formatter};
 use std::marker::PhantomData;
 
 pub(crate) mod stateless_helpers;
 
 // TODO: mark it cdl-only
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub(crate) enum TransferStatus {
     AddrPhaseNew,
     AddrPhaseStalled,
@@ -45,6 +47,8 @@ pub(crate) enum TransferStatus {
 }
 impl StateMachine for TransferStatus {}
 
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub(crate) struct TransferInfo<P>
 where
     P: Handler,
@@ -129,13 +133,18 @@ where
 }
 
 pub(crate) trait Handler: AHBPortConfig + Sized {
-    type UserData: Debug;
+    type UserData: Debug + Clone + Serialize + DeserializeOwned;
     /// Can the Master change valid address to another during waitstates?
     const AHB_LITE_COMPAT: bool = false;
 
     // TODO: move this to MasterOutput for routing
     const HAS_GRANTING_WIRE: bool = false;
 
+    /// Does the master cancel the transfer in address phase on an error response?
+    /// [ARM-AHB-Lite] (ERROR response) allows the master to either cancel it or continue with it,
+    /// so otherwise it advances after the second cycle of the response.
+    const CANCELS_ADDR_PHASE_ON_ERROR: bool = true;
+
     /// The interface presented an address on the bus (it was granted an access).
     /// This doesn't mean that the transfer will necessarily advance on the edge.
     /// The `cancellable` bool indicates whether calling `try_force_*` in the next cycle may succeed
@@ -221,7 +230,8 @@ pub(crate) trait Handler: AHBPortConfig + Sized {
     }
 }
 
-#[derive(Subcomponent, TickComponent)]
+#[derive(Clone, Subcomponent, TickComponent, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub(crate) struct MasterDriver<SC, P>
 where
     SC: Subcomponent<Member = Self>,
@@ -258,7 +268,7 @@ where
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 enum MDStm {
     TickExtra,
     RunDriver,
@@ -453,7 +463,11 @@ where
                 <P as Handler>::transfers_will_stall(this.component_mut(), ctx, has_addr, has_data);
             }
             AhbResponseControl::Error1 => {
-                let addr_phase = this.addr_phase.take();
+                let addr_phase = if <P as Handler>::CANCELS_ADDR_PHASE_ON_ERROR {
+                    this.addr_phase.take()
+                } else {
+                    None
+                };
                 let data_phase = this.data_phase.take();
                 <P as Handler>::transfers_aborted(
                     this.component_mut(),
@@ -526,7 +540,13 @@ where
         let mut this = SubcomponentProxyMut::<SC>::from(comp);
         move_state_machine!(this.stm => MDStm = {RunDriver => SentMessages}, "run_driver not called?");
 
-        if this.addr_phase.is_none() && this.data_phase.is_none() {
+        // The slave still drives the second cycle of an ERROR response to the aborted transfers,
+        // so the interconnect expects (idle) wires from us in it.
+        let in_error_response = this
+            .last_resp
+            .try_prev_cycle()
+            .is_some_and(|r| *r == AhbResponseControl::Error1);
+        if this.addr_phase.is_none() && this.data_phase.is_none() && !in_error_response {
             return;
         }
 
@@ -9,6 +9,8 @@ use crate::common::new_ahb::ports::{AHBMasterPortOutput, AHBPortConfig};
 use crate::common::new_ahb::signals::{Burst, Direction, Protection, Size, TransferMeta};
 use crate::common::new_ahb::{MasterToSlaveWires, SlaveToMasterWires};
 use crate::engine::{Context, Subcomponent};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
 use std::fmt::Debug;
 
 pub(crate) trait SimplerHandler: AHBPortConfig + Sized
@@ -18,12 +20,13 @@ where
             Component = <<Self as SimplerHandler>::MasterDriverSC as Subcomponent>::Component,
         >,
 {
-    type UserData: Debug;
+    type UserData: Debug + Clone + Serialize + DeserializeOwned;
     type MasterDriverSC: Subcomponent<Member = MasterDriver<Self::MasterDriverSC, Self>>;
 
     const AHB_LITE_COMPAT: bool;
     const DEFAULT_PROT: Protection;
     const HAS_GRANTING_WIRE: bool = false;
+    const CANCELS_ADDR_PHASE_ON_ERROR: bool = true;
 
     #[allow(unused_variables)]
     fn address_presented(
@@ -77,8 +80,8 @@ where
     fn transfers_aborted(
         comp: &mut <Self as AHBPortConfig>::Component,
         ctx: &mut Context,
-        addr_phase: Option<<Self as SimplerHandler>::UserData>,
-        data_phase: Option<<Self as SimplerHandler>::UserData>,
+        addr_phase: Option<(TransferMeta, <Self as SimplerHandler>::UserData)>,
+        data_phase: Option<(TransferMeta, <Self as SimplerHandler>::UserData)>,
     ) {
         panic!("Transfer errors not handled!")
     }
@@ -131,6 +134,7 @@ where
     type UserData = <T as SimplerHandler>::UserData;
     const AHB_LITE_COMPAT: bool = <T as SimplerHandler>::AHB_LITE_COMPAT;
     const HAS_GRANTING_WIRE: bool = <T as SimplerHandler>::HAS_GRANTING_WIRE;
+    const CANCELS_ADDR_PHASE_ON_ERROR: bool = <T as SimplerHandler>::CANCELS_ADDR_PHASE_ON_ERROR;
 
     fn address_presented(
         comp: &mut <Self as AHBPortConfig>::Component,
@@ -217,8 +221,8 @@ where
         <T as SimplerHandler>::transfers_aborted(
             comp,
             ctx,
-            addr_phase.map(|i| i.user),
-            data_phase.map(|i| i.user),
+            addr_phase.map(|i| (i.meta, i.user)),
+            data_phase.map(|i| (i.meta, i.user)),
         );
     }
 
@@ -257,6 +261,16 @@ where
         addr: Address,
         size: Size,
         user: <P as SimplerHandler>::UserData,
+    ) -> bool {
+        self.try_read_data_with_prot(addr, size, <P as SimplerHandler>::DEFAULT_PROT, user)
+    }
+
+    pub(crate) fn try_read_data_with_prot(
+        &mut self,
+        addr: Address,
+        size: Size,
+        prot: Protection,
+        user: <P as SimplerHandler>::UserData,
     ) -> bool {
         self.try_request(TransferInfo {
             meta: TransferMeta {
@@ -264,7 +278,7 @@ where
                 size,
                 burst: Burst::Single,
                 dir: Direction::Read,
-                prot: <P as SimplerHandler>::DEFAULT_PROT,
+                prot,
             },
             status: TransferStatus::AddrPhaseNew,
             data: None,
@@ -278,6 +292,7 @@ where
         &mut self,
         addr: Address,
         size: Size,
+        prot: Protection,
         user: <P as SimplerHandler>::UserData,
         #[cfg(feature = "cycle-debug-logger")] tag: &'static str,
     ) -> bool {
@@ -287,7 +302,7 @@ where
                 size,
                 burst: Burst::Single,
                 dir: Direction::Read,
-                prot: <P as SimplerHandler>::DEFAULT_PROT,
+                prot,
             },
             status: TransferStatus::AddrPhaseNew,
             data: None,
@@ -320,11 +335,22 @@ where
         })
     }
 
+    #[allow(dead_code)]
     pub(crate) fn try_write_data(
         &mut self,
         addr: Address,
         size: Size,
         user: <P as SimplerHandler>::UserData,
+    ) -> bool {
+        self.try_write_data_with_prot(addr, size, <P as SimplerHandler>::DEFAULT_PROT, user)
+    }
+
+    pub(crate) fn try_write_data_with_prot(
+        &mut self,
+        addr: Address,
+        size: Size,
+        prot: Protection,
+        user: <P as SimplerHandler>::UserData,
     ) -> bool {
         self.try_request(TransferInfo {
             meta: TransferMeta {
@@ -332,7 +358,7 @@ where
                 size,
                 burst: Burst::Single,
                 dir: Direction::Write,
-                prot: <P as SimplerHandler>::DEFAULT_PROT,
+                prot,
             },
             status: TransferStatus::AddrPhaseNew,
             data: None,
@@ -348,6 +374,18 @@ where
         size: Size,
         data: DataBus,
         user: <P as SimplerHandler>::UserData,
+    ) -> bool {
+        let prot = <P as SimplerHandler>::DEFAULT_PROT;
+        self.try_write_latched_data_with_prot(addr, size, data, prot, user)
+    }
+
+    pub(crate) fn try_write_latched_data_with_prot(
+        &mut self,
+        addr: Address,
+        size: Size,
+        data: DataBus,
+        prot: Protection,
+        user: <P as SimplerHandler>::UserData,
     ) -> bool {
         self.try_request(TransferInfo {
             meta: TransferMeta {
@@ -355,7 +393,7 @@ where
                 size,
                 burst: Burst::Single,
                 dir: Direction::Write,
-                prot: <P as SimplerHandler>::DEFAULT_PROT,
+                prot,
             },
             status: TransferStatus::AddrPhaseNew,
             data: Some(data),
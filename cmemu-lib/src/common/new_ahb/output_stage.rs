@@ -4,6 +4,7 @@
 //! as an arbiter.
 //! It is not directly usable outside the interconnect,
 //! because it is not pure AHB-Lite, which lacks GRANT wires.
+use serde::{Deserialize, Serialize};
 use std::fmt::{Debug, Display, Formatter};
 use std::marker::PhantomData;
 
@@ -53,7 +54,8 @@ make_port_struct!(pub(crate) AhbPort<SC, PM>);
 ///   - if it provided low HREADYIN, they won't consider it advanced,
 ///   - if its addr-phase is not routed to the outside, it will get a DENY comb. grant response,
 ///   - if the slave returns low HREADY, the addr-route master will get a DENY too.
-#[derive(Subcomponent, TickComponent, DisableableComponent)]
+#[derive(Clone, Subcomponent, TickComponent, DisableableComponent, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub(crate) struct OutputStage<SC>
 where
     SC: Subcomponent<Member = OutputStage<SC>>,
@@ -87,7 +89,7 @@ where
 }
 
 #[cfg(debug_assertions)]
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 enum OutputStageSTM {
     Tick,
     // Strict ordering has replies before responses
@@ -1,4 +1,5 @@
 //! [`OutputStage`] implementation that decides arbitration in the same cycle as the transfers come
+use serde::{Deserialize, Serialize};
 use std::fmt::{Debug, Display, Formatter};
 use std::marker::PhantomData;
 
@@ -33,7 +34,12 @@ use crate::{debug_move_state_machine, make_port_struct};
 make_port_struct!(pub(crate) AhbPort<SC, PM>);
 
 /// A combinatorial output stage implementation that decides arbitration in the same cycle.
-#[derive(Subcomponent, TickComponent, DisableableComponent)]
+#[allow(
+    clippy::unsafe_derive_deserialize,
+    reason = "the only `unsafe` is in reading the `confeature` statics"
+)]
+#[derive(Clone, Subcomponent, TickComponent, DisableableComponent, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub(crate) struct OutputStage<SC>
 where
     SC: Subcomponent<Member = OutputStage<SC>>,
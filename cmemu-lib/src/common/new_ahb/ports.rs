@@ -1,8 +1,10 @@
 use crate::common::new_ahb::signals::{MasterToSlaveWires, SlaveToMasterWires, TrackedBool};
 use crate::engine::Context;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
 
 pub(crate) trait AHBPortConfig {
-    type Data: Default + Debug + Clone + 'static;
+    type Data: Default + Debug + Clone + Serialize + DeserializeOwned + 'static;
     type Component;
     const TAG: &'static str;
     #[cfg(feature = "cycle-debug-logger")]
@@ -102,17 +104,24 @@ use std::fmt::{Display, Formatter};
 macro_rules! make_port_struct {
 ($(#[$attr:meta])* $vis:vis $id:ident<$($tvar:ident),*>) => {
     $(#[$attr])*
+    #[derive(::serde::Serialize, ::serde::Deserialize)]
+    #[serde(bound = "")]
     $vis struct $id<$($tvar),*> ($(std::marker::PhantomData<$tvar>),*);
     impl<$($tvar),*> Default for $id<$($tvar),*> {
     fn default() -> Self {
         Self ($(std::marker::PhantomData::<$tvar>),*)
     }
     }
+    impl<$($tvar),*> Clone for $id<$($tvar),*> {
+    fn clone(&self) -> Self {
+        Self ($(std::marker::PhantomData::<$tvar>),*)
+    }
+    }
 };
 ($(#[$attr:meta])* $vis:vis $p:path) => {
     paste::paste!{
     $(#[$attr])*
-    #[derive(Default)]
+    #[derive(Default, Clone, ::serde::Serialize, ::serde::Deserialize)]
     $vis struct [<$p>];
     }
 };
@@ -35,6 +35,7 @@ use crate::engine::TransitionValidator;
 use crate::proxy::CycleDebugLoggerProxy;
 use cmemu_common::Address;
 use owo_colors::OwoColorize;
+use serde::{Deserialize, Serialize};
 #[cfg(feature = "cdl-ahb-trace")]
 use std::any::Any;
 use std::fmt::{Debug, Display, Formatter};
@@ -67,7 +68,7 @@ pub mod wire {
 ///
 /// See: [ARM-AHB-Lite] 2.2 Master signals, 2.4 Decoder signals, and 2.5 Multiplexor signals
 #[cfg_attr(test, derive(PartialEq, Eq))]
-#[derive(Default, Clone)]
+#[derive(Default, Clone, Serialize, Deserialize)]
 pub(crate) struct MasterToSlaveWires<D: Default + 'static> {
     pub(crate) addr_phase: MasterToSlaveAddrPhase,
     pub(crate) data_phase: MasterToSlaveDataPhase<D>,
@@ -79,7 +80,7 @@ pub(crate) struct MasterToSlaveWires<D: Default + 'static> {
 ///
 /// See: [ARM-AHB-Lite] 2.2 Master signals, 2.4 Decoder signals, and 2.5 Multiplexor signals
 #[cfg_attr(test, derive(PartialEq, Eq))]
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub(crate) struct MasterToSlaveAddrPhase {
     /// Combines `HADDR`, `HBURST`, `HPROT`, `HSIZE`, `HTRANS`, `HWRITE`, and `HSELx` semantically.
     pub(crate) meta: TransferType,
@@ -129,7 +130,7 @@ impl MasterToSlaveAddrPhase {
 /// The `D` is generic over the `HWDATA` signal, which can have a configurable width.
 /// Use [`Self::continue_read`], [`Self::continue_write`], [`Self::empty`] to properly create a base struct.
 #[cfg_attr(test, derive(PartialEq, Eq))]
-#[derive(Default, Clone)]
+#[derive(Default, Clone, Serialize, Deserialize)]
 pub(crate) struct MasterToSlaveDataPhase<D: Default + 'static> {
     ///  [ARM-AHB-Lite] 2.2 Master signals: `HWDATA`
     pub(crate) data: D,
@@ -160,7 +161,7 @@ pub(crate) struct MasterToSlaveDataPhase<D: Default + 'static> {
 ///
 /// See: [ARM-AHB-Lite] 2.3 Slave signals, 2.4 Decoder signals, and 2.5 Multiplexor signals
 #[cfg_attr(test, derive(PartialEq, Eq))]
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub(crate) struct SlaveToMasterWires<D: 'static> {
     ///  Semantic encoding of [ARM-AHB-Lite] 2.3 Slave signals: `HREADYOUT` and `HRESP`
     pub(crate) meta: AhbResponseControl,
@@ -182,7 +183,7 @@ pub(crate) struct SlaveToMasterWires<D: 'static> {
 /// Slave transfer response: `HREADYOUT` x `HRESP`.
 ///
 /// See [ARM-AHB-Lite] Table 5-2 Transfer response
-#[derive(Eq, PartialEq, Clone, Copy)]
+#[derive(Eq, PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub enum AhbResponseControl {
     Success,
     Pending,
@@ -242,7 +243,7 @@ impl AhbResponseControl {
 ///
 /// See: [ARM-AHB-Lite] 3.2 Transfer types
 #[cfg_attr(test, derive(PartialEq, Eq))]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TransferType {
     /// [ARM-AHB-Lite] 3.2 Transfer types: `b00`
     ///
@@ -382,7 +383,7 @@ impl TransferType {
 /// Direction (Read/Write) of the transfer.
 ///
 /// [ARM-AHB-Lite] 2.2 Master signals; 3.1 Basic transfers
-#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum Direction {
     Read = 0,
     Write = 1,
@@ -393,7 +394,7 @@ pub enum Direction {
 ///
 /// [ARM-AHB-Lite] 2.3 Slave signals
 #[cfg_attr(test, derive(PartialEq, Eq))]
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct TransferMeta {
     /// Address. Corresponds to `HADDR`
     pub addr: Address,
@@ -458,7 +459,7 @@ impl TransferMeta {
 ///
 /// The names come from [ARM-AHB-Lite] Table 3-2 Transfer size encoding.
 /// Note: the discriminant is size in bytes rather than the table.
-#[derive(Eq, PartialEq, Debug, Clone, Copy, Ord, PartialOrd)]
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Ord, PartialOrd, Serialize, Deserialize)]
 pub enum Size {
     Byte = 1,
     Halfword = 2,
@@ -529,7 +530,7 @@ impl Size {
 }
 
 /// Burst transfer wires as per [ARM-AHB-Lite] 3.5 Burst operation
-#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
 #[non_exhaustive]
 pub enum Burst {
     /// Single transfer
@@ -548,7 +549,7 @@ pub enum Burst {
 }
 
 /// Transfer protection wires as per [ARM-AHB-Lite] 3.7 Protection control
-#[derive(Eq, PartialEq, Clone, Copy)]
+#[derive(Eq, PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub struct Protection {
     /// Corresponds to `HPROT[0]`: Data/Opcode
     pub(crate) is_data: BinaryWire, // opcode otherwise
@@ -589,7 +590,6 @@ impl Protection {
     }
 
     /// Return new protocol wires with the `bufferable` value overridden.
-    #[allow(dead_code, reason = "Completeness of API")]
     #[must_use]
     pub fn with_bufferable(self, new_val: BinaryWire) -> Self {
         Self {
@@ -598,6 +598,15 @@ impl Protection {
         }
     }
 
+    /// Return new protocol wires with the `privileged` value overridden.
+    #[must_use]
+    pub const fn with_privileged(self, new_val: BinaryWire) -> Self {
+        Self {
+            is_privileged: new_val,
+            ..self
+        }
+    }
+
     /// Return new protocol wires with the `cacheable` value overridden.
     #[must_use]
     pub fn with_cacheable(self, new_val: BinaryWire) -> Self {
@@ -616,7 +625,7 @@ impl Protection {
 /// Use constructors to properly create a base instance of this struct.
 #[cfg_attr(test, derive(PartialEq, Eq))]
 #[cfg_attr(not(feature = "cycle-debug-logger"), repr(transparent))]
-#[derive(Default, Clone)]
+#[derive(Default, Clone, Serialize, Deserialize)]
 pub(crate) struct TrackedWire<D: 'static> {
     pub(crate) data: D,
 
@@ -1134,7 +1143,7 @@ impl MasterToSlaveAddrPhase {
 ///
 /// This is an uninhabited type.
 #[cfg_attr(not(test), allow(dead_code))]
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub(crate) enum UnknownPort {}
 impl Default for UnknownPort {
     fn default() -> Self {
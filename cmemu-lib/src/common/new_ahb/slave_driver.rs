@@ -13,6 +13,7 @@ use crate::engine::{
 #[cfg_attr(not(debug_assertions), allow(unused))]
 use log::{debug, trace};
 use owo_colors::OwoColorize;
+use serde::{Deserialize, Serialize};
 use std::fmt::{Debug, Formatter};
 use std::marker::PhantomData;
 
@@ -24,7 +25,7 @@ pub(crate) mod stateless_simplifiers;
 ////////
 /// This is roughly based on [ARM-SDK-TRM].
 /// Especially sections 3.2 and 3.7 (AHB to SRAM interface module)
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub(crate) enum SimpleResponse<D> {
     Success(D),
     Pending,
@@ -146,7 +147,7 @@ pub(crate) trait SimpleHandler: AHBPortConfig {
 }
 
 #[cfg(debug_assertions)]
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 enum SssiLastState {
     PreTick,
     Tick,
@@ -170,7 +171,7 @@ enum SssiLastState {
 /// before receiving one. (That is, this holds for "non-null" messages.)
 ///
 /// See documentation for [`SimpleHandler`] trait and its simplifications for details on usage.
-#[derive(Subcomponent, TickComponent)]
+#[derive(Clone, Subcomponent, TickComponent, Serialize, Deserialize)]
 pub(crate) struct SimpleSynchronousSlaveInterface<SC, P>
 where
     SC: Subcomponent<Member = SimpleSynchronousSlaveInterface<SC, P>>,
@@ -12,6 +12,7 @@ use crate::engine::{
     Context, DisableableComponent, Subcomponent, TickComponent, TickComponentExtra,
 };
 use log::trace;
+use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 
 pub(crate) type WaitstatesOrErr = Result<u8, &'static str>;
@@ -103,7 +104,15 @@ where
     fn write(slave: &mut Self::Component, ctx: &mut Context, address: Address, data: Self::Native);
 }
 
-#[derive(Subcomponent, TickComponent, TickComponentExtra, DisableableComponent)]
+#[derive(
+    Clone,
+    Subcomponent,
+    TickComponent,
+    TickComponentExtra,
+    DisableableComponent,
+    Serialize,
+    Deserialize,
+)]
 pub(crate) struct FakingSlaveInterface<SC, P>
 where
     SC: Subcomponent<Member = Self>,
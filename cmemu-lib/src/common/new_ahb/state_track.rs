@@ -2,6 +2,7 @@
 use crate::common::new_ahb::signals::{AhbResponseControl, MasterToSlaveAddrPhase};
 #[cfg_attr(not(debug_assertions), allow(unused_imports))]
 use crate::utils::IfExpr;
+use serde::{Deserialize, Serialize};
 
 /// Simple and unoptimized helper to track the state of AHB buses for a "through" component.
 ///
@@ -10,7 +11,7 @@ use crate::utils::IfExpr;
 /// - `Idle` proceeds to a data phase considered non-existent,
 /// - stay in address phase due to low `HREADY` signal,
 /// - by denied lines by e.g., arbiters (in this case, the existing data phase finishes).
-#[derive(Default, Debug)]
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
 pub(crate) struct AHBStateTrack {
     // Note: this is not Flop-based, as the whole struct is Flop-like
     // write-only
@@ -42,7 +42,7 @@ impl Checker for SSC {
     }
 }
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone, serde::Serialize, serde::Deserialize)]
 struct TestData {
     transfer_data: u32,
     should_advance: bool,
@@ -8,10 +8,18 @@ use crate::common::new_ahb::signals::{
 use crate::common::utils::FromMarker;
 use crate::engine::{Context, DisableableComponent, TickComponent};
 use enum_map::EnumArray;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
 use std::fmt::Debug;
 
 pub(crate) trait AhbDecoderTag: AHBPortConfig {
-    type Enum: AhbDecode + FromMarker<DefaultSlave> + PartialEq + Copy + Debug;
+    type Enum: AhbDecode
+        + FromMarker<DefaultSlave>
+        + PartialEq
+        + Copy
+        + Debug
+        + Serialize
+        + DeserializeOwned;
     /// Specify if the decoder should reflect HREADY, that is data-phase HREADYOUT
     /// has combinatorial flow into address-phase HREADYIN.
     const REFLECTS_HREADY: bool = true;
@@ -81,15 +89,23 @@ pub(crate) trait AhbMultiMasterConfig {
     type MastersEnum: Debug
         + PartialEq
         + Copy
-        + EnumArray<Option<MasterToSlaveAddrPhase>>
-        + EnumArray<bool>
-        + EnumArray<Option<bool>>
-        + EnumArray<MasterToSlaveAddrPhase>;
+        + EnumArray<Option<MasterToSlaveAddrPhase>, Array: Clone>
+        + EnumArray<bool, Array: Clone>
+        + EnumArray<Option<bool>, Array: Clone>
+        + EnumArray<MasterToSlaveAddrPhase, Array: Clone>
+        // Deserializing an EnumMap<_, V> requires an EnumMap<_, Option<V>>.
+        + EnumArray<Option<Option<MasterToSlaveAddrPhase>>>
+        + EnumArray<Option<Option<bool>>>
+        + Serialize
+        + DeserializeOwned;
     type Arbiter: Arbiter<Self::MastersEnum>
         + TickComponent
         + DisableableComponent
         + Debug
-        + Default;
+        + Default
+        + Clone
+        + Serialize
+        + DeserializeOwned;
 }
 
 pub(crate) trait AhbSlaveOutputDispatcher<T>: AhbMultiMasterConfig + AHBPortConfig {
@@ -223,7 +239,7 @@ pub(crate) trait Unit: Debug + Copy {
 macro_rules! decoder_tags_and_markers {
     (@make_markers $vis:vis $($tag:ident),* $(,)?) => {
 $(
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, ::serde::Serialize, ::serde::Deserialize)]
 $vis struct $tag;
 )*
     };
@@ -289,7 +305,7 @@ where
 )*
 
 #[allow(clippy::enum_variant_names)]
-#[derive(Debug, PartialEq, Clone, Copy, enum_map::Enum)]
+#[derive(Debug, PartialEq, Clone, Copy, enum_map::Enum, ::serde::Serialize, ::serde::Deserialize)]
 $vis enum $type {
     $($tag,)*
 }
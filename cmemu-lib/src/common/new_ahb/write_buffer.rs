@@ -27,6 +27,7 @@ use crate::engine::{
 use crate::utils::IfExpr;
 use log::trace;
 use owo_colors::OwoColorize;
+use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 use std::marker::PhantomData;
 // Naming of sides:
@@ -45,7 +46,7 @@ pub(crate) trait WriteBufferCfg {
     const IS_BUF_TO_LOAD_FAST: bool = true;
 }
 
-#[derive(Subcomponent, TickComponent, DisableableComponent)]
+#[derive(Clone, Subcomponent, TickComponent, DisableableComponent, Serialize, Deserialize)]
 pub(crate) struct WriteBuffer<SC>
 where
     SC: Subcomponent<Member = Self>,
@@ -75,7 +76,7 @@ where
     phantom_sc: PhantomData<SC>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 enum WriteBufSTM {
     Transparent,       // aka data phase active
     IdleInjectionDone, // aka addr phase wait in buffer, but no data phase
@@ -153,7 +154,8 @@ where
             }
         } else if self.state == WriteBufSTM::BufferedWriteFirst {
             if let Some(AhbResponseControl::Error1) = self.last_reply {
-                // Let us handle Error2
+                // Let us handle Error2, the write is dropped
+                self.data_buffer = None;
                 WriteBufSTM::Transparent
             } else {
                 debug_assert!(
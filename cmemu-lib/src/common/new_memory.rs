@@ -14,6 +14,7 @@ use crate::utils::IfExpr;
 use cmemu_common::Address;
 #[cfg(feature = "poison-unitialized")]
 use fixedbitset::FixedBitSet;
+use serde::{Deserialize, Serialize};
 use std::ops::Range;
 use thiserror::Error;
 
@@ -34,13 +35,22 @@ pub enum MemoryError {
 
 type Iface<SC> = FakingSlaveInterface<FakingSC<SC>, Memory<SC>>;
 
+#[derive(Clone, Serialize, Deserialize)]
 struct MemoryBackend {
     mem: Vec<u8>,
     #[cfg(feature = "poison-unitialized")]
     initialized: FixedBitSet,
 }
 
-#[derive(Subcomponent, TickComponent, TickComponentExtra, DisableableComponent)]
+#[derive(
+    Clone,
+    Subcomponent,
+    TickComponent,
+    TickComponentExtra,
+    DisableableComponent,
+    Serialize,
+    Deserialize,
+)]
 pub(crate) struct Memory<SC>
 where
     SC: Subcomponent<Member = Self>,
@@ -116,6 +126,28 @@ where
         })
     }
 
+    /// Brings the bus interface to its reset state, the contents are left intact.
+    pub(crate) fn reset_interface(&mut self) {
+        self.slave_driver = Default::default();
+    }
+
+    /// Models the loss of power by a part of the memory: its contents become garbage
+    /// (and uninitialized, if poisoning is enabled).
+    pub(crate) fn lose_contents(&mut self, range: Range<Address>) {
+        debug_assert!(
+            Address::is_range_covered(&Self::ADDRESS_SPACE, &range),
+            "Range {range:?} is out of {}",
+            <Self as AHBPortConfig>::TAG,
+        );
+        let start = range.start.offset_from(Self::ADDRESS_SPACE.start) as usize;
+        let inner_range = start..start + range.end.offset_from(range.start) as usize;
+        let backend = self.memory.unsafe_as_mut();
+        #[cfg(feature = "poison-unitialized")]
+        backend.initialized.set_range(inner_range.clone(), false);
+        // The garbage is deterministic, so the emulation is reproducible.
+        fastrand::Rng::with_seed(start as u64).fill(&mut backend.mem[inner_range]);
+    }
+
     pub(crate) fn run_driver(slave: &mut SC::Component, ctx: &mut Context) {
         Iface::<SC>::run_driver(slave, ctx);
     }
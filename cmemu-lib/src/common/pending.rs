@@ -4,10 +4,11 @@
 /// having it set, or having a value moved out.
 /// Therefore, it is like [`crate::engine::flop::BufferFlop`], but it is not a flop,
 /// i.e., it may be used for stuff in a single cycle, where a value may be set in one of two messages.
+use serde::{Deserialize, Serialize};
 use std::mem;
 use std::ops::Deref;
 
-#[derive(PartialEq, Debug, Default)]
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
 pub(crate) enum Pending<T> {
     /// Not set, not waiting for data
     #[default]
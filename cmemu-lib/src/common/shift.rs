@@ -1,11 +1,12 @@
 use crate::Bitstring;
 use crate::common::bitstring::constants as bsc;
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
 /// Represents types of shift done by ARM Cortex-M3
 ///
 /// See: [ARM-ARM] A7.4.2 for their meaning
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub(crate) enum SRType {
     LSL,
     LSR,
@@ -31,7 +32,7 @@ impl fmt::Display for SRType {
 }
 
 /// Represents pair `(shift_t, shift_n)` from [ARM-ARM].
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub(crate) struct Shift {
     pub srtype: SRType,
     pub amount: u8,
@@ -1,6 +1,7 @@
 use std::ops::{Deref, DerefMut};
 
 use enum_map::{EnumArray, EnumMap};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::engine::Subcomponent;
 
@@ -115,6 +116,7 @@ impl<T> Deref for MaybeMut<'_, T> {
 }
 
 impl<C> MaybeMut<'_, C> {
+    #[allow(dead_code)]
     pub(crate) fn get_mut(&mut self) -> Option<&mut C> {
         match self {
             MaybeMut::Ref(_) => None,
@@ -145,3 +147,132 @@ pub trait FromMarker<M> {
     fn from_marker() -> Self;
     const MARKER_NAME: &'static str = "?";
 }
+
+/// A slot for a host-side object attached to a component (e.g., a modem backend).
+///
+/// Such objects are not a part of the emulated state: cloning the component
+/// (e.g., for an ``Emulator::snapshot``) yields an empty slot, so that the host object
+/// is never duplicated. ``Emulator::restore`` carries over the attached objects.
+/// For the same reason, the slot is serialized as empty.
+pub(crate) struct HostHandle<T>(Option<T>);
+
+impl<T> HostHandle<T> {
+    pub(crate) fn new(value: Option<T>) -> Self {
+        Self(value)
+    }
+}
+
+impl<T> Clone for HostHandle<T> {
+    fn clone(&self) -> Self {
+        Self(None)
+    }
+}
+
+impl<T> Serialize for HostHandle<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_unit()
+    }
+}
+
+impl<'de, T> Deserialize<'de> for HostHandle<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        <()>::deserialize(deserializer)?;
+        Ok(Self(None))
+    }
+}
+
+impl<T> Deref for HostHandle<T> {
+    type Target = Option<T>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for HostHandle<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// Serialization of types that don't implement `serde` traits, for use with `#[serde(with)]`.
+pub(crate) mod serde_with {
+    use serde::{Deserialize, Deserializer};
+
+    /// Arrays of any length (`serde` implements the traits up to 32 elements).
+    pub(crate) mod array {
+        use serde::de::Error;
+        use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+        pub(crate) fn serialize<S, T, const N: usize>(
+            array: &[T; N],
+            serializer: S,
+        ) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+            T: Serialize,
+        {
+            array.as_slice().serialize(serializer)
+        }
+
+        pub(crate) fn deserialize<'de, D, T, const N: usize>(
+            deserializer: D,
+        ) -> Result<[T; N], D::Error>
+        where
+            D: Deserializer<'de>,
+            T: Deserialize<'de>,
+        {
+            let vec = Vec::<T>::deserialize(deserializer)?;
+            let len = vec.len();
+            let expected = format!("an array of length {N}");
+            vec.try_into()
+                .map_err(|_| D::Error::invalid_length(len, &expected.as_str()))
+        }
+    }
+
+    /// A `&'static str`, deserialized by leaking a copy (meant for rare, short names).
+    pub(crate) fn leak_str<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<&'static str, D::Error> {
+        String::deserialize(deserializer).map(|s| &*s.leak())
+    }
+
+    /// A generated hardware register, stored as its raw value.
+    pub(crate) mod hw_register {
+        use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+        pub(crate) fn serialize<S, T>(register: &T, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+            T: Copy + Into<u32>,
+        {
+            Into::<u32>::into(*register).serialize(serializer)
+        }
+
+        pub(crate) fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+        where
+            D: Deserializer<'de>,
+            T: From<u32>,
+        {
+            u32::deserialize(deserializer).map(T::from)
+        }
+    }
+
+    /// A random number generator, stored as its current state.
+    pub(crate) mod rng {
+        use fastrand::Rng;
+        use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+        pub(crate) fn serialize<S: Serializer>(
+            rng: &Rng,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            rng.get_seed().serialize(serializer)
+        }
+
+        pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Rng, D::Error> {
+            u64::deserialize(deserializer).map(Rng::with_seed)
+        }
+    }
+}
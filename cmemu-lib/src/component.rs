@@ -4,16 +4,21 @@
 pub(crate) mod ahb_wiring;
 pub(crate) mod aon_bus;
 pub(crate) mod aon_event;
+pub(crate) mod batmon;
 pub(crate) mod bitband;
 pub(crate) mod bus_matrix;
 pub(crate) mod core;
+pub(crate) mod crypto;
 #[cfg(feature = "cycle-debug-logger")]
 pub(crate) mod cycle_debug_logger;
 pub(crate) mod dwt;
 pub(crate) mod event_fabric;
 pub(crate) mod flash;
+pub(crate) mod flash_controller;
 pub(crate) mod gpio;
 pub(crate) mod gpram;
+pub(crate) mod gpt;
+pub(crate) mod i2c;
 pub(crate) mod mem_mock;
 pub(crate) mod memory_bypass;
 pub(crate) mod nvic;
@@ -22,13 +27,19 @@ pub(crate) mod prcm;
 pub(crate) mod rfc;
 pub(crate) mod rom;
 pub(crate) mod rtc;
-pub(crate) mod rtc_bypass;
+pub(crate) mod rtc_shadow;
 pub(crate) mod semi_hosting;
+pub(crate) mod sensor_controller;
 pub(crate) mod sram;
+pub(crate) mod ssi;
 pub(crate) mod sync_down_bridge;
 pub(crate) mod sysbus;
+pub(crate) mod trng;
+pub(crate) mod uart;
 pub(crate) mod uart_lite;
+pub(crate) mod udma;
 pub(crate) mod vims;
+pub(crate) mod wdt;
 pub(crate) mod wuc;
 
 mod components {
@@ -37,13 +48,15 @@ mod components {
     include!(concat!(env!("OUT_DIR"), "/component.rs"));
 }
 pub(crate) use components::Components;
+use serde::{Deserialize, Serialize};
 
 // Clock tree is a special components, so it is treated separately
 pub(crate) mod clock_tree;
 pub(crate) use clock_tree::PowerClockManager;
 
 // TODO: move it to some sensible place
-#[derive(Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub(crate) enum WakeupEvent {
     Radio,
+    Gpio,
 }
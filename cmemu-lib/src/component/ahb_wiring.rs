@@ -1,10 +1,13 @@
 use crate::component::{
-    aon_bus, aon_bus::AonBusComponent, aon_event::AONEventComponent, bus_matrix, core,
-    dwt::DWTComponent, event_fabric::EventFabricComponent, flash::FlashComponent,
-    gpio::GPIOComponent, gpram::GPRAMComponent, mem_mock::MemoryMockComponent, nvic::NVICComponent,
+    aon_bus, aon_bus::AonBusComponent, aon_event::AONEventComponent, batmon::BatmonComponent,
+    bus_matrix, core, crypto, crypto::CryptoComponent, dwt::DWTComponent,
+    event_fabric::EventFabricComponent, flash::FlashComponent,
+    flash_controller::FlashControllerComponent, gpio::GPIOComponent, gpram::GPRAMComponent,
+    gpt::GPTComponent, i2c::I2CComponent, mem_mock::MemoryMockComponent, nvic::NVICComponent,
     osc::OSCComponent, prcm::PRCMComponent, rfc::RFCComponent, rom::ROMComponent,
-    rtc::RTCComponent, rtc_bypass::RTCBypass, sram::SRAMComponent, sysbus,
-    uart_lite::UARTLiteComponent, vims, wuc::WUCComponent,
+    rtc_shadow::RTCShadowComponent, sensor_controller::SensorControllerComponent,
+    sram::SRAMComponent, ssi::SSIComponent, sysbus, trng::TRNGComponent, uart::UARTComponent, udma,
+    udma::UDMAComponent, vims, wdt::WDTComponent, wuc::WUCComponent,
 };
 use crate::{bridge_ports, terminate_port};
 
@@ -21,13 +24,24 @@ bridge_ports!(@proxied @master bus_matrix::DwtM => @proxied @slave DWTComponent)
 bridge_ports!(@proxied @master sysbus::VimsMPort => @proxied @slave vims::SysbusSPort);
 bridge_ports!(@proxied @master sysbus::SramMPort => @proxied @slave SRAMComponent);
 bridge_ports!(@proxied @master sysbus::MemMockMPort => @proxied @slave MemoryMockComponent);
-bridge_ports!(@proxied @master sysbus::UartLiteMPort => @proxied @slave UARTLiteComponent);
+bridge_ports!(@proxied @master sysbus::SensorControllerMPort => @proxied @slave SensorControllerComponent);
 bridge_ports!(@proxied @master sysbus::PrcmMPort => @proxied @slave PRCMComponent);
 bridge_ports!(@proxied @master sysbus::AonBusMPort => @proxied @slave AonBusComponent);
 bridge_ports!(@proxied @master sysbus::GpioMPort => @proxied @slave GPIOComponent);
 bridge_ports!(@proxied @master sysbus::RfcMPort => @proxied @slave RFCComponent);
 bridge_ports!(@proxied @master sysbus::EventFabricMPort => @proxied @slave EventFabricComponent);
-bridge_ports!(@proxied @master sysbus::RTCBypassMPort => @proxied @slave RTCBypass);
+bridge_ports!(@proxied @master sysbus::RTCShadowMPort => @proxied @slave RTCShadowComponent);
+bridge_ports!(@proxied @master sysbus::FlashControllerMPort => @proxied @slave FlashControllerComponent);
+bridge_ports!(@proxied @master sysbus::GptMPort => @proxied @slave GPTComponent);
+bridge_ports!(@proxied @master sysbus::UartMPort => @proxied @slave UARTComponent);
+bridge_ports!(@proxied @master sysbus::UdmaMPort => @proxied @slave UDMAComponent);
+bridge_ports!(@proxied @master sysbus::SsiMPort => @proxied @slave SSIComponent);
+bridge_ports!(@proxied @master sysbus::I2cMPort => @proxied @slave I2CComponent);
+bridge_ports!(@proxied @master sysbus::WdtMPort => @proxied @slave WDTComponent);
+bridge_ports!(@proxied @master sysbus::CryptoMPort => @proxied @slave CryptoComponent);
+bridge_ports!(@proxied @master sysbus::TrngMPort => @proxied @slave TRNGComponent);
+bridge_ports!(@proxied @master udma::SysbusMPort => @proxied @slave sysbus::DmaSPort);
+bridge_ports!(@proxied @master crypto::SysbusMPort => @proxied @slave sysbus::CryptoSPort);
 
 bridge_ports!(@proxied @master core::IBusM => @proxied @slave bus_matrix::IBusS);
 bridge_ports!(@proxied @master core::DBusM => @proxied @slave bus_matrix::DBusS);
@@ -35,6 +49,6 @@ terminate_port!(@configured_slave bus_matrix::DebugS);
 
 // Slow busses follow next
 bridge_ports!(@proxied @master aon_bus::OscMPort => @proxied @slave OSCComponent);
-bridge_ports!(@proxied @master aon_bus::RtcMPort => @proxied @slave RTCComponent);
 bridge_ports!(@proxied @master aon_bus::AonEventMPort => @proxied @slave AONEventComponent);
 bridge_ports!(@proxied @master aon_bus::WucMPort => @proxied @slave WUCComponent);
+bridge_ports!(@proxied @master aon_bus::BatmonMPort => @proxied @slave BatmonComponent);
@@ -19,8 +19,8 @@ use crate::common::new_ahb::{AHBPortConfig, DataBus, SlaveToMasterWires};
 #[proxy_use(proxy_only)]
 use crate::component::aon_bus::PublicMasters;
 use crate::component::aon_event::AON_EVENT_ROUTE_INJECTION;
+use crate::component::batmon::BATMON_ROUTE_INJECTION;
 use crate::component::osc::OSC_ROUTE_INJECTION;
-use crate::component::rtc::RTC_ROUTE_INJECTION;
 use crate::component::sync_down_bridge::SyncDownBridge;
 use crate::component::wuc::WUC_ROUTE_INJECTION;
 #[proxy_use]
@@ -35,24 +35,35 @@ use cmemu_common::address::RangeUnion;
 use cmemu_common::{Address, address_match_range};
 use cmemu_proc_macros::{component_impl, handler, proxy_use};
 use log::trace;
+use serde::{Deserialize, Serialize};
 use std::ops::Range;
 
 // pub const AON_BUS_ROUTE_INJECTION: Range<Address> =
 //     soc::AON::ADDR_SPACE.start..soc::AUX::ADDR_SPACE.end;
+// The RTC is not here: it is accessed through its own MCU-AON interface, see `rtc_shadow`.
 #[allow(clippy::type_complexity)]
 pub const AON_BUS_ROUTE_INJECTION: RangeUnion<
     RangeUnion<Range<Address>, Range<Address>>,
     RangeUnion<Range<Address>, Range<Address>>,
 > = RangeUnion(
-    &RangeUnion(&OSC_ROUTE_INJECTION, &RTC_ROUTE_INJECTION),
+    &RangeUnion(&OSC_ROUTE_INJECTION, &BATMON_ROUTE_INJECTION),
     &RangeUnion(&AON_EVENT_ROUTE_INJECTION, &WUC_ROUTE_INJECTION),
 );
 
 pub(crate) type SlowDecoder = Decoder<SlowDecoderSC>;
 
+#[derive(Clone, Serialize, Deserialize)]
 pub(crate) struct SlowDecoderSC {}
 
-#[derive(MainComponent, TickComponent, TickComponentExtra, DisableableComponent)]
+#[derive(
+    Clone,
+    MainComponent,
+    TickComponent,
+    TickComponentExtra,
+    DisableableComponent,
+    Serialize,
+    Deserialize,
+)]
 pub(crate) struct AonBusComponent {
     #[subcomponent(SyncDownBridge)]
     sync_down_bridge: SyncDownBridge,
@@ -159,9 +170,9 @@ impl AhbDecoderTag for SlowDecoder {
 expose_ports! {
     AonBusComponent data = DataBus,
     Master PublicMasters [proxy=AonBusProxy.on_new_ahb_master_tagged_input] {
-        RtcMPort, // part of AON
         AonEventMPort, // part of AON
         WucMPort, // part of AON
+        BatmonMPort, // part of AON
         OscMPort, // part of AUX
     }
 }
@@ -170,9 +181,9 @@ expose_ports! {
 impl AhbDecode for Option<PublicMasters> {
     fn decode(addr: Address) -> Self {
         address_match_range!(addr,
-            soc::AON_RTC::ADDR_SPACE => Some(PublicMasters::RtcMPort),
             soc::AON_EVENT::ADDR_SPACE => Some(PublicMasters::AonEventMPort),
             soc::AON_WUC::ADDR_SPACE => Some(PublicMasters::WucMPort),
+            soc::AON_BATMON::ADDR_SPACE => Some(PublicMasters::BatmonMPort),
             soc::AUX_DDI0_OSC::ADDR_SPACE => Some(PublicMasters::OscMPort),
             _ => None,
         )
@@ -198,15 +209,15 @@ impl AhbMasterOutputDispatcher<Option<PublicMasters>> for SlowDecoder {
         msg: MasterToSlaveWires<Self::Data>,
     ) {
         match tag {
-            Some(PublicMasters::RtcMPort) => {
-                <RtcMPort as AHBMasterPortOutput>::send_ahb_output(comp, ctx, msg);
-            }
             Some(PublicMasters::AonEventMPort) => {
                 <AonEventMPort as AHBMasterPortOutput>::send_ahb_output(comp, ctx, msg);
             }
             Some(PublicMasters::WucMPort) => {
                 <WucMPort as AHBMasterPortOutput>::send_ahb_output(comp, ctx, msg);
             }
+            Some(PublicMasters::BatmonMPort) => {
+                <BatmonMPort as AHBMasterPortOutput>::send_ahb_output(comp, ctx, msg);
+            }
             Some(PublicMasters::OscMPort) => {
                 <OscMPort as AHBMasterPortOutput>::send_ahb_output(comp, ctx, msg);
             }
@@ -218,9 +229,9 @@ impl AhbMasterOutputDispatcher<Option<PublicMasters>> for SlowDecoder {
 }
 // This is second-level boilerplate (since expose_ports! require hard-vlan level routing)
 bridge_ports!(@no_m2s @master AhbPort<SlowDecoderSC, OscMPort> => @master OscMPort);
-bridge_ports!(@no_m2s @master AhbPort<SlowDecoderSC, RtcMPort> => @master RtcMPort);
 bridge_ports!(@no_m2s @master AhbPort<SlowDecoderSC, AonEventMPort> => @master AonEventMPort);
 bridge_ports!(@no_m2s @master AhbPort<SlowDecoderSC, WucMPort> => @master WucMPort);
+bridge_ports!(@no_m2s @master AhbPort<SlowDecoderSC, BatmonMPort> => @master BatmonMPort);
 
 impl crate::engine::Subcomponent for SlowDecoderSC {
     type Component = <AonBusComponent as crate::engine::Subcomponent>::Component;
@@ -20,29 +20,40 @@ use crate::engine::{
     Context, DisableableComponent, MainComponent, SkippableClockTreeNode, Subcomponent,
     TickComponent, TickComponentExtra,
 };
-use crate::proxy::{AONEventProxy, EventFabricProxy, RTCProxy, WUCProxy};
+use crate::proxy::{AONEventProxy, EventFabricProxy, RTCProxy, SensorControllerProxy, WUCProxy};
 use cc2650_constants::AON_EVENT;
 use cmemu_common::{Address, HwRegister};
 use cmemu_proc_macros::{component_impl, handler, proxy_use};
 use log::debug;
 use num_enum::{IntoPrimitive, TryFromPrimitive};
+use serde::{Deserialize, Serialize};
 use std::ops::Range;
 
 #[derive(
-    MainComponent, SkippableClockTreeNode, TickComponent, TickComponentExtra, DisableableComponent,
+    Clone,
+    MainComponent,
+    SkippableClockTreeNode,
+    TickComponent,
+    TickComponentExtra,
+    DisableableComponent,
 )]
 #[skippable_if_disableable]
+#[derive(Serialize, Deserialize)]
 pub(crate) struct AONEventComponent {
     #[subcomponent(DriverSC)]
     driver: BusDriver,
 
     #[flop]
+    #[serde(with = "crate::engine::serde_hw_register")]
     mcuwusel: SeqRegister<AON_EVENT::MCUWUSEL::Register>,
     #[flop]
+    #[serde(with = "crate::engine::serde_hw_register")]
     auxwusel: SeqRegister<AON_EVENT::AUXWUSEL::Register>,
     #[flop]
+    #[serde(with = "crate::engine::serde_hw_register")]
     evtomcusel: SeqRegister<AON_EVENT::EVTOMCUSEL::Register>,
     #[flop]
+    #[serde(with = "crate::engine::serde_hw_register")]
     rtcsel: SeqRegister<AON_EVENT::RTCSEL::Register>,
 }
 type BusDriver = SimpleSynchronousSlaveInterface<DriverSC, AONEventComponent>;
@@ -63,6 +74,11 @@ impl AONEventComponent {
         BusDriver::run_driver(self, ctx);
     }
 
+    /// Resets the bus interface, but keeps the registers, as in a warm reset.
+    pub(crate) fn reset_keeping_registers(&mut self) {
+        self.driver = Default::default();
+    }
+
     pub fn tock(&mut self, ctx: &mut Context) {
         BusDriver::tock(self, ctx);
     }
@@ -251,6 +267,10 @@ impl AONEventComponent {
             AonEvent::RTC_UPD => EventFabricProxy.notify(ctx, EventFabricEvent::AON_RTC_UPD),
             _ => None,
         };
+        // The channel 2 of the RTC is a wakeup event of AUX, see `AUX_WUC:WUEVFLAGS.AON_RTC_CH2`.
+        if event == AonEvent::RTC_CH2 {
+            SensorControllerProxy.on_rtc_ch2_event(ctx);
+        }
 
         let rtcsel = self.rtcsel.bitfields();
         if rtcsel.RTC_CH1_CAPT_EV() == raw {
@@ -260,7 +280,9 @@ impl AONEventComponent {
 }
 
 #[allow(non_camel_case_types, dead_code)]
-#[derive(Debug, Copy, Clone, Eq, PartialEq, TryFromPrimitive, IntoPrimitive)]
+#[derive(
+    Debug, Copy, Clone, Eq, PartialEq, TryFromPrimitive, IntoPrimitive, Serialize, Deserialize,
+)]
 #[repr(u8)]
 pub(crate) enum AonEvent {
     /// No event, always low
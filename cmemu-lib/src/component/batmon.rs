@@ -0,0 +1,319 @@
+pub const BATMON_ROUTE_INJECTION: Range<Address> = BATMON::ADDR_SPACE;
+
+use crate::bridge_ports;
+#[proxy_use]
+use crate::common::Address;
+#[proxy_use]
+use crate::common::new_ahb::databus::DataBus;
+use crate::common::new_ahb::ports::AHBSlavePortProxiedInput;
+#[proxy_use]
+use crate::common::new_ahb::ports::{AHBPortConfig, AHBSlavePortInput};
+#[proxy_use]
+use crate::common::new_ahb::signals::{MasterToSlaveWires, Size};
+use crate::common::new_ahb::slave_driver::stateless_simplifiers::AlignedHandler;
+use crate::common::new_ahb::slave_driver::{
+    SimpleResponse, SimpleSynchronousSlaveInterface, SimpleWriteResponse, WriteMode,
+};
+use crate::component::aon_event::AonEvent;
+use crate::engine::AnalogInput;
+#[proxy_use]
+use crate::engine::{
+    Context, DisableableComponent, MainComponent, SkippableClockTreeNode, TickComponent,
+    TickComponentExtra,
+};
+use crate::proxy::{AONEventProxy, BatmonProxy};
+use cc2650_constants::AON_BATMON as BATMON;
+use cmemu_proc_macros::{component_impl, handler, proxy_use};
+use log::{debug, trace, warn};
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+// [TI-TRM] 18 Battery Monitor and Temperature Sensor
+// The battery monitor measures the supply voltage (VDDS) and the temperature of the chip,
+// while both `CTL.MEAS_EN` and `CTL.CALC_EN` are set. The results are in `BAT` (in 1/256 V)
+// and in `TEMP` (in whole °C). When a result differs from the previous one, it is updated,
+// `BATUPD` or `TEMPUPD` is set, and the `BATMON_VOLT` or `BATMON_TEMP` AON event is raised.
+// The values come from the host, see `AnalogInput`.
+// The timing of the measurements is not documented, it is assumed that a measurement takes
+// a cycle of `SCLK_LF`, and the next one starts after the period of `MEASCFG.PER`
+// (continuously, or every 8, 16 or 32 cycles).
+// Not implemented: the trims (`TEMPP*`, `BATMONP*`, `IOSTRP0` and `FLASHPUMPP0` are plain
+// storage), and the measurement errors.
+
+const CTL_ENABLED: u32 = BATMON::CTL::MEAS_EN::BIT_MASK | BATMON::CTL::CALC_EN::BIT_MASK;
+
+#[derive(
+    Clone,
+    MainComponent,
+    TickComponent,
+    TickComponentExtra,
+    DisableableComponent,
+    Serialize,
+    Deserialize,
+)]
+pub(crate) struct BatmonComponent {
+    #[subcomponent(DriverSC)]
+    driver: BusDriver,
+
+    ctl: u32,
+    meascfg: u32,
+    /// `TEMPP0`, `TEMPP1`, `TEMPP2`, `BATMONP0`, `BATMONP1`, `IOSTRP0` and `FLASHPUMPP0`.
+    trims: [u32; TRIM_REGISTERS.len()],
+    bat: u32,
+    batupd: u32,
+    temp: u32,
+    tempupd: u32,
+
+    /// Cycles of `SCLK_LF` until the next measurement, while enabled.
+    cycles_to_measurement: u64,
+}
+type BusDriver = SimpleSynchronousSlaveInterface<DriverSC, BatmonComponent>;
+
+/// The registers of the trims, which are only stored.
+const TRIM_REGISTERS: [Address; 7] = [
+    BATMON::TEMPP0::ADDR,
+    BATMON::TEMPP1::ADDR,
+    BATMON::TEMPP2::ADDR,
+    BATMON::BATMONP0::ADDR,
+    BATMON::BATMONP1::ADDR,
+    BATMON::IOSTRP0::ADDR,
+    BATMON::FLASHPUMPP0::ADDR,
+];
+
+#[component_impl(batmon)]
+impl BatmonComponent {
+    pub(crate) fn new() -> Self {
+        Self {
+            driver: Default::default(),
+
+            ctl: BATMON::CTL::RESET_VALUE,
+            meascfg: BATMON::MEASCFG::RESET_VALUE,
+            trims: [
+                BATMON::TEMPP0::RESET_VALUE,
+                BATMON::TEMPP1::RESET_VALUE,
+                BATMON::TEMPP2::RESET_VALUE,
+                BATMON::BATMONP0::RESET_VALUE,
+                BATMON::BATMONP1::RESET_VALUE,
+                BATMON::IOSTRP0::RESET_VALUE,
+                BATMON::FLASHPUMPP0::RESET_VALUE,
+            ],
+            bat: BATMON::BAT::RESET_VALUE,
+            batupd: BATMON::BATUPD::RESET_VALUE,
+            temp: BATMON::TEMP::RESET_VALUE,
+            tempupd: BATMON::TEMPUPD::RESET_VALUE,
+
+            cycles_to_measurement: 0,
+        }
+    }
+
+    /// Resets the bus interface, but keeps the registers, as in a warm reset.
+    pub(crate) fn reset_keeping_registers(&mut self) {
+        self.driver = Default::default();
+    }
+
+    pub(crate) fn tick(&mut self, ctx: &mut Context) {
+        BusDriver::run_driver(self, ctx);
+
+        if !self.is_enabled() {
+            return;
+        }
+        if self.cycles_to_measurement > 1 {
+            self.cycles_to_measurement -= 1;
+        } else {
+            self.measure(ctx);
+            self.cycles_to_measurement = self.period();
+        }
+    }
+
+    pub(crate) fn tock(&mut self, ctx: &mut Context) {
+        BusDriver::tock(self, ctx);
+    }
+
+    #[handler]
+    pub(crate) fn on_new_ahb_slave_input(
+        &mut self,
+        ctx: &mut Context,
+        msg: MasterToSlaveWires<<BatmonComponent as AHBPortConfig>::Data>,
+    ) {
+        <Self as AHBSlavePortInput>::on_ahb_input(self, ctx, msg);
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.ctl & CTL_ENABLED == CTL_ENABLED
+    }
+
+    /// Cycles of `SCLK_LF` between the starts of the measurements.
+    fn period(&self) -> u64 {
+        match self.meascfg & BATMON::MEASCFG::PER::BIT_MASK {
+            BATMON::MEASCFG::PER::Named::_8CYC => 8,
+            BATMON::MEASCFG::PER::Named::_16CYC => 16,
+            BATMON::MEASCFG::PER::Named::_32CYC => 32,
+            _ => 1,
+        }
+    }
+
+    fn measure(&mut self, ctx: &mut Context) {
+        let voltage = ctx.sample_analog(AnalogInput::SupplyVoltage);
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let bat = (voltage * 256.0).round().clamp(
+            0.0,
+            f64::from(BATMON::BAT::INT::BIT_MASK | BATMON::BAT::FRAC::BIT_MASK),
+        ) as u32;
+        if bat != self.bat {
+            trace!("BATMON: {voltage:.3} V");
+            self.bat = bat;
+            self.batupd = BATMON::BATUPD::STAT::BIT_MASK;
+            AONEventProxy.notify(ctx, AonEvent::BATMON_VOLT);
+        }
+
+        let temperature = ctx.sample_analog(AnalogInput::Temperature);
+        #[allow(clippy::cast_possible_truncation)]
+        let degrees = temperature.round().clamp(-256.0, 255.0) as i32;
+        #[allow(clippy::cast_sign_loss)]
+        let temp = ((degrees as u32) << BATMON::TEMP::INT::BIT_RANGE.start())
+            & BATMON::TEMP::INT::BIT_MASK;
+        if temp != self.temp {
+            trace!("BATMON: {temperature:.1} °C");
+            self.temp = temp;
+            self.tempupd = BATMON::TEMPUPD::STAT::BIT_MASK;
+            AONEventProxy.notify(ctx, AonEvent::BATMON_TEMP);
+        }
+    }
+
+    fn set_data_for_address(&mut self, addr: Address, data: u32) {
+        match addr {
+            BATMON::CTL::ADDR => {
+                if !self.is_enabled() && data & CTL_ENABLED == CTL_ENABLED {
+                    debug!("BATMON enabled");
+                    self.cycles_to_measurement = self.period();
+                }
+                self.ctl = data & CTL_ENABLED;
+            }
+            BATMON::MEASCFG::ADDR => self.meascfg = data & BATMON::MEASCFG::PER::BIT_MASK,
+            // Writing 1 clears the flag.
+            BATMON::BATUPD::ADDR => self.batupd &= !data,
+            BATMON::TEMPUPD::ADDR => self.tempupd &= !data,
+            BATMON::BAT::ADDR | BATMON::TEMP::ADDR => {
+                warn!("Ignoring a write {data:#x} to the read-only BATMON register at {addr:?}");
+            }
+            a => {
+                let Some(i) = TRIM_REGISTERS.iter().position(|&r| r == a) else {
+                    unimplemented!("Requested BATMON data write {data:#x} for address {a:?}")
+                };
+                self.trims[i] = data;
+            }
+        }
+    }
+
+    fn get_data_for_address(&self, addr: Address) -> u32 {
+        match addr {
+            BATMON::CTL::ADDR => self.ctl,
+            BATMON::MEASCFG::ADDR => self.meascfg,
+            BATMON::BAT::ADDR => self.bat,
+            BATMON::BATUPD::ADDR => self.batupd,
+            BATMON::TEMP::ADDR => self.temp,
+            BATMON::TEMPUPD::ADDR => self.tempupd,
+            a => {
+                let Some(i) = TRIM_REGISTERS.iter().position(|&r| r == a) else {
+                    unimplemented!("Requested BATMON data read for address {a:?}")
+                };
+                self.trims[i]
+            }
+        }
+    }
+
+    /// Cycles of `SCLK_LF` until a tick, which measures.
+    fn cycles_until_event(&self) -> Option<u64> {
+        self.is_enabled().then_some(self.cycles_to_measurement)
+    }
+}
+
+bridge_ports!(@slave BatmonComponent => @slave BusDriver);
+
+#[component_impl(batmon)]
+impl AHBPortConfig for BatmonComponent {
+    type Data = DataBus;
+    type Component = Self;
+    const TAG: &'static str = "BATMON";
+}
+#[component_impl(batmon)]
+impl AHBSlavePortProxiedInput for BatmonComponent {
+    fn proxy_ahb_input(ctx: &mut Context, msg: MasterToSlaveWires<Self::Data>) {
+        BatmonProxy.on_new_ahb_slave_input(ctx, msg);
+    }
+}
+
+#[component_impl(batmon)]
+impl AlignedHandler for BatmonComponent {
+    const WRITE_MODE: WriteMode = WriteMode::Combinatorial;
+    const ALIGN: Size = Size::Word;
+    type Native = u32;
+
+    fn read_for_write_filler(
+        slave: &Self::Component,
+        _ctx: &Context,
+        address: Address,
+    ) -> Self::Native {
+        slave.get_data_for_address(address)
+    }
+
+    fn pre_write(
+        _slave: &mut Self::Component,
+        _ctx: &mut Context,
+        _address: Address,
+    ) -> SimpleWriteResponse {
+        SimpleWriteResponse::Pending
+    }
+
+    fn read_data(
+        slave: &mut Self::Component,
+        _ctx: &mut Context,
+        address: Address,
+    ) -> SimpleResponse<Self::Native> {
+        SimpleResponse::Success(slave.get_data_for_address(address))
+    }
+
+    fn write_data(
+        slave: &mut Self::Component,
+        _ctx: &mut Context,
+        address: Address,
+        data: Self::Native,
+        post_success: bool,
+    ) -> SimpleWriteResponse {
+        if post_success {
+            slave.set_data_for_address(address, data);
+        }
+        SimpleWriteResponse::SUCCESS
+    }
+}
+
+#[component_impl(batmon)]
+impl SkippableClockTreeNode for BatmonComponent {
+    fn max_cycles_to_skip(
+        comp: &mut Self::Component,
+        _ctx: &mut Context,
+        _parent: Self::IdSpace,
+        _extra: &mut Self::Extra,
+    ) -> u64 {
+        if !comp.can_be_disabled_now() {
+            0
+        } else if let Some(cycles) = comp.cycles_until_event() {
+            // -1, so that the measurement is done in a tick
+            cycles.saturating_sub(1)
+        } else {
+            u64::MAX
+        }
+    }
+
+    fn emulate_skipped_cycles(
+        comp: &mut Self::Component,
+        _ctx: &mut Context,
+        _parent: Self::IdSpace,
+        _extra: &mut Self::Extra,
+        skipped_cycles: u64,
+    ) {
+        if comp.is_enabled() {
+            comp.cycles_to_measurement -= skipped_cycles;
+        }
+    }
+}
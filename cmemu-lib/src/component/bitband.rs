@@ -21,6 +21,7 @@ use crate::engine::{
 };
 use cc2650_constants::CoreMap;
 use cmemu_common::address::offset_range;
+use serde::{Deserialize, Serialize};
 use std::ops::Range;
 
 /// [ARM-TRM] 3.4 - Figure 3-1 - SRAM
@@ -57,7 +58,15 @@ pub(crate) const PERIPH_BITBAND_ALIAS_REGION: Range<Address> =
 /// Contains quite a lot of code copied from previous implementation
 /// which could be found at cmemu-framework/cmemu-lib/src/component/bus_matrix/bitband.rs.
 
-#[derive(Subcomponent, TickComponent, TickComponentExtra, DisableableComponent)]
+#[derive(
+    Clone,
+    Subcomponent,
+    TickComponent,
+    TickComponentExtra,
+    DisableableComponent,
+    Serialize,
+    Deserialize,
+)]
 #[subcomponent_1to1]
 pub(crate) struct Bitband {
     #[subcomponent(pub(crate) DriverSC)]
@@ -77,6 +86,7 @@ pub(crate) struct Bitband {
 pub(crate) type BusDriver = SimpleSynchronousSlaveInterface<DriverSC, Bitband>;
 pub(crate) type DBusDriver = MasterDriver<DBusDriverSC, Bitband>;
 
+#[derive(Clone, Serialize, Deserialize)]
 enum DataRequest {
     Read(Address),
     Write(Address, u8),
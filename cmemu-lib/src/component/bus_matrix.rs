@@ -24,6 +24,7 @@
 //! - [`interconnect`] implements the main Bus Matrix logic of routing and arbitration,
 //! - [`internal_routing`] is in a separate module, to route the publicly exposed ports internally,
 //! - [`aligner`], `bitband`, [`mpu`], [`registration_buffer`] implement the "adjacent blocks",
+//!   the MPU configuration is shared with the NVIC and the Core through [`MPUConfiguration`],
 //! - [`ppb`] hosts the PPB bus implementation (dispatch to mentioned [`PpbMasters`]).
 // Bibliography:
 //  [AHB] AMBA 3 AHB-Lite Protocol
@@ -31,6 +32,7 @@
 //  Also see CMSDK.
 
 use cmemu_proc_macros::{component_impl, handler, proxy_use};
+use serde::{Deserialize, Serialize};
 
 use crate::common::new_ahb::databus::DataBus;
 #[proxy_use]
@@ -42,7 +44,7 @@ use crate::common::new_ahb::write_buffer::WriteBuffer;
 use crate::component::bus_matrix::aligner::Aligner;
 use crate::component::bus_matrix::registration_buffer::IBusRegistrationBuffer;
 #[proxy_use(proxy_only)]
-use crate::component::bus_matrix::{PpbMasters, PublicMasters, PublicSlaves};
+use crate::component::bus_matrix::{MPUConfiguration, PpbMasters, PublicMasters, PublicSlaves};
 #[proxy_use]
 use crate::engine::{Context, PowerNode};
 use crate::engine::{
@@ -63,11 +65,21 @@ mod mpu;
 mod ppb;
 mod registration_buffer;
 
+pub(crate) use self::mpu::{MPU_REGIONS_COUNT, MPUConfiguration, MPUError}; // NVIC and Core export
+
 // ===========================================================================
 // BusMatrixComponent
 // ===========================================================================
 
-#[derive(MainComponent, SkippableClockTreeNode, TickComponent, TickComponentExtra)]
+#[derive(
+    Clone,
+    MainComponent,
+    SkippableClockTreeNode,
+    TickComponent,
+    TickComponentExtra,
+    Serialize,
+    Deserialize,
+)]
 #[skippable_if_disableable]
 pub(crate) struct BusMatrixComponent {
     #[subcomponent(Interconnect)]
@@ -83,6 +95,8 @@ pub(crate) struct BusMatrixComponent {
     dcode_aligner: DBusAligner,
     #[subcomponent(DCodeWBSC)]
     dcode_wb: DCodeWB,
+    /// Copy of the MPU configuration kept by the NVIC, see [`Self::update_mpu_configuration`].
+    mpu: MPUConfiguration,
     // #[subcomponent(Bitband)]
     // bitband: Bitband,
 }
@@ -103,6 +117,7 @@ impl BusMatrixComponent {
             sys_wb: WriteBuffer::new(),
             dcode_wb: WriteBuffer::new(),
             dcode_aligner: Aligner::new(),
+            mpu: MPUConfiguration::new(),
         }
     }
 
@@ -134,6 +149,15 @@ impl BusMatrixComponent {
         PPB::tock(self, ctx);
     }
 
+    #[handler]
+    pub(crate) fn update_mpu_configuration(
+        &mut self,
+        _ctx: &mut Context,
+        configuration: MPUConfiguration,
+    ) {
+        self.mpu = configuration;
+    }
+
     #[handler]
     pub fn on_new_ahb_master_tagged_input(
         &mut self,
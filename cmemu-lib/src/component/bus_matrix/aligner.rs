@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use std::marker::PhantomData;
 
 use log::trace;
@@ -52,7 +53,7 @@ pub(crate) trait AlignerCfg: AHBPortConfig {
     }
 }
 
-#[derive(Subcomponent, TickComponent, DisableableComponent)]
+#[derive(Clone, Subcomponent, TickComponent, DisableableComponent, Serialize, Deserialize)]
 pub(crate) struct Aligner<SC>
 where
     SC: Subcomponent<Member = Self>,
@@ -10,7 +10,7 @@
 use std::fmt::Debug;
 
 use cc2650_constants::CoreMap::{Bus, CoreMemoryMap};
-use cc2650_constants::MPU;
+use log::debug;
 
 use crate::common::Address;
 use crate::common::new_ahb::arbiter::{
@@ -25,7 +25,7 @@ use crate::common::new_ahb::ports::{
     AHBSlavePortInput, AHBSlavePortOutput, AhbMasterPortInputWithGranting,
     AhbSlavePortOutputWithGranting,
 };
-use crate::common::new_ahb::signals::{MasterToSlaveWires, TrackedBool};
+use crate::common::new_ahb::signals::{MasterToSlaveWires, TrackedBool, TransferMeta};
 use crate::common::new_ahb::vlan::{
     AHBSoftVlanSlavePortInput, AhbDecoderTag, AhbMultiMasterConfig, AhbSlaveOutputDispatcher,
 };
@@ -102,14 +102,27 @@ impl TransparentInputStageCfg for TransparentInputStage<DebuggerInputSC> {
 impl AhbDecoderTag for decoder::Decoder<InstructionDecoderSC> {
     type Enum = Option<MasterPorts>;
 
-    fn decode(addr: Address) -> Self::Enum {
-        // Vendor is permanently XN
+    fn dynamic_decode(
+        comp: &BusMatrixComponent,
+        _ctx: &mut Context,
+        meta: &TransferMeta,
+    ) -> Self::Enum {
+        // Fetches denied by the MPU (also the default memory map XN with the MPU disabled)
+        // never reach a slave. The Core decided it is a MemManage fault when requesting the fetch,
+        // and raises it only if it attempts to execute the instruction.
         // [ARM-ARM] B3.5.2 Behavior when the MPU is disabled
-        let region = CoreMemoryMap::from(addr);
-        if MPU::XN::from(region) == MPU::XN::InstructionFetchDisabled {
-            todo!("We should generate MemManage fault")
+        // Vector table reads (marked as data accesses) always use the default memory map.
+        if meta.prot.is_instruction()
+            && let Err(err) = comp.mpu.validate(meta.addr, meta.dir, meta.prot)
+        {
+            debug!("MPU denies fetch from {:?}: {}", meta.addr, err);
+            return None;
         }
-        let bus = Bus::from_map(region, false);
+        Self::decode(meta.addr)
+    }
+
+    fn decode(addr: Address) -> Self::Enum {
+        let bus = Bus::from_map(CoreMemoryMap::from(addr), false);
 
         // XXX: For now we route to DCode to do the arbitration -- but it may require separation
         if bus == Bus::ICode {
@@ -117,12 +130,26 @@ impl AhbDecoderTag for decoder::Decoder<InstructionDecoderSC> {
         }
         Some(bus.into())
     }
+
     const REFLECTS_HREADY: bool = false;
     const GRANTER: Option<fn(&mut Self::Component, &mut Context, TrackedBool)> =
         Some(<Self as AhbSlavePortOutputWithGranting>::send_grant_wire);
 }
 impl AhbDecoderTag for decoder::Decoder<DataDecoderSC> {
     type Enum = Option<MasterPorts>;
+
+    fn dynamic_decode(
+        comp: &BusMatrixComponent,
+        _ctx: &mut Context,
+        meta: &TransferMeta,
+    ) -> Self::Enum {
+        // The Core classifies the error response of a denied access as a MemManage fault.
+        if let Err(err) = comp.mpu.validate(meta.addr, meta.dir, meta.prot) {
+            debug!("MPU denies access to {:?}: {}", meta.addr, err);
+            return None;
+        }
+        Self::decode(meta.addr)
+    }
     const REFLECTS_HREADY: bool = false;
     const GRANTER: Option<fn(&mut Self::Component, &mut Context, TrackedBool)> =
         Some(<Self as AhbSlavePortOutputWithGranting>::send_grant_wire);
@@ -1,58 +1,513 @@
-// This module is for the future
-#![allow(dead_code, unreachable_code, unused_variables)]
-use crate::common::new_ahb::signals::TransferMeta;
-use cc2650_constants::{CoreMap, MPU, is_unbuffered_alias};
+//! Memory Protection Unit, as in [ARM-ARM] B3.5 Protected Memory System Architecture, `PMSAv7`
+//!
+//! The MPU registers live in the System Control Space, therefore they are implemented by the NVIC.
+//! On every change the NVIC sends the whole [`MPUConfiguration`] to the Bus Matrix,
+//! which denies violating transfers with an error response, so they never reach a slave.
+//! The Core keeps a copy as well: it sets `HPROT` from the memory attributes of the regions,
+//! faults precisely on denied accesses of instructions, and tells a `MemManage` fault
+//! from a `BusFault` on error responses.
+//!
+//! Without the `soc-has-mpu` feature no regions are implemented (`MPU_TYPE.DREGION == 0`),
+//! so only the default memory map applies.
+use crate::common::new_ahb::signals::{Direction, Protection};
+use crate::common::{Address, BitstringUtils, Word};
+use cc2650_constants::CoreMap::{CoreMemoryMap, PPB_RANGE};
+use cc2650_constants::MPU;
+use log::warn;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-#[derive(Error, Debug)]
-enum MPUError {
-    #[error("aaa")]
+/// Number of regions reported in `MPU_TYPE.DREGION`. Cortex-M3 implements either none or eight.
+pub(crate) const MPU_REGIONS_COUNT: usize = if cfg!(feature = "soc-has-mpu") { 8 } else { 0 };
+
+#[derive(Error, Debug, Clone, Copy, PartialEq)]
+pub(crate) enum MPUError {
+    #[error("instruction fetch from an Execute Never region")]
     FetchDisallowed,
-    #[error("bbb")]
+    #[error("access not permitted by the region access permissions: {0:?}")]
     AccessDenied(MPU::Access),
+    #[error("no region matches and the background region is not enabled")]
+    BackgroundRegionDisallowed,
+}
+
+/// A single region, as described by its `MPU_RBAR` and `MPU_RASR` registers.
+/// [ARM-ARM] B3.5.8 and B3.5.9
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct MPURegion {
+    /// `MPU_RBAR.ADDR` (the `VALID` and `REGION` fields are not stored).
+    base: Word,
+    /// `MPU_RASR`
+    attributes: Word,
+}
+
+impl MPURegion {
+    const ADDR_MASK: u32 = !0b1_1111;
+    /// Reserved bits of `MPU_RASR` are RAZ/WI.
+    const RASR_MASK: u32 = 0b0001_0111_0011_1111_1111_1111_0011_1111;
+
+    const ENABLE_BITNUM: u32 = 0;
+    const B_BITNUM: u32 = 16;
+    const C_BITNUM: u32 = 17;
+    const S_BITNUM: u32 = 18;
+    const XN_BITNUM: u32 = 28;
+
+    const fn new() -> Self {
+        Self {
+            base: Word::from_const(0),
+            attributes: Word::from_const(0),
+        }
+    }
+
+    fn field(self, lsb: u32, width: u32) -> u32 {
+        (u32::from(self.attributes) >> lsb) & ((1 << width) - 1)
+    }
+
+    fn is_enabled(self) -> bool {
+        self.attributes.get_bit(Self::ENABLE_BITNUM)
+    }
+
+    /// The region size is `2^(SIZE+1)` bytes, but at least 32 bytes.
+    fn size_log2(self) -> u32 {
+        self.field(1, 5).max(4) + 1
+    }
+
+    /// Does the address hit the region? A disabled subregion is not a part of the region.
+    fn matches(self, addr: Address) -> bool {
+        if !self.is_enabled() {
+            return false;
+        }
+        let size_log2 = self.size_log2();
+        let mask = u32::MAX.checked_shl(size_log2).unwrap_or(0);
+        let offset = u32::from(addr).wrapping_sub(u32::from(self.base) & mask);
+        if offset & mask != 0 {
+            return false;
+        }
+        // [ARM-ARM] B3.5.4 Subregions are supported only for regions of 256 bytes or more.
+        if size_log2 < 8 {
+            return true;
+        }
+        let subregion = offset >> (size_log2 - 3);
+        (self.field(8, 8) >> subregion) & 1 == 0
+    }
+
+    fn is_execute_never(self) -> bool {
+        self.attributes.get_bit(Self::XN_BITNUM)
+    }
+
+    fn access_permissions(self) -> MPU::AP {
+        #[allow(clippy::cast_possible_truncation)]
+        MPU::AP::from(self.field(24, 3) as u8)
+    }
+
+    /// Maps the TEX, C, B encoding onto (`HPROT[3]` Cacheable, `HPROT[2]` Bufferable).
+    /// [ARM-TRM-G] Table 9-8 TEX, C, B encoding
+    #[allow(clippy::cast_possible_truncation)]
+    fn cacheable_and_bufferable(self) -> (bool, bool) {
+        let attributes = MPU::TEX_C_B::from_bits(
+            self.field(19, 3) as u8,
+            u8::from(self.attributes.get_bit(Self::C_BITNUM)),
+            u8::from(self.attributes.get_bit(Self::B_BITNUM)),
+            self.attributes.get_bit(Self::S_BITNUM),
+        );
+        match attributes.memory_type() {
+            MPU::MemoryType::Device => (false, true),
+            MPU::MemoryType::Normal => match attributes.inner_cache_policy() {
+                MPU::CachePolicy::NonCachable => (false, true),
+                MPU::CachePolicy::WriteTroughNoWriteAllocate => (true, false),
+                _ => (true, true),
+            },
+            _ => (false, false),
+        }
+    }
 }
 
-// TODO: pass wires through it
-fn baseline_mpu_pass(mut meta: TransferMeta) -> Result<TransferMeta, MPUError> {
-    let memmap = CoreMap::CoreMemoryMap::from(meta.addr);
-    let old_prot = meta.prot;
+/// The state of `MPU_CTRL` and of all the regions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct MPUConfiguration {
+    /// `MPU_CTRL`
+    ctrl: Word,
+    /// Set by the NVIC when the MPU is disabled due to a negative execution priority.
+    bypassed: bool,
+    regions: [MPURegion; MPU_REGIONS_COUNT],
+}
+
+#[allow(
+    clippy::trivially_copy_pass_by_ref,
+    reason = "It is small only without the soc-has-mpu feature"
+)]
+impl MPUConfiguration {
+    const CTRL_MASK: u32 = 0b111;
+    const ENABLE_BITNUM: u32 = 0;
+    const HFNMIENA_BITNUM: u32 = 1;
+    const PRIVDEFENA_BITNUM: u32 = 2;
+
+    pub(crate) const fn new() -> Self {
+        Self {
+            ctrl: Word::from_const(0),
+            bypassed: false,
+            regions: [MPURegion::new(); MPU_REGIONS_COUNT],
+        }
+    }
+
+    pub(crate) fn ctrl(&self) -> Word {
+        self.ctrl
+    }
+
+    pub(crate) fn set_ctrl(&mut self, ctrl: Word) {
+        self.ctrl = ctrl & Word::from_const(Self::CTRL_MASK);
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.ctrl.get_bit(Self::ENABLE_BITNUM)
+    }
+
+    pub(crate) fn is_hfnmi_enabled(&self) -> bool {
+        self.ctrl.get_bit(Self::HFNMIENA_BITNUM)
+    }
+
+    pub(crate) fn is_bypassed(&self) -> bool {
+        self.bypassed
+    }
 
-    let xn = MPU::XN::from(memmap);
-    let cache_policy = MPU::CachePolicy::from(memmap);
+    /// [ARM-ARM] B3.5.7: with `MPU_CTRL.HFNMIENA` clear, the MPU is disabled
+    /// while the execution priority is negative.
+    pub(crate) fn set_bypassed(&mut self, bypassed: bool) {
+        self.bypassed = bypassed;
+    }
+
+    pub(crate) fn region_base(&self, region: usize) -> Word {
+        self.regions[region].base
+    }
+
+    pub(crate) fn set_region_base(&mut self, region: usize, base: Word) {
+        self.regions[region].base = base & Word::from_const(MPURegion::ADDR_MASK);
+    }
+
+    pub(crate) fn region_attributes(&self, region: usize) -> Word {
+        self.regions[region].attributes
+    }
 
-    if old_prot.is_instruction() && xn == MPU::XN::InstructionFetchDisabled {
-        return Err(MPUError::FetchDisallowed);
+    pub(crate) fn set_region_attributes(&mut self, region: usize, attributes: Word) {
+        let region = &mut self.regions[region];
+        region.attributes = attributes & Word::from_const(MPURegion::RASR_MASK);
+        if region.is_enabled() && region.field(1, 5) < 4 {
+            warn!("MPU region SIZE below 4 is UNPREDICTABLE, using 32 bytes");
+        }
     }
-    // LOCAL_TODO: Those policies mean something for writes!
-    meta.prot.is_cacheable &= cache_policy != MPU::CachePolicy::NonCachable;
 
-    if let Some(addr) = is_unbuffered_alias(meta.addr) {
-        meta.addr = addr;
-        meta.prot.is_bufferable = false;
+    /// Finds the region that defines the attributes of `addr`,
+    /// `None` stands for the default memory map.
+    ///
+    /// [ARM-ARM] B3.5.3 `ValidateAddress()`
+    fn matching_region(
+        &self,
+        addr: Address,
+        is_privileged: bool,
+    ) -> Result<Option<MPURegion>, MPUError> {
+        // Accesses to the PPB always use the default memory map.
+        if !self.is_enabled() || self.bypassed || PPB_RANGE.contains(&addr) {
+            return Ok(None);
+        }
+        // The highest-numbered matching region takes priority.
+        if let Some(region) = self.regions.iter().rev().find(|r| r.matches(addr)) {
+            Ok(Some(*region))
+        } else if is_privileged && self.ctrl.get_bit(Self::PRIVDEFENA_BITNUM) {
+            Ok(None)
+        } else {
+            Err(MPUError::BackgroundRegionDisallowed)
+        }
+    }
+
+    /// Checks if an access is permitted.
+    /// Vector table reads are not checked, as they always use the default memory map.
+    ///
+    /// [ARM-ARM] B3.5.3 `ValidateAddress()`
+    pub(crate) fn validate(
+        &self,
+        addr: Address,
+        dir: Direction,
+        prot: Protection,
+    ) -> Result<(), MPUError> {
+        let region = self.matching_region(addr, prot.is_privileged)?;
+        if prot.is_instruction() {
+            let memory_map = CoreMemoryMap::from(addr);
+            let default_xn = MPU::XN::from(memory_map) == MPU::XN::InstructionFetchDisabled;
+            let xn = region.map_or(default_xn, |r| {
+                r.is_execute_never() || MPU::XN::is_region_permanently_xn(memory_map)
+            });
+            if xn {
+                return Err(MPUError::FetchDisallowed);
+            }
+        }
+        let Some(region) = region else {
+            return Ok(());
+        };
+        let ap = region.access_permissions();
+        let access = if prot.is_privileged {
+            ap.privileged
+        } else {
+            ap.user
+        };
+        match (access, dir) {
+            (MPU::Access::ReadWrite, _) | (MPU::Access::ReadOnly, Direction::Read) => Ok(()),
+            _ => Err(MPUError::AccessDenied(access)),
+        }
+    }
+
+    /// Sets the Cacheable and Bufferable `HPROT` bits from the attributes of the region
+    /// matching `addr`. The default memory map keeps them untouched.
+    pub(crate) fn apply_memory_attributes(&self, addr: Address, prot: Protection) -> Protection {
+        match self.matching_region(addr, prot.is_privileged) {
+            Ok(Some(region)) => {
+                let (cacheable, bufferable) = region.cacheable_and_bufferable();
+                prot.with_cacheable(cacheable).with_bufferable(bufferable)
+            }
+            _ => prot,
+        }
     }
-    Ok(meta)
 }
 
-fn mpu_pass(meta: TransferMeta) -> Result<TransferMeta, MPUError> {
-    let meta = baseline_mpu_pass(meta)?;
-    let prot = meta.prot;
-
-    if cfg!(not(feature = "soc-has-mpu")) {
-        Ok(meta)
-    } else {
-        // Cortex-M3 doesn't have proper MPU
-        unimplemented!("MPU is not yet implemented!");
-        // use MPU::Access;
-        // let access_policy: MPU::AP = todo!("self.access_policy(meta.addr)");
-        // let access = prot
-        //     .is_privileged
-        //     .ife(access_policy.privileged, access_policy.user);
-        // match (access, meta.dir) {
-        //     (Access::NoAccess | Access::Unpredictable, _)
-        //     | (Access::ReadOnly, Direction::Write) => Err(MPUError::AccessDenied(access)),
-        //     _ => Ok(()),
-        // }?;
-        // // LOCAL_TODO: ARM-TRM-G Table 9-8 TEX, C, B encoding
-        // Ok(meta)
+#[cfg(test)]
+mod tests {
+    use super::{MPUConfiguration, MPUError};
+    use crate::common::Address;
+    use crate::common::new_ahb::signals::{Direction, Protection};
+
+    #[allow(
+        clippy::trivially_copy_pass_by_ref,
+        reason = "It is small only without the soc-has-mpu feature"
+    )]
+    fn check(
+        mpu: &MPUConfiguration,
+        addr: u32,
+        dir: Direction,
+        prot: Protection,
+    ) -> Result<(), MPUError> {
+        mpu.validate(Address::from_const(addr), dir, prot)
+    }
+
+    fn fetch(is_privileged: bool) -> Protection {
+        Protection::new_instruction().with_privileged(is_privileged)
+    }
+
+    fn data(is_privileged: bool) -> Protection {
+        Protection::new_data().with_privileged(is_privileged)
+    }
+
+    #[test]
+    fn default_map_xn_applies_with_mpu_disabled() {
+        let mpu = MPUConfiguration::new();
+        assert_eq!(
+            check(&mpu, 0x0000_1000, Direction::Read, fetch(false)),
+            Ok(())
+        );
+        assert_eq!(
+            check(&mpu, 0x2000_1000, Direction::Read, fetch(false)),
+            Ok(())
+        );
+        for addr in [0x4000_0000, 0xA000_0000, 0xE000_E000, 0xF000_0000] {
+            assert_eq!(
+                check(&mpu, addr, Direction::Read, fetch(true)),
+                Err(MPUError::FetchDisallowed)
+            );
+            assert_eq!(check(&mpu, addr, Direction::Write, data(false)), Ok(()));
+        }
+    }
+
+    #[cfg(feature = "soc-has-mpu")]
+    mod regions {
+        use super::{MPUConfiguration, MPUError, check, data, fetch};
+        use crate::common::Word;
+        use crate::common::new_ahb::signals::Direction;
+        use cc2650_constants::MPU;
+
+        const ENABLE: u32 = 0b001;
+        const HFNMIENA: u32 = 0b010;
+        const PRIVDEFENA: u32 = 0b100;
+
+        fn rasr(size_log2: u32, ap: u32, srd: u32, xn: bool) -> Word {
+            Word::from(1 | ((size_log2 - 1) << 1) | (srd << 8) | (ap << 24) | (u32::from(xn) << 28))
+        }
+
+        fn configured(ctrl: u32, regions: &[(u32, Word)]) -> MPUConfiguration {
+            let mut mpu = MPUConfiguration::new();
+            mpu.set_ctrl(Word::from(ctrl));
+            for (i, &(base, attributes)) in regions.iter().enumerate() {
+                mpu.set_region_base(i, Word::from(base));
+                mpu.set_region_attributes(i, attributes);
+            }
+            mpu
+        }
+
+        #[test]
+        fn highest_matching_region_wins() {
+            let mpu = configured(
+                ENABLE,
+                &[
+                    (0x2000_0000, rasr(12, 0b011, 0, false)),
+                    (0x2000_0400, rasr(8, 0b110, 0, false)),
+                ],
+            );
+            let denied = Err(MPUError::AccessDenied(MPU::Access::ReadOnly));
+            assert_eq!(
+                check(&mpu, 0x2000_03FC, Direction::Write, data(true)),
+                Ok(())
+            );
+            assert_eq!(
+                check(&mpu, 0x2000_0400, Direction::Write, data(true)),
+                denied
+            );
+            assert_eq!(
+                check(&mpu, 0x2000_04FC, Direction::Write, data(false)),
+                denied
+            );
+            assert_eq!(
+                check(&mpu, 0x2000_04FC, Direction::Read, data(false)),
+                Ok(())
+            );
+            assert_eq!(
+                check(&mpu, 0x2000_0500, Direction::Write, data(true)),
+                Ok(())
+            );
+        }
+
+        #[test]
+        fn disabled_subregion_is_not_a_part_of_the_region() {
+            // 1 KiB no-access region with the second and the last 128-byte subregions disabled
+            let mpu = configured(
+                ENABLE | PRIVDEFENA,
+                &[(0x2000_0000, rasr(10, 0b000, 0b1000_0010, false))],
+            );
+            let denied = Err(MPUError::AccessDenied(MPU::Access::NoAccess));
+            assert_eq!(
+                check(&mpu, 0x2000_007C, Direction::Read, data(true)),
+                denied
+            );
+            assert_eq!(
+                check(&mpu, 0x2000_0080, Direction::Read, data(true)),
+                Ok(())
+            );
+            assert_eq!(
+                check(&mpu, 0x2000_0100, Direction::Read, data(true)),
+                denied
+            );
+            assert_eq!(
+                check(&mpu, 0x2000_0380, Direction::Read, data(true)),
+                Ok(())
+            );
+            // The background region doesn't apply to unprivileged accesses.
+            assert_eq!(
+                check(&mpu, 0x2000_0080, Direction::Read, data(false)),
+                Err(MPUError::BackgroundRegionDisallowed)
+            );
+        }
+
+        #[test]
+        fn small_regions_have_no_subregions() {
+            let mpu = configured(
+                ENABLE | PRIVDEFENA,
+                &[(0x2000_0000, rasr(7, 0b000, 0xFF, false))],
+            );
+            let denied = Err(MPUError::AccessDenied(MPU::Access::NoAccess));
+            assert_eq!(
+                check(&mpu, 0x2000_0000, Direction::Read, data(true)),
+                denied
+            );
+            assert_eq!(
+                check(&mpu, 0x2000_007C, Direction::Read, data(true)),
+                denied
+            );
+            assert_eq!(
+                check(&mpu, 0x2000_0080, Direction::Read, data(true)),
+                Ok(())
+            );
+        }
+
+        #[test]
+        fn background_region_needs_privdefena() {
+            let regions = [(0x0000_0000, rasr(17, 0b011, 0, false))];
+            let mpu = configured(ENABLE, &regions);
+            assert_eq!(
+                check(&mpu, 0x0000_1000, Direction::Read, fetch(false)),
+                Ok(())
+            );
+            for prot in [data(true), data(false)] {
+                assert_eq!(
+                    check(&mpu, 0x2000_0000, Direction::Read, prot),
+                    Err(MPUError::BackgroundRegionDisallowed)
+                );
+            }
+
+            let mpu = configured(ENABLE | PRIVDEFENA, &regions);
+            assert_eq!(
+                check(&mpu, 0x2000_0000, Direction::Read, data(true)),
+                Ok(())
+            );
+            assert_eq!(
+                check(&mpu, 0x2000_0000, Direction::Read, data(false)),
+                Err(MPUError::BackgroundRegionDisallowed)
+            );
+            // The default memory map XN applies to the background region.
+            assert_eq!(
+                check(&mpu, 0x4000_0000, Direction::Read, fetch(true)),
+                Err(MPUError::FetchDisallowed)
+            );
+        }
+
+        #[test]
+        fn execute_never_region() {
+            let mpu = configured(
+                ENABLE | PRIVDEFENA,
+                &[(0x0000_1000, rasr(8, 0b011, 0, true))],
+            );
+            assert_eq!(
+                check(&mpu, 0x0000_1000, Direction::Read, fetch(true)),
+                Err(MPUError::FetchDisallowed)
+            );
+            assert_eq!(
+                check(&mpu, 0x0000_1000, Direction::Read, data(false)),
+                Ok(())
+            );
+            assert_eq!(
+                check(&mpu, 0x0000_1100, Direction::Read, fetch(true)),
+                Ok(())
+            );
+        }
+
+        #[test]
+        fn bypassed_mpu_uses_default_map() {
+            // HFNMIENA is clear, so the NVIC bypasses the MPU at a negative priority.
+            let mut mpu = configured(ENABLE, &[(0x2000_0000, rasr(8, 0b000, 0, true))]);
+            assert!(!mpu.is_hfnmi_enabled());
+            mpu.set_bypassed(true);
+            assert_eq!(
+                check(&mpu, 0x2000_0000, Direction::Write, data(true)),
+                Ok(())
+            );
+            assert_eq!(
+                check(&mpu, 0x2000_0000, Direction::Read, fetch(true)),
+                Ok(())
+            );
+            assert_eq!(
+                check(&mpu, 0xE000_E000, Direction::Read, fetch(true)),
+                Err(MPUError::FetchDisallowed)
+            );
+
+            let mpu = configured(ENABLE | HFNMIENA, &[]);
+            assert!(mpu.is_hfnmi_enabled());
+        }
+
+        #[test]
+        fn ppb_always_uses_default_map() {
+            let mpu = configured(ENABLE, &[(0xE000_0000, rasr(20, 0b000, 0, true))]);
+            assert_eq!(
+                check(&mpu, 0xE000_ED00, Direction::Read, data(false)),
+                Ok(())
+            );
+            assert_eq!(
+                check(&mpu, 0xE010_0000, Direction::Read, data(true)),
+                Err(MPUError::BackgroundRegionDisallowed)
+            );
+        }
     }
 }
@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 
 use log::trace;
@@ -21,7 +22,7 @@ use crate::proxy::CoreProxy;
 use crate::proxy::CycleDebugLoggerProxy;
 use crate::utils::IfExpr;
 
-#[derive(Subcomponent, TickComponent, DisableableComponent)]
+#[derive(Clone, Subcomponent, TickComponent, DisableableComponent, Serialize, Deserialize)]
 #[subcomponent_1to1]
 pub(super) struct IBusRegistrationBuffer {
     last_reply: Option<AhbResponseControl>,
@@ -105,7 +106,7 @@ impl TickComponentExtra for IBusRegistrationBuffer {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 enum RegistrationSTM {
     Transparent,
     Holding,
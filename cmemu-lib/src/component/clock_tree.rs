@@ -12,6 +12,7 @@ use crate::utils::{IfExpr, dife};
 use enum_map::{EnumMap, enum_map};
 use log::{debug, info, trace, warn};
 use owo_colors::OwoColorize;
+use serde::{Deserialize, Serialize};
 use std::ops::RangeInclusive;
 
 pub mod nodes;
@@ -45,7 +46,11 @@ fn macros_example() {
     dispatch!(build_data::oscillators => osc = ex_osc => EnergyEntity::Oscillator(osc));
 }
 
-#[derive(Subcomponent)]
+#[allow(
+    clippy::unsafe_derive_deserialize,
+    reason = "the only `unsafe` is in reading the `confeature` statics"
+)]
+#[derive(Clone, Subcomponent, Serialize, Deserialize)]
 pub(crate) struct PowerClockManager {
     #[subcomponent(Nodes)]
     nodes: Nodes,
@@ -86,13 +91,13 @@ impl PowerClockManager {
 // External API: handlers code                       //
 ///////////////////////////////////////////////////////
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub(crate) struct ClockTreeState {
     pub(crate) fast_clock_source: Oscillators,
     pub(crate) slow_clock_source: Oscillators,
 }
 
-#[derive(PartialEq, Clone, Copy, Debug)]
+#[derive(PartialEq, Clone, Copy, Debug, Serialize, Deserialize)]
 pub(crate) enum ClockTreeStateQuerent {
     OSC,
 }
@@ -109,7 +114,7 @@ impl PowerClockManager {
         node: ClockTreeNodes,
         power: PowerMode,
     ) {
-        self.want_gate_node_state(ctx, node, power.is_active());
+        self.want_gate_node_state(ctx, node, power);
     }
 
     // #[handler]
@@ -352,8 +357,9 @@ impl PowerClockManager {
         all_done
     }
 
-    fn want_gate_node_state(&mut self, ctx: &mut Context, node: ClockTreeNodes, pass: bool) {
-        debug!("PM Want gate node state {node:?} to pass: {pass:?}");
+    fn want_gate_node_state(&mut self, ctx: &mut Context, node: ClockTreeNodes, power: PowerMode) {
+        let pass = power.is_active();
+        debug!("PM Want gate node state {node:?} to pass: {pass:?} (power: {power:?})");
 
         // Publish lower state to Context as it should be pessimistic
         let entity = EnergyEntity::ClockTree(node);
@@ -366,7 +372,7 @@ impl PowerClockManager {
         // Template by calling a generator macro from a macro-passed list
         macro_rules! generator {
             ($id:ident, $path:ident) => {{
-                graph::$path::set_next(self, pass);
+                graph::$path::set_next(self, power);
                 self.nodes_wait_change[$id] = true;
             }};
         }
@@ -461,7 +467,11 @@ impl DispatchGetPower for PowerClockManager {
 /// Oscillators in *Skipping* mode act as if thy were waiting on a conditional variable,
 /// checking if the managed components need any processing.
 /// Right now, we make any regular pulse event resulting in full node processing act as a `wake_all` call.
-#[derive(Subcomponent)]
+#[allow(
+    clippy::unsafe_derive_deserialize,
+    reason = "the only `unsafe` is in reading the `confeature` statics"
+)]
+#[derive(Clone, Subcomponent, Serialize, Deserialize)]
 #[subcomponent_1to1]
 struct TicksScheduler {
     skipping_oscs: EnumMap<Oscillators, Option<(RangeInclusive<Timepoint>, EventRevokeToken)>>,
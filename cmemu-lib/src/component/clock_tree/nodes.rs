@@ -10,6 +10,7 @@ use crate::utils::IfExpr;
 use enum_map::Enum;
 use log::{debug, trace};
 use owo_colors::OwoColorize;
+use serde::{Deserialize, Serialize};
 use std::any::Any;
 use std::cmp::min;
 use std::marker::PhantomData;
@@ -48,14 +49,14 @@ pub(super) struct EmulateMapper(pub u64);
 pub(super) struct PrepareMapper(pub PowerMode);
 pub(super) struct SetPowerMapper(pub PowerMode);
 
-#[derive(Debug, PartialEq, Clone, Copy, IntoStaticStr)]
+#[derive(Debug, PartialEq, Clone, Copy, IntoStaticStr, Serialize, Deserialize)]
 #[strum(serialize_all = "UPPERCASE")]
 enum TickKind {
     Tick,
     Tock,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct Divider {
     tick_counter: u32,
     tock_counter: u32,
@@ -194,7 +195,7 @@ impl Divider {
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 pub(crate) struct Gate {
     relays_ticks: bool,
     next_relays_ticks: Option<bool>,
@@ -240,7 +241,7 @@ impl Gate {
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 pub(crate) struct Switch<P: Enum + PartialEq> {
     parent: P,
     next_parent: Option<P>,
@@ -305,7 +306,7 @@ impl<P: Enum + PartialEq> Switch<P> {
     }
 }
 
-#[derive(Subcomponent, Debug)]
+#[derive(Clone, Subcomponent, Debug, Serialize, Deserialize)]
 pub(super) struct OscillatorNode<SC, Osc>
 where
     SC: PureSubcomponentMarker + Subcomponent<Member = Self>,
@@ -501,7 +502,7 @@ where
     }
 }
 
-#[derive(Subcomponent, Debug)]
+#[derive(Clone, Subcomponent, Debug, Serialize, Deserialize)]
 pub(super) struct GateNode<SC>
 where
     SC: PureSubcomponentMarker + Subcomponent<Member = Self>,
@@ -509,6 +510,10 @@ where
     gate: Gate,
     // do we need that, or is it only debug?
     in_mode: PowerMode,
+    /// The power supplied behind the gate: power domains go to `Retention` or `Off`
+    /// after their clock is gated, and have their power restored before it is ungated.
+    supply: PowerMode,
+    next_supply: Option<PowerMode>,
     _phantom_data: PhantomData<SC>,
 }
 
@@ -521,12 +526,14 @@ where
             gate: Gate::new(initial_passes),
             // TODO: default to Off
             in_mode: PowerMode::Active,
+            supply: PowerMode::Active,
+            next_supply: None,
             _phantom_data: PhantomData,
         }
     }
 
     fn effective_power_mode(&self) -> PowerMode {
-        min(self.in_mode, self.gate_as_pm())
+        min(min(self.in_mode, self.gate_as_pm()), self.supply)
     }
 
     fn gate_as_pm(&self) -> PowerMode {
@@ -536,7 +543,7 @@ where
     }
 
     fn effective_pm_will_change(&self, new_in: PowerMode, new_gate: PowerMode) -> bool {
-        self.effective_power_mode() != min(new_in, new_gate)
+        self.effective_power_mode() != min(min(new_in, new_gate), self.supply)
     }
 }
 
@@ -627,9 +634,41 @@ where
     Self: RPITITNode<SetPowerMapper, ()>,
     Self: PowerNode,
 {
-    pub(super) fn set_next(comp: &mut <Self as Subcomponent>::Component, pass: bool) {
+    /// Requests the gate to relay ticks iff `mode` is active.
+    /// A mode below `ClockGated` is applied to the supply, once the gate stops relaying ticks.
+    pub(super) fn set_next(comp: &mut <Self as Subcomponent>::Component, mode: PowerMode) {
         let mut this = Self::get_proxy(comp);
-        this.gate.set_next(pass);
+        this.gate.set_next(mode.is_active());
+        this.next_supply = Some(if mode < PowerMode::ClockGated {
+            mode
+        } else {
+            PowerMode::Active
+        });
+    }
+
+    fn set_supply(
+        comp: &mut <Self as Subcomponent>::Component,
+        ctx: &mut Context,
+        extra: &mut <Self as EnergyNode>::Extra,
+        supply: PowerMode,
+    ) {
+        let mut this = Self::get_proxy(comp);
+        let old_mode = this.effective_power_mode();
+        this.supply = supply;
+        let new_mode = this.effective_power_mode();
+        if old_mode != new_mode {
+            trace!(
+                "PM:{} supply -> {supply:?}: {old_mode:?} -> {new_mode:?}",
+                Self::NAME.blue()
+            );
+            <Self as RPITITNode<_, _>>::map_children(
+                this.component_mut(),
+                ctx,
+                extra,
+                SetPowerMapper(new_mode),
+            )
+            .for_each(drop);
+        }
     }
 
     pub(super) fn try_apply_next(
@@ -641,6 +680,11 @@ where
         if !this.gate.is_ready_for_switch() {
             return false;
         }
+        // The power is restored before the clock, and cut off after it.
+        let next_supply = this.next_supply.take().unwrap_or(this.supply);
+        if next_supply > this.supply {
+            Self::set_supply(this.component_mut(), ctx, extra, next_supply);
+        }
         if let Some(next) = this.gate.next_relays_ticks {
             let next_state = next.ife(PowerMode::Active, PowerMode::ClockGated);
             if this.effective_pm_will_change(this.in_mode, next_state) {
@@ -658,20 +702,29 @@ where
                     this.effective_power_mode()
                 );
                 if next.ife(next_state != possible_next, possible_next > next_state) {
+                    this.next_supply = Some(next_supply);
                     return false;
                 } else {
                     this.gate.apply_next();
+                    let supply = this.supply;
                     <Self as RPITITNode<_, _>>::map_children(
                         this.component_mut(),
                         ctx,
                         extra,
-                        SetPowerMapper(next_state),
+                        SetPowerMapper(min(next_state, supply)),
                     )
                     .for_each(drop);
                 }
             }
         }
         this.gate.apply_next();
+        if next_supply < this.supply {
+            debug_assert!(
+                !this.gate.should_relay(),
+                "Cutting off power of a running clock"
+            );
+            Self::set_supply(this.component_mut(), ctx, extra, next_supply);
+        }
         true
     }
 }
@@ -713,13 +766,14 @@ where
         let run = this.effective_pm_will_change(mode, this.gate_as_pm());
         this.in_mode = mode;
         if run {
+            let mode = this.effective_power_mode();
             <Self as RPITITNode<_, _>>::map_children(comp, ctx, extra, SetPowerMapper(mode))
                 .for_each(drop);
         }
     }
 }
 
-#[derive(Subcomponent, Debug)]
+#[derive(Clone, Subcomponent, Debug, Serialize, Deserialize)]
 pub(super) struct DividerNode<SC>
 where
     SC: PureSubcomponentMarker + Subcomponent<Member = Self>,
@@ -904,7 +958,8 @@ pub(super) trait SwitchConf: EnergyNode {
     fn is_valid_parent(parent: &Self::IdSpace) -> bool;
 }
 
-#[derive(Subcomponent)]
+#[derive(Clone, Subcomponent, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub(super) struct SwitchNode<SC>
 where
     SC: PureSubcomponentMarker + Subcomponent<Member = Self>,
@@ -50,7 +50,9 @@ enum Ev {
     Emulate(Id, u64),
 }
 
-#[derive(Debug, Hash, Eq, PartialEq, Clone, Copy, enum_map::Enum)]
+#[derive(
+    Debug, Hash, Eq, PartialEq, Clone, Copy, enum_map::Enum, serde::Serialize, serde::Deserialize,
+)]
 enum Id {
     G,
     D,
@@ -273,7 +275,7 @@ fn gate_node() {
 
     // Disable
     <G as ClockTreeNode>::tick(&mut test, &mut ctx, Id::R, unit);
-    G::set_next(&mut test, false);
+    G::set_next(&mut test, PowerMode::ClockGated);
     // No dice until Tock
     assert!(!G::try_apply_next(&mut test, &mut ctx, unit));
     test.log.allow_mode = PowerMode::ClockGated;
@@ -308,6 +310,54 @@ fn gate_node() {
     assert_eq!(test.log.l, vec![]);
 }
 
+#[test]
+fn gate_node_power_supply() {
+    let mut test = TestG::new();
+    let mut ctx = Context::new_for_test();
+    let unit = &mut ();
+    test.g.in_mode = PowerMode::Active;
+    test.g.gate.relays_ticks = true;
+    test.log.allow_mode = PowerMode::Off;
+
+    // The power is cut off after the clock is gated
+    G::set_next(&mut test, PowerMode::Off);
+    assert!(G::try_apply_next(&mut test, &mut ctx, unit));
+    assert_eq!(
+        <G as PowerNode>::get_power_state(&test, &ctx),
+        PowerMode::Off
+    );
+    assert_eq!(
+        mem::take(&mut test.log.l),
+        vec![
+            Ev::Prepare(Id::G, PowerMode::ClockGated),
+            Ev::SetPower(Id::G, PowerMode::ClockGated),
+            Ev::SetPower(Id::G, PowerMode::Off),
+        ]
+    );
+
+    // The parent clock doesn't bring back the power
+    <G as PowerNode>::set_power_state(&mut test, &mut ctx, Id::R, unit, PowerMode::ClockGated);
+    <G as PowerNode>::set_power_state(&mut test, &mut ctx, Id::R, unit, PowerMode::Active);
+    assert_eq!(test.log.l, vec![]);
+
+    // The power is restored before the clock is ungated
+    test.log.allow_mode = PowerMode::Active;
+    G::set_next(&mut test, PowerMode::Active);
+    assert!(G::try_apply_next(&mut test, &mut ctx, unit));
+    assert_eq!(
+        <G as PowerNode>::get_power_state(&test, &ctx),
+        PowerMode::Active
+    );
+    assert_eq!(
+        mem::take(&mut test.log.l),
+        vec![
+            Ev::SetPower(Id::G, PowerMode::ClockGated),
+            Ev::Prepare(Id::G, PowerMode::Active),
+            Ev::SetPower(Id::G, PowerMode::Active),
+        ]
+    );
+}
+
 #[test]
 fn divider_node() {
     let mut test = TestG::new();
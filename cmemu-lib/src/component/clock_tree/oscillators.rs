@@ -1,6 +1,7 @@
 // IMPORTANT: keep all RNG code replicable (no OS seed in impls!)
 use crate::engine::{Context, Duration, PICOS_IN_SECOND, Timepoint};
 use fastrand::Rng;
+use serde::{Deserialize, Serialize};
 use std::ops::RangeInclusive;
 
 // Oscillators:
@@ -27,7 +28,7 @@ pub trait Oscillator {
 }
 
 /// A perfect oscillator.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub(crate) struct ConstOsc<const N: u64>;
 
 impl<const N: u64> Oscillator for ConstOsc<N> {
@@ -7,6 +7,8 @@ use crate::common::new_ahb::ports::{AHBMasterPortInput, AHBMasterPortProxiedInpu
 #[proxy_use]
 use crate::common::new_ahb::signals::SlaveToMasterWires;
 #[proxy_use]
+use crate::component::bus_matrix::MPUConfiguration;
+#[proxy_use]
 use crate::component::nvic::InterruptData;
 #[proxy_use]
 use crate::engine::{Context, PowerNode};
@@ -19,8 +21,9 @@ use cc2650_constants::operation::StackPointer;
 use cmemu_common::Address;
 use cmemu_proc_macros::{component_impl, handler, proxy_use};
 use fetch::Fetch;
-use log::info;
+use log::{info, trace};
 use lsu::LSU;
+use serde::{Deserialize, Serialize};
 
 mod builtins;
 mod decode;
@@ -56,7 +59,15 @@ pub use register_bank::RegisterID; // crate::common export
 //                  and in clock_tree.tick() we check if all flops ticked exactly once
 
 /// Component representing ARM Cortex-M3 Core, see [TRM, section 2.1]
-#[derive(MainComponent, SkippableClockTreeNode, TickComponent, TickComponentExtra)]
+#[derive(
+    Clone,
+    MainComponent,
+    SkippableClockTreeNode,
+    TickComponent,
+    TickComponentExtra,
+    Serialize,
+    Deserialize,
+)]
 pub(crate) struct CoreComponent {
     dwt: DWTProxy,
     nvic: NVICProxy,
@@ -80,6 +91,15 @@ pub(crate) struct CoreComponent {
     nonstandard_entrypoint: Option<Address>,
     /// Did the pipeline advance this cycle? Used for the component API.
     pipeline_advanced: bool,
+    /// [ARM-ARM] B1.5.18 Wait For Event and Send Event - the Event Register.
+    event_register: bool,
+    /// [ARM-ARM] A3.4 Synchronization and semaphores - state of the local exclusive monitor.
+    /// Cortex-M3 doesn't tag the address: any access is treated as matching the previous LDREX,
+    /// so only the Exclusive Access state is kept.
+    exclusive_access: bool,
+    /// Copy of the MPU configuration kept by the NVIC, see [`Self::update_mpu_configuration`].
+    /// Used to set the memory attributes of transfers and to detect `MemManage` faults.
+    mpu_copy: MPUConfiguration,
 }
 
 #[component_impl(core)]
@@ -98,6 +118,9 @@ impl CoreComponent {
             interrupt_handler: InterruptEntryAndExitHandler::new(),
             nonstandard_entrypoint: None,
             pipeline_advanced: false,
+            event_register: false,
+            exclusive_access: false,
+            mpu_copy: MPUConfiguration::new(),
         }
     }
 
@@ -229,6 +252,25 @@ impl CoreComponent {
         InterruptEntryAndExitHandler::update_vector_table_offset_register(self, vtor);
     }
 
+    #[handler]
+    pub(crate) fn update_configuration_and_control_register(
+        &mut self,
+        _ctx: &mut Context,
+        ccr: Word,
+        mask: Word,
+    ) {
+        Execute::update_configuration_and_control_register(self, ccr, mask);
+    }
+
+    #[handler]
+    pub(crate) fn update_mpu_configuration(
+        &mut self,
+        _ctx: &mut Context,
+        configuration: MPUConfiguration,
+    ) {
+        self.mpu_copy = configuration;
+    }
+
     /// Wake up the processor if it is sleeping and continue execution after the WFI instruction.
     #[handler]
     pub(crate) fn spurious_wakeup(&mut self, _ctx: &mut Context) {
@@ -239,6 +281,13 @@ impl CoreComponent {
         Fetch::make_delayed_branch(self, Execute::next_instr_addr(self));
     }
 
+    /// An event for WFE: SEVONPEND in the NVIC, when the core is not sleeping.
+    #[handler]
+    pub(crate) fn signal_event(&mut self, _ctx: &mut Context) {
+        trace!("CPU event signaled");
+        self.set_event_register();
+    }
+
     #[handler]
     pub fn on_ahb_ibus_input(
         &mut self,
@@ -312,6 +361,32 @@ impl CoreComponent {
         self.pipeline_advanced
     }
 
+    /// [ARM-ARM] B1.5.18 - `SetEventRegister()` pseudocode.
+    fn set_event_register(&mut self) {
+        self.event_register = true;
+    }
+
+    /// [ARM-ARM] B1.5.18 - `EventRegistered()` followed by `ClearEventRegister()`.
+    fn take_event_register(&mut self) -> bool {
+        std::mem::take(&mut self.event_register)
+    }
+
+    /// [ARM-ARM] A3.4.4 - `SetExclusiveMonitors()` pseudocode.
+    fn set_exclusive_monitors(&mut self) {
+        self.exclusive_access = true;
+    }
+
+    /// [ARM-ARM] A3.4.4 - `ExclusiveMonitorsPass()` pseudocode.
+    /// STREX always leaves the local monitor in the Open Access state.
+    fn take_exclusive_monitors_pass(&mut self) -> bool {
+        std::mem::take(&mut self.exclusive_access)
+    }
+
+    /// [ARM-ARM] A3.4.4 - `ClearExclusiveLocal()` pseudocode.
+    fn clear_exclusive_local(&mut self) {
+        self.exclusive_access = false;
+    }
+
     pub(crate) fn set_nonstandard_entrypoint(&mut self, entrypoint: Option<Address>) {
         // assert!(!entrypoint.is_some_and(|e| Word::from(e).get_bit(0)));
         self.nonstandard_entrypoint = entrypoint;
@@ -19,12 +19,3 @@ pub(super) const fn have_fp_ext() -> bool {
     // [ARM-TDG] Table 1.1 - floating point isn't listed in other features of Cortex-M3.
     false
 }
-
-// Note: this function will probably be a method on some (sub)component, not a
-// builtin function.
-/// [ARM-ARM] D6.7.30
-#[allow(dead_code)] // it's not really dead, though
-pub(super) const fn integer_zero_divide_trapping_enabled() -> bool {
-    // TODO: Read DIV_0_TRP bit from CCR register. Not supported yet in cmemu.
-    false
-}
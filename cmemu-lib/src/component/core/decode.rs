@@ -13,11 +13,20 @@ use crate::proxy::CycleDebugLoggerProxy;
 use crate::utils::{IfExpr, dife};
 use log::trace;
 use owo_colors::OwoColorize;
+use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 
 mod instruction;
 
-#[derive(Subcomponent, TickComponent, TickComponentExtra, DisableableComponent)]
+#[derive(
+    Clone,
+    Subcomponent,
+    TickComponent,
+    TickComponentExtra,
+    DisableableComponent,
+    Serialize,
+    Deserialize,
+)]
 #[subcomponent_1to1]
 pub(super) struct Decode {
     state: DecodeState,
@@ -43,7 +52,7 @@ pub(super) struct Decode {
 }
 
 /// Represents decode as state machine.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 enum DecodeState {
     /// Decode is waiting for execute to be ready and for fetch to have data.
     WaitingForFetchAndExecute,
@@ -97,7 +106,7 @@ enum DecodeState {
 
 /// Represents all information produced in decode phase
 /// that is needed in execute phase.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub(super) struct PipelineStepPack {
     /// The main instruction
     pub(super) instruction: Instruction,
@@ -145,7 +154,7 @@ impl PipelineStepPack {
 /// See: [ARM-TRM-G] 15.3 Note below Table 15-3.
 /// But it turns out, ADD PC also suppresses fetching -- what makes sense for an unconditional branch.
 /// Yet, MOV PC, R4 is unconditional and apparently doesn't suppress that fetching.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub(super) enum Brchstat {
     NoBranch = 0b0000,
     DecodeTimeConditionalBackwards = 0b0001,
@@ -701,6 +710,9 @@ impl Decode {
                 );
                 (instruction::decode_short_instruction(instr_value, xpsr), 1)
             };
+            // The placeholder of an aborted fetch is decoded, but the fault replaces it.
+            let decoded_instr = Fetch::head_fetch_fault(core, instr_address, instr_len.into())
+                .map_or(decoded_instr, |fault| Instruction::FetchAborted { fault });
             trace!(
                 "Decode  [{:?}]: is {} len: {}",
                 instr_address.yellow(),
@@ -795,6 +807,8 @@ impl Decode {
         let this = Self::component_to_member(core);
         if !matches!(this.state, DecodeState::WaitingForFetchAndExecute) {
             let head = Fetch::peek_head(core); // ask earlier because of borrow checker
+            let second_fetch_aborted =
+                Fetch::head_fetch_fault(core, Fetch::head_address(core).offset(2), 1).is_some();
             let this = Self::component_to_member_mut(core);
             if let DecodeState::AGUWaitingForData {
                 addr,
@@ -828,9 +842,12 @@ impl Decode {
                 //  Proof: control_flow/it_fold.asm for the following conditions
                 if *length == 1
                     && is_second_instruction_short(&head)
+                    && !second_fetch_aborted
                     && !instr.is_lsu_instruction()
                     && !instr.is_branch()
                     && !matches!(instr, Instruction::ChangeProcessorState { .. })
+                    // Proof: interrupts/it_curse_svc.asm
+                    && !matches!(instr, Instruction::SupervisorCall { .. })
                     // This is likely decoded as sub.n sp, rm; with ignored flags, this only for rn
                     // Note: This is in contrast to "cmp.n rn, sp", which folds! (such encoding is deprecated though)
                     && !matches!(instr, Instruction::Compare_Register { rn: RegisterID::SP, .. })
@@ -1264,6 +1281,13 @@ impl Decode {
                 Some(agu_result)
             }
 
+            Instruction::LoadRegisterExclusiveByte { rn, .. }
+            | Instruction::LoadRegisterExclusiveHalfword { rn, .. }
+            | Instruction::StoreRegisterExclusiveByte { rn, .. }
+            | Instruction::StoreRegisterExclusiveHalfword { rn, .. } => {
+                Self::get_register_value(core, *rn, dirty_registers, fast_forwarded_reg)
+            }
+
             Instruction::LoadMultiple { rn, .. } | Instruction::StoreMultiple { rn, .. } => {
                 let rn_value =
                     Self::get_register_value(core, *rn, dirty_registers, fast_forwarded_reg)?;
@@ -1347,6 +1371,10 @@ impl Decode {
             | Instruction::StoreRegisterByteUnprivileged { rn, .. }
             | Instruction::StoreRegisterHalfwordUnprivileged { rn, .. }
             | Instruction::StoreRegisterExclusive { rn, .. }
+            | Instruction::LoadRegisterExclusiveByte { rn, .. }
+            | Instruction::LoadRegisterExclusiveHalfword { rn, .. }
+            | Instruction::StoreRegisterExclusiveByte { rn, .. }
+            | Instruction::StoreRegisterExclusiveHalfword { rn, .. }
             | Instruction::LoadMultiple { rn, .. }
             | Instruction::LoadMultipleDecrementBefore { rn, .. }
             | Instruction::StoreMultiple { rn, .. }
@@ -1391,6 +1419,8 @@ impl Decode {
             | Instruction::LoadRegisterSignedByteUnprivileged { .. }
             | Instruction::LoadRegisterSignedHalfwordUnprivileged { .. }
             | Instruction::LoadRegisterExclusive { .. }
+            | Instruction::LoadRegisterExclusiveByte { .. }
+            | Instruction::LoadRegisterExclusiveHalfword { .. }
             | Instruction::LoadMultiple { .. }
             | Instruction::LoadMultipleDecrementBefore { .. }
             | Instruction::StoreRegister_Immediate { .. }
@@ -1404,6 +1434,8 @@ impl Decode {
             | Instruction::StoreRegisterByteUnprivileged { .. }
             | Instruction::StoreRegisterHalfwordUnprivileged { .. }
             | Instruction::StoreRegisterExclusive { .. }
+            | Instruction::StoreRegisterExclusiveByte { .. }
+            | Instruction::StoreRegisterExclusiveHalfword { .. }
             | Instruction::StoreMultiple { .. }
             | Instruction::StoreMultipleDecrementBefore { .. }
             | Instruction::TableBranch { .. } => AdditionalAction::ComputeAGUValue,
@@ -6,16 +6,19 @@ use super::{CoreComponent, Decode, Fetch, LSU, RegisterBank};
 use crate::common::{Address, BitstringUtils, Word};
 use crate::component::core::decode::Brchstat;
 use crate::component::core::execute::instruction::memory_instruction::MultipleLoadStoreExecutionState;
+use crate::component::nvic::{CCR, Fault, SCBRegister};
 use crate::confeature::cm_hyp;
 use crate::engine::{
     Context, DisableableComponent, Subcomponent, TickComponent, TickComponentExtra,
 };
 #[cfg(feature = "cycle-debug-logger")]
 use crate::proxy::CycleDebugLoggerProxy;
+use crate::proxy::NVICProxy;
 use crate::utils::{DisplayOr, IfExpr, dife, ife};
 use instruction::ExecutionStepResult;
 use log::trace;
 use owo_colors::OwoColorize;
+use serde::{Deserialize, Serialize};
 #[cfg(debug_assertions)]
 use std::cell::Cell;
 use std::ops::BitAnd;
@@ -39,7 +42,7 @@ mod instruction;
 ///
 /// When the instruction from `main_slot` finishes execution, the instruction execution context
 /// from `pipelined_slot` slot is moved to `main_slot`.
-#[derive(Subcomponent, TickComponent, DisableableComponent)]
+#[derive(Clone, Subcomponent, TickComponent, DisableableComponent, Serialize, Deserialize)]
 #[subcomponent_1to1]
 pub(super) struct Execute {
     pipelined_slot: Option<InstructionExecutionContext>,
@@ -71,9 +74,16 @@ pub(super) struct Execute {
     get_pc_called: Cell<bool>,
 
     interruption_state: InterruptStackingOrUnstackingState,
+
+    /// Stores a read-only copy of [`CCR`]. Can be updated
+    /// only by [`Self::update_configuration_and_control_register()`], that is used to
+    /// synchronize value.
+    #[flop]
+    ccr_copy: CCR,
 }
 
 /// Contains all data needed to execute instruction.
+#[derive(Clone, Serialize, Deserialize)]
 struct InstructionExecutionContext {
     /// Contains decoded instructions, addresses and other information generated by decode
     pipeline_step_pack: PipelineStepPack,
@@ -115,7 +125,7 @@ struct InstructionExecutionContext {
     folded_instr_executed: bool,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 enum InstructionExecutionState {
     None,
 
@@ -143,7 +153,7 @@ enum InstructionExecutionState {
     },
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 struct MultiplyLongExecutionState {
     result_hi: Word,
     result_lo: Word,
@@ -151,10 +161,10 @@ struct MultiplyLongExecutionState {
     write_lo_cycle_no: u32,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 struct SingleLoadStoreExecutionState {}
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
 enum ActiveSlot {
     None,
     Main,
@@ -163,11 +173,13 @@ enum ActiveSlot {
 
 /// Contains information if Execute should be interrupted for stacking/unstacking,
 /// because one of interrupts has been risen.
-#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Serialize, Deserialize)]
 enum InterruptStackingOrUnstackingState {
     None,
     /// State from WFI
     WaitingForInterrupt,
+    /// State after an instruction raising a synchronous exception (e.g., SVC)
+    WaitingForException,
     Requested,
     WaitingForExecutionToFinish,
     Running,
@@ -278,9 +290,54 @@ impl Execute {
             get_pc_called: Cell::new(false),
 
             interruption_state: InterruptStackingOrUnstackingState::None,
+
+            ccr_copy: CCR::initial(),
         }
     }
 
+    /// Can be called ONLY by core which receives new value of [`CCR`].
+    pub(super) fn update_configuration_and_control_register(
+        core: &mut CoreComponent,
+        ccr: Word,
+        mask: Word,
+    ) {
+        let mut this = Self::get_proxy(core);
+        this.ccr_copy.write(ccr, mask);
+    }
+
+    /// [ARM-ARM] D6.7.30 `IntegerZeroDivideTrappingEnabled()`
+    fn integer_zero_divide_trapping_enabled(core: &CoreComponent) -> bool {
+        Self::component_to_member(core).ccr_copy.get_div_0_trp()
+    }
+
+    /// [ARM-ARM] B3.2.8 `CCR.NONBASETHRDENA`: whether an exception may return to Thread mode
+    /// while other exceptions are still active.
+    pub(super) fn nonbase_thread_enabled(core: &CoreComponent) -> bool {
+        Self::component_to_member(core)
+            .ccr_copy
+            .get_nonbasethrdena()
+    }
+
+    /// Abandons the instruction from the active slot and raises a synchronous `fault`,
+    /// which entry will stack `return_address` - usually the address of the instruction.
+    ///
+    /// The instruction must not have any side effects visible outside the core yet,
+    /// since its register writes and xPSR changes are dropped here.
+    fn raise_fault(
+        core: &mut CoreComponent,
+        ctx: &mut Context,
+        fault: Fault,
+        return_address: Word,
+    ) -> ExecutionStepResult {
+        let this = Self::component_to_member_mut(core);
+        this.next_instr_addr = return_address;
+        let iectx = this.get_active_instruction_execution_context();
+        iectx.mark_all_registers_clean();
+        iectx.next_xpsr = iectx.visible_xpsr;
+        NVICProxy.raise_fault(ctx, fault);
+        ExecutionStepResult::FaultRaised
+    }
+
     /// Updates interruption state. Especially might interrupt currently
     /// executing instructions or decide to wait until they are finished.
     /// Must be called before any other [`Execute`] method.
@@ -655,6 +712,7 @@ impl Execute {
             this.interruption_state,
             InterruptStackingOrUnstackingState::None
                 | InterruptStackingOrUnstackingState::WaitingForInterrupt
+                | InterruptStackingOrUnstackingState::WaitingForException
         ) {
             this.interruption_state = InterruptStackingOrUnstackingState::Requested;
         }
@@ -668,6 +726,17 @@ impl Execute {
         )
     }
 
+    /// Used when the requested exception return turned out to be illegal:
+    /// instead of unstacking, the core waits for the entry of the raised fault.
+    pub(super) fn wait_for_exception_after_interruption(core: &mut CoreComponent) {
+        let this = Self::component_to_member_mut(core);
+        debug_assert_eq!(
+            this.interruption_state,
+            InterruptStackingOrUnstackingState::Running
+        );
+        this.interruption_state = InterruptStackingOrUnstackingState::WaitingForException;
+    }
+
     pub(super) fn restore_execution(core: &mut CoreComponent) {
         let this = Self::component_to_member_mut(core);
 
@@ -1163,6 +1232,18 @@ impl Execute {
                 this.next_instr_addr = address.with_bit_set(0, false);
                 Some(TriggerData::ignore_currently_decoding_instruction())
             }
+            ExecutionStepResult::ExceptionRaised => {
+                iectx.mark_last_cycle();
+                this.next_instr_addr = following_instruction_address;
+                this.wait_for_exception();
+                Some(TriggerData::ignore_currently_decoding_instruction())
+            }
+            ExecutionStepResult::FaultRaised => {
+                // `next_instr_addr` is already set to the return address of the fault.
+                iectx.mark_last_cycle();
+                this.wait_for_exception();
+                Some(TriggerData::ignore_currently_decoding_instruction())
+            }
             ExecutionStepResult::PreSleep => {
                 Some(TriggerData::ignore_currently_decoding_instruction())
             }
@@ -1217,7 +1298,10 @@ impl Execute {
         }
     }
 
-    fn finish_instruction_in_tock(core: &mut CoreComponent, clear_reg: Option<RegisterID>) {
+    pub(super) fn finish_instruction_in_tock(
+        core: &mut CoreComponent,
+        clear_reg: Option<RegisterID>,
+    ) {
         // LSU instructions may be finished late in the cycle
         // Fortunately, we always pass trigger_decode=true
         // Probably only XPSR is not updated properly
@@ -1243,6 +1327,30 @@ impl Execute {
         Self::set_xpsr_if_slot_finished(core);
     }
 
+    /// Abandons the main slot instruction late in the cycle (tock phase), e.g., after its
+    /// memory access failed. Nothing is executed until the entry of the raised fault,
+    /// which returns to the abandoned instruction.
+    pub(super) fn abandon_instruction_in_tock(core: &mut CoreComponent) {
+        let this = Self::component_to_member_mut(core);
+        let iectx = this
+            .main_slot
+            .as_mut()
+            .expect("Late abandoning instruction can be only made in the in main slot");
+        iectx.mark_all_registers_clean();
+        iectx.next_xpsr = iectx.visible_xpsr;
+        this.next_instr_addr = iectx.instruction_address().into();
+        iectx.mark_last_cycle();
+        this.wait_for_exception();
+        Self::set_xpsr_if_slot_finished(core);
+    }
+
+    /// Stops starting new instructions until the entry of a raised synchronous exception.
+    fn wait_for_exception(&mut self) {
+        if self.interruption_state == InterruptStackingOrUnstackingState::None {
+            self.interruption_state = InterruptStackingOrUnstackingState::WaitingForException;
+        }
+    }
+
     #[track_caller]
     fn get_active_instruction_execution_context(&mut self) -> &mut InstructionExecutionContext {
         match self.active_slot {
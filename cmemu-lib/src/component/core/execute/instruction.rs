@@ -9,17 +9,19 @@ use super::{
 };
 use crate::common::new_ahb::Size;
 use crate::common::new_ahb::databus::DataBus;
+use crate::common::new_ahb::signals::Direction;
 use crate::common::{BitstringUtils, SRType, Shift, Word, bitstring::constants as bsc};
 use crate::component::core::decode::Brchstat;
+use crate::component::core::lsu::{DecodeFn, ReadDataFn};
 use crate::component::core::register_bank::ItState;
 use crate::component::core::{
     CoreComponent, Fetch, InterruptEntryAndExitHandler, RegisterBank,
-    builtins::{have_dsp_ext, have_fp_ext, integer_zero_divide_trapping_enabled},
+    builtins::{have_dsp_ext, have_fp_ext},
     instruction::Instruction,
     interrupt,
     register_bank::{BasePriorityMaskRegister, RegisterID, XPSR},
 };
-use crate::component::nvic::InterruptId;
+use crate::component::nvic::{Fault, InterruptId};
 use crate::engine::DisableableComponent;
 use crate::engine::{Context, Subcomponent};
 #[cfg(feature = "cycle-debug-logger")]
@@ -57,6 +59,13 @@ pub(super) enum ExecutionStepResult {
     BranchNotTaken,
     LateBranch,
     ExceptionReturn,
+    /// The instruction finished by raising a synchronous exception (e.g., SVC),
+    /// so nothing is executed until its entry starts.
+    ExceptionRaised,
+    /// The instruction caused a synchronous fault (e.g., `UsageFault`), so it is abandoned
+    /// without any side effects and nothing is executed until the fault entry starts.
+    /// See [`Execute::raise_fault`].
+    FaultRaised,
     PreSleep,
     Sleep,
 }
@@ -69,8 +78,13 @@ impl ExecutionStepResult {
             | Self::DecodeTimeBranch { .. }
             | Self::LateBranch
             | Self::PreSleep
+            | Self::FaultRaised
             | Self::ExceptionReturn => false,
-            Self::NextInstruction | Self::Skipped | Self::BranchNotTaken | Self::Sleep => true,
+            Self::NextInstruction
+            | Self::Skipped
+            | Self::BranchNotTaken
+            | Self::ExceptionRaised
+            | Self::Sleep => true,
         }
     }
 
@@ -80,6 +94,8 @@ impl ExecutionStepResult {
             | Self::NextInstruction
             | Self::Skipped
             | Self::BranchNotTaken
+            | Self::ExceptionRaised
+            | Self::FaultRaised
             | Self::PreSleep
             | Self::Sleep => false,
             Self::ExecuteTimeBranch { .. }
@@ -116,6 +132,7 @@ impl Execute {
         let xpsr = iectx.visible_xpsr;
 
         let instr = iectx.instruction().clone();
+        let instr_addr = iectx.instruction_address();
 
         // Notify CDL about execution
         #[cfg(feature = "cycle-debug-logger")]
@@ -136,6 +153,18 @@ impl Execute {
             instr = instr,
         );
 
+        // [ARM-ARM] B1.5.14, B3.5.3: an error response to the fetch or the fetch denied by the MPU
+        // (decided when the word was fetched) is a precise fault raised only when the instruction
+        // is executed.
+        if iectx.cycle_cntr == 0
+            && let Instruction::FetchAborted { fault } = instr
+        {
+            debug!("executing aborted fetch at address: {:?}", instr_addr);
+            return Self::raise_fault(core, ctx, fault, instr_addr.into());
+        }
+        let this = Self::component_to_member_mut(core);
+        let iectx = this.get_active_instruction_execution_context();
+
         if xpsr.in_it_block() {
             // NOTE:
             //   According to [ARM-ARM] A7.3.3 this should happen *after* finishing the instruction.
@@ -181,11 +210,13 @@ impl Execute {
                 name,
             ),
             // [ARM-ARM] A5.1.1
-            // TODO: should throw UndefinedInstruction exception
-            Instruction::Undefined => panic!(
-                "undefined operation at address: {:?}",
-                iectx.instruction_address()
-            ),
+            Instruction::Undefined => {
+                debug!("undefined operation at address: {:?}", instr_addr);
+                Self::raise_fault(core, ctx, Fault::UndefinedInstruction, instr_addr.into())
+            }
+            Instruction::FetchAborted { .. } => {
+                unreachable!("Aborted fetch raises the fault in the first cycle")
+            }
             // [ARM-ARM] A5.1.1
             Instruction::Unpredictable => panic!(
                 "unpredictable operation at address: {:?}",
@@ -592,11 +623,16 @@ impl Execute {
             }
 
             // [ARM-ARM] A7.7.17
-            Instruction::Breakpoint { imm32 } => unimplemented!(
-                "BKPTInstrDebugEvent() [imm32 = 0x{:x}, at address {:?}]",
-                imm32,
-                iectx.instruction_address()
-            ),
+            Instruction::Breakpoint { imm32 } => {
+                info!(
+                    "bkpt #{} at {:?}, no debugger attached",
+                    imm32,
+                    iectx.instruction_address()
+                );
+                // BKPTInstrDebugEvent(): NVIC escalates it to HardFault
+                NVICProxy.raise_exception(ctx, InterruptId::DebugMonitor);
+                ExecutionStepResult::ExceptionRaised
+            }
 
             // [ARM-ARM] A7.7.18
             Instruction::BranchWithLink_Immediate { imm32 } => {
@@ -630,7 +666,9 @@ impl Execute {
 
                 Self::set_register(core, RegisterID::LR, link_address);
 
-                if branch_kind.is_decode_time() {
+                // Interworking to the ARM state is handled by `blx_write_pc` regardless of
+                // the branch kind.
+                if branch_kind.is_decode_time() && target_address.get_bit(0) {
                     let iectx = Self::component_to_member_mut(core)
                         .get_active_instruction_execution_context();
                     iectx.mark_register_clean(RegisterID::PC);
@@ -639,8 +677,7 @@ impl Execute {
                         was_speculative: branch_kind.is_speculative(),
                     }
                 } else {
-                    debug_assert!(branch_kind.is_execute_time());
-                    Self::blx_write_pc(core, target_address)
+                    Self::blx_write_pc(core, ctx, target_address)
                 }
             }
 
@@ -649,7 +686,9 @@ impl Execute {
                 let branch_kind = iectx.pipeline_step_pack.branch_kind;
                 let target_address = RegisterBank::get_register(core, rm);
 
-                if branch_kind.is_decode_time() {
+                // Interworking to the ARM state is handled by `bx_write_pc` regardless of
+                // the branch kind.
+                if branch_kind.is_decode_time() && target_address.get_bit(0) {
                     let iectx = Self::component_to_member_mut(core)
                         .get_active_instruction_execution_context();
 
@@ -659,8 +698,7 @@ impl Execute {
                         was_speculative: branch_kind.is_speculative(),
                     }
                 } else {
-                    debug_assert!(branch_kind.is_execute_time());
-                    Self::bx_write_pc(core, target_address)
+                    Self::bx_write_pc(core, ctx, target_address)
                 }
             }
 
@@ -689,10 +727,10 @@ impl Execute {
             }
 
             // [ARM-ARM] A7.7.23
-            Instruction::ClearExclusive => unimplemented!(
-                "ClearExclusiveLocal(ProcessorID()) [at address {:?}]",
-                iectx.instruction_address()
-            ),
+            Instruction::ClearExclusive => {
+                core.clear_exclusive_local();
+                ExecutionStepResult::NextInstruction
+            }
 
             // [ARM-ARM] A7.7.24
             Instruction::CountLeadingZeros { rd, rm } => {
@@ -812,21 +850,17 @@ impl Execute {
             // [ARM-ARM] A7.7.33
             Instruction::DataMemoryBarrier { option } => {
                 debug_assert_eq!(option, bsc::C_1111, "Reserved (unsupported) DMB option");
-                unimplemented!(
-                    "DataMemoryBarrier(option) [option = 0x{:x}, at address {:?}]",
-                    u8::from(option),
-                    iectx.instruction_address()
-                );
+                // TODO: timing: wait for the outstanding memory accesses (needs tests).
+                //       Functionally, the accesses are already performed in the program order.
+                ExecutionStepResult::NextInstruction
             }
 
             // [ARM-ARM] A7.7.34
             Instruction::DataSynchronizationBarrier { option } => {
                 debug_assert_eq!(option, bsc::C_1111, "Reserved (unsupported) DSB option");
-                unimplemented!(
-                    "DataSynchronizationBarrier(option) [option = 0x{:x}, at address {:?}]",
-                    u8::from(option),
-                    iectx.instruction_address()
-                );
+                // TODO: timing: wait for the outstanding memory accesses (needs tests).
+                //       It is used, e.g., around the write to `AIRCR` in `NVIC_SystemReset()`.
+                ExecutionStepResult::NextInstruction
             }
 
             // [ARM-ARM] A7.7.35
@@ -1875,16 +1909,16 @@ impl Execute {
 
                 if finish == cycle_cntr {
                     let result = if rm_val.sint() == 0 {
-                        // It is assumed that false will be returned.
-                        // After implementation both values will be valid and
-                        // const assert could be removed.
-                        const _: () = assert!(!integer_zero_divide_trapping_enabled());
-                        if integer_zero_divide_trapping_enabled() {
-                            // exceptions haven't been implemented yet
-                            unimplemented!("GenerateIntegerZeroDivide()");
-                        } else {
-                            Word::from(0u32)
+                        if Self::integer_zero_divide_trapping_enabled(core) {
+                            // GenerateIntegerZeroDivide()
+                            return Self::raise_fault(
+                                core,
+                                ctx,
+                                Fault::DivideByZero,
+                                instr_addr.into(),
+                            );
                         }
+                        Word::from(0u32)
                     } else if rn_val.sint() == (1 << 31) && rm_val.sint() == -1 {
                         // This is an edge case described in [ARM-ARM] A7.7.127 - Notes.
                         // This division is equal to 0x80000000 / 0xFFFFFFFF.
@@ -1910,10 +1944,11 @@ impl Execute {
             }
 
             // [ARM-ARM] A7.7.129
-            Instruction::SendEvent => unimplemented!(
-                "Hint_SendEvent() [at address {:?}]",
-                iectx.instruction_address()
-            ),
+            Instruction::SendEvent => {
+                // There are no other processors to signal, so only the local Event Register is set.
+                core.set_event_register();
+                ExecutionStepResult::NextInstruction
+            }
 
             // [ARM-ARM] A7.7.138
             Instruction::SignedMultiplyAccumulateLong {
@@ -2157,11 +2192,12 @@ impl Execute {
             }
 
             // [ARM-ARM] A7.7.178
-            Instruction::SupervisorCall { imm32 } => unimplemented!(
-                "CallSupervisor() [imm32 = 0x{:x}, at address {:?}]",
-                imm32,
-                iectx.instruction_address()
-            ),
+            Instruction::SupervisorCall { imm32 } => {
+                trace!("svc #{} at {:?}", imm32, iectx.instruction_address());
+                // CallSupervisor(): the exception entry is started by NVIC, as for interrupts.
+                NVICProxy.raise_exception(ctx, InterruptId::SVCall);
+                ExecutionStepResult::ExceptionRaised
+            }
 
             // [ARM-ARM] A7.7.182
             Instruction::SignedExtendByte { rd, rm, rotation } => {
@@ -2198,24 +2234,18 @@ impl Execute {
                     // Step 1: Prepare memory request.
                     let address = RegisterBank::get_agu_result(core);
                     let size = if is_tbh { Size::Halfword } else { Size::Byte };
+                    if let Some(result) =
+                        Self::validate_access(core, ctx, address, Direction::Read, false)
+                    {
+                        return result;
+                    }
                     LSU::request_read(
                         core,
                         address,
                         size,
                         ReadDataCallback::WithDecodeFn(
-                            |core, decode, data| {
-                                // Step 3: Store loaded data for next cycle.
-                                let this = Self::component_to_member_mut(core);
-                                let ctx = this
-                                    .main_slot
-                                    .as_mut()
-                                    .expect("No main_slot in TableBranch read_data function");
-                                ctx.state = InstructionExecutionState::TableBranchOffset {
-                                    branch_offset: decode(data),
-                                    dest_addr: None,
-                                };
-                            },
-                            DataBus::zero_extend_into_word,
+                            ReadDataFn::TableBranchOffset,
+                            DecodeFn::ZeroExtend,
                         ),
                     );
 
@@ -2351,11 +2381,13 @@ impl Execute {
             }
 
             // [ARM-ARM] A7.7.194
-            Instruction::PermanentlyUndefined { imm32 } => panic!(
-                "permanently undefined operation at address: {:?}, with imm: {:?}",
-                iectx.instruction_address(),
-                imm32
-            ),
+            Instruction::PermanentlyUndefined { imm32 } => {
+                debug!(
+                    "permanently undefined operation at address: {:?}, with imm: {:?}",
+                    instr_addr, imm32
+                );
+                Self::raise_fault(core, ctx, Fault::UndefinedInstruction, instr_addr.into())
+            }
 
             // [ARM-ARM] A7.7.195
             Instruction::UnsignedDivide { rd, rn, rm } => {
@@ -2366,16 +2398,16 @@ impl Execute {
 
                 if finish == cycle_cntr {
                     let result = if rm_val.uint() == 0 {
-                        // It is assumed that false will be returned.
-                        // After implementation both values will be valid and
-                        // const assert could be removed.
-                        const _: () = assert!(!integer_zero_divide_trapping_enabled());
-                        if integer_zero_divide_trapping_enabled() {
-                            // exceptions haven't been implemented yet
-                            unimplemented!("GenerateIntegerZeroDivide()");
-                        } else {
-                            Word::from(0u32)
+                        if Self::integer_zero_divide_trapping_enabled(core) {
+                            // GenerateIntegerZeroDivide()
+                            return Self::raise_fault(
+                                core,
+                                ctx,
+                                Fault::DivideByZero,
+                                instr_addr.into(),
+                            );
                         }
+                        Word::from(0u32)
                     } else {
                         // In [ARM-ARM] division is done in the way that result
                         // of division is real, and it is rounded towards zero
@@ -2552,10 +2584,34 @@ impl Execute {
             }
 
             // [ARM-ARM] A7.7.261
-            Instruction::WaitForEvent => unimplemented!(
-                "EventRegistered() / ClearEventRegister() / WaitForEvent() [at address {:?}]",
-                iectx.instruction_address()
-            ),
+            Instruction::WaitForEvent => {
+                debug_assert!(!iectx.has_folded_instruction());
+                let cycle_cntr = iectx.cycle_cntr;
+                let following_instruction_address = iectx.following_instruction_address();
+
+                if core.take_event_register() {
+                    debug!("wfe: the event register was set, not going to sleep.");
+                    if cycle_cntr == 0 {
+                        ExecutionStepResult::NextInstruction
+                    } else {
+                        // Fetch has been disabled in the first cycle.
+                        ExecutionStepResult::ExecuteTimeBranch {
+                            address: following_instruction_address.into(),
+                        }
+                    }
+                } else if cycle_cntr == 0 {
+                    debug!("Preparing for sleep.");
+                    Fetch::disable_fetch(core);
+
+                    ExecutionStepResult::PreSleep
+                } else if core.can_be_disabled_now() {
+                    info!("wfe: going to sleep");
+                    NVICProxy.start_sleep(ctx, true);
+                    ExecutionStepResult::Sleep
+                } else {
+                    ExecutionStepResult::PreSleep
+                }
+            }
 
             // [ARM-ARM] A7.7.262
             Instruction::WaitForInterrupt => {
@@ -2575,7 +2631,7 @@ impl Execute {
                     ExecutionStepResult::PreSleep
                 } else if core.can_be_disabled_now() {
                     warn!("Really going to sleep");
-                    NVICProxy.start_sleep(ctx);
+                    NVICProxy.start_sleep(ctx, false);
                     ExecutionStepResult::Sleep
                 } else {
                     warn!("Waiting for Core to be disableable");
@@ -2742,7 +2798,11 @@ impl Execute {
     /// Performs an interworking branch
     #[must_use]
     #[inline]
-    fn bx_write_pc(core: &mut CoreComponent, address: Word) -> ExecutionStepResult {
+    fn bx_write_pc(
+        core: &mut CoreComponent,
+        ctx: &mut Context,
+        address: Word,
+    ) -> ExecutionStepResult {
         if interrupt::check_if_branch_to_given_address_returns_from_exception(core, address) {
             Self::request_interruption(core);
             InterruptEntryAndExitHandler::init_exception_return(core, address);
@@ -2752,11 +2812,9 @@ impl Execute {
                 .mark_register_clean(RegisterID::PC);
 
             ExecutionStepResult::ExceptionReturn
+        } else if !address.get_bit(0) {
+            Self::interworking_to_arm(core, ctx, address)
         } else {
-            // TODO: Set EPSR.T bit.
-            if !address.get_bit(0) {
-                unimplemented!("UsageFault on interworking to ARM")
-            }
             Self::branch_to(core, address.with_bit_set(0, false))
         }
     }
@@ -2765,36 +2823,77 @@ impl Execute {
     /// Performs an interworking branch
     #[must_use]
     #[inline]
-    fn blx_write_pc(core: &mut CoreComponent, address: Word) -> ExecutionStepResult {
-        // TODO: Set EPSR.T bit.
+    fn blx_write_pc(
+        core: &mut CoreComponent,
+        ctx: &mut Context,
+        address: Word,
+    ) -> ExecutionStepResult {
         if !address.get_bit(0) {
-            unimplemented!("UsageFault on interworking to ARM")
+            return Self::interworking_to_arm(core, ctx, address);
         }
         Self::branch_to(core, address.with_bit_set(0, false))
     }
 
+    /// Callback of [`ReadDataFn::TableBranchOffset`]: stores loaded data for next cycle.
+    pub(in crate::component::core) fn on_table_branch_offset_read(
+        core: &mut CoreComponent,
+        decode: DecodeFn,
+        data: DataBus,
+    ) {
+        let this = Self::component_to_member_mut(core);
+        let ctx = this
+            .main_slot
+            .as_mut()
+            .expect("No main_slot in TableBranch read_data function");
+        ctx.state = InstructionExecutionState::TableBranchOffset {
+            branch_offset: decode.decode(data),
+            dest_addr: None,
+        };
+    }
+
     /// [ARM-ARM] A2.3.1
     /// Called late in the cycle (tock phase)
     /// Performs an interworking branch (because it is delegated to `BXWritePC()`.
-    fn load_write_pc(core: &mut CoreComponent, address: Word) {
+    fn load_write_pc(core: &mut CoreComponent, ctx: &mut Context, address: Word) {
         // TODO: assert we're after Tock
         // This is a duplicate of bx_write_pc, but in the context of Tock phase
         // PC is cleaned in finish_instruction_in_tock
         if interrupt::check_if_branch_to_given_address_returns_from_exception(core, address) {
             Self::request_interruption(core);
             InterruptEntryAndExitHandler::init_exception_return(core, address);
+        } else if !address.get_bit(0) {
+            // See `interworking_to_arm`, the instruction is finished by the caller.
+            let this = Self::component_to_member_mut(core);
+            this.next_instr_addr = address;
+            this.wait_for_exception();
+            NVICProxy.raise_fault(ctx, Fault::InvalidState);
         } else {
             // Delayed "make branch", analogous to alu_write_pc
-            if !address.get_bit(0) {
-                unimplemented!("UsageFault on interworking to ARM")
-            }
-
             // Analogous code in case of non-LSU branches is run
             let address = address.with_bit_set(0, false);
             Self::late_branch_to(core, address);
         }
     }
 
+    /// [ARM-ARM] A2.3.1: an interworking branch to an even address clears `EPSR.T`,
+    /// so the next instruction raises an INVSTATE `UsageFault` ([ARM-ARM] B1.4.2).
+    ///
+    /// We don't model `EPSR.T`, so the fault is raised right away with the branch target
+    /// as its return address. Note, that the stacked xPSR has `T` bit still set.
+    #[must_use]
+    fn interworking_to_arm(
+        core: &mut CoreComponent,
+        ctx: &mut Context,
+        address: Word,
+    ) -> ExecutionStepResult {
+        let this = Self::component_to_member_mut(core);
+        this.next_instr_addr = address;
+        this.get_active_instruction_execution_context()
+            .mark_register_clean(RegisterID::PC);
+        NVICProxy.raise_fault(ctx, Fault::InvalidState);
+        ExecutionStepResult::FaultRaised
+    }
+
     /// [ARM-ARM] A2.3.1
     #[must_use]
     #[inline]
@@ -1,10 +1,11 @@
 use itertools::Itertools;
-use log::trace;
+use log::{debug, trace};
+use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 
 use crate::bitstring_extract;
 use crate::common::new_ahb::databus::DataBus;
-use crate::common::new_ahb::signals::Size;
+use crate::common::new_ahb::signals::{Direction, Size};
 use crate::common::{Word, bitstring::constants as bsc};
 use crate::component::core::execute::instruction::ExecutionStepResult;
 use crate::component::core::execute::instruction::memory_instruction::RegMode::{
@@ -14,14 +15,18 @@ use crate::component::core::execute::{
     Decode, Execute, InstructionExecutionState, LSU, ReadDataCallback,
     SingleLoadStoreExecutionState,
 };
-use crate::component::core::lsu::AddrAdvancedCallback;
+use crate::component::core::lsu::{
+    AddrAdvancedCallback, DecodeFn, GetWriteDataFn, ReadDataFn, ReadRegisterFn, WriteDoneFn,
+};
 use crate::component::core::register_bank::RegisterBitmap;
 use crate::component::core::{
     CoreComponent, Fetch, RegisterBank,
     instruction::{Instruction, MemoryInstructionDescription},
     register_bank::RegisterID,
 };
+use crate::component::nvic::Fault;
 use crate::engine::{Context, Subcomponent};
+use crate::utils::IfExpr;
 use cmemu_common::Address;
 
 #[derive(Debug, Clone, Copy)]
@@ -451,7 +456,11 @@ impl IntoIterator for RegPreIter {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "A::IntoIter: Serialize, B::IntoIter: Serialize",
+    deserialize = "A::IntoIter: Deserialize<'de>, B::IntoIter: Deserialize<'de>"
+))]
 pub(crate) enum VariantIterator<A: IntoIterator, B: IntoIterator>
 where
     B: IntoIterator<Item = <A as IntoIterator>::Item>,
@@ -494,13 +503,52 @@ impl From<RegisterBitmap> for RegIter {
 
 impl From<[RegisterID; 2]> for RegIter {
     fn from(b: [RegisterID; 2]) -> Self {
-        Self::B(b.into_iter())
+        Self::B(RegisterPair(b).into_iter())
     }
 }
 
-type RegIter = VariantIterator<RegisterBitmap, [RegisterID; 2]>;
+type RegIter = VariantIterator<RegisterBitmap, RegisterPair>;
 
-#[derive(Debug)]
+/// Registers of a dual load/store, in transfer order.
+///
+/// Unlike `std::array::IntoIter`, its iterator can be stored in a snapshot.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct RegisterPair([RegisterID; 2]);
+
+impl IntoIterator for RegisterPair {
+    type Item = RegisterID;
+    type IntoIter = RegisterPairIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        RegisterPairIter {
+            pair: self.0,
+            next: 0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct RegisterPairIter {
+    pair: [RegisterID; 2],
+    next: usize,
+}
+
+impl Iterator for RegisterPairIter {
+    type Item = RegisterID;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let reg = self.pair.get(self.next).copied();
+        self.next += usize::from(reg.is_some());
+        reg
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.pair.len() - self.next;
+        (len, Some(len))
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub(in crate::component::core) struct MultipleLoadStoreExecutionState {
     /// Contain address for consecutive transfers.
     /// Its meaning depends on the instruction.
@@ -517,15 +565,19 @@ pub(in crate::component::core) struct MultipleLoadStoreExecutionState {
 }
 
 impl Execute {
+    fn base_transfer_address(core: &CoreComponent, mem_desc: &MemorierDescription) -> Word {
+        if let Source(rn) = mem_desc.addr_from_reg {
+            RegisterBank::get_register(core, rn)
+        } else {
+            RegisterBank::get_agu_result(core)
+        }
+    }
+
     fn determine_transfer_address(
         core: &mut CoreComponent,
         mem_desc: &MemorierDescription,
     ) -> Word {
-        let addr = if let Source(rn) = mem_desc.addr_from_reg {
-            RegisterBank::get_register(core, rn)
-        } else {
-            RegisterBank::get_agu_result(core)
-        };
+        let addr = Self::base_transfer_address(core, mem_desc);
         if mem_desc.writeback {
             let rn = mem_desc.addr_from_reg.unwrap();
             // We do writeback in the first cycle of instruction execution
@@ -554,13 +606,6 @@ impl Execute {
 
             LSU::get_proxy(core).addr_advanced_callback =
                 Some(AddrAdvancedCallback::WritebackCallback {
-                    cb: |core, _ctx, reg, value| {
-                        // This is late in the cycle!
-                        RegisterBank::set_register(core, reg, value);
-                        let this = Self::component_to_member_mut(core);
-                        let iectx = this.main_slot.as_mut().unwrap();
-                        iectx.mark_register_clean(reg);
-                    },
                     reg: rn,
                     data: value,
                 });
@@ -568,16 +613,25 @@ impl Execute {
         addr
     }
 
+    /// Callback of [`AddrAdvancedCallback::WritebackCallback`].
+    pub(in crate::component::core) fn on_writeback_address_advanced(
+        core: &mut CoreComponent,
+        reg: RegisterID,
+        value: Word,
+    ) {
+        // This is late in the cycle!
+        RegisterBank::set_register(core, reg, value);
+        let this = Self::component_to_member_mut(core);
+        let iectx = this.main_slot.as_mut().unwrap();
+        iectx.mark_register_clean(reg);
+    }
+
     fn determine_transfer_address_for_multiple(
         core: &mut CoreComponent,
         mem_desc: &MemorierDescription,
         inplace_writeback: bool,
     ) -> Word {
-        let addr = if let Source(rn) = mem_desc.addr_from_reg {
-            RegisterBank::get_register(core, rn)
-        } else {
-            RegisterBank::get_agu_result(core)
-        };
+        let addr = Self::base_transfer_address(core, mem_desc);
         if mem_desc.writeback {
             let rn = mem_desc.addr_from_reg.unwrap();
 
@@ -637,12 +691,51 @@ impl Execute {
         addr
     }
 
+    /// [ARM-ARM] B3.5.3: an access denied by the MPU is a precise `MemManage` fault.
+    /// It is checked before the address phase, so the access never happens
+    /// and the writeback of the base register is dropped with the instruction.
+    pub(super) fn validate_access(
+        core: &mut CoreComponent,
+        ctx: &mut Context,
+        address: Word,
+        dir: Direction,
+        unprivileged: bool,
+    ) -> Option<ExecutionStepResult> {
+        let err = LSU::validate_access(core, address, dir, unprivileged).err()?;
+        debug!("MPU denies access to {address:?}: {err}");
+        LSU::get_proxy(core).addr_advanced_callback = None;
+        let this = Self::component_to_member_mut(core);
+        let instr_addr = this
+            .get_active_instruction_execution_context()
+            .instruction_address();
+        let fault = Fault::DataAccessViolation {
+            address: address.into(),
+        };
+        Some(Self::raise_fault(core, ctx, fault, instr_addr.into()))
+    }
+
+    /// Checks all the accesses of a multiple load/store before the first one (and the writeback),
+    /// as the instruction is restarted after the fault anyway.
+    fn validate_multiple_access(
+        core: &mut CoreComponent,
+        ctx: &mut Context,
+        mem_desc: &MemorierDescription,
+    ) -> Option<ExecutionStepResult> {
+        let address = Self::base_transfer_address(core, mem_desc);
+        let dir = mem_desc.is_store.ife(Direction::Write, Direction::Read);
+        (0..mem_desc.length).find_map(|i| {
+            let offset = u32::try_from(mem_desc.size.bytes() * i).unwrap();
+            Self::validate_access(core, ctx, address + Word::from(offset), dir, false)
+        })
+    }
+
     pub(super) fn request_read_data_into_register(
         core: &mut CoreComponent,
         addr: Word,
         size: Size,
         rt: RegisterID,
         signed: bool,
+        unprivileged: bool,
     ) {
         Self::load_read_data_to_register_exact::<true>(
             core,
@@ -650,10 +743,11 @@ impl Execute {
             size,
             rt,
             if signed {
-                DataBus::sign_extend_into_word
+                DecodeFn::SignExtend
             } else {
-                DataBus::zero_extend_into_word
+                DecodeFn::ZeroExtend
             },
+            unprivileged,
         );
     }
 
@@ -670,10 +764,11 @@ impl Execute {
             size,
             rt,
             if signed {
-                DataBus::sign_extend_into_word
+                DecodeFn::SignExtend
             } else {
-                DataBus::zero_extend_into_word
+                DecodeFn::ZeroExtend
             },
+            false,
         );
     }
 
@@ -682,22 +777,22 @@ impl Execute {
         addr: Word,
         size: Size,
         reg: RegisterID,
+        unprivileged: bool,
     ) {
+        let request = if unprivileged {
+            LSU::request_unprivileged_write
+        } else {
+            LSU::request_write
+        };
         // NOTE: the logic was moved from more straightforward "provided data when Execute asked for it"
         // Consider if moving it back makes thinking about it simpler.
-        LSU::request_write(
+        request(
             core,
             addr,
             size,
             ReadDataCallback::WriteCallbacks {
-                get_data: |core, reg, size| {
-                    let data = RegisterBank::get_register(core, reg);
-                    DataBus::clip_word(data, size)
-                },
-                write_done: |core, _reg| {
-                    // TODO: what about writebacks forwarding to AGU?
-                    Self::finish_instruction_in_tock(core, None);
-                },
+                get_data: GetWriteDataFn::Register,
+                write_done: WriteDoneFn::FinishInstruction,
                 reg,
             },
         );
@@ -732,14 +827,11 @@ impl Execute {
             addr,
             size,
             ReadDataCallback::WriteCallbacks {
-                get_data: |_core, _reg, _size| unreachable!(),
+                get_data: GetWriteDataFn::Latched,
                 write_done: if is_last {
-                    |core, _reg| {
-                        // TODO: what about writebacks forwarding to AGU?
-                        Self::finish_instruction_in_tock(core, None);
-                    }
+                    WriteDoneFn::FinishInstruction
                 } else {
-                    |_core, _reg| {}
+                    WriteDoneFn::Nothing
                 },
                 reg,
             },
@@ -755,19 +847,26 @@ impl Execute {
     ///            that will be stored in target register
     /// `LAST_LOAD_IN_EXECUTION` - specifies whether current transfer should end
     ///                            instruction execution when transfer completes
+    /// `unprivileged` - whether the transfer is made as unprivileged (e.g., LDRT)
     #[allow(clippy::shadow_unrelated)]
     pub(super) fn load_read_data_to_register_exact<const LAST_LOAD_IN_EXECUTION: bool>(
         core: &mut CoreComponent,
         addr: Word,
         size: Size,
         reg: RegisterID,
-        decode: fn(DataBus) -> Word,
+        decode: DecodeFn,
+        unprivileged: bool,
     ) {
         debug_assert_ne!(
             reg,
             RegisterID::PC,
             "Use `load_write_pc` to load data to PC register"
         );
+        let request = if unprivileged {
+            LSU::request_unprivileged_read
+        } else {
+            LSU::request_read
+        };
 
         // let this = Self::component_to_member_mut(core);
         // debug_assert_eq!(
@@ -775,75 +874,13 @@ impl Execute {
         //     ActiveSlot::Main,
         //     "Handling read data on Data bus can be only done for instruction in main slot"
         // );
-        LSU::request_read(
+        request(
             core,
             addr,
             size,
             ReadDataCallback::WithRegisterAndDecodeFn(
-                |core, #[cfg(feature = "cycle-debug-logger")] ctx, reg, decode, data| {
-                    let value = decode(data);
-
-                    // Both main and pipelined instruction can write the same register.
-                    // Before forwarding register, make sure it is not written by pipelined instruction.
-                    // Note: data always come to the main slot.
-                    // Note2: is there any instruction that can pipeline and write to `reg` before
-                    //        main instruction does so? Probably not. But if yes, in such case
-                    //        we don't want to forward the register.
-                    let this = Self::component_to_member_mut(core);
-                    let iectx = this.main_slot.as_mut().expect("Handling read data on Data bus can be only done for instruction in main slot");
-                    iectx.mark_register_clean(reg);
-
-                    let is_reg_dirty_because_of_pipelined_instruction = this
-                        .pipelined_slot
-                        .as_ref()
-                        .is_some_and(|iectx| iectx.dirty_regs.get(reg));
-
-                    if !is_reg_dirty_because_of_pipelined_instruction {
-                        // Since we should update register value, do it.
-                        RegisterBank::set_register(core, reg, value);
-
-                        // Now, let's decide whether data should be fast-forwarded to Decode / AGU.
-                        let this = Self::component_to_member(core);
-
-                        let wback = match this
-                            .main_slot
-                            .as_ref()
-                            .unwrap()
-                            .instruction()
-                            .get_memory_description()
-                        {
-                            MemoryInstructionDescription::LoadSingle { writeback, .. } => writeback,
-                            // "LDM.N" behaves like it would always do writeback:
-                            // see results of `ldr_ldm_ldmdb_ldrd_deps.asm`.
-                            // (Note: according to [ARM-ARM], "LDM.N" always updates
-                            //        its base register, so it kinda makes sense.)
-                            MemoryInstructionDescription::LoadMultiple {
-                                writeback,
-                                is_narrow_ldm,
-                            } => writeback || is_narrow_ldm,
-                            MemoryInstructionDescription::None => {
-                                unreachable!("Only LSU instruction can receive data from LSU.")
-                            }
-                            // TODO: research the `Store` instructions.
-                            _ => false,
-                        };
-
-                        let is_pipelined_slot_occupied = this.pipelined_slot.is_some();
-
-                        if !wback || is_pipelined_slot_occupied {
-                            Decode::fast_forward_agu_register(
-                                core,
-                                #[cfg(feature = "cycle-debug-logger")]
-                                ctx,
-                                reg,
-                                value,
-                            );
-                        }
-                    }
-
-                    if LAST_LOAD_IN_EXECUTION {
-                        Self::finish_instruction_in_tock(core, None);
-                    }
+                ReadRegisterFn::Load {
+                    last: LAST_LOAD_IN_EXECUTION,
                 },
                 reg,
                 decode,
@@ -851,6 +888,83 @@ impl Execute {
         );
     }
 
+    /// Callback of [`ReadRegisterFn::Load`]: stores the loaded data in the register.
+    #[allow(clippy::shadow_unrelated)]
+    pub(in crate::component::core) fn on_register_read<const LAST_LOAD_IN_EXECUTION: bool>(
+        core: &mut CoreComponent,
+        #[cfg_attr(not(feature = "cycle-debug-logger"), allow(unused))] ctx: &mut Context,
+        reg: RegisterID,
+        decode: DecodeFn,
+        data: DataBus,
+    ) {
+        let value = decode.decode(data);
+
+        // Both main and pipelined instruction can write the same register.
+        // Before forwarding register, make sure it is not written by pipelined instruction.
+        // Note: data always come to the main slot.
+        // Note2: is there any instruction that can pipeline and write to `reg` before
+        //        main instruction does so? Probably not. But if yes, in such case
+        //        we don't want to forward the register.
+        let this = Self::component_to_member_mut(core);
+        let iectx = this
+            .main_slot
+            .as_mut()
+            .expect("Handling read data on Data bus can be only done for instruction in main slot");
+        iectx.mark_register_clean(reg);
+
+        let is_reg_dirty_because_of_pipelined_instruction = this
+            .pipelined_slot
+            .as_ref()
+            .is_some_and(|iectx| iectx.dirty_regs.get(reg));
+
+        if !is_reg_dirty_because_of_pipelined_instruction {
+            // Since we should update register value, do it.
+            RegisterBank::set_register(core, reg, value);
+
+            // Now, let's decide whether data should be fast-forwarded to Decode / AGU.
+            let this = Self::component_to_member(core);
+
+            let wback = match this
+                .main_slot
+                .as_ref()
+                .unwrap()
+                .instruction()
+                .get_memory_description()
+            {
+                MemoryInstructionDescription::LoadSingle { writeback, .. } => writeback,
+                // "LDM.N" behaves like it would always do writeback:
+                // see results of `ldr_ldm_ldmdb_ldrd_deps.asm`.
+                // (Note: according to [ARM-ARM], "LDM.N" always updates
+                //        its base register, so it kinda makes sense.)
+                MemoryInstructionDescription::LoadMultiple {
+                    writeback,
+                    is_narrow_ldm,
+                } => writeback || is_narrow_ldm,
+                MemoryInstructionDescription::None => {
+                    unreachable!("Only LSU instruction can receive data from LSU.")
+                }
+                // TODO: research the `Store` instructions.
+                _ => false,
+            };
+
+            let is_pipelined_slot_occupied = this.pipelined_slot.is_some();
+
+            if !wback || is_pipelined_slot_occupied {
+                Decode::fast_forward_agu_register(
+                    core,
+                    #[cfg(feature = "cycle-debug-logger")]
+                    ctx,
+                    reg,
+                    value,
+                );
+            }
+        }
+
+        if LAST_LOAD_IN_EXECUTION {
+            Self::finish_instruction_in_tock(core, None);
+        }
+    }
+
     /// [ARM-ARM] A2.3.1
     ///
     /// Generates and sets callback function that stores data received
@@ -882,16 +996,21 @@ impl Execute {
             core,
             addr,
             Size::Word,
-            ReadDataCallback::WithDecodeFn(
-                |core, decode, data| {
-                    let address = decode(data);
-                    Self::load_write_pc(core, address);
-                    Self::finish_instruction_in_tock(core, Some(RegisterID::PC));
-                },
-                DataBus::unwrap_word,
-            ),
+            ReadDataCallback::WithDecodeFn(ReadDataFn::LoadPc, DecodeFn::UnwrapWord),
         );
     }
+
+    /// Callback of [`ReadDataFn::LoadPc`].
+    pub(in crate::component::core) fn on_pc_read(
+        core: &mut CoreComponent,
+        ctx: &mut Context,
+        decode: DecodeFn,
+        data: DataBus,
+    ) {
+        let address = decode.decode(data);
+        Self::load_write_pc(core, ctx, address);
+        Self::finish_instruction_in_tock(core, Some(RegisterID::PC));
+    }
 }
 
 impl Execute {
@@ -910,7 +1029,7 @@ impl Execute {
     )]
     pub(super) fn execute_memory_instruction_step(
         core: &mut CoreComponent,
-        _ctx: &mut Context,
+        ctx: &mut Context,
         instr: Instruction,
     ) -> ExecutionStepResult {
         let this = Self::component_to_member_mut(core);
@@ -947,6 +1066,9 @@ impl Execute {
                 // TODO: Interrupts: read [ARM-ARM] B1.5.10, [ARM-TRM] 3.9.2 & 3
                 let mem_desc = Self::get_memorier_description(&instr);
                 if iectx.cycle_cntr == 0 {
+                    if let Some(result) = Self::validate_multiple_access(core, ctx, &mem_desc) {
+                        return result;
+                    }
                     let address = Self::determine_transfer_address_for_multiple(
                         core,
                         &mem_desc,
@@ -1000,6 +1122,7 @@ impl Execute {
                                 mem_desc.size,
                                 reg,
                                 mem_desc.is_signed,
+                                false,
                             );
                         } else {
                             // Have more registers to load - continue exectution after loading data
@@ -1053,15 +1176,22 @@ impl Execute {
                     "Processing LoadSingle with desc {:?}:  {:?}",
                     mem_desc, instr
                 );
-                if mem_desc.is_exclusive || mem_desc.is_unpriviledged {
-                    unimplemented!(
-                        "Instruction \"{}\" [at address {:?}]",
-                        instr,
-                        iectx.instruction_address()
-                    );
-                }
                 if iectx.cycle_cntr == 0 {
                     let address = Self::determine_transfer_address(core, &mem_desc);
+                    if let Some(result) = Self::validate_access(
+                        core,
+                        ctx,
+                        address,
+                        Direction::Read,
+                        mem_desc.is_unpriviledged,
+                    ) {
+                        return result;
+                    }
+
+                    if mem_desc.is_exclusive {
+                        // [ARM-ARM] A7.7.52 - A7.7.54
+                        core.set_exclusive_monitors();
+                    }
 
                     if mem_desc.is_branch {
                         if bitstring_extract!(address<1:0> | 2 bits) != bsc::C_00 {
@@ -1086,6 +1216,7 @@ impl Execute {
                             mem_desc.size,
                             rt,
                             mem_desc.is_signed,
+                            mem_desc.is_unpriviledged,
                         );
                     }
                     Self::set_state(
@@ -1119,17 +1250,39 @@ impl Execute {
                 //       as they are always set to the same value, but we decided
                 //       that the code is less confusing when it handles these flags.
                 let mem_desc = Self::get_memorier_description(&instr);
-                if mem_desc.is_exclusive || mem_desc.is_unpriviledged {
-                    unimplemented!(
-                        "Instruction \"{}\" [at address {:?}]",
-                        instr,
-                        iectx.instruction_address()
-                    );
-                }
                 if iectx.cycle_cntr == 0 {
                     let address = Self::determine_transfer_address(core, &mem_desc);
+                    if let Some(result) = Self::validate_access(
+                        core,
+                        ctx,
+                        address,
+                        Direction::Write,
+                        mem_desc.is_unpriviledged,
+                    ) {
+                        return result;
+                    }
 
-                    Self::request_write_data_from_register(core, address, mem_desc.size, rt);
+                    // [ARM-ARM] A7.7.167 - A7.7.169
+                    if let Instruction::StoreRegisterExclusive { rd, .. }
+                    | Instruction::StoreRegisterExclusiveByte { rd, .. }
+                    | Instruction::StoreRegisterExclusiveHalfword { rd, .. } = instr
+                    {
+                        if core.take_exclusive_monitors_pass() {
+                            Self::set_register(core, rd, Word::from(0));
+                        } else {
+                            // The store is not performed at all.
+                            Self::set_register(core, rd, Word::from(1));
+                            return ExecutionStepResult::NextInstruction;
+                        }
+                    }
+
+                    Self::request_write_data_from_register(
+                        core,
+                        address,
+                        mem_desc.size,
+                        rt,
+                        mem_desc.is_unpriviledged,
+                    );
                     Self::set_state(
                         core,
                         InstructionExecutionState::SingleLoadStore(
@@ -1150,6 +1303,9 @@ impl Execute {
                 let mem_desc = Self::get_memorier_description(&instr);
 
                 if iectx.cycle_cntr == 0 {
+                    if let Some(result) = Self::validate_multiple_access(core, ctx, &mem_desc) {
+                        return result;
+                    }
                     let address = Self::determine_transfer_address_for_multiple(
                         core,
                         &mem_desc,
@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::fmt::{Debug, Formatter};
 
@@ -16,9 +17,10 @@ use cmemu_common::Address;
 
 use crate::component::core::decode::{Brchstat, Decode, PipelineStepPack};
 use crate::component::core::register_bank::XPSR;
+use crate::component::nvic::Fault;
 use owo_colors::OwoColorize;
 
-use super::{CoreComponent, RegisterBank, RegisterID, instruction};
+use super::{CoreComponent, InterruptEntryAndExitHandler, RegisterBank, RegisterID, instruction};
 
 mod piq;
 pub(super) mod transfers; // TODO: export is_inflight() in a cleaner way
@@ -60,7 +62,7 @@ const STACK_POINTER_INITIAL_VALUE_ADDRESS: Address = FLASHMEM::ADDR;
 /// - [`on_ibus_data()`](Self::on_ibus_data()) (called by [`IBusDriver`])
 ///
 
-#[derive(Subcomponent, TickComponent, DisableableComponent)]
+#[derive(Clone, Subcomponent, TickComponent, DisableableComponent, Serialize, Deserialize)]
 #[subcomponent_1to1]
 #[allow(clippy::struct_excessive_bools)] // To allow helper bools
 pub(super) struct Fetch {
@@ -107,9 +109,10 @@ pub(super) struct Fetch {
     fetch_disable_buffer: bool,
 }
 
+#[derive(Clone, Serialize, Deserialize)]
 struct VectorBuffer(Address, FetchedHandlerCallback);
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
 enum CyclePhase {
     /// [`Fetch::tick_extra()`] was called.
     TickExtra,
@@ -129,7 +132,7 @@ enum CyclePhase {
 impl StateMachine for CyclePhase {}
 
 /// State machine of the Fetch subcomponent. It generates transfers.
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 enum State {
     Idle,
     Initial,
@@ -158,7 +161,7 @@ enum State {
 
 // Internal type, but passed to CDL (so the type is pub(crate)).
 #[cfg(feature = "cycle-debug-logger")]
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub(crate) enum TransferType {
     StackPointer,
     Instruction,
@@ -176,13 +179,28 @@ impl From<TransferType> for &'static str {
     }
 }
 
-type FetchedHandlerCallback = Option<fn(&mut CoreComponent)>;
-#[derive(Clone, Copy)]
+type FetchedHandlerCallback = Option<HandlerFetchedFn>;
+
+/// Callbacks run when the first instruction of an exception handler is fetched.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub(super) enum HandlerFetchedFn {
+    /// Lets the interrupt entry complete.
+    FinishFetchingHandler,
+}
+
+impl HandlerFetchedFn {
+    fn call(self, core: &mut CoreComponent) {
+        match self {
+            Self::FinishFetchingHandler => InterruptEntryAndExitHandler::on_handler_fetched(core),
+        }
+    }
+}
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub(super) enum TransferState {
     AddrPhase,
     DataPhase,
 }
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub(super) enum DataReadCallback {
     SetStackPointerAndFetchResetVector,
     BranchToAddressAfterVectorCall { callback: FetchedHandlerCallback },
@@ -322,6 +340,16 @@ impl Fetch {
         // TODO: we should be able to move it to late tock phase of the previous cycle
         #[allow(unused_variables)]
         if let Some((data, address, callback)) = this.transfers.take_delayed_transfer() {
+            let data = match (data, callback) {
+                (Ok(data), _) => data,
+                (Err(fault), DataReadCallback::AddToPiq { skip_half }) => {
+                    this.piq.push_back_aborted(skip_half, address, fault);
+                    return;
+                }
+                (Err(fault), _) => {
+                    unreachable!("Only an aborted instruction fetch is delivered, got {fault:?}")
+                }
+            };
             match callback {
                 DataReadCallback::Ignore { in_state } => {
                     // with the current ordering of execution, the transfer would be already marked as ignored,
@@ -348,7 +376,7 @@ impl Fetch {
                 }
                 DataReadCallback::BranchToAddressAfterVectorCall { callback } => {
                     if let Some(callback) = callback {
-                        callback(core);
+                        callback.call(core);
                     }
                     let target_address = data;
                     Self::make_branch(core, target_address);
@@ -759,6 +787,17 @@ impl Fetch {
         debug_assert_eq!(this.cycle_phase, CyclePhase::TickPiq);
         this.piq.get_head_address()
     }
+
+    /// Returns the fault of fetching any of `halfwords` at `address` in the head.
+    pub(super) fn head_fetch_fault(
+        core: &CoreComponent,
+        address: Address,
+        halfwords: u32,
+    ) -> Option<Fault> {
+        let this = Self::component_to_member(core);
+        debug_assert_eq!(this.cycle_phase, CyclePhase::TickPiq);
+        this.piq.fetch_fault(address, halfwords)
+    }
 }
 
 // API for state changing
@@ -1,4 +1,5 @@
 use crate::common::Word;
+use crate::component::nvic::Fault;
 #[cfg(feature = "cycle-debug-logger")]
 use crate::confeature::cdl as cdl_conf;
 #[cfg(feature = "cycle-debug-logger")]
@@ -11,6 +12,7 @@ use crate::utils::dife;
 use cmemu_common::Address;
 use heapless::Deque as ArrayDeque;
 use log::trace;
+use serde::{Deserialize, Serialize};
 use std::fmt;
 use strum::IntoStaticStr;
 
@@ -20,6 +22,8 @@ const PREFETCH_CACHE_ENTRIES_COUNT: usize = 6;
 const PREFETCH_HEAD_ENTRIES_COUNT: usize = 2;
 const PREFETCH_QUEUE_ENTRIES_COUNT: usize =
     PREFETCH_CACHE_ENTRIES_COUNT - PREFETCH_HEAD_ENTRIES_COUNT;
+/// The prefetch cache and the incoming data hold at most 4 different words.
+const FETCH_FAULTS_COUNT: usize = PREFETCH_CACHE_ENTRIES_COUNT / 2 + 1;
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug, IntoStaticStr)]
 pub enum PIQShiftMode {
@@ -69,7 +73,7 @@ type ShadowQ = ShadowBuffer<u16, PREFETCH_CACHE_ENTRIES_COUNT>;
 /// 1. "`@pos_edge`" `tick_piq` do the requested shift + pull incoming data into the structure.
 /// 2. Provide data (immutable) and record reservations.
 /// 3. @late tock: remember incoming data to use on the edge `push_back_bytes`
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PrefetchInputQueue {
     /// Contains data-phase of the fetch transfers to be used on the phi1 edge.
     ///
@@ -107,6 +111,11 @@ pub struct PrefetchInputQueue {
     /// Entries are reserved when transfer is requested on `IBus`
     /// so that when the data arrives, there will be place for it in the cache.
     in_flight: u8,
+
+    /// Faults of the most recently pushed words, which got a placeholder instead of the data.
+    /// A word in the PIQ is always among the last pushed, so it is enough to remember
+    /// as many of them as fit in the PIQ.
+    fetch_faults: ArrayDeque<(Address, Fault), FETCH_FAULTS_COUNT>,
 }
 
 use crate::confeature::cm_hyp::{ablation, shadow_piq};
@@ -130,6 +139,7 @@ impl PrefetchInputQueue {
             shadow_unaligned_head: false,
             prepare_for_impact: false,
             in_flight: 0,
+            fetch_faults: ArrayDeque::new(),
         }
     }
 
@@ -363,6 +373,10 @@ impl PrefetchInputQueue {
 
         self.in_flight -= 1;
 
+        if !self.fetch_faults.is_empty() {
+            self.record_fetch_fault(address, None);
+        }
+
         let address = if skip_half {
             address.offset(2)
         } else {
@@ -378,6 +392,29 @@ impl PrefetchInputQueue {
         // debug_assert!(self.total_slots_allocated() <= PREFETCH_CACHE_ENTRIES_COUNT);
     }
 
+    /// Pushes a placeholder of the word which fetch raised the `fault`,
+    /// so that the fault is raised only if an instruction from the word is executed.
+    pub(super) fn push_back_aborted(&mut self, skip_half: bool, address: Address, fault: Fault) {
+        self.push_back_bytes(Word::from(0), skip_half, address);
+        self.record_fetch_fault(address, Some(fault));
+    }
+
+    fn record_fetch_fault(&mut self, address: Address, fault: Option<Fault>) {
+        let mut fetch_faults = ArrayDeque::new();
+        for &(a, f) in &self.fetch_faults {
+            if a != address {
+                fetch_faults.push_back((a, f)).unwrap();
+            }
+        }
+        if let Some(fault) = fault {
+            if fetch_faults.is_full() {
+                fetch_faults.pop_front();
+            }
+            fetch_faults.push_back((address, fault)).unwrap();
+        }
+        self.fetch_faults = fetch_faults;
+    }
+
     // See point D. of `ShadowBuffer`
     pub(super) fn ignored_data(&mut self, data: Word, _address: Address) {
         if self.allow_stale_data_this_cycle {
@@ -394,6 +431,16 @@ impl PrefetchInputQueue {
         self.head_addr
     }
 
+    /// Returns the fault of the fetch of any of `halfwords` starting at `address`.
+    pub(super) fn fetch_fault(&self, address: Address, halfwords: u32) -> Option<Fault> {
+        (0..halfwords).find_map(|i| {
+            let word = address.offset(2 * i).aligned_down_to_4_bytes();
+            self.fetch_faults
+                .iter()
+                .find_map(|&(a, fault)| (a == word).then_some(fault))
+        })
+    }
+
     /// Flush the "queue" part, while retaining the Fetch/Decode register.
     #[inline(always)]
     pub(super) fn branch(&mut self, new_head_addr: Address) {
@@ -441,6 +488,7 @@ impl PrefetchInputQueue {
         self.head_reg.clear();
         self.head_addr = new_head_addr;
         self.in_flight = 0;
+        self.fetch_faults.clear();
         self.shadow.reset();
         self.shadow_unaligned_head = false;
         self.allow_stale_data_this_cycle = false;
@@ -653,11 +701,13 @@ impl fmt::Display for PrefetchInputQueue {
 //          Consider using flops for PIQ.
 // TODO: note about execute-time branches
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(serialize = "T: Serialize", deserialize = "T: Deserialize<'de>"))]
 struct ShadowBuffer<T, const N: usize>
 where
     T: Default + Copy,
 {
+    #[serde(with = "crate::common::utils::serde_with::array")]
     arr: [T; N],
     tail: usize,
     head: usize,
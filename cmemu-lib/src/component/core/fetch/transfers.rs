@@ -1,4 +1,5 @@
-use log::trace;
+use log::{debug, trace};
+use serde::{Deserialize, Serialize};
 use std::mem;
 
 use crate::bridge_ports;
@@ -6,7 +7,7 @@ use crate::common::new_ahb::databus::DataBus;
 use crate::common::new_ahb::master_driver::MasterDriver;
 use crate::common::new_ahb::master_driver::stateless_helpers::SimplerHandler;
 use crate::common::new_ahb::ports::AHBPortConfig;
-use crate::common::new_ahb::signals::{Protection, Size};
+use crate::common::new_ahb::signals::{Direction, Protection, Size, TransferMeta};
 #[cfg(feature = "cycle-debug-logger")]
 use crate::common::new_ahb::{
     master_driver::{TransferInfoView, TransferStatus},
@@ -16,7 +17,8 @@ use crate::common::{Address, Word};
 #[cfg(feature = "cycle-debug-logger")]
 use crate::component::core::TransferType;
 use crate::component::core::fetch::{DataReadCallback, TransferState};
-use crate::component::core::{CoreComponent, IBusM};
+use crate::component::core::{CoreComponent, IBusM, RegisterBank};
+use crate::component::nvic::Fault;
 use crate::engine::{
     Context, DisableableComponent, Subcomponent, TickComponent, TickComponentExtra,
 };
@@ -31,15 +33,24 @@ pub(in crate::component::core) type IBusDriver = MasterDriver<IBusDriverSC, Tran
 
 /// Maintains information about each transfer that is requested by the Fetch
 /// till the moment when the data arrives.
-#[derive(Subcomponent, TickComponent, TickComponentExtra, DisableableComponent)]
+#[derive(
+    Clone,
+    Subcomponent,
+    TickComponent,
+    TickComponentExtra,
+    DisableableComponent,
+    Serialize,
+    Deserialize,
+)]
 #[subcomponent_1to1]
 pub(in crate::component::core) struct Transfers {
     /// Transfer that was finished in previous cycle and it was scheduled
     /// to be handled in current cycle.
     ///
     /// The transfer will be handled in [`super::Fetch::handle_requested_data()`].
+    /// The data is replaced with a fault, if the transfer got an error response.
     // TODO: or where?
-    delayed_transfer: Option<(Word, Address, DataReadCallback)>,
+    delayed_transfer: Option<(Result<Word, Fault>, Address, DataReadCallback)>,
 
     #[subcomponent(pub(in crate::component::core) IBusDriverSC)]
     instruction_bus_driver: IBusDriver,
@@ -52,9 +63,12 @@ bridge_ports!(@auto_configured @master Transfers => @master IBusM);
 bridge_ports!(@auto_configured @master IBusDriver => @master Transfers);
 
 // Pub only because interface is parametrized with this type
-#[derive(Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub(in crate::component::core) struct TransferUserData {
     cb: DataReadCallback,
+    /// Whether the MPU denies the fetch, decided when the word is requested.
+    /// The Bus Matrix responds with an error then, see [`Self::transfers_aborted`].
+    mpu_denied: bool,
     #[cfg(feature = "cycle-debug-logger")]
     transfer_type: TransferType,
 }
@@ -75,6 +89,8 @@ impl SimplerHandler for Transfers {
     const AHB_LITE_COMPAT: bool = true;
     const DEFAULT_PROT: Protection = Protection::new_instruction();
     const HAS_GRANTING_WIRE: bool = true;
+    // The next fetch may be a branch target, which must not fault because of the previous one.
+    const CANCELS_ADDR_PHASE_ON_ERROR: bool = false;
 
     // TODO: consider DENIES, not only hready
     fn read_will_advance(
@@ -95,13 +111,44 @@ impl SimplerHandler for Transfers {
         trace!("Fetch stalled (addr: {has_addr}, data: {has_data})");
     }
 
+    /// [ARM-ARM] B1.5.14: an error response to an instruction fetch is an IBUSERR `BusFault`,
+    /// raised only when the instruction is executed. Therefore, the word is delivered to the PIQ
+    /// as aborted, while an error response to an ignored (flushed) fetch is dropped.
+    /// The Bus Matrix responds with an error to a fetch denied by the MPU as well,
+    /// which is an IACCVIOL `MemManage` fault instead.
     fn transfers_aborted(
-        _comp: &mut <Self as AHBPortConfig>::Component,
+        comp: &mut <Self as AHBPortConfig>::Component,
         _ctx: &mut Context,
-        _addr_phase: Option<<Self as SimplerHandler>::UserData>,
-        _data_phase: Option<<Self as SimplerHandler>::UserData>,
+        addr_phase: Option<(TransferMeta, <Self as SimplerHandler>::UserData)>,
+        data_phase: Option<(TransferMeta, <Self as SimplerHandler>::UserData)>,
     ) {
-        unimplemented!()
+        debug_assert!(
+            addr_phase.is_none(),
+            "Fetch doesn't cancel the address phase"
+        );
+        let Some((meta, user)) = data_phase else {
+            return;
+        };
+        debug!("Bus error response for the fetch from {:?}", meta.addr);
+        match user.cb {
+            DataReadCallback::Ignore { .. } => {}
+            DataReadCallback::AddToPiq { .. } => {
+                let mut this = Self::get_proxy(comp);
+                debug_assert!(this.delayed_transfer.is_none());
+                // The MPU check precedes the bus transfer.
+                let fault = if user.mpu_denied {
+                    Fault::InstructionAccessViolation
+                } else {
+                    Fault::InstructionBusError
+                };
+                this.delayed_transfer = Some((Err(fault), meta.addr, user.cb));
+            }
+            // [ARM-ARM] B1.5.14: a vector table read error is a VECTTBL HardFault.
+            cb => unimplemented!(
+                "Bus error on vector table read from {:?} ({cb:?})",
+                meta.addr
+            ),
+        }
     }
 
     /// Handles data that arrived on the Instruction bus in combinatorial way (same cycle).
@@ -128,7 +175,7 @@ impl SimplerHandler for Transfers {
         // Delay handling of the data to the next cycle.
         // However it may turn out in the future that some transfers
         // should be handled without delay. If so, do it here.
-        let transfer_result = (data, address, user.cb);
+        let transfer_result = (Ok(data), address, user.cb);
         this.delayed_transfer = Some(transfer_result);
 
         // Transfers::handle_transfer_done(comp, data, address, user.cb);
@@ -194,7 +241,9 @@ impl Transfers {
 }
 
 impl Transfers {
-    pub(super) fn take_delayed_transfer(&mut self) -> Option<(Word, Address, DataReadCallback)> {
+    pub(super) fn take_delayed_transfer(
+        &mut self,
+    ) -> Option<(Result<Word, Fault>, Address, DataReadCallback)> {
         self.delayed_transfer.take()
     }
 
@@ -293,6 +342,22 @@ impl Transfers {
         #[cfg(feature = "cycle-debug-logger")] transfer_type: TransferType,
     ) {
         debug_assert!(address.is_aligned_to_4_bytes());
+        let (prot, mpu_denied) = if matches!(callback, DataReadCallback::AddToPiq { .. }) {
+            let is_privileged = RegisterBank::current_mode_is_privileged(core);
+            let prot = Self::DEFAULT_PROT.with_privileged(is_privileged);
+            // The default memory map XN applies even with the MPU disabled.
+            let mpu_denied = core
+                .mpu_copy
+                .validate(address, Direction::Read, prot)
+                .is_err();
+            (
+                core.mpu_copy.apply_memory_attributes(address, prot),
+                mpu_denied,
+            )
+        } else {
+            // [ARM-TRM-G] 12.2 ICode bus interface: vector table reads are marked as data accesses.
+            (Protection::new_data(), false)
+        };
         let this = Self::component_to_member_mut(core);
         #[cfg(feature = "cycle-debug-logger")]
         {
@@ -302,8 +367,10 @@ impl Transfers {
         assert!(this.instruction_bus_driver.try_read_data_maybe_tag(
             address,
             Size::Word,
+            prot,
             TransferUserData {
                 cb: callback,
+                mpu_denied,
                 #[cfg(feature = "cycle-debug-logger")]
                 transfer_type,
             },
@@ -1,6 +1,8 @@
 use super::register_bank::{RegisterBitmap, RegisterID, XPSR};
 use crate::common::{BitstringUtils, SRType, Shift, Word, bitstring::constants as bsc};
+use crate::component::nvic::Fault;
 use crate::{Bitstring, bitstring_concat, bitstring_extract};
+use serde::{Deserialize, Serialize};
 use std::fmt;
 use strum::IntoStaticStr;
 
@@ -15,18 +17,28 @@ use strum::IntoStaticStr;
 //   documentation of `LDR (register)` starts with "Load Register (literal)",
 //   so the generated enum variant name is `LoadRegister_Literal`
 
+/// Name of an unsupported instruction.
+/// It is an alias, as `serde` would otherwise borrow a `&str` from the deserialized data.
+type UnsupportedName = &'static str;
+
 // Used inside core (methods are pub(super)),
 // but passed to CDL (so the type is pub(crate)).
 /// ARMv7-M instruction representation
 #[allow(non_camel_case_types, clippy::enum_variant_names)]
-#[derive(Clone, Debug, IntoStaticStr)]
+#[derive(Clone, Debug, IntoStaticStr, Serialize, Deserialize)]
 pub(crate) enum Instruction {
     /// Instruction not supported by the emulator.
-    Unsupported { name: &'static str },
+    Unsupported {
+        #[serde(deserialize_with = "crate::common::utils::serde_with::leak_str")]
+        name: UnsupportedName,
+    },
     /// [ARM-ARM] A5.1.1
     Undefined,
     /// [ARM-ARM] A5.1.1
     Unpredictable,
+    /// Instruction which fetch was aborted, its execution raises the `fault` instead.
+    /// [ARM-ARM] B1.5.14 Fault behavior
+    FetchAborted { fault: Fault },
     /// [ARM-ARM] A7.7.1
     AddWithCarry_Immediate {
         rd: RegisterID,
@@ -963,7 +975,7 @@ pub(crate) enum Instruction {
 // Used inside core (methods are pub(super)),
 // but passed to CDL as part of Instruction (so the type is pub(crate)).
 /// Newtype helper type for branch and if-then conditions.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub(crate) struct Condition(pub(super) Bitstring![4]);
 
 /// Describes some properties of instructions operating on memory
@@ -998,6 +1010,7 @@ impl Instruction {
             | Self::Unsupported { .. }
             | Self::Undefined
             | Self::Unpredictable
+            | Self::FetchAborted { .. }
             | Self::Breakpoint { .. }
             | Self::ChangeProcessorState { .. }
             | Self::ClearExclusive
@@ -1198,6 +1211,7 @@ impl Instruction {
             Self::Unsupported { .. }
             | Self::Undefined
             | Self::Unpredictable
+            | Self::FetchAborted { .. }
             | Self::Breakpoint { .. }
             | Self::ChangeProcessorState { .. }
             | Self::MoveToSpecialRegisterFromARMRegister { .. }
@@ -1580,6 +1594,7 @@ impl Instruction {
             Self::Unsupported { .. }
             | Self::Undefined
             | Self::Unpredictable
+            | Self::FetchAborted { .. }
             | Self::Breakpoint { .. }
             | Self::ChangeProcessorState { .. }
             | Self::DataMemoryBarrier { .. }
@@ -1685,6 +1700,7 @@ impl Instruction {
             Self::Unsupported { .. }
             | Self::Undefined
             | Self::Unpredictable
+            | Self::FetchAborted { .. }
             | Self::AddWithCarry_Immediate { .. }
             | Self::AddWithCarry_Register { .. }
             | Self::Add_Immediate { .. }
@@ -1993,6 +2009,7 @@ impl Instruction {
             Self::Unsupported { .. }
             | Self::Undefined
             | Self::Unpredictable
+            | Self::FetchAborted { .. }
             | Self::Breakpoint { .. }
             | Self::ChangeProcessorState { .. }
             | Self::AddressToRegister { .. }
@@ -2096,6 +2113,7 @@ impl fmt::Display for Instruction {
             Self::Unsupported { name } => write!(f, "unsupported ({name})"),
             Self::Undefined => write!(f, "undefined"),
             Self::Unpredictable => write!(f, "unpredictable"),
+            Self::FetchAborted { fault } => write!(f, "fetch aborted ({fault:?})"),
             Self::Breakpoint { imm32 } => write!(f, "bkpt {}", PrintImm(*imm32)),
             Self::ChangeProcessorState {
                 enable,
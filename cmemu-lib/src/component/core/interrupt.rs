@@ -1,5 +1,6 @@
 use cc2650_constants::operation::{ExecutionMode, StackPointer};
-use log::{debug, trace};
+use log::{debug, trace, warn};
+use serde::{Deserialize, Serialize};
 
 use crate::common::new_ahb::databus::DataBus;
 use crate::common::{BitstringUtils, Word, bitstring::constants as bsc, new_ahb};
@@ -21,7 +22,10 @@ use cmemu_common::Address;
 
 use super::builtins::have_fp_ext;
 use super::decode::TriggerData;
-use super::lsu::ReadDataCallback;
+use super::fetch::HandlerFetchedFn;
+use super::lsu::{
+    DecodeFn, GetWriteDataFn, ReadDataCallback, ReadDataFn, ReadRegisterFn, WriteDoneFn,
+};
 use super::{LSU, PipelineAction};
 
 // Remark: [TI-TRM] 4.1.2: Interrupt is an exception signaled by a software signal.
@@ -76,14 +80,15 @@ impl From<Word> for ReturnBehavior {
             return ReturnBehavior::NormalBranch;
         }
 
-        #[allow(clippy::manual_assert)] // Not an assertion.
+        // These cases are UNPREDICTABLE in [ARM-ARM], but Cortex-M3 treats them as illegal
+        // EXC_RETURN values and raises an INVPC UsageFault ([ARM-TRM-G] 8.2.2 UFSR).
         if !addr_27_5.is_ones() {
-            panic!("UNPREDICTABLE exception return value {addr:?} on bits 27-5");
+            warn!("UNPREDICTABLE exception return value {addr:?} on bits 27-5");
+            return ReturnBehavior::Reserved;
         }
-
-        #[allow(clippy::manual_assert)] // Not an assertion.
         if !addr_4 && !have_fp_ext() {
-            panic!("Exception return with Addr[4]==0 is UNPREDICTABLE without FP extension");
+            warn!("Exception return with Addr[4]==0 is UNPREDICTABLE without FP extension");
+            return ReturnBehavior::Reserved;
         }
 
         match addr_3_0 {
@@ -102,10 +107,7 @@ impl From<Word> for ReturnBehavior {
                 return_stack: StackPointer::Process,
                 _extended_frame: !addr_4,
             },
-            _ => {
-                paranoid!(error, "Reserved EXC_RETURN is not properly supported yet!");
-                ReturnBehavior::Reserved
-            }
+            _ => ReturnBehavior::Reserved,
         }
     }
 }
@@ -117,7 +119,15 @@ impl From<Word> for ReturnBehavior {
 /// - register updates
 /// - unstacking
 /// - exception return
-#[derive(Subcomponent, TickComponent, TickComponentExtra, DisableableComponent)]
+#[derive(
+    Clone,
+    Subcomponent,
+    TickComponent,
+    TickComponentExtra,
+    DisableableComponent,
+    Serialize,
+    Deserialize,
+)]
 #[subcomponent_1to1]
 pub(super) struct InterruptEntryAndExitHandler {
     interrupt_entry_exit_state: InterruptEntryExitState,
@@ -144,10 +154,17 @@ pub(super) struct InterruptEntryAndExitHandler {
     /// synchronize value.
     #[flop]
     vtor_copy: VTOR,
+    /// Exceptions taken and not deactivated yet, as a bitmask indexed by the exception number.
+    /// The exception return has to check them synchronously, while the state of record
+    /// is kept by [`NVICComponent`].
+    /// Note: writes to the active bits of `SHCSR` are not reflected here.
+    ///
+    /// [`NVICComponent`]: crate::component::nvic::NVICComponent
+    active_exceptions: u64,
 }
 
 /// `InterruptEntryExitState` enum represents the state of the interrupt entry and exit.
-#[derive(Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 enum InterruptEntryExitState {
     None,
     ReadyToEntry(EntryState),
@@ -159,18 +176,18 @@ enum InterruptEntryExitState {
     ExitUnstacking(UnstackingState),
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 struct EntryState {
     interrupt_data: InterruptEntryData,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 enum StackingState {
     InProgress(StackingInProgressState),
     Finished(InterruptId),
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 struct StackingInProgressState {
     /// Iterator over registers that have to be pushed onto stack.
     stack_frame_iterator: stack_frame::Iter,
@@ -189,25 +206,25 @@ struct StackingInProgressState {
     next_instr_addr: Word,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 struct TailChainState {
     exc_return: Word,
     tail_chained_interrupt_data: InterruptData,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 struct ExitState {
     exc_return: Word,
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 pub(super) struct InterruptEntryData {
     pub(super) interrupt_id: InterruptId,
     pub(super) this_instr_addr: Word,
     pub(super) next_instr_addr: Word,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 struct UnstackingState {
     /// Iterator over registers that have to be popped from stack.
     stack_frame_iterator: stack_frame::Iter,
@@ -222,7 +239,7 @@ struct UnstackingState {
     address_to_branch_to_after_unstacking: Option<Word>,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 enum FetchingHandlerState {
     None,
     InProgress,
@@ -239,6 +256,7 @@ impl InterruptEntryAndExitHandler {
             exc_return: SeqFlop::new(),
             tail_chained_interrupt: CombFlopMemoryBankSimple::new(None),
             vtor_copy: VTOR::initial(),
+            active_exceptions: 0,
         }
     }
 
@@ -313,8 +331,11 @@ impl InterruptEntryAndExitHandler {
         }
 
         if let Some(exc_return) = this.exc_return.try_take() {
+            // An illegal return raises INVPC instead, so it cannot tail-chain.
+            let tail_chained_interrupt = (*this.tail_chained_interrupt)
+                .filter(|_| Self::is_exception_return_legal(this.component(), exc_return));
             // TODO: do we really need nested Option inside or could we just use try_take?
-            this.interrupt_entry_exit_state = (*this.tail_chained_interrupt).map_or_else(
+            this.interrupt_entry_exit_state = tail_chained_interrupt.map_or_else(
                 || InterruptEntryExitState::ReadyToExit(ExitState { exc_return }),
                 |interrupt_data| {
                     InterruptEntryExitState::TailChain(TailChainState {
@@ -396,8 +417,9 @@ impl InterruptEntryAndExitHandler {
                 }
             }
             InterruptEntryExitState::ReadyToExit(..) => {
-                Self::run_exception_return(core, ctx);
-                Self::run_unstacking(core, ctx);
+                if Self::run_exception_return(core, ctx) {
+                    Self::run_unstacking(core, ctx);
+                }
                 // Unlike entry to interrupt, exit doesn't ignore currently
                 // decoding instruction. It is done by `Execute` subcomponent.
                 // The reason for this asymmetry is that `Execute` doesn't know
@@ -543,24 +565,11 @@ impl InterruptEntryAndExitHandler {
                     address.into(),
                     new_ahb::Size::Word,
                     ReadDataCallback::WriteCallbacks {
-                        get_data: |_core, _reg, _size| unreachable!(),
+                        get_data: GetWriteDataFn::Latched,
                         write_done: if is_last {
-                            |core, _reg| {
-                                let state =
-                                    Self::get_state(core).unwrap_stacking_in_progress_state();
-                                // Stacking finished
-                                debug_assert!(state.stack_frame_iterator.next().is_none());
-                                let interrupt_id = state.interrupt_id;
-                                Self::update_lr(core);
-                                Self::set_state(
-                                    core,
-                                    InterruptEntryExitState::EntryStacking(
-                                        StackingState::Finished(interrupt_id),
-                                    ),
-                                );
-                            }
+                            WriteDoneFn::FinishStacking
                         } else {
-                            |_core, _reg| {}
+                            WriteDoneFn::Nothing
                         },
                         reg: RegisterID::LR,
                     },
@@ -656,7 +665,6 @@ impl InterruptEntryAndExitHandler {
         // [ARM-ARM] B1.5.6 Exception entry behaviour - ReturnAddress() pseudocode.
         // Return address is always halfword aligned - bit<0> is always 0.
         debug_assert!(!pc.get_bit(0));
-
         pc
     }
 
@@ -715,11 +723,13 @@ impl InterruptEntryAndExitHandler {
 
         RegisterBank::set_register(core, RegisterID::LR, exc_return);
 
-        let returning_exception_number = InterruptId::try_from_exception_number(
-            RegisterBank::get_xpsr(core).get_exception_number() as usize,
-        )
-        .unwrap();
-        Self::deactivate(core, ctx, returning_exception_number);
+        let returning_exception_number = Self::returning_exception_number(core);
+        Self::deactivate(
+            core,
+            ctx,
+            returning_exception_number,
+            CoreStateChange::StartedTailChain,
+        );
     }
 }
 
@@ -732,13 +742,18 @@ impl InterruptEntryAndExitHandler {
             compute_interrupt_handler_address(interrupt_id, vector_table_offset);
         this.fetching_handler_state
             .set_next(FetchingHandlerState::InProgress);
-        let callback: fn(&mut CoreComponent) = |core| {
-            let mut this = Self::get_proxy(core);
-            this.fetching_handler_state
-                .set_next(FetchingHandlerState::Finished);
-        };
+        Fetch::make_vector_call(
+            core,
+            interrupt_handler_address,
+            Some(HandlerFetchedFn::FinishFetchingHandler),
+        );
+    }
 
-        Fetch::make_vector_call(core, interrupt_handler_address, Some(callback));
+    /// Callback of [`HandlerFetchedFn::FinishFetchingHandler`].
+    pub(super) fn on_handler_fetched(core: &mut CoreComponent) {
+        let mut this = Self::get_proxy(core);
+        this.fetching_handler_state
+            .set_next(FetchingHandlerState::Finished);
     }
 
     fn complete_entry(core: &mut CoreComponent, ctx: &mut Context, interrupt_id: InterruptId) {
@@ -766,6 +781,9 @@ impl InterruptEntryAndExitHandler {
         let interrupt_id = interrupt_id.as_exception_number() as u32;
         // Note: the handler mode is simply when an exception is executing (exception_no!=0)
         // We don't set EPSR.T, since there is no ARM mode
+        let this = Self::component_to_member_mut(core);
+        this.active_exceptions |= 1 << interrupt_id;
+
         let xpsr = RegisterBank::get_xpsr(core)
             .with_exception_number(interrupt_id)
             .with_itstate(ItState::new_outside_it_block());
@@ -775,11 +793,11 @@ impl InterruptEntryAndExitHandler {
             RegisterBank::get_control(core).with_stack_pointer_selector(StackPointer::Main);
         RegisterBank::set_control(core, control);
 
-        // TODO: Add implementation of 4 last function calls in
+        core.clear_exclusive_local();
+        core.set_event_register();
+        // TODO: Add implementation of the remaining function calls in
         // [ARM-ARM] ExceptionTaken() pseudocode, i.e.:
         // - disable floating-point mode
-        // - ClearExclusiveLocal(),
-        // - SetEventRegister(),
         // - InstructionSynchronizationBarrier().
 
         let this = Self::component_to_member(core);
@@ -803,59 +821,111 @@ impl InterruptEntryAndExitHandler {
 // Interrupt exit.
 impl InterruptEntryAndExitHandler {
     /// [ARM-ARM] B1.5.8 Exception return behavior - Exception return operation.
-    /// This function is only executed once, then it is followed with `run_unstacking`
-    fn run_exception_return(core: &mut CoreComponent, ctx: &mut Context) {
+    /// This function is only executed once, then it is followed with `run_unstacking`,
+    /// unless the return was illegal (then `false` is returned and `UsageFault` is raised).
+    fn run_exception_return(core: &mut CoreComponent, ctx: &mut Context) -> bool {
         let running_mode = RegisterBank::get_xpsr(core).current_mode();
         debug_assert_eq!(running_mode, ExecutionMode::Handler);
 
-        let state = Self::get_state(core).unwrap_exit_state();
-        let exc_return = ReturnBehavior::from(state.exc_return);
+        let exc_return_value = Self::get_state(core).unwrap_exit_state().exc_return;
 
         // Remaining checks done in the ReturnBehavior
         if have_fp_ext() {
             unimplemented!("FP not supported by CM");
         }
 
-        let returning_exception_number = InterruptId::try_from_exception_number(
-            RegisterBank::get_xpsr(core).get_exception_number() as usize,
-        )
-        .unwrap();
+        let returning_exception_number = Self::returning_exception_number(core);
 
-        // TODO: Get number of active exceptions.
+        if !Self::is_exception_return_legal(core, exc_return_value) {
+            Self::run_illegal_exception_return(
+                core,
+                ctx,
+                returning_exception_number,
+                exc_return_value,
+            );
+            return false;
+        }
 
-        // TODO: Check if current interrupt/exception is active - it cannot be
-        // done, because UsageFault isn't supported yet.
+        // when '0001' or '1x01'
+        let ReturnBehavior::ExcReturn { return_stack, .. } = ReturnBehavior::from(exc_return_value)
+        else {
+            unreachable!("Illegal EXC_RETURN should be handled by the checks above")
+        };
+        let control = RegisterBank::get_control(core).with_stack_pointer_selector(return_stack);
+        RegisterBank::set_control(core, control);
 
-        // Similar arms will be changed when nested interrupts are implemented.
-        #[allow(clippy::match_same_arms)]
-        match exc_return {
-            // when '0001'
+        Self::deactivate(
+            core,
+            ctx,
+            returning_exception_number,
+            CoreStateChange::StartedExit,
+        );
+        core.clear_exclusive_local();
+        core.set_event_register();
+
+        // see PopStack() below
+        true
+    }
+
+    /// [ARM-ARM] B1.5.8 `ExceptionReturn()` pseudocode - the checks resulting in INVPC `UsageFault`:
+    /// - the returning exception is not active,
+    /// - a return to Thread mode leaves other exceptions active, while `CCR.NONBASETHRDENA` is clear,
+    /// - `EXC_RETURN` is reserved.
+    fn is_exception_return_legal(core: &CoreComponent, exc_return: Word) -> bool {
+        let this = Self::component_to_member(core);
+        let returning_exception_bit = 1 << RegisterBank::get_xpsr(core).get_exception_number();
+        if this.active_exceptions & returning_exception_bit == 0 {
+            return false;
+        }
+
+        match ReturnBehavior::from(exc_return) {
             ReturnBehavior::ExcReturn {
                 return_to: ExecutionMode::Handler,
-                return_stack,
                 ..
-            } => {
-                let control =
-                    RegisterBank::get_control(core).with_stack_pointer_selector(return_stack);
-                RegisterBank::set_control(core, control);
-            }
-            // when '1x01'
+            } => true,
             ReturnBehavior::ExcReturn {
                 return_to: ExecutionMode::Thread,
-                return_stack,
                 ..
             } => {
-                // TODO: Check if there are any nested interrupts - not implemented yet.
-                let control =
-                    RegisterBank::get_control(core).with_stack_pointer_selector(return_stack);
-                RegisterBank::set_control(core, control);
+                this.active_exceptions & !returning_exception_bit == 0
+                    || Execute::nonbase_thread_enabled(core)
+            }
+            ReturnBehavior::Reserved => false,
+            ReturnBehavior::NormalBranch => {
+                unreachable!("Exception return started with a non EXC_RETURN value")
             }
-            _ => unimplemented!("Illegal EXC_RETURN is not supported yet!"),
         }
+    }
 
-        Self::deactivate(core, ctx, returning_exception_number);
-
-        // see PopStack() below
+    /// [ARM-ARM] B1.5.8 `ExceptionReturn()` pseudocode - the illegal return case.
+    ///
+    /// The returning exception is deactivated, but nothing is unstacked. Instead, INVPC
+    /// `UsageFault` is raised by [`NVICComponent`] on [`CoreStateChange::IllegalExit`],
+    /// with the instruction loading `EXC_RETURN` as its return address ([ARM-TRM-G] 8.2.2 UFSR).
+    /// Note: the fault is taken with a regular entry, which stacks another frame.
+    ///
+    /// [`NVICComponent`]: crate::component::nvic::NVICComponent
+    fn run_illegal_exception_return(
+        core: &mut CoreComponent,
+        ctx: &mut Context,
+        returning_exception_number: InterruptId,
+        exc_return: Word,
+    ) {
+        warn!("Illegal exception return with EXC_RETURN {exc_return:?}");
+        Self::deactivate(
+            core,
+            ctx,
+            returning_exception_number,
+            CoreStateChange::IllegalExit,
+        );
+        let exc_return_27_0 = bitstring_extract!(exc_return<27:0> | 28 bits);
+        RegisterBank::set_register(
+            core,
+            RegisterID::LR,
+            Word::from(0xF000_0000 | u32::from(exc_return_27_0)),
+        );
+        Self::set_state(core, InterruptEntryExitState::None);
+        Execute::wait_for_exception_after_interruption(core);
     }
 
     /// `run_unstacking` executes unstacking. Returned value informs whether unstacking finished.
@@ -868,9 +938,15 @@ impl InterruptEntryAndExitHandler {
         #[cfg(feature = "cycle-debug-logger")]
         CycleDebugLoggerProxy::new().on_core_run_unstacking(ctx);
 
-        if let InterruptEntryExitState::ReadyToExit(..) = state {
-            // We already have SP fixed back to the original stack
-            let sp_address = Address::from(RegisterBank::get_register(core, RegisterID::SP));
+        if let InterruptEntryExitState::ReadyToExit(ExitState { exc_return }) = state {
+            // [ARM-ARM] B1.5.8 PopStack() pseudocode: the frame pointer is selected by EXC_RETURN.
+            // Note: the new CONTROL.SPSEL is not visible yet, and IPSR is not restored,
+            // so `LookUpSP()` cannot be used here.
+            let ReturnBehavior::ExcReturn { return_stack, .. } = ReturnBehavior::from(*exc_return)
+            else {
+                unreachable!("Illegal EXC_RETURN should be handled in exception return")
+            };
+            let sp_address = Address::from(RegisterBank::get_stack_pointer(core, return_stack));
 
             Self::set_state(
                 core,
@@ -943,48 +1019,71 @@ impl InterruptEntryAndExitHandler {
         reg: stack_frame::Register,
         is_last: bool,
     ) -> ReadDataCallback {
-        let decode_fn = DataBus::unwrap_word;
+        let decode_fn = DecodeFn::UnwrapWord;
         match reg {
-            // [ARM-ARM] B1.5.8 Exception return behaviour - PopStack() pseudocode.
-            // PC is popped from the stack in different way than the other registers.
-            // BranchTo() is used - unpredictable if the new PC is not halfword aligned.
-            stack_frame::Register::Reg(RegisterID::PC) => ReadDataCallback::WithDecodeFn(
-                |core, decode, data| {
-                    let address = decode(data);
-                    Self::set_address_to_branch_to(core, address);
-                },
-                decode_fn,
-            ),
-            stack_frame::Register::XPSR => ReadDataCallback::WithDecodeFn(
-                |core, decode, data| {
-                    let xpsr = XPSR::from(decode(data));
-
-                    // Execute writeback - set value of SP to the one that is expected after unstacking.
-                    // It has to be done after reading value of XPSR from stack to know if SP alignment was required during stacking.
-                    // With this information new value for SP can be computed.
-                    Self::do_sp_writeback(core, xpsr);
-
-                    Self::update_current_xpsr(core, xpsr);
-                },
-                decode_fn,
-            ),
+            stack_frame::Register::Reg(RegisterID::PC) => {
+                ReadDataCallback::WithDecodeFn(ReadDataFn::UnstackPc, decode_fn)
+            }
+            stack_frame::Register::XPSR => {
+                ReadDataCallback::WithDecodeFn(ReadDataFn::UnstackXpsr, decode_fn)
+            }
             stack_frame::Register::Reg(reg) => ReadDataCallback::WithRegisterAndDecodeFn(
-                if is_last {
-                    |core, #[cfg(feature = "cycle-debug-logger")] _ctx, reg, decode, data| {
-                        RegisterBank::set_register(core, reg, decode(data));
-                        Self::late_finish_unstacking(core);
-                    }
-                } else {
-                    |core, #[cfg(feature = "cycle-debug-logger")] _ctx, reg, decode, data| {
-                        RegisterBank::set_register(core, reg, decode(data));
-                    }
-                },
+                ReadRegisterFn::Unstack { last: is_last },
                 reg,
                 decode_fn,
             ),
         }
     }
 
+    /// Callback of [`WriteDoneFn::FinishStacking`], run when the last register is pushed.
+    pub(super) fn on_stacking_finished(core: &mut CoreComponent) {
+        let state = Self::get_state(core).unwrap_stacking_in_progress_state();
+        // Stacking finished
+        debug_assert!(state.stack_frame_iterator.next().is_none());
+        let interrupt_id = state.interrupt_id;
+        Self::update_lr(core);
+        Self::set_state(
+            core,
+            InterruptEntryExitState::EntryStacking(StackingState::Finished(interrupt_id)),
+        );
+    }
+
+    /// Callback of [`ReadDataFn::UnstackPc`].
+    ///
+    /// [ARM-ARM] B1.5.8 Exception return behaviour - `PopStack()` pseudocode.
+    /// PC is popped from the stack in different way than the other registers.
+    /// `BranchTo()` is used - unpredictable if the new PC is not halfword aligned.
+    pub(super) fn on_pc_unstacked(core: &mut CoreComponent, decode: DecodeFn, data: DataBus) {
+        let address = decode.decode(data);
+        Self::set_address_to_branch_to(core, address);
+    }
+
+    /// Callback of [`ReadDataFn::UnstackXpsr`].
+    pub(super) fn on_xpsr_unstacked(core: &mut CoreComponent, decode: DecodeFn, data: DataBus) {
+        let xpsr = XPSR::from(decode.decode(data));
+
+        // Execute writeback - set value of SP to the one that is expected after unstacking.
+        // It has to be done after reading value of XPSR from stack to know if SP alignment was required during stacking.
+        // With this information new value for SP can be computed.
+        Self::do_sp_writeback(core, xpsr);
+
+        Self::update_current_xpsr(core, xpsr);
+    }
+
+    /// Callback of [`ReadRegisterFn::Unstack`].
+    pub(super) fn on_register_unstacked(
+        core: &mut CoreComponent,
+        reg: RegisterID,
+        decode: DecodeFn,
+        data: DataBus,
+        is_last: bool,
+    ) {
+        RegisterBank::set_register(core, reg, decode.decode(data));
+        if is_last {
+            Self::late_finish_unstacking(core);
+        }
+    }
+
     fn set_address_to_branch_to(core: &mut CoreComponent, address: Word) {
         let state = Self::get_state(core).unwrap_unstacking_state();
         state.address_to_branch_to_after_unstacking = Some(address);
@@ -1013,7 +1112,8 @@ impl InterruptEntryAndExitHandler {
             address = address.offset(4);
         }
 
-        RegisterBank::set_register(core, RegisterID::SP, address.into());
+        let stack = RegisterBank::get_control(core).stack_pointer_selector();
+        RegisterBank::set_stack_pointer(core, stack, address.into());
     }
 
     /// [ARM-ARM] B1.5.8: Exception return operation - `PopStack()` pseudocode with `xPSR` update.
@@ -1037,25 +1137,41 @@ impl InterruptEntryAndExitHandler {
         RegisterBank::set_xpsr(core, XPSR::from(xpsr));
     }
 
+    /// `ReturningExceptionNumber` of [ARM-ARM] B1.5.8 `ExceptionReturn()` pseudocode.
+    fn returning_exception_number(core: &CoreComponent) -> InterruptId {
+        InterruptId::try_from_exception_number(
+            RegisterBank::get_xpsr(core).get_exception_number() as usize
+        )
+        .unwrap()
+    }
+
     /// [ARM-ARM] B1.5.8 - `DeActivate()` pseudocode.
+    /// The `state_change` tells [`NVICComponent`] why the exception is deactivated.
+    ///
+    /// [`NVICComponent`]: crate::component::nvic::NVICComponent
     fn deactivate(
         core: &mut CoreComponent,
         ctx: &mut Context,
         returning_exception_number: InterruptId,
+        state_change: CoreStateChange,
     ) {
-        let this = Self::component_to_member(core);
-        match this.interrupt_entry_exit_state {
-            InterruptEntryExitState::TailChain(..) => core
-                .nvic
-                .change_core_state(ctx, CoreStateChange::StartedTailChain),
-            InterruptEntryExitState::ReadyToExit(..) => core
-                .nvic
-                .change_core_state(ctx, CoreStateChange::StartedExit),
-            _ => panic!(
-                "Deactivate has been called in wrong state: {:?}",
-                this.interrupt_entry_exit_state
+        let this = Self::component_to_member_mut(core);
+        debug_assert!(
+            matches!(
+                (&this.interrupt_entry_exit_state, state_change),
+                (
+                    InterruptEntryExitState::TailChain(..),
+                    CoreStateChange::StartedTailChain
+                ) | (
+                    InterruptEntryExitState::ReadyToExit(..),
+                    CoreStateChange::StartedExit | CoreStateChange::IllegalExit
+                )
             ),
-        };
+            "Deactivate with {state_change:?} has been called in wrong state: {:?}",
+            this.interrupt_entry_exit_state
+        );
+        this.active_exceptions &= !(1 << returning_exception_number.as_exception_number());
+        core.nvic.change_core_state(ctx, state_change);
 
         if returning_exception_number != InterruptId::NMI {
             let faultmask = RegisterBank::get_faultmask(core).with_faultmask(false);
@@ -1126,6 +1242,8 @@ impl InterruptEntryExitState {
 }
 
 mod stack_frame {
+    use serde::{Deserialize, Serialize};
+
     use super::RegisterID;
 
     /// [ARM-ARM] B1.5.6 Exception entry behaviour.
@@ -1135,7 +1253,7 @@ mod stack_frame {
 
     /// [ARM-TDG] Figure 9.2 - order of registers in stacking sequence.
     /// [ARM-TDG] 9.2 - order of registers during unstacking sequence is the same as during stacking.
-    const REGISTER_ORDER: [Register; SIZE] = [
+    static REGISTER_ORDER: [Register; SIZE] = [
         Register::Reg(RegisterID::PC),
         Register::XPSR,
         Register::Reg(RegisterID::R0),
@@ -1148,16 +1266,29 @@ mod stack_frame {
 
     /// `Register` enum represents register that can be pushed/popped to the stack.
     /// [ARM-ARM] B1.5.6 Exception entry behaviour, these registers are: R0-R3, R12, LR, PC and `xPSR`.
-    #[derive(Copy, Clone, Debug)]
+    #[derive(Copy, Clone, Debug, Serialize, Deserialize)]
     pub(super) enum Register {
         XPSR,
         Reg(RegisterID),
     }
 
-    pub(super) type Iter = std::slice::Iter<'static, Register>;
+    /// Iterator over the stack frame registers in [`REGISTER_ORDER`].
+    /// It is stored as a position, so that it can be serialized.
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub(super) struct Iter(usize);
+
+    impl Iterator for Iter {
+        type Item = &'static Register;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            let reg = REGISTER_ORDER.get(self.0)?;
+            self.0 += 1;
+            Some(reg)
+        }
+    }
 
     pub(super) fn new_register_iterator() -> Iter {
-        REGISTER_ORDER.iter()
+        Iter(0)
     }
 
     /// Returns offset from SP address after stacking.
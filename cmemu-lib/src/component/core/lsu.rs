@@ -1,6 +1,7 @@
+use serde::{Deserialize, Serialize};
 use std::fmt::{Debug, Formatter};
 
-use log::trace;
+use log::{debug, trace};
 
 use crate::bridge_ports;
 use crate::common::Word;
@@ -8,7 +9,7 @@ use crate::common::new_ahb::databus::DataBus;
 use crate::common::new_ahb::master_driver::MasterDriver;
 use crate::common::new_ahb::master_driver::stateless_helpers::SimplerHandler;
 use crate::common::new_ahb::ports::AHBPortConfig;
-use crate::common::new_ahb::signals::{Protection, Size};
+use crate::common::new_ahb::signals::{Direction, Protection, Size, TransferMeta};
 #[cfg(feature = "cycle-debug-logger")]
 use crate::common::new_ahb::{
     master_driver::{TransferInfoView, TransferStatus},
@@ -24,7 +25,10 @@ use crate::utils::IfExpr;
 use cmemu_common::Address;
 
 use super::register_bank::RegisterID;
-use super::{CoreComponent, DBusM};
+use super::{CoreComponent, DBusM, Execute, InterruptEntryAndExitHandler, RegisterBank};
+use crate::component::bus_matrix::MPUError;
+use crate::component::nvic::Fault;
+use crate::proxy::NVICProxy;
 
 /// Load-Store Unit
 /// Handles data bus transfers for execute and (in the future) interrupts.
@@ -54,7 +58,7 @@ use super::{CoreComponent, DBusM};
 ///   * In the first cycle we also finish the `str` instruction execution.
 ///   * In the second cycle we execute `mov` instruction and in the background
 ///     set write data for the requested in the previous cycle transfer.
-#[derive(Subcomponent, TickComponent, DisableableComponent)]
+#[derive(Clone, Subcomponent, TickComponent, DisableableComponent, Serialize, Deserialize)]
 #[subcomponent_1to1]
 pub(super) struct LSU {
     // Writeback happens when the transfer goes to data phase (i.e., it won't be repeated)
@@ -77,35 +81,74 @@ bridge_ports!(@auto_configured @master LSU => @master DBusM);
 bridge_ports!(@auto_configured @master DBusDriver => @master LSU);
 
 /// Family of functions that translates data received on Data bus to value
-/// that can be e.g. stored into a register
-pub(super) type DecodeFn = fn(DataBus) -> Word;
+/// that can be e.g. stored into a register.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub(super) enum DecodeFn {
+    UnwrapWord,
+    ZeroExtend,
+    SignExtend,
+}
 
-/// Represents callback that can be executed when data on Data bus arrives
+/// Represents callback that can be executed when data on Data bus arrives.
+///
+/// The callbacks are tags instead of function pointers, so a transfer in flight
+/// can be a part of a serialized snapshot.
+#[derive(Clone, Serialize, Deserialize)]
 pub(super) enum ReadDataCallback {
-    WithDecodeFn(fn(&mut CoreComponent, DecodeFn, DataBus), DecodeFn),
-    WithRegisterAndDecodeFn(
-        // bug in rustfmt removes `#[cfg(...)]` from inside the type
-        #[cfg(feature = "cycle-debug-logger")]
-        fn(&mut CoreComponent, &mut Context, RegisterID, DecodeFn, DataBus),
-        #[cfg(not(feature = "cycle-debug-logger"))]
-        fn(&mut CoreComponent, RegisterID, DecodeFn, DataBus),
-        RegisterID,
-        DecodeFn,
-    ),
+    WithDecodeFn(ReadDataFn, DecodeFn),
+    WithRegisterAndDecodeFn(ReadRegisterFn, RegisterID, DecodeFn),
     WriteCallbacks {
-        get_data: fn(&mut CoreComponent, RegisterID, Size) -> DataBus,
-        write_done: fn(&mut CoreComponent, RegisterID),
+        get_data: GetWriteDataFn,
+        write_done: WriteDoneFn,
         reg: RegisterID,
     },
     #[allow(dead_code)]
     NoCallback,
 }
+
+/// Consumers of read data that don't target a general purpose register.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub(super) enum ReadDataFn {
+    /// Branch to the loaded address (e.g., `LDR PC`).
+    LoadPc,
+    /// Offset of `TBB`/`TBH`.
+    TableBranchOffset,
+    /// Return address popped from the stack.
+    UnstackPc,
+    /// `xPSR` popped from the stack.
+    UnstackXpsr,
+}
+
+/// Consumers of read data that target a general purpose register.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub(super) enum ReadRegisterFn {
+    /// Load by an instruction, `last` finishes its execution.
+    Load { last: bool },
+    /// Register popped from the stack, `last` finishes the unstacking.
+    Unstack { last: bool },
+}
+
+/// Sources of the data of a write.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub(super) enum GetWriteDataFn {
+    /// The register, sampled in the data phase.
+    Register,
+    /// The data was latched with the request.
+    Latched,
+}
+
+/// Actions run when a write has finished.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub(super) enum WriteDoneFn {
+    Nothing,
+    FinishInstruction,
+    FinishStacking,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub(super) enum AddrAdvancedCallback {
-    WritebackCallback {
-        cb: fn(&mut CoreComponent, &mut Context, RegisterID, Word),
-        reg: RegisterID,
-        data: Word,
-    },
+    /// Writeback of the base register of a load/store instruction.
+    WritebackCallback { reg: RegisterID, data: Word },
     #[allow(dead_code)]
     NoCallback,
 }
@@ -177,21 +220,98 @@ impl SimplerHandler for LSU {
     ) -> DataBus {
         let view = Self::MasterDriverSC::component_to_member_mut(comp).view_data_phase();
         if let ReadDataCallback::WriteCallbacks { get_data, reg, .. } = view.user {
-            let reg = *reg;
-            get_data(comp, reg, size)
+            let (get_data, reg) = (*get_data, *reg);
+            get_data.call(comp, reg, size)
         } else {
             unreachable!("Wrong callback for write!")
         }
     }
 
+    /// [ARM-ARM] B1.5.14 and [ARM-TRM-G] 8.2.2 BFSR: an error response to a read is
+    /// a precise `BusFault`, while an error response to a write is imprecise,
+    /// since the instruction could have already finished.
     fn transfers_aborted(
         comp: &mut <Self as AHBPortConfig>::Component,
-        _ctx: &mut Context,
-        _addr_phase: Option<<Self as SimplerHandler>::UserData>,
-        _data_phase: Option<<Self as SimplerHandler>::UserData>,
+        ctx: &mut Context,
+        addr_phase: Option<(TransferMeta, <Self as SimplerHandler>::UserData)>,
+        data_phase: Option<(TransferMeta, <Self as SimplerHandler>::UserData)>,
     ) {
         let this = Self::component_to_member_mut(comp);
         this.addr_advanced_callback = None;
+
+        let mut instruction_abandoned = false;
+        if let Some((meta, user)) = data_phase {
+            let addr = meta.addr;
+            debug!("Bus error response for the transfer to {:?}", addr);
+            // Instructions check their accesses against the MPU before requesting them,
+            // but stacking and unstacking rely on the Bus Matrix responding with an error
+            // to transfers denied by the MPU, so the fault is classified with the same configuration.
+            let denied_by_mpu = comp.mpu_copy.validate(addr, meta.dir, meta.prot).is_err();
+            let fault = match user {
+                ReadDataCallback::WriteCallbacks {
+                    write_done, reg, ..
+                } => {
+                    // The write is treated as done, it cannot be retried anyway.
+                    write_done.call(comp, reg);
+                    match (
+                        Execute::is_stacking_or_unstacking_running(comp),
+                        denied_by_mpu,
+                    ) {
+                        (true, true) => Fault::StackingAccessViolation,
+                        (true, false) => Fault::StackingBusError,
+                        (false, true) => Fault::DataAccessViolation { address: addr },
+                        (false, false) => Fault::ImpreciseDataBusError,
+                    }
+                }
+                read_callback if Execute::is_stacking_or_unstacking_running(comp) => {
+                    // [ARM-ARM] B1.5.8 PopStack(): the popped value is UNKNOWN, but the exception
+                    // return completes and the fault is taken afterwards.
+                    read_callback.call(comp, ctx, DataBus::Word(0));
+                    if denied_by_mpu {
+                        Fault::UnstackingAccessViolation
+                    } else {
+                        Fault::UnstackingBusError
+                    }
+                }
+                _ => {
+                    Execute::abandon_instruction_in_tock(comp);
+                    instruction_abandoned = true;
+                    if denied_by_mpu {
+                        Fault::DataAccessViolation { address: addr }
+                    } else {
+                        Fault::PreciseDataBusError { address: addr }
+                    }
+                }
+            };
+            NVICProxy.raise_fault(ctx, fault);
+        }
+
+        // The transfer in address phase is cancelled, so it never happens.
+        match addr_phase {
+            Some((
+                _,
+                ReadDataCallback::WriteCallbacks {
+                    write_done, reg, ..
+                },
+            )) => {
+                write_done.call(comp, reg);
+            }
+            Some((meta, read_callback)) if Execute::is_stacking_or_unstacking_running(comp) => {
+                debug!("Unstacking from {:?} cancelled by a bus error", meta.addr);
+                // Only the popped PC and xPSR drive the rest of the exception return,
+                // the general purpose registers are UNKNOWN after the fault anyway.
+                if matches!(read_callback, ReadDataCallback::WithDecodeFn(..)) {
+                    read_callback.call(comp, ctx, DataBus::Word(0));
+                }
+            }
+            Some((meta, _)) if !instruction_abandoned => {
+                // TODO: the instruction is restarted after the imprecise BusFault handler,
+                //   which may never happen if the fault stays pending.
+                debug!("Read from {:?} cancelled by a bus error", meta.addr);
+                Execute::abandon_instruction_in_tock(comp);
+            }
+            _ => {}
+        }
     }
 
     fn read_done(
@@ -220,12 +340,7 @@ impl SimplerHandler for LSU {
             this.on_read_data_executed = true;
         }
 
-        cb.call(
-            comp,
-            #[cfg(feature = "cycle-debug-logger")]
-            ctx,
-            data,
-        );
+        cb.call(comp, ctx, data);
     }
 
     fn write_done(
@@ -244,7 +359,7 @@ impl SimplerHandler for LSU {
             write_done, reg, ..
         } = user
         {
-            write_done(comp, reg);
+            write_done.call(comp, reg);
         } else {
             // TODO: probably remove this comment, but it was commented out in ab352a63c9e2d5cb200fcba66072815acbd6c3f8 ("michcioperz's fix to interrupts")
             unreachable!("Wrong callback for write!")
@@ -365,9 +480,11 @@ impl LSU {
         size: Size,
         cb: ReadDataCallback,
     ) {
+        let prot = Self::request_prot(core, addr, RegisterBank::current_mode_is_privileged(core));
         let this = Self::component_to_member_mut(core);
-        let posted = this.data_bus_driver.try_read_data(addr.into(), size, cb);
-        // TODO: use RegisterBank::current_mode_is_privileged -> we already implement this!
+        let posted = this
+            .data_bus_driver
+            .try_read_data_with_prot(addr.into(), size, prot, cb);
         debug_assert!(
             posted,
             "Core attempted to schedule AHB transfer to {addr} when not available"
@@ -380,31 +497,95 @@ impl LSU {
         size: Size,
         cb: ReadDataCallback,
     ) {
+        let prot = Self::request_prot(core, addr, RegisterBank::current_mode_is_privileged(core));
         let this = Self::component_to_member_mut(core);
-        let posted = this.data_bus_driver.try_write_data(addr.into(), size, cb);
+        let posted = this
+            .data_bus_driver
+            .try_write_data_with_prot(addr.into(), size, prot, cb);
         debug_assert!(
             posted,
             "Core attempted to schedule AHB transfer to {addr} when not available"
         );
     }
 
-    pub(super) fn request_write_multiple(
+    /// Requests a read with unprivileged `HPROT`, as done by `MemU_unpriv[]` in [ARM-ARM] B2.3.4.
+    pub(super) fn request_unprivileged_read(
+        core: &mut CoreComponent,
+        addr: Word,
+        size: Size,
+        cb: ReadDataCallback,
+    ) {
+        let prot = Self::request_prot(core, addr, false);
+        let this = Self::component_to_member_mut(core);
+        let posted = this
+            .data_bus_driver
+            .try_read_data_with_prot(addr.into(), size, prot, cb);
+        debug_assert!(
+            posted,
+            "Core attempted to schedule AHB transfer to {addr} when not available"
+        );
+    }
+
+    /// Requests a write with unprivileged `HPROT`, as done by `MemU_unpriv[]` in [ARM-ARM] B2.3.4.
+    pub(super) fn request_unprivileged_write(
         core: &mut CoreComponent,
         addr: Word,
         size: Size,
         cb: ReadDataCallback,
-        data: DataBus,
     ) {
+        let prot = Self::request_prot(core, addr, false);
         let this = Self::component_to_member_mut(core);
         let posted = this
             .data_bus_driver
-            .try_write_latched_data(addr.into(), size, data, cb);
+            .try_write_data_with_prot(addr.into(), size, prot, cb);
         debug_assert!(
             posted,
             "Core attempted to schedule AHB transfer to {addr} when not available"
         );
     }
 
+    pub(super) fn request_write_multiple(
+        core: &mut CoreComponent,
+        addr: Word,
+        size: Size,
+        cb: ReadDataCallback,
+        data: DataBus,
+    ) {
+        let prot = Self::request_prot(core, addr, RegisterBank::current_mode_is_privileged(core));
+        let this = Self::component_to_member_mut(core);
+        let posted = this.data_bus_driver.try_write_latched_data_with_prot(
+            addr.into(),
+            size,
+            data,
+            prot,
+            cb,
+        );
+        debug_assert!(
+            posted,
+            "Core attempted to schedule AHB transfer to {addr} when not available"
+        );
+    }
+
+    /// `HPROT` of a data transfer: the privilege of the access and the memory attributes
+    /// of the MPU region.
+    fn request_prot(core: &CoreComponent, addr: Word, is_privileged: bool) -> Protection {
+        let prot = Self::DEFAULT_PROT.with_privileged(is_privileged);
+        core.mpu_copy.apply_memory_attributes(addr.into(), prot)
+    }
+
+    /// [ARM-ARM] B3.5.3 `ValidateAddress()`: checks an access of an instruction against the MPU
+    /// before it is requested, so that a denied access never reaches the bus.
+    pub(super) fn validate_access(
+        core: &CoreComponent,
+        addr: Word,
+        dir: Direction,
+        unprivileged: bool,
+    ) -> Result<(), MPUError> {
+        let is_privileged = !unprivileged && RegisterBank::current_mode_is_privileged(core);
+        let prot = Self::DEFAULT_PROT.with_privileged(is_privileged);
+        core.mpu_copy.validate(addr.into(), dir, prot)
+    }
+
     /// Sets callback to run when read data arrives on data bus in current cycle.
     /// XXX: why would we knew that that data will arrive?
     #[allow(dead_code)] // Leave the impl for now.
@@ -435,32 +616,80 @@ impl LSU {
 // Helper functions
 // ============================================================================
 
+impl DecodeFn {
+    pub(super) fn decode(self, data: DataBus) -> Word {
+        match self {
+            Self::UnwrapWord => DataBus::unwrap_word(data),
+            Self::ZeroExtend => DataBus::zero_extend_into_word(data),
+            Self::SignExtend => DataBus::sign_extend_into_word(data),
+        }
+    }
+}
+
 impl ReadDataCallback {
     pub(super) fn call(
         &self,
         core: &mut CoreComponent,
-        #[cfg(feature = "cycle-debug-logger")] ctx: &mut Context,
+        #[cfg_attr(not(feature = "cycle-debug-logger"), allow(unused))] ctx: &mut Context,
         data: DataBus,
     ) {
-        match self {
-            ReadDataCallback::WithDecodeFn(f, decode) => f(core, *decode, data),
-            ReadDataCallback::WithRegisterAndDecodeFn(f, reg, decode) => f(
-                core,
-                #[cfg(feature = "cycle-debug-logger")]
-                ctx,
-                *reg,
-                *decode,
-                data,
-            ),
+        match *self {
+            ReadDataCallback::WithDecodeFn(f, decode) => match f {
+                ReadDataFn::LoadPc => Execute::on_pc_read(core, ctx, decode, data),
+                ReadDataFn::TableBranchOffset => {
+                    Execute::on_table_branch_offset_read(core, decode, data);
+                }
+                ReadDataFn::UnstackPc => {
+                    InterruptEntryAndExitHandler::on_pc_unstacked(core, decode, data);
+                }
+                ReadDataFn::UnstackXpsr => {
+                    InterruptEntryAndExitHandler::on_xpsr_unstacked(core, decode, data);
+                }
+            },
+            ReadDataCallback::WithRegisterAndDecodeFn(f, reg, decode) => match f {
+                ReadRegisterFn::Load { last: true } => {
+                    Execute::on_register_read::<true>(core, ctx, reg, decode, data);
+                }
+                ReadRegisterFn::Load { last: false } => {
+                    Execute::on_register_read::<false>(core, ctx, reg, decode, data);
+                }
+                ReadRegisterFn::Unstack { last } => {
+                    InterruptEntryAndExitHandler::on_register_unstacked(
+                        core, reg, decode, data, last,
+                    );
+                }
+            },
             _ => unimplemented!(),
         }
     }
 }
 
-impl AddrAdvancedCallback {
-    pub(super) fn call(&self, core: &mut CoreComponent, ctx: &mut Context) {
+impl GetWriteDataFn {
+    fn call(self, core: &mut CoreComponent, reg: RegisterID, size: Size) -> DataBus {
         match self {
-            Self::WritebackCallback { cb, reg, data } => cb(core, ctx, *reg, *data),
+            Self::Register => DataBus::clip_word(RegisterBank::get_register(core, reg), size),
+            Self::Latched => unreachable!("The data of this write was provided with the request"),
+        }
+    }
+}
+
+impl WriteDoneFn {
+    fn call(self, core: &mut CoreComponent, _reg: RegisterID) {
+        match self {
+            Self::Nothing => {}
+            // TODO: what about writebacks forwarding to AGU?
+            Self::FinishInstruction => Execute::finish_instruction_in_tock(core, None),
+            Self::FinishStacking => InterruptEntryAndExitHandler::on_stacking_finished(core),
+        }
+    }
+}
+
+impl AddrAdvancedCallback {
+    pub(super) fn call(&self, core: &mut CoreComponent, _ctx: &mut Context) {
+        match *self {
+            Self::WritebackCallback { reg, data } => {
+                Execute::on_writeback_address_advanced(core, reg, data);
+            }
             Self::NoCallback => (),
         }
     }
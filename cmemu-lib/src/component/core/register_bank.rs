@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 #[cfg(debug_assertions)]
 use std::cell::Cell;
 use std::fmt;
@@ -23,7 +24,7 @@ use crate::proxy::CycleDebugLoggerProxy;
 use crate::utils::IfExpr;
 use crate::{Bitstring, bitfield, bitstring_concat, bitstring_extract, bitstring_substitute};
 
-#[derive(Subcomponent, TickComponent, DisableableComponent)]
+#[derive(Clone, Subcomponent, TickComponent, DisableableComponent, Serialize, Deserialize)]
 pub(super) struct RegisterBank<SC>
 where
     SC: Subcomponent<Member = Self>,
@@ -192,7 +193,9 @@ where
         pc: Word,
     ) {
         let this = SC::component_to_member(component);
-        let sp = match this.look_up_sp() {
+        // Don't use `look_up_sp()`, since during exception return to the Thread mode with PSP,
+        // SPSEL is updated before unstacking restores IPSR.
+        let sp = match this.control.stack_pointer_selector() {
             StackPointer::Main => this.core_registers.sp_main,
             StackPointer::Process => this.core_registers.sp_process,
         };
@@ -434,7 +437,7 @@ where
 // ============================================================================
 
 /// B1.4.7 Register-related definitions for pseudocode/ `RName`
-#[derive(Clone, Copy, Eq, PartialEq)]
+#[derive(Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
 enum RegisterName {
     R(u8),
     SP(StackPointer),
@@ -449,6 +452,7 @@ enum RegisterName {
 /// [ARM-ARM] A2.3.1 ARM Core registers
 ///
 /// PC register is stored in `RegisterBank::pc`
+#[derive(Clone, Serialize, Deserialize)]
 pub struct CoreRegisters {
     regs: [Word; 13],
     sp_main: Word,
@@ -473,7 +477,7 @@ impl CoreRegisters {
 // ============================================================================
 
 /// Core general purpose register.
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct RegisterID(u8);
 
 impl RegisterID {
@@ -537,7 +541,7 @@ impl Display for RegisterID {
 // but passed to CDL (so the type is pub(crate)).
 bitfield! {
 /// [ARM-ARM] B1.4.2
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub(crate) struct XPSR[32]{
     N[31:31]: 1 bits,
     Z[30:30]: 1 bits,
@@ -797,7 +801,7 @@ impl Default for XPSR {
 // Used inside core and nvic, so that's the reason for pub(crate).
 /// [ARM-ARM] B1.4.3
 /// [TI-TRM] 2.5.2.18
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub(crate) struct PriorityMaskRegister(Bitstring![1]);
 
 impl PriorityMaskRegister {
@@ -830,7 +834,7 @@ impl From<PriorityMaskRegister> for Word {
 // Used inside core and nvic, so that's the reason for pub(crate).
 /// [ARM-ARM] B1.4.3
 /// [TI-TRM] 2.5.2.19
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub(crate) struct FaultMaskRegister(Bitstring![1]);
 
 impl FaultMaskRegister {
@@ -867,7 +871,7 @@ impl From<FaultMaskRegister> for Word {
 // for the wrapped type.
 /// [ARM-ARM] B1.4.3
 /// [TI-TRM] 2.5.2.20
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub(crate) struct BasePriorityMaskRegister(Bitstring![3]);
 
 impl BasePriorityMaskRegister {
@@ -908,7 +912,7 @@ impl From<BasePriorityMaskRegister> for Word {
 // but passed to CDL (so the type is pub(crate)).
 bitfield! {
 /// [ARM-ARM] B1.4.4
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub(crate) struct ControlRegister[3] {
         /// Execution privilege in Thread mode: HIGH=unpriv, Handler mode is always priv.
         nPRIV[0:0]: 1 bits,
@@ -1083,7 +1087,7 @@ impl From<ItState> for Word {
 ///
 /// The `RegisterBitmap` is compatible with `register_list`
 /// used in `push` and `pop` (and some other) encodings in [ARM-ARM] A7.7.x.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub(crate) struct RegisterBitmap(u16);
 
 impl RegisterBitmap {
@@ -1174,7 +1178,7 @@ impl Display for RegisterBitmap {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub(crate) struct RegisterListIterator(Bitstring![16], u8);
 
 impl IntoIterator for RegisterBitmap {
@@ -0,0 +1,1269 @@
+pub const CRYPTO_ROUTE_INJECTION: Range<Address> = CRYPTO::ADDR_SPACE;
+
+use crate::bridge_ports;
+use crate::build_data::{ClockTreeNodes, EnergyEntity};
+use crate::common::new_ahb::databus::DataBus;
+use crate::common::new_ahb::master_driver::MasterDriver;
+use crate::common::new_ahb::master_driver::stateless_helpers::SimplerHandler as MasterDriverSimplerHandler;
+#[proxy_use]
+use crate::common::new_ahb::ports::{AHBMasterPortInput, AHBPortConfig, AHBSlavePortInput};
+use crate::common::new_ahb::ports::{AHBMasterPortProxiedInput, AHBSlavePortProxiedInput};
+#[proxy_use]
+use crate::common::new_ahb::signals::{MasterToSlaveWires, SlaveToMasterWires};
+use crate::common::new_ahb::signals::{Protection, Size, TransferMeta};
+use crate::common::new_ahb::slave_driver::WriteMode;
+use crate::common::new_ahb::slave_driver::faking_slave_driver::{
+    AlignedFakingHandler, FakingIface, WaitstatesOrErr,
+};
+#[proxy_use(proxy_only)]
+use crate::component::crypto::SysbusMPort;
+use crate::component::event_fabric::EventFabricEvent;
+#[proxy_use]
+use crate::engine::Context;
+use crate::engine::{
+    DisableableComponent, MainComponent, SkippableClockTreeNode, TickComponent, TickComponentExtra,
+};
+use crate::make_port_struct;
+use crate::proxy::{CryptoProxy, EventFabricProxy};
+use aes::Aes128;
+use aes::cipher::{BlockDecrypt, BlockEncrypt, KeyInit};
+use cc2650_constants::CRYPTO;
+use cmemu_common::Address;
+use cmemu_proc_macros::{component_impl, handler, proxy_use};
+use log::{debug, trace, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::ops::Range;
+
+// [TI-TRM] 10 Cryptography
+// The CRYPTO peripheral is an AES-128 engine with a key store of eight 128-bit areas and
+// a DMA controller of its own, which is a master of the system bus. Channel 0 reads the keys
+// or the input data from the memory and channel 1 writes the results back.
+// The keys are loaded to the key store by DMA (`ALGSEL.KEY_STORE`) and copied to the engine
+// with `KEYREADAREA`. Then the engine works on the data selected with `ALGSEL.AES` in the mode
+// of `AESCTL`: ECB, CBC, CTR, CBC-MAC and CCM. The operation takes `AESDATALEN` bytes of data,
+// preceded by `AESAUTHLEN` bytes of the additional authenticated data in CCM.
+// The engine takes `CYCLES_PER_BLOCK` cycles for every 16-byte block, and one more block to
+// produce a tag, while the DMA transfers are timed by the bus.
+// Not implemented: the GCM and hash modes, the transfers of the data through the
+// `AESDATAIN`/`AESDATAOUT` registers, the DMA of the tag (`ALGSEL.TAG`), level interrupts
+// (the events are pulses), the priorities of the channels (the output is served first)
+// and bursts on the bus.
+
+const BLOCK_SIZE: usize = 16;
+const KEY_AREAS: usize = 8;
+
+/// Cycles of processing of a single block by the AES engine.
+/// An estimate, as the documentation states only that the engine is fast enough for the radio.
+const CYCLES_PER_BLOCK: u32 = 16;
+/// Cycles of copying a key from the key store to the engine, while `KEYREADAREA.BUSY` is set.
+const KEY_LOAD_CYCLES: u32 = 4;
+
+/// The 4-word register arrays.
+const AESKEY2_REGISTERS: Range<Address> = register_array(CRYPTO::AESKEY2::ADDR);
+const AESKEY3_REGISTERS: Range<Address> = register_array(CRYPTO::AESKEY3::ADDR);
+const AESIV_REGISTERS: Range<Address> = register_array(CRYPTO::AESIV::ADDR);
+const AESDATA_REGISTERS: Range<Address> = register_array(CRYPTO::AESDATAIN0::ADDR);
+const AESTAGOUT_REGISTERS: Range<Address> = register_array(CRYPTO::AESTAGOUT::ADDR);
+
+const fn register_array(base: Address) -> Range<Address> {
+    base..base.offset(4 * 4)
+}
+
+/// The word of a 4-word register array at `addr`.
+fn array_index(array: &Range<Address>, addr: Address) -> usize {
+    (addr.offset_from(array.start) / 4) as usize
+}
+
+type Block = [u8; BLOCK_SIZE];
+
+fn encrypt_block(key: &Block, block: &Block) -> Block {
+    let mut data = (*block).into();
+    Aes128::new(key.into()).encrypt_block(&mut data);
+    data.into()
+}
+
+fn decrypt_block(key: &Block, block: &Block) -> Block {
+    let mut data = (*block).into();
+    Aes128::new(key.into()).decrypt_block(&mut data);
+    data.into()
+}
+
+fn xor_block(a: &Block, b: &Block) -> Block {
+    std::array::from_fn(|i| a[i] ^ b[i])
+}
+
+/// The words of the registers, in the order of the bytes in the memory.
+fn block_from_words(words: [u32; 4]) -> Block {
+    std::array::from_fn(|i| words[i / 4].to_le_bytes()[i % 4])
+}
+
+fn words_from_block(block: &Block) -> [u32; 4] {
+    std::array::from_fn(|i| {
+        u32::from_le_bytes(
+            block[4 * i..4 * i + 4]
+                .try_into()
+                .expect("a word of a block"),
+        )
+    })
+}
+
+/// Increments the big-endian counter in the last `width` bytes of the `block`.
+fn increment_counter(block: &mut Block, width: usize) {
+    for byte in block.iter_mut().rev().take(width) {
+        *byte = byte.wrapping_add(1);
+        if *byte != 0 {
+            break;
+        }
+    }
+}
+
+/// The mode of an AES operation, as selected by `AESCTL`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+enum AesMode {
+    Ecb,
+    Cbc,
+    /// The width of the counter in bytes.
+    Ctr(usize),
+    CbcMac,
+    /// The sizes of the tag (`M`) and of the length field (`L`) in bytes.
+    Ccm {
+        tag_size: u8,
+        length_size: u8,
+    },
+}
+
+impl AesMode {
+    /// [TI-TRM] 10.9.1.19 AESCTL Register
+    fn from_control(ctl: u32) -> Self {
+        use CRYPTO::AESCTL as R;
+        if ctl & R::CCM::BIT_MASK != 0 {
+            let m = (ctl & R::CCM_M::BIT_MASK) >> R::CCM_M::BIT_RANGE.start();
+            let l = (ctl & R::CCM_L::BIT_MASK) >> R::CCM_L::BIT_RANGE.start();
+            #[allow(clippy::cast_possible_truncation, reason = "3-bit fields")]
+            Self::Ccm {
+                tag_size: 2 * (m as u8 + 1),
+                length_size: l as u8 + 1,
+            }
+        } else if ctl & R::CBC_MAC::BIT_MASK != 0 {
+            Self::CbcMac
+        } else if ctl & R::CTR::BIT_MASK != 0 {
+            let width = (ctl & R::CTR_WIDTH::BIT_MASK) >> R::CTR_WIDTH::BIT_RANGE.start();
+            Self::Ctr(4 * (width as usize + 1))
+        } else if ctl & R::CBC::BIT_MASK != 0 {
+            Self::Cbc
+        } else {
+            Self::Ecb
+        }
+    }
+
+    fn has_tag(self) -> bool {
+        matches!(self, Self::CbcMac | Self::Ccm { .. })
+    }
+}
+
+/// An operation of the AES engine, started by the first input after configuring the context.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct AesOperation {
+    mode: AesMode,
+    encrypt: bool,
+    key: Block,
+    /// The IV of CBC, or the counter block of CTR and CCM.
+    iv: Block,
+    /// The state of the CBC-MAC and its incomplete block.
+    mac: Block,
+    mac_block: Vec<u8>,
+    /// The encrypted counter block 0 of CCM, hiding the tag.
+    tag_mask: Block,
+    auth_left: u64,
+    data_left: u64,
+}
+
+impl AesOperation {
+    // The lengths are transferred with the most significant byte first in the formatted blocks.
+    #[allow(clippy::big_endian_bytes)]
+    fn new(
+        mode: AesMode,
+        encrypt: bool,
+        key: Block,
+        iv: Block,
+        auth_len: u32,
+        data_len: u64,
+    ) -> Self {
+        let mut op = Self {
+            mode,
+            encrypt,
+            key,
+            iv,
+            mac: [0; BLOCK_SIZE],
+            mac_block: Vec::with_capacity(BLOCK_SIZE),
+            tag_mask: [0; BLOCK_SIZE],
+            auth_left: 0,
+            data_left: data_len,
+        };
+        if let AesMode::Ccm {
+            tag_size,
+            length_size,
+        } = mode
+        {
+            // [NIST 800-38C] A.2 Formatting of the Input Data: the IV holds the flags of the
+            // counter blocks (`L - 1`) and the nonce, so B0 differs only in the flags and the length.
+            op.auth_left = u64::from(auth_len);
+            let mut b0 = iv;
+            b0[0] = (u8::from(auth_len > 0) << 6)
+                | ((tag_size.saturating_sub(2) / 2) << 3)
+                | (length_size - 1);
+            let length_size = usize::from(length_size);
+            let length = data_len.to_be_bytes();
+            b0[BLOCK_SIZE - length_size..]
+                .copy_from_slice(&length[length.len().saturating_sub(length_size)..]);
+            op.absorb_mac(&b0);
+            match u16::try_from(auth_len) {
+                Ok(0) => {}
+                Ok(len) if len < 0xff00 => op.absorb_mac(&len.to_be_bytes()),
+                _ => {
+                    op.absorb_mac(&[0xff, 0xfe]);
+                    op.absorb_mac(&auth_len.to_be_bytes());
+                }
+            }
+            op.iv[BLOCK_SIZE - length_size..].fill(0);
+            op.tag_mask = encrypt_block(&key, &op.iv);
+            increment_counter(&mut op.iv, length_size);
+        } else if auth_len > 0 {
+            warn!("CRYPTO: AESAUTHLEN is ignored in {mode:?}");
+        }
+        op
+    }
+
+    fn is_done(&self) -> bool {
+        self.auth_left == 0 && self.data_left == 0
+    }
+
+    /// Bytes of the next block to process, the last block of a phase may be shorter.
+    fn next_block_size(&self) -> usize {
+        let left = if self.auth_left > 0 {
+            self.auth_left
+        } else {
+            self.data_left
+        };
+        usize::try_from(left).map_or(BLOCK_SIZE, |left| left.min(BLOCK_SIZE))
+    }
+
+    fn absorb_mac(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.mac_block.push(byte);
+            if self.mac_block.len() == BLOCK_SIZE {
+                self.flush_mac();
+            }
+        }
+    }
+
+    /// Processes the incomplete block of the CBC-MAC padded with zeroes.
+    fn flush_mac(&mut self) {
+        if self.mac_block.is_empty() {
+            return;
+        }
+        let mut block = [0; BLOCK_SIZE];
+        block[..self.mac_block.len()].copy_from_slice(&self.mac_block);
+        self.mac = encrypt_block(&self.key, &xor_block(&self.mac, &block));
+        self.mac_block.clear();
+    }
+
+    fn next_key_stream(&mut self, width: usize) -> Block {
+        let stream = encrypt_block(&self.key, &self.iv);
+        increment_counter(&mut self.iv, width);
+        stream
+    }
+
+    /// Processes a block of the input and appends the produced data to the `output`.
+    fn process(&mut self, input: &[u8], output: &mut VecDeque<u8>) {
+        let mut block = [0; BLOCK_SIZE];
+        block[..input.len()].copy_from_slice(input);
+        if self.auth_left > 0 {
+            self.auth_left -= input.len() as u64;
+            self.absorb_mac(input);
+            if self.auth_left == 0 {
+                self.flush_mac();
+            }
+            return;
+        }
+        self.data_left -= input.len() as u64;
+
+        let result = match self.mode {
+            AesMode::Ecb if self.encrypt => encrypt_block(&self.key, &block),
+            AesMode::Ecb => decrypt_block(&self.key, &block),
+            AesMode::Cbc if self.encrypt => {
+                self.iv = encrypt_block(&self.key, &xor_block(&block, &self.iv));
+                self.iv
+            }
+            AesMode::Cbc => {
+                let plain = xor_block(&decrypt_block(&self.key, &block), &self.iv);
+                self.iv = block;
+                plain
+            }
+            AesMode::Ctr(width) => xor_block(&block, &self.next_key_stream(width)),
+            AesMode::CbcMac => {
+                self.absorb_mac(input);
+                return;
+            }
+            AesMode::Ccm { length_size, .. } => {
+                let stream = self.next_key_stream(usize::from(length_size));
+                let mut result = xor_block(&block, &stream);
+                // The MAC is computed over the plaintext, so the input when encrypting.
+                let plain = if self.encrypt {
+                    input
+                } else {
+                    &result[..input.len()]
+                };
+                self.absorb_mac(plain);
+                result[input.len()..].fill(0);
+                result
+            }
+        };
+        output.extend(&result[..input.len()]);
+        if self.data_left == 0 && self.mode.has_tag() {
+            self.flush_mac();
+        }
+    }
+
+    /// The authentication tag, as the CBC-MAC encrypted in CCM.
+    fn tag(&self) -> Block {
+        match self.mode {
+            AesMode::Ccm { .. } => xor_block(&self.mac, &self.tag_mask),
+            _ => self.mac,
+        }
+    }
+}
+
+/// A channel of the DMA controller of CRYPTO.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct DmaChannel {
+    ctl: u32,
+    ext_addr: u32,
+    /// Bytes left to transfer, as read from `DMACHnLEN`.
+    len: u32,
+}
+
+impl DmaChannel {
+    fn is_active(&self) -> bool {
+        self.len > 0
+    }
+
+    /// The size of the next transfer, which is a word only if the address is aligned.
+    fn transfer_size(&self, room: usize) -> Size {
+        if self.ext_addr.is_multiple_of(4) && self.len >= 4 && room >= 4 {
+            Size::Word
+        } else {
+            Size::Byte
+        }
+    }
+
+    fn advance(&mut self, bytes: u32) {
+        self.ext_addr = self.ext_addr.wrapping_add(bytes);
+        self.len -= bytes;
+    }
+}
+
+/// The bus transfer of a DMA channel in progress.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+enum Transfer {
+    Input(Size),
+    Output(Size),
+}
+
+type BusDriver = FakingIface<DriverSC, CryptoComponent>;
+type SysbusDriver = MasterDriver<SysbusDriverSC, SysbusMPort>;
+
+#[derive(
+    Clone,
+    MainComponent,
+    SkippableClockTreeNode,
+    TickComponent,
+    TickComponentExtra,
+    Serialize,
+    Deserialize,
+)]
+#[skippable_if_disableable]
+pub(crate) struct CryptoComponent {
+    #[subcomponent(DriverSC)]
+    driver: BusDriver,
+
+    #[subcomponent(pub(crate) SysbusDriverSC)]
+    sysbus_driver: SysbusDriver,
+
+    channels: [DmaChannel; 2],
+    dmabuscfg: u32,
+    dmaporterr: u32,
+    dmaprotctl: u32,
+    algsel: u32,
+
+    key_store: [Block; KEY_AREAS],
+    keywritearea: u32,
+    keywrittenarea: u32,
+    /// Areas selected by `KEYWRITEAREA` and not written yet by the DMA.
+    key_areas_left: u32,
+    keyreadarea: u32,
+    /// Cycles left of copying a key to the engine.
+    key_load_left: u32,
+
+    aesctl: u32,
+    key: Option<Block>,
+    iv: [u32; 4],
+    datalen: [u32; 2],
+    authlen: u32,
+    data_in: [u32; 4],
+    data_out: [u32; 4],
+    tag: [u32; 4],
+
+    irqtype: u32,
+    irqen: u32,
+    irqstat: u32,
+
+    operation: Option<AesOperation>,
+    /// Bytes read by channel 0, but not processed yet.
+    input: Vec<u8>,
+    /// Bytes produced by the engine, waiting for channel 1.
+    output: VecDeque<u8>,
+    /// Cycles left of processing the last block by the engine.
+    busy_left: u32,
+    transfer: Option<Transfer>,
+}
+
+#[component_impl(crypto)]
+impl CryptoComponent {
+    pub(crate) fn new() -> Self {
+        Self {
+            driver: Default::default(),
+            sysbus_driver: SysbusDriver::new(),
+
+            channels: Default::default(),
+            dmabuscfg: CRYPTO::DMABUSCFG::RESET_VALUE,
+            dmaporterr: CRYPTO::DMAPORTERR::RESET_VALUE,
+            dmaprotctl: CRYPTO::DMAPROTCTL::RESET_VALUE,
+            algsel: CRYPTO::ALGSEL::RESET_VALUE,
+
+            key_store: [[0; BLOCK_SIZE]; KEY_AREAS],
+            keywritearea: CRYPTO::KEYWRITEAREA::RESET_VALUE,
+            keywrittenarea: CRYPTO::KEYWRITTENAREA::RESET_VALUE,
+            key_areas_left: 0,
+            keyreadarea: CRYPTO::KEYREADAREA::RESET_VALUE,
+            key_load_left: 0,
+
+            aesctl: CRYPTO::AESCTL::RESET_VALUE,
+            key: None,
+            iv: [0; 4],
+            datalen: [0; 2],
+            authlen: 0,
+            data_in: [0; 4],
+            data_out: [0; 4],
+            tag: [0; 4],
+
+            irqtype: CRYPTO::IRQTYPE::RESET_VALUE,
+            irqen: CRYPTO::IRQEN::RESET_VALUE,
+            irqstat: CRYPTO::IRQSTAT::RESET_VALUE,
+
+            operation: None,
+            input: Vec::with_capacity(BLOCK_SIZE),
+            output: VecDeque::with_capacity(2 * BLOCK_SIZE),
+            busy_left: 0,
+            transfer: None,
+        }
+    }
+
+    pub(crate) fn tick(&mut self, ctx: &mut Context) {
+        BusDriver::run_driver(self, ctx);
+        SysbusDriver::run_driver(self, ctx);
+
+        // Both the engine and the DMA are stopped while the clock is gated.
+        if !Self::is_clock_enabled(ctx) {
+            return;
+        }
+        if self.key_load_left > 0 {
+            self.key_load_left -= 1;
+        }
+        self.run_engine(ctx);
+        if self.transfer.is_none() && self.sysbus_driver.is_free() {
+            self.issue_transfer();
+        }
+    }
+
+    pub(crate) fn tock(&mut self, ctx: &mut Context) {
+        BusDriver::tock(self, ctx);
+        SysbusDriver::tock(self, ctx);
+    }
+
+    #[handler]
+    pub(crate) fn on_new_ahb_slave_input(
+        &mut self,
+        ctx: &mut Context,
+        msg: MasterToSlaveWires<<CryptoComponent as AHBPortConfig>::Data>,
+    ) {
+        <Self as AHBSlavePortInput>::on_ahb_input(self, ctx, msg);
+    }
+
+    #[handler]
+    pub(crate) fn on_new_ahb_master_input(
+        &mut self,
+        ctx: &mut Context,
+        msg: SlaveToMasterWires<<SysbusMPort as AHBPortConfig>::Data>,
+    ) {
+        <SysbusMPort as AHBMasterPortInput>::on_ahb_input(self, ctx, msg);
+    }
+
+    fn is_clock_enabled(ctx: &Context) -> bool {
+        ctx.get_energy_state_of(EnergyEntity::ClockTree(ClockTreeNodes::CryptoGate))
+            .is_active()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.transfer.is_none()
+            && self.busy_left == 0
+            && self.key_load_left == 0
+            && !self.channels.iter().any(DmaChannel::is_active)
+            && self.input.is_empty()
+            && self.output.is_empty()
+            && self.operation.is_none()
+    }
+
+    /// Takes the next block of the input, when the output of the previous one is written.
+    /// The output is held back until the engine has processed the block.
+    fn run_engine(&mut self, ctx: &mut Context) {
+        if self.busy_left > 0 {
+            self.busy_left -= 1;
+            return;
+        }
+        if self.operation.as_ref().is_some_and(AesOperation::is_done) {
+            // The result is available after the output is written to the memory.
+            if self.output.is_empty() && !matches!(self.transfer, Some(Transfer::Output(_))) {
+                self.finish_operation(ctx);
+            }
+            return;
+        }
+        if self.input.is_empty() || !self.output.is_empty() {
+            return;
+        }
+
+        if self.algsel & CRYPTO::ALGSEL::KEY_STORE::BIT_MASK != 0 {
+            self.write_key_store();
+        } else if self.algsel & CRYPTO::ALGSEL::AES::BIT_MASK != 0 {
+            self.process_aes_input();
+        } else {
+            warn!(
+                "CRYPTO: dropping {} bytes of DMA input with no algorithm selected",
+                self.input.len()
+            );
+            self.input.clear();
+        }
+    }
+
+    /// [TI-TRM] 10.9.1.12 KEYWRITEAREA Register: the keys are written to the selected areas
+    /// in their order.
+    fn write_key_store(&mut self) {
+        if self.input.len() < BLOCK_SIZE {
+            return;
+        }
+        let block: Block = self.input[..BLOCK_SIZE].try_into().expect("a key block");
+        self.input.drain(..BLOCK_SIZE);
+        if self.key_areas_left == 0 {
+            warn!("CRYPTO: a key written beyond the selected key store areas");
+            self.irqstat |= CRYPTO::IRQSTAT::KEY_ST_WR_ERR::BIT_MASK;
+            return;
+        }
+        let area = self.key_areas_left.trailing_zeros();
+        debug!("CRYPTO: key written to the key store area {area}");
+        self.key_store[area as usize] = block;
+        self.key_areas_left &= !(1 << area);
+        self.keywrittenarea |= 1 << area;
+    }
+
+    fn process_aes_input(&mut self) {
+        if self.operation.is_none() {
+            let Some(key) = self.key else {
+                warn!("CRYPTO: AES operation started without a key loaded from the key store");
+                self.irqstat |= CRYPTO::IRQSTAT::KEY_ST_RD_ERR::BIT_MASK;
+                self.input.clear();
+                return;
+            };
+            let mode = AesMode::from_control(self.aesctl);
+            let encrypt = self.aesctl & CRYPTO::AESCTL::DIR::BIT_MASK != 0;
+            trace!(
+                "CRYPTO: AES {mode:?} (encrypt: {encrypt}) of {} bytes after {} bytes of AAD",
+                self.data_length(),
+                self.authlen
+            );
+            let op = AesOperation::new(
+                mode,
+                encrypt,
+                key,
+                block_from_words(self.iv),
+                self.authlen,
+                self.data_length(),
+            );
+            if op.is_done() {
+                warn!("CRYPTO: dropping DMA input of an AES operation of no data");
+                self.input.clear();
+                return;
+            }
+            self.aesctl &= !CRYPTO::AESCTL::CONTEXT_RDY::BIT_MASK;
+            self.operation = Some(op);
+        }
+        let op = self.operation.as_mut().expect("AES operation");
+        let size = op.next_block_size();
+        if self.input.len() < size {
+            return;
+        }
+        let block: Vec<u8> = self.input.drain(..size).collect();
+        op.process(&block, &mut self.output);
+        if let Some(last) = self.output.len().checked_sub(BLOCK_SIZE) {
+            let mut out = [0; BLOCK_SIZE];
+            for (byte, &value) in out.iter_mut().zip(self.output.range(last..)) {
+                *byte = value;
+            }
+            self.data_out = words_from_block(&out);
+        }
+        self.busy_left = CYCLES_PER_BLOCK;
+        if op.is_done() && op.mode.has_tag() {
+            self.busy_left += CYCLES_PER_BLOCK;
+        }
+    }
+
+    /// [TI-TRM] 10.9.1.19 AESCTL Register: the context is saved if requested, and the result is
+    /// available.
+    fn finish_operation(&mut self, ctx: &mut Context) {
+        let op = self.operation.take().expect("AES operation to finish");
+        trace!("CRYPTO: AES {:?} done", op.mode);
+        if op.mode.has_tag() {
+            self.tag = words_from_block(&op.tag());
+        }
+        if self.aesctl & CRYPTO::AESCTL::SAVE_CONTEXT::BIT_MASK != 0 {
+            if matches!(op.mode, AesMode::Cbc | AesMode::Ctr(_)) {
+                self.iv = words_from_block(&op.iv);
+            }
+            self.aesctl |= CRYPTO::AESCTL::SAVED_CONTEXT_RDY::BIT_MASK;
+        } else {
+            self.aesctl |= CRYPTO::AESCTL::CONTEXT_RDY::BIT_MASK;
+        }
+        self.raise(ctx, CRYPTO::IRQSTAT::RESULT_AVAIL::BIT_MASK);
+    }
+
+    /// Channel 1 is served first, so that the engine can go on.
+    fn issue_transfer(&mut self) {
+        let [input, output] = &self.channels;
+        let room = BLOCK_SIZE - self.input.len();
+        let prot = if self.algsel & CRYPTO::ALGSEL::KEY_STORE::BIT_MASK != 0 {
+            Protection::new_data()
+                .with_privileged(self.dmaprotctl & CRYPTO::DMAPROTCTL::EN::BIT_MASK != 0)
+        } else {
+            Protection::new_data()
+        };
+        let driver = &mut self.sysbus_driver;
+        let issued = if output.is_active() && !self.output.is_empty() && self.busy_left == 0 {
+            let size = output.transfer_size(self.output.len());
+            let bytes: Vec<u8> = self.output.drain(..size.bytes()).collect();
+            let issued = driver.try_write_latched_data_with_prot(
+                Address::from(output.ext_addr),
+                size,
+                DataBus::from_slice(&bytes),
+                Protection::new_data(),
+                (),
+            );
+            issued.then_some(Transfer::Output(size))
+        } else if input.is_active() && room > 0 {
+            let size = input.transfer_size(room);
+            let issued =
+                driver.try_read_data_with_prot(Address::from(input.ext_addr), size, prot, ());
+            issued.then_some(Transfer::Input(size))
+        } else {
+            return;
+        };
+        debug_assert!(
+            issued.is_some(),
+            "CRYPTO transfer not accepted by a free driver"
+        );
+        self.transfer = issued;
+    }
+
+    fn transfer_done(&mut self, ctx: &mut Context, data: Option<DataBus>) {
+        let transfer = self.transfer.take().expect("CRYPTO transfer in progress");
+        match transfer {
+            Transfer::Input(size) => {
+                let data = data.expect("CRYPTO read without data");
+                data.map_into_slice(|bytes| self.input.extend_from_slice(bytes));
+                let channel = &mut self.channels[0];
+                channel.advance(size.bytes32());
+                if !channel.is_active() {
+                    self.input_done(ctx);
+                }
+            }
+            Transfer::Output(size) => self.channels[1].advance(size.bytes32()),
+        }
+    }
+
+    /// [TI-TRM] 10.9.1.39 IRQSTAT Register: `DMA_IN_DONE` is set after the last read of channel 0.
+    fn input_done(&mut self, ctx: &mut Context) {
+        trace!("CRYPTO: DMA input done");
+        if self.algsel & CRYPTO::ALGSEL::KEY_STORE::BIT_MASK != 0 {
+            // The key store takes the keys right away.
+            while self.input.len() >= BLOCK_SIZE {
+                self.write_key_store();
+            }
+            if !self.input.is_empty() || self.key_areas_left != 0 {
+                warn!("CRYPTO: the DMA transfer does not cover the selected key store areas");
+                self.irqstat |= CRYPTO::IRQSTAT::KEY_ST_WR_ERR::BIT_MASK;
+                self.input.clear();
+            }
+            self.raise(
+                ctx,
+                CRYPTO::IRQSTAT::DMA_IN_DONE::BIT_MASK | CRYPTO::IRQSTAT::RESULT_AVAIL::BIT_MASK,
+            );
+        } else {
+            self.raise(ctx, CRYPTO::IRQSTAT::DMA_IN_DONE::BIT_MASK);
+        }
+    }
+
+    /// [TI-TRM] 10.9.1.36 IRQEN Register: the enabled sources notify the event fabric.
+    fn raise(&mut self, ctx: &mut Context, sources: u32) {
+        let newly = sources & !self.irqstat;
+        self.irqstat |= sources;
+        self.notify(ctx, newly & self.irqen);
+    }
+
+    fn notify(&self, ctx: &mut Context, sources: u32) {
+        if sources & CRYPTO::IRQSTAT::RESULT_AVAIL::BIT_MASK != 0 {
+            EventFabricProxy.notify(ctx, EventFabricEvent::CRYPTO_RESULT_AVAIL_IRQ);
+        }
+        if sources & CRYPTO::IRQSTAT::DMA_IN_DONE::BIT_MASK != 0 {
+            EventFabricProxy.notify(ctx, EventFabricEvent::CRYPTO_DMA_DONE_IRQ);
+        }
+    }
+
+    /// [TI-TRM] 10.9.1.10 DMAPORTERR Register: the transfer and the channel are dropped.
+    fn bus_error(&mut self, ctx: &mut Context) {
+        let transfer = self.transfer.take();
+        warn!("CRYPTO: DMA bus error of {transfer:?}");
+        let channel = match transfer {
+            Some(Transfer::Output(_)) => 1,
+            _ => 0,
+        };
+        self.channels[channel].len = 0;
+        self.dmaporterr |= CRYPTO::DMAPORTERR::AHB_ERR::BIT_MASK;
+        self.raise(ctx, CRYPTO::IRQSTAT::DMA_BUS_ERR::BIT_MASK);
+    }
+
+    /// [TI-TRM] 10.9.1.15 KEYREADAREA Register: only written areas can be copied to the engine.
+    fn read_key_store(&mut self, area: u32) {
+        self.keyreadarea = area & CRYPTO::KEYREADAREA::RAM_AREA::BIT_MASK;
+        let area = self.keyreadarea as usize;
+        if area < KEY_AREAS && self.keywrittenarea & (1 << area) != 0 {
+            debug!("CRYPTO: key loaded from the key store area {area}");
+            self.key = Some(self.key_store[area]);
+            self.key_load_left = KEY_LOAD_CYCLES;
+            self.aesctl = (self.aesctl & !CRYPTO::AESCTL::KEY_SIZE::BIT_MASK)
+                | (1 << CRYPTO::AESCTL::KEY_SIZE::BIT_RANGE.start());
+        } else {
+            warn!("CRYPTO: reading the unwritten key store area {area}");
+            self.irqstat |= CRYPTO::IRQSTAT::KEY_ST_RD_ERR::BIT_MASK;
+        }
+    }
+
+    /// [TI-TRM] 10.9.1.19 AESCTL Register: writing `SAVED_CONTEXT_RDY` ignores the other bits.
+    fn write_aesctl(&mut self, data: u32) {
+        use CRYPTO::AESCTL as R;
+        if data & R::SAVED_CONTEXT_RDY::BIT_MASK != 0 {
+            self.release_saved_context();
+            return;
+        }
+        let status = R::CONTEXT_RDY::BIT_MASK
+            | R::SAVED_CONTEXT_RDY::BIT_MASK
+            | R::KEY_SIZE::BIT_MASK
+            | R::INPUT_RDY::BIT_MASK
+            | R::OUTPUT_RDY::BIT_MASK;
+        self.aesctl = (self.aesctl & status) | (data & !status);
+    }
+
+    fn release_saved_context(&mut self) {
+        use CRYPTO::AESCTL as R;
+        if self.aesctl & R::SAVED_CONTEXT_RDY::BIT_MASK != 0 {
+            self.aesctl &= !R::SAVED_CONTEXT_RDY::BIT_MASK;
+            self.aesctl |= R::CONTEXT_RDY::BIT_MASK;
+        }
+    }
+
+    /// [TI-TRM] 10.9.1.4 DMASTAT Register
+    fn dmastat(&self) -> u32 {
+        let mut status = 0;
+        if self.channels[0].is_active() {
+            status |= CRYPTO::DMASTAT::CH0_ACTIVE::BIT_MASK;
+        }
+        if self.channels[1].is_active() {
+            status |= CRYPTO::DMASTAT::CH1_ACTIVE::BIT_MASK;
+        }
+        if self.dmaporterr & CRYPTO::DMAPORTERR::AHB_ERR::BIT_MASK != 0 {
+            status |= CRYPTO::DMASTAT::PORT_ERR::BIT_MASK;
+        }
+        status
+    }
+
+    fn data_length(&self) -> u64 {
+        u64::from(self.datalen[0]) | (u64::from(self.datalen[1]) << 32)
+    }
+
+    fn keyreadarea(&self) -> u32 {
+        if self.key_load_left > 0 {
+            self.keyreadarea | CRYPTO::KEYREADAREA::BUSY::BIT_MASK
+        } else {
+            self.keyreadarea
+        }
+    }
+
+    /// [TI-TRM] 10.9.1.5 DMASWRESET Register: the DMA is reset, and the key store is emptied.
+    fn reset_dma(&mut self) {
+        if self.transfer.is_some() {
+            warn!("CRYPTO: DMA reset with a bus transfer in progress");
+        }
+        self.channels = Default::default();
+        self.keywrittenarea = 0;
+    }
+
+    /// [TI-TRM] 10.9.1.34 SWRESET Register: the engine is reset.
+    fn reset_engine(&mut self) {
+        self.operation = None;
+        self.input.clear();
+        self.output.clear();
+        self.busy_left = 0;
+        self.aesctl = CRYPTO::AESCTL::RESET_VALUE;
+        self.key = None;
+        self.algsel = CRYPTO::ALGSEL::RESET_VALUE;
+    }
+
+    fn start_channel(&mut self, channel: usize, len: u32) {
+        let ch = &mut self.channels[channel];
+        if ch.ctl & CRYPTO::DMACH0CTL::EN::BIT_MASK == 0 {
+            warn!("CRYPTO: DMA channel {channel} length written while disabled");
+            return;
+        }
+        trace!(
+            "CRYPTO: DMA channel {channel} of {len} bytes at {:#x}",
+            ch.ext_addr
+        );
+        ch.len = len;
+        if channel == 0 && self.algsel & CRYPTO::ALGSEL::KEY_STORE::BIT_MASK != 0 {
+            // The selected areas are overwritten.
+            self.key_areas_left = self.keywritearea;
+            self.keywrittenarea &= !self.keywritearea;
+        }
+    }
+
+    fn get_data_for_address(&self, ctx: &Context, addr: Address) -> u32 {
+        use CRYPTO as C;
+        match addr {
+            C::DMACH0CTL::ADDR => self.channels[0].ctl,
+            C::DMACH0EXTADDR::ADDR => self.channels[0].ext_addr,
+            C::DMACH0LEN::ADDR => self.channels[0].len,
+            C::DMACH1CTL::ADDR => self.channels[1].ctl,
+            C::DMACH1EXTADDR::ADDR => self.channels[1].ext_addr,
+            C::DMACH1LEN::ADDR => self.channels[1].len,
+            C::DMASTAT::ADDR => self.dmastat(),
+            C::DMABUSCFG::ADDR => self.dmabuscfg,
+            C::DMAPORTERR::ADDR => self.dmaporterr,
+            C::DMAHWVER::ADDR => C::DMAHWVER::RESET_VALUE,
+            C::KEYWRITEAREA::ADDR => self.keywritearea,
+            C::KEYWRITTENAREA::ADDR => self.keywrittenarea,
+            C::KEYSIZE::ADDR => C::KEYSIZE::RESET_VALUE,
+            C::KEYREADAREA::ADDR => self.keyreadarea(),
+            C::AESCTL::ADDR => self.aesctl,
+            C::AESDATALEN0::ADDR => self.datalen[0],
+            C::AESDATALEN1::ADDR => self.datalen[1],
+            C::AESAUTHLEN::ADDR => self.authlen,
+            a if a.is_in_range(&AESIV_REGISTERS) => self.iv[array_index(&AESIV_REGISTERS, a)],
+            a if a.is_in_range(&AESDATA_REGISTERS) => {
+                self.data_out[array_index(&AESDATA_REGISTERS, a)]
+            }
+            a if a.is_in_range(&AESTAGOUT_REGISTERS) => {
+                self.tag[array_index(&AESTAGOUT_REGISTERS, a)]
+            }
+            C::ALGSEL::ADDR => self.algsel,
+            C::DMAPROTCTL::ADDR => self.dmaprotctl,
+            C::IRQTYPE::ADDR => self.irqtype,
+            C::IRQEN::ADDR => self.irqen,
+            C::IRQSTAT::ADDR => self.irqstat,
+            C::HWVER::ADDR => C::HWVER::RESET_VALUE,
+            // Write-only registers
+            C::DMASWRESET::ADDR | C::SWRESET::ADDR | C::IRQCLR::ADDR | C::IRQSET::ADDR => 0,
+            a if a.is_in_range(&AESKEY2_REGISTERS) || a.is_in_range(&AESKEY3_REGISTERS) => 0,
+            _ => unimplemented!(
+                "Requested CRYPTO data read from address {:?}: {}",
+                addr,
+                ctx.display_named_address(addr)
+            ),
+        }
+    }
+
+    fn set_data_for_address(&mut self, ctx: &mut Context, addr: Address, data: u32) {
+        use CRYPTO as C;
+        match addr {
+            C::DMACH0CTL::ADDR => self.channels[0].ctl = data,
+            C::DMACH0EXTADDR::ADDR => self.channels[0].ext_addr = data,
+            C::DMACH0LEN::ADDR => self.start_channel(0, data),
+            C::DMACH1CTL::ADDR => self.channels[1].ctl = data,
+            C::DMACH1EXTADDR::ADDR => self.channels[1].ext_addr = data,
+            C::DMACH1LEN::ADDR => self.start_channel(1, data),
+            C::DMASWRESET::ADDR if data & C::DMASWRESET::RESET::BIT_MASK != 0 => self.reset_dma(),
+            C::DMABUSCFG::ADDR => self.dmabuscfg = data,
+            C::DMAPORTERR::ADDR => self.dmaporterr = data,
+            C::KEYWRITEAREA::ADDR => self.keywritearea = data & 0xff,
+            C::KEYWRITTENAREA::ADDR => self.keywrittenarea &= !data,
+            // Only 128-bit keys are supported, but the written areas are reset anyway.
+            C::KEYSIZE::ADDR => self.keywrittenarea = 0,
+            C::KEYREADAREA::ADDR => self.read_key_store(data),
+            C::AESCTL::ADDR => self.write_aesctl(data),
+            C::AESDATALEN0::ADDR => self.datalen[0] = data,
+            C::AESDATALEN1::ADDR => self.datalen[1] = data & C::AESDATALEN1::LEN_MSW::BIT_MASK,
+            C::AESAUTHLEN::ADDR => self.authlen = data,
+            a if a.is_in_range(&AESIV_REGISTERS) => {
+                self.iv[array_index(&AESIV_REGISTERS, a)] = data;
+            }
+            a if a.is_in_range(&AESDATA_REGISTERS) => {
+                self.data_in[array_index(&AESDATA_REGISTERS, a)] = data;
+            }
+            // The hash keys of GCM
+            a if a.is_in_range(&AESKEY2_REGISTERS) || a.is_in_range(&AESKEY3_REGISTERS) => {}
+            C::ALGSEL::ADDR => self.algsel = data,
+            C::DMAPROTCTL::ADDR => self.dmaprotctl = data,
+            C::SWRESET::ADDR if data & C::SWRESET::RESET::BIT_MASK != 0 => self.reset_engine(),
+            C::IRQTYPE::ADDR => self.irqtype = data,
+            C::IRQEN::ADDR => {
+                let newly_enabled = data & !self.irqen;
+                self.irqen = data;
+                self.notify(ctx, self.irqstat & newly_enabled);
+            }
+            C::IRQCLR::ADDR => self.irqstat &= !data,
+            C::IRQSET::ADDR => self.raise(ctx, data),
+            C::DMASWRESET::ADDR | C::SWRESET::ADDR => {}
+            C::DMASTAT::ADDR
+            | C::DMAHWVER::ADDR
+            | C::HWVER::ADDR
+            | C::IRQSTAT::ADDR
+            | C::AESTAGOUT::ADDR => {
+                warn!(
+                    "Ignoring write {:#x} to read-only {}",
+                    data,
+                    ctx.display_named_address(addr)
+                );
+            }
+            _ => unimplemented!(
+                "Requested CRYPTO data write {:#x} to address {:?}: {}",
+                data,
+                addr,
+                ctx.display_named_address(addr)
+            ),
+        }
+    }
+
+    /// Reading the last word of the tag releases the saved context.
+    fn read_with_side_effects(&mut self, ctx: &Context, addr: Address) -> u32 {
+        let data = self.get_data_for_address(ctx, addr);
+        if addr == AESTAGOUT_REGISTERS.start.offset(12) {
+            self.release_saved_context();
+        }
+        data
+    }
+
+    fn warn_if_gated(ctx: &Context, addr: Address) {
+        if !Self::is_clock_enabled(ctx) {
+            warn!(
+                "Accessing {} while the CRYPTO clock is gated.",
+                ctx.display_named_address(addr)
+            );
+        }
+    }
+}
+
+bridge_ports!(@slave CryptoComponent => @auto_configured @slave BusDriver);
+
+#[component_impl(crypto)]
+impl AHBPortConfig for CryptoComponent {
+    type Data = DataBus;
+    type Component = Self;
+    const TAG: &'static str = "CRYPTO";
+}
+
+#[component_impl(crypto)]
+impl AHBSlavePortProxiedInput for CryptoComponent {
+    fn proxy_ahb_input(ctx: &mut Context, msg: MasterToSlaveWires<Self::Data>) {
+        CryptoProxy.on_new_ahb_slave_input(ctx, msg);
+    }
+}
+
+#[component_impl(crypto)]
+impl AlignedFakingHandler for CryptoComponent {
+    const WRITE_MODE: WriteMode = WriteMode::Combinatorial;
+    const ALIGN: Size = Size::Word;
+    type Native = u32;
+
+    fn read_for_write_filler(
+        slave: &Self::Component,
+        ctx: &Context,
+        address: Address,
+    ) -> Self::Native {
+        slave.get_data_for_address(ctx, address)
+    }
+
+    fn pre_read(
+        _slave: &mut Self::Component,
+        ctx: &mut Context,
+        address: Address,
+    ) -> WaitstatesOrErr {
+        Self::warn_if_gated(ctx, address);
+        Ok(0)
+    }
+
+    fn read(slave: &mut Self::Component, ctx: &mut Context, address: Address) -> Self::Native {
+        let data = slave.read_with_side_effects(ctx, address);
+        trace!(
+            "read {address:?} (\"{}\") = {data:#x}",
+            ctx.display_named_address(address)
+        );
+        data
+    }
+
+    fn pre_write(
+        _slave: &mut Self::Component,
+        ctx: &mut Context,
+        address: Address,
+    ) -> WaitstatesOrErr {
+        Self::warn_if_gated(ctx, address);
+        Ok(0)
+    }
+
+    fn write(slave: &mut Self::Component, ctx: &mut Context, address: Address, data: Self::Native) {
+        trace!(
+            "write {address:?} (\"{}\") {data:#x}",
+            ctx.display_named_address(address)
+        );
+        slave.set_data_for_address(ctx, address, data);
+    }
+}
+
+#[component_impl(crypto)]
+impl DisableableComponent for CryptoComponent {
+    fn can_be_disabled_now(&self) -> bool {
+        self.driver.can_be_disabled_now()
+            && self.sysbus_driver.can_be_disabled_now()
+            && self.is_idle()
+    }
+}
+
+// The port of the CRYPTO DMA on the system bus
+make_port_struct!(pub(crate) SysbusMPort);
+
+impl AHBPortConfig for SysbusMPort {
+    type Data = DataBus;
+    type Component = CryptoComponent;
+    const TAG: &'static str = "CRYPTO:Master";
+}
+
+impl AHBMasterPortProxiedInput for SysbusMPort {
+    fn proxy_ahb_input(ctx: &mut Context, msg: SlaveToMasterWires<Self::Data>) {
+        CryptoProxy.on_new_ahb_master_input(ctx, msg);
+    }
+}
+
+bridge_ports!(@auto_configured @master SysbusDriver => @master SysbusMPort);
+
+impl MasterDriverSimplerHandler for SysbusMPort {
+    type UserData = ();
+    type MasterDriverSC = SysbusDriverSC;
+    const AHB_LITE_COMPAT: bool = true;
+    const DEFAULT_PROT: Protection = Protection::new_data();
+
+    fn read_done(
+        comp: &mut <Self as AHBPortConfig>::Component,
+        ctx: &mut Context,
+        _addr: Address,
+        data: DataBus,
+        _user: <Self as MasterDriverSimplerHandler>::UserData,
+    ) {
+        comp.transfer_done(ctx, Some(data));
+    }
+
+    fn write_done(
+        comp: &mut <Self as AHBPortConfig>::Component,
+        ctx: &mut Context,
+        _addr: Address,
+        _user: <Self as MasterDriverSimplerHandler>::UserData,
+    ) {
+        comp.transfer_done(ctx, None);
+    }
+
+    fn transfers_aborted(
+        comp: &mut <Self as AHBPortConfig>::Component,
+        ctx: &mut Context,
+        _addr_phase: Option<(TransferMeta, <Self as MasterDriverSimplerHandler>::UserData)>,
+        _data_phase: Option<(TransferMeta, <Self as MasterDriverSimplerHandler>::UserData)>,
+    ) {
+        comp.bus_error(ctx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AesMode, AesOperation, BLOCK_SIZE, Block};
+    use std::collections::VecDeque;
+
+    // [NIST 800-38A] Appendix F: the key and the plaintext of the AES-128 examples.
+    const SP800_38A_KEY: &str = "2b7e151628aed2a6abf7158809cf4f3c";
+    const SP800_38A_PLAINTEXT: &str = "6bc1bee22e409f96e93d7e117393172a\
+                                       ae2d8a571e03ac9c9eb76fac45af8e51\
+                                       30c81c46a35ce411e5fbc1191a0a52ef\
+                                       f69f2445df4f9b17ad2b417be66c3710";
+    // [NIST 800-38C] Appendix C: the key of the examples.
+    const SP800_38C_KEY: &str = "404142434445464748494a4b4c4d4e4f";
+
+    fn hex(text: &str) -> Vec<u8> {
+        (0..text.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&text[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn block(text: &str) -> Block {
+        hex(text).try_into().unwrap()
+    }
+
+    /// Runs the operation on the additional authenticated data and the data,
+    /// feeding it the blocks like the DMA does. Returns the output and the tag.
+    fn run(
+        mode: AesMode,
+        encrypt: bool,
+        key: &str,
+        iv: Block,
+        aad: &[u8],
+        data: &[u8],
+    ) -> (Vec<u8>, Block) {
+        let auth_len = u32::try_from(aad.len()).unwrap();
+        let mut op = AesOperation::new(mode, encrypt, block(key), iv, auth_len, data.len() as u64);
+        let input = [aad, data].concat();
+        let mut input = input.as_slice();
+        let mut output = VecDeque::new();
+        while !op.is_done() {
+            let (next, rest) = input.split_at(op.next_block_size());
+            op.process(next, &mut output);
+            input = rest;
+        }
+        assert!(input.is_empty());
+        (output.into(), op.tag())
+    }
+
+    /// The IV of CCM: the flags with `L - 1`, the nonce and the zeroed counter.
+    fn ccm_iv(nonce: &str) -> Block {
+        let nonce = hex(nonce);
+        let mut iv = [0; BLOCK_SIZE];
+        iv[0] = u8::try_from(BLOCK_SIZE - 2 - nonce.len()).unwrap();
+        iv[1..=nonce.len()].copy_from_slice(&nonce);
+        iv
+    }
+
+    fn check_both_ways(mode: AesMode, iv: Block, ciphertext: &str) {
+        let plaintext = hex(SP800_38A_PLAINTEXT);
+        let ciphertext = hex(ciphertext);
+        let (output, _) = run(mode, true, SP800_38A_KEY, iv, &[], &plaintext);
+        assert_eq!(output, ciphertext);
+        let (output, _) = run(mode, false, SP800_38A_KEY, iv, &[], &ciphertext);
+        assert_eq!(output, plaintext);
+    }
+
+    #[test]
+    fn ecb_aes128() {
+        // [NIST 800-38A] F.1.1 and F.1.2
+        check_both_ways(
+            AesMode::Ecb,
+            [0; BLOCK_SIZE],
+            "3ad77bb40d7a3660a89ecaf32466ef97\
+             f5d3d58503b9699de785895a96fdbaaf\
+             43b1cd7f598ece23881b00e3ed030688\
+             7b0c785e27e8ad3f8223207104725dd4",
+        );
+    }
+
+    #[test]
+    fn cbc_aes128() {
+        // [NIST 800-38A] F.2.1 and F.2.2
+        check_both_ways(
+            AesMode::Cbc,
+            block("000102030405060708090a0b0c0d0e0f"),
+            "7649abac8119b246cee98e9b12e9197d\
+             5086cb9b507219ee95db113a917678b2\
+             73bed6b8e3c1743b7116e69e22229516\
+             3ff1caa1681fac09120eca307586e1a7",
+        );
+    }
+
+    #[test]
+    fn ctr_aes128() {
+        // [NIST 800-38A] F.5.1 and F.5.2, the counter doesn't carry out of its last 32 bits.
+        check_both_ways(
+            AesMode::Ctr(4),
+            block("f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff"),
+            "874d6191b620e3261bef6864990db6ce\
+             9806f66b7970fdff8617187bb9fffdff\
+             5ae4df3edbd5d35e5b4f09020db03eab\
+             1e031dda2fbe03d1792170a0f3009cee",
+        );
+    }
+
+    fn check_ccm(nonce: &str, aad: &str, plaintext: &str, ciphertext: &str, tag: &str) {
+        let aad = hex(aad);
+        let plaintext = hex(plaintext);
+        let ciphertext = hex(ciphertext);
+        let tag = hex(tag);
+        let mode = AesMode::Ccm {
+            tag_size: u8::try_from(tag.len()).unwrap(),
+            length_size: u8::try_from(BLOCK_SIZE - 1 - nonce.len() / 2).unwrap(),
+        };
+        let iv = ccm_iv(nonce);
+
+        let (output, output_tag) = run(mode, true, SP800_38C_KEY, iv, &aad, &plaintext);
+        assert_eq!(output, ciphertext);
+        assert_eq!(output_tag[..tag.len()], tag);
+        let (output, output_tag) = run(mode, false, SP800_38C_KEY, iv, &aad, &ciphertext);
+        assert_eq!(output, plaintext);
+        assert_eq!(output_tag[..tag.len()], tag);
+    }
+
+    #[test]
+    fn ccm_example_1() {
+        // [NIST 800-38C] C.1: a partial block of data.
+        check_ccm(
+            "10111213141516",
+            "0001020304050607",
+            "20212223",
+            "7162015b",
+            "4dac255d",
+        );
+    }
+
+    #[test]
+    fn ccm_example_2() {
+        // [NIST 800-38C] C.2
+        check_ccm(
+            "1011121314151617",
+            "000102030405060708090a0b0c0d0e0f",
+            "202122232425262728292a2b2c2d2e2f",
+            "d2a1f0e051ea5f62081a7792073d593d",
+            "1fc64fbfaccd",
+        );
+    }
+
+    #[test]
+    fn ccm_example_3() {
+        // [NIST 800-38C] C.3: the data and the additional data span multiple blocks.
+        check_ccm(
+            "101112131415161718191a1b",
+            "000102030405060708090a0b0c0d0e0f10111213",
+            "202122232425262728292a2b2c2d2e2f3031323334353637",
+            "e3b201a9f5b71a7a9b1ceaeccd97e70b6176aad9a4428aa5",
+            "484392fbc1b09951",
+        );
+    }
+}
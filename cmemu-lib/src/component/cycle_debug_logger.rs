@@ -36,7 +36,7 @@ use std::path::{Path, PathBuf};
 
 mod render_json_log;
 
-#[derive(MainComponent, SkippableClockTreeNode, TickComponent, DisableableComponent)] // TODO: refactor using subcomponents
+#[derive(Clone, MainComponent, SkippableClockTreeNode, TickComponent, DisableableComponent)] // TODO: refactor using subcomponents
 #[skippable_if_disableable]
 pub(crate) struct CycleDebugLoggerComponent {
     // component config
@@ -648,7 +648,7 @@ impl CycleDebugLoggerComponent {
         if self.dwt_registers.is_set() {
             // make sure all fields are written
             tf.dwt = DWTState {
-                registers: self.dwt_registers.clone(),
+                registers: (*self.dwt_registers).clone(),
                 cpicnt_incremented: self.dwt_cpicnt_incremented.is_set_and(|()| true),
                 exccnt_incremented: self.dwt_exccnt_incremented.is_set_and(|()| true),
                 sleepcnt_incremented: self.dwt_sleepcnt_incremented.is_set_and(|()| true),
@@ -994,7 +994,7 @@ trait FlopTickTrickery {
     fn is_empty(&self) -> bool;
 }
 
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 struct ConnectionMultiFlopImpl<D: Debug + Default + Clone + 'static> {
     m2s: BufferFlop<MasterToSlaveWires<D>>,
     s2m: BufferFlop<SlaveToMasterWires<D>>,
@@ -1063,7 +1063,7 @@ impl Display for FreeStatusEnum {
 }
 
 #[cfg(feature = "cdl-ahb-trace")]
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 enum ConnectionMultiFlop {
     DataBus(ConnectionMultiFlopImpl<DataBus>),
     // Leave those for a while in case we use AHB with this as data somewhere.
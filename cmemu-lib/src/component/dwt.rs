@@ -1,6 +1,7 @@
 use cmemu_proc_macros::{component_impl, handler, proxy_use};
 #[cfg(feature = "cycle-debug-logger")]
 pub(crate) use register_bank::DWTRegisters;
+use serde::{Deserialize, Serialize};
 
 use crate::bridge_ports;
 use crate::common::new_ahb::databus::DataBus;
@@ -56,6 +57,8 @@ const ONE_BYTE_COUNTER_MASK: u32 = 0xFF;
 // Note: Haven't found this info in documentation, but counter's value (for others than `CYCCNT`)
 // is only updated if corresponding event is enabled in `CTRL`. (There is a test for it.)
 mod ctrl {
+    use serde::{Deserialize, Serialize};
+
     use crate::bitfield;
     use crate::common::Word;
 
@@ -64,7 +67,7 @@ mod ctrl {
         ///
         /// We list only implemented bits.
         // TODO: use one from cc2650_constants::CPU_DWT
-        #[derive(Clone, Copy)]
+        #[derive(Clone, Copy, Serialize, Deserialize)]
         pub(super) struct CTRL[32 (raw pub)] {
             /// bit(21): Enables Folded instruction count event.
             pub(super) FOLDEVTENA[21:21]: 1 bits,
@@ -88,7 +91,15 @@ mod ctrl {
     // Note/TODO: all counters (beside CYCCNT) also should generate event on overflow - same as above.
 }
 
-#[derive(MainComponent, SkippableClockTreeNode, TickComponent, TickComponentExtra)]
+#[derive(
+    Clone,
+    MainComponent,
+    SkippableClockTreeNode,
+    TickComponent,
+    TickComponentExtra,
+    Serialize,
+    Deserialize,
+)]
 pub(crate) struct DWTComponent {
     #[subcomponent(SlaveDriverSubcomponent)]
     slave_driver: BusDriver,
@@ -308,6 +319,8 @@ impl AHBSlavePortProxiedInput for DWTComponent {
 mod register_bank {
     use std::marker::PhantomData;
 
+    use serde::{Deserialize, Serialize};
+
     use crate::common::Word;
     use crate::common::bitstring::bitfield::ExpandedBitfield;
     use crate::component::dwt::ctrl::CTRL;
@@ -324,7 +337,15 @@ mod register_bank {
     /// It provides direct access to the fields since its main goal is to make
     /// DWT component definition cleaner while still providing direct access.
     /// It is a subcomponent, so the flops automatically tick.
-    #[derive(Subcomponent, TickComponent, TickComponentExtra, DisableableComponent)]
+    #[derive(
+        Clone,
+        Subcomponent,
+        TickComponent,
+        TickComponentExtra,
+        DisableableComponent,
+        Serialize,
+        Deserialize,
+    )]
     pub(super) struct DWTRegisterBank<SC>
     where
         SC: Subcomponent<Member = Self>,
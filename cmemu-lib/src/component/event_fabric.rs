@@ -10,32 +10,147 @@ use crate::common::new_ahb::{
 };
 #[proxy_use(proxy_only)]
 use crate::component::event_fabric::EventFabricEvent;
+use crate::component::nvic::InterruptId;
+use crate::component::udma::RequestKind;
 #[proxy_use]
 use crate::engine::Context;
 use crate::engine::{
     DisableableComponent, MainComponent, SeqRegister, SkippableClockTreeNode, TickComponent,
     TickComponentExtra,
 };
-use crate::proxy::{EventFabricProxy, NVICProxy};
+use crate::proxy::{EventFabricProxy, NVICProxy, UDMAProxy};
 use cc2650_constants::EVENT;
 use cmemu_common::{Address, HwRegister};
 use cmemu_proc_macros::{component_impl, handler, proxy_use};
-use log::trace;
+use log::{trace, warn};
 use num_enum::{IntoPrimitive, TryFromPrimitive};
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+const UDMA_CHANNELS_COUNT: usize = 32;
+
+/// `UDMACHnSSEL` and `UDMACHnBSEL` registers are interleaved.
+const UDMACH_SEL_REGISTERS: Range<Address> =
+    EVENT::UDMACH0SSEL::ADDR..EVENT::UDMACH31BSEL::ADDR.offset(4);
+
+/// Reset values of `[UDMACHnSSEL, UDMACHnBSEL]`, the reserved ones are zeroed.
+const UDMACH_SEL_RESET_VALUES: [[u32; 2]; UDMA_CHANNELS_COUNT] = [
+    [0, 0],
+    [
+        EVENT::UDMACH1SSEL::RESET_VALUE,
+        EVENT::UDMACH1BSEL::RESET_VALUE,
+    ],
+    [
+        EVENT::UDMACH2SSEL::RESET_VALUE,
+        EVENT::UDMACH2BSEL::RESET_VALUE,
+    ],
+    [
+        EVENT::UDMACH3SSEL::RESET_VALUE,
+        EVENT::UDMACH3BSEL::RESET_VALUE,
+    ],
+    [
+        EVENT::UDMACH4SSEL::RESET_VALUE,
+        EVENT::UDMACH4BSEL::RESET_VALUE,
+    ],
+    [
+        EVENT::UDMACH5SSEL::RESET_VALUE,
+        EVENT::UDMACH5BSEL::RESET_VALUE,
+    ],
+    [
+        EVENT::UDMACH6SSEL::RESET_VALUE,
+        EVENT::UDMACH6BSEL::RESET_VALUE,
+    ],
+    [
+        EVENT::UDMACH7SSEL::RESET_VALUE,
+        EVENT::UDMACH7BSEL::RESET_VALUE,
+    ],
+    [
+        EVENT::UDMACH8SSEL::RESET_VALUE,
+        EVENT::UDMACH8BSEL::RESET_VALUE,
+    ],
+    [
+        EVENT::UDMACH9SSEL::RESET_VALUE,
+        EVENT::UDMACH9BSEL::RESET_VALUE,
+    ],
+    [
+        EVENT::UDMACH10SSEL::RESET_VALUE,
+        EVENT::UDMACH10BSEL::RESET_VALUE,
+    ],
+    [
+        EVENT::UDMACH11SSEL::RESET_VALUE,
+        EVENT::UDMACH11BSEL::RESET_VALUE,
+    ],
+    [
+        EVENT::UDMACH12SSEL::RESET_VALUE,
+        EVENT::UDMACH12BSEL::RESET_VALUE,
+    ],
+    [0, EVENT::UDMACH13BSEL::RESET_VALUE],
+    [0, EVENT::UDMACH14BSEL::RESET_VALUE],
+    [0, EVENT::UDMACH15BSEL::RESET_VALUE],
+    [
+        EVENT::UDMACH16SSEL::RESET_VALUE,
+        EVENT::UDMACH16BSEL::RESET_VALUE,
+    ],
+    [
+        EVENT::UDMACH17SSEL::RESET_VALUE,
+        EVENT::UDMACH17BSEL::RESET_VALUE,
+    ],
+    [0, 0],
+    [0, 0],
+    [0, 0],
+    [
+        EVENT::UDMACH21SSEL::RESET_VALUE,
+        EVENT::UDMACH21BSEL::RESET_VALUE,
+    ],
+    [
+        EVENT::UDMACH22SSEL::RESET_VALUE,
+        EVENT::UDMACH22BSEL::RESET_VALUE,
+    ],
+    [
+        EVENT::UDMACH23SSEL::RESET_VALUE,
+        EVENT::UDMACH23BSEL::RESET_VALUE,
+    ],
+    [
+        EVENT::UDMACH24SSEL::RESET_VALUE,
+        EVENT::UDMACH24BSEL::RESET_VALUE,
+    ],
+    [0, 0],
+    [0, 0],
+    [0, 0],
+    [0, 0],
+    [0, 0],
+    [0, 0],
+    [0, 0],
+];
+
+/// Only the selections of the channels 9 to 12 and 14 are configurable.
+const fn is_udmach_sel_writable(channel: usize) -> bool {
+    matches!(channel, 9..=12 | 14)
+}
 
 // TODO: should it be FakingIface?
 type BusDriver = FakingIface<SlaveDriverSubcomponent, EventFabricComponent>;
 
 #[derive(
-    MainComponent, SkippableClockTreeNode, TickComponent, TickComponentExtra, DisableableComponent,
+    Clone,
+    MainComponent,
+    SkippableClockTreeNode,
+    TickComponent,
+    TickComponentExtra,
+    DisableableComponent,
 )]
 #[skippable_if_disableable]
+#[derive(Serialize, Deserialize)]
 pub(crate) struct EventFabricComponent {
     #[subcomponent(SlaveDriverSubcomponent)]
     driver: BusDriver,
 
     #[flop]
+    #[serde(with = "crate::engine::serde_hw_register")]
     swev: SeqRegister<EVENT::SWEV::Register>,
+
+    /// `[UDMACHnSSEL, UDMACHnBSEL]` selecting the single and burst requests of the uDMA channels.
+    udmach_sel: [[u32; 2]; UDMA_CHANNELS_COUNT],
 }
 
 #[component_impl(event_fabric)]
@@ -45,6 +160,8 @@ impl EventFabricComponent {
             driver: BusDriver::new(),
 
             swev: SeqRegister::new(EVENT::SWEV::Register::new()),
+
+            udmach_sel: UDMACH_SEL_RESET_VALUES,
         }
     }
 
@@ -68,6 +185,10 @@ impl EventFabricComponent {
     fn get_data_for_address(&self, addr: Address, ctx: &mut Context) -> u32 {
         match addr {
             EVENT::SWEV::ADDR => self.swev.read(),
+            a if a.is_in_range(&UDMACH_SEL_REGISTERS) => {
+                let (channel, kind) = Self::decode_udmach_sel(a);
+                self.udmach_sel[channel][kind]
+            }
             a => unimplemented!(
                 "Requested EVENT_FABRIC data read for address {:?}: {}",
                 a,
@@ -96,6 +217,18 @@ impl EventFabricComponent {
                     self.notify(ctx, EventFabricEvent::SWEV3);
                 }
             }
+            a if a.is_in_range(&UDMACH_SEL_REGISTERS) => {
+                let (channel, kind) = Self::decode_udmach_sel(a);
+                if is_udmach_sel_writable(channel) {
+                    self.udmach_sel[channel][kind] = data & EVENT::UDMACH9SSEL::EV::BIT_MASK;
+                } else {
+                    warn!(
+                        "Ignoring write {:#x} to read-only {}",
+                        data,
+                        ctx.display_named_address(a)
+                    );
+                }
+            }
             a => unimplemented!(
                 "Requested EVENT_FABRIC data write {:?} for address {:?}: {}",
                 data,
@@ -105,6 +238,12 @@ impl EventFabricComponent {
         }
     }
 
+    /// Returns the channel and the index of `[SSEL, BSEL]`.
+    fn decode_udmach_sel(addr: Address) -> (usize, usize) {
+        let offset = addr.offset_from(EVENT::UDMACH0SSEL::ADDR) as usize;
+        (offset / 8, offset % 8 / 4)
+    }
+
     fn get_waitstates_for_address(&self, _addr: Address, _writing: bool) -> u8 {
         0
     }
@@ -178,6 +317,38 @@ impl EventFabricComponent {
         NVICProxy.raise_interrupt(ctx, interrupt_id.try_into().unwrap());
     }
 
+    /// Channels selecting the `event` as the single and the burst request.
+    fn udma_channels_of(&self, event: EventFabricEvent) -> [u32; 2] {
+        let event = u32::from(u8::from(event));
+        let mut channels = [0; 2];
+        for (channel, selections) in self.udmach_sel.iter().enumerate() {
+            for (mask, &selected) in channels.iter_mut().zip(selections) {
+                if selected == event {
+                    *mask |= 1 << channel;
+                }
+            }
+        }
+        channels
+    }
+
+    /// A change of a DMA request line of a peripheral, which is held until served.
+    #[handler]
+    pub(crate) fn set_dma_request(
+        &mut self,
+        ctx: &mut Context,
+        event: EventFabricEvent,
+        asserted: bool,
+    ) {
+        trace!("DMA request {event:?} asserted: {asserted}");
+        let [single, burst] = self.udma_channels_of(event);
+        if single != 0 {
+            UDMAProxy.set_request_level(ctx, single, RequestKind::Single, asserted);
+        }
+        if burst != 0 {
+            UDMAProxy.set_request_level(ctx, burst, RequestKind::Burst, asserted);
+        }
+    }
+
     #[handler]
     pub(crate) fn notify(&mut self, ctx: &mut Context, event: EventFabricEvent) {
         use cc2650_constants::interrupts as ints;
@@ -185,6 +356,13 @@ impl EventFabricComponent {
             "calling EventFabric component handler with argument {:?}.",
             event
         );
+        let [single, burst] = self.udma_channels_of(event);
+        if single != 0 {
+            UDMAProxy.request(ctx, single, RequestKind::Single);
+        }
+        if burst != 0 {
+            UDMAProxy.request(ctx, burst, RequestKind::Burst);
+        }
         // NOTE: we have a reverse map as a configuration, so we inline the numbers for now,
         // as for the CPU, only ev 30 is configurable
         // TODO: implement full dispatch if there are other subscribers to the events
@@ -195,6 +373,9 @@ impl EventFabricComponent {
             EventFabricEvent::AON_GPIO_EDGE => self.raise_interrupt(ctx, ints::GPIO),
             EventFabricEvent::AON_RTC_COMB => self.raise_interrupt(ctx, ints::AON_RTC),
             EventFabricEvent::WDT_IRQ => self.raise_interrupt(ctx, ints::WDT),
+            EventFabricEvent::WDT_NMI => {
+                NVICProxy.raise_exception(ctx, InterruptId::NMI);
+            }
             // NOTE: these two names are reversed! RFC_PE0 == 2 => is actually an RFC_CPE_1 event!
             EventFabricEvent::RFC_CPE_0 => self.raise_interrupt(ctx, ints::RFC_PE1),
             EventFabricEvent::RFC_CPE_1 => self.raise_interrupt(ctx, ints::RFC_PE0),
@@ -202,7 +383,41 @@ impl EventFabricComponent {
             EventFabricEvent::RFC_CMD_ACK => self.raise_interrupt(ctx, ints::RFC_CA),
             EventFabricEvent::SWEV0 => self.raise_interrupt(ctx, ints::SWE0),
             EventFabricEvent::AUX_COMB => self.raise_interrupt(ctx, ints::AUX_CE),
+            // NOTE: the generated list names the interrupt 6 after UART1 and misses 13,
+            // but they are fixed to these events, see `EVENT:CPUIRQSEL6` and `EVENT:CPUIRQSEL13`.
+            EventFabricEvent::AUX_SWEV0 => self.raise_interrupt(ctx, ints::UART1),
+            EventFabricEvent::AUX_SWEV1 => self.raise_interrupt(ctx, 13),
             EventFabricEvent::AON_PROG0 => self.raise_interrupt(ctx, ints::AON_EVENT),
+            EventFabricEvent::GPT0A => self.raise_interrupt(ctx, ints::GPT0A),
+            EventFabricEvent::GPT0B => self.raise_interrupt(ctx, ints::GPT0B),
+            EventFabricEvent::GPT1A => self.raise_interrupt(ctx, ints::GPT1A),
+            EventFabricEvent::GPT1B => self.raise_interrupt(ctx, ints::GPT1B),
+            EventFabricEvent::GPT2A => self.raise_interrupt(ctx, ints::GPT2A),
+            EventFabricEvent::GPT2B => self.raise_interrupt(ctx, ints::GPT2B),
+            EventFabricEvent::GPT3A => self.raise_interrupt(ctx, ints::GPT3A),
+            EventFabricEvent::GPT3B => self.raise_interrupt(ctx, ints::GPT3B),
+            EventFabricEvent::UART0_COMB => self.raise_interrupt(ctx, ints::UART0),
+            EventFabricEvent::SSI0_COMB => self.raise_interrupt(ctx, ints::SSI0),
+            EventFabricEvent::SSI1_COMB => self.raise_interrupt(ctx, ints::SSI1),
+            EventFabricEvent::I2C_IRQ => self.raise_interrupt(ctx, ints::I2C),
+            EventFabricEvent::DMA_DONE_COMB => self.raise_interrupt(ctx, ints::UDMA),
+            EventFabricEvent::DMA_ERR => self.raise_interrupt(ctx, ints::UDMA_ERR),
+            EventFabricEvent::CRYPTO_RESULT_AVAIL_IRQ => self.raise_interrupt(ctx, ints::CRYPTO),
+            EventFabricEvent::TRNG_IRQ => self.raise_interrupt(ctx, ints::TRNG),
+            // Subscribed only by the uDMA channels
+            EventFabricEvent::GPT0A_DMABREQ
+            | EventFabricEvent::GPT0B_DMABREQ
+            | EventFabricEvent::GPT1A_DMABREQ
+            | EventFabricEvent::GPT1B_DMABREQ
+            | EventFabricEvent::GPT2A_DMABREQ
+            | EventFabricEvent::GPT2B_DMABREQ
+            | EventFabricEvent::GPT3A_DMABREQ
+            | EventFabricEvent::GPT3B_DMABREQ
+            | EventFabricEvent::CRYPTO_DMA_DONE_IRQ
+            | EventFabricEvent::DMA_CH0_DONE
+            | EventFabricEvent::DMA_CH18_DONE
+            // Subscribed also by the configurable interrupt and the RF core, not implemented yet
+            | EventFabricEvent::AON_AUX_SWEV0 => {}
             e => unimplemented!("Unknown event: {:?}", e),
         }
     }
@@ -211,7 +426,9 @@ impl EventFabricComponent {
 // Copied from cc2650_constants::EVENT::UDMACH14BSEL::EV::Values
 #[allow(non_camel_case_types, dead_code)]
 #[repr(u8)]
-#[derive(Clone, Copy, Debug, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[derive(
+    Clone, Copy, Debug, Eq, PartialEq, IntoPrimitive, TryFromPrimitive, Serialize, Deserialize,
+)]
 pub(crate) enum EventFabricEvent {
     /// Always asserted
     ALWAYS_ACTIVE = 121,
@@ -353,6 +570,10 @@ pub(crate) enum EventFabricEvent {
     ///
     /// AUX domain wakeup control `AON_EVENT:AUXWUSEL`
     AUX_SWEV1 = 29,
+    /// AUX software event 0, triggered by `AUX_EVCTL:SWEVSET.SWEV0`, also available as `AUX_EVENT0` AON wake up event.
+    ///
+    /// Not selectable for the uDMA, but fixed as the interrupt 6, see `EVENT:CPUIRQSEL6`
+    AUX_SWEV0 = 28,
     /// Combined Interrupt for CPE Generated events. Corresponding flags are here `RFC_DBELL:RFCPEIFG`. Only interrupts selected with CPE0 in `RFC_DBELL:RFCPEIFG` can trigger a `RFC_CPE_0` event
     RFC_CPE_0 = 27,
     /// Combined RFC hardware interrupt, corresponding flag is here `RFC_DBELL:RFHWIFG`
@@ -1,6 +1,8 @@
+use serde::{Deserialize, Serialize};
+#[proxy_use]
 use std::ops::Range;
 
-use log::debug;
+use log::{debug, warn};
 
 use cc2650_constants as soc;
 use cmemu_proc_macros::{component_impl, handler, proxy_use};
@@ -45,7 +47,15 @@ impl MemoryConfiguration for FlashMemory {
 
 type FlashMemory = Memory<MemorySC>;
 
-#[derive(MainComponent, SkippableClockTreeNode, TickComponent, TickComponentExtra)]
+#[derive(
+    Clone,
+    MainComponent,
+    SkippableClockTreeNode,
+    TickComponent,
+    TickComponentExtra,
+    Serialize,
+    Deserialize,
+)]
 #[skippable_if_disableable]
 pub(crate) struct FlashComponent {
     #[subcomponent(MemorySC)]
@@ -61,6 +71,11 @@ impl FlashComponent {
         }
     }
 
+    /// The memory keeps its contents across a reset, only its bus interface is reset.
+    pub(crate) fn reset_keeping_contents(&mut self) {
+        self.memory.reset_interface();
+    }
+
     pub(crate) fn tick(&mut self, ctx: &mut Context) {
         FlashMemory::run_driver(self, ctx);
     }
@@ -80,6 +95,52 @@ impl FlashComponent {
             .request_memory_write_bypass(ctx, start_address, data);
     }
 
+    /// Programs the bytes at `start_address`. Programming can only clear bits,
+    /// so a bit is set after the operation only if it was set before and in `data`.
+    #[handler]
+    pub(crate) fn program_memory(
+        &mut self,
+        _ctx: &mut Context,
+        start_address: Address,
+        data: DataBus,
+    ) {
+        let (mut buf, buf_size) = DataBus::make_slice(data.size());
+        let buf = &mut buf[..buf_size];
+        let (mut old, _) = DataBus::make_slice(data.size());
+        let old = &mut old[..buf_size];
+        self.memory
+            .read_memory(start_address, old)
+            .expect("Programming outside of the Flash memory");
+        data.write_into_slice(buf);
+        if buf.iter().zip(old.iter()).any(|(new, old)| new & !old != 0) {
+            warn!("Programming {buf:02x?} at {start_address:?} cannot set the bits of {old:02x?}.");
+        }
+        for (new, old) in buf.iter_mut().zip(old.iter()) {
+            *new &= old;
+        }
+        self.memory
+            .write_memory(start_address, buf)
+            .expect("Programming outside of the Flash memory");
+    }
+
+    /// Erases the given range, what sets all its bits.
+    #[handler]
+    pub(crate) fn erase_memory(&mut self, _ctx: &mut Context, range: Range<Address>) {
+        let erased = vec![0xFF; range.end.offset_from(range.start) as usize];
+        self.memory
+            .write_memory(range.start, &erased)
+            .expect("Erasing outside of the Flash memory");
+    }
+
+    /// Returns the contents of the whole Flash memory.
+    pub(crate) fn get_contents(&self) -> Vec<u8> {
+        let mut contents = vec![0; FlashMemory::memory_size()];
+        self.memory
+            .read_memory(soc::FLASHMEM::ADDR, &mut contents)
+            .expect("Flash memory covers its address space");
+        contents
+    }
+
     pub(crate) fn write_memory(
         &mut self,
         start_address: Address,
@@ -0,0 +1,393 @@
+pub const FLASH_CONTROLLER_ROUTE_INJECTION: Range<Address> = FLASH::ADDR_SPACE;
+
+use crate::bridge_ports;
+use crate::common::new_ahb::databus::DataBus;
+use crate::common::new_ahb::ports::AHBSlavePortProxiedInput;
+#[proxy_use]
+use crate::common::new_ahb::ports::{AHBPortConfig, AHBSlavePortInput};
+#[proxy_use]
+use crate::common::new_ahb::signals::{MasterToSlaveWires, Size};
+use crate::common::new_ahb::slave_driver::WriteMode;
+use crate::common::new_ahb::slave_driver::faking_slave_driver::{
+    AlignedFakingHandler, FakingIface, WaitstatesOrErr,
+};
+#[proxy_use]
+use crate::engine::Context;
+use crate::engine::{
+    DisableableComponent, Duration, MainComponent, SkippableClockTreeNode, TickComponent,
+    TickComponentExtra, Timepoint,
+};
+use crate::proxy::{FlashControllerProxy, FlashProxy};
+use cc2650_constants::{FLASH, FLASHMEM};
+use cmemu_common::{Address, address_match_range};
+use cmemu_proc_macros::{component_impl, handler, proxy_use};
+use log::{debug, trace, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+// [TI-TRM-I] 7.2 The Flash is programmed and erased by the Flash State Machine (FSM) of the Flash
+// memory controller, and the interface of the FSM is "Internal. Only to be used through TI
+// provided API." We follow the sequences of the TI driverlib (`flash.c`) and the ROM, e.g.:
+//   FSM_WR_ENA = 5; FSM_CMD = <command>; FADDR = <address>; FSM_EXECUTE = 0x15; FSM_WR_ENA = 2;
+//   while FMSTAT.BUSY {}; check FMSTAT.CSTAT
+// The registers configuring the analog side (pulse widths, voltages, etc.) are only stored.
+
+/// Value of `FSM_WR_ENA` which allows writing the other FSM registers.
+const FSM_WR_ENABLE: u32 = 5;
+/// Value of `FSM_EXECUTE` starting the command from `FSM_CMD`.
+const FSM_EXECUTE_CMD: u32 = 0x15;
+/// Registers with this offset are guarded by `FSM_WR_ENA`.
+const FSM_REGISTERS: Range<Address> = FLASH::ADDR.offset(0x2200)..FLASH::ADDR.offset(0x2400);
+
+/// `FADDR` holds the address within the Flash bank, which is aliased in the address space.
+const FADDR_MASK: u32 = FLASHMEM::SIZE.next_power_of_two() - 1;
+/// Bytes programmed at once from `FWPWRITE*` registers (`FCFG_BANK.MAIN_BANK_WIDTH` bits).
+#[allow(clippy::cast_possible_truncation)]
+const BANK_WIDTH: usize = (FLASH::FCFG_BANK::MAIN_BANK_WIDTH::RESET_VALUE / 8) as usize;
+/// `FCFG_B0_SSIZE0.B0_SECT_SIZE` is in KB.
+const SECTOR_SIZE: u32 = FLASH::FCFG_B0_SSIZE0::B0_SECT_SIZE::RESET_VALUE * 1024;
+
+/// [CC2650-DS] Flash Memory Characteristics: Flash write time (4 bytes).
+const PROGRAM_TIME: Duration = Duration::from_picos(8_000_000);
+/// [CC2650-DS] Flash Memory Characteristics: Flash page/sector erase time.
+/// The mass erase time is not specified, so we assume the same.
+const ERASE_TIME: Duration = Duration::from_picos(8_000_000_000);
+
+/// Commands of the FSM, as named by the TI Flash API.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+enum FsmCommand {
+    ProgramData,
+    EraseSector,
+    EraseBank,
+    ClearStatus,
+}
+
+impl FsmCommand {
+    fn from_bits(bits: u32) -> Option<Self> {
+        match bits {
+            0x02 => Some(Self::ProgramData),
+            0x06 => Some(Self::EraseSector),
+            0x08 => Some(Self::EraseBank),
+            0x10 => Some(Self::ClearStatus),
+            _ => None,
+        }
+    }
+}
+
+/// An operation on the Flash memory performed by the FSM until `deadline`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Operation {
+    command: FsmCommand,
+    address: Address,
+    data: DataBus,
+    deadline: Timepoint,
+}
+
+#[derive(
+    Clone,
+    MainComponent,
+    SkippableClockTreeNode,
+    TickComponent,
+    TickComponentExtra,
+    Serialize,
+    Deserialize,
+)]
+#[skippable_if_disableable]
+pub(crate) struct FlashControllerComponent {
+    #[subcomponent(DriverSC)]
+    driver: BusDriver,
+
+    fsm_wr_ena: u32,
+    fsm_cmd: u32,
+    faddr: u32,
+    fwpwrite: [u32; 8],
+    /// Status bits of `FMSTAT` other than the ones of the operation in progress.
+    fmstat: u32,
+    operation: Option<Operation>,
+
+    cfg: u32,
+    fpac1: u32,
+    /// The registers which have no effect on the emulated Flash.
+    other_registers: BTreeMap<Address, u32>,
+}
+type BusDriver = FakingIface<DriverSC, FlashControllerComponent>;
+
+#[component_impl(flash_controller)]
+impl FlashControllerComponent {
+    pub(crate) fn new() -> Self {
+        Self {
+            driver: Default::default(),
+
+            fsm_wr_ena: FLASH::FSM_WR_ENA::RESET_VALUE,
+            fsm_cmd: FLASH::FSM_CMD::RESET_VALUE,
+            faddr: FLASH::FADDR::RESET_VALUE,
+            fwpwrite: [FLASH::FWPWRITE0::RESET_VALUE; 8],
+            fmstat: FLASH::FMSTAT::RESET_VALUE,
+            operation: None,
+
+            // Values observed on a device after the boot code.
+            cfg: 0x8000_0125,
+            fpac1: FLASH::FPAC1::RESET_VALUE,
+            other_registers: BTreeMap::new(),
+        }
+    }
+
+    pub(crate) fn tick(&mut self, ctx: &mut Context) {
+        BusDriver::run_driver(self, ctx);
+
+        if let Some(operation) = &self.operation
+            && ctx.event_queue().get_current_time() >= operation.deadline
+        {
+            let operation = self.operation.take().expect("checked above");
+            Self::finish_operation(ctx, operation);
+        }
+    }
+
+    pub(crate) fn tock(&mut self, ctx: &mut Context) {
+        BusDriver::tock(self, ctx);
+    }
+
+    #[handler]
+    pub(crate) fn on_new_ahb_slave_input(
+        &mut self,
+        ctx: &mut Context,
+        msg: MasterToSlaveWires<<FlashControllerComponent as AHBPortConfig>::Data>,
+    ) {
+        <Self as AHBSlavePortInput>::on_ahb_input(self, ctx, msg);
+    }
+
+    fn start_command(&mut self, ctx: &mut Context) {
+        let Some(command) = FsmCommand::from_bits(self.fsm_cmd & FLASH::FSM_CMD::FSMCMD::BIT_MASK)
+        else {
+            // The other commands (e.g. the verify ones) are not used by the TI Flash API.
+            warn!("Unsupported Flash FSM command {:#x} fails.", self.fsm_cmd);
+            self.fmstat |= FLASH::FMSTAT::CSTAT::BIT_MASK | FLASH::FMSTAT::INVDAT::BIT_MASK;
+            return;
+        };
+        if self.operation.is_some() {
+            warn!("Flash FSM command {command:?} issued while busy is ignored.");
+            return;
+        }
+        let address = FLASHMEM::ADDR.offset(self.faddr & FADDR_MASK);
+        debug!("Flash FSM command {command:?} at {address:?}");
+
+        let (address, data, time) = match command {
+            FsmCommand::ClearStatus => {
+                self.fmstat = 0;
+                return;
+            }
+            FsmCommand::ProgramData => {
+                #[allow(clippy::cast_possible_truncation)]
+                let address = address.masked(!(BANK_WIDTH as u32 - 1));
+                let mut bytes = [0; BANK_WIDTH];
+                for (chunk, word) in bytes.chunks_exact_mut(4).zip(self.fwpwrite.iter()) {
+                    chunk.copy_from_slice(&word.to_le_bytes());
+                }
+                // The next program operation starts with the data buffer cleared.
+                self.fwpwrite = [FLASH::FWPWRITE0::RESET_VALUE; 8];
+                let data = DataBus::from_slice(&bytes);
+                #[allow(clippy::cast_possible_truncation)]
+                let time = PROGRAM_TIME * (BANK_WIDTH as u64 / 4);
+                (address, data, time)
+            }
+            FsmCommand::EraseSector => {
+                let address = address.masked(!(SECTOR_SIZE - 1));
+                (address, DataBus::HighZ, ERASE_TIME)
+            }
+            FsmCommand::EraseBank => (FLASHMEM::ADDR, DataBus::HighZ, ERASE_TIME),
+        };
+        if !address.is_in_range(&FLASHMEM::ADDR_SPACE) {
+            warn!("Flash FSM command {command:?} at invalid address {address:?}");
+            self.fmstat |= FLASH::FMSTAT::CSTAT::BIT_MASK;
+            return;
+        }
+        self.operation = Some(Operation {
+            command,
+            address,
+            data,
+            deadline: ctx.event_queue().get_current_time() + time,
+        });
+    }
+
+    fn finish_operation(ctx: &mut Context, operation: Operation) {
+        debug!("Flash FSM finished {operation:?}");
+        let Operation {
+            command,
+            address,
+            data,
+            ..
+        } = operation;
+        match command {
+            FsmCommand::ProgramData => {
+                FlashProxy.program_memory(ctx, address, data);
+            }
+            FsmCommand::EraseSector => {
+                FlashProxy.erase_memory(ctx, address..address.offset(SECTOR_SIZE));
+            }
+            FsmCommand::EraseBank => {
+                FlashProxy.erase_memory(ctx, FLASHMEM::ADDR_SPACE);
+            }
+            FsmCommand::ClearStatus => unreachable!("Clearing status is immediate"),
+        }
+    }
+
+    fn fmstat(&self) -> u32 {
+        let operation_bits = match self.operation {
+            Some(Operation {
+                command: FsmCommand::ProgramData,
+                ..
+            }) => FLASH::FMSTAT::BUSY::BIT_MASK | FLASH::FMSTAT::PGM::BIT_MASK,
+            Some(_) => FLASH::FMSTAT::BUSY::BIT_MASK | FLASH::FMSTAT::ERS::BIT_MASK,
+            None => 0,
+        };
+        self.fmstat | operation_bits
+    }
+
+    fn is_fwpwrite(addr: Address) -> bool {
+        addr.is_in_range(&(FLASH::FWPWRITE0::ADDR..FLASH::FWPWRITE7::ADDR.offset(4)))
+    }
+
+    fn get_waitstates_for_address(&self, addr: Address, _writing: bool) -> u8 {
+        if cfg!(not(feature = "nm-unstable")) {
+            // TODO: waitstating is bugged, since pre_write is registered and write_data is combinatorial
+            //  (that is, these two may be called in the same cycle if we return 1 for write)
+            return 0;
+        }
+
+        address_match_range! {addr,
+            FLASH::BASE_ADDR => 2,
+            FLASH::FRDCTL::ADDR => 5,
+            _ => 0,
+        }
+    }
+
+    fn get_data_for_address(&self, addr: Address) -> u32 {
+        match addr {
+            FLASH::FMSTAT::ADDR => self.fmstat(),
+            FLASH::FSM_WR_ENA::ADDR => self.fsm_wr_ena,
+            FLASH::FSM_CMD::ADDR => self.fsm_cmd,
+            FLASH::FSM_EXECUTE::ADDR => FLASH::FSM_EXECUTE::RESET_VALUE,
+            FLASH::FADDR::ADDR => self.faddr,
+            a if Self::is_fwpwrite(a) => {
+                self.fwpwrite[a.offset_from(FLASH::FWPWRITE0::ADDR) as usize / 4]
+            }
+            FLASH::CFG::ADDR => self.cfg,
+            FLASH::FPAC1::ADDR => self.fpac1,
+            FLASH::FCFG_BANK::ADDR => FLASH::FCFG_BANK::RESET_VALUE,
+            FLASH::FCFG_B0_SSIZE0::ADDR => FLASH::FCFG_B0_SSIZE0::RESET_VALUE,
+            FLASH::FLASH_SIZE::ADDR => FLASHMEM::SIZE / SECTOR_SIZE,
+            a => self.other_registers.get(&a).copied().unwrap_or(0),
+        }
+    }
+
+    fn set_data_for_address(&mut self, addr: Address, data: u32, ctx: &mut Context) {
+        if addr.is_in_range(&FSM_REGISTERS)
+            && addr != FLASH::FSM_WR_ENA::ADDR
+            && self.fsm_wr_ena & FLASH::FSM_WR_ENA::WR_ENA::BIT_MASK != FSM_WR_ENABLE
+        {
+            warn!(
+                "Write of {data:#x} to {} ignored, as FSM_WR_ENA is not set.",
+                ctx.display_named_address(addr)
+            );
+            return;
+        }
+        match addr {
+            FLASH::FMSTAT::ADDR => {
+                warn!("Writing value {data:#x} to read-only FMSTAT has no effect.");
+            }
+            FLASH::FSM_WR_ENA::ADDR => self.fsm_wr_ena = data & FLASH::FSM_WR_ENA::WR_ENA::BIT_MASK,
+            FLASH::FSM_CMD::ADDR => self.fsm_cmd = data,
+            FLASH::FSM_EXECUTE::ADDR => {
+                if data & FLASH::FSM_EXECUTE::FSMEXECUTE::BIT_MASK == FSM_EXECUTE_CMD {
+                    self.start_command(ctx);
+                }
+            }
+            FLASH::FADDR::ADDR => self.faddr = data,
+            a if Self::is_fwpwrite(a) => {
+                self.fwpwrite[a.offset_from(FLASH::FWPWRITE0::ADDR) as usize / 4] = data;
+            }
+            FLASH::CFG::ADDR => self.cfg = data,
+            FLASH::FPAC1::ADDR => self.fpac1 = data,
+            a => {
+                debug!(
+                    "Flash register {} = {data:#x} has no effect",
+                    ctx.display_named_address(a)
+                );
+                self.other_registers.insert(a, data);
+            }
+        }
+    }
+}
+
+bridge_ports!(@slave FlashControllerComponent => @auto_configured @slave BusDriver);
+
+#[component_impl(flash_controller)]
+impl AHBPortConfig for FlashControllerComponent {
+    type Data = DataBus;
+    type Component = Self;
+    const TAG: &'static str = "FlashController";
+}
+
+#[component_impl(flash_controller)]
+impl AHBSlavePortProxiedInput for FlashControllerComponent {
+    fn proxy_ahb_input(ctx: &mut Context, msg: MasterToSlaveWires<Self::Data>) {
+        FlashControllerProxy.on_new_ahb_slave_input(ctx, msg);
+    }
+}
+
+#[component_impl(flash_controller)]
+impl AlignedFakingHandler for FlashControllerComponent {
+    const WRITE_MODE: WriteMode = WriteMode::Combinatorial;
+    const ALIGN: Size = Size::Word;
+    type Native = u32;
+
+    fn read_for_write_filler(
+        slave: &Self::Component,
+        _ctx: &Context,
+        address: Address,
+    ) -> Self::Native {
+        slave.get_data_for_address(address)
+    }
+
+    fn pre_read(
+        slave: &mut Self::Component,
+        _ctx: &mut Context,
+        address: Address,
+    ) -> WaitstatesOrErr {
+        Ok(slave.get_waitstates_for_address(address, false))
+    }
+
+    fn read(slave: &mut Self::Component, ctx: &mut Context, address: Address) -> Self::Native {
+        let data = slave.get_data_for_address(address);
+        trace!(
+            "read {address:?} (\"{}\") = {data:#x}",
+            ctx.display_named_address(address)
+        );
+        data
+    }
+
+    fn pre_write(
+        slave: &mut Self::Component,
+        _ctx: &mut Context,
+        address: Address,
+    ) -> WaitstatesOrErr {
+        Ok(slave.get_waitstates_for_address(address, true))
+    }
+
+    fn write(slave: &mut Self::Component, ctx: &mut Context, address: Address, data: Self::Native) {
+        trace!(
+            "write {address:?} (\"{}\") {data:#x}",
+            ctx.display_named_address(address)
+        );
+        slave.set_data_for_address(address, data, ctx);
+    }
+}
+
+#[component_impl(flash_controller)]
+impl DisableableComponent for FlashControllerComponent {
+    fn can_be_disabled_now(&self) -> bool {
+        // The time of an operation is checked on ticks.
+        self.operation.is_none()
+    }
+}
@@ -1,4 +1,5 @@
-pub const GPIO_ROUTE_INJECTION: Range<Address> = GPIO::ADDR_SPACE;
+pub const GPIO_ROUTE_INJECTION: RangeUnion<Range<Address>, Range<Address>> =
+    RangeUnion(&GPIO::ADDR_SPACE, &IOC::ADDR_SPACE);
 
 use crate::bridge_ports;
 use crate::common::new_ahb::databus::DataBus;
@@ -11,33 +12,104 @@ use crate::common::new_ahb::slave_driver::WriteMode;
 use crate::common::new_ahb::slave_driver::faking_slave_driver::{
     AlignedFakingHandler, FakingIface, WaitstatesOrErr,
 };
-use crate::engine::BufferFlop;
+use crate::common::utils::HostHandle;
+use crate::component::WakeupEvent;
+use crate::component::aon_event::AonEvent;
+use crate::engine::{BufferFlop, Duration, EventRevokeToken, ResetRequest, Timepoint};
 #[proxy_use]
 use crate::engine::{
-    Context, DisableableComponent, MainComponent, SeqFlopMemoryBankSimple, SkippableClockTreeNode,
-    TickComponent, TickComponentExtra,
+    Context, DisableableComponent, MainComponent, SkippableClockTreeNode, TickComponent,
+    TickComponentExtra,
 };
-use crate::proxy::GPIOProxy;
+use crate::proxy::event_data::EventData;
+use crate::proxy::{AONEventProxy, GPIOProxy};
 use crate::utils::IfExpr;
-use cc2650_constants::GPIO;
 use cc2650_constants::{AddressExt, is_unbuffered_alias};
+use cc2650_constants::{GPIO, IOC};
+use cmemu_common::address::RangeUnion;
 use cmemu_common::{Address, address_match_range};
 use cmemu_proc_macros::{component_impl, handler, proxy_use};
-use log::trace;
+use log::{info, trace};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 use std::ops::Range;
+use std::panic::UnwindSafe;
 
-#[derive(MainComponent, SkippableClockTreeNode, TickComponent, TickComponentExtra)]
+// [TI-TRM] 11 I/O Control
+// The IOC configures the 32 DIO pads, which are driven either by the GPIO outputs,
+// by the emulator host, by the pull resistors, or are left floating.
+// The pads are evaluated whenever any of these changes, and the configured edges
+// set `GPIO:EVFLAGS31_0` and raise the `PADn` events of `AON_EVENT`, which is also
+// the GPIO interrupt and the wakeup source (the `PAD` event, if `EDGE_IRQ_EN`).
+// The host drives the pads at scheduled times, delivered as external wakeups, thus
+// the edges are detected also while the MCU sleeps.
+// In the shutdown mode, only the edges selected by `WU_CFG` matter: they reset the device.
+// Not implemented: the peripheral ports other than GPIO (their pads are not driven),
+// the input enable (`IE`, inputs are always sampled), the drive strength and the hysteresis.
+
+/// `GpioInterface` observes the DIO pads driven by the MCU.
+pub trait GpioInterface {
+    /// Called at the emulation `time` when the level of the `dio` pad changes due to the MCU,
+    /// i.e., a write to the GPIO outputs or to the IOC configuration.
+    fn pad_changed(&mut self, time: Timepoint, dio: u8, level: bool);
+}
+
+pub type GpioInterfaceImpl = Box<dyn GpioInterface + Send + Sync + UnwindSafe>;
+
+pub(crate) const DIO_COUNT: usize = 32;
+
+/// Floating pads read as observed on `CherryMotes`.
+// Note: this particular "default" value is important, as some tests rely on this
+// funny value of `DIN31_0` (0x2000_0000 -> an address in SRAM!).
+const FLOATING_PADS: u32 = 1 << 29;
+
+/// The GPIO port of `IOCFGn.PORT_ID`.
+const PORT_ID_GPIO: u32 = 0;
+
+/// `IOCFGn.WU_CFG` waking up from the shutdown when the pad is going low or high.
+const WU_CFG_FALLING: u32 = 0b10;
+const WU_CFG_RISING: u32 = 0b11;
+
+/// `IOCFGn` is the same for all DIOs.
+use IOC::IOCFG0 as IOCFG;
+
+/// A change of a pad driven by the host: the time, the DIO and the level or `None` to release it.
+type ScheduledInput = (Timepoint, u8, Option<bool>);
+
+#[derive(
+    Clone,
+    MainComponent,
+    SkippableClockTreeNode,
+    TickComponent,
+    TickComponentExtra,
+    Serialize,
+    Deserialize,
+)]
 #[skippable_if_disableable]
 pub(crate) struct GPIOComponent {
     #[subcomponent(DriverSC)]
     driver: BusDriver,
 
-    #[flop]
-    pub(crate) yellow_led_state: SeqFlopMemoryBankSimple<bool>,
-    #[flop]
-    pub(crate) v1_led_state: SeqFlopMemoryBankSimple<bool>,
-    #[flop]
-    pub(crate) v2_led_state: SeqFlopMemoryBankSimple<bool>,
+    /// `GPIO:DOUT31_0`, also accessed by the set, clear, toggle and per-DIO registers.
+    dout: u32,
+    doe: u32,
+    evflags: u32,
+    iocfg: [u32; DIO_COUNT],
+
+    /// The levels of the pads, as last evaluated.
+    pads: u32,
+    /// The inputs of the pads (`DIN31_0`), as last evaluated for the edge detection.
+    inputs: u32,
+    /// The pads driven by the host and their levels.
+    host_driven: u32,
+    host_levels: u32,
+    /// Changes of the pads driven by the host, sorted by time.
+    scheduled_inputs: VecDeque<ScheduledInput>,
+    /// The wakeup event of the earliest scheduled input.
+    wakeup: Option<EventRevokeToken>,
+    /// The device is in the shutdown mode, waiting for a wakeup from a pad.
+    in_shutdown: bool,
+    interface_impl: HostHandle<GpioInterfaceImpl>,
 
     // Some buses pipeline better if addresses are back-to-back. (Most likely arbiter)
     #[flop]
@@ -50,13 +122,26 @@ type BusDriver = FakingIface<DriverSC, GPIOComponent>;
 
 #[component_impl(gpio)]
 impl GPIOComponent {
+    pub(crate) const YELLOW_LED_IO_ID: u8 = 20; // yellow LED on CherryMote
+    pub(crate) const V1_LED_IO_ID: u8 = 13; // VLED1 on CherryMote
+    pub(crate) const V2_LED_IO_ID: u8 = 14; // VLED2 on CherryMote
+
     pub fn new() -> Self {
         Self {
             driver: Default::default(),
 
-            yellow_led_state: SeqFlopMemoryBankSimple::new(false),
-            v1_led_state: SeqFlopMemoryBankSimple::new(false),
-            v2_led_state: SeqFlopMemoryBankSimple::new(false),
+            dout: GPIO::DOUT31_0::RESET_VALUE,
+            doe: GPIO::DOE31_0::RESET_VALUE,
+            evflags: GPIO::EVFLAGS31_0::RESET_VALUE,
+            iocfg: [IOCFG::RESET_VALUE; DIO_COUNT],
+            pads: FLOATING_PADS,
+            inputs: FLOATING_PADS,
+            host_driven: 0,
+            host_levels: 0,
+            scheduled_inputs: VecDeque::new(),
+            wakeup: None,
+            in_shutdown: false,
+            interface_impl: HostHandle::new(None),
             prev_addr: BufferFlop::new(),
             gpio_trick_prev: BufferFlop::new(),
         }
@@ -66,12 +151,6 @@ impl GPIOComponent {
         self.prev_addr.allow_skip();
         self.gpio_trick_prev.allow_skip();
         BusDriver::run_driver(self, ctx);
-        trace!(
-            "Yellow LED is {}, VLED1 is {}, VLED2 is {}",
-            if *self.yellow_led_state { "ON" } else { "OFF" },
-            if *self.v1_led_state { "ON" } else { "OFF" },
-            if *self.v2_led_state { "ON" } else { "OFF" }
-        );
     }
 
     pub fn tock(&mut self, ctx: &mut Context) {
@@ -86,13 +165,203 @@ impl GPIOComponent {
     ) {
         <Self as AHBSlavePortInput>::on_ahb_input(self, ctx, msg);
     }
+
+    pub(crate) fn set_interface(&mut self, interface: Option<GpioInterfaceImpl>) {
+        *self.interface_impl = interface;
+    }
+
+    pub(crate) fn take_interface(&mut self) -> Option<GpioInterfaceImpl> {
+        self.interface_impl.take()
+    }
+
+    pub(crate) fn dout(&self) -> u32 {
+        self.dout
+    }
+
+    /// Schedules the host to drive the `dio` pad to `level` at `time`, or to release it.
+    /// A `time` in the past is the nearest future.
+    pub(crate) fn schedule_input(
+        &mut self,
+        ctx: &mut Context,
+        time: Timepoint,
+        dio: u8,
+        level: Option<bool>,
+    ) {
+        let index = self.scheduled_inputs.partition_point(|&(t, ..)| t <= time);
+        self.scheduled_inputs.insert(index, (time, dio, level));
+        if index == 0 {
+            self.schedule_wakeup(ctx);
+        }
+    }
+
+    /// Takes over the pads driven by the host from the `old` instance across a reset.
+    /// The scheduled wakeup event survives the reset of the event queue.
+    /// The pads released by the reset are reported to the observer.
+    pub(crate) fn take_host_inputs(&mut self, ctx: &mut Context, old: &mut Self) {
+        self.host_driven = old.host_driven;
+        self.host_levels = old.host_levels;
+        self.scheduled_inputs = std::mem::take(&mut old.scheduled_inputs);
+        self.wakeup = old.wakeup.take();
+        self.pads = old.pads;
+        self.update_pads(ctx, true);
+    }
+
+    #[handler]
+    pub fn enter_shutdown(&mut self, _ctx: &mut Context) {
+        self.in_shutdown = true;
+    }
+
+    /// Applies the inputs scheduled until now, on their wakeup event.
+    pub(crate) fn apply_scheduled_inputs(&mut self, ctx: &mut Context) {
+        self.wakeup = None;
+        let now = ctx.event_queue().get_current_time();
+        while let Some(&(time, dio, level)) = self.scheduled_inputs.front()
+            && time <= now
+        {
+            self.scheduled_inputs.pop_front();
+            trace!("Host drives DIO{dio} to {level:?}");
+            let bit = 1 << dio;
+            match level {
+                Some(level) => {
+                    self.host_driven |= bit;
+                    self.host_levels = (self.host_levels & !bit) | (u32::from(level) << dio);
+                }
+                None => self.host_driven &= !bit,
+            }
+        }
+        self.update_pads(ctx, false);
+        self.schedule_wakeup(ctx);
+    }
+
+    fn schedule_wakeup(&mut self, ctx: &mut Context) {
+        if let Some(token) = self.wakeup.take() {
+            token.revoke(ctx);
+        }
+        if let Some(&(time, ..)) = self.scheduled_inputs.front() {
+            let now = ctx.event_queue().get_current_time();
+            // The wakeup must not happen in the middle of the current phase.
+            let delay = if time > now {
+                time - now
+            } else {
+                Duration::from_picos(1)
+            };
+            self.wakeup = ctx
+                .event_queue_mut()
+                .add(delay, EventData::Wakeup(WakeupEvent::Gpio));
+        }
+    }
+}
+
+// The pads.
+#[component_impl(gpio)]
+impl GPIOComponent {
+    fn is_inverted(&self, dio: usize) -> bool {
+        // The lowest bit of `IOMODE` is the inversion in all modes.
+        self.iocfg[dio] & (IOCFG::IOMODE::Named::INV << IOCFG::IOMODE::BIT_RANGE.start()) != 0
+    }
+
+    fn pad_level(&self, dio: usize) -> bool {
+        let bit = 1 << dio;
+        let cfg = self.iocfg[dio];
+        if self.doe & bit != 0 && cfg & IOCFG::PORT_ID::BIT_MASK == PORT_ID_GPIO {
+            let output = (self.dout & bit != 0) != self.is_inverted(dio);
+            let mode = (cfg & IOCFG::IOMODE::BIT_MASK) >> IOCFG::IOMODE::BIT_RANGE.start();
+            // The open drain and open source modes release the pad in one of the states.
+            match mode & !IOCFG::IOMODE::Named::INV {
+                IOCFG::IOMODE::Named::OPENDR if output => {}
+                IOCFG::IOMODE::Named::OPENSRC if !output => {}
+                _ => return output,
+            }
+        }
+        if self.host_driven & bit != 0 {
+            return self.host_levels & bit != 0;
+        }
+        match (cfg & IOCFG::PULL_CTL::BIT_MASK) >> IOCFG::PULL_CTL::BIT_RANGE.start() {
+            IOCFG::PULL_CTL::Named::UP => true,
+            IOCFG::PULL_CTL::Named::DWN => false,
+            _ => FLOATING_PADS & bit != 0,
+        }
+    }
+
+    /// Evaluates the pads after a change of the outputs, of their configuration
+    /// or of the inputs driven by the host (`by_mcu` is false), and detects the edges.
+    fn update_pads(&mut self, ctx: &mut Context, by_mcu: bool) {
+        let mut pads = 0;
+        let mut inputs = 0;
+        for dio in 0..DIO_COUNT {
+            let level = self.pad_level(dio);
+            pads |= u32::from(level) << dio;
+            inputs |= u32::from(level != self.is_inverted(dio)) << dio;
+        }
+
+        let changed_pads = pads ^ self.pads;
+        self.pads = pads;
+        if self.in_shutdown {
+            self.detect_shutdown_wakeup(ctx, changed_pads);
+            return;
+        }
+        if by_mcu && changed_pads != 0 {
+            let now = ctx.event_queue().get_current_time();
+            for dio in (0..DIO_COUNT).filter(|dio| changed_pads & (1 << dio) != 0) {
+                let level = pads & (1 << dio) != 0;
+                trace!("MCU drives DIO{dio} to {level}");
+                if let Some(interface) = &mut *self.interface_impl {
+                    #[allow(clippy::cast_possible_truncation)]
+                    interface.pad_changed(now, dio as u8, level);
+                }
+            }
+        }
+
+        let changed_inputs = inputs ^ self.inputs;
+        self.inputs = inputs;
+        let mut edge_irq = false;
+        for dio in (0..DIO_COUNT).filter(|dio| changed_inputs & (1 << dio) != 0) {
+            let rising = inputs & (1 << dio) != 0;
+            let cfg = self.iocfg[dio];
+            let detected =
+                match (cfg & IOCFG::EDGE_DET::BIT_MASK) >> IOCFG::EDGE_DET::BIT_RANGE.start() {
+                    IOCFG::EDGE_DET::Named::POS => rising,
+                    IOCFG::EDGE_DET::Named::NEG => !rising,
+                    IOCFG::EDGE_DET::Named::BOTH => true,
+                    _ => false,
+                };
+            if detected {
+                trace!("Edge detected on DIO{dio}");
+                self.evflags |= 1 << dio;
+                #[allow(clippy::cast_possible_truncation)]
+                let event = AonEvent::try_from(dio as u8).expect("PADn events are numbered by DIO");
+                AONEventProxy.notify(ctx, event);
+                edge_irq |= cfg & IOCFG::EDGE_IRQ_EN::BIT_MASK != 0;
+            }
+        }
+        if edge_irq {
+            AONEventProxy.notify(ctx, AonEvent::PAD);
+        }
+    }
+
+    /// Requests the wakeup from the shutdown if any of the `changed_pads` is configured for it.
+    fn detect_shutdown_wakeup(&mut self, ctx: &mut Context, changed_pads: u32) {
+        for dio in (0..DIO_COUNT).filter(|dio| changed_pads & (1 << dio) != 0) {
+            let rising = self.pads & (1 << dio) != 0;
+            let wu_cfg =
+                (self.iocfg[dio] & IOCFG::WU_CFG::BIT_MASK) >> IOCFG::WU_CFG::BIT_RANGE.start();
+            if wu_cfg == WU_CFG_RISING && rising || wu_cfg == WU_CFG_FALLING && !rising {
+                info!("DIO{dio} wakes up the device from the shutdown");
+                ctx.request_reset(ResetRequest::ShutdownWakeup);
+            }
+        }
+    }
 }
 
+// Reading and writing the registers.
 #[component_impl(gpio)]
 impl GPIOComponent {
     fn get_waitstates_for_address(&mut self, addr: Address, writing: bool) -> u8 {
         debug_assert!(is_unbuffered_alias(addr).is_none());
 
+        if addr.is_in_range(&IOC::ADDR_SPACE) {
+            return 0;
+        }
         let res = address_match_range! {addr,
             GPIO::DIN31_0::ADDR => 3,
             GPIO::EVFLAGS31_0::ADDR => writing.ife(7, 4),
@@ -107,18 +376,53 @@ impl GPIOComponent {
         }
     }
 
-    #[allow(clippy::match_same_arms)] // TODO: get yellow led state
+    fn note_access(&mut self, addr: Address) {
+        // The timing hack applies to the GPIO registers only.
+        if addr.is_in_range(&GPIO::ADDR_SPACE) {
+            self.prev_addr.set_next(addr);
+        }
+    }
+
+    /// Returns the index of the first DIO of a `DOUTx_y` register, with a byte per DIO.
+    fn dout_group(addr: Address) -> Option<usize> {
+        let group = match addr {
+            GPIO::DOUT3_0::ADDR => 0,
+            GPIO::DOUT7_4::ADDR => 1,
+            GPIO::DOUT11_8::ADDR => 2,
+            GPIO::DOUT15_12::ADDR => 3,
+            GPIO::DOUT19_16::ADDR => 4,
+            GPIO::DOUT23_20::ADDR => 5,
+            GPIO::DOUT27_24::ADDR => 6,
+            GPIO::DOUT31_28::ADDR => 7,
+            _ => return None,
+        };
+        Some(group * 4)
+    }
+
+    /// Returns the index of the DIO of an `IOCFGn` register.
+    fn iocfg_index(addr: Address) -> Option<usize> {
+        let offset = addr.offset_from(IOC::ADDR) as usize;
+        (addr.is_in_range(&IOC::ADDR_SPACE) && offset < 4 * DIO_COUNT).then_some(offset / 4)
+    }
+
+    #[allow(clippy::match_same_arms)]
     fn get_data_for_address(&self, addr: Address, #[allow(unused)] ctx: &Context) -> [u8; 4] {
         trace!("gpio read: {:?}", addr);
+        if let Some(first) = Self::dout_group(addr) {
+            return std::array::from_fn(|i| u8::from(self.dout & (1 << (first + i)) != 0));
+        }
+        if let Some(dio) = Self::iocfg_index(addr) {
+            return self.iocfg[dio].to_le_bytes();
+        }
         match addr {
-            GPIO::DOE31_0::ADDR => [0x00, 0x60, 0x10, 0x00],
-            GPIO::DOUT3_0::ADDR => [0x00, 0x00, 0x00, 0x00],
-            GPIO::DOUT7_4::ADDR => [0x00, 0x00, 0x00, 0x00],
-            // Note: this particular "default" value is important, as some tests rely on this
-            // funny value observed on CherryMotes.
-            // This is 0x2000_0000 -> an address in SRAM!
-            GPIO::DIN31_0::ADDR => [0x00, 0x00, 0x00, 0x20],
-            GPIO::EVFLAGS31_0::ADDR => [0x00, 0x00, 0x00, 0x00],
+            GPIO::DOUT31_0::ADDR => self.dout.to_le_bytes(),
+            GPIO::DOE31_0::ADDR => self.doe.to_le_bytes(),
+            GPIO::DIN31_0::ADDR => self.inputs.to_le_bytes(),
+            GPIO::EVFLAGS31_0::ADDR => self.evflags.to_le_bytes(),
+            // The set, clear and toggle registers are write-only.
+            GPIO::DOUTSET31_0::ADDR | GPIO::DOUTCLR31_0::ADDR | GPIO::DOUTTGL31_0::ADDR => {
+                [0x00, 0x00, 0x00, 0x00]
+            }
             // We use those for LDRs with wait states on sysbus.
             // undocumented address, but accessible (accessed by some tests with unaligned reads)
             // (Note: in particular, all unallocated addresses in a 1/4 KB range act like RZ/WI).
@@ -133,84 +437,40 @@ impl GPIOComponent {
                 [0x00, 0x00, 0x00, 0x00]
             }
             _ => unimplemented!(
-                "Requested mocked GPIO data read from address {:?} {}",
+                "Requested GPIO data read from address {:?} {}",
                 addr,
                 ctx.display_named_address(addr)
             ),
         }
     }
 
-    const YELLOW_LED_IO_ID: u8 = 20; // yellow LED on CherryMote
-    const V1_LED_IO_ID: u8 = 13; // VLED1 on CherryMote
-    const V2_LED_IO_ID: u8 = 14; // VLED2 on CherryMote
-    const BL_PIN_IO_ID: u8 = 11; // Bootloader backdoor pin on CherryMote (our Contiki-NG thinks it's Key Select)
-    const D26_PIN_IO_ID: u8 = 26; // D26 pin on CherryMote's JP1 (our Contiki-NG thinks it's Ambient Light Sensor)
-    fn set_data_for_address(&mut self, addr: Address, ctx: &Context, data: [u8; 4]) {
+    fn set_data_for_address(&mut self, addr: Address, ctx: &mut Context, data: [u8; 4]) {
         trace!("gpio write: {:?} {:?}", addr, data);
-        match addr {
-            GPIO::DOUTSET31_0::ADDR => {
-                if u32::from_le_bytes(data) & (1 << Self::YELLOW_LED_IO_ID) != 0 {
-                    self.yellow_led_state.set_next(true);
-                }
-                if u32::from_le_bytes(data) & (1 << Self::V1_LED_IO_ID) != 0 {
-                    self.v1_led_state.set_next(true);
-                }
-                if u32::from_le_bytes(data) & (1 << Self::V2_LED_IO_ID) != 0 {
-                    self.v2_led_state.set_next(true);
-                }
+        let value = u32::from_le_bytes(data);
+        if let Some(first) = Self::dout_group(addr) {
+            for (i, byte) in data.into_iter().enumerate() {
+                let bit = 1 << (first + i);
+                self.dout = (self.dout & !bit) | if byte & 1 != 0 { bit } else { 0 };
             }
-            GPIO::DOUTCLR31_0::ADDR => {
-                if u32::from_le_bytes(data) & (1 << Self::YELLOW_LED_IO_ID) != 0 {
-                    self.yellow_led_state.set_next(false);
-                }
-                if u32::from_le_bytes(data) & (1 << Self::V1_LED_IO_ID) != 0 {
-                    self.v1_led_state.set_next(false);
-                }
-                if u32::from_le_bytes(data) & (1 << Self::V2_LED_IO_ID) != 0 {
-                    self.v2_led_state.set_next(false);
-                }
-            }
-            GPIO::DOUTTGL31_0::ADDR => {
-                if u32::from_le_bytes(data) & (1 << Self::YELLOW_LED_IO_ID) != 0 {
-                    self.yellow_led_state.set_next(!*self.yellow_led_state);
-                }
-                if u32::from_le_bytes(data) & (1 << Self::V1_LED_IO_ID) != 0 {
-                    self.v1_led_state.set_next(!*self.v1_led_state);
-                }
-                if u32::from_le_bytes(data) & (1 << Self::V2_LED_IO_ID) != 0 {
-                    self.v2_led_state.set_next(!*self.v2_led_state);
-                }
-            }
-            GPIO::DOE31_0::ADDR => {
-                let expected_pins = (1 << Self::YELLOW_LED_IO_ID)
-                    | (1 << Self::V1_LED_IO_ID)
-                    | (1 << Self::V2_LED_IO_ID)
-                    | (1 << Self::D26_PIN_IO_ID);
-                if (u32::from_le_bytes(data) & !expected_pins) != 0 {
-                    unimplemented!(
-                        "Configuring these pins as Data Out is not supported: {:0>32x}",
-                        u32::from_le_bytes(data) & !expected_pins
-                    );
-                }
-                /* Do nothing: currently we don't emulate configuring DIO pins. */
-            }
-            GPIO::EVFLAGS31_0::ADDR => {
-                let expected_pins = 1 << Self::BL_PIN_IO_ID;
-                if (u32::from_le_bytes(data) & !expected_pins) != 0 {
-                    unimplemented!(
-                        "Clearing these events is not supported: {:0>32x}",
-                        u32::from_le_bytes(data) & !expected_pins
-                    );
-                }
-                /* Do nothing: currently we don't emulate clearing DIO events. */
+        } else if let Some(dio) = Self::iocfg_index(addr) {
+            self.iocfg[dio] = value;
+        } else {
+            match addr {
+                GPIO::DOUT31_0::ADDR => self.dout = value,
+                GPIO::DOUTSET31_0::ADDR => self.dout |= value,
+                GPIO::DOUTCLR31_0::ADDR => self.dout &= !value,
+                GPIO::DOUTTGL31_0::ADDR => self.dout ^= value,
+                GPIO::DOE31_0::ADDR => self.doe = value,
+                GPIO::EVFLAGS31_0::ADDR => self.evflags &= !value,
+                _ => panic!(
+                    "Requested GPIO data {:?} write to address {:?} {}",
+                    data,
+                    addr,
+                    ctx.display_named_address(addr)
+                ),
             }
-            _ => panic!(
-                "Requested mocked GPIO data {:?} write to address {:?} {}",
-                data,
-                addr,
-                ctx.display_named_address(addr)
-            ),
         }
+        self.update_pads(ctx, true);
     }
 }
 
@@ -253,7 +513,7 @@ impl AlignedFakingHandler for GPIOComponent {
     }
 
     fn read(slave: &mut Self::Component, ctx: &mut Context, address: Address) -> Self::Native {
-        slave.prev_addr.set_next(address);
+        slave.note_access(address);
         slave.get_data_for_address(address, ctx)
     }
 
@@ -266,7 +526,7 @@ impl AlignedFakingHandler for GPIOComponent {
     }
 
     fn write(slave: &mut Self::Component, ctx: &mut Context, address: Address, data: Self::Native) {
-        slave.prev_addr.set_next(address);
+        slave.note_access(address);
         slave.set_data_for_address(address, ctx, data);
     }
 }
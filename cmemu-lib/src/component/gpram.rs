@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use std::ops::Range;
 
 use cc2650_constants as soc;
@@ -22,6 +23,7 @@ use crate::engine::{
     DisableableComponent, MainComponent, SkippableClockTreeNode, TickComponent, TickComponentExtra,
 };
 use crate::proxy::GPRAMProxy;
+use log::info;
 
 impl MemoryConfiguration for GPRAMMemory {
     const IS_WRITABLE: bool = true;
@@ -31,7 +33,15 @@ impl MemoryConfiguration for GPRAMMemory {
 }
 type GPRAMMemory = Memory<MemorySC>;
 
-#[derive(MainComponent, SkippableClockTreeNode, TickComponent, TickComponentExtra)]
+#[derive(
+    Clone,
+    MainComponent,
+    SkippableClockTreeNode,
+    TickComponent,
+    TickComponentExtra,
+    Serialize,
+    Deserialize,
+)]
 #[skippable_if_disableable]
 pub(crate) struct GPRAMComponent {
     #[subcomponent(MemorySC)]
@@ -46,6 +56,13 @@ impl GPRAMComponent {
         }
     }
 
+    /// The contents are lost, as by powering off VIMS without the retention of its RAM.
+    #[handler]
+    pub(crate) fn lose_contents(&mut self, _ctx: &mut Context) {
+        info!("GPRAM is not retained, its contents are lost");
+        self.memory.lose_contents(soc::GPRAM::ADDR_SPACE);
+    }
+
     pub(crate) fn tick(&mut self, ctx: &mut Context) {
         GPRAMMemory::run_driver(self, ctx);
     }
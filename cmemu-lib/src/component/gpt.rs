@@ -0,0 +1,514 @@
+pub const GPT_ROUTE_INJECTION: Range<Address> = GPT0::ADDR..GPT3::ADDR_SPACE.end;
+
+use crate::bridge_ports;
+#[proxy_use]
+use crate::build_data::{ClockTreeNodes, EnergyEntity};
+use crate::common::new_ahb::databus::DataBus;
+use crate::common::new_ahb::ports::AHBSlavePortProxiedInput;
+#[proxy_use]
+use crate::common::new_ahb::ports::{AHBPortConfig, AHBSlavePortInput};
+#[proxy_use]
+use crate::common::new_ahb::signals::{MasterToSlaveWires, Size};
+use crate::common::new_ahb::slave_driver::WriteMode;
+use crate::common::new_ahb::slave_driver::faking_slave_driver::{
+    AlignedFakingHandler, FakingIface, WaitstatesOrErr,
+};
+use crate::component::event_fabric::EventFabricEvent;
+#[proxy_use]
+use crate::engine::Context;
+use crate::engine::{
+    DisableableComponent, MainComponent, SkippableClockTreeNode, TickComponent, TickComponentExtra,
+};
+use crate::proxy::{EventFabricProxy, GPTProxy};
+use cc2650_constants::{GPT0, GPT1, GPT2, GPT3};
+use cmemu_common::Address;
+use cmemu_proc_macros::{component_impl, handler, proxy_use};
+use log::{trace, warn};
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use timer::{Timer, TimerEvents};
+
+mod timer;
+
+// [TI-TRM] 13 General-Purpose Timers
+// Each of the four GPT modules has two 16-bit timers, A and B, which can be concatenated
+// into a single 32-bit timer. The modules are clocked by the MCU clock through the per-module
+// clock gates of the PRCM. The timers are daisy-chained in the order GPT0A, GPT0B, GPT1A, ...
+// for the wait-on-trigger feature.
+// Not implemented: the snapshot mode, the compare actions on the CCP output (`TnMR.TCACT`),
+// stalling by the debugger and the `ANDCCP` output combination.
+
+const MODULES_COUNT: usize = 4;
+
+const MODULE_BASES: [Address; MODULES_COUNT] = [GPT0::ADDR, GPT1::ADDR, GPT2::ADDR, GPT3::ADDR];
+
+const CLOCK_GATES: [ClockTreeNodes; MODULES_COUNT] = [
+    ClockTreeNodes::Gpt0Gate,
+    ClockTreeNodes::Gpt1Gate,
+    ClockTreeNodes::Gpt2Gate,
+    ClockTreeNodes::Gpt3Gate,
+];
+
+/// Interrupt events of Timer A and Timer B of each module.
+const INTERRUPT_EVENTS: [[EventFabricEvent; 2]; MODULES_COUNT] = [
+    [EventFabricEvent::GPT0A, EventFabricEvent::GPT0B],
+    [EventFabricEvent::GPT1A, EventFabricEvent::GPT1B],
+    [EventFabricEvent::GPT2A, EventFabricEvent::GPT2B],
+    [EventFabricEvent::GPT3A, EventFabricEvent::GPT3B],
+];
+
+/// DMA trigger events of Timer A and Timer B of each module, selected by `DMAEV`.
+const DMA_EVENTS: [[EventFabricEvent; 2]; MODULES_COUNT] = [
+    [
+        EventFabricEvent::GPT0A_DMABREQ,
+        EventFabricEvent::GPT0B_DMABREQ,
+    ],
+    [
+        EventFabricEvent::GPT1A_DMABREQ,
+        EventFabricEvent::GPT1B_DMABREQ,
+    ],
+    [
+        EventFabricEvent::GPT2A_DMABREQ,
+        EventFabricEvent::GPT2B_DMABREQ,
+    ],
+    [
+        EventFabricEvent::GPT3A_DMABREQ,
+        EventFabricEvent::GPT3B_DMABREQ,
+    ],
+];
+
+/// Interrupt bits of Timer A in `IMR`, `RIS`, `MIS` and `ICLR`. Timer B bits are shifted by 8.
+const TIMER_A_INTERRUPTS: u32 = GPT0::RIS::TATORIS::BIT_MASK
+    | GPT0::RIS::CAMRIS::BIT_MASK
+    | GPT0::RIS::CAERIS::BIT_MASK
+    | GPT0::RIS::TAMRIS::BIT_MASK
+    | GPT0::RIS::DMAARIS::BIT_MASK;
+const TIMER_B_SHIFT: u32 = 8;
+
+/// Timer A or Timer B of a module.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum TimerHalf {
+    A,
+    B,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct GptModule {
+    cfg: u32,
+    timer_a: Timer,
+    timer_b: Timer,
+    imr: u32,
+    ris: u32,
+    dmaev: u32,
+    andccp: u32,
+}
+
+impl GptModule {
+    fn new() -> Self {
+        Self {
+            cfg: GPT0::CFG::RESET_VALUE,
+            timer_a: Timer::new(GPT0::TAILR::RESET_VALUE, GPT0::TAMATCHR::RESET_VALUE),
+            timer_b: Timer::new(GPT0::TBILR::RESET_VALUE, GPT0::TBMATCHR::RESET_VALUE),
+            imr: GPT0::IMR::RESET_VALUE,
+            ris: GPT0::RIS::RESET_VALUE,
+            dmaev: GPT0::DMAEV::RESET_VALUE,
+            andccp: GPT0::ANDCCP::RESET_VALUE,
+        }
+    }
+
+    /// In the 32-bit configuration, Timer A controls the concatenated timer.
+    fn is_concatenated(&self) -> bool {
+        self.cfg & GPT0::CFG::CFG::BIT_MASK == GPT0::CFG::CFG::E::_32BIT_TIMER
+    }
+
+    fn timer(&self, half: TimerHalf) -> &Timer {
+        match half {
+            TimerHalf::A => &self.timer_a,
+            TimerHalf::B => &self.timer_b,
+        }
+    }
+
+    fn timer_mut(&mut self, half: TimerHalf) -> &mut Timer {
+        match half {
+            TimerHalf::A => &mut self.timer_a,
+            TimerHalf::B => &mut self.timer_b,
+        }
+    }
+
+    fn halves(&self) -> &'static [TimerHalf] {
+        if self.is_concatenated() {
+            &[TimerHalf::A]
+        } else {
+            &[TimerHalf::A, TimerHalf::B]
+        }
+    }
+
+    fn is_running(&self) -> bool {
+        self.halves()
+            .iter()
+            .any(|&half| self.timer(half).is_running())
+    }
+
+    fn ctl(&self) -> u32 {
+        self.timer_a.ctl() | (self.timer_b.ctl() << TIMER_B_SHIFT)
+    }
+
+    fn set_ctl(&mut self, value: u32) {
+        let timer_a_mask = (1 << TIMER_B_SHIFT) - 1;
+        self.timer_a.set_ctl(value & timer_a_mask);
+        self.timer_b
+            .set_ctl((value >> TIMER_B_SHIFT) & timer_a_mask);
+    }
+
+    fn set_cfg(&mut self, value: u32) {
+        self.cfg = value & GPT0::CFG::CFG::BIT_MASK;
+        let concatenated = self.is_concatenated();
+        self.timer_a.set_concatenated(concatenated);
+        self.timer_b.set_concatenated(false);
+    }
+}
+
+type BusDriver = FakingIface<DriverSC, GPTComponent>;
+
+#[derive(
+    Clone,
+    MainComponent,
+    TickComponent,
+    TickComponentExtra,
+    DisableableComponent,
+    Serialize,
+    Deserialize,
+)]
+pub(crate) struct GPTComponent {
+    #[subcomponent(DriverSC)]
+    driver: BusDriver,
+
+    modules: [GptModule; MODULES_COUNT],
+}
+
+#[component_impl(gpt)]
+impl GPTComponent {
+    pub(crate) fn new() -> Self {
+        Self {
+            driver: Default::default(),
+
+            modules: [
+                GptModule::new(),
+                GptModule::new(),
+                GptModule::new(),
+                GptModule::new(),
+            ],
+        }
+    }
+
+    pub(crate) fn tick(&mut self, ctx: &mut Context) {
+        BusDriver::run_driver(self, ctx);
+
+        for index in 0..MODULES_COUNT {
+            if !self.modules[index].is_running() || !Self::is_clock_enabled(ctx, index) {
+                continue;
+            }
+            for &half in self.modules[index].halves() {
+                let events = self.modules[index].timer_mut(half).tick();
+                self.handle_events(ctx, index, half, events);
+            }
+        }
+    }
+
+    pub(crate) fn tock(&mut self, ctx: &mut Context) {
+        BusDriver::tock(self, ctx);
+    }
+
+    #[handler]
+    pub(crate) fn on_new_ahb_slave_input(
+        &mut self,
+        ctx: &mut Context,
+        msg: MasterToSlaveWires<<GPTComponent as AHBPortConfig>::Data>,
+    ) {
+        <Self as AHBSlavePortInput>::on_ahb_input(self, ctx, msg);
+    }
+
+    /// An edge on the capture input of a timer, as routed by `EVENT:GPTnmCAPTSEL`.
+    #[allow(dead_code)] // TODO: there are no capture inputs until the IOC is implemented
+    pub(crate) fn capture_edge(
+        &mut self,
+        ctx: &mut Context,
+        module: usize,
+        half: TimerHalf,
+        rising: bool,
+    ) {
+        let events = self.modules[module].timer_mut(half).capture_edge(rising);
+        self.handle_events(ctx, module, half, events);
+    }
+
+    fn is_clock_enabled(ctx: &Context, index: usize) -> bool {
+        ctx.get_energy_state_of(EnergyEntity::ClockTree(CLOCK_GATES[index]))
+            .is_active()
+    }
+
+    fn handle_events(
+        &mut self,
+        ctx: &mut Context,
+        index: usize,
+        half: TimerHalf,
+        events: TimerEvents,
+    ) {
+        if events.interrupts != 0 {
+            let shift = match half {
+                TimerHalf::A => 0,
+                TimerHalf::B => TIMER_B_SHIFT,
+            };
+            let module = &mut self.modules[index];
+            let interrupts = events.interrupts << shift;
+            trace!("GPT{index}{half:?} events: {interrupts:#x}");
+            module.ris |= interrupts;
+            if interrupts & module.imr != 0 {
+                EventFabricProxy.notify(ctx, INTERRUPT_EVENTS[index][half as usize]);
+            }
+            if interrupts & module.dmaev != 0 {
+                EventFabricProxy.notify(ctx, DMA_EVENTS[index][half as usize]);
+            }
+        }
+        if events.timeout {
+            self.trigger_next(index, half);
+        }
+    }
+
+    /// [TI-TRM] 13.4.7 Wait-for-Trigger Mode: a time-out triggers the next timer in the chain.
+    fn trigger_next(&mut self, index: usize, half: TimerHalf) {
+        let (index, half) = match half {
+            TimerHalf::A if !self.modules[index].is_concatenated() => (index, TimerHalf::B),
+            _ => (index + 1, TimerHalf::A),
+        };
+        if let Some(module) = self.modules.get_mut(index) {
+            module.timer_mut(half).trigger();
+        }
+    }
+
+    fn raise_unmasked(&self, ctx: &mut Context, index: usize, newly_unmasked: u32) {
+        let pending = self.modules[index].ris & newly_unmasked;
+        if pending & TIMER_A_INTERRUPTS != 0 {
+            EventFabricProxy.notify(ctx, INTERRUPT_EVENTS[index][0]);
+        }
+        if pending & (TIMER_A_INTERRUPTS << TIMER_B_SHIFT) != 0 {
+            EventFabricProxy.notify(ctx, INTERRUPT_EVENTS[index][1]);
+        }
+    }
+
+    fn cycles_until_event(&self, ctx: &Context) -> Option<u64> {
+        self.modules
+            .iter()
+            .enumerate()
+            .filter(|&(index, _)| Self::is_clock_enabled(ctx, index))
+            .flat_map(|(_, module)| {
+                module
+                    .halves()
+                    .iter()
+                    .filter_map(|&half| module.timer(half).cycles_until_event())
+            })
+            .min()
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn decode_address(addr: Address) -> (usize, u32) {
+        let index = (addr.offset_from(GPT0::ADDR) / GPT0::SIZE) as usize;
+        (index, addr.offset_from(MODULE_BASES[index]))
+    }
+
+    fn get_data_for_address(&self, ctx: &Context, addr: Address) -> u32 {
+        let (index, offset) = Self::decode_address(addr);
+        let module = &self.modules[index];
+        let (a, b) = (&module.timer_a, &module.timer_b);
+        match offset {
+            GPT0::CFG::OFFSET => module.cfg,
+            GPT0::TAMR::OFFSET => a.mode_register(),
+            GPT0::TBMR::OFFSET => b.mode_register(),
+            GPT0::CTL::OFFSET => module.ctl(),
+            GPT0::SYNC::OFFSET | GPT0::ICLR::OFFSET => 0,
+            GPT0::IMR::OFFSET => module.imr,
+            GPT0::RIS::OFFSET => module.ris,
+            GPT0::MIS::OFFSET => module.ris & module.imr,
+            GPT0::TAILR::OFFSET => a.interval_load(),
+            GPT0::TBILR::OFFSET => b.interval_load(),
+            GPT0::TAMATCHR::OFFSET => a.match_value(),
+            GPT0::TBMATCHR::OFFSET => b.match_value(),
+            GPT0::TAPR::OFFSET => a.prescale(),
+            GPT0::TBPR::OFFSET => b.prescale(),
+            GPT0::TAPMR::OFFSET => a.prescale_match(),
+            GPT0::TBPMR::OFFSET => b.prescale_match(),
+            GPT0::TAR::OFFSET => a.register(),
+            GPT0::TBR::OFFSET => b.register(),
+            GPT0::TAV::OFFSET => a.value(),
+            GPT0::TBV::OFFSET => b.value(),
+            GPT0::TAPS::OFFSET | GPT0::TAPV::OFFSET => a.prescaler_value(),
+            GPT0::TBPS::OFFSET | GPT0::TBPV::OFFSET => b.prescaler_value(),
+            GPT0::DMAEV::OFFSET => module.dmaev,
+            GPT0::VERSION::OFFSET => GPT0::VERSION::RESET_VALUE,
+            GPT0::ANDCCP::OFFSET => module.andccp,
+            _ => unimplemented!(
+                "Requested GPT data read from address {:?}: {}",
+                addr,
+                ctx.display_named_address(addr)
+            ),
+        }
+    }
+
+    fn set_data_for_address(&mut self, ctx: &mut Context, addr: Address, data: u32) {
+        let (index, offset) = Self::decode_address(addr);
+        let module = &mut self.modules[index];
+        match offset {
+            GPT0::CFG::OFFSET => module.set_cfg(data),
+            GPT0::TAMR::OFFSET => module.timer_a.set_mode_register(data),
+            GPT0::TBMR::OFFSET => module.timer_b.set_mode_register(data),
+            GPT0::CTL::OFFSET => module.set_ctl(data),
+            // [TI-TRM] 13.5.1.5 SYNC Register: only in GPT0, resets the selected timers.
+            GPT0::SYNC::OFFSET if index == 0 => {
+                for (module, bits) in self.modules.iter_mut().zip((0..).step_by(2)) {
+                    let sync = data >> bits;
+                    if sync & 1 != 0 {
+                        module.timer_a.synchronize();
+                    }
+                    if sync & 2 != 0 {
+                        module.timer_b.synchronize();
+                    }
+                }
+            }
+            GPT0::IMR::OFFSET => {
+                let newly_unmasked = data & !module.imr;
+                module.imr = data;
+                self.raise_unmasked(ctx, index, newly_unmasked);
+            }
+            GPT0::ICLR::OFFSET => module.ris &= !data,
+            GPT0::TAILR::OFFSET => module.timer_a.set_interval_load(data),
+            GPT0::TBILR::OFFSET => module.timer_b.set_interval_load(data),
+            GPT0::TAMATCHR::OFFSET => module.timer_a.set_match_value(data),
+            GPT0::TBMATCHR::OFFSET => module.timer_b.set_match_value(data),
+            GPT0::TAPR::OFFSET => module.timer_a.set_prescale(data),
+            GPT0::TBPR::OFFSET => module.timer_b.set_prescale(data),
+            GPT0::TAPMR::OFFSET => module.timer_a.set_prescale_match(data),
+            GPT0::TBPMR::OFFSET => module.timer_b.set_prescale_match(data),
+            GPT0::TAV::OFFSET => module.timer_a.set_value(data),
+            GPT0::TBV::OFFSET => module.timer_b.set_value(data),
+            GPT0::DMAEV::OFFSET => module.dmaev = data,
+            GPT0::ANDCCP::OFFSET => module.andccp = data,
+            _ => unimplemented!(
+                "Requested GPT data write {:#x} to address {:?}: {}",
+                data,
+                addr,
+                ctx.display_named_address(addr)
+            ),
+        }
+    }
+
+    fn warn_if_gated(ctx: &Context, addr: Address) {
+        let (index, _) = Self::decode_address(addr);
+        if !Self::is_clock_enabled(ctx, index) {
+            warn!(
+                "Accessing {} while the clock of GPT{index} is gated.",
+                ctx.display_named_address(addr)
+            );
+        }
+    }
+}
+
+bridge_ports!(@slave GPTComponent => @auto_configured @slave BusDriver);
+
+#[component_impl(gpt)]
+impl AHBPortConfig for GPTComponent {
+    type Data = DataBus;
+    type Component = Self;
+    const TAG: &'static str = "GPT";
+}
+
+#[component_impl(gpt)]
+impl AHBSlavePortProxiedInput for GPTComponent {
+    fn proxy_ahb_input(ctx: &mut Context, msg: MasterToSlaveWires<Self::Data>) {
+        GPTProxy.on_new_ahb_slave_input(ctx, msg);
+    }
+}
+
+#[component_impl(gpt)]
+impl AlignedFakingHandler for GPTComponent {
+    const WRITE_MODE: WriteMode = WriteMode::Combinatorial;
+    const ALIGN: Size = Size::Word;
+    type Native = u32;
+
+    fn read_for_write_filler(
+        slave: &Self::Component,
+        ctx: &Context,
+        address: Address,
+    ) -> Self::Native {
+        slave.get_data_for_address(ctx, address)
+    }
+
+    fn pre_read(
+        _slave: &mut Self::Component,
+        ctx: &mut Context,
+        address: Address,
+    ) -> WaitstatesOrErr {
+        Self::warn_if_gated(ctx, address);
+        Ok(0)
+    }
+
+    fn read(slave: &mut Self::Component, ctx: &mut Context, address: Address) -> Self::Native {
+        let data = slave.get_data_for_address(ctx, address);
+        trace!(
+            "read {address:?} (\"{}\") = {data:#x}",
+            ctx.display_named_address(address)
+        );
+        data
+    }
+
+    fn pre_write(
+        _slave: &mut Self::Component,
+        ctx: &mut Context,
+        address: Address,
+    ) -> WaitstatesOrErr {
+        Self::warn_if_gated(ctx, address);
+        Ok(0)
+    }
+
+    fn write(slave: &mut Self::Component, ctx: &mut Context, address: Address, data: Self::Native) {
+        trace!(
+            "write {address:?} (\"{}\") {data:#x}",
+            ctx.display_named_address(address)
+        );
+        slave.set_data_for_address(ctx, address, data);
+    }
+}
+
+#[component_impl(gpt)]
+impl SkippableClockTreeNode for GPTComponent {
+    fn max_cycles_to_skip(
+        comp: &mut Self::Component,
+        ctx: &mut Context,
+        _parent: Self::IdSpace,
+        _extra: &mut Self::Extra,
+    ) -> u64 {
+        if !comp.can_be_disabled_now() {
+            0
+        } else if let Some(cycles) = comp.cycles_until_event(ctx) {
+            // -1 because the events are raised only by ticks
+            cycles.saturating_sub(1)
+        } else {
+            u64::MAX
+        }
+    }
+
+    fn emulate_skipped_cycles(
+        comp: &mut Self::Component,
+        ctx: &mut Context,
+        _parent: Self::IdSpace,
+        _extra: &mut Self::Extra,
+        skipped_cycles: u64,
+    ) {
+        for (index, module) in comp.modules.iter_mut().enumerate() {
+            if !Self::is_clock_enabled(ctx, index) {
+                continue;
+            }
+            for &half in module.halves() {
+                module.timer_mut(half).skip_cycles(skipped_cycles);
+            }
+        }
+    }
+}
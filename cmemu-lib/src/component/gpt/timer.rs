@@ -0,0 +1,453 @@
+//! A single timer of a GPT module: Timer A or Timer B, or both concatenated into a 32-bit timer.
+//!
+//! Both timers share the layout of their registers, so Timer A names are used for both.
+//! The interrupt bits returned are in the Timer A layout of `RIS`.
+
+use cc2650_constants::GPT0::{CTL, RIS, TAMR};
+use serde::{Deserialize, Serialize};
+
+/// Modes of operation selected by `TnMR`.
+/// [TI-TRM] 13.4 Timer Modes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Mode {
+    OneShot,
+    Periodic,
+    EdgeCount,
+    EdgeTime,
+    Pwm,
+}
+
+/// Outcome of a change of the timer state.
+#[derive(Clone, Copy, Debug, Default)]
+pub(super) struct TimerEvents {
+    /// Raw interrupt bits in the Timer A layout.
+    pub(super) interrupts: u32,
+    /// The timer reached its time-out, which triggers the next timer in the daisy chain.
+    pub(super) timeout: bool,
+}
+
+impl TimerEvents {
+    fn interrupt(bit: u32) -> Self {
+        Self {
+            interrupts: bit,
+            timeout: false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(super) struct Timer {
+    /// `TnMR`
+    mode: u32,
+    /// `CTL` bits of this timer, in the Timer A layout.
+    ctl: u32,
+    /// `TnILR`
+    interval_load: u32,
+    /// `TnMATCHR`
+    match_value: u32,
+    /// `TnPR`
+    prescale: u32,
+    /// `TnPMR`
+    prescale_match: u32,
+
+    /// The counter, including the prescaler bits if it acts as a timer extension.
+    counter: u32,
+    /// The prescaler counter if it acts as a true prescaler.
+    prescaler_counter: u32,
+    /// `TnR` in the Edge-Time mode.
+    captured: u32,
+    /// `CFG` selects a single 32-bit timer instead of two 16-bit timers.
+    concatenated: bool,
+    /// Waiting for the time-out of the previous timer in the daisy chain (`TnMR.TnWOT`).
+    waiting_for_trigger: bool,
+    /// Level of the PWM output before the inversion by `CTL.TnPWML`.
+    pwm_output: bool,
+}
+
+impl Timer {
+    pub(super) fn new(interval_load: u32, match_value: u32) -> Self {
+        Self {
+            mode: TAMR::RESET_VALUE,
+            ctl: CTL::RESET_VALUE,
+            interval_load,
+            match_value,
+            prescale: 0,
+            prescale_match: 0,
+
+            counter: interval_load,
+            prescaler_counter: 0,
+            captured: interval_load,
+            concatenated: false,
+            waiting_for_trigger: false,
+            pwm_output: false,
+        }
+    }
+
+    fn mode(&self) -> Option<Mode> {
+        let alternate = self.mode & TAMR::TAAMS::BIT_MASK != 0;
+        let edge_time = self.mode & TAMR::TACM::BIT_MASK != 0;
+        match self.mode & TAMR::TAMR::BIT_MASK {
+            TAMR::TAMR::E::PERIODIC if alternate => Some(Mode::Pwm),
+            TAMR::TAMR::E::ONE_SHOT => Some(Mode::OneShot),
+            TAMR::TAMR::E::PERIODIC => Some(Mode::Periodic),
+            TAMR::TAMR::E::CAPTURE if edge_time => Some(Mode::EdgeTime),
+            TAMR::TAMR::E::CAPTURE => Some(Mode::EdgeCount),
+            _ => None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.ctl & CTL::TAEN::BIT_MASK != 0
+    }
+
+    fn counts_up(&self) -> bool {
+        // [TI-TRM] 13.4.5 PWM Mode: the timer is a down-counter.
+        self.mode & TAMR::TACDIR::BIT_MASK != 0 && self.mode() != Some(Mode::Pwm)
+    }
+
+    /// [TI-TRM] 13.4.1 One-Shot or Periodic Timer Mode: in the 16-bit modes, the prescaler
+    /// divides the clock. In the capture and PWM modes, it extends the counter to 24 bits.
+    fn has_true_prescaler(&self) -> bool {
+        !self.concatenated && matches!(self.mode(), Some(Mode::OneShot | Mode::Periodic))
+    }
+
+    fn counter_mask(&self) -> u32 {
+        if self.concatenated {
+            u32::MAX
+        } else if self.has_true_prescaler() {
+            0xFFFF
+        } else {
+            0xFF_FFFF
+        }
+    }
+
+    fn load_value(&self) -> u32 {
+        if self.concatenated || self.has_true_prescaler() {
+            self.interval_load & self.counter_mask()
+        } else {
+            (self.interval_load & 0xFFFF) | ((self.prescale & 0xFF) << 16)
+        }
+    }
+
+    fn compare_value(&self) -> u32 {
+        if self.concatenated || self.has_true_prescaler() {
+            self.match_value & self.counter_mask()
+        } else {
+            (self.match_value & 0xFFFF) | ((self.prescale_match & 0xFF) << 16)
+        }
+    }
+
+    fn prescaler_load(&self) -> u32 {
+        if self.has_true_prescaler() {
+            self.prescale & 0xFF
+        } else {
+            0
+        }
+    }
+
+    fn reload(&mut self) {
+        self.counter = if self.counts_up() {
+            0
+        } else {
+            self.load_value()
+        };
+        self.prescaler_counter = self.prescaler_load();
+    }
+
+    /// Whether the timer counts the clock cycles.
+    pub(super) fn is_running(&self) -> bool {
+        self.is_enabled()
+            && !self.waiting_for_trigger
+            && matches!(
+                self.mode(),
+                Some(Mode::OneShot | Mode::Periodic | Mode::EdgeTime | Mode::Pwm)
+            )
+    }
+
+    pub(super) fn set_concatenated(&mut self, concatenated: bool) {
+        self.concatenated = concatenated;
+    }
+
+    // Registers interface
+
+    pub(super) fn mode_register(&self) -> u32 {
+        self.mode
+    }
+
+    pub(super) fn set_mode_register(&mut self, value: u32) {
+        self.mode = value;
+        self.reload();
+    }
+
+    pub(super) fn ctl(&self) -> u32 {
+        self.ctl
+    }
+
+    pub(super) fn set_ctl(&mut self, value: u32) {
+        let enabling = value & CTL::TAEN::BIT_MASK != 0 && !self.is_enabled();
+        self.ctl = value;
+        if enabling && self.mode & TAMR::TAWOT::BIT_MASK != 0 {
+            self.waiting_for_trigger = true;
+        }
+    }
+
+    pub(super) fn interval_load(&self) -> u32 {
+        self.interval_load
+    }
+
+    /// Unless `TnMR.TnILD` is set, the new value is loaded into the counter immediately.
+    pub(super) fn set_interval_load(&mut self, value: u32) {
+        self.interval_load = value;
+        let update_on_timeout = self.mode & TAMR::TAILD::BIT_MASK != 0 && self.is_enabled();
+        if !self.counts_up() && !update_on_timeout {
+            self.reload();
+        }
+    }
+
+    pub(super) fn match_value(&self) -> u32 {
+        self.match_value
+    }
+
+    pub(super) fn set_match_value(&mut self, value: u32) {
+        self.match_value = value;
+    }
+
+    pub(super) fn prescale(&self) -> u32 {
+        self.prescale
+    }
+
+    pub(super) fn set_prescale(&mut self, value: u32) {
+        self.prescale = value;
+    }
+
+    pub(super) fn prescale_match(&self) -> u32 {
+        self.prescale_match
+    }
+
+    pub(super) fn set_prescale_match(&mut self, value: u32) {
+        self.prescale_match = value;
+    }
+
+    /// `TnV` - the free-running value of the counter with the prescaler.
+    pub(super) fn value(&self) -> u32 {
+        if self.has_true_prescaler() {
+            self.counter | (self.prescaler_counter << 16)
+        } else {
+            self.counter
+        }
+    }
+
+    pub(super) fn set_value(&mut self, value: u32) {
+        self.counter = value & self.counter_mask();
+        if self.has_true_prescaler() {
+            self.prescaler_counter = (value >> 16) & 0xFF;
+        }
+    }
+
+    /// `TnR` - the time captured in the Edge-Time mode, otherwise the value of the counter.
+    pub(super) fn register(&self) -> u32 {
+        if self.mode() == Some(Mode::EdgeTime) {
+            self.captured
+        } else {
+            self.value()
+        }
+    }
+
+    /// `TnPS` and `TnPV` - the prescaler counter.
+    pub(super) fn prescaler_value(&self) -> u32 {
+        if self.has_true_prescaler() {
+            self.prescaler_counter
+        } else {
+            self.counter >> 16
+        }
+    }
+
+    // Events
+
+    /// The previous timer in the daisy chain timed out.
+    pub(super) fn trigger(&mut self) {
+        self.waiting_for_trigger = false;
+    }
+
+    /// Reset by a write to `SYNC`, which does not raise interrupts.
+    pub(super) fn synchronize(&mut self) {
+        self.reload();
+    }
+
+    /// A clock cycle of the timer.
+    pub(super) fn tick(&mut self) -> TimerEvents {
+        if !self.is_running() {
+            return TimerEvents::default();
+        }
+        if self.prescaler_counter > 0 {
+            self.prescaler_counter -= 1;
+            return TimerEvents::default();
+        }
+        self.prescaler_counter = self.prescaler_load();
+        self.step()
+    }
+
+    fn step(&mut self) -> TimerEvents {
+        let mode = self.mode().expect("checked by is_running");
+        let at_timeout = if self.counts_up() {
+            self.counter >= self.load_value()
+        } else {
+            self.counter == 0
+        };
+        if at_timeout {
+            return self.timeout(mode);
+        }
+
+        if self.counts_up() {
+            self.counter += 1;
+        } else {
+            self.counter -= 1;
+        }
+        if self.counter != self.compare_value() {
+            return TimerEvents::default();
+        }
+        match mode {
+            Mode::OneShot | Mode::Periodic if self.mode & TAMR::TAMIE::BIT_MASK != 0 => {
+                TimerEvents::interrupt(RIS::TAMRIS::BIT_MASK)
+            }
+            Mode::Pwm => self.set_pwm_output(false),
+            _ => TimerEvents::default(),
+        }
+    }
+
+    fn timeout(&mut self, mode: Mode) -> TimerEvents {
+        self.reload();
+        let interrupts = match mode {
+            Mode::OneShot | Mode::Periodic if self.mode & TAMR::TACINTD::BIT_MASK != 0 => 0,
+            Mode::OneShot | Mode::Periodic | Mode::EdgeTime => RIS::TATORIS::BIT_MASK,
+            Mode::Pwm => self.set_pwm_output(true).interrupts,
+            Mode::EdgeCount => unreachable!("Edge-Count mode does not count the clock"),
+        };
+        if mode == Mode::OneShot {
+            self.ctl &= !CTL::TAEN::BIT_MASK;
+        }
+        TimerEvents {
+            interrupts,
+            timeout: true,
+        }
+    }
+
+    /// [TI-TRM] 13.4.5 PWM Mode: the output is asserted when the counter is reloaded
+    /// and deasserted when it matches `TnMATCHR`. `CTL.TnEVENT` selects the edges
+    /// raising the capture event interrupt, if enabled by `TnMR.TnPWMIE`.
+    fn set_pwm_output(&mut self, level: bool) -> TimerEvents {
+        let changed = self.pwm_output != level;
+        self.pwm_output = level;
+        let inverted = self.ctl & CTL::TAPWML::BIT_MASK != 0;
+        if changed
+            && self.mode & TAMR::TAPWMIE::BIT_MASK != 0
+            && self.edge_selected(level != inverted)
+        {
+            TimerEvents::interrupt(RIS::CAERIS::BIT_MASK)
+        } else {
+            TimerEvents::default()
+        }
+    }
+
+    fn edge_selected(&self, rising: bool) -> bool {
+        match (self.ctl & CTL::TAEVENT::BIT_MASK) >> 2 {
+            CTL::TAEVENT::E::POS => rising,
+            CTL::TAEVENT::E::NEG => !rising,
+            CTL::TAEVENT::E::BOTH => true,
+            _ => false,
+        }
+    }
+
+    /// An edge on the capture input of the timer.
+    /// [TI-TRM] 13.4.3 Input Edge-Count Mode and 13.4.4 Input Edge-Time Mode
+    pub(super) fn capture_edge(&mut self, rising: bool) -> TimerEvents {
+        if !self.is_enabled() || self.waiting_for_trigger || !self.edge_selected(rising) {
+            return TimerEvents::default();
+        }
+        match self.mode() {
+            Some(Mode::EdgeCount) => {
+                if self.counts_up() {
+                    self.counter = self.counter.wrapping_add(1) & self.counter_mask();
+                } else {
+                    self.counter = self.counter.wrapping_sub(1) & self.counter_mask();
+                }
+                if self.counter == self.compare_value() {
+                    // The timer stops after reaching the match value.
+                    self.reload();
+                    self.ctl &= !CTL::TAEN::BIT_MASK;
+                    TimerEvents::interrupt(RIS::CAMRIS::BIT_MASK)
+                } else {
+                    TimerEvents::default()
+                }
+            }
+            Some(Mode::EdgeTime) => {
+                self.captured = self.counter;
+                TimerEvents::interrupt(RIS::CAERIS::BIT_MASK)
+            }
+            _ => TimerEvents::default(),
+        }
+    }
+
+    // Skipping cycles
+
+    /// Number of clock cycles until the next event of the timer, or `None` if it has none.
+    pub(super) fn cycles_until_event(&self) -> Option<u64> {
+        if !self.is_running() {
+            return None;
+        }
+        let compare = u64::from(self.compare_value());
+        let counter = u64::from(self.counter);
+        let steps = if self.counts_up() {
+            let timeout = u64::from(self.load_value()).saturating_sub(counter) + 1;
+            if compare > counter {
+                timeout.min(compare - counter)
+            } else {
+                timeout
+            }
+        } else {
+            let timeout = counter + 1;
+            if compare < counter {
+                timeout.min(counter - compare)
+            } else {
+                timeout
+            }
+        };
+        let cycles_per_step = u64::from(self.prescaler_load()) + 1;
+        Some(u64::from(self.prescaler_counter) + 1 + (steps - 1) * cycles_per_step)
+    }
+
+    /// Advances the timer by `cycles` clock cycles, which must be fewer than
+    /// [`Self::cycles_until_event`].
+    pub(super) fn skip_cycles(&mut self, cycles: u64) {
+        if !self.is_running() || cycles == 0 {
+            return;
+        }
+        debug_assert!(self.cycles_until_event().is_some_and(|c| cycles < c));
+        let prescaler_counter = u64::from(self.prescaler_counter);
+        if cycles <= prescaler_counter {
+            #[allow(
+                clippy::cast_possible_truncation,
+                reason = "bounded by prescaler_counter"
+            )]
+            let cycles = cycles as u32;
+            self.prescaler_counter -= cycles;
+            return;
+        }
+        let cycles_per_step = u64::from(self.prescaler_load()) + 1;
+        let after_first_step = cycles - prescaler_counter - 1;
+        #[allow(
+            clippy::cast_possible_truncation,
+            reason = "bounded by the counter range"
+        )]
+        {
+            let steps = (1 + after_first_step / cycles_per_step) as u32;
+            self.prescaler_counter =
+                (cycles_per_step - 1 - after_first_step % cycles_per_step) as u32;
+            if self.counts_up() {
+                self.counter += steps;
+            } else {
+                self.counter -= steps;
+            }
+        }
+    }
+}
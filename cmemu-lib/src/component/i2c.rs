@@ -0,0 +1,521 @@
+pub const I2C_ROUTE_INJECTION: Range<Address> = I2C0::ADDR_SPACE;
+
+use crate::bridge_ports;
+use crate::build_data::{ClockTreeNodes, EnergyEntity};
+use crate::common::new_ahb::databus::DataBus;
+use crate::common::new_ahb::ports::AHBSlavePortProxiedInput;
+#[proxy_use]
+use crate::common::new_ahb::ports::{AHBPortConfig, AHBSlavePortInput};
+#[proxy_use]
+use crate::common::new_ahb::signals::{MasterToSlaveWires, Size};
+use crate::common::new_ahb::slave_driver::WriteMode;
+use crate::common::new_ahb::slave_driver::faking_slave_driver::{
+    AlignedFakingHandler, FakingIface, WaitstatesOrErr,
+};
+use crate::common::utils::HostHandle;
+use crate::component::event_fabric::EventFabricEvent;
+#[proxy_use]
+use crate::engine::Context;
+use crate::engine::{
+    DisableableComponent, MainComponent, SkippableClockTreeNode, TickComponent, TickComponentExtra,
+    Timepoint,
+};
+use crate::proxy::{EventFabricProxy, I2CProxy};
+use cc2650_constants::I2C0;
+use cmemu_common::Address;
+use cmemu_proc_macros::{component_impl, handler, proxy_use};
+use log::{trace, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::ops::Range;
+use std::panic::UnwindSafe;
+
+// [TI-TRM] 21 Inter-Integrated Circuit (I2C)
+// The I2C0 module is clocked with the MCU clock through the I2C clock gate.
+// Only the master is emulated: the bus is connected to the emulator-attached
+// `I2cBusInterface` slaves, which are called at the end of each bus phase
+// (the address, every data byte and the STOP condition) timed with the SCL clock.
+// The first slave acknowledging the address takes part in the transfer.
+// Not implemented: the slave function (`MCR.SFE`), the loopback mode (`MCR.LPBK`),
+// clock stretching by the slaves and the arbitration (there are no other masters).
+
+/// `I2cBusInterface` is a virtual slave on the I2C bus of I2C0.
+pub trait I2cBusInterface {
+    /// Called on a (repeated) START condition with the 7-bit slave `address`.
+    /// Returns whether the slave acknowledges the address, i.e., takes part in the transfer.
+    fn start(&mut self, now: Timepoint, address: u8, read: bool) -> bool;
+    /// Called with a byte sent by the master to this slave. Returns the acknowledge.
+    fn write(&mut self, byte: u8) -> bool;
+    /// Returns the byte read by the master from this slave, `ack` is the master's acknowledge.
+    fn read(&mut self, ack: bool) -> u8;
+    /// Called on a STOP condition.
+    fn stop(&mut self);
+}
+
+pub type I2cBusInterfaceImpl = Box<dyn I2cBusInterface + Send + Sync + UnwindSafe>;
+
+/// [TI-TRM] 21.5.1.13 MTPR Register: `SCL_PRD = 2 * (1 + TPR) * (SCL_LP + SCL_HP) * CLK_PRD`
+/// with the low and high periods fixed at 6 and 4.
+const SCL_PERIOD_CLOCKS: u64 = 2 * (6 + 4);
+
+/// A byte on the bus is followed by the acknowledge bit.
+const BYTE_PERIODS: u64 = 9;
+
+/// A part of a master operation, ending with a call to the slaves.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+enum Phase {
+    /// The (repeated) START condition and the address byte.
+    Start,
+    /// A data byte in the direction selected by the address.
+    Data { ack: bool },
+    /// The STOP condition.
+    Stop,
+}
+
+impl Phase {
+    fn periods(self) -> u64 {
+        match self {
+            Self::Start => 1 + BYTE_PERIODS,
+            Self::Data { .. } => BYTE_PERIODS,
+            Self::Stop => 1,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+enum BusState {
+    Idle,
+    Transmit,
+    Receive,
+}
+
+#[derive(
+    Clone,
+    MainComponent,
+    TickComponent,
+    TickComponentExtra,
+    DisableableComponent,
+    Serialize,
+    Deserialize,
+)]
+pub(crate) struct I2CComponent {
+    #[subcomponent(DriverSC)]
+    driver: BusDriver,
+
+    msa: u32,
+    mdr: u32,
+    mtpr: u32,
+    mimr: u32,
+    mris: u32,
+    mcr: u32,
+    soar: u32,
+    simr: u32,
+    /// The `MSTAT` bits of the result of the last operation.
+    result: u32,
+
+    bus: BusState,
+    /// The remaining phases of the current operation.
+    phases: VecDeque<Phase>,
+    /// Remaining time in cycles of the first phase.
+    phase_remaining: u64,
+    /// The slave that acknowledged the last address, as an index in `devices`.
+    target: Option<usize>,
+
+    devices: HostHandle<Vec<I2cBusInterfaceImpl>>,
+}
+type BusDriver = FakingIface<DriverSC, I2CComponent>;
+
+#[component_impl(i2c)]
+impl I2CComponent {
+    pub(crate) fn new() -> Self {
+        Self {
+            driver: Default::default(),
+
+            msa: I2C0::MSA::RESET_VALUE,
+            mdr: I2C0::MDR::RESET_VALUE,
+            mtpr: I2C0::MTPR::RESET_VALUE,
+            mimr: I2C0::MIMR::RESET_VALUE,
+            mris: I2C0::MRIS::RESET_VALUE,
+            mcr: I2C0::MCR::RESET_VALUE,
+            soar: I2C0::SOAR::RESET_VALUE,
+            simr: I2C0::SIMR::RESET_VALUE,
+            result: 0,
+
+            bus: BusState::Idle,
+            phases: VecDeque::with_capacity(3),
+            phase_remaining: 0,
+            target: None,
+
+            devices: HostHandle::new(None),
+        }
+    }
+
+    pub(crate) fn tick(&mut self, ctx: &mut Context) {
+        BusDriver::run_driver(self, ctx);
+
+        if !self.phases.is_empty() && Self::is_clock_enabled(ctx) {
+            self.phase_remaining = self.phase_remaining.saturating_sub(1);
+            if self.phase_remaining == 0 {
+                self.finish_phase(ctx);
+            }
+        }
+    }
+
+    pub(crate) fn tock(&mut self, ctx: &mut Context) {
+        BusDriver::tock(self, ctx);
+    }
+
+    #[handler]
+    pub(crate) fn on_new_ahb_slave_input(
+        &mut self,
+        ctx: &mut Context,
+        msg: MasterToSlaveWires<<I2CComponent as AHBPortConfig>::Data>,
+    ) {
+        <Self as AHBSlavePortInput>::on_ahb_input(self, ctx, msg);
+    }
+
+    pub(crate) fn attach_device(&mut self, device: I2cBusInterfaceImpl) {
+        self.devices.get_or_insert_with(Vec::new).push(device);
+    }
+
+    pub(crate) fn set_devices(&mut self, devices: Vec<I2cBusInterfaceImpl>) {
+        *self.devices = Some(devices);
+    }
+
+    pub(crate) fn take_devices(&mut self) -> Vec<I2cBusInterfaceImpl> {
+        self.devices.take().unwrap_or_default()
+    }
+
+    fn is_clock_enabled(ctx: &Context) -> bool {
+        ctx.get_energy_state_of(EnergyEntity::ClockTree(ClockTreeNodes::I2cGate))
+            .is_active()
+    }
+
+    fn scl_period(&self) -> u64 {
+        SCL_PERIOD_CLOCKS * (1 + u64::from(self.mtpr & I2C0::MTPR::TPR::BIT_MASK))
+    }
+
+    fn slave_address(&self) -> u8 {
+        #[allow(clippy::cast_possible_truncation)]
+        let address = (self.msa & I2C0::MSA::SA::BIT_MASK) as u8;
+        address >> 1
+    }
+
+    fn is_reading(&self) -> bool {
+        self.msa & I2C0::MSA::RS::BIT_MASK != 0
+    }
+
+    fn status(&self) -> u32 {
+        let mut mstat = self.result;
+        if !self.phases.is_empty() {
+            mstat |= I2C0::MSTAT::BUSY::BIT_MASK;
+        }
+        if self.bus == BusState::Idle && self.phases.is_empty() {
+            mstat |= I2C0::MSTAT::IDLE::BIT_MASK;
+        }
+        if self.bus != BusState::Idle || self.phases.front() == Some(&Phase::Start) {
+            mstat |= I2C0::MSTAT::BUSBSY::BIT_MASK;
+        }
+        mstat
+    }
+
+    /// [TI-TRM] 21.3.5 Command Sequence Flowcharts: the commands allowed in each bus state.
+    fn command(&mut self, ctx: &Context, mctrl: u32) {
+        let run = mctrl & I2C0::MCTRL::RUN::BIT_MASK != 0;
+        let start = mctrl & I2C0::MCTRL::START::BIT_MASK != 0;
+        let stop = mctrl & I2C0::MCTRL::STOP::BIT_MASK != 0;
+        let ack = mctrl & I2C0::MCTRL::ACK::BIT_MASK != 0;
+
+        if self.mcr & I2C0::MCR::MFE::BIT_MASK == 0 {
+            warn!("I2C master command {mctrl:#x} ignored: the master function is disabled.");
+            return;
+        }
+        if !self.phases.is_empty() {
+            warn!("I2C master command {mctrl:#x} ignored: the master is busy.");
+            return;
+        }
+        if !Self::is_clock_enabled(ctx) {
+            warn!("I2C master command {mctrl:#x} issued while the I2C clock is gated.");
+        }
+
+        if start && run {
+            self.phases.push_back(Phase::Start);
+        } else if self.bus == BusState::Idle && (run || stop) {
+            warn!("I2C master command {mctrl:#x} without a START on an idle bus is ignored.");
+            return;
+        }
+        if run {
+            self.phases.push_back(Phase::Data { ack });
+        }
+        if stop {
+            self.phases.push_back(Phase::Stop);
+        }
+        if let Some(&phase) = self.phases.front() {
+            self.result = 0;
+            self.phase_remaining = phase.periods() * self.scl_period();
+        }
+    }
+
+    fn finish_phase(&mut self, ctx: &mut Context) {
+        let phase = self
+            .phases
+            .pop_front()
+            .expect("finishing a phase of an operation");
+        match phase {
+            Phase::Start => self.start(ctx),
+            Phase::Data { ack } => self.transfer_byte(ack),
+            Phase::Stop => {
+                trace!("I2C STOP");
+                if let Some(devices) = &mut *self.devices {
+                    for device in devices {
+                        device.stop();
+                    }
+                }
+                self.target = None;
+                self.bus = BusState::Idle;
+            }
+        }
+
+        if let Some(&next) = self.phases.front() {
+            self.phase_remaining = next.periods() * self.scl_period();
+        } else {
+            // [TI-TRM] 21.4.1 I2C Master Interrupts: raised at the end of a transaction or on an error.
+            self.mris |= I2C0::MRIS::RIS::BIT_MASK;
+            if self.mimr & I2C0::MIMR::IM::BIT_MASK != 0 {
+                EventFabricProxy.notify(ctx, EventFabricEvent::I2C_IRQ);
+            }
+        }
+    }
+
+    fn start(&mut self, ctx: &Context) {
+        let address = self.slave_address();
+        let read = self.is_reading();
+        let now = ctx.event_queue().get_current_time();
+        self.bus = if read {
+            BusState::Receive
+        } else {
+            BusState::Transmit
+        };
+        // Every slave sees the START, the first one acknowledging the address is the target.
+        self.target = None;
+        if let Some(devices) = &mut *self.devices {
+            for (index, device) in devices.iter_mut().enumerate() {
+                if device.start(now, address, read) && self.target.is_none() {
+                    self.target = Some(index);
+                }
+            }
+        }
+        trace!(
+            "I2C START {address:#04x} {}: {}",
+            if read { "read" } else { "write" },
+            if self.target.is_some() { "ACK" } else { "NACK" }
+        );
+        if self.target.is_none() {
+            self.result |= I2C0::MSTAT::ERR::BIT_MASK | I2C0::MSTAT::ADRACK_N::BIT_MASK;
+            // The data byte isn't transferred after a not acknowledged address.
+            self.phases.retain(|phase| *phase == Phase::Stop);
+        }
+    }
+
+    fn transfer_byte(&mut self, ack: bool) {
+        let device = self
+            .target
+            .and_then(|index| self.devices.as_mut()?.get_mut(index));
+        match (self.bus, device) {
+            (BusState::Transmit, device) => {
+                #[allow(clippy::cast_possible_truncation)]
+                let byte = self.mdr as u8;
+                let acknowledged = device.is_some_and(|device| device.write(byte));
+                trace!(
+                    "I2C wrote {byte:#04x}: {}",
+                    if acknowledged { "ACK" } else { "NACK" }
+                );
+                if !acknowledged {
+                    self.result |= I2C0::MSTAT::ERR::BIT_MASK | I2C0::MSTAT::DATACK_N::BIT_MASK;
+                }
+            }
+            (BusState::Receive, device) => {
+                // Nobody drives the pulled-up data line.
+                let byte = device.map_or(u8::MAX, |device| device.read(ack));
+                trace!("I2C read {byte:#04x}");
+                self.mdr = u32::from(byte);
+            }
+            (BusState::Idle, _) => unreachable!("data phase on an idle bus"),
+        }
+    }
+
+    fn get_data_for_address(&self, ctx: &Context, addr: Address) -> u32 {
+        match addr {
+            I2C0::MSA::ADDR => self.msa,
+            I2C0::MSTAT::ADDR => self.status(),
+            I2C0::MDR::ADDR => self.mdr,
+            I2C0::MTPR::ADDR => self.mtpr,
+            I2C0::MIMR::ADDR => self.mimr,
+            I2C0::MRIS::ADDR => self.mris,
+            I2C0::MMIS::ADDR => self.mris & self.mimr,
+            I2C0::MCR::ADDR => self.mcr,
+            I2C0::SOAR::ADDR => self.soar,
+            I2C0::SIMR::ADDR => self.simr,
+            I2C0::MICR::ADDR
+            | I2C0::SSTAT::ADDR
+            | I2C0::SDR::ADDR
+            | I2C0::SRIS::ADDR
+            | I2C0::SMIS::ADDR
+            | I2C0::SICR::ADDR => 0,
+            _ => unimplemented!(
+                "Requested I2C data read from address {:?}: {}",
+                addr,
+                ctx.display_named_address(addr)
+            ),
+        }
+    }
+
+    fn set_data_for_address(&mut self, ctx: &mut Context, addr: Address, data: u32) {
+        match addr {
+            I2C0::MSA::ADDR => self.msa = data & 0xff,
+            I2C0::MCTRL::ADDR => self.command(ctx, data),
+            I2C0::MDR::ADDR => self.mdr = data & I2C0::MDR::DATA::BIT_MASK,
+            I2C0::MTPR::ADDR => {
+                if data & I2C0::MTPR::TPR_7::BIT_MASK == 0 {
+                    self.mtpr = data & I2C0::MTPR::TPR::BIT_MASK;
+                }
+            }
+            I2C0::MIMR::ADDR => {
+                let newly_unmasked = data & !self.mimr;
+                self.mimr = data & I2C0::MIMR::IM::BIT_MASK;
+                if self.mris & newly_unmasked != 0 {
+                    EventFabricProxy.notify(ctx, EventFabricEvent::I2C_IRQ);
+                }
+            }
+            I2C0::MICR::ADDR => self.mris &= !data,
+            I2C0::MCR::ADDR => {
+                self.mcr = data;
+                if data & (I2C0::MCR::SFE::BIT_MASK | I2C0::MCR::LPBK::BIT_MASK) != 0 {
+                    warn!("I2C slave function and loopback mode are not implemented.");
+                }
+            }
+            I2C0::SOAR::ADDR => self.soar = data,
+            I2C0::SIMR::ADDR => self.simr = data,
+            I2C0::SCTL::ADDR | I2C0::SICR::ADDR | I2C0::SDR::ADDR => {}
+            I2C0::MRIS::ADDR | I2C0::MMIS::ADDR | I2C0::SRIS::ADDR | I2C0::SMIS::ADDR => {
+                warn!(
+                    "Ignoring write {:#x} to read-only {}",
+                    data,
+                    ctx.display_named_address(addr)
+                );
+            }
+            _ => unimplemented!(
+                "Requested I2C data write {:#x} to address {:?}: {}",
+                data,
+                addr,
+                ctx.display_named_address(addr)
+            ),
+        }
+    }
+
+    fn warn_if_gated(ctx: &Context, addr: Address) {
+        if !Self::is_clock_enabled(ctx) {
+            warn!(
+                "Accessing {} while the I2C clock is gated.",
+                ctx.display_named_address(addr)
+            );
+        }
+    }
+}
+
+bridge_ports!(@slave I2CComponent => @auto_configured @slave BusDriver);
+
+#[component_impl(i2c)]
+impl AHBPortConfig for I2CComponent {
+    type Data = DataBus;
+    type Component = Self;
+    const TAG: &'static str = "I2C0";
+}
+
+#[component_impl(i2c)]
+impl AHBSlavePortProxiedInput for I2CComponent {
+    fn proxy_ahb_input(ctx: &mut Context, msg: MasterToSlaveWires<Self::Data>) {
+        I2CProxy.on_new_ahb_slave_input(ctx, msg);
+    }
+}
+
+#[component_impl(i2c)]
+impl AlignedFakingHandler for I2CComponent {
+    const WRITE_MODE: WriteMode = WriteMode::Combinatorial;
+    const ALIGN: Size = Size::Word;
+    type Native = u32;
+
+    fn read_for_write_filler(
+        slave: &Self::Component,
+        ctx: &Context,
+        address: Address,
+    ) -> Self::Native {
+        slave.get_data_for_address(ctx, address)
+    }
+
+    fn pre_read(
+        _slave: &mut Self::Component,
+        ctx: &mut Context,
+        address: Address,
+    ) -> WaitstatesOrErr {
+        Self::warn_if_gated(ctx, address);
+        Ok(0)
+    }
+
+    fn read(slave: &mut Self::Component, ctx: &mut Context, address: Address) -> Self::Native {
+        let data = slave.get_data_for_address(ctx, address);
+        trace!(
+            "read {address:?} (\"{}\") = {data:#x}",
+            ctx.display_named_address(address)
+        );
+        data
+    }
+
+    fn pre_write(
+        _slave: &mut Self::Component,
+        ctx: &mut Context,
+        address: Address,
+    ) -> WaitstatesOrErr {
+        Self::warn_if_gated(ctx, address);
+        Ok(0)
+    }
+
+    fn write(slave: &mut Self::Component, ctx: &mut Context, address: Address, data: Self::Native) {
+        trace!(
+            "write {address:?} (\"{}\") {data:#x}",
+            ctx.display_named_address(address)
+        );
+        slave.set_data_for_address(ctx, address, data);
+    }
+}
+
+#[component_impl(i2c)]
+impl SkippableClockTreeNode for I2CComponent {
+    fn max_cycles_to_skip(
+        comp: &mut Self::Component,
+        ctx: &mut Context,
+        _parent: Self::IdSpace,
+        _extra: &mut Self::Extra,
+    ) -> u64 {
+        if !comp.can_be_disabled_now() {
+            0
+        } else if comp.phases.is_empty() || !Self::is_clock_enabled(ctx) {
+            u64::MAX
+        } else {
+            // -1 because the phases end only in ticks
+            comp.phase_remaining.max(1) - 1
+        }
+    }
+
+    fn emulate_skipped_cycles(
+        comp: &mut Self::Component,
+        ctx: &mut Context,
+        _parent: Self::IdSpace,
+        _extra: &mut Self::Extra,
+        skipped_cycles: u64,
+    ) {
+        if !comp.phases.is_empty() && Self::is_clock_enabled(ctx) {
+            comp.phase_remaining = comp.phase_remaining.saturating_sub(skipped_cycles);
+        }
+    }
+}
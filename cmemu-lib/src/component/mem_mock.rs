@@ -1,6 +1,7 @@
-use cc2650_constants::{FLASH, TRNG, WDT, is_unbuffered_alias};
+use cc2650_constants::{AON_SYSCTL, is_unbuffered_alias};
 use cmemu_common::address_match_range;
 use cmemu_proc_macros::{component_impl, handler, proxy_use};
+use serde::{Deserialize, Serialize};
 
 use crate::bridge_ports;
 use crate::common::Address;
@@ -18,10 +19,12 @@ use crate::common::utils::MaybeMut;
 #[proxy_use]
 use crate::engine::Context;
 use crate::engine::{
-    DisableableComponent, MainComponent, SkippableClockTreeNode, TickComponent, TickComponentExtra,
+    DisableableComponent, MainComponent, ResetRequest, SkippableClockTreeNode, TickComponent,
+    TickComponentExtra,
 };
 use crate::proxy::MemoryMockProxy;
-use log::trace;
+use cmemu_common::HwRegister;
+use log::{info, trace};
 
 mod autogenerated;
 use autogenerated::AutoMockComponent;
@@ -34,7 +37,15 @@ use autogenerated::AutoMockComponent;
 // WEB: reference to some web resource
 // It's better than nothing but it may become unavailable in the future.
 
-#[derive(MainComponent, SkippableClockTreeNode, TickComponent, TickComponentExtra)]
+#[derive(
+    Clone,
+    MainComponent,
+    SkippableClockTreeNode,
+    TickComponent,
+    TickComponentExtra,
+    Serialize,
+    Deserialize,
+)]
 #[skippable_if_disableable]
 pub(crate) struct MemoryMockComponent {
     #[subcomponent(DriverSC)]
@@ -42,8 +53,6 @@ pub(crate) struct MemoryMockComponent {
 
     #[subcomponent(AutoMockComponent)]
     pub(crate) automock: AutoMockComponent,
-
-    rng: fastrand::Rng,
 }
 
 type BusDriver = FakingIface<DriverSC, MemoryMockComponent>;
@@ -54,7 +63,6 @@ impl MemoryMockComponent {
         Self {
             slave_driver: BusDriver::new(),
             automock: AutoMockComponent::new(),
-            rng: fastrand::Rng::with_seed(0),
         }
     }
 
@@ -64,18 +72,29 @@ impl MemoryMockComponent {
 
     pub(crate) fn tock(&mut self, ctx: &mut Context) {
         BusDriver::tock(self, ctx);
-        if self.automock.trng_swreset.bitfields().RESET() != 0 {
-            // this should take 4-5 cycles but the perceivable difference is probably negligible
-            self.automock.trng_swreset.set_next(
-                self.automock
-                    .trng_swreset
-                    .mutate_copy(|bf| bf.with_RESET(0)),
-            );
-        }
     }
 
-    pub(crate) fn set_rng_seed(&mut self, seed: u64) {
-        self.rng.seed(seed);
+    /// Takes over the mocked registers of the AON domain from the `old` instance
+    /// across a warm reset.
+    pub(crate) fn take_aon_registers(&mut self, old: &Self) {
+        let (new, old) = (&mut self.automock, &old.automock);
+        new.aon_ioc_ioclatch = old.aon_ioc_ioclatch.clone();
+        new.aon_sysctl_sleepctl = old.aon_sysctl_sleepctl.clone();
+        new.aon_sysctl_pwrctl = old.aon_sysctl_pwrctl.clone();
+        new.aon_sysctl_resetctl = old.aon_sysctl_resetctl.clone();
+    }
+
+    /// Records the source of a reset with the full power-up sequence in `AON_SYSCTL:RESETCTL`
+    /// of a freshly reset instance.
+    pub(crate) fn record_system_reset(&mut self, reset_src: u32, from_shutdown: bool) {
+        use AON_SYSCTL::RESETCTL::{GPIO_WU_FROM_SD, RESET_SRC, WU_FROM_SD};
+        let resetctl = self.automock.aon_sysctl_resetctl.unsafe_as_mut();
+        let mut value = resetctl.read() & !RESET_SRC::BIT_MASK;
+        value |= reset_src << RESET_SRC::BIT_RANGE.start();
+        if from_shutdown {
+            value |= WU_FROM_SD::BIT_MASK | GPIO_WU_FROM_SD::BIT_MASK;
+        }
+        *resetctl = value.into();
     }
 
     #[handler]
@@ -162,8 +181,6 @@ impl MemoryMockComponent {
         }
 
         address_match_range! {addr,
-            FLASH::BASE_ADDR => 2,
-            FLASH::FRDCTL::ADDR => 5,
             _ => 0,
         }
     }
@@ -182,9 +199,6 @@ impl MemoryMockComponent {
             );
             return data;
         }
-        if let (Some(this), TRNG::OUT0::ADDR | TRNG::OUT1::ADDR) = (this.get_mut(), addr) {
-            return this.rng.u32(..).to_le_bytes();
-        }
         unimplemented!(
             "Requested mem_mock data read from address {:?} {}",
             addr,
@@ -192,20 +206,18 @@ impl MemoryMockComponent {
         )
     }
 
-    fn set_data_for_address(
-        &mut self,
-        addr: Address,
-        data: [u8; 4],
-        #[allow(unused)] ctx: &mut Context,
-    ) {
+    fn set_data_for_address(&mut self, addr: Address, data: [u8; 4], ctx: &mut Context) {
+        if addr == AON_SYSCTL::RESETCTL::ADDR
+            && u32::from_le_bytes(data) & AON_SYSCTL::RESETCTL::SYSRESET::BIT_MASK != 0
+        {
+            info!("System reset requested with AON_SYSCTL:RESETCTL.SYSRESET");
+            ctx.request_reset(ResetRequest::SystemReset);
+        }
         if let Some(()) = self.automock.set_data_for_address(addr, data) {
-            if addr != WDT::ICR::ADDR {
-                // spammy!
-                trace!(
-                    "*0x{addr:?} <- {data:02X?} {}",
-                    ctx.display_named_address(addr)
-                );
-            }
+            trace!(
+                "*0x{addr:?} <- {data:02X?} {}",
+                ctx.display_named_address(addr)
+            );
         } else {
             unimplemented!(
                 "Requested mem_mock data write {:02X?} to address {:?} {}",
@@ -6,8 +6,9 @@ use crate::component::rfc::RfcMemoryBypassReceiver;
 use crate::engine::{Context, Subcomponent};
 use crate::proxy::RfcProxy;
 use cmemu_common::Address;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub(crate) enum MemoryBypassReceiver {
     Rfc(RfcMemoryBypassReceiver),
 }
@@ -1,4 +1,5 @@
 use core::ops::Range;
+use serde::{Deserialize, Serialize};
 
 use cmemu_common::address_match_range;
 use cmemu_proc_macros::{component_impl, handler, proxy_use};
@@ -18,18 +19,21 @@ use crate::common::{Address, BitstringUtils, Word};
 #[proxy_use]
 use crate::component::core::{BasePriorityMaskRegister, FaultMaskRegister, PriorityMaskRegister};
 #[proxy_use(proxy_only)]
-use crate::component::nvic::{CoreStateChange, InterruptId};
+use crate::component::nvic::{CoreStateChange, Fault, InterruptId};
 use crate::engine::{
-    CpuMode, DisableableComponent, MainComponent, SeqFlop, SeqFlopMemoryBank,
+    CpuMode, DisableableComponent, MainComponent, ResetRequest, SeqFlop, SeqFlopMemoryBank,
     SeqFlopMemoryBankSimple, SkippableClockTreeNode, TickComponent, TickComponentExtra,
+    WarmResetSource,
 };
 // export for core
-pub(in crate::component) use self::system_control_block::{SCBRegister, VTOR};
+use self::mpu_registers::{MPU_ADDR_SPACE, MPURegisters};
+pub(in crate::component) use self::system_control_block::{CCR, SCBRegister, VTOR};
 #[proxy_use]
 use crate::engine::Context;
 use crate::proxy::{CoreProxy, NVICProxy, PRCMProxy};
 use crate::utils::IfExpr;
 
+mod mpu_registers;
 mod register_bank;
 mod stir;
 mod system_control_block;
@@ -95,7 +99,15 @@ type NVICRegisterBank = register_bank::NVICRegisterBank<NVICRegisterBankSubcompo
 type SystemControlBlock = system_control_block::SystemControlBlock<SystemControlBlockSubcomponent>;
 type SysTick = systick::SysTick<SysTickSubcomponent>;
 
-#[derive(MainComponent, TickComponent, TickComponentExtra, DisableableComponent)]
+#[derive(
+    Clone,
+    MainComponent,
+    TickComponent,
+    TickComponentExtra,
+    DisableableComponent,
+    Serialize,
+    Deserialize,
+)]
 pub(crate) struct NVICComponent {
     core: CoreProxy,
 
@@ -107,6 +119,7 @@ pub(crate) struct NVICComponent {
     system_control_block: SystemControlBlock,
     #[subcomponent(SysTickSubcomponent)]
     systick: SysTick,
+    mpu: MPURegisters,
 
     #[flop]
     core_state_change: SeqFlop<CoreStateChange>,
@@ -128,11 +141,15 @@ pub(crate) struct NVICComponent {
     interrupt_state: SeqFlopMemoryBankSimple<InterruptState>,
     #[flop]
     tail_chained_interrupt: SeqFlopMemoryBankSimple<Option<InterruptData>>,
+    /// `HardFault` has no pending bit in the SCB registers, so its pending state is kept here.
+    #[flop]
+    hardfault_pending: SeqFlopMemoryBankSimple<bool>,
 
     /// [ARM-ARM] B1.5.5 Reset behavior - "it's a conceptual array of active flag
     /// bits for all exceptions". It has been decided to keep active exceptions
     /// in that way to make implementation easier and more similar to docs.
     #[flop]
+    #[serde(with = "crate::engine::serde_array_bank")]
     exception_active: SeqFlopMemoryBank<[bool; EXCEPTIONS_AND_INTERRUPTS_COUNT], (usize, bool)>,
 
     /// Counts how many exceptions have been started, but not yet finished.
@@ -146,9 +163,14 @@ pub(crate) struct NVICComponent {
     /// However, this is used normally to indicate if the new exception is taken,
     /// therefore the CPU may continue execution after the WFI instruction (as a spurious wakeup).
     wfi_primask_handling_scope: bool,
+
+    /// The CPU sleeps in WFE, so it is woken up only by events, see [`Self::start_sleep()`].
+    waiting_for_event: bool,
+    /// An interrupt became pending with SCR.SEVONPEND set while the CPU was waiting for an event.
+    sev_on_pend_wakeup: bool,
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 enum InterruptState {
     None,
     /// [ARM-TRM-G] Figure 5-2 Exception entry timing.
@@ -171,12 +193,15 @@ enum InterruptState {
 }
 
 // pub(crate) because used by proxy (shared with core)
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 pub(crate) enum CoreStateChange {
     FinishedEntry,
     StartedTailChain,
     FinishedTailChain,
     StartedExit,
+    /// [ARM-ARM] B1.5.8 The exception return failed the INVPC checks: the returning exception
+    /// is deactivated (if active) and `UsageFault` is raised instead of unstacking.
+    IllegalExit,
     /// Stores preempted interrupt data (in case of nested interrupts).
     /// At the exit stage, core knows if return from handler should happen to
     /// some other interrupt, so it can send this interrupt data. Because of
@@ -184,8 +209,69 @@ pub(crate) enum CoreStateChange {
     FinishedExit(Option<InterruptData>),
 }
 
+/// Faults detected by the core, which are reported in the Configurable Fault Status Register.
+///
+/// [ARM-ARM] B1.5.14 Fault behavior
+// pub(crate) because used by proxy (shared with core)
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum Fault {
+    /// `UFSR.UNDEFINSTR` - execution of an undefined instruction.
+    UndefinedInstruction,
+    /// `UFSR.INVSTATE` - execution of an instruction with `EPSR.T == 0`,
+    /// e.g., after an interworking branch to the ARM state.
+    InvalidState,
+    /// `UFSR.INVPC` - an exception return with an illegal `EXC_RETURN` value.
+    InvalidPcLoad,
+    /// `UFSR.DIVBYZERO` - `SDIV` or `UDIV` with a zero divisor, when `CCR.DIV_0_TRP` is set.
+    DivideByZero,
+    /// `MMFSR.IACCVIOL` - execution of an instruction from a location not permitted by the MPU.
+    InstructionAccessViolation,
+    /// `MMFSR.DACCVIOL` - a data access of the faulting instruction not permitted by the MPU.
+    DataAccessViolation { address: Address },
+    /// `MMFSR.MUNSTKERR` - a read during exception return unstacking not permitted by the MPU.
+    UnstackingAccessViolation,
+    /// `MMFSR.MSTKERR` - a write during exception entry stacking not permitted by the MPU.
+    StackingAccessViolation,
+    /// `BFSR.IBUSERR` - execution of an instruction which fetch got an error response.
+    InstructionBusError,
+    /// `BFSR.PRECISERR` - an error response to a data access of the faulting instruction.
+    PreciseDataBusError { address: Address },
+    /// `BFSR.IMPRECISERR` - an error response to a buffered write, which instruction has
+    /// already finished.
+    ImpreciseDataBusError,
+    /// `BFSR.UNSTKERR` - an error response to a read during exception return unstacking.
+    UnstackingBusError,
+    /// `BFSR.STKERR` - an error response to a write during exception entry stacking.
+    StackingBusError,
+}
+
+impl Fault {
+    fn exception(self) -> InterruptId {
+        match self {
+            Self::UndefinedInstruction
+            | Self::InvalidState
+            | Self::InvalidPcLoad
+            | Self::DivideByZero => InterruptId::UsageFault,
+            Self::InstructionAccessViolation
+            | Self::DataAccessViolation { .. }
+            | Self::UnstackingAccessViolation
+            | Self::StackingAccessViolation => InterruptId::MemManage,
+            Self::InstructionBusError
+            | Self::PreciseDataBusError { .. }
+            | Self::ImpreciseDataBusError
+            | Self::UnstackingBusError
+            | Self::StackingBusError => InterruptId::BusFault,
+        }
+    }
+
+    /// [ARM-ARM] B1.5.14: imprecise `BusFault`s are the only asynchronous faults.
+    fn is_synchronous(self) -> bool {
+        !matches!(self, Self::ImpreciseDataBusError)
+    }
+}
+
 // pub(crate) because used by proxy (shared with core)
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
 pub(crate) struct InterruptData {
     pub(in crate::component) interrupt_id: InterruptId,
 }
@@ -200,6 +286,7 @@ impl NVICComponent {
             register_bank: NVICRegisterBank::new(),
             system_control_block: SystemControlBlock::new(),
             systick: SysTick::new(),
+            mpu: MPURegisters::new(),
 
             core_state_change: SeqFlop::new(),
             interrupt_state: SeqFlopMemoryBankSimple::new(InterruptState::None),
@@ -209,18 +296,21 @@ impl NVICComponent {
             cpu_mode: SeqFlopMemoryBankSimple::new(CpuMode::Run),
 
             tail_chained_interrupt: SeqFlopMemoryBankSimple::new(None),
+            hardfault_pending: SeqFlopMemoryBankSimple::new(false),
 
             exception_active: SeqFlopMemoryBank::new([false; EXCEPTIONS_AND_INTERRUPTS_COUNT]),
 
             exception_nesting_level: 0,
             wfi_primask_handling_scope: false,
+            waiting_for_event: false,
+            sev_on_pend_wakeup: false,
         }
     }
 
     pub(crate) fn tick(&mut self, ctx: &mut Context) {
         BusDriver::run_driver(self, ctx);
 
-        self.check_core_state_change();
+        self.check_core_state_change(ctx);
 
         // [ARM-ARM] B3.3.1 - SysTick can use the processor clock or an external
         // clock, but [TI-TRM] 2.7.4.3 `CLKSOURCE` field description says that
@@ -236,15 +326,123 @@ impl NVICComponent {
 
         // TODO: can be done better, e.g. only when state changes in a specific way.
         self.check_interrupts(ctx);
+
+        MPURegisters::run_tick(self, ctx);
     }
 
     pub(crate) fn tock(&mut self, ctx: &mut Context) {
         BusDriver::tock(self, ctx);
     }
 
-    fn inner_raise_exception(&mut self, _ctx: &mut Context, exc: InterruptId) {
+    fn inner_raise_exception(&mut self, ctx: &mut Context, exc: InterruptId) {
         trace!("Raised exception: {:?}", exc);
-        self.register_bank.set_interrupt_pending(exc);
+        match exc {
+            InterruptId::Interrupt(_) => {
+                if !self.register_bank.get_interrupt_pending(exc) {
+                    self.signal_event_on_pend(ctx);
+                }
+                self.register_bank.set_interrupt_pending(exc);
+            }
+            InterruptId::NMI => self.system_control_block.icsr_mut().set_nmipendset(),
+            InterruptId::HardFault => self.hardfault_pending.set_next(true),
+            InterruptId::SVCall => {
+                // [ARM-ARM] B1.5.14 Fault behavior: SVC executed when its priority
+                // is not sufficient to preempt is escalated to HardFault.
+                let priority = self.compute_group_priority(self.get_interrupt_priority(exc));
+                if priority < self.execution_priority() {
+                    self.system_control_block.shcsr_mut().set_svcallpended();
+                } else {
+                    trace!("SVCall priority is not sufficient, escalating to HardFault");
+                    self.system_control_block.hfsr_mut().set_forced();
+                    self.raise_hardfault(ctx, self.execution_priority());
+                }
+            }
+            InterruptId::DebugMonitor => {
+                // [ARM-ARM] B1.5.14 Fault behavior: without halting debug and with
+                // the DebugMonitor disabled, a debug event (BKPT) escalates to HardFault.
+                // Neither DHCSR.C_DEBUGEN nor DEMCR.MON_EN can be set in the emulator.
+                trace!("DebugMonitor is disabled, escalating the debug event to HardFault");
+                self.system_control_block.hfsr_mut().set_debugevt();
+                self.raise_hardfault(ctx, self.execution_priority());
+            }
+            InterruptId::PendSV => self.system_control_block.icsr_mut().set_pendsvset(),
+            InterruptId::SysTick => self.system_control_block.icsr_mut().set_pendstset(),
+            InterruptId::MemManage | InterruptId::BusFault | InterruptId::UsageFault => {
+                panic!("{exc:?} has to be raised with its fault status, see `raise_fault()`.")
+            }
+            InterruptId::Reset => unimplemented!("Raising {exc:?} is not supported yet."),
+        }
+    }
+
+    fn inner_raise_fault(&mut self, ctx: &mut Context, fault: Fault) {
+        self.raise_fault_at_priority(ctx, fault, self.execution_priority());
+    }
+
+    /// [ARM-ARM] B1.5.14 Fault behavior
+    /// A synchronous fault has to preempt the `execution_priority`, otherwise it is escalated.
+    fn raise_fault_at_priority(
+        &mut self,
+        ctx: &mut Context,
+        fault: Fault,
+        execution_priority: i32,
+    ) {
+        trace!("Raised fault: {:?}", fault);
+        self.system_control_block.record_fault_status(fault);
+
+        let exc = fault.exception();
+        let shcsr = self.system_control_block.shcsr();
+        let enabled = match exc {
+            InterruptId::MemManage => shcsr.get_memfaultena(),
+            InterruptId::BusFault => shcsr.get_busfaultena(),
+            InterruptId::UsageFault => shcsr.get_usgfaultena(),
+            _ => unreachable!(),
+        };
+        // A disabled fault is always escalated, but only a synchronous fault has to preempt
+        // the current execution - an imprecise BusFault may wait as pending.
+        let priority = self.compute_group_priority(self.get_interrupt_priority(exc));
+        if !enabled || (fault.is_synchronous() && priority >= execution_priority) {
+            trace!(
+                "{exc:?} is disabled or its priority is not sufficient, escalating to HardFault"
+            );
+            self.system_control_block.hfsr_mut().set_forced();
+            self.raise_hardfault(ctx, execution_priority);
+        } else {
+            let shcsr = self.system_control_block.shcsr_mut();
+            match exc {
+                InterruptId::MemManage => shcsr.set_memfaultpended(),
+                InterruptId::BusFault => shcsr.set_busfaultpended(),
+                _ => shcsr.set_usgfaultpended(),
+            }
+        }
+    }
+
+    fn raise_hardfault(&mut self, ctx: &mut Context, execution_priority: i32) {
+        if interrupt::HARD_FAULT_PRIORITY >= execution_priority {
+            // [ARM-ARM] B1.5.15 Unrecoverable exception cases: the processor enters the lockup
+            // state. In this SoC, the lockup is a warm reset source, so the state is not modeled.
+            warn!("Lockup, requesting a warm reset");
+            ctx.request_reset(ResetRequest::Warm(WarmResetSource::Lockup));
+            return;
+        }
+        self.hardfault_pending.set_next(true);
+    }
+
+    /// [ARM-ARM] B1.5.18: with SCR.SEVONPEND set, an interrupt transition from inactive
+    /// to pending is a WFE wakeup event (or sets the Event Register if the CPU is running).
+    fn signal_event_on_pend(&mut self, ctx: &mut Context) {
+        let sev_on_pend = self
+            .system_control_block
+            .scr()
+            .read(Word::from(!0))
+            .get_bit(4);
+        if !sev_on_pend {
+            return;
+        }
+        if self.waiting_for_event {
+            self.sev_on_pend_wakeup = true;
+        } else {
+            self.core.signal_event(ctx);
+        }
     }
 
     #[handler]
@@ -260,11 +458,15 @@ impl NVICComponent {
     }
 
     #[handler]
-    #[allow(dead_code)]
     pub(crate) fn raise_exception(&mut self, ctx: &mut Context, interrupt: InterruptId) {
         self.inner_raise_exception(ctx, interrupt);
     }
 
+    #[handler]
+    pub(crate) fn raise_fault(&mut self, ctx: &mut Context, fault: Fault) {
+        self.inner_raise_fault(ctx, fault);
+    }
+
     #[handler]
     pub fn on_new_ahb_slave_input(
         &mut self,
@@ -289,8 +491,13 @@ impl NVICComponent {
         self.basepri_copy.set_next(basepri);
     }
 
+    /// Puts the CPU to sleep after WFI or WFE (with `wait_for_event`).
+    ///
+    /// [ARM-ARM] B1.5.18: WFE is not woken up by interrupts masked by PRIMASK,
+    /// but only by events, see [`Self::check_non_exc_wakeup_event()`].
     #[handler]
-    pub(crate) fn start_sleep(&mut self, ctx: &mut Context) {
+    pub(crate) fn start_sleep(&mut self, ctx: &mut Context, wait_for_event: bool) {
+        self.waiting_for_event = wait_for_event;
         let deep_sleep = self
             .system_control_block
             .scr()
@@ -324,7 +531,7 @@ impl AHBSlavePortProxiedInput for NVICComponent {
 
 #[component_impl(nvic)]
 impl NVICComponent {
-    fn check_core_state_change(&mut self) {
+    fn check_core_state_change(&mut self, ctx: &mut Context) {
         if self.core_state_change.is_set() {
             let next_state = match *self.core_state_change {
                 CoreStateChange::FinishedEntry => {
@@ -371,6 +578,25 @@ impl NVICComponent {
                         panic!("Tried to start exit in state {:?}.", *self.interrupt_state)
                     }
                 }
+                CoreStateChange::IllegalExit => {
+                    if let InterruptState::Handling { id } = *self.interrupt_state {
+                        if self.exception_active[id.as_exception_number()] {
+                            self.deactivate_interrupt(id);
+                        }
+                        // The core doesn't tail-chain an illegal return.
+                        self.tail_chained_interrupt.set_next(None);
+                        // The active state is updated in the next cycle, but the fault
+                        // has to preempt the priority without the returning exception.
+                        let priority = self.compute_group_priority(self.target_priority(id));
+                        self.raise_fault_at_priority(ctx, Fault::InvalidPcLoad, priority);
+                        InterruptState::None
+                    } else {
+                        panic!(
+                            "Tried to exit illegally in state {:?}.",
+                            *self.interrupt_state
+                        )
+                    }
+                }
                 CoreStateChange::FinishedExit(preempted_interrupt) => {
                     if let InterruptState::Exit = *self.interrupt_state {
                         preempted_interrupt.map_or(InterruptState::None, |interrupt_data| {
@@ -415,27 +641,22 @@ impl NVICComponent {
         match *self.interrupt_state {
             InterruptState::None => {
                 if let Some(interrupt_data) = self.find_interrupt_to_handle() {
-                    self.interrupt_state
-                        .set_next(InterruptState::InterruptFoundToHandle {
-                            data: interrupt_data,
-                        });
+                    if matches!(interrupt_data.interrupt_id, InterruptId::SVCall) {
+                        // SVCall is raised synchronously by the core, so there is no
+                        // additional cycle of delay as in [ARM-TRM-G] Figure 5-2.
+                        self.start_interrupt_entry(ctx, interrupt_data);
+                    } else {
+                        self.interrupt_state
+                            .set_next(InterruptState::InterruptFoundToHandle {
+                                data: interrupt_data,
+                            });
+                    }
                 } else if *self.cpu_mode != CpuMode::Run {
                     self.check_non_exc_wakeup_event(ctx);
                 }
             }
             InterruptState::InterruptFoundToHandle { data } => {
-                if let InterruptId::Interrupt(_) = data.interrupt_id {
-                    self.set_icsr_interrupt_pending(
-                        data.interrupt_id.as_exception_number().try_into().unwrap(),
-                    );
-                }
-                self.interrupt_state.set_next(InterruptState::Entry {
-                    id: data.interrupt_id,
-                });
-                self.core.start_interrupt_entry(ctx, data);
-                if *self.cpu_mode != CpuMode::Run {
-                    self.wakeup_cpu(ctx);
-                }
+                self.start_interrupt_entry(ctx, data);
             }
             InterruptState::Handling { id } => {
                 // [ARM-ARM] B1.5.12 Tail chain happens when there is pending
@@ -481,6 +702,21 @@ impl NVICComponent {
         }
     }
 
+    fn start_interrupt_entry(&mut self, ctx: &mut Context, data: InterruptData) {
+        if let InterruptId::Interrupt(_) = data.interrupt_id {
+            self.set_icsr_interrupt_pending(
+                data.interrupt_id.as_exception_number().try_into().unwrap(),
+            );
+        }
+        self.interrupt_state.set_next(InterruptState::Entry {
+            id: data.interrupt_id,
+        });
+        self.core.start_interrupt_entry(ctx, data);
+        if *self.cpu_mode != CpuMode::Run {
+            self.wakeup_cpu(ctx);
+        }
+    }
+
     fn set_icsr_interrupt_pending(&mut self, exception_number: u32) {
         self.system_control_block.icsr_mut().set_isrpending();
         self.system_control_block
@@ -542,21 +778,37 @@ impl NVICComponent {
                 self.system_control_block.icsr().get_nmipendset()
                     && !self.exception_active[InterruptId::NMI.as_exception_number()]
             }
+            InterruptId::HardFault => *self.hardfault_pending,
+            // The active bits may be also set by software, what prevents the exception
+            // from being taken on the hardware (verified by experiments for SVCall).
+            // The same is assumed for the configurable faults below.
+            InterruptId::SVCall => {
+                let shcsr = self.system_control_block.shcsr();
+                shcsr.get_svcallpended() && !shcsr.get_svcallact()
+            }
+            InterruptId::PendSV => {
+                self.system_control_block.icsr().get_pendsvset()
+                    && !self.system_control_block.shcsr().get_pendsvact()
+            }
             InterruptId::SysTick => self.system_control_block.icsr().get_pendstset(),
             InterruptId::Interrupt(_) => {
                 self.register_bank.get_interrupt_enabled(id)
                     && self.register_bank.get_interrupt_pending(id)
             }
-            InterruptId::Reset
-            | InterruptId::HardFault
-            | InterruptId::MemManage
-            | InterruptId::BusFault
-            | InterruptId::UsageFault
-            | InterruptId::SVCall
-            | InterruptId::DebugMonitor
-            | InterruptId::PendSV => {
-                // TODO: Change to checking corresponding registers for each
-                // interrupt (ARCR, ICSR and SHCSR not implemented yet).
+            InterruptId::MemManage => {
+                let shcsr = self.system_control_block.shcsr();
+                shcsr.get_memfaultpended() && shcsr.get_memfaultena() && !shcsr.get_memfaultact()
+            }
+            InterruptId::BusFault => {
+                let shcsr = self.system_control_block.shcsr();
+                shcsr.get_busfaultpended() && shcsr.get_busfaultena() && !shcsr.get_busfaultact()
+            }
+            InterruptId::UsageFault => {
+                let shcsr = self.system_control_block.shcsr();
+                shcsr.get_usgfaultpended() && shcsr.get_usgfaultena() && !shcsr.get_usgfaultact()
+            }
+            InterruptId::Reset | InterruptId::DebugMonitor => {
+                // TODO: Change to checking DEMCR for DebugMonitor.
                 false
             }
         }
@@ -573,8 +825,17 @@ impl NVICComponent {
     /// - Async exception preempting the current priority (handled in main exc logic)
     /// - Async exception that would preempt if PRIMASK were zero – just causes a wake-up
     ///   (handled here)
+    ///
+    /// WFE is woken up only by events, that are handled by the core while it is running.
+    /// Here it is only SEVONPEND (see [`Self::signal_event_on_pend()`]).
     fn check_non_exc_wakeup_event(&mut self, ctx: &mut Context) {
-        if self.primask_copy.primask() {
+        if self.waiting_for_event {
+            if self.sev_on_pend_wakeup {
+                trace!("NVIC wakes up the CPU from WFE on SEVONPEND");
+                self.core.spurious_wakeup(ctx);
+                self.wakeup_cpu(ctx);
+            }
+        } else if self.primask_copy.primask() {
             let would_preempt_if_not_primask = {
                 self.wfi_primask_handling_scope = true;
                 // Fixup on possible unwinding
@@ -590,6 +851,8 @@ impl NVICComponent {
     }
 
     fn wakeup_cpu(&mut self, ctx: &mut Context) {
+        self.waiting_for_event = false;
+        self.sev_on_pend_wakeup = false;
         self.cpu_mode.set_next(CpuMode::Run);
         // Set SLEEPING wire to low
         PRCMProxy.on_cpu_mode(ctx, CpuMode::Run);
@@ -680,14 +943,25 @@ impl NVICComponent {
                 i32::from(self.system_control_block.shpr3().get_systick_priority())
             }
             InterruptId::Interrupt(_) => i32::from(self.register_bank.get_interrupt_priority(id)),
-            InterruptId::MemManage
-            | InterruptId::BusFault
-            | InterruptId::UsageFault
-            | InterruptId::SVCall
-            | InterruptId::DebugMonitor
-            | InterruptId::PendSV => {
-                // TODO: Change to reading value from SHPR - not implemented yet.
-                unimplemented!()
+            InterruptId::MemManage => {
+                i32::from(self.system_control_block.shpr1().get_memmanage_priority())
+            }
+            InterruptId::BusFault => {
+                i32::from(self.system_control_block.shpr1().get_busfault_priority())
+            }
+            InterruptId::UsageFault => {
+                i32::from(self.system_control_block.shpr1().get_usagefault_priority())
+            }
+            InterruptId::SVCall => {
+                i32::from(self.system_control_block.shpr2().get_svcall_priority())
+            }
+            InterruptId::DebugMonitor => i32::from(
+                self.system_control_block
+                    .shpr3()
+                    .get_debugmonitor_priority(),
+            ),
+            InterruptId::PendSV => {
+                i32::from(self.system_control_block.shpr3().get_pendsv_priority())
             }
         }
     }
@@ -801,6 +1075,9 @@ impl NVICComponent {
             SYSTICK_ADDR_SPACE => {
                 SysTick::write_register(self, ctx, req);
             },
+            MPU_ADDR_SPACE => {
+                MPURegisters::write_register(self, ctx, req);
+            },
             stir::STIR_ADDR => stir::SoftwareTriggerInterruptRegister::write(self, data),
             _ => panic!("Cannot write to memory adress: {addr:?}"),
         }
@@ -842,6 +1119,7 @@ impl NVICComponent {
             },
             SCB_ADDR_SPACE => SystemControlBlock::read_register(self, req),
             SYSTICK_ADDR_SPACE => SysTick::read_register(self, req),
+            MPU_ADDR_SPACE => MPURegisters::read_register(self, req),
             // FIXME: STIR can only be written by unprivileged when a special bit in CSR is set
             stir::STIR_ADDR => stir::SoftwareTriggerInterruptRegister::read(),
             _ => panic!("We cannot read from memory adresses: {addr:?}"),
@@ -864,6 +1142,7 @@ impl NVICComponent {
             InterruptId::NMI => {
                 self.system_control_block.icsr_mut().set_nmipendset();
             }
+            InterruptId::HardFault => self.hardfault_pending.set_next(false),
             InterruptId::MemManage => {
                 self.system_control_block.shcsr_mut().clear_memfaultpended();
                 self.system_control_block.shcsr_mut().set_memfaultact();
@@ -919,6 +1198,8 @@ impl NVICComponent {
 
         match id {
             InterruptId::NMI => self.system_control_block.icsr_mut().clear_nmipendset(),
+            // HardFault has no active bit in SHCSR.
+            InterruptId::HardFault => (),
             InterruptId::MemManage => self.system_control_block.shcsr_mut().clear_memfaultact(),
             InterruptId::BusFault => self.system_control_block.shcsr_mut().clear_busfaultact(),
             InterruptId::UsageFault => self.system_control_block.shcsr_mut().clear_usgfaultact(),
@@ -1055,6 +1336,7 @@ impl SimpleHandler for NVICComponent {
 
 mod interrupt {
     use super::{EXCEPTIONS_AND_INTERRUPTS_COUNT, EXCEPTIONS_COUNT, INTERRUPTS_COUNT};
+    use serde::{Deserialize, Serialize};
 
     /// [ARM-TDG] Table 7.1 - List of System Exceptions.
     pub(super) const RESET_PRIORITY: i32 = -3;
@@ -1078,7 +1360,7 @@ mod interrupt {
     /// to (exception number - `EXCEPTIONS_COUNT`) and it corresponds to bit in
     /// interrupt registers ([TI-TRM] Table 4-2).
     /// `interrupt_number` is defined in [ARM-ARM] B3.4.1 NVIC operation - the last note.
-    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    #[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
     pub enum Id {
         Reset,
         NMI,
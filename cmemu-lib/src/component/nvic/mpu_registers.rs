@@ -0,0 +1,160 @@
+use core::ops::Range;
+use serde::{Deserialize, Serialize};
+
+use crate::common::{Address, BitstringUtils, Word};
+use crate::component::bus_matrix::{MPU_REGIONS_COUNT, MPUConfiguration};
+use crate::component::nvic::{NVICComponent, ReadRequest, WriteRequest};
+use crate::engine::Context;
+use crate::proxy::BusMatrixProxy;
+use log::{trace, warn};
+
+/// MPU address space.
+/// [ARM-ARM] Table B3-11 MPU registers summary.
+pub(super) const MPU_ADDR_SPACE: Range<Address> =
+    Address::from_const(0xE000_ED90)..Address::from_const(0xE000_EDBC);
+
+/// [ARM-ARM] B3.5.5 MPU Type Register.
+const MPU_TYPE_ADDR: Address = Address::from_const(0xE000_ED90);
+/// [ARM-ARM] B3.5.6 MPU Control Register.
+const MPU_CTRL_ADDR: Address = Address::from_const(0xE000_ED94);
+/// [ARM-ARM] B3.5.7 MPU Region Number Register.
+const MPU_RNR_ADDR: Address = Address::from_const(0xE000_ED98);
+/// [ARM-ARM] B3.5.8 MPU Region Base Address Register, followed by `MPU_RASR`
+/// and three aliases of the pair: `MPU_RBAR_A1`, `MPU_RASR_A1`, ..., `MPU_RASR_A3`.
+const MPU_RBAR_ADDR: Address = Address::from_const(0xE000_ED9C);
+
+/// [ARM-ARM] B3.5 Protected Memory System Architecture, `PMSAv7` - the MPU registers.
+///
+/// The configuration is sent to the Bus Matrix and the Core, which check the accesses,
+/// whenever it changes.
+#[derive(Clone, Serialize, Deserialize)]
+pub(super) struct MPURegisters {
+    /// `MPU_RNR` - selects the region accessed through `MPU_RBAR` and `MPU_RASR`.
+    region_number: usize,
+    configuration: MPUConfiguration,
+}
+
+impl MPURegisters {
+    /// [ARM-ARM] B3.5.8 `MPU_RBAR.VALID`
+    const RBAR_VALID_BITNUM: u32 = 4;
+
+    pub(super) fn new() -> Self {
+        Self {
+            region_number: 0,
+            configuration: MPUConfiguration::new(),
+        }
+    }
+
+    pub(super) fn read_register(nvic: &NVICComponent, req: ReadRequest) -> Word {
+        let this = &nvic.mpu;
+        let ReadRequest { addr, mask } = req;
+        let addr = addr.aligned_down_to_4_bytes();
+        let value = match addr {
+            // DREGION, and no separate instruction regions
+            #[allow(clippy::cast_possible_truncation)]
+            MPU_TYPE_ADDR => Word::from((MPU_REGIONS_COUNT as u32) << 8),
+            _ if MPU_REGIONS_COUNT == 0 => {
+                warn!("Reading MPU register {addr:?}, but the MPU is not implemented.");
+                Word::from(0)
+            }
+            MPU_CTRL_ADDR => this.configuration.ctrl(),
+            #[allow(clippy::cast_possible_truncation)]
+            MPU_RNR_ADDR => Word::from(this.region_number as u32),
+            _ => {
+                let Some(region) = this.selected_region() else {
+                    return Word::from(0);
+                };
+                if Self::is_rbar(addr) {
+                    #[allow(clippy::cast_possible_truncation)]
+                    let region_field = Word::from(region as u32);
+                    this.configuration.region_base(region) | region_field
+                } else {
+                    this.configuration.region_attributes(region)
+                }
+            }
+        };
+        value & mask
+    }
+
+    pub(super) fn write_register(nvic: &mut NVICComponent, ctx: &mut Context, req: WriteRequest) {
+        let WriteRequest { addr, data, mask } = req;
+        let addr = addr.aligned_down_to_4_bytes();
+        if addr == MPU_TYPE_ADDR {
+            warn!("Writing value {data:x} to read-only MPU_TYPE has no effect.");
+            return;
+        }
+        if MPU_REGIONS_COUNT == 0 {
+            warn!("Writing MPU register {addr:?}, but the MPU is not implemented.");
+            return;
+        }
+
+        let old = Self::read_register(
+            nvic,
+            ReadRequest {
+                addr,
+                mask: Word::from(u32::MAX),
+            },
+        );
+        let value = (data & mask) | (old & !mask);
+        let this = &mut nvic.mpu;
+        match addr {
+            MPU_CTRL_ADDR => this.configuration.set_ctrl(value),
+            MPU_RNR_ADDR => this.region_number = u32::from(value) as usize & 0xFF,
+            _ if Self::is_rbar(addr) => {
+                if value.get_bit(Self::RBAR_VALID_BITNUM) {
+                    this.region_number = u32::from(value) as usize & 0xF;
+                }
+                if let Some(region) = this.selected_region() {
+                    this.configuration.set_region_base(region, value);
+                }
+            }
+            _ => {
+                if let Some(region) = this.selected_region() {
+                    this.configuration.set_region_attributes(region, value);
+                }
+            }
+        }
+        Self::send_configuration(nvic, ctx);
+    }
+
+    /// [ARM-ARM] B3.5.6 `MPU_CTRL.HFNMIENA`: unless it is set, the MPU is disabled
+    /// when the execution priority is negative, i.e., in `HardFault` or NMI handlers,
+    /// or with FAULTMASK set.
+    pub(super) fn run_tick(nvic: &mut NVICComponent, ctx: &mut Context) {
+        let configuration = &nvic.mpu.configuration;
+        let bypassed = configuration.is_enabled()
+            && !configuration.is_hfnmi_enabled()
+            && nvic.execution_priority() < 0;
+        if bypassed != configuration.is_bypassed() {
+            trace!("MPU bypassed: {bypassed}");
+            nvic.mpu.configuration.set_bypassed(bypassed);
+            Self::send_configuration(nvic, ctx);
+        }
+    }
+
+    fn send_configuration(nvic: &mut NVICComponent, ctx: &mut Context) {
+        let configuration = nvic.mpu.configuration;
+        nvic.core.update_mpu_configuration(ctx, configuration);
+        BusMatrixProxy.update_mpu_configuration(ctx, configuration);
+    }
+
+    #[allow(
+        clippy::absurd_extreme_comparisons,
+        reason = "No regions without the soc-has-mpu feature"
+    )]
+    fn selected_region(&self) -> Option<usize> {
+        if self.region_number < MPU_REGIONS_COUNT {
+            Some(self.region_number)
+        } else {
+            warn!(
+                "Accessing MPU region {}, which is not implemented, is UNPREDICTABLE.",
+                self.region_number
+            );
+            None
+        }
+    }
+
+    fn is_rbar(addr: Address) -> bool {
+        addr.offset_from(MPU_RBAR_ADDR).is_multiple_of(8)
+    }
+}
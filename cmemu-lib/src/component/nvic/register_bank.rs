@@ -4,6 +4,7 @@ use crate::engine::{
     DisableableComponent, SeqFlopMemoryBank, Subcomponent, TickComponent, TickComponentExtra,
 };
 use log::warn;
+use serde::{Deserialize, Serialize};
 
 /// Each bit of bit register stores information of one interrupt.
 /// One bit register is used for as many interrupts as bits per register.
@@ -19,7 +20,15 @@ pub(super) const PRIORITY_REGISTERS_COUNT: usize = INTERRUPTS_COUNT.div_ceil(REG
 /// to 3 highest bits of register, other bits are zeroed.
 const PRIORITY_BITS_MASK: Word = Word::from_const(0b1110_0000_1110_0000_1110_0000_1110_0000);
 
-#[derive(Subcomponent, TickComponent, TickComponentExtra, DisableableComponent)]
+#[derive(
+    Clone,
+    Subcomponent,
+    TickComponent,
+    TickComponentExtra,
+    DisableableComponent,
+    Serialize,
+    Deserialize,
+)]
 pub(super) struct NVICRegisterBank<SC>
 where
     SC: Subcomponent<Member = Self>,
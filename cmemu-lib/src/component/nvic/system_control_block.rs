@@ -8,12 +8,13 @@
 use crate::Bitstring;
 use crate::bitstring_extract;
 use crate::common::{Address, Bitstring, BitstringUtils, Word, bitstring::constants as bsc};
-use crate::component::nvic::{NVICComponent, ReadRequest, WriteRequest};
+use crate::component::nvic::{Fault, NVICComponent, ReadRequest, WriteRequest};
 use crate::engine::{
-    CombFlopMemoryBankSimple, Context, DisableableComponent, SeqFlopMemoryBankSimple, Subcomponent,
-    TickComponent, TickComponentExtra,
+    CombFlopMemoryBankSimple, Context, DisableableComponent, ResetRequest, SeqFlopMemoryBankSimple,
+    Subcomponent, TickComponent, TickComponentExtra, WarmResetSource,
 };
 use log::warn;
+use serde::{Deserialize, Serialize};
 
 /// [ARM-ARM] Table B3-4 Summary of SCB registers
 const CPUID_ADDR: Address = Address::from_const(0xE000_ED00);
@@ -67,16 +68,17 @@ macro_rules! reg_accessors {
     };
 }
 
+// Note: bits are modified on the next value, so multiple bits can be changed in a cycle.
 macro_rules! reg_bit_setters {
     ($set_func:ident, $clear_func:ident, $bitnum:path) => {
         pub(super) fn $clear_func(&mut self) {
-            let new_val = Word::from_const(0);
-            self.hardware_write(new_val, new_val.with_bit_set($bitnum, true));
+            let next = self.next_builder();
+            next.0 = next.0.with_bit_set($bitnum, false);
         }
 
         pub(super) fn $set_func(&mut self) {
-            let new_val = Word::from_const(0).with_bit_set($bitnum, true);
-            self.hardware_write(new_val, new_val);
+            let next = self.next_builder();
+            next.0 = next.0.with_bit_set($bitnum, true);
         }
     };
 }
@@ -86,7 +88,15 @@ macro_rules! reg_bit_setters {
 /// System Control Block.
 ///
 /// For a description of the System Control Block see [`system_control_block`](self).
-#[derive(Subcomponent, TickComponent, TickComponentExtra, DisableableComponent)]
+#[derive(
+    Clone,
+    Subcomponent,
+    TickComponent,
+    TickComponentExtra,
+    DisableableComponent,
+    Serialize,
+    Deserialize,
+)]
 pub(super) struct SystemControlBlock<SC>
 where
     SC: Subcomponent<Member = Self>,
@@ -229,9 +239,18 @@ where
                 this.vtor_mut().write(data, mask);
                 nvic.core.update_vector_table_offset_register(ctx, data);
             }
-            AIRCR_ADDR => this.aircr_mut().write(data, mask),
+            AIRCR_ADDR => {
+                this.aircr_mut().write(data, mask);
+                if AIRCR::is_system_reset_request(data & mask) {
+                    ctx.request_reset(ResetRequest::Warm(WarmResetSource::SystemResetRequest));
+                }
+            }
             SCR_ADDR => this.scr_mut().write(data, mask),
-            CCR_ADDR => this.ccr_mut().write(data, mask),
+            CCR_ADDR => {
+                this.ccr_mut().write(data, mask);
+                nvic.core
+                    .update_configuration_and_control_register(ctx, data, mask);
+            }
             SHPR1_ADDR => this.shpr1_mut().write(data, mask),
             SHPR2_ADDR => this.shpr2_mut().write(data, mask),
             SHPR3_ADDR => this.shpr3_mut().write(data, mask),
@@ -269,6 +288,35 @@ where
     reg_accessors!(bfar, bfar_mut, BFAR);
     reg_accessors!(afsr, afsr_mut, AFSR);
     reg_accessors!(cpacr, cpacr_mut, CPACR);
+
+    /// Records the status of `fault` in [`CFSR`]
+    /// (and the faulting address in [`MMFAR`] or [`BFAR`]).
+    pub(super) fn record_fault_status(&mut self, fault: Fault) {
+        let bitnum = match fault {
+            Fault::UndefinedInstruction => CFSR::UNDEFINSTR_BITNUM,
+            Fault::InvalidState => CFSR::INVSTATE_BITNUM,
+            Fault::InvalidPcLoad => CFSR::INVPC_BITNUM,
+            Fault::DivideByZero => CFSR::DIVBYZERO_BITNUM,
+            Fault::InstructionAccessViolation => CFSR::IACCVIOL_BITNUM,
+            Fault::DataAccessViolation { address } => {
+                self.cfsr_mut().set_mmarvalid();
+                self.mmfar_mut().set_address(address);
+                CFSR::DACCVIOL_BITNUM
+            }
+            Fault::UnstackingAccessViolation => CFSR::MUNSTKERR_BITNUM,
+            Fault::StackingAccessViolation => CFSR::MSTKERR_BITNUM,
+            Fault::InstructionBusError => CFSR::IBUSERR_BITNUM,
+            Fault::PreciseDataBusError { address } => {
+                self.cfsr_mut().set_bfarvalid();
+                self.bfar_mut().set_address(address);
+                CFSR::PRECISERR_BITNUM
+            }
+            Fault::ImpreciseDataBusError => CFSR::IMPRECISERR_BITNUM,
+            Fault::UnstackingBusError => CFSR::UNSTKERR_BITNUM,
+            Fault::StackingBusError => CFSR::STKERR_BITNUM,
+        };
+        self.cfsr_mut().set_fault_status(bitnum);
+    }
 }
 
 pub(in crate::component) trait SCBRegister: FlopProxy
@@ -331,12 +379,6 @@ where
         self.write_impl(data, mask, forbidden_bits);
     }
 
-    /// This function sets bits of the register ignoring `reserved_bits_mask` and `read_only_bits_mask`.
-    /// It is used to write bits which are set by hardware only.
-    fn hardware_write(&mut self, data: Word, mask: Word) {
-        self.write_impl(data, mask, Word::from_const(0_u32));
-    }
-
     fn write_impl(&mut self, data: Word, mask: Word, forbidden_bits: Word) {
         let old_value = Word::from(self.get());
         let modified_bits = mask & !forbidden_bits;
@@ -412,7 +454,15 @@ macro_rules! word_conversions {
 // - clearing the pending state of SysTick exception,
 // - pending this exception once again.
 
-#[derive(Subcomponent, TickComponent, TickComponentExtra, DisableableComponent)]
+#[derive(
+    Clone,
+    Subcomponent,
+    TickComponent,
+    TickComponentExtra,
+    DisableableComponent,
+    Serialize,
+    Deserialize,
+)]
 pub(super) struct ICSR {
     #[flop]
     vectpending: CombFlopMemoryBankSimple<Bitstring![9]>,
@@ -425,12 +475,8 @@ pub(super) struct ICSR {
     #[flop]
     isrpreeemt: CombFlopMemoryBankSimple<Bitstring![1]>,
     #[flop]
-    pendstclr: CombFlopMemoryBankSimple<Bitstring![1]>,
-    #[flop]
     pendstset: CombFlopMemoryBankSimple<Bitstring![1]>,
     #[flop]
-    pendsvclr: CombFlopMemoryBankSimple<Bitstring![1]>,
-    #[flop]
     pendsvset: CombFlopMemoryBankSimple<Bitstring![1]>,
     #[flop]
     nmipendset: CombFlopMemoryBankSimple<Bitstring![1]>,
@@ -446,9 +492,7 @@ impl FlopProxy for ICSR {
                 | (u32::from(self.vectpending.get()) << Self::VECTPENDING_MASK.trailing_zeros())
                 | (u32::from(self.isrpending.get()) << Self::ISRPENDING_BITNUM)
                 | (u32::from(self.isrpreeemt.get()) << Self::ISRPREEMPT_BITNUM)
-                | (u32::from(self.pendstclr.get()) << Self::PENDSTCLR_BITNUM)
                 | (u32::from(self.pendstset.get()) << Self::PENDSTSET_BITNUM)
-                | (u32::from(self.pendsvclr.get()) << Self::PENDSVCLR_BITNUM)
                 | (u32::from(self.pendsvset.get()) << Self::PENDSVSET_BITNUM)
                 | (u32::from(self.nmipendset.get()) << Self::NMIPENDSET_BITNUM),
         )
@@ -504,9 +548,7 @@ impl SCBRegister for ICSR {
             vectactive: CombFlopMemoryBankSimple::new(bsc::C_0_0000_0000),
             isrpending: CombFlopMemoryBankSimple::new(bsc::C_0),
             isrpreeemt: CombFlopMemoryBankSimple::new(bsc::C_0),
-            pendstclr: CombFlopMemoryBankSimple::new(bsc::C_0),
             pendstset: CombFlopMemoryBankSimple::new(bsc::C_0),
-            pendsvclr: CombFlopMemoryBankSimple::new(bsc::C_0),
             pendsvset: CombFlopMemoryBankSimple::new(bsc::C_0),
             nmipendset: CombFlopMemoryBankSimple::new(bsc::C_0),
         }
@@ -518,11 +560,7 @@ impl SCBRegister for ICSR {
         self.try_write_vectactive(data, mask, forbidden_bits);
         self.try_write_isrpending(data, mask, forbidden_bits);
         self.try_write_isrpreempt(data, mask, forbidden_bits);
-        self.try_write_pendstclr(data, mask, forbidden_bits);
-        self.try_write_pendstset(data, mask, forbidden_bits);
-        self.try_write_pendsvclr(data, mask, forbidden_bits);
-        self.try_write_pendsvset(data, mask, forbidden_bits);
-        self.try_write_nmipendset(data, mask, forbidden_bits);
+        self.write_pending_bits(data & mask & !forbidden_bits);
     }
 }
 
@@ -591,23 +629,18 @@ impl ICSR {
 
     /// [ARM-ARM] B3.2.4
     const PENDSTCLR_BITNUM: u32 = 25;
-    const PENDSTCLR_MASK: u32 = 1 << Self::PENDSTCLR_BITNUM;
 
     /// [ARM-ARM] B3.2.4
     const PENDSTSET_BITNUM: u32 = 26;
-    const PENDSTSET_MASK: u32 = 1 << Self::PENDSTSET_BITNUM;
 
     /// [ARM-ARM] B3.2.4
     const PENDSVCLR_BITNUM: u32 = 27;
-    const PENDSVCLR_MASK: u32 = 1 << Self::PENDSVCLR_BITNUM;
 
     /// [ARM-ARM] B3.2.4
     const PENDSVSET_BITNUM: u32 = 28;
-    const PENDSVSET_MASK: u32 = 1 << Self::PENDSVSET_BITNUM;
 
     /// [ARM-ARM] B3.2.4
     const NMIPENDSET_BITNUM: u32 = 31;
-    const NMIPENDSET_MASK: u32 = 1 << Self::NMIPENDSET_BITNUM;
 
     pub(super) fn get_nmipendset(&self) -> bool {
         self.nmipendset.get() == bsc::C_1
@@ -617,6 +650,10 @@ impl ICSR {
         self.pendstset.get() == bsc::C_1
     }
 
+    pub(super) fn get_pendsvset(&self) -> bool {
+        self.pendsvset.get() == bsc::C_1
+    }
+
     // TODO: Remove a leading underscore from methods names once they'll be used.
     reg_range_setters_composite!(
         set_vectactive,
@@ -653,41 +690,51 @@ impl ICSR {
         Self::ISRPREEMPT_MASK,
         isrpreeemt
     );
-    reg_bit_setters_composite!(
-        _set_pendstclr,
-        _clear_pendstclr,
-        try_write_pendstclr,
-        Self::PENDSTCLR_MASK,
-        pendstclr
-    );
-    reg_bit_setters_composite!(
-        set_pendstset,
-        clear_pendstset,
-        try_write_pendstset,
-        Self::PENDSTSET_MASK,
-        pendstset
-    );
-    reg_bit_setters_composite!(
-        _set_pendsvclr,
-        _clear_pendsvclr,
-        try_write_pendsvclr,
-        Self::PENDSVCLR_MASK,
-        pendsvclr
-    );
-    reg_bit_setters_composite!(
-        _set_pendsvset,
-        clear_pendsvset,
-        try_write_pendsvset,
-        Self::PENDSVSET_MASK,
-        pendsvset
-    );
-    reg_bit_setters_composite!(
-        set_nmipendset,
-        clear_nmipendset,
-        try_write_nmipendset,
-        Self::NMIPENDSET_MASK,
-        nmipendset
-    );
+    pub(super) fn set_pendstset(&mut self) {
+        self.pendstset.set_next(bsc::C_1);
+    }
+
+    pub(super) fn clear_pendstset(&mut self) {
+        self.pendstset.set_next(bsc::C_0);
+    }
+
+    pub(super) fn set_pendsvset(&mut self) {
+        self.pendsvset.set_next(bsc::C_1);
+    }
+
+    pub(super) fn clear_pendsvset(&mut self) {
+        self.pendsvset.set_next(bsc::C_0);
+    }
+
+    pub(super) fn set_nmipendset(&mut self) {
+        self.nmipendset.set_next(bsc::C_1);
+    }
+
+    pub(super) fn clear_nmipendset(&mut self) {
+        self.nmipendset.set_next(bsc::C_0);
+    }
+
+    /// [ARM-ARM] B3.2.4: the pending state of NMI, `PendSV` and `SysTick` is set or cleared
+    /// by writing 1 to the corresponding bit, while writing 0 has no effect.
+    /// Writing 1 to both PENDSVSET and PENDSVCLR (or PENDSTSET and PENDSTCLR) is UNPREDICTABLE,
+    /// we let the clear win.
+    fn write_pending_bits(&mut self, written: Word) {
+        if written.get_bit(Self::NMIPENDSET_BITNUM) {
+            self.set_nmipendset();
+        }
+        if written.get_bit(Self::PENDSVSET_BITNUM) {
+            self.set_pendsvset();
+        }
+        if written.get_bit(Self::PENDSVCLR_BITNUM) {
+            self.clear_pendsvset();
+        }
+        if written.get_bit(Self::PENDSTSET_BITNUM) {
+            self.set_pendstset();
+        }
+        if written.get_bit(Self::PENDSTCLR_BITNUM) {
+            self.clear_pendstset();
+        }
+    }
 }
 
 // ----------------------------------------------------------------------------
@@ -701,7 +748,7 @@ impl ICSR {
 /// * [ARM-TRM-G] 8.2.2 NVIC register descriptions :: Vector Table Offset Register
 pub(in crate::component) type VTOR = SeqFlopMemoryBankSimple<VTORContent>;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub(in crate::component) struct VTORContent(Word);
 
 word_conversions!(VTORContent);
@@ -739,7 +786,7 @@ impl SCBRegister for VTOR {
 /// * [ARM-TRM-G] 8.2.2 NVIC register descriptions :: Application Interrupt and Reset Control Register
 type AIRCR = SeqFlopMemoryBankSimple<AIRCRContent>;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub(super) struct AIRCRContent(Word);
 
 word_conversions!(AIRCRContent);
@@ -791,6 +838,14 @@ impl AIRCR {
     const VECTKEY_WRITE_VALUE: Word = Word::from_const(0x05FA_0000);
     const VECTKEYSTAT_READ_VALUE: Word = Word::from_const(0xFA05_0000);
 
+    /// Whether the written `data` requests a system reset with `SYSRESETREQ`.
+    /// `VECTRESET` is for debugging only and it is ignored.
+    pub(super) fn is_system_reset_request(data: Word) -> bool {
+        // [ARM-ARM] B3.2.6 - SYSRESETREQ, bit[2].
+        let key_bits = data & Self::VECTKEY_VECTKEYSTAT_BITS_MASK;
+        (key_bits ^ Self::VECTKEY_WRITE_VALUE).is_zero() && data.get_bit(2)
+    }
+
     pub(super) fn get_prigroup(&self) -> u32 {
         // [ARM-ARM] B3.2.6 - PRIGROUP, bits[10:8].
         let prigroup = bitstring_extract!((self.0)<10:8> | 3 bits);
@@ -809,7 +864,7 @@ impl AIRCR {
 /// * [ARM-TRM-G] 8.2.2 NVIC register descriptions :: System Control Register
 type SCR = SeqFlopMemoryBankSimple<SCRContent>;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub(super) struct SCRContent(Word);
 
 word_conversions!(SCRContent);
@@ -845,10 +900,10 @@ impl SCBRegister for SCR {
 /// Relevant documentation:
 /// * [ARM-ARM] B3.2.8 Configuration and Control Register
 /// * [ARM-TRM-G] 8.2.2 NVIC register descriptions :: Configuration Control Register
-type CCR = SeqFlopMemoryBankSimple<CCRContent>;
+pub(in crate::component) type CCR = SeqFlopMemoryBankSimple<CCRContent>;
 
-#[derive(Clone, Copy)]
-pub(super) struct CCRContent(Word);
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub(in crate::component) struct CCRContent(Word);
 
 word_conversions!(CCRContent);
 
@@ -874,6 +929,21 @@ impl SCBRegister for CCR {
     }
 }
 
+impl CCR {
+    /// [ARM-ARM] B3.2.8
+    const NONBASETHRDENA_BITNUM: u32 = 0;
+    /// [ARM-ARM] B3.2.8
+    const DIV_0_TRP_BITNUM: u32 = 4;
+
+    pub(in crate::component) fn get_nonbasethrdena(&self) -> bool {
+        self.0.get_bit(Self::NONBASETHRDENA_BITNUM)
+    }
+
+    pub(in crate::component) fn get_div_0_trp(&self) -> bool {
+        self.0.get_bit(Self::DIV_0_TRP_BITNUM)
+    }
+}
+
 // ----------------------------------------------------------------------------
 // [ARM-ARM] B3.2.10 System Handler Priority Register 1
 // ----------------------------------------------------------------------------
@@ -885,7 +955,7 @@ impl SCBRegister for CCR {
 /// * [ARM-TRM-G] 8.2.2 NVIC register descriptions :: System Handler Priority Registers
 type SHPR1 = SeqFlopMemoryBankSimple<SHPR1Content>;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub(super) struct SHPR1Content(Word);
 
 word_conversions!(SHPR1Content);
@@ -920,6 +990,27 @@ impl SCBRegister for SHPR1 {
     }
 }
 
+impl SHPR1 {
+    /// [ARM-ARM] B3.2.10
+    const MEMMANAGE_PRIORITY_BYTENUM: usize = 0;
+    /// [ARM-ARM] B3.2.10
+    const BUSFAULT_PRIORITY_BYTENUM: usize = 1;
+    /// [ARM-ARM] B3.2.10
+    const USAGEFAULT_PRIORITY_BYTENUM: usize = 2;
+
+    pub(super) fn get_memmanage_priority(&self) -> u8 {
+        self.0.to_le_bytes()[Self::MEMMANAGE_PRIORITY_BYTENUM]
+    }
+
+    pub(super) fn get_busfault_priority(&self) -> u8 {
+        self.0.to_le_bytes()[Self::BUSFAULT_PRIORITY_BYTENUM]
+    }
+
+    pub(super) fn get_usagefault_priority(&self) -> u8 {
+        self.0.to_le_bytes()[Self::USAGEFAULT_PRIORITY_BYTENUM]
+    }
+}
+
 // ----------------------------------------------------------------------------
 // [ARM-ARM] B3.2.11 System Handler Priority Register 2
 // ----------------------------------------------------------------------------
@@ -931,7 +1022,7 @@ impl SCBRegister for SHPR1 {
 /// * [ARM-TRM-G] 8.2.2 NVIC register descriptions :: System Handler Priority Registers
 type SHPR2 = SeqFlopMemoryBankSimple<SHPR2Content>;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub(super) struct SHPR2Content(Word);
 
 word_conversions!(SHPR2Content);
@@ -966,6 +1057,15 @@ impl SCBRegister for SHPR2 {
     }
 }
 
+impl SHPR2 {
+    /// [ARM-ARM] B3.2.11
+    const SVCALL_PRIORITY_BYTENUM: usize = 3;
+
+    pub(super) fn get_svcall_priority(&self) -> u8 {
+        self.0.to_le_bytes()[Self::SVCALL_PRIORITY_BYTENUM]
+    }
+}
+
 // ----------------------------------------------------------------------------
 // [ARM-ARM] B3.2.12 System Handler Priority Register 3
 // ----------------------------------------------------------------------------
@@ -977,7 +1077,7 @@ impl SCBRegister for SHPR2 {
 /// * [ARM-TRM-G] 8.2.2 NVIC register descriptions :: System Handler Priority Registers
 type SHPR3 = SeqFlopMemoryBankSimple<SHPR3Content>;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub(super) struct SHPR3Content(Word);
 
 word_conversions!(SHPR3Content);
@@ -1013,9 +1113,21 @@ impl SCBRegister for SHPR3 {
 }
 
 impl SHPR3 {
+    /// [ARM-ARM] B3.2.12
+    const DEBUGMONITOR_PRIORITY_BYTENUM: usize = 0;
+    /// [ARM-ARM] B3.2.12
+    const PENDSV_PRIORITY_BYTENUM: usize = 2;
     /// [ARM-ARM] B3.2.12
     const SYSTICK_PRIORITY_BYTENUM: usize = 3;
 
+    pub(super) fn get_debugmonitor_priority(&self) -> u8 {
+        self.0.to_le_bytes()[Self::DEBUGMONITOR_PRIORITY_BYTENUM]
+    }
+
+    pub(super) fn get_pendsv_priority(&self) -> u8 {
+        self.0.to_le_bytes()[Self::PENDSV_PRIORITY_BYTENUM]
+    }
+
     pub(super) fn get_systick_priority(&self) -> u8 {
         self.0.to_le_bytes()[Self::SYSTICK_PRIORITY_BYTENUM]
     }
@@ -1030,9 +1142,12 @@ impl SHPR3 {
 /// Relevant documentation:
 /// * [ARM-ARM] B3.2.13 System Handler Control and State Register
 /// * [ARM-TRM-G] 8.2.2 NVIC register descriptions :: System Handler Control and State Register
-type SHCSR = SeqFlopMemoryBankSimple<SHCSRContent>;
+// Comb is required here because 2 changes can happen in the same cycle, e.g.:
+// - activating the SVCall exception (clearing its pending state),
+// - pending another system exception.
+type SHCSR = CombFlopMemoryBankSimple<SHCSRContent>;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub(super) struct SHCSRContent(Word);
 
 word_conversions!(SHCSRContent);
@@ -1080,25 +1195,30 @@ impl SHCSR {
     const BUSFAULTPENDED_BITNUM: u32 = 14;
     /// [ARM-ARM] B3.2.13.
     const SVCALLPENDED_BITNUM: u32 = 15;
+    /// [ARM-ARM] B3.2.13.
+    const MEMFAULTENA_BITNUM: u32 = 16;
+    /// [ARM-ARM] B3.2.13.
+    const BUSFAULTENA_BITNUM: u32 = 17;
+    /// [ARM-ARM] B3.2.13.
+    const USGFAULTENA_BITNUM: u32 = 18;
 
-    // TODO: Remove a leading underscore from methods names once they'll be used.
     reg_bit_setters!(
-        _set_svcallpended,
+        set_svcallpended,
         clear_svcallpended,
         Self::SVCALLPENDED_BITNUM
     );
     reg_bit_setters!(
-        _set_busfaultpended,
+        set_busfaultpended,
         clear_busfaultpended,
         Self::BUSFAULTPENDED_BITNUM
     );
     reg_bit_setters!(
-        _set_memfaultpended,
+        set_memfaultpended,
         clear_memfaultpended,
         Self::MEMFAULTPENDED_BITNUM
     );
     reg_bit_setters!(
-        _set_usgfaultpended,
+        set_usgfaultpended,
         clear_usgfaultpended,
         Self::USGFAULTPENDED_BITNUM
     );
@@ -1109,6 +1229,54 @@ impl SHCSR {
     reg_bit_setters!(set_usgfaultact, clear_usgfaultact, Self::USGFAULTACT_BITNUM);
     reg_bit_setters!(set_busfaultact, clear_busfaultact, Self::BUSFAULTACT_BITNUM);
     reg_bit_setters!(set_memfaultact, clear_memfaultact, Self::MEMFAULTACT_BITNUM);
+
+    pub(super) fn get_svcallpended(&self) -> bool {
+        self.0.get_bit(Self::SVCALLPENDED_BITNUM)
+    }
+
+    pub(super) fn get_svcallact(&self) -> bool {
+        self.0.get_bit(Self::SVCALLACT_BITNUM)
+    }
+
+    pub(super) fn get_pendsvact(&self) -> bool {
+        self.0.get_bit(Self::PENDSVACT_BITNUM)
+    }
+
+    pub(super) fn get_memfaultpended(&self) -> bool {
+        self.0.get_bit(Self::MEMFAULTPENDED_BITNUM)
+    }
+
+    pub(super) fn get_busfaultpended(&self) -> bool {
+        self.0.get_bit(Self::BUSFAULTPENDED_BITNUM)
+    }
+
+    pub(super) fn get_usgfaultpended(&self) -> bool {
+        self.0.get_bit(Self::USGFAULTPENDED_BITNUM)
+    }
+
+    pub(super) fn get_memfaultena(&self) -> bool {
+        self.0.get_bit(Self::MEMFAULTENA_BITNUM)
+    }
+
+    pub(super) fn get_busfaultena(&self) -> bool {
+        self.0.get_bit(Self::BUSFAULTENA_BITNUM)
+    }
+
+    pub(super) fn get_usgfaultena(&self) -> bool {
+        self.0.get_bit(Self::USGFAULTENA_BITNUM)
+    }
+
+    pub(super) fn get_memfaultact(&self) -> bool {
+        self.0.get_bit(Self::MEMFAULTACT_BITNUM)
+    }
+
+    pub(super) fn get_busfaultact(&self) -> bool {
+        self.0.get_bit(Self::BUSFAULTACT_BITNUM)
+    }
+
+    pub(super) fn get_usgfaultact(&self) -> bool {
+        self.0.get_bit(Self::USGFAULTACT_BITNUM)
+    }
 }
 
 // ----------------------------------------------------------------------------
@@ -1119,6 +1287,7 @@ impl SHCSR {
 /// * [ARM-ARM] B3.2.3 CPUID Base Register
 /// * [ARM-ARM] B4 The CPUID Scheme
 /// * [TI-TRM-I] 2.7.4.26, 2.7.4.42 - 2.7.4.54
+#[derive(Clone, Serialize, Deserialize)]
 struct IdRegisters;
 
 impl IdRegisters {
@@ -1209,9 +1378,12 @@ impl IdRegisters {
 /// Relevant documentation:
 /// * [ARM-ARM] B3.2.15 Configurable Fault Status Register
 /// * [TI-TRM-I] 2.7.4.36
-type CFSR = SeqFlopMemoryBankSimple<CFSRContent>;
+// Comb is required here because 2 changes can happen in the same cycle, e.g.:
+// - clearing some status bits by software,
+// - recording a new fault.
+type CFSR = CombFlopMemoryBankSimple<CFSRContent>;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub(super) struct CFSRContent(Word);
 
 word_conversions!(CFSRContent);
@@ -1255,11 +1427,36 @@ impl SCBRegister for CFSR {
 }
 
 impl CFSR {
-    // TODO: add helper accessors for other parts of the emulator to set the fault statuses.
-
+    /// [ARM-ARM] B3.2.15
+    const IACCVIOL_BITNUM: u32 = 0;
+    /// [ARM-ARM] B3.2.15
+    const DACCVIOL_BITNUM: u32 = 1;
+    /// [ARM-ARM] B3.2.15
+    const MUNSTKERR_BITNUM: u32 = 3;
+    /// [ARM-ARM] B3.2.15
+    const MSTKERR_BITNUM: u32 = 4;
     /// [ARM-ARM] B3.2.15
     const MMARVALID_BITNUM: u32 = 7;
+    /// [ARM-ARM] B3.2.15
+    const IBUSERR_BITNUM: u32 = 8;
+    /// [ARM-ARM] B3.2.15
+    const PRECISERR_BITNUM: u32 = 9;
+    /// [ARM-ARM] B3.2.15
+    const IMPRECISERR_BITNUM: u32 = 10;
+    /// [ARM-ARM] B3.2.15
+    const UNSTKERR_BITNUM: u32 = 11;
+    /// [ARM-ARM] B3.2.15
+    const STKERR_BITNUM: u32 = 12;
+    /// [ARM-ARM] B3.2.15
     const BFARVALID_BITNUM: u32 = 15;
+    /// [ARM-ARM] B3.2.15
+    const UNDEFINSTR_BITNUM: u32 = 16;
+    /// [ARM-ARM] B3.2.15
+    const INVSTATE_BITNUM: u32 = 17;
+    /// [ARM-ARM] B3.2.15
+    const INVPC_BITNUM: u32 = 18;
+    /// [ARM-ARM] B3.2.15
+    const DIVBYZERO_BITNUM: u32 = 25;
 
     fn get_mmarvalid(&self) -> bool {
         self.0.get_bit(Self::MMARVALID_BITNUM)
@@ -1268,6 +1465,20 @@ impl CFSR {
     fn get_bfarvalid(&self) -> bool {
         self.0.get_bit(Self::BFARVALID_BITNUM)
     }
+
+    // Note: `reg_bit_setters!` cannot be used, since writes are write-one-to-clear.
+    fn set_fault_status(&mut self, bitnum: u32) {
+        let next = self.next_builder();
+        next.0 = next.0.with_bit_set(bitnum, true);
+    }
+
+    fn set_mmarvalid(&mut self) {
+        self.set_fault_status(Self::MMARVALID_BITNUM);
+    }
+
+    fn set_bfarvalid(&mut self) {
+        self.set_fault_status(Self::BFARVALID_BITNUM);
+    }
 }
 
 // ----------------------------------------------------------------------------
@@ -1277,9 +1488,10 @@ impl CFSR {
 /// Relevant documentation:
 /// * [ARM-ARM] B3.2.16 Hard Fault Status Register
 /// * [TI-TRM-I] 2.7.4.37
-type HFSR = SeqFlopMemoryBankSimple<HFSRContent>;
+// Comb is required here for the same reason as in the case of CFSR.
+type HFSR = CombFlopMemoryBankSimple<HFSRContent>;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub(super) struct HFSRContent(Word);
 
 word_conversions!(HFSRContent);
@@ -1313,7 +1525,22 @@ impl SCBRegister for HFSR {
 }
 
 impl HFSR {
-    // TODO: add helper accessors for other parts of the emulator to set the fault statuses.
+    /// [ARM-ARM] B3.2.16
+    const FORCED_BITNUM: u32 = 30;
+    /// [ARM-ARM] B3.2.16
+    const DEBUGEVT_BITNUM: u32 = 31;
+
+    // Note: `reg_bit_setters!` cannot be used, since writes are write-one-to-clear.
+    pub(super) fn set_forced(&mut self) {
+        let next = self.next_builder();
+        next.0 = next.0.with_bit_set(Self::FORCED_BITNUM, true);
+    }
+
+    pub(super) fn set_debugevt(&mut self) {
+        let next = self.next_builder();
+        next.0 = next.0.with_bit_set(Self::DEBUGEVT_BITNUM, true);
+    }
+    // TODO: add a helper accessor for VECTTBL, once bus errors on vector table reads are handled.
 }
 
 // ----------------------------------------------------------------------------
@@ -1325,7 +1552,7 @@ impl HFSR {
 /// * [TI-TRM-I] 2.7.4.38
 type DFSR = SeqFlopMemoryBankSimple<DFSRContent>;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub(super) struct DFSRContent(Word);
 
 word_conversions!(DFSRContent);
@@ -1373,12 +1600,11 @@ impl DFSR {
 /// * [TI-TRM-I] 2.7.4.39
 type MMFAR = SeqFlopMemoryBankSimple<MMFARContent>;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub(super) struct MMFARContent(Word);
 
 word_conversions!(MMFARContent);
 
-// TODO: implement the logic controlled by this register
 /// [ARM-TRM-G] Table 8-27 Memory Manage Fault Address Register bit assignments
 impl SCBRegister for MMFAR {
     const NAME: &'static str = "MMFAR";
@@ -1414,12 +1640,11 @@ impl SCBRegister for MMFAR {
 /// * [TI-TRM-I] 2.7.4.40
 type BFAR = SeqFlopMemoryBankSimple<BFARContent>;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub(super) struct BFARContent(Word);
 
 word_conversions!(BFARContent);
 
-// TODO: implement the logic controlled by this register
 /// [ARM-TRM-G] Table 8-28 Bus Fault Address Register bit assignments
 impl SCBRegister for BFAR {
     const NAME: &'static str = "BFAR";
@@ -1446,6 +1671,20 @@ impl SCBRegister for BFAR {
     }
 }
 
+impl MMFAR {
+    /// Should be paired with setting MMFSR.MMARVALID, see [`CFSR::set_mmarvalid()`].
+    fn set_address(&mut self, address: Address) {
+        self.set_next(MMFARContent(address.into()));
+    }
+}
+
+impl BFAR {
+    /// Should be paired with setting BFSR.BFARVALID, see [`CFSR::set_bfarvalid()`].
+    fn set_address(&mut self, address: Address) {
+        self.set_next(BFARContent(address.into()));
+    }
+}
+
 // ----------------------------------------------------------------------------
 // [ARM-ARM] B3.2.19 Auxiliary Fault Status Register, AFSR
 // ----------------------------------------------------------------------------
@@ -1455,7 +1694,7 @@ impl SCBRegister for BFAR {
 /// * [TI-TRM-I] 2.7.4.41
 type AFSR = SeqFlopMemoryBankSimple<AFSRContent>;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub(super) struct AFSRContent(Word);
 
 word_conversions!(AFSRContent);
@@ -1497,7 +1736,7 @@ impl SCBRegister for AFSR {
 /// * [TI-TRM-I] 2.7.4.55
 type CPACR = SeqFlopMemoryBankSimple<CPACRContent>;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub(super) struct CPACRContent(Word);
 
 word_conversions!(CPACRContent);
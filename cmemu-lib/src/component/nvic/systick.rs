@@ -5,6 +5,7 @@ use crate::engine::{
     SeqFlopMemoryBank, Subcomponent, TickComponent, TickComponentExtra,
 };
 use log::warn;
+use serde::{Deserialize, Serialize};
 
 /// [ARM-ARM] Table B3-7 `SysTick` register summary.
 /// `SysTick` Control and Status Register.
@@ -20,14 +21,14 @@ const SYST_CVR_ADDR: Address = Address::from_const(0xE000_E018);
 const SYST_CALIB_ADDR: Address = Address::from_const(0xE000_E01C);
 
 /// Action that [`SysTick`] should execute while ticking is enabled.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 enum EnabledTickAction {
     Tick,
     ReloadCurrentCounterValue,
 }
 
 /// [ARM-ARM] B3.3 The system timer, `SysTick`.
-#[derive(Subcomponent, TickComponent, DisableableComponent)]
+#[derive(Clone, Subcomponent, TickComponent, DisableableComponent, Serialize, Deserialize)]
 pub(super) struct SysTick<SC>
 where
     SC: Subcomponent<Component = NVICComponent, Member = Self>,
@@ -233,7 +234,7 @@ where
 }
 
 /// [ARM-ARM] B3.3.3 `SysTick` Control and Status Register, `SYST_CSR`.
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub(crate) struct SysTickControlAndStatusRegister(Word);
 
 impl SysTickControlAndStatusRegister {
@@ -282,7 +283,7 @@ impl SysTickControlAndStatusRegister {
 }
 
 /// [ARM-ARM] B3.3.4 `SysTick` Reload Value Register, `SYST_RVR`.
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub(crate) struct SysTickReloadValueRegister(Word);
 
 impl SysTickReloadValueRegister {
@@ -314,7 +315,7 @@ impl SysTickReloadValueRegister {
 }
 
 /// [ARM-ARM] B3.3.5 `SysTick` Current Value Register, `SYST_CVR`.
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub(crate) struct SysTickCurrentValueRegister(Word);
 
 impl SysTickCurrentValueRegister {
@@ -365,7 +366,7 @@ impl SysTickCurrentValueRegister {
 /// [ARM-ARM] B3.3.6 `SysTick` Calibration value Register, `SYST_CALIB`.
 /// [TI-TRM] Table 2-102 - all fields are read only. It means that any write
 /// has no effect, that's why `write` method is missing for this register.
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub(crate) struct SysTickCalibrationValueRegister(Word);
 
 impl SysTickCalibrationValueRegister {
@@ -19,6 +19,7 @@ use crate::engine::{
     Context, DisableableComponent, MainComponent, SkippableClockTreeNode, Subcomponent,
     TickComponent, TickComponentExtra,
 };
+use serde::{Deserialize, Serialize};
 
 use crate::build_data::{ClockTreeNodes, EnergyEntity, Oscillators};
 use crate::common::Word;
@@ -32,9 +33,15 @@ use num_enum::TryFromPrimitive;
 use std::ops::Range;
 
 #[derive(
-    MainComponent, SkippableClockTreeNode, TickComponent, TickComponentExtra, DisableableComponent,
+    Clone,
+    MainComponent,
+    SkippableClockTreeNode,
+    TickComponent,
+    TickComponentExtra,
+    DisableableComponent,
 )]
 #[skippable_if_disableable]
+#[derive(Serialize, Deserialize)]
 pub(crate) struct OSCComponent {
     #[subcomponent(pub(crate) DriverSC)]
     driver: BusDriver,
@@ -42,8 +49,10 @@ pub(crate) struct OSCComponent {
     clock_tree_proxy: ClockTreeProxy,
 
     #[flop]
+    #[serde(with = "crate::engine::serde_hw_register")]
     ctl0: SeqRegister<AUX_DDI0_OSC::CTL0::Register>,
     #[flop]
+    #[serde(with = "crate::engine::serde_hw_register")]
     ctl1: SeqRegister<AUX_DDI0_OSC::CTL1::Register>,
 
     waiting_for_clock_tree_state_response: bool, // TODO: write enum: RemoteState{Unknown,Awaited,Known(T)}
@@ -759,7 +768,7 @@ impl SimplerHandler for OSCComponent {
 mod clippy_scope {
     use super::*;
 
-    #[derive(PartialEq, Clone, Copy, Debug, TryFromPrimitive)]
+    #[derive(PartialEq, Clone, Copy, Debug, TryFromPrimitive, Serialize, Deserialize)]
     #[repr(u8)]
     pub(super) enum LfClkSource {
         DerivedRC48M = AUX_DDI0_OSC::CTL0::SCLK_LF_SRC_SEL::E::RCOSCHFDLF as u8,
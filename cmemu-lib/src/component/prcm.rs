@@ -17,9 +17,9 @@ use crate::common::{BitstringUtils, Word};
 #[proxy_use]
 use crate::engine::{
     Context, CpuMode, DisableableComponent, MainComponent, PowerMode, SeqFlopMemoryBank,
-    SkippableClockTreeNode, Subcomponent, TickComponent, TickComponentExtra,
+    SkippableClockTreeNode, Subcomponent, TickComponent, TickComponentExtra, WarmResetSource,
 };
-use crate::proxy::{ClockTreeProxy, PRCMProxy, WUCProxy};
+use crate::proxy::{ClockTreeProxy, GPRAMProxy, PRCMProxy, WUCProxy};
 use crate::utils::IfExpr;
 use cc2650_constants::PRCM::{self};
 use cmemu_common::{Address, HwRegister};
@@ -27,10 +27,11 @@ use cmemu_proc_macros::{component_impl, handler, proxy_use};
 use enum_map::{Enum, EnumMap, enum_map};
 use log::{debug, info, trace};
 use num_enum::IntoPrimitive;
+use serde::{Deserialize, Serialize};
 use std::ops::Range;
 
 #[allow(clippy::enum_variant_names)]
-#[derive(Clone, Copy, Debug, Enum)]
+#[derive(Clone, Copy, Debug, Enum, Serialize, Deserialize)]
 enum PerCPUModeGates {
     DmaGate,
     CryptoGate,
@@ -42,13 +43,14 @@ enum PerCPUModeGates {
     Gpt3Gate,
     I2cGate,
     UartGate,
-    SsiGate,
+    Ssi0Gate,
+    Ssi1Gate,
     I2sGate,
     // This is not a per-mode register, but has such behavior
     VimsGate,
 }
 
-#[derive(Eq, Hash, PartialEq, Clone, Copy, Debug, Enum)]
+#[derive(Eq, Hash, PartialEq, Clone, Copy, Debug, Enum, Serialize, Deserialize)]
 pub(crate) enum McuPowerDomains {
     PERIPH,
     SERIAL,
@@ -58,6 +60,9 @@ pub(crate) enum McuPowerDomains {
     RFCORE,
 }
 
+/// The bit of `RAMRETEN.VIMS` enabling the retention of the cache RAM, that is of GPRAM.
+const VIMS_CRAM: u8 = 0b10;
+
 impl TryFrom<ClockTreeNodes> for PerCPUModeGates {
     type Error = ();
     fn try_from(value: ClockTreeNodes) -> Result<Self, Self::Error> {
@@ -67,10 +72,13 @@ impl TryFrom<ClockTreeNodes> for PerCPUModeGates {
             ClockTreeNodes::CryptoGate => Ok(PerCPUModeGates::CryptoGate),
             ClockTreeNodes::GpioGate => Ok(PerCPUModeGates::GpioGate),
             ClockTreeNodes::UartGate => Ok(PerCPUModeGates::UartGate),
+            ClockTreeNodes::I2cGate => Ok(PerCPUModeGates::I2cGate),
             ClockTreeNodes::Gpt0Gate => Ok(PerCPUModeGates::Gpt0Gate),
             ClockTreeNodes::Gpt1Gate => Ok(PerCPUModeGates::Gpt1Gate),
             ClockTreeNodes::Gpt2Gate => Ok(PerCPUModeGates::Gpt2Gate),
             ClockTreeNodes::Gpt3Gate => Ok(PerCPUModeGates::Gpt3Gate),
+            ClockTreeNodes::Ssi0Gate => Ok(PerCPUModeGates::Ssi0Gate),
+            ClockTreeNodes::Ssi1Gate => Ok(PerCPUModeGates::Ssi1Gate),
             ClockTreeNodes::VimsGate => Ok(PerCPUModeGates::VimsGate),
             // TODO: handle other known
             _ => Err(()),
@@ -87,12 +95,15 @@ impl From<PerCPUModeGates> for ClockTreeNodes {
             PerCPUModeGates::CryptoGate => ClockTreeNodes::CryptoGate,
             PerCPUModeGates::GpioGate => ClockTreeNodes::GpioGate,
             PerCPUModeGates::UartGate => ClockTreeNodes::UartGate,
+            PerCPUModeGates::I2cGate => ClockTreeNodes::I2cGate,
             PerCPUModeGates::Gpt0Gate => ClockTreeNodes::Gpt0Gate,
             PerCPUModeGates::Gpt1Gate => ClockTreeNodes::Gpt1Gate,
             PerCPUModeGates::Gpt2Gate => ClockTreeNodes::Gpt2Gate,
             PerCPUModeGates::Gpt3Gate => ClockTreeNodes::Gpt3Gate,
+            PerCPUModeGates::Ssi0Gate => ClockTreeNodes::Ssi0Gate,
+            PerCPUModeGates::Ssi1Gate => ClockTreeNodes::Ssi1Gate,
             PerCPUModeGates::VimsGate => ClockTreeNodes::VimsGate,
-            _ => todo!("implement all gates {value:?}"),
+            PerCPUModeGates::I2sGate => todo!("implement all gates {value:?}"),
         }
     }
 }
@@ -126,7 +137,7 @@ impl TryFrom<ClockTreeNodes> for McuPowerDomains {
 }
 
 /// "Wanted" state as written to PDCTL registers
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub(crate) struct PowerDomainCtl {
     periph: bool,
     serial: bool,
@@ -158,7 +169,7 @@ impl PowerDomainCtl {
 }
 
 // consider merging NeedLoad with ConfiguredState by removing extra typing from the latter
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub(crate) struct NeedLoad {
     run: EnumMap<PerCPUModeGates, bool>,
     sleep: EnumMap<PerCPUModeGates, bool>,
@@ -166,7 +177,7 @@ pub(crate) struct NeedLoad {
     global: ClkloadctlBufferGlobal,
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub(crate) struct ClkloadctlBufferGlobal {
     rfc_gate: bool,
 }
@@ -205,7 +216,11 @@ impl NeedLoad {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[allow(
+    clippy::unsafe_derive_deserialize,
+    reason = "the only `unsafe` is in reading the `confeature` statics"
+)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub(crate) struct ConfiguredState {
     run: EnumMap<PerCPUModeGates, PowerMode>,
     sleep: EnumMap<PerCPUModeGates, PowerMode>,
@@ -213,7 +228,7 @@ pub(crate) struct ConfiguredState {
     global: WantedGateStatesGlobal,
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub(crate) struct WantedGateStatesGlobal {
     pub(crate) rfc_gate: PowerMode,
 }
@@ -257,7 +272,7 @@ impl ConfiguredState {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 enum PowerSequence {
     CpuActive,
     WaitForClocks {
@@ -270,7 +285,19 @@ enum PowerSequence {
     Off,
 }
 
-#[derive(MainComponent, TickComponent, TickComponentExtra, DisableableComponent)]
+#[allow(
+    clippy::unsafe_derive_deserialize,
+    reason = "the only `unsafe` is in reading the `confeature` statics"
+)]
+#[derive(
+    Clone,
+    MainComponent,
+    TickComponent,
+    TickComponentExtra,
+    DisableableComponent,
+    Serialize,
+    Deserialize,
+)]
 pub(crate) struct PRCMComponent {
     #[subcomponent(DriverSC)]
     driver: BusDriver,
@@ -290,16 +317,20 @@ pub(crate) struct PRCMComponent {
     last_cpu_mode: CpuMode,
 
     #[flop]
+    #[serde(with = "crate::engine::serde_hw_register_bank")]
     warmreset: SeqFlopMemoryBank<PRCM::WARMRESET::Register, PRCM::WARMRESET::Register>,
 
     // TODO: use it somewhere (maybe to know if we should replace components before turning them on again?)
     #[flop]
+    #[serde(with = "crate::engine::serde_hw_register_bank")]
     ramreten: SeqFlopMemoryBank<PRCM::RAMRETEN::Register, PRCM::RAMRETEN::Register>,
 
     #[flop]
+    #[serde(with = "crate::engine::serde_hw_register_bank")]
     vdctl: SeqFlopMemoryBank<PRCM::VDCTL::Register, PRCM::VDCTL::Register>,
 
     #[flop]
+    #[serde(with = "crate::engine::serde_hw_register_bank")]
     rfcmodesel: SeqFlopMemoryBank<PRCM::RFCMODESEL::Register, PRCM::RFCMODESEL::Register>,
 }
 type BusDriver = SimpleSynchronousSlaveInterface<DriverSC, PRCMComponent>;
@@ -351,6 +382,9 @@ impl PRCMComponent {
             PRCM::GPIOCLKGR::ADDR => (CpuMode::Run, PerCPUModeGates::GpioGate),
             PRCM::GPIOCLKGS::ADDR => (CpuMode::Sleep, PerCPUModeGates::GpioGate),
             PRCM::GPIOCLKGDS::ADDR => (CpuMode::DeepSleep, PerCPUModeGates::GpioGate),
+            PRCM::I2CCLKGR::ADDR => (CpuMode::Run, PerCPUModeGates::I2cGate),
+            PRCM::I2CCLKGS::ADDR => (CpuMode::Sleep, PerCPUModeGates::I2cGate),
+            PRCM::I2CCLKGDS::ADDR => (CpuMode::DeepSleep, PerCPUModeGates::I2cGate),
             _ => return None,
         })
     }
@@ -364,7 +398,7 @@ impl PRCMComponent {
         );
         let data_as_word = data.zero_extend_into_word();
         let bit_0 = data_as_word.get_bit(0);
-        // TODO: handle INFRCLKDIV, VIMSCLKG, I2CCLKG, SSICLKG, I2SCLKG, CPUCLKDIV, *DIV, SWRESET, RFCBITS, and few other
+        // TODO: handle INFRCLKDIV, VIMSCLKG, I2SCLKG, CPUCLKDIV, *DIV, SWRESET, RFCBITS, and few other
         match addr {
             PRCM::WARMRESET::ADDR => {
                 let write = PRCM::WARMRESET::Register::from(data_as_word.uint());
@@ -421,7 +455,10 @@ impl PRCMComponent {
             | PRCM::UARTCLKGDS::ADDR
             | PRCM::GPIOCLKGR::ADDR
             | PRCM::GPIOCLKGS::ADDR
-            | PRCM::GPIOCLKGDS::ADDR => {
+            | PRCM::GPIOCLKGDS::ADDR
+            | PRCM::I2CCLKGR::ADDR
+            | PRCM::I2CCLKGS::ADDR
+            | PRCM::I2CCLKGDS::ADDR => {
                 let (run_mode, gate) = Self::addr_to_mode_and_gate(addr).unwrap();
                 let map = self.configured_state.map_for_mode_mut(run_mode);
                 map[gate] = bit_0.ife(PowerMode::Active, PowerMode::ClockGated);
@@ -452,6 +489,24 @@ impl PRCMComponent {
                         .ife(PowerMode::Active, PowerMode::ClockGated);
                 }
             }
+            PRCM::SSICLKGR::ADDR | PRCM::SSICLKGS::ADDR | PRCM::SSICLKGDS::ADDR => {
+                let run_mode = match addr {
+                    PRCM::SSICLKGR::ADDR => CpuMode::Run,
+                    PRCM::SSICLKGS::ADDR => CpuMode::Sleep,
+                    PRCM::SSICLKGDS::ADDR => CpuMode::DeepSleep,
+                    _ => unreachable!(),
+                };
+                let per_bit_gate = [PerCPUModeGates::Ssi0Gate, PerCPUModeGates::Ssi1Gate];
+                let load = self.need_load.map_for_mode_mut(run_mode);
+                let map = self.configured_state.map_for_mode_mut(run_mode);
+                #[allow(clippy::cast_possible_truncation, reason = "false positive")]
+                for (i, gate) in per_bit_gate.into_iter().enumerate() {
+                    load[gate] = true;
+                    map[gate] = data_as_word
+                        .get_bit(i as u32)
+                        .ife(PowerMode::Active, PowerMode::ClockGated);
+                }
+            }
             PRCM::SECDMACLKGR::ADDR | PRCM::SECDMACLKGS::ADDR | PRCM::SECDMACLKGDS::ADDR => {
                 let run_mode = match addr {
                     PRCM::SECDMACLKGR::ADDR => CpuMode::Run,
@@ -524,7 +579,7 @@ impl PRCMComponent {
                     .mutate_next(write, |data, write| data.mutate(u32::from(write)));
             }
             PRCM::RAMRETEN::ADDR => {
-                // TODO: RAMRETEN logic
+                // Not implemented: the RAM of the RF core (`RAMRETEN.RFC`).
                 let write = PRCM::RAMRETEN::Register::from(data_as_word.uint());
                 self.ramreten
                     .mutate_next(write, |data, write| data.mutate(u32::from(write)));
@@ -551,13 +606,14 @@ impl PRCMComponent {
         );
         let data = match addr {
             PRCM::WARMRESET::ADDR => {
-                let mut new_value = *self.warmreset;
-                new_value
-                    .mut_bitfields()
-                    .with_WDT_STAT(0)
-                    .with_LOCKUP_STAT(0);
+                // The status bits are cleared on read.
+                let value = *self.warmreset;
+                let mut new_value = value;
+                let bitfields = new_value.mut_bitfields();
+                bitfields.set_WDT_STAT(0);
+                bitfields.set_LOCKUP_STAT(0);
                 self.warmreset.set_next(new_value);
-                Some(new_value.read())
+                Some(value.read())
             }
             PRCM::CLKLOADCTL::ADDR => {
                 let mut reg = PRCM::CLKLOADCTL::Register::new();
@@ -589,7 +645,10 @@ impl PRCMComponent {
             | PRCM::UARTCLKGDS::ADDR
             | PRCM::GPIOCLKGR::ADDR
             | PRCM::GPIOCLKGS::ADDR
-            | PRCM::GPIOCLKGDS::ADDR => {
+            | PRCM::GPIOCLKGDS::ADDR
+            | PRCM::I2CCLKGR::ADDR
+            | PRCM::I2CCLKGS::ADDR
+            | PRCM::I2CCLKGDS::ADDR => {
                 let (run_mode, gate) = Self::addr_to_mode_and_gate(addr).unwrap();
                 let map = self.configured_state.map_for_mode(run_mode);
                 Some(map[gate].is_active().into())
@@ -616,6 +675,22 @@ impl PRCMComponent {
                 }
                 Some(ret.into())
             }
+            PRCM::SSICLKGR::ADDR | PRCM::SSICLKGS::ADDR | PRCM::SSICLKGDS::ADDR => {
+                let run_mode = match addr {
+                    PRCM::SSICLKGR::ADDR => CpuMode::Run,
+                    PRCM::SSICLKGS::ADDR => CpuMode::Sleep,
+                    PRCM::SSICLKGDS::ADDR => CpuMode::DeepSleep,
+                    _ => unreachable!(),
+                };
+                let per_bit_gate = [PerCPUModeGates::Ssi0Gate, PerCPUModeGates::Ssi1Gate];
+                let mut ret = Word::from_const(0);
+                let map = self.configured_state.map_for_mode(run_mode);
+                #[allow(clippy::cast_possible_truncation, reason = "false positive")]
+                for (i, gate) in per_bit_gate.into_iter().enumerate() {
+                    ret = ret.with_bit_set(i as u32, map[gate].is_active());
+                }
+                Some(ret.into())
+            }
             PRCM::SECDMACLKGR::ADDR | PRCM::SECDMACLKGS::ADDR | PRCM::SECDMACLKGDS::ADDR => {
                 let run_mode = match addr {
                     PRCM::SECDMACLKGR::ADDR => CpuMode::Run,
@@ -741,7 +816,26 @@ impl PRCMComponent {
             ClockTreeProxy.want_node_state(ctx, clock_node, PowerMode::Active);
         } else {
             debug!("PRCM wants {pd:?} to be disabled");
-            ClockTreeProxy.want_node_state(ctx, clock_node, PowerMode::Retention);
+            ClockTreeProxy.want_node_state(ctx, clock_node, Self::pd_power_down_mode(pd));
+            if pd == McuPowerDomains::VIMS && self.ramreten.bitfields().VIMS() & VIMS_CRAM == 0 {
+                // Not implemented: losing the contents of the cache, as the legal modes
+                // of `VIMS:CTL.MODE` require it to be turned off anyway.
+                GPRAMProxy.lose_contents(ctx);
+            }
+        }
+    }
+
+    /// The power mode of a disabled power domain: the logic of CPU, BUS and VIMS power domains
+    /// is retained, so the system may resume after the standby.
+    /// The others are powered off, thus their components lose the state.
+    fn pd_power_down_mode(pd: McuPowerDomains) -> PowerMode {
+        match pd {
+            McuPowerDomains::CPU | McuPowerDomains::BUS | McuPowerDomains::VIMS => {
+                PowerMode::Retention
+            }
+            McuPowerDomains::PERIPH | McuPowerDomains::SERIAL | McuPowerDomains::RFCORE => {
+                PowerMode::Off
+            }
         }
     }
 
@@ -873,6 +967,21 @@ impl PRCMComponent {
         // TODO: WIC sets up PRCM::cpu_on?
     }
 
+    /// Whether a warm reset is converted to an emulated pin reset (`WARMRESET.WR_TO_PINRESET`).
+    pub(crate) fn is_warm_reset_to_pin_reset(&self) -> bool {
+        self.warmreset.bitfields().WR_TO_PINRESET() != 0
+    }
+
+    /// Records the source of the warm reset in `WARMRESET` of a freshly reset PRCM.
+    pub(crate) fn record_warm_reset(&mut self, source: WarmResetSource) {
+        let warmreset = self.warmreset.unsafe_as_mut().mut_bitfields();
+        match source {
+            WarmResetSource::Watchdog => warmreset.set_WDT_STAT(1),
+            WarmResetSource::Lockup => warmreset.set_LOCKUP_STAT(1),
+            WarmResetSource::SystemResetRequest => {}
+        }
+    }
+
     fn request_mode_based_nodes(
         &mut self,
         ctx: &mut Context,
@@ -1112,7 +1221,7 @@ impl SimplerHandler for PRCMComponent {
     }
 }
 
-#[derive(Debug, Eq, PartialEq, Clone, Copy, IntoPrimitive)]
+#[derive(Debug, Eq, PartialEq, Clone, Copy, IntoPrimitive, Serialize, Deserialize)]
 #[repr(u8)]
 enum VimsMode {
     /// Vims is powered whenever only when CPU is powered
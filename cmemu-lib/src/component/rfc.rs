@@ -4,13 +4,19 @@
 )]
 // NOTE: This is a highly experimental module in a snapshot state!
 
+mod ble;
+mod data_queue;
+mod rat;
 mod register_bank;
 mod timing;
 
 use crate::component::rfc::command::{
-    Command, CommandData, CommandId, CommandStatus, ImmediateCommandParameters,
-    RadioOperationCommandParameters,
+    Command, CommandData, CommandId, CommandResult, CommandStatus, ConditionRule,
+    FrameFilteringConfiguration, IeeeRxConfig, ImmediateCommandParameters, PatternOptions,
+    RadioOperationCommandParameters, RadioOperationCommandPreamble, RatCaptureConfig,
+    RatOutputConfig, Trigger, TriggerType,
 };
+use serde::{Deserialize, Serialize};
 pub mod command;
 use crate::bridge_ports;
 #[proxy_use]
@@ -24,25 +30,38 @@ use crate::common::new_ahb::slave_driver::faking_slave_driver::{FakingHandler, W
 use crate::common::new_ahb::{
     AHBPortConfig, DataBus, MasterToSlaveWires, slave_driver::faking_slave_driver::FakingIface,
 };
+use crate::common::utils::HostHandle;
+use crate::component::event_fabric::EventFabricEvent;
 #[proxy_use]
 use crate::component::memory_bypass::MemoryBypassReceiver;
 #[proxy_use(proxy_only)]
 use crate::component::rfc::RfcMemoryBypassReceiver;
+use crate::component::rfc::ble::{
+    BLE_CHANNEL_KEEP, BLE_CHANNELS, BLE_CUSTOM_CHANNELS, BLE_MAX_READ_SIZE, BleAction, BleKind,
+    BleOperation, RxReport, air_time_ticks,
+};
+use crate::component::rfc::data_queue::{
+    DataEntryHeader, DataEntryStatus, DataEntryType, DataQueue, ENTRY_HEADER_SIZE,
+    GENERAL_ENTRY_DATA_OFFSET, MULTI_ELEMENT_DATA_OFFSET, QUEUE_SIZE, QueueCommand, QueueOperation,
+    QueueStep,
+};
+use crate::component::rfc::rat::{AVAILABLE_CHANNELS, CYCLES_PER_TICK, RadioTimer, TICK};
 use crate::component::rfc::timing::{cycles_to_ack, cycles_to_done};
 #[proxy_use]
 use crate::engine::Context;
 use crate::engine::{
-    DisableableComponent, MainComponent, SeqFlopMemoryBankSimple, SkippableClockTreeNode,
-    TickComponent, TickComponentExtra,
+    DisableableComponent, MainComponent, RadioActivity, SeqFlopMemoryBankSimple,
+    SkippableClockTreeNode, TickComponent, TickComponentExtra, Timepoint,
 };
 use crate::proxy::{EventFabricProxy, FlashProxy, GPRAMProxy, ROMProxy, RfcProxy, SRAMProxy};
-use cc2650_constants::{self as soc};
+use cc2650_constants::{self as soc, RFC};
 use cmemu_common::address_match_range;
 use cmemu_proc_macros::{component_impl, handler, proxy_use};
+use enum_map::{Enum, EnumMap};
 use log::{debug, info, trace, warn};
-use modular_bitfield::specifiers::B4;
-use modular_bitfield::{BitfieldSpecifier, bitfield};
 use num_enum::{IntoPrimitive, TryFromPrimitive};
+use std::collections::VecDeque;
+use std::ops::RangeInclusive;
 use std::panic::UnwindSafe;
 
 type RfcRegisterBank = register_bank::RfcRegisterBank<RfcRegisterBankSubcomponent>;
@@ -65,6 +84,63 @@ pub trait ModemInterface {
     fn cca_prereq(&self) {
         self.send_op(ModemOp::RequestCca)
     }
+    /// The BLE extension of the modem, if it supports BLE.
+    fn as_ble(&self) -> Option<&dyn BleModemInterface> {
+        None
+    }
+}
+
+/// A modem of the BLE operations, which sends and receives whole packets.
+///
+/// The host acts as the peer devices: it receives the packets sent by the operations
+/// and sends them the packets of the advertisers, the scanners, or the other end of a connection.
+pub trait BleModemInterface {
+    fn send_ble_op(&self, op: BleModemOp);
+    /// A packet received since the last `Listen`.
+    fn take_ble_rx(&self) -> Option<BlePacket>;
+    /// The packet of the last `Transmit` was sent.
+    fn take_ble_tx_finished(&self) -> Option<()>;
+}
+
+/// The `channel` of the operations: 0-39 are the BLE channels, 60-207 are the frequencies
+/// of `2300 + channel` MHz, and 255 keeps the frequency programmed by `CMD_FS`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BleModemOp {
+    Listen {
+        channel: u8,
+        access_address: u32,
+        crc_init: u32,
+    },
+    /// Sends the PDU (the header and the payload), the modem appends the CRC.
+    Transmit {
+        channel: u8,
+        access_address: u32,
+        crc_init: u32,
+        pdu: Vec<u8>,
+    },
+    /// The operation ended, so nothing is received anymore.
+    Idle,
+}
+
+/// A BLE packet: the PDU without the preamble and the CRC, which the modem checks.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlePacket {
+    pub access_address: u32,
+    pub pdu: Vec<u8>,
+    pub crc_ok: bool,
+    pub rssi: i8,
+}
+
+impl BlePacket {
+    /// A correct packet.
+    pub fn new(access_address: u32, pdu: Vec<u8>) -> Self {
+        Self {
+            access_address,
+            pdu,
+            crc_ok: true,
+            rssi: RX_FRAME_RSSI,
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -84,8 +160,51 @@ const CC26XX_DEFAULT_CHANNEL: u8 = 11;
 const MAXIMUM_IEEE_TX_PAYLOAD_SIZE: usize = 256;
 /// The biggest radio command is IEEE_RX
 const BIGGEST_RADIO_COMMAND_SIZE: usize = 60;
-
-#[derive(Clone, Copy, Debug)]
+const NULL: Address = Address::from_const(0);
+
+/// `mode` of `CMD_RADIO_SETUP` for BLE.
+const RADIO_MODE_BLE: u8 = 0;
+/// `mode` of `CMD_RADIO_SETUP` for IEEE 802.15.4.
+const RADIO_MODE_IEEE: u8 = 1;
+/// `mode` of `CMD_RADIO_SETUP`, which keeps the current mode.
+const RADIO_MODE_KEEP: u8 = 0xFF;
+/// The IEEE 802.15.4 channels in the 2.4 GHz band.
+const IEEE_CHANNELS: RangeInclusive<u8> = 11..=26;
+/// The preamble, the SFD and the PHY header, which are sent before the PSDU.
+const IEEE_SYNC_HEADER_SIZE: u32 = 6;
+/// RAT ticks of sending a byte at 250 kbps.
+const IEEE_TICKS_PER_BYTE: u32 = 128;
+/// RAT ticks of the unit backoff period of CSMA-CA (20 symbols).
+const IEEE_BACKOFF_PERIOD_TICKS: u32 = 1280;
+/// The CRC appended to the frames.
+const IEEE_CRC_SIZE: usize = 2;
+/// Type of the frame in the first byte of the frame control field.
+const IEEE_FRAME_TYPE_MASK: u8 = 0b111;
+const IEEE_FRAME_TYPE_BEACON: u8 = 0;
+const IEEE_FRAME_TYPE_DATA: u8 = 1;
+const IEEE_FRAME_TYPE_ACK: u8 = 2;
+const IEEE_FRAME_TYPE_MAC_CMD: u8 = 3;
+/// The frame pending bit in the first byte of the frame control field.
+const IEEE_FCF_FRAME_PENDING: u8 = 1 << 4;
+/// Seed of the backoff generator of CSMA-CA, used when `randomState` is 0.
+const CSMA_RANDOM_SEED: u16 = 0xACE1;
+/// Taps of the 16-bit Galois LFSR of CSMA-CA.
+const CSMA_RANDOM_TAPS: u16 = 0xB400;
+/// `ccaState` of the CCA reading.
+const CCA_STATE_IDLE: u8 = 0b00;
+const CCA_STATE_BUSY: u8 = 0b01;
+/// The RSSI reported when there is no valid measurement.
+const RSSI_UNKNOWN: i8 = -128;
+/// The modem doesn't report the RSSI of the received frames.
+const RX_FRAME_RSSI: i8 = 0;
+/// Source matching is not modelled, so no entry matches the received frames.
+const NO_SOURCE_MATCH: u8 = 0xFF;
+/// Size of the `output` structure of `CMD_IEEE_RX`.
+const IEEE_RX_OUTPUT_SIZE: usize = 16;
+/// Size of the value compared by `CMD_PATTERN_CHECK`.
+const PATTERN_VALUE_SIZE: usize = 4;
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 enum TaskHandle {
     /// CMDR is zero
     Nothing,
@@ -97,17 +216,263 @@ enum TaskHandle {
         param: u8,
         extension: u8,
     },
+    /// CMDR was written with an invalid value, which is rejected with the result.
+    Invalid(ResultByte),
 }
 
-#[derive(Clone, Copy, Debug)]
+/// The level of a radio operation.
+/// Some IEEE 802.15.4 operations run in the foreground, while a background operation receives.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Enum, Serialize, Deserialize)]
+enum Level {
+    Background,
+    Foreground,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 struct PendingCommand {
     command: Command,
     /// Address of the Radio or Immediate command. It's None for Direct commands.
     pointer: Option<Address>,
     received_at_cycle: usize,
+    /// RAT time of the submission, for the triggers relative to it.
+    submitted_at: u32,
+    /// Written to CMDR, thus acknowledged in CMDSTA, unlike the chained operations.
+    submitted: bool,
+    /// The level of a radio operation, known once it's acknowledged.
+    level: Option<Level>,
+}
+
+/// The next operation of a chain, until it's fetched.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+struct ChainLink {
+    pointer: Address,
+    /// The operation before, which receives the errors of the link.
+    previous: Address,
+    /// Operations to skip, starting with this one.
+    skips: u8,
+}
+
+/// A radio operation waiting for its start trigger.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+struct ScheduledOperation {
+    id: CommandId,
+    preamble: RadioOperationCommandPreamble,
+    parameters: RadioOperationCommandParameters,
+    pointer: Address,
+    submitted_at: u32,
+    /// The trigger time was checked for being in the past.
+    checked: bool,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+struct RunningOperation {
+    id: CommandId,
+    preamble: RadioOperationCommandPreamble,
+    /// The parameters, which are updated by the operation and written back when it ends.
+    parameters: RadioOperationCommandParameters,
+    pointer: Address,
+    submitted_at: u32,
+    end_trigger: Option<Trigger>,
+    /// RAT time of the end trigger.
+    end_time: Option<u32>,
+    /// RAT time of the timeout trigger of the BLE operations, which have it.
+    timeout_time: Option<u32>,
+    state: OperationState,
+}
+
+impl RunningOperation {
+    fn new(op: ScheduledOperation) -> Self {
+        Self {
+            id: op.id,
+            preamble: op.preamble,
+            parameters: op.parameters,
+            pointer: op.pointer,
+            submitted_at: op.submitted_at,
+            end_trigger: None,
+            end_time: None,
+            timeout_time: None,
+            state: OperationState::Running,
+        }
+    }
+
+    /// The status of the operation ended by its end trigger.
+    fn end_status(&self) -> CommandStatus {
+        match self.id {
+            CommandId::IeeeRx | CommandId::IeeeEdScan => CommandStatus::IeeeDoneOk,
+            CommandId::IeeeCsma | CommandId::IeeeRxAck => CommandStatus::IeeeDoneTimeout,
+            _ if self.parameters.is_ble() => CommandStatus::BleDoneEnded,
+            _ => CommandStatus::DoneOk,
+        }
+    }
+
+    /// The status of the operation ended by `CMD_ABORT` or `CMD_STOP` and their variants.
+    fn stop_status(&self, abort: bool) -> CommandStatus {
+        if self.parameters.is_ble() {
+            return if abort {
+                CommandStatus::BleDoneAbort
+            } else {
+                CommandStatus::BleDoneStopped
+            };
+        }
+        match (self.parameters.is_ieee(), abort) {
+            (true, true) => CommandStatus::IeeeDoneAbort,
+            (true, false) => CommandStatus::IeeeDoneStopped,
+            (false, true) => CommandStatus::DoneAbort,
+            (false, false) => CommandStatus::DoneStopped,
+        }
+    }
+
+    /// The operation makes progress on its own or waits for the host, thus the cycles may be skipped.
+    fn allows_skipping(&self) -> bool {
+        matches!(
+            self.state,
+            OperationState::Running
+                | OperationState::Backoff { .. }
+                | OperationState::Transmitting { until: Some(_) }
+                | OperationState::Listening { .. }
+                | OperationState::Waiting { .. }
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+enum OperationState {
+    /// Runs until the end trigger, a command or an event of the modem.
+    Running,
+    /// `CMD_IEEE_TX` waits for its payload.
+    FetchingPayload,
+    /// `CMD_IEEE_TX` sends the frame, until the modem reports it's done or until the time passes.
+    Transmitting { until: Option<Timepoint> },
+    /// `CMD_IEEE_CSMA` waits for the end of the backoff.
+    Backoff { until: Timepoint },
+    /// `CMD_IEEE_CSMA` waits for the CCA reading.
+    Cca,
+    /// `CMD_PATTERN_CHECK` waits for the value.
+    FetchingValue,
+    /// A BLE operation waits for the memory it reads.
+    FetchingBle,
+    /// A BLE operation receives, until a packet or until the time passes.
+    Listening { until: Option<Timepoint> },
+    /// A BLE operation waits before sending, e.g. for the inter frame space.
+    Waiting { until: Timepoint },
+}
+
+/// What happens next, when a radio operation ends.
+#[derive(Clone, Copy, Debug)]
+enum Continuation {
+    /// The condition of the operation decides.
+    Condition,
+    /// Jump to the operation, like `CMD_COUNT_BRANCH` or `CMD_PATTERN_CHECK` do.
+    Branch(Address),
+    /// The chain ends.
+    Stop,
+}
+
+/// The time of a trigger.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TriggerTime {
+    Now,
+    /// Only `CMD_TRIGGER` may fire the trigger, if it's enabled.
+    Never,
+    At(u32),
+}
+
+/// Start times of the operations in a chain, for the triggers relative to them.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+struct ChainTimes {
+    first_start: Option<u32>,
+    prev_start: u32,
+    prev_end: u32,
+}
+
+/// Who waits for the CCA reading of the modem.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+enum CcaUser {
+    /// `CMD_IEEE_CCA_REQ`
+    Request(Address),
+    Operation(Level),
+}
+
+/// What an operation does after the CCA reading.
+#[derive(Clone, Copy, Debug)]
+enum CcaOutcome {
+    ReadAgain,
+    Backoff,
+    Finish(CommandStatus),
+}
+
+/// [TI-TRM-I] Table 23-73. Receive Statistics Structure
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+struct IeeeRxOutput {
+    n_tx_ack: u8,
+    n_rx_beacon: u8,
+    n_rx_data: u8,
+    n_rx_ack: u8,
+    n_rx_mac_cmd: u8,
+    n_rx_reserved: u8,
+    n_rx_nok: u8,
+    n_rx_ignored: u8,
+    n_rx_buf_full: u8,
+    last_rssi: i8,
+    max_rssi: i8,
+    beacon_time_stamp: u32,
+}
+
+impl IeeeRxOutput {
+    fn new() -> Self {
+        Self {
+            last_rssi: RSSI_UNKNOWN,
+            max_rssi: RSSI_UNKNOWN,
+            ..Self::default()
+        }
+    }
+
+    fn to_bytes(self) -> [u8; IEEE_RX_OUTPUT_SIZE] {
+        let mut bytes = [0u8; IEEE_RX_OUTPUT_SIZE];
+        bytes[..11].copy_from_slice(&[
+            self.n_tx_ack,
+            self.n_rx_beacon,
+            self.n_rx_data,
+            self.n_rx_ack,
+            self.n_rx_mac_cmd,
+            self.n_rx_reserved,
+            self.n_rx_nok,
+            self.n_rx_ignored,
+            self.n_rx_buf_full,
+            self.last_rssi.to_le_bytes()[0],
+            self.max_rssi.to_le_bytes()[0],
+        ]);
+        bytes[12..].copy_from_slice(&self.beacon_time_stamp.to_le_bytes());
+        bytes
+    }
+
+    fn count_frame(&mut self, frame_type: Option<u8>) {
+        let counter = match frame_type {
+            Some(IEEE_FRAME_TYPE_BEACON) => &mut self.n_rx_beacon,
+            Some(IEEE_FRAME_TYPE_DATA) => &mut self.n_rx_data,
+            Some(IEEE_FRAME_TYPE_ACK) => &mut self.n_rx_ack,
+            Some(IEEE_FRAME_TYPE_MAC_CMD) => &mut self.n_rx_mac_cmd,
+            _ => &mut self.n_rx_reserved,
+        };
+        *counter = counter.saturating_add(1);
+        self.last_rssi = RX_FRAME_RSSI;
+        self.max_rssi = self.max_rssi.max(RX_FRAME_RSSI);
+    }
+}
+
+/// A received frame, which waits to be stored in the RX queue.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+enum RxFrame {
+    /// An IEEE 802.15.4 frame with its PHY header.
+    Ieee(Vec<u8>),
+    /// A BLE packet, formatted by the operation, and how it's counted once stored.
+    Ble {
+        data: Vec<u8>,
+        report: Option<RxReport>,
+    },
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 struct MemoryBypassStorage {
     data: Vec<u8>,
     address: Address,
@@ -124,7 +489,7 @@ impl MemoryBypassStorage {
     }
 }
 
-#[derive(MainComponent, TickComponent, TickComponentExtra)]
+#[derive(Clone, MainComponent, TickComponent, TickComponentExtra, Serialize, Deserialize)]
 pub(crate) struct RFCComponent {
     #[subcomponent(SlaveDriverSubcomponent)]
     driver: BusDriver,
@@ -134,28 +499,52 @@ pub(crate) struct RFCComponent {
     #[flop]
     cycles_clock: SeqFlopMemoryBankSimple<usize>,
 
-    #[flop]
-    pending_command: SeqFlopMemoryBankSimple<Option<PendingCommand>>,
-    #[flop]
-    current_task: SeqFlopMemoryBankSimple<TaskHandle>,
-
     #[subcomponent(RfcRegisterBankSubcomponent)]
     register_bank: RfcRegisterBank,
 
-    #[flop]
-    address_for_pending_cca_req: SeqFlopMemoryBankSimple<Option<Address>>,
-
-    modem_impl: Option<ModemImpl>,
+    modem_impl: HostHandle<ModemImpl>,
+
+    /// The command written to CMDR, until it's fetched.
+    current_task: TaskHandle,
+    /// The fetched command, until it's run.
+    pending_command: Option<PendingCommand>,
+    chain_links: EnumMap<Level, Option<ChainLink>>,
+    scheduled: EnumMap<Level, Option<ScheduledOperation>>,
+    running: EnumMap<Level, Option<RunningOperation>>,
+    chain_times: EnumMap<Level, ChainTimes>,
+    queue_operation: Option<QueueOperation>,
+
+    rat: RadioTimer,
+    /// `mode` of the last `CMD_RADIO_SETUP`.
+    radio_mode: Option<u8>,
+    /// The frequency synthesizer is on.
+    fs_on: bool,
+    cca_users: Vec<CcaUser>,
+    /// RSSI of the last CCA reading.
+    last_rssi: i8,
+
+    /// Received frames, which wait to be stored in the RX queue.
+    rx_frames: VecDeque<RxFrame>,
+    /// The first of `rx_frames` is being stored.
+    rx_storing: bool,
+    /// The RX queue read for the frame being stored.
+    rx_queue: Option<DataQueue>,
+    rx_output: IeeeRxOutput,
+    /// The last stored frame without its PHY header, for `CMD_PATTERN_CHECK`.
+    last_rx_payload: Option<Vec<u8>>,
+    /// The running BLE operation, which always runs at the background level.
+    ble: Option<BleOperation>,
 
     command_storage: Option<MemoryBypassStorage>,
     payload_storage: Option<MemoryBypassStorage>,
     rx_queue_storage: Option<MemoryBypassStorage>,
     rx_entry_header_storage: Option<MemoryBypassStorage>,
-    rx_buffer_to_write: Option<Vec<u8>>,
+    queue_storage: Option<MemoryBypassStorage>,
+    value_storage: Option<MemoryBypassStorage>,
+    ble_storage: Option<MemoryBypassStorage>,
 
-    #[flop]
-    background_command: SeqFlopMemoryBankSimple<Option<(RadioOperationCommandParameters, Address)>>,
-    tx_awaiting_done: Option<Address>,
+    /// Interrupt events raised in this cycle.
+    pending_events: Vec<EventFabricEvent>,
 
     // Used externally to inform radio about events on the modem
     need_wakeup: bool,
@@ -168,18 +557,34 @@ impl RFCComponent {
             driver: BusDriver::new(),
             fake_clock: SeqFlopMemoryBankSimple::new(0usize),
             cycles_clock: SeqFlopMemoryBankSimple::new(0usize),
-            address_for_pending_cca_req: SeqFlopMemoryBankSimple::new(None),
-            pending_command: SeqFlopMemoryBankSimple::new(None),
-            current_task: SeqFlopMemoryBankSimple::new(TaskHandle::Nothing),
+            register_bank: RfcRegisterBank::new(),
+            modem_impl: HostHandle::new(None),
+            current_task: TaskHandle::Nothing,
+            pending_command: None,
+            chain_links: EnumMap::default(),
+            scheduled: EnumMap::default(),
+            running: EnumMap::default(),
+            chain_times: EnumMap::default(),
+            queue_operation: None,
+            rat: RadioTimer::new(),
+            radio_mode: None,
+            fs_on: false,
+            cca_users: Vec::new(),
+            last_rssi: RSSI_UNKNOWN,
+            rx_frames: VecDeque::new(),
+            rx_storing: false,
+            rx_queue: None,
+            rx_output: IeeeRxOutput::new(),
+            last_rx_payload: None,
+            ble: None,
             command_storage: Some(MemoryBypassStorage::new(BIGGEST_RADIO_COMMAND_SIZE)),
             payload_storage: Some(MemoryBypassStorage::new(MAXIMUM_IEEE_TX_PAYLOAD_SIZE)),
-            rx_queue_storage: Some(MemoryBypassStorage::new(8)),
-            rx_entry_header_storage: Some(MemoryBypassStorage::new(8)),
-            rx_buffer_to_write: None,
-            register_bank: RfcRegisterBank::new(),
-            background_command: SeqFlopMemoryBankSimple::new(None),
-            tx_awaiting_done: None,
-            modem_impl: None,
+            rx_queue_storage: Some(MemoryBypassStorage::new(QUEUE_SIZE)),
+            rx_entry_header_storage: Some(MemoryBypassStorage::new(ENTRY_HEADER_SIZE)),
+            queue_storage: Some(MemoryBypassStorage::new(ENTRY_HEADER_SIZE)),
+            value_storage: Some(MemoryBypassStorage::new(PATTERN_VALUE_SIZE)),
+            ble_storage: Some(MemoryBypassStorage::new(BLE_MAX_READ_SIZE)),
+            pending_events: Vec::new(),
             need_wakeup: false,
         }
     }
@@ -189,136 +594,128 @@ impl RFCComponent {
         BusDriver::run_driver(self, ctx);
         self.cycles_clock
             .set_next(self.cycles_clock.overflowing_add(1).0);
-        self.fake_clock_tick(ctx);
+        self.fire_rat_channels(ctx);
+        self.poll_modem(ctx);
+        self.fetch_task(ctx);
         self.try_acknowledge_task(ctx);
         self.try_run_task(ctx);
+        self.progress_operations(ctx);
+        self.register_bank.commit_interrupt_flags();
+        for event in self.pending_events.drain(..) {
+            EventFabricProxy.notify(ctx, event);
+        }
     }
 
-    fn fake_clock_tick(&mut self, ctx: &mut Context) {
-        if let Some(()) = self
-            .modem_impl
-            .as_ref()
-            .and_then(|modem| modem.take_tx_finished())
-        {
-            // TODO: czy moze się nie powieść?
-            let pointer = self.tx_awaiting_done.take();
-            self.write_back_status_field(ctx, pointer, CommandStatus::IeeeDoneOk);
-        }
+    pub(crate) fn tock(&mut self, ctx: &mut Context) {
+        BusDriver::tock(self, ctx);
+    }
 
-        if let Some(cmd_addr) = *self.address_for_pending_cca_req {
-            let maybe_cca_resp = if let Some(modem) = self.modem_impl.as_ref() {
-                modem.cca_read()
-            } else {
-                Some(CcaReq::new_clear())
-            };
-            if let Some(cca_resp) = maybe_cca_resp {
-                trace!("read {cca_resp:?} from modem, finishing the command");
-                self.ack_command_with_status(ctx, ResultByte::Done);
+    fn rat_time(&self, ctx: &Context) -> u32 {
+        self.rat.counter(ctx.event_queue().get_current_time())
+    }
 
-                self.write_back_status_field(ctx, Some(cmd_addr), CommandStatus::DoneOk);
-                let cca_resp_bytes = cca_resp.into_bytes();
-                self.request_memory_write(
-                    ctx,
-                    cmd_addr.offset(2),
-                    DataBus::from_slice(&cca_resp_bytes[0..2]),
-                );
-                self.request_memory_write(
-                    ctx,
-                    cmd_addr.offset(2 + 2),
-                    DataBus::from_slice(&cca_resp_bytes[2..3]),
-                );
+    fn rat_has_reached(&self, now: Timepoint, time: u32) -> bool {
+        self.rat.is_running() && self.rat.has_reached(now, time)
+    }
 
-                self.address_for_pending_cca_req.set_next(None);
-            }
+    fn fire_rat_channels(&mut self, ctx: &mut Context) {
+        let flags = self
+            .rat
+            .take_due_compare_flags(ctx.event_queue().get_current_time());
+        if flags != 0 && self.register_bank.set_hw_interrupts_pending(flags) {
+            self.notify_event(self.register_bank.get_event_for_hw_interrupt());
         }
+    }
 
-        if let (
-            Some((
-                RadioOperationCommandParameters::IeeeRx {
-                    p_output,
-                    rx_config,
-                    p_rx_q,
-                    ..
-                },
-                addr,
-            )),
-            Some(modem),
-        ) = (*self.background_command, self.modem_impl.as_ref())
-        {
-            if let Some(rx_buf) = modem.take_rx() {
-                assert_eq!(usize::from(rx_buf[0]) + 1, rx_buf.len());
-                assert!(
-                    self.rx_buffer_to_write.is_none(),
-                    "received another rx buffer before handling the previous one"
-                );
-                // dbg!(rx_config);
-                self.rx_buffer_to_write = Some(rx_buf);
-                let storage = self.rx_queue_storage.take();
-                self.request_memory_read(ctx, p_rx_q, storage, 8, RfcMemoryBypassReceiver::RxQueue);
-            }
+    fn poll_modem(&mut self, ctx: &mut Context) {
+        let receiving = self.is_receiving();
+        let reading_cca = !self.cca_users.is_empty();
+        let listening = matches!(
+            self.running[Level::Background],
+            Some(RunningOperation {
+                state: OperationState::Listening { .. },
+                ..
+            })
+        );
+        let (tx_finished, frame, cca) = match self.modem_impl.as_ref() {
+            Some(modem) => (
+                modem.take_tx_finished().is_some(),
+                receiving.then(|| modem.take_rx()).flatten(),
+                reading_cca.then(|| modem.cca_read()).flatten(),
+            ),
+            // Without a modem, the channel is always clear.
+            None => (false, None, reading_cca.then(CcaReq::new_clear)),
+        };
+        let (ble_tx_finished, packet) = match self.ble_modem() {
+            Some(modem) => (
+                modem.take_ble_tx_finished().is_some(),
+                listening.then(|| modem.take_ble_rx()).flatten(),
+            ),
+            None => (false, None),
+        };
+        if tx_finished || ble_tx_finished {
+            self.on_tx_finished(ctx);
+        }
+        if let Some(frame) = frame {
+            self.on_frame_received(ctx, frame);
+        }
+        if let Some(packet) = packet {
+            self.with_ble(ctx, |ble, now| ble.on_packet(&packet, now));
+        }
+        if let Some(cca) = cca {
+            self.on_cca_read(ctx, cca);
         }
+    }
+
+    fn is_receiving(&self) -> bool {
+        matches!(
+            self.running[Level::Background],
+            Some(RunningOperation {
+                id: CommandId::IeeeRx,
+                ..
+            })
+        )
+    }
+
+    /// A background operation, which the IEEE 802.15.4 foreground operations and commands need.
+    fn runs_background_rx(&self) -> bool {
+        matches!(
+            self.running[Level::Background],
+            Some(RunningOperation {
+                id: CommandId::IeeeRx | CommandId::IeeeEdScan,
+                ..
+            })
+        )
+    }
 
+    fn fetch_task(&mut self, ctx: &mut Context) {
         // TODO(pk): [TI-TRM 23.2.1] Implement behaviour described there.
         // For immediate commands, CMDSTA becomes non 0 only after command is processed
-        match *self.current_task {
+        if self.pending_command.is_some() {
+            return;
+        }
+        match self.current_task {
             TaskHandle::Pointer(ptr) => {
-                if self.pending_command.is_some() {
-                    self.ack_command_with_status(ctx, ResultByte::SchedulingError);
+                let Some(data) = self.take_fetched_command(ctx, ptr) else {
                     return;
-                }
-                match &mut self.command_storage {
-                    Some(MemoryBypassStorage {
-                        data,
-                        address,
-                        consumed,
-                    }) if *address == ptr && !*consumed => {
-                        *consumed = true;
-                        self.current_task.set_next(TaskHandle::Nothing);
-                        match Command::try_from(data.as_slice()) {
-                            Ok(command) => {
-                                trace!("parsed command as {:?}", command);
-                                if let Command {
-                                    id: CommandId::IeeeTx,
-                                    data:
-                                        CommandData::RadioOperation {
-                                            parameters:
-                                                RadioOperationCommandParameters::IeeeTx {
-                                                    payload_len,
-                                                    p_payload,
-                                                    ..
-                                                },
-                                            ..
-                                        },
-                                } = command
-                                {
-                                    self.request_radio_message_payload_read(
-                                        ctx,
-                                        Address::from(p_payload),
-                                        payload_len as usize,
-                                    );
-                                }
-
-                                // Command has to be persisted here, as we may need more that single cycle to process.
-                                // It is the case when handling IeeeTx command as we need to fetch additional data.
-                                self.pending_command.set_next(Some(PendingCommand {
-                                    command,
-                                    pointer: Some(ptr),
-                                    received_at_cycle: *self.cycles_clock,
-                                }));
-                            }
-                            Err(status @ (register_status, _memory_status)) => {
-                                trace!("command parsing resulted in {:?}", status);
-                                self.ack_command_with_status(ctx, register_status.result);
-                            }
-                        }
-                    }
-                    None => {
-                        // We are waiting for data to be ready.
+                };
+                self.current_task = TaskHandle::Nothing;
+                match Command::try_from(data.as_slice()) {
+                    Ok(command) => {
+                        trace!("parsed command as {:?}", command);
+                        // Command has to be persisted here, as we may need more that single cycle to process.
+                        self.pending_command = Some(PendingCommand {
+                            command,
+                            pointer: Some(ptr),
+                            received_at_cycle: *self.cycles_clock,
+                            submitted_at: self.rat_time(ctx),
+                            submitted: true,
+                            level: None,
+                        });
                     }
-                    _ => {
-                        // We have buffer in hand, and the data in the buffer is not for the address we are interested in,
-                        // or it has been already processed meaning - it may be older than the actual data under this address.
-                        self.request_radio_command_read(ctx, ptr);
+                    Err(status @ (register_status, _memory_status)) => {
+                        trace!("command parsing resulted in {:?}", status);
+                        self.ack_command_with_status(ctx, register_status);
                     }
                 }
             }
@@ -327,525 +724,1919 @@ impl RFCComponent {
                 param,
                 extension,
             } => {
-                debug_assert!(
-                    self.pending_command.is_none(),
-                    "overwriting pending {:?} with {:?}",
-                    *self.pending_command,
-                    *self.current_task
-                );
-                self.current_task.set_next(TaskHandle::Nothing);
-                let pending_command = PendingCommand {
+                self.current_task = TaskHandle::Nothing;
+                self.pending_command = Some(PendingCommand {
                     command: Command {
                         id,
                         data: CommandData::Direct { param, extension },
                     },
                     pointer: None,
                     received_at_cycle: *self.cycles_clock,
-                };
-                self.pending_command.set_next(Some(pending_command));
+                    submitted_at: self.rat_time(ctx),
+                    submitted: true,
+                    level: None,
+                });
+            }
+            TaskHandle::Invalid(result) => {
+                self.current_task = TaskHandle::Nothing;
+                self.ack_command_with_status(ctx, result);
             }
-            TaskHandle::Nothing => {}
+            TaskHandle::Nothing => self.fetch_chain_link(ctx),
         }
     }
 
-    fn write_back_status_field(
-        &self,
-        ctx: &mut Context,
-        command_pointer: Option<Address>,
-        status: CommandStatus,
-    ) {
-        if let Some(pointer) = command_pointer {
-            trace!(
-                "writing back status field (CMDSTA) with {:?} under {:?}",
-                status, pointer
-            );
-            let status_field = pointer.offset(2);
-            let status_value: u16 = status.into();
-            self.request_memory_write(ctx, status_field, status_value.into())
+    /// Returns the command under `pointer`, once it's read.
+    fn take_fetched_command(&mut self, ctx: &mut Context, pointer: Address) -> Option<Vec<u8>> {
+        match &mut self.command_storage {
+            Some(MemoryBypassStorage {
+                data,
+                address,
+                consumed,
+            }) if *address == pointer && !*consumed => {
+                *consumed = true;
+                Some(data.clone())
+            }
+            None => {
+                // We are waiting for data to be ready.
+                None
+            }
+            Some(_) => {
+                // We have buffer in hand, and the data in the buffer is not for the address we are interested in,
+                // or it has been already processed meaning - it may be older than the actual data under this address.
+                self.request_radio_command_read(ctx, pointer);
+                None
+            }
+        }
+    }
+
+    fn fetch_chain_link(&mut self, ctx: &mut Context) {
+        let Some((level, link)) = self
+            .chain_links
+            .iter()
+            .find_map(|(level, link)| link.map(|link| (level, link)))
+        else {
+            return;
+        };
+        if !is_command_pointer(link.pointer) {
+            self.chain_links[level] = None;
+            self.break_chain(ctx, level, link.previous, CommandStatus::ErrorPointer);
+            return;
+        }
+        let Some(data) = self.take_fetched_command(ctx, link.pointer) else {
+            return;
+        };
+        self.chain_links[level] = None;
+        let Ok(Command {
+            id,
+            data: data @ CommandData::RadioOperation { preamble, .. },
+        }) = Command::try_from(data.as_slice())
+        else {
+            self.break_chain(ctx, level, link.previous, CommandStatus::ErrorCmdId);
+            return;
+        };
+        if link.skips > 0 {
+            trace!("skipping {id:?} at {:?}", link.pointer);
+            self.write_back_status_field(ctx, link.pointer, CommandStatus::Skipped);
+            let next = Some((preamble.p_next_op, link.skips - 1));
+            self.continue_chain(ctx, level, link.pointer, next);
+            return;
+        }
+        self.pending_command = Some(PendingCommand {
+            command: Command { id, data },
+            pointer: Some(link.pointer),
+            received_at_cycle: *self.cycles_clock,
+            submitted_at: self.rat_time(ctx),
+            submitted: false,
+            level: Some(level),
+        });
+    }
+
+    fn try_acknowledge_task(&mut self, ctx: &mut Context) {
+        // For immediate command there is no distinction between acknowledging command and executing it -
+        // command is acknowledged once its executed.
+        let Some(
+            pending @ PendingCommand {
+                command:
+                    command @ Command {
+                        id,
+                        data: CommandData::RadioOperation { .. },
+                    },
+                pointer: Some(cmd_addr),
+                received_at_cycle,
+                submitted: true,
+                level: None,
+                ..
+            },
+        ) = self.pending_command
+        else {
+            return;
+        };
+        let (cycles_since_received, _) = self.cycles_clock.overflowing_sub(received_at_cycle);
+        if cycles_since_received < cycles_to_ack(&command) {
+            return;
+        }
+        if let Some(level) = self.level_for(id) {
+            self.write_back_status_field(ctx, cmd_addr, CommandStatus::Pending);
+            self.ack_command_with_status(ctx, ResultByte::Done);
+            self.pending_command = Some(PendingCommand {
+                level: Some(level),
+                ..pending
+            });
         } else {
-            warn!(
-                "Tried to write status {:?} field on empty command pointer.",
-                command_pointer
-            );
+            debug!("Cannot schedule {id:?}, as another operation is running.");
+            self.pending_command = None;
+            self.ack_command_with_status(ctx, ResultByte::SchedulingError);
         }
     }
 
-    fn run_command(
-        &mut self,
-        ctx: &mut Context,
-        command: Command,
-        pointer: Option<Address>,
-    ) -> CommandStatus {
-        info!("Running command: {command:?}");
-        match command {
-            Command {
-                id: CommandId::MagicalSecretSauce | CommandId::Ping,
-                data: CommandData::Immediate { .. } | CommandData::Direct { .. },
+    /// The level to run the submitted operation at, if it's free.
+    fn level_for(&self, id: CommandId) -> Option<Level> {
+        let level = if id.is_ieee_foreground() && self.runs_background_rx() {
+            Level::Foreground
+        } else {
+            Level::Background
+        };
+        let busy = self.running[level].is_some()
+            || self.scheduled[level].is_some()
+            || self.chain_links[level].is_some();
+        (!busy).then_some(level)
+    }
+
+    fn try_run_task(&mut self, ctx: &mut Context) {
+        let Some(pending) = self.pending_command else {
+            return;
+        };
+        let (cycles_since_received, _) =
+            self.cycles_clock.overflowing_sub(pending.received_at_cycle);
+        if cycles_since_received < cycles_to_done(&pending.command) {
+            return;
+        }
+
+        let id = pending.command.id;
+        match pending.command.data {
+            CommandData::RadioOperation {
+                preamble,
+                parameters,
             } => {
-                self.ack_command_with_status(ctx, ResultByte::Done);
-                self.write_back_status_field(ctx, pointer, CommandStatus::DoneOk);
+                // The submitted operations are acknowledged first, which determines their level.
+                let (Some(level), Some(pointer)) = (pending.level, pending.pointer) else {
+                    return;
+                };
+                self.pending_command = None;
+                if !pending.submitted {
+                    self.write_back_status_field(ctx, pointer, CommandStatus::Pending);
+                }
+                self.scheduled[level] = Some(ScheduledOperation {
+                    id,
+                    preamble,
+                    parameters,
+                    pointer,
+                    submitted_at: pending.submitted_at,
+                    checked: false,
+                });
+                self.try_start_scheduled(ctx, level);
             }
-            Command {
-                id: CommandId::Abort,
-                data: CommandData::Immediate { .. } | CommandData::Direct { .. },
-            } => {
-                // TODO: handle those commands somewhere up the stack - or introduce something like tiers of command handling
-                // New - not processed command
-                // Acked - sent ack for command
-                // Done? - finished
-
-                // I think in practice it's only used to stop IeeeRx and Cca, which we keep in totally separate place that pending_command.
-                // We don't need to introduce something like queue of commands.
-                self.ack_command_with_status(ctx, ResultByte::Done);
-                self.write_back_status_field(ctx, pointer, CommandStatus::DoneOk);
-                if let Some((cmd, cmd_addr)) = *self.background_command {
-                    self.write_back_status_field(
-                        ctx,
-                        Some(cmd_addr),
-                        if cmd.is_ieee() {
-                            CommandStatus::IeeeDoneAbort
-                        } else {
-                            CommandStatus::DoneAbort
-                        },
-                    );
-                    self.background_command.set_next(None);
+            CommandData::Immediate { parameters } => {
+                self.pending_command = None;
+                info!("Running command: {:?}", pending.command);
+                if let Some(status) = self.run_immediate(ctx, parameters, pending.pointer) {
+                    self.ack_command_with_status(ctx, status);
                 }
             }
-            Command {
-                id: CommandId::SyncStartRat,
-                data:
-                    CommandData::RadioOperation {
-                        parameters: params @ RadioOperationCommandParameters::SyncStartRat { .. },
-                        ..
-                    },
-            } => {
-                trace!("running {:?}", &params);
-                // TODO: move to actual ack
-                self.ack_command_with_status(ctx, ResultByte::Done);
-
-                self.write_back_status_field(ctx, pointer, CommandStatus::DoneOk);
+            CommandData::Direct { param, extension } => {
+                self.pending_command = None;
+                info!("Running command: {:?}", pending.command);
+                let status = match direct_parameters(id, param, extension) {
+                    Some(parameters) => self.run_immediate(ctx, parameters, None),
+                    None => Some(ResultByte::UnknownDirCommand.into()),
+                };
+                if let Some(status) = status {
+                    self.ack_command_with_status(ctx, status);
+                }
+            }
+        }
+    }
 
-                self.raise_cpe_interrupt_if_enabled(ctx, Interrupt::CommandDone);
-                // TODO: Chained commands are ignore, so pretend current command is the last one.
-                self.raise_cpe_interrupt_if_enabled(ctx, Interrupt::LastCommandDone);
+    fn progress_operations(&mut self, ctx: &mut Context) {
+        let now = ctx.event_queue().get_current_time();
+        for level in [Level::Background, Level::Foreground] {
+            self.try_start_scheduled(ctx, level);
+            let Some(op) = self.running[level] else {
+                continue;
+            };
+            // The BLE operations end as soon as allowed, that is not in the middle of a packet.
+            let ble_transmitting =
+                op.parameters.is_ble() && matches!(op.state, OperationState::Transmitting { .. });
+            if !ble_transmitting
+                && op
+                    .end_time
+                    .is_some_and(|time| self.rat_has_reached(now, time))
+            {
+                trace!("end trigger of {:?} at {:?}", op.id, op.pointer);
+                self.finish_operation(ctx, level, op.end_status(), Continuation::Condition);
+                continue;
             }
-            Command {
-                id: CommandId::RadioSetup,
-                data:
-                    CommandData::RadioOperation {
-                        parameters: params @ RadioOperationCommandParameters::RadioSetup { .. },
-                        ..
-                    },
-            } => {
-                trace!("running {:?}", &params);
-                trace!("scheduling XOSCON strobe");
-                if let Some(modem) = self.modem_impl.as_ref() {
-                    modem.strobe(Strobe::RegSxoscon.into());
+            if op
+                .timeout_time
+                .is_some_and(|time| self.rat_has_reached(now, time))
+            {
+                if let Some(op) = &mut self.running[level] {
+                    op.timeout_time = None;
                 }
-                self.ack_command_with_status(ctx, ResultByte::Done);
-                self.write_back_status_field(ctx, pointer, CommandStatus::DoneOk);
-
-                self.raise_cpe_interrupt_if_enabled(ctx, Interrupt::CommandDone);
-                // TODO: Chained commands are ignore, so pretend current command is the last one.
-                self.raise_cpe_interrupt_if_enabled(ctx, Interrupt::LastCommandDone);
-            }
-            Command {
-                id: CommandId::SyncStopRat,
-                data:
-                    CommandData::RadioOperation {
-                        parameters: params @ RadioOperationCommandParameters::SyncStopRat { .. },
-                        ..
-                    },
-            } => {
-                trace!("running {:?}", &params);
-                self.ack_command_with_status(ctx, ResultByte::Done);
-                self.write_back_status_field(ctx, pointer, CommandStatus::DoneOk);
-            }
-            Command {
-                id: CommandId::FsPowerdown,
-                data:
-                    CommandData::RadioOperation {
-                        parameters: params @ RadioOperationCommandParameters::FsPowerdown {},
-                        ..
-                    },
-            } => {
-                trace!("running {:?}", &params);
-                self.ack_command_with_status(ctx, ResultByte::Done);
-                self.write_back_status_field(ctx, pointer, CommandStatus::DoneOk);
+                self.with_ble(ctx, |ble, now| ble.on_timeout_trigger(now));
+                continue;
             }
-            Command {
-                id: CommandId::IeeeRx,
-                data:
-                    CommandData::RadioOperation {
-                        parameters:
-                            params @ RadioOperationCommandParameters::IeeeRx {
-                                channel,
-                                rx_config,
-                                p_rx_q,
-                                p_output,
-                                frame_filt_opt,
-                                frame_types,
-                                cca_opt,
-                                cca_rssi_thr,
-                                num_ext_entries,
-                                num_short_entries,
-                                p_ext_entry_list,
-                                p_short_entry_list,
-                                local_ext_address,
-                                local_short_address,
-                                local_pan_id,
-                                end_trigger,
-                                end_time,
-                            },
-                        ..
-                    },
-            } => {
-                assert_eq!(rx_config.b_auto_flush_crc(), true);
-                assert_eq!(rx_config.b_include_phy_hdr(), false);
-                assert_eq!(rx_config.b_append_src_ind(), false);
-                assert_eq!(p_ext_entry_list, 0);
-                assert_eq!(p_short_entry_list, 0);
-                // dbg!(
-                //     frame_filt_opt,
-                //     frame_types,
-                //     local_ext_address,
-                //     local_short_address,
-                //     local_pan_id
-                // );
-
-                trace!("running {:?}", &params);
-                trace!("scheduling RXON strobe");
-                if let Some(modem) = self.modem_impl.as_ref() {
-                    modem.send_op(ModemOp::SetAddr {
-                        panid: local_pan_id,
-                        short: local_short_address,
-                        ext: local_ext_address,
-                    });
-                    modem.send_op(ModemOp::SetAutoAck(frame_filt_opt.auto_ack_en()));
-                    modem.strobe(Strobe::RegSrxon.into());
+            match op.state {
+                OperationState::Transmitting { until: Some(until) } if now >= until => {
+                    self.finish_transmission(ctx, level);
                 }
-                self.write_back_status_field(ctx, pointer, CommandStatus::Active);
-                self.ack_command_with_status(ctx, ResultByte::Done);
-                self.background_command
-                    .set_next(pointer.map(|addr| (params, addr)));
-                // contiki will spin until it gets it or 0.001 (?) RTC seconds pass
-            }
-            Command {
-                id: CommandId::IeeeTx,
-                data:
-                    CommandData::RadioOperation {
-                        preamble,
-                        parameters:
-                            RadioOperationCommandParameters::IeeeTx {
-                                tx_opt,
-                                payload_len,
-                                p_payload,
-                                time_stamp,
-                            },
-                    },
-            } => {
-                // Acording to 23.5.3.2
-                // We optionally need to append PHY header and CRC
-                match &self.payload_storage {
-                    Some(MemoryBypassStorage {
-                        data,
-                        address,
-                        consumed,
-                    }) if *address == Address::from(p_payload) => {
-                        if let Some(modem) = self.modem_impl.as_ref() {
-                            modem.push_to_tx_fifo(payload_len + 2);
-
-                            // Write PHY header.
-                            if tx_opt.b_include_phy_hdr() {
-                                // 23.5.3.1
-                                // As Contiki does not include PHY header in RxQueue entries, we can send anything as header byte.
-                                todo!();
-                                modem.push_to_tx_fifo(0u8);
-                            }
-
-                            // Write payload.
-                            for byte in &data[..payload_len as usize] {
-                                modem.push_to_tx_fifo(*byte);
-                            }
-
-                            // Write crc.
-                            if tx_opt.b_include_crc() {
-                                // TODO add actual crc
-                                // I think this is the one: https://opencores.org/projects/crc802154 this is CRC16
-                                // put 0 for now a hope it works
-                                todo!();
-                                modem.push_to_tx_fifo(0u8);
-                            }
-
-                            // Notify modem.
-                            modem.strobe(Strobe::RegStxon.into());
-                        } else {
-                            warn!(
-                                "Running IeeeTx command, but there's no modem implementation set. No data will be sent."
-                            );
-                        }
-
-                        self.register_bank.set_command_status(ResultByte::Done);
-                        self.write_back_status_field(ctx, pointer, CommandStatus::Active);
-                        assert!(self.tx_awaiting_done.is_none());
-                        self.tx_awaiting_done = pointer;
-
-                        // When transmission of the packet starts, the trigger RAT time used for starting the modem is written to the
-                        // timeStamp field by the radio CPU. This timestamp is delayed by the firmware-defined parameter
-                        // startToTXRatOffset.
-
-                        // I couldn't find any usages of timestamp in Contiki code.
-                        // Timestamp is used in case of received frames.
-
-                        match pointer {
-                            Some(pointer) => {
-                                let status_field = pointer.offset(20);
-                                let timestamp = 0u32;
-                                self.request_memory_write(ctx, status_field, timestamp.into());
-                            }
-                            None => {
-                                panic!(
-                                    "Pointer for IeeeTx should be set, as it can't be send as direct command."
-                                );
-                            }
-                        }
-
-                        // TODO(pk): When do ieee commands raise ack interrupt?
-                        self.raise_cpe_interrupt_if_enabled(ctx, Interrupt::FgCommandDone);
-                    }
-                    _ => unreachable!(
-                        "IeeeTx should have been prealoaded but was: {:?}",
-                        self.payload_storage
-                    ),
+                OperationState::Backoff { until } if now >= until => {
+                    self.set_state(level, OperationState::Cca);
+                    self.request_cca(CcaUser::Operation(level));
                 }
-                match &mut self.payload_storage {
-                    Some(MemoryBypassStorage { consumed, .. }) => {
-                        *consumed = true;
-                    }
-                    _ => unreachable!(),
+                OperationState::Listening { until: Some(until) } if now >= until => {
+                    self.set_state(level, OperationState::Running);
+                    self.with_ble(ctx, |ble, now| ble.on_listen_timeout(now));
                 }
-            }
-            Command {
-                id: CommandId::IeeeCcaReq,
-                data:
-                    CommandData::Immediate {
-                        parameters: ImmediateCommandParameters::IeeeCcaReq(params),
-                    },
-            } => {
-                trace!("running {:?}", &params);
-                // TODO: should return ContextError if no ongoing RX or energy-detect scan
-                trace!("requesting cca info");
-                if let Some(modem) = self.modem_impl.as_ref() {
-                    modem.cca_prereq();
+                OperationState::Waiting { until } if now >= until => {
+                    self.set_state(level, OperationState::Running);
+                    self.with_ble(ctx, |ble, now| ble.on_wait_done(now));
                 }
-                self.address_for_pending_cca_req.set_next(pointer);
-            }
-            Command {
-                id: CommandId::StartRat,
-                data: CommandData::Direct { param, extension },
-            } => {
-                todo!("CommandId::StartRat");
+                _ => {}
             }
+        }
+        self.try_finish_ble(ctx);
+    }
 
-            Command {
-                id: CommandId::BusRequest,
-                data: CommandData::Direct { param, extension },
-            } => {
-                todo!("CommandId::BusRequest");
-            }
-            Command {
-                id: CommandId::RadioSetup,
-                data:
-                    CommandData::RadioOperation {
-                        preamble,
-                        parameters,
-                    },
-            } => {
-                todo!("CommandId::RadioSetup");
-            }
-            _ => {
-                todo!("{command:?}")
-            }
+    fn set_state(&mut self, level: Level, state: OperationState) {
+        if let Some(op) = &mut self.running[level] {
+            op.state = state;
         }
-        CommandStatus::DoneOk
     }
 
-    fn get_modem(&self) -> &ModemImpl {
-        self.modem_impl
-            .as_ref()
-            .expect("Modem impl is not set, but radio received some command.")
+    fn write_back_status_field(&self, ctx: &mut Context, pointer: Address, status: CommandStatus) {
+        trace!(
+            "writing back status field (CMDSTA) with {:?} under {:?}",
+            status, pointer
+        );
+        let status_field = pointer.offset(2);
+        let status_value: u16 = status.into();
+        self.request_memory_write(ctx, status_field, status_value.into())
+    }
+
+    fn update_radio_activity(&self, ctx: &mut Context) {
+        let mut operations = self.running.values().flatten();
+        let activity = if operations.clone().any(|op| {
+            matches!(op.state, OperationState::Transmitting { .. }) || op.id == CommandId::TxTest
+        }) {
+            RadioActivity::Tx
+        } else if operations.any(|op| {
+            matches!(
+                op.state,
+                OperationState::Cca | OperationState::Listening { .. }
+            ) || matches!(
+                op.id,
+                CommandId::IeeeRx
+                    | CommandId::IeeeEdScan
+                    | CommandId::IeeeRxAck
+                    | CommandId::RxTest
+            )
+        }) {
+            RadioActivity::Rx
+        } else {
+            RadioActivity::Idle
+        };
+        ctx.set_radio_activity(activity);
     }
 
-    fn try_acknowledge_task(&mut self, ctx: &mut Context) -> bool {
-        // For immediate command there is no distinction between acknowledging command and executing it -
-        // command is acknowledged once its executed.
-        match *self.pending_command {
-            Some(PendingCommand {
-                command:
-                    command @ Command {
-                        data: CommandData::RadioOperation { .. },
-                        ..
-                    },
-                received_at_cycle,
-                pointer: Some(cmd_addr),
-            }) => {
-                let (cycles_since_received, _) =
-                    self.cycles_clock.overflowing_sub(received_at_cycle);
-                if cycles_to_ack(&command) == cycles_since_received {
-                    self.write_back_status_field(ctx, Some(cmd_addr), CommandStatus::Active);
-                    self.ack_command_with_status(ctx, ResultByte::Done);
-                    true
-                } else {
-                    false
+    pub(crate) fn set_interface(&mut self, interface: Option<ModemImpl>) {
+        *self.modem_impl = interface;
+    }
+
+    pub(crate) fn take_interface(&mut self) -> Option<ModemImpl> {
+        self.modem_impl.take()
+    }
+
+    pub(crate) fn notify_radio_wakeup(&mut self) {
+        self.need_wakeup = true;
+    }
+
+    #[handler]
+    pub(crate) fn on_new_ahb_slave_input(
+        &mut self,
+        ctx: &mut Context,
+        msg: MasterToSlaveWires<<RFCComponent as AHBPortConfig>::Data>,
+    ) {
+        <Self as AHBSlavePortInput>::on_ahb_input(self, ctx, msg);
+    }
+}
+
+// Scheduling the radio operations and following their chains.
+#[component_impl(rfc)]
+impl RFCComponent {
+    fn trigger_time(
+        &self,
+        level: Level,
+        trigger: Trigger,
+        time: u32,
+        submitted_at: u32,
+        start: Option<u32>,
+    ) -> Option<TriggerTime> {
+        let times = self.chain_times[level];
+        let Ok(trigger_type) = TriggerType::try_from(trigger.trigger_type()) else {
+            return None;
+        };
+        let reference = match trigger_type {
+            TriggerType::Now => return Some(TriggerTime::Now),
+            TriggerType::Never => return Some(TriggerTime::Never),
+            TriggerType::AbsTime => 0,
+            TriggerType::RelSubmit => submitted_at,
+            TriggerType::RelStart => start.unwrap_or(submitted_at),
+            TriggerType::RelPrevStart => times.prev_start,
+            TriggerType::RelFirstStart => times.first_start.or(start).unwrap_or(submitted_at),
+            TriggerType::RelPrevEnd => times.prev_end,
+            // TODO: the events of the radio and the external input are not modelled.
+            TriggerType::RelEvt1 | TriggerType::RelEvt2 | TriggerType::External => {
+                warn!("Unsupported trigger {trigger:?}");
+                return None;
+            }
+        };
+        Some(TriggerTime::At(reference.wrapping_add(time)))
+    }
+
+    fn try_start_scheduled(&mut self, ctx: &mut Context, level: Level) {
+        let Some(op) = self.scheduled[level] else {
+            return;
+        };
+        let trigger = op.preamble.start_trigger;
+        let start_time = match self.trigger_time(
+            level,
+            trigger,
+            op.preamble.start_time,
+            op.submitted_at,
+            None,
+        ) {
+            None => {
+                self.scheduled[level] = None;
+                self.reject_operation(ctx, level, op, CommandStatus::ErrorStartTrig);
+                return;
+            }
+            // It waits for CMD_TRIGGER, or until it's aborted.
+            Some(TriggerTime::Never) => return,
+            Some(TriggerTime::Now) => None,
+            Some(TriggerTime::At(time)) => Some(time),
+        };
+        if let Some(time) = start_time {
+            if !self.rat.is_running() {
+                return;
+            }
+            let now = ctx.event_queue().get_current_time();
+            #[allow(clippy::cast_possible_wrap)]
+            let in_past = (time.wrapping_sub(self.rat.counter(now)) as i32) < 0;
+            if !op.checked && in_past && !trigger.past_trig() {
+                self.scheduled[level] = None;
+                self.reject_operation(ctx, level, op, CommandStatus::ErrorPastStart);
+                return;
+            }
+            if !self.rat.has_reached(now, time) {
+                self.scheduled[level] = Some(ScheduledOperation {
+                    checked: true,
+                    ..op
+                });
+                return;
+            }
+        }
+        self.scheduled[level] = None;
+        self.start_operation(ctx, level, op);
+    }
+
+    /// Ends the operation, which couldn't start, with the error.
+    fn reject_operation(
+        &mut self,
+        ctx: &mut Context,
+        level: Level,
+        op: ScheduledOperation,
+        status: CommandStatus,
+    ) {
+        warn!("{:?} at {:?} cannot start: {status:?}", op.id, op.pointer);
+        self.running[level] = Some(RunningOperation::new(op));
+        self.finish_operation(ctx, level, status, Continuation::Condition);
+    }
+
+    fn start_operation(&mut self, ctx: &mut Context, level: Level, op: ScheduledOperation) {
+        let now = self.rat_time(ctx);
+        info!(
+            "Starting {:?} at {:?}: {:?}",
+            op.id, op.pointer, op.parameters
+        );
+        let end_trigger = end_trigger(&op.parameters);
+        let end_time = end_trigger.map(|(trigger, time)| {
+            self.trigger_time(level, trigger, time, op.submitted_at, Some(now))
+        });
+        let times = &mut self.chain_times[level];
+        times.first_start.get_or_insert(now);
+        times.prev_start = now;
+        self.running[level] = Some(RunningOperation {
+            end_trigger: end_trigger.map(|(trigger, _)| trigger),
+            end_time: match end_time {
+                Some(Some(TriggerTime::Now)) => Some(now),
+                Some(Some(TriggerTime::At(time))) => Some(time),
+                _ => None,
+            },
+            ..RunningOperation::new(op)
+        });
+
+        if end_time == Some(None) {
+            let status = if op.parameters.is_ieee() {
+                CommandStatus::IeeeErrorPar
+            } else {
+                CommandStatus::ErrorPar
+            };
+            self.finish_operation(ctx, level, status, Continuation::Condition);
+            return;
+        }
+        if ConditionRule::try_from(op.preamble.condition.rule()).is_err() {
+            self.finish_operation(
+                ctx,
+                level,
+                CommandStatus::ErrorCondition,
+                Continuation::Stop,
+            );
+            return;
+        }
+        self.write_back_status_field(ctx, op.pointer, CommandStatus::Active);
+        self.begin_operation(ctx, level);
+        self.update_radio_activity(ctx);
+    }
+
+    fn finish_operation(
+        &mut self,
+        ctx: &mut Context,
+        level: Level,
+        status: CommandStatus,
+        continuation: Continuation,
+    ) {
+        let Some(op) = self.running[level].take() else {
+            return;
+        };
+        debug!("{:?} at {:?} finished with {status:?}", op.id, op.pointer);
+        self.write_back_status_field(ctx, op.pointer, status);
+        self.write_operation_output(ctx, &op);
+        if op.parameters.is_ble() {
+            self.close_ble(ctx);
+        }
+        self.chain_times[level].prev_end = self.rat_time(ctx);
+        self.cca_users
+            .retain(|user| *user != CcaUser::Operation(level));
+        self.raise_cpe_interrupt(
+            ctx,
+            match level {
+                Level::Background => Interrupt::CommandDone,
+                Level::Foreground => Interrupt::FgCommandDone,
+            },
+        );
+        if matches!(op.id, CommandId::IeeeRx | CommandId::IeeeEdScan) {
+            self.send_modem_op(ModemOp::Strobe(Strobe::RegSrfoff.into()));
+        }
+        if level == Level::Background && matches!(op.id, CommandId::IeeeRx | CommandId::IeeeEdScan)
+        {
+            // The foreground operations need the background one.
+            self.stop_level(ctx, Level::Foreground, |_| CommandStatus::IeeeDoneBgend);
+        }
+
+        let next = match continuation {
+            Continuation::Condition => next_operation(&op, status.result()),
+            Continuation::Branch(pointer) => Some((pointer, 0)),
+            Continuation::Stop => None,
+        };
+        self.continue_chain(ctx, level, op.pointer, next);
+        self.update_radio_activity(ctx);
+    }
+
+    /// Writes back the fields updated by the operation.
+    fn write_operation_output(&self, ctx: &mut Context, op: &RunningOperation) {
+        match op.parameters {
+            RadioOperationCommandParameters::IeeeEdScan { max_rssi, .. } => {
+                self.request_memory_write(ctx, op.pointer.offset(18), DataBus::Byte(max_rssi));
+            }
+            RadioOperationCommandParameters::IeeeCsma {
+                random_state,
+                nb,
+                be,
+                remaining_periods,
+                last_rssi,
+                last_time_stamp,
+                ..
+            } => {
+                self.request_memory_write(ctx, op.pointer.offset(14), random_state.into());
+                self.write_bytes(
+                    ctx,
+                    op.pointer.offset(19),
+                    &[nb, be, remaining_periods, last_rssi],
+                );
+                self.request_memory_write(ctx, op.pointer.offset(24), last_time_stamp.into());
+            }
+            RadioOperationCommandParameters::IeeeRx { p_output, .. } => {
+                self.write_rx_output(ctx, p_output);
+            }
+            _ => {}
+        }
+    }
+
+    /// Schedules fetching the next operation in the chain, or ends the chain.
+    fn continue_chain(
+        &mut self,
+        ctx: &mut Context,
+        level: Level,
+        previous: Address,
+        next: Option<(Address, u8)>,
+    ) {
+        match next {
+            Some((pointer, skips)) if pointer != NULL => {
+                self.chain_links[level] = Some(ChainLink {
+                    pointer,
+                    previous,
+                    skips,
+                });
+            }
+            _ => self.end_chain(ctx, level),
+        }
+    }
+
+    fn end_chain(&mut self, ctx: &mut Context, level: Level) {
+        self.chain_times[level].first_start = None;
+        self.raise_cpe_interrupt(
+            ctx,
+            match level {
+                Level::Background => Interrupt::LastCommandDone,
+                Level::Foreground => Interrupt::LastFgCommandDone,
+            },
+        );
+    }
+
+    /// Reports the invalid next operation in the status of the one before.
+    fn break_chain(
+        &mut self,
+        ctx: &mut Context,
+        level: Level,
+        previous: Address,
+        status: CommandStatus,
+    ) {
+        warn!("The chain after {previous:?} is broken: {status:?}");
+        self.write_back_status_field(ctx, previous, status);
+        self.end_chain(ctx, level);
+    }
+
+    /// Ends the operations of the level with the status, returns whether there were any.
+    fn stop_level(
+        &mut self,
+        ctx: &mut Context,
+        level: Level,
+        status: impl Fn(&RunningOperation) -> CommandStatus,
+    ) -> bool {
+        let mut stopped = false;
+        if self.chain_links[level].take().is_some() {
+            self.end_chain(ctx, level);
+            stopped = true;
+        }
+        if let Some(op) = self.scheduled[level].take() {
+            self.running[level] = Some(RunningOperation::new(op));
+        }
+        if let Some(op) = self.running[level] {
+            self.finish_operation(ctx, level, status(&op), Continuation::Stop);
+            stopped = true;
+        }
+        stopped
+    }
+
+    /// `CMD_TRIGGER`, returns whether any trigger fired.
+    fn fire_command_trigger(&mut self, ctx: &mut Context, trigger_no: u8) -> bool {
+        let fires = |trigger: Trigger| trigger.b_ena_cmd() && trigger.trigger_no() == trigger_no;
+        let mut fired = false;
+        for level in [Level::Background, Level::Foreground] {
+            if let Some(op) = self.scheduled[level].filter(|op| fires(op.preamble.start_trigger)) {
+                self.scheduled[level] = None;
+                self.start_operation(ctx, level, op);
+                fired = true;
+            } else if let Some(op) =
+                self.running[level].filter(|op| op.end_trigger.is_some_and(fires))
+            {
+                self.finish_operation(ctx, level, op.end_status(), Continuation::Condition);
+                fired = true;
+            }
+        }
+        fired
+    }
+
+    /// Ticks of the RAT until the earliest timed event.
+    fn ticks_to_next_event(&self, ctx: &Context) -> Option<u32> {
+        let now = ctx.event_queue().get_current_time();
+        let rat_ticks = |time: u32| {
+            self.rat
+                .is_running()
+                .then(|| self.rat.ticks_until(now, time))
+        };
+        let ticks = |until: Timepoint| {
+            let left = until
+                .checked_sub_timepoint(now)
+                .map_or(0, |left| left / TICK);
+            Some(u32::try_from(left).unwrap_or(u32::MAX))
+        };
+        let scheduled = self.scheduled.iter().filter_map(|(level, op)| {
+            let op = op.as_ref()?;
+            let time = self.trigger_time(
+                level,
+                op.preamble.start_trigger,
+                op.preamble.start_time,
+                op.submitted_at,
+                None,
+            );
+            match time? {
+                TriggerTime::At(time) => rat_ticks(time),
+                TriggerTime::Now => Some(0),
+                TriggerTime::Never => None,
+            }
+        });
+        let running = self.running.values().flatten().flat_map(|op| {
+            let state = match op.state {
+                OperationState::Transmitting { until: Some(until) }
+                | OperationState::Backoff { until }
+                | OperationState::Listening { until: Some(until) }
+                | OperationState::Waiting { until } => ticks(until),
+                _ => None,
+            };
+            [
+                op.end_time.and_then(rat_ticks),
+                op.timeout_time.and_then(rat_ticks),
+                state,
+            ]
+        });
+        scheduled
+            .chain(running.flatten())
+            .chain(self.rat.ticks_until_compare(now))
+            .min()
+    }
+}
+
+// Running the radio operations.
+#[component_impl(rfc)]
+impl RFCComponent {
+    fn begin_operation(&mut self, ctx: &mut Context, level: Level) {
+        let Some(op) = self.running[level] else {
+            return;
+        };
+        let status = match op.parameters {
+            RadioOperationCommandParameters::Nop {}
+            | RadioOperationCommandParameters::FsPowerup { .. } => CommandStatus::DoneOk,
+            RadioOperationCommandParameters::RadioSetup { mode, .. } => {
+                if mode != RADIO_MODE_KEEP {
+                    self.radio_mode = Some(mode);
+                }
+                trace!("scheduling XOSCON strobe");
+                self.send_modem_op(ModemOp::Strobe(Strobe::RegSxoscon.into()));
+                CommandStatus::DoneOk
+            }
+            RadioOperationCommandParameters::FsPowerdown {}
+            | RadioOperationCommandParameters::FsOff {} => {
+                self.fs_on = false;
+                CommandStatus::DoneOk
+            }
+            RadioOperationCommandParameters::Fs { .. } => {
+                if self.radio_mode.is_some() {
+                    self.fs_on = true;
+                    CommandStatus::DoneOk
+                } else {
+                    CommandStatus::ErrorNoSetup
+                }
+            }
+            RadioOperationCommandParameters::RxTest { .. }
+            | RadioOperationCommandParameters::TxTest { .. } => {
+                match (self.radio_mode, self.fs_on) {
+                    (None, _) => CommandStatus::ErrorNoSetup,
+                    (_, false) => CommandStatus::ErrorNoFs,
+                    // It runs until the end trigger.
+                    _ => return,
+                }
+            }
+            RadioOperationCommandParameters::SyncStopRat { .. } => {
+                let rat0 = self.rat.sync_stop(ctx.event_queue().get_current_time());
+                self.request_memory_write(ctx, op.pointer.offset(16), rat0.into());
+                CommandStatus::DoneOk
+            }
+            RadioOperationCommandParameters::SyncStartRat { rat0 } => {
+                self.rat.sync_start(rat0);
+                CommandStatus::DoneOk
+            }
+            RadioOperationCommandParameters::Count { counter } => {
+                let counter = counter.saturating_sub(1);
+                self.request_memory_write(ctx, op.pointer.offset(14), counter.into());
+                if counter == 0 {
+                    CommandStatus::DoneCountdown
+                } else {
+                    CommandStatus::DoneOk
+                }
+            }
+            RadioOperationCommandParameters::CountBranch {
+                counter,
+                p_next_op_if_ok,
+            } => {
+                let counter = counter.saturating_sub(1);
+                self.request_memory_write(ctx, op.pointer.offset(14), counter.into());
+                if counter == 0 {
+                    CommandStatus::DoneCountdown
+                } else {
+                    let branch = Continuation::Branch(Address::from_const(p_next_op_if_ok));
+                    self.finish_operation(ctx, level, CommandStatus::DoneOk, branch);
+                    return;
+                }
+            }
+            RadioOperationCommandParameters::PatternCheck {
+                pattern_opt,
+                p_value,
+                ..
+            } => {
+                let options = PatternOptions::from_bytes(pattern_opt.to_le_bytes());
+                if options.b_rx_val() {
+                    let Some(payload) = &self.last_rx_payload else {
+                        self.finish_operation(
+                            ctx,
+                            level,
+                            CommandStatus::ErrorNoRx,
+                            Continuation::Condition,
+                        );
+                        return;
+                    };
+                    let mut value = [0u8; PATTERN_VALUE_SIZE];
+                    for (idx, byte) in value.iter_mut().enumerate() {
+                        *byte = payload
+                            .get(p_value as usize + idx)
+                            .copied()
+                            .unwrap_or_default();
+                    }
+                    self.check_pattern(ctx, level, u32::from_le_bytes(value));
+                    return;
+                }
+                let address = Address::from_const(p_value);
+                if is_memory(address) {
+                    self.set_state(level, OperationState::FetchingValue);
+                    let storage = self.value_storage.take();
+                    self.request_memory_read(
+                        ctx,
+                        address,
+                        storage,
+                        PATTERN_VALUE_SIZE,
+                        RfcMemoryBypassReceiver::PatternValue,
+                    );
+                    return;
+                }
+                CommandStatus::ErrorPar
+            }
+            RadioOperationCommandParameters::SchImm { .. } => {
+                warn!("CMD_SCH_IMM is not supported.");
+                CommandStatus::ErrorPar
+            }
+            RadioOperationCommandParameters::IeeeRx {
+                channel,
+                p_rx_q,
+                p_output,
+                frame_filt_opt,
+                local_ext_address,
+                local_short_address,
+                local_pan_id,
+                ..
+            } => match self.tune_ieee(channel) {
+                Err(status) => status,
+                Ok(()) if !is_memory(p_rx_q) => CommandStatus::IeeeErrorPar,
+                Ok(()) => {
+                    self.rx_output = IeeeRxOutput::new();
+                    self.rx_frames.clear();
+                    self.write_rx_output(ctx, p_output);
+                    trace!("scheduling RXON strobe");
+                    self.send_modem_op(ModemOp::SetAddr {
+                        panid: local_pan_id,
+                        short: local_short_address,
+                        ext: local_ext_address,
+                    });
+                    self.send_modem_op(ModemOp::SetAutoAck(frame_filt_opt.auto_ack_en()));
+                    self.send_modem_op(ModemOp::Strobe(Strobe::RegSrxon.into()));
+                    return;
+                }
+            },
+            RadioOperationCommandParameters::IeeeEdScan { channel, .. } => {
+                match self.tune_ieee(channel) {
+                    Err(status) => status,
+                    Ok(()) => {
+                        if let Some(RunningOperation {
+                            parameters: RadioOperationCommandParameters::IeeeEdScan { max_rssi, .. },
+                            ..
+                        }) = &mut self.running[level]
+                        {
+                            *max_rssi = RSSI_UNKNOWN.to_le_bytes()[0];
+                        }
+                        self.send_modem_op(ModemOp::Strobe(Strobe::RegSrxon.into()));
+                        self.request_cca(CcaUser::Operation(level));
+                        return;
+                    }
+                }
+            }
+            RadioOperationCommandParameters::IeeeTx {
+                payload_len,
+                p_payload,
+                ..
+            } => {
+                let p_payload = Address::from_const(p_payload);
+                if self.radio_mode != Some(RADIO_MODE_IEEE) {
+                    CommandStatus::IeeeErrorNoSetup
+                } else if !is_memory(p_payload) {
+                    CommandStatus::IeeeErrorPar
+                } else {
+                    // When transmission of the packet starts, the trigger RAT time used for starting the modem is written to the
+                    // timeStamp field by the radio CPU.
+                    let timestamp = self.rat_time(ctx);
+                    self.request_memory_write(ctx, op.pointer.offset(20), timestamp.into());
+                    self.set_state(level, OperationState::FetchingPayload);
+                    self.request_radio_message_payload_read(ctx, p_payload, payload_len.into());
+                    return;
+                }
+            }
+            RadioOperationCommandParameters::IeeeCsma { .. } => {
+                if self.radio_mode == Some(RADIO_MODE_IEEE) {
+                    self.start_backoff(ctx, level);
+                    return;
+                }
+                CommandStatus::IeeeErrorNoSetup
+            }
+            RadioOperationCommandParameters::IeeeRxAck { .. } => match level {
+                // The ACK is received by the background operation.
+                Level::Background => CommandStatus::IeeeDoneBgend,
+                Level::Foreground => return,
+            },
+            RadioOperationCommandParameters::Ble {
+                channel,
+                p_params,
+                p_output,
+                ..
+            } => match self.tune_ble(channel) {
+                Err(status) => status,
+                Ok(()) if !is_memory(p_params) => CommandStatus::BleErrorPar,
+                Ok(()) if p_output != NULL && !is_memory(p_output) => CommandStatus::BleErrorPar,
+                Ok(()) => {
+                    let kind =
+                        BleKind::from_id(op.id).expect("the BLE operations have the parameters");
+                    self.rx_frames.clear();
+                    self.ble = Some(BleOperation::new(kind, channel, p_params, p_output));
+                    self.run_ble_actions(ctx);
+                    return;
+                }
+            },
+            RadioOperationCommandParameters::IeeeAbortBg {} => {
+                self.finish_operation(
+                    ctx,
+                    level,
+                    CommandStatus::IeeeDoneOk,
+                    Continuation::Condition,
+                );
+                if level == Level::Foreground {
+                    self.stop_level(ctx, Level::Background, |op| op.stop_status(true));
+                }
+                return;
+            }
+        };
+        self.finish_operation(ctx, level, status, Continuation::Condition);
+    }
+
+    /// Tunes to the channel of an IEEE 802.15.4 operation.
+    fn tune_ieee(&mut self, channel: u8) -> Result<(), CommandStatus> {
+        if self.radio_mode != Some(RADIO_MODE_IEEE) {
+            return Err(CommandStatus::IeeeErrorNoSetup);
+        }
+        match channel {
+            // Channel 0 keeps the frequency programmed by CMD_FS.
+            0 if self.fs_on => Ok(()),
+            0 => Err(CommandStatus::IeeeErrorNoFs),
+            channel if IEEE_CHANNELS.contains(&channel) => {
+                self.fs_on = true;
+                Ok(())
+            }
+            _ => Err(CommandStatus::IeeeErrorPar),
+        }
+    }
+
+    /// Tunes to the channel of a BLE operation.
+    fn tune_ble(&mut self, channel: u8) -> Result<(), CommandStatus> {
+        if self.radio_mode != Some(RADIO_MODE_BLE) {
+            return Err(CommandStatus::BleErrorNoSetup);
+        }
+        match channel {
+            BLE_CHANNEL_KEEP if self.fs_on => Ok(()),
+            BLE_CHANNEL_KEEP => Err(CommandStatus::BleErrorNoFs),
+            channel
+                if BLE_CHANNELS.contains(&channel) || BLE_CUSTOM_CHANNELS.contains(&channel) =>
+            {
+                self.fs_on = true;
+                Ok(())
+            }
+            _ => Err(CommandStatus::BleErrorPar),
+        }
+    }
+
+    fn send_modem_op(&self, op: ModemOp) {
+        if let Some(modem) = self.modem_impl.as_ref() {
+            modem.send_op(op);
+        }
+    }
+
+    fn check_pattern(&mut self, ctx: &mut Context, level: Level, value: u32) {
+        let Some(RunningOperation {
+            parameters:
+                RadioOperationCommandParameters::PatternCheck {
+                    pattern_opt,
+                    p_next_op_if_ok,
+                    mask,
+                    compare_val,
+                    ..
+                },
+            ..
+        }) = self.running[level]
+        else {
+            return;
+        };
+        let options = PatternOptions::from_bytes(pattern_opt.to_le_bytes());
+        let mut value = value;
+        if options.b_byte_rev() {
+            value = value.swap_bytes();
+        }
+        if options.b_bit_rev() {
+            value = value.reverse_bits();
+        }
+        value &= mask;
+        let ordering = match options.sign_ext() {
+            0 => value.cmp(&compare_val),
+            bit => sign_extend(value, bit).cmp(&sign_extend(compare_val, bit)),
+        };
+        let result = match options.operation() {
+            0 => ordering.is_eq(),
+            1 => ordering.is_lt(),
+            2 => ordering.is_gt(),
+            _ => ordering.is_ne(),
+        };
+        trace!("pattern check of {value:#x} against {compare_val:#x}: {result}");
+        let continuation = if result {
+            Continuation::Branch(Address::from_const(p_next_op_if_ok))
+        } else {
+            Continuation::Condition
+        };
+        self.finish_operation(ctx, level, CommandStatus::DoneOk, continuation);
+    }
+
+    fn start_backoff(&mut self, ctx: &mut Context, level: Level) {
+        let now = ctx.event_queue().get_current_time();
+        let Some(RunningOperation {
+            parameters:
+                RadioOperationCommandParameters::IeeeCsma {
+                    random_state,
+                    be,
+                    remaining_periods,
+                    ..
+                },
+            state,
+            ..
+        }) = &mut self.running[level]
+        else {
+            return;
+        };
+        let periods = if *remaining_periods != 0 {
+            u32::from(std::mem::take(remaining_periods))
+        } else {
+            if *random_state == 0 {
+                *random_state = CSMA_RANDOM_SEED;
+            }
+            *random_state = next_random(*random_state);
+            let window = (1u32 << (*be).min(15)) - 1;
+            u32::from(*random_state) & window
+        };
+        trace!("CSMA-CA backoff for {periods} periods");
+        *state = OperationState::Backoff {
+            until: now + RadioTimer::ticks_duration(periods * IEEE_BACKOFF_PERIOD_TICKS),
+        };
+    }
+
+    fn request_cca(&mut self, user: CcaUser) {
+        if self.cca_users.is_empty() {
+            trace!("requesting cca info");
+            self.send_modem_op(ModemOp::RequestCca);
+        }
+        if !self.cca_users.contains(&user) {
+            self.cca_users.push(user);
+        }
+    }
+
+    fn on_cca_read(&mut self, ctx: &mut Context, cca: CcaReq) {
+        trace!("read {cca:?} from modem");
+        self.last_rssi = i8::from_le_bytes([cca.current_rssi()]);
+        for user in std::mem::take(&mut self.cca_users) {
+            match user {
+                CcaUser::Request(pointer) => {
+                    self.write_bytes(ctx, pointer.offset(2), &cca.into_bytes());
+                    self.ack_command_with_status(ctx, ResultByte::Done);
+                }
+                CcaUser::Operation(level) => self.on_operation_cca(ctx, level, cca),
+            }
+        }
+    }
+
+    fn on_operation_cca(&mut self, ctx: &mut Context, level: Level, cca: CcaReq) {
+        let now = self.rat_time(ctx);
+        let rssi = cca.current_rssi();
+        let Some(op) = &mut self.running[level] else {
+            return;
+        };
+        let outcome = match &mut op.parameters {
+            RadioOperationCommandParameters::IeeeEdScan { max_rssi, .. } => {
+                if i8::from_le_bytes([rssi]) > i8::from_le_bytes([*max_rssi]) {
+                    *max_rssi = rssi;
+                }
+                CcaOutcome::ReadAgain
+            }
+            RadioOperationCommandParameters::IeeeCsma {
+                mac_max_be,
+                mac_max_cdma_backoffs,
+                nb,
+                be,
+                last_rssi,
+                last_time_stamp,
+                ..
+            } => {
+                *last_rssi = rssi;
+                match cca.cca_state() {
+                    CCA_STATE_IDLE => {
+                        *last_time_stamp = now;
+                        CcaOutcome::Finish(CommandStatus::IeeeDoneOk)
+                    }
+                    CCA_STATE_BUSY => {
+                        *nb = nb.saturating_add(1);
+                        *be = be.saturating_add(1).min(*mac_max_be);
+                        if *nb > *mac_max_cdma_backoffs {
+                            CcaOutcome::Finish(CommandStatus::IeeeDoneBusy)
+                        } else {
+                            CcaOutcome::Backoff
+                        }
+                    }
+                    // The reading is invalid, so it's repeated.
+                    _ => CcaOutcome::ReadAgain,
+                }
+            }
+            _ => return,
+        };
+        match outcome {
+            CcaOutcome::ReadAgain => self.request_cca(CcaUser::Operation(level)),
+            CcaOutcome::Backoff => {
+                self.start_backoff(ctx, level);
+                self.update_radio_activity(ctx);
+            }
+            CcaOutcome::Finish(status) => {
+                self.finish_operation(ctx, level, status, Continuation::Condition);
+            }
+        }
+    }
+
+    fn transmit(&mut self, ctx: &mut Context, payload: &[u8]) {
+        let Some((level, tx_opt)) =
+            [Level::Foreground, Level::Background]
+                .into_iter()
+                .find_map(|level| match self.running[level] {
+                    Some(RunningOperation {
+                        parameters: RadioOperationCommandParameters::IeeeTx { tx_opt, .. },
+                        state: OperationState::FetchingPayload,
+                        ..
+                    }) => Some((level, tx_opt)),
+                    _ => None,
+                })
+        else {
+            warn!("Received a payload, which no operation waits for.");
+            return;
+        };
+
+        // According to 23.5.3.2, the payload may include the PHY header and the CRC.
+        // The modem takes the PHY header followed by the PSDU without the CRC, which it appends itself.
+        let (phy_header, psdu) = match payload.split_first() {
+            Some((&phy_header, psdu)) if tx_opt.b_include_phy_hdr() => (phy_header, psdu),
+            _ => {
+                let crc_size = if tx_opt.b_include_crc() {
+                    0
+                } else {
+                    IEEE_CRC_SIZE
+                };
+                let length = u8::try_from(payload.len() + crc_size).unwrap_or(u8::MAX);
+                (length, payload)
+            }
+        };
+        let psdu = if tx_opt.b_include_crc() {
+            &psdu[..psdu.len().saturating_sub(IEEE_CRC_SIZE)]
+        } else {
+            psdu
+        };
+
+        let until = if self.modem_impl.is_some() {
+            self.send_modem_op(ModemOp::PushTx(phy_header));
+            for byte in psdu {
+                self.send_modem_op(ModemOp::PushTx(*byte));
+            }
+            self.send_modem_op(ModemOp::Strobe(Strobe::RegStxon.into()));
+            None
+        } else {
+            warn!(
+                "Running IeeeTx command, but there's no modem implementation set. No data will be sent."
+            );
+            let ticks = (u32::from(phy_header) + IEEE_SYNC_HEADER_SIZE) * IEEE_TICKS_PER_BYTE;
+            Some(ctx.event_queue().get_current_time() + RadioTimer::ticks_duration(ticks))
+        };
+        self.set_state(level, OperationState::Transmitting { until });
+        self.update_radio_activity(ctx);
+    }
+
+    fn on_tx_finished(&mut self, ctx: &mut Context) {
+        let level = [Level::Foreground, Level::Background]
+            .into_iter()
+            .find(|&level| {
+                matches!(
+                    self.running[level],
+                    Some(RunningOperation {
+                        state: OperationState::Transmitting { .. },
+                        ..
+                    })
+                )
+            });
+        match level {
+            Some(level) => self.finish_transmission(ctx, level),
+            None => warn!("The modem finished a transmission, which no operation waits for."),
+        }
+    }
+
+    fn finish_transmission(&mut self, ctx: &mut Context, level: Level) {
+        if self.running[level].is_some_and(|op| op.parameters.is_ble()) {
+            self.set_state(level, OperationState::Running);
+            self.with_ble(ctx, |ble, _| ble.on_tx_done());
+            return;
+        }
+        self.raise_cpe_interrupt(ctx, Interrupt::TxDone);
+        self.finish_operation(
+            ctx,
+            level,
+            CommandStatus::IeeeDoneOk,
+            Continuation::Condition,
+        );
+    }
+}
+
+// Receiving the IEEE 802.15.4 frames.
+#[component_impl(rfc)]
+impl RFCComponent {
+    fn on_frame_received(&mut self, ctx: &mut Context, frame: Vec<u8>) {
+        if frame
+            .first()
+            .is_none_or(|&phy_header| usize::from(phy_header) + 1 != frame.len())
+        {
+            warn!("Dropping a malformed frame {frame:?}");
+            self.rx_output.n_rx_nok = self.rx_output.n_rx_nok.saturating_add(1);
+            self.raise_cpe_interrupt(ctx, Interrupt::RxNok);
+            self.write_current_rx_output(ctx);
+            return;
+        }
+        self.match_ack(ctx, &frame);
+        self.rx_frames.push_back(RxFrame::Ieee(frame));
+        self.store_next_frame(ctx);
+    }
+
+    /// Ends `CMD_IEEE_RX_ACK` waiting for the frame, if it's the ACK.
+    fn match_ack(&mut self, ctx: &mut Context, frame: &[u8]) {
+        let Some(RunningOperation {
+            parameters: RadioOperationCommandParameters::IeeeRxAck { seq_no, .. },
+            ..
+        }) = self.running[Level::Foreground]
+        else {
+            return;
+        };
+        // The PHY header, the frame control field and the sequence number.
+        let [_, fcf, _, seq, ..] = *frame else {
+            return;
+        };
+        if fcf & IEEE_FRAME_TYPE_MASK == IEEE_FRAME_TYPE_ACK && seq == seq_no {
+            let status = if fcf & IEEE_FCF_FRAME_PENDING == 0 {
+                CommandStatus::IeeeDoneAck
+            } else {
+                CommandStatus::IeeeDoneAckpend
+            };
+            self.finish_operation(ctx, Level::Foreground, status, Continuation::Condition);
+        }
+    }
+
+    fn store_next_frame(&mut self, ctx: &mut Context) {
+        if self.rx_storing || self.rx_frames.is_empty() {
+            return;
+        }
+        let Some(p_rx_q) = self.rx_queue_pointer() else {
+            self.rx_frames.clear();
+            return;
+        };
+        self.rx_storing = true;
+        let storage = self.rx_queue_storage.take();
+        self.request_memory_read(
+            ctx,
+            p_rx_q,
+            storage,
+            QUEUE_SIZE,
+            RfcMemoryBypassReceiver::RxQueue,
+        );
+    }
+
+    /// The RX queue of the running operation.
+    fn rx_queue_pointer(&self) -> Option<Address> {
+        match self.running[Level::Background] {
+            Some(RunningOperation {
+                parameters: RadioOperationCommandParameters::IeeeRx { p_rx_q, .. },
+                ..
+            }) => Some(p_rx_q),
+            _ => self.ble.as_ref().and_then(BleOperation::rx_queue),
+        }
+    }
+
+    fn on_rx_queue(&mut self, ctx: &mut Context, queue: DataQueue) {
+        if self.rx_queue_pointer().is_none() || self.rx_frames.is_empty() {
+            self.rx_storing = false;
+            return;
+        }
+        if queue.is_empty() {
+            self.on_rx_buffer_full(ctx);
+            return;
+        }
+        self.rx_queue = Some(queue);
+        self.read_rx_entry(ctx, queue.p_curr_entry);
+    }
+
+    fn read_rx_entry(&mut self, ctx: &mut Context, entry_address: Address) {
+        if !is_memory(entry_address) {
+            warn!("The RX queue points to {entry_address:?}, which is not memory.");
+            self.end_rx_with_error(ctx);
+            return;
+        }
+        let storage = self.rx_entry_header_storage.take();
+        self.request_memory_read(
+            ctx,
+            entry_address,
+            storage,
+            ENTRY_HEADER_SIZE,
+            RfcMemoryBypassReceiver::RxEntryHeader,
+        );
+    }
+
+    fn write_rx_contents(
+        &mut self,
+        ctx: &mut Context,
+        entry_address: Address,
+        entry_header: Option<DataEntryHeader>,
+    ) {
+        let (Some(p_rx_q), Some(queue), Some(frame)) = (
+            self.rx_queue_pointer(),
+            self.rx_queue,
+            self.rx_frames.front().cloned(),
+        ) else {
+            self.rx_storing = false;
+            return;
+        };
+        let Some(entry_header) = entry_header else {
+            warn!("The RX entry at {entry_address:?} has an invalid status.");
+            self.on_rx_buffer_full(ctx);
+            return;
+        };
+        let config = entry_header.config;
+        let (Some(length_size), false) = (
+            config.len_sz().bytes(),
+            config.r#type() == DataEntryType::Reserved,
+        ) else {
+            warn!("The RX entry at {entry_address:?} has unsupported configuration {config:?}");
+            self.end_rx_with_error(ctx);
+            return;
+        };
+        let element = match &frame {
+            RxFrame::Ieee(frame) => {
+                let Some(RunningOperation {
+                    parameters: RadioOperationCommandParameters::IeeeRx { rx_config, .. },
+                    ..
+                }) = self.running[Level::Background]
+                else {
+                    self.rx_storing = false;
+                    return;
+                };
+                let timestamp = self.rat_time(ctx);
+                rx_element(frame, rx_config, length_size, timestamp)
+            }
+            RxFrame::Ble { data, .. } => with_length(data.clone(), length_size),
+        };
+
+        if config.r#type() == DataEntryType::Multielement {
+            // The length covers `numElements` and `nextIndex` too.
+            let capacity = usize::from(entry_header.length).saturating_sub(4);
+            let next_index = usize::from(entry_header.next_index());
+            if !matches!(
+                entry_header.status,
+                DataEntryStatus::Pending | DataEntryStatus::Active
+            ) {
+                self.on_rx_buffer_full(ctx);
+            } else if next_index + element.len() > capacity {
+                if entry_header.num_elements() == 0 {
+                    self.on_rx_buffer_full(ctx);
+                    return;
+                }
+                // The entry is full, so the frame goes to the next one.
+                self.finish_rx_entry(ctx, p_rx_q, queue, entry_address, entry_header);
+                match self.rx_queue {
+                    Some(queue) if !queue.is_empty() => self.read_rx_entry(ctx, queue.p_curr_entry),
+                    _ => self.on_rx_buffer_full(ctx),
+                }
+            } else {
+                let data_offset = MULTI_ELEMENT_DATA_OFFSET
+                    + u32::try_from(next_index).expect("fits in the entry");
+                self.write_bytes(ctx, entry_address.offset(data_offset), &element);
+                let num_elements = u32::from(entry_header.num_elements()) + 1;
+                let next_index =
+                    u32::try_from(next_index + element.len()).expect("fits in the entry");
+                self.request_memory_write(
+                    ctx,
+                    entry_address.offset(8),
+                    (num_elements | (next_index << 16)).into(),
+                );
+                if entry_header.status == DataEntryStatus::Pending {
+                    self.request_memory_write(
+                        ctx,
+                        entry_address.offset(4),
+                        DataBus::Byte(DataEntryStatus::Active.into()),
+                    );
+                }
+                self.on_frame_stored(ctx, &frame);
+            }
+            return;
+        }
+
+        if entry_header.status != DataEntryStatus::Pending
+            || element.len() > usize::from(entry_header.length)
+        {
+            self.on_rx_buffer_full(ctx);
+            return;
+        }
+        let data_address = match config.r#type() {
+            DataEntryType::Pointer => entry_header.p_data(),
+            _ => entry_address.offset(GENERAL_ENTRY_DATA_OFFSET),
+        };
+        if !is_memory(data_address) {
+            warn!(
+                "The RX entry at {entry_address:?} points to {data_address:?}, which is not memory."
+            );
+            self.end_rx_with_error(ctx);
+            return;
+        }
+        self.write_bytes(ctx, data_address, &element);
+        self.finish_rx_entry(ctx, p_rx_q, queue, entry_address, entry_header);
+        self.on_frame_stored(ctx, &frame);
+    }
+
+    /// Marks the entry as finished and moves the queue to the next one.
+    fn finish_rx_entry(
+        &mut self,
+        ctx: &mut Context,
+        p_rx_q: Address,
+        queue: DataQueue,
+        entry_address: Address,
+        entry_header: DataEntryHeader,
+    ) {
+        self.request_memory_write(
+            ctx,
+            entry_address.offset(4),
+            DataBus::Byte(DataEntryStatus::Finished.into()),
+        );
+        let next_entry = if entry_address == queue.p_last_entry {
+            NULL
+        } else {
+            entry_header.p_next_entry
+        };
+        self.request_memory_write(ctx, p_rx_q, next_entry.to_const().into());
+        self.rx_queue = Some(DataQueue {
+            p_curr_entry: next_entry,
+            ..queue
+        });
+        self.raise_cpe_interrupt(ctx, Interrupt::RxEntryDone);
+    }
+
+    fn on_frame_stored(&mut self, ctx: &mut Context, frame: &RxFrame) {
+        let frame = match frame {
+            RxFrame::Ieee(frame) => frame,
+            RxFrame::Ble { report, .. } => {
+                let report = *report;
+                self.next_rx_frame(ctx);
+                if let Some(report) = report {
+                    self.with_ble(ctx, |ble, _| ble.on_rx_stored(report));
+                }
+                return;
+            }
+        };
+        let frame_type = frame.get(1).map(|fcf| fcf & IEEE_FRAME_TYPE_MASK);
+        self.rx_output.count_frame(frame_type);
+        if frame_type == Some(IEEE_FRAME_TYPE_BEACON) {
+            self.rx_output.beacon_time_stamp = self.rat_time(ctx);
+        }
+        self.last_rx_payload = Some(frame[1..].to_vec());
+        self.raise_cpe_interrupt(ctx, Interrupt::RxOk);
+        self.write_current_rx_output(ctx);
+        self.next_rx_frame(ctx);
+    }
+
+    fn on_rx_buffer_full(&mut self, ctx: &mut Context) {
+        warn!("There is no space for the received frame in the RX queue, dropping it.");
+        self.raise_cpe_interrupt(ctx, Interrupt::RxBufFull);
+        if let Some(RxFrame::Ble { report, .. }) = self.rx_frames.front() {
+            let report = *report;
+            self.next_rx_frame(ctx);
+            self.with_ble(ctx, |ble, _| ble.on_rx_buffer_full(report));
+            return;
+        }
+        self.rx_output.n_rx_buf_full = self.rx_output.n_rx_buf_full.saturating_add(1);
+        self.write_current_rx_output(ctx);
+        self.next_rx_frame(ctx);
+    }
+
+    fn next_rx_frame(&mut self, ctx: &mut Context) {
+        self.rx_frames.pop_front();
+        self.rx_storing = false;
+        self.store_next_frame(ctx);
+    }
+
+    fn end_rx_with_error(&mut self, ctx: &mut Context) {
+        self.rx_frames.clear();
+        self.rx_storing = false;
+        let status = if self.ble.is_some() {
+            CommandStatus::BleErrorPar
+        } else {
+            CommandStatus::IeeeErrorPar
+        };
+        self.finish_operation(ctx, Level::Background, status, Continuation::Condition);
+    }
+
+    fn write_current_rx_output(&self, ctx: &mut Context) {
+        if let Some(RunningOperation {
+            parameters: RadioOperationCommandParameters::IeeeRx { p_output, .. },
+            ..
+        }) = self.running[Level::Background]
+        {
+            self.write_rx_output(ctx, p_output);
+        }
+    }
+
+    fn write_rx_output(&self, ctx: &mut Context, p_output: Address) {
+        if p_output != NULL && is_memory(p_output) {
+            self.write_bytes(ctx, p_output, &self.rx_output.to_bytes());
+        }
+    }
+}
+
+// Running the BLE operations.
+#[component_impl(rfc)]
+impl RFCComponent {
+    fn ble_modem(&self) -> Option<&dyn BleModemInterface> {
+        self.modem_impl.as_ref().and_then(|modem| modem.as_ble())
+    }
+
+    fn send_ble_modem_op(&self, op: BleModemOp) {
+        if let Some(modem) = self.ble_modem() {
+            modem.send_ble_op(op);
+        }
+    }
+
+    /// Tells the BLE operation about an event, and does what it asks for.
+    fn with_ble(&mut self, ctx: &mut Context, f: impl FnOnce(&mut BleOperation, u32)) {
+        let now = self.rat_time(ctx);
+        let Some(ble) = &mut self.ble else {
+            return;
+        };
+        f(ble, now);
+        self.run_ble_actions(ctx);
+    }
+
+    fn run_ble_actions(&mut self, ctx: &mut Context) {
+        let level = Level::Background;
+        let Some(ble) = &mut self.ble else {
+            return;
+        };
+        let actions = ble.take_actions();
+        let channel = ble.channel();
+        let (access_address, crc_init) = ble.link();
+        let now = ctx.event_queue().get_current_time();
+        for action in actions {
+            // The operation may end with an error on the way.
+            if self.ble.is_none() {
+                break;
+            }
+            match action {
+                BleAction::Read { address, size } => {
+                    self.set_state(level, OperationState::FetchingBle);
+                    let storage = self.ble_storage.take();
+                    self.request_memory_read(
+                        ctx,
+                        address,
+                        storage,
+                        size,
+                        RfcMemoryBypassReceiver::BleData,
+                    );
+                }
+                BleAction::Write { address, data } => self.write_bytes(ctx, address, &data),
+                BleAction::Triggers { end, timeout } => self.set_ble_triggers(ctx, end, timeout),
+                BleAction::Transmit { pdu } => {
+                    let until = if self.ble_modem().is_some() {
+                        self.send_ble_modem_op(BleModemOp::Transmit {
+                            channel,
+                            access_address,
+                            crc_init,
+                            pdu,
+                        });
+                        None
+                    } else {
+                        debug!("There's no BLE modem, the packet {pdu:02x?} is not sent anywhere.");
+                        Some(now + RadioTimer::ticks_duration(air_time_ticks(pdu.len())))
+                    };
+                    self.set_state(level, OperationState::Transmitting { until });
+                }
+                BleAction::Listen { timeout } => {
+                    self.send_ble_modem_op(BleModemOp::Listen {
+                        channel,
+                        access_address,
+                        crc_init,
+                    });
+                    let until = timeout.map(|ticks| now + RadioTimer::ticks_duration(ticks));
+                    self.set_state(level, OperationState::Listening { until });
+                }
+                BleAction::Wait { ticks } => {
+                    let until = now + RadioTimer::ticks_duration(ticks);
+                    self.set_state(level, OperationState::Waiting { until });
                 }
+                BleAction::StoreRx { data, report } => {
+                    self.rx_frames.push_back(RxFrame::Ble { data, report });
+                    self.store_next_frame(ctx);
+                }
+                BleAction::Interrupt(interrupt) => self.raise_cpe_interrupt(ctx, interrupt),
             }
-            _ => false,
         }
+        self.update_radio_activity(ctx);
+        self.try_finish_ble(ctx);
     }
 
-    fn try_run_task(&mut self, ctx: &mut Context) -> bool {
-        let Some(PendingCommand {
-            command,
-            pointer,
-            received_at_cycle,
-        }) = *self.pending_command
-        else {
-            return false;
+    /// Sets the end trigger and the timeout trigger from the parameters of the BLE operation.
+    fn set_ble_triggers(
+        &mut self,
+        ctx: &mut Context,
+        end: (Trigger, u32),
+        timeout: Option<(Trigger, u32)>,
+    ) {
+        let level = Level::Background;
+        let Some(op) = self.running[level] else {
+            return;
         };
-
-        let cycles_to_done_for_command = cycles_to_done(&command);
-        let (cycles_since_received, _) = self.cycles_clock.overflowing_sub(received_at_cycle);
-        if cycles_since_received < cycles_to_done_for_command {
-            if cycles_since_received % 100 == 0 {
-                // trace!("Command {command:?} not ready to ba handled now. Elapsed {cycles_since_received} since radio received command, but it takes {cycles_to_done_for_command} to handle this command.");
-            }
-            return false;
-        } else if cycles_since_received > cycles_to_done_for_command {
-            panic!(
-                "Should run command {command:?} after {cycles_to_done_for_command} cycles, but {cycles_since_received} cycles passed since received."
+        let now = self.rat_time(ctx);
+        // The triggers relative to the start refer to the start of the operation.
+        let start = self.chain_times[level].prev_start;
+        let time = |(trigger, time): (Trigger, u32)| {
+            self.trigger_time(level, trigger, time, op.submitted_at, Some(start))
+        };
+        let (Some(end_time), Some(timeout_time)) = (
+            time(end),
+            timeout.map_or(Some(None), |timeout| time(timeout).map(Some)),
+        ) else {
+            self.finish_operation(
+                ctx,
+                level,
+                CommandStatus::BleErrorPar,
+                Continuation::Condition,
             );
+            return;
+        };
+        let at = |time| match time {
+            TriggerTime::Now => Some(now),
+            TriggerTime::At(time) => Some(time),
+            TriggerTime::Never => None,
+        };
+        if let Some(op) = &mut self.running[level] {
+            op.end_trigger = Some(end.0);
+            op.end_time = at(end_time);
+            op.timeout_time = timeout_time.and_then(at);
         }
+    }
 
-        let command_status = self.run_command(ctx, command, pointer);
-        match command_status {
-            CommandStatus::DoneOk | CommandStatus::IeeeDoneOk => {
-                self.pending_command.set_next(None);
-            }
-            _ => {}
+    /// Finishes the BLE operation, which ended, once its packets are stored.
+    fn try_finish_ble(&mut self, ctx: &mut Context) {
+        let Some(status) = self.ble.as_ref().and_then(BleOperation::status) else {
+            return;
+        };
+        if self.rx_storing || !self.rx_frames.is_empty() {
+            return;
         }
-        true
+        self.finish_operation(ctx, Level::Background, status, Continuation::Condition);
     }
 
-    pub(crate) fn tock(&mut self, ctx: &mut Context) {
-        BusDriver::tock(self, ctx);
+    /// Writes back the parameters and the output of the BLE operation, which finished.
+    fn close_ble(&mut self, ctx: &mut Context) {
+        let Some(mut ble) = self.ble.take() else {
+            return;
+        };
+        ble.close();
+        for action in ble.take_actions() {
+            if let BleAction::Write { address, data } = action {
+                self.write_bytes(ctx, address, &data);
+            }
+        }
+        self.send_ble_modem_op(BleModemOp::Idle);
     }
+}
 
-    fn write_rx_contents(
+// Running the immediate and direct commands.
+#[component_impl(rfc)]
+impl RFCComponent {
+    /// Returns the result, or `None` if the command finishes later.
+    fn run_immediate(
         &mut self,
         ctx: &mut Context,
-        entry_address: Address,
-        entry_header: RxEntryHeader,
-    ) {
-        let Some(mut buffer) = self.rx_buffer_to_write.take() else {
-            todo!("requested to write rx contents when there isn't any pending rx buffer")
-        };
-        let Some((
-            RadioOperationCommandParameters::IeeeRx {
-                p_output,
-                rx_config,
-                p_rx_q,
-                ..
+        parameters: ImmediateCommandParameters,
+        pointer: Option<Address>,
+    ) -> Option<CommandStatusHandle> {
+        let result = match parameters {
+            ImmediateCommandParameters::Abort {} | ImmediateCommandParameters::Stop {} => {
+                let abort = matches!(parameters, ImmediateCommandParameters::Abort {});
+                self.stop_level(ctx, Level::Foreground, |op| op.stop_status(abort));
+                self.stop_level(ctx, Level::Background, |op| op.stop_status(abort));
+                ResultByte::Done
+            }
+            ImmediateCommandParameters::GetRssi {} => {
+                if !self.runs_background_rx() {
+                    return Some(ResultByte::ContextError.into());
+                }
+                let rssi = self.last_rssi.to_le_bytes()[0];
+                return Some(CommandStatusHandle::from(ResultByte::Done).with_return_byte_2(rssi));
+            }
+            ImmediateCommandParameters::UpdateRadioSetup { .. } => {
+                if self.radio_mode.is_some() {
+                    ResultByte::Done
+                } else {
+                    ResultByte::ContextError
+                }
+            }
+            ImmediateCommandParameters::Trigger { trigger_no } => {
+                if self.fire_command_trigger(ctx, trigger_no) {
+                    ResultByte::Done
+                } else {
+                    ResultByte::ContextError
+                }
+            }
+            ImmediateCommandParameters::GetFwInfo { .. } => {
+                let Some(pointer) = pointer else {
+                    return Some(ResultByte::ParError.into());
+                };
+                // TODO: the version, the start offset and the free RAM are not known.
+                let fw_info = [0u16, 0, 0, AVAILABLE_CHANNELS];
+                for (idx, field) in (0u32..).zip(fw_info) {
+                    self.request_memory_write(ctx, pointer.offset(2 + 2 * idx), field.into());
+                }
+                ResultByte::Done
+            }
+            ImmediateCommandParameters::StartRat {} => {
+                if self.rat.is_running() {
+                    ResultByte::ContextError
+                } else {
+                    self.rat.start(ctx.event_queue().get_current_time());
+                    ResultByte::Done
+                }
+            }
+            ImmediateCommandParameters::Ping {}
+            | ImmediateCommandParameters::SetTxPower { .. }
+            | ImmediateCommandParameters::BusRequest { .. }
+            | ImmediateCommandParameters::MagicalSecretSauce {} => ResultByte::Done,
+            ImmediateCommandParameters::ReadRfreg { address, .. } => {
+                // TODO: the registers of the radio are not modelled.
+                warn!("CMD_READ_RFREG of {address:#x} is not supported, reading 0.");
+                if let Some(pointer) = pointer {
+                    self.request_memory_write(ctx, pointer.offset(4), 0u32.into());
+                }
+                ResultByte::Done
+            }
+            ImmediateCommandParameters::SetRatCmp {
+                rat_ch,
+                compare_time,
+            } => self.with_rat_channel(rat_ch, |rat, idx| rat.set_compare(idx, compare_time)),
+            ImmediateCommandParameters::SetRatCpt { config } => {
+                let config = RatCaptureConfig::from_bytes(config.to_le_bytes());
+                self.with_rat_channel(config.rat_ch(), |rat, idx| {
+                    rat.set_capture(idx, config.b_repeated());
+                })
+            }
+            ImmediateCommandParameters::DisableRatCh { rat_ch } => {
+                self.with_rat_channel(rat_ch, RadioTimer::disable)
+            }
+            ImmediateCommandParameters::ArmRatCh { rat_ch } => {
+                self.with_rat_channel(rat_ch, RadioTimer::arm)
+            }
+            ImmediateCommandParameters::DisarmRatCh { rat_ch } => {
+                self.with_rat_channel(rat_ch, RadioTimer::disarm)
+            }
+            ImmediateCommandParameters::SetRatOutput { config } => {
+                let rat_ch = RatOutputConfig::from_bytes(config.to_le_bytes()).rat_ch();
+                self.with_rat_channel(rat_ch, |rat, _| rat.set_output(config))
+            }
+            ImmediateCommandParameters::UpdateFs { .. }
+            | ImmediateCommandParameters::ModifyFs { .. } => {
+                if self.fs_on {
+                    ResultByte::Done
+                } else {
+                    ResultByte::ContextError
+                }
+            }
+            ImmediateCommandParameters::AddDataEntry { p_queue, p_entry } => {
+                let entry = Address::from_const(p_entry);
+                if !is_memory(entry) {
+                    return Some(ResultByte::ParError.into());
+                }
+                let command = QueueCommand::AddEntry { entry };
+                return self.start_queue_operation(ctx, command, pointer, p_queue);
+            }
+            ImmediateCommandParameters::RemoveDataEntry { p_queue, .. } => {
+                return self.start_queue_operation(
+                    ctx,
+                    QueueCommand::RemoveEntry,
+                    pointer,
+                    p_queue,
+                );
+            }
+            ImmediateCommandParameters::FlushQueue { p_queue, .. } => {
+                return self.start_queue_operation(ctx, QueueCommand::Flush, pointer, p_queue);
+            }
+            ImmediateCommandParameters::ClearRx { p_queue } => {
+                return self.start_queue_operation(ctx, QueueCommand::ClearRx, pointer, p_queue);
+            }
+            ImmediateCommandParameters::RemovePendingEntries { p_queue, .. } => {
+                let command = QueueCommand::RemovePendingEntries;
+                return self.start_queue_operation(ctx, command, pointer, p_queue);
+            }
+            ImmediateCommandParameters::IeeeModCca {
+                new_cca_opt,
+                new_cca_rssi_thr,
+            } => match &mut self.running[Level::Background] {
+                Some(RunningOperation {
+                    parameters:
+                        RadioOperationCommandParameters::IeeeRx {
+                            cca_opt,
+                            cca_rssi_thr,
+                            ..
+                        },
+                    ..
+                }) => {
+                    *cca_opt = new_cca_opt;
+                    *cca_rssi_thr = new_cca_rssi_thr;
+                    ResultByte::Done
+                }
+                _ => ResultByte::ContextError,
+            },
+            ImmediateCommandParameters::IeeeModFilt {
+                new_frame_filt_opt,
+                new_frame_types,
+            } => match &mut self.running[Level::Background] {
+                Some(RunningOperation {
+                    parameters:
+                        RadioOperationCommandParameters::IeeeRx {
+                            frame_filt_opt,
+                            frame_types,
+                            ..
+                        },
+                    ..
+                }) => {
+                    *frame_filt_opt =
+                        FrameFilteringConfiguration::from_bytes(new_frame_filt_opt.to_le_bytes());
+                    *frame_types = new_frame_types;
+                    let auto_ack = frame_filt_opt.auto_ack_en();
+                    self.send_modem_op(ModemOp::SetAutoAck(auto_ack));
+                    ResultByte::Done
+                }
+                _ => ResultByte::ContextError,
+            },
+            ImmediateCommandParameters::IeeeModSrcMatch { .. } => {
+                if self.is_receiving() {
+                    // TODO: source matching is not modelled.
+                    warn!("Ignoring CMD_IEEE_MOD_SRC_MATCH, as source matching is not supported.");
+                    ResultByte::Done
+                } else {
+                    ResultByte::ContextError
+                }
+            }
+            ImmediateCommandParameters::IeeeAbortFg {}
+            | ImmediateCommandParameters::IeeeStopFg {} => {
+                let abort = matches!(parameters, ImmediateCommandParameters::IeeeAbortFg {});
+                if self.stop_level(ctx, Level::Foreground, |op| op.stop_status(abort)) {
+                    ResultByte::Done
+                } else {
+                    ResultByte::ContextError
+                }
+            }
+            ImmediateCommandParameters::BleAdvPayload {
+                payload_type,
+                new_len,
+                p_new_data,
+                p_params,
+            } => {
+                // The advertiser reads its data when it starts, so the change applies to the next one.
+                let p_params = Address::from_const(p_params);
+                if !is_memory(p_params) {
+                    return Some(ResultByte::ParError.into());
+                }
+                let (len_offset, data_offset) = match payload_type {
+                    0 => (6, 8),
+                    _ => (7, 12),
+                };
+                self.request_memory_write(ctx, p_params.offset(len_offset), DataBus::Byte(new_len));
+                self.request_memory_write(ctx, p_params.offset(data_offset), p_new_data.into());
+                ResultByte::Done
+            }
+            ImmediateCommandParameters::IeeeCcaReq(_) => match pointer {
+                Some(pointer) if self.runs_background_rx() => {
+                    self.request_cca(CcaUser::Request(pointer));
+                    return None;
+                }
+                _ => ResultByte::ContextError,
             },
-            cmd_addr,
-        )) = *self.background_command
-        else {
-            todo!("requested to write rx contents when background command isn't rx")
         };
-        assert!(matches!(entry_header.status, RxEntryStatus::Pending));
-        self.request_memory_write(
-            ctx,
-            p_rx_q.offset(0),
-            entry_header.p_next_entry.to_const().into(),
-        );
-        // self.request_memory_write(ctx, entry_address.offset(2), DataBus::Byte(RxEntryStatus::Busy.into())); // TODO: to w tym miejscu by było trochę na prozno
-        let mut offset = entry_address.offset(8);
-
-        // Modify the message to match rx_config
-        if rx_config.b_include_phy_hdr() {
-            unimplemented!("requested phy hdr in rx config")
-        }
-        if !rx_config.b_include_crc() {
-            // CRC is included in passed messages, so it must be removed
-            _ = buffer.pop();
-            _ = buffer.pop();
-        }
-        if rx_config.b_append_rssi() {
-            buffer.push(0); // TODO
-        }
-        if rx_config.b_append_corr_crc() {
-            buffer.push(0); // TODO
-        }
-        if rx_config.b_append_src_ind() {
-            unimplemented!("requested source index in rx config")
-        }
-        if rx_config.b_append_timestamp() {
-            buffer.push(0);
-            buffer.push(0);
-            buffer.push(0);
-            buffer.push(0);
-        }
+        Some(result.into())
+    }
 
-        let mut data = &buffer[1..];
-        let length = data.len();
-        match entry_header.config.len_sz() {
-            RxEntryLengthSize::NoIndicator => {}
-            RxEntryLengthSize::OneByte => {
-                self.request_memory_write(ctx, offset, DataBus::Byte(length.try_into().unwrap()));
-                offset = offset.offset(1);
+    fn with_rat_channel(
+        &mut self,
+        rat_ch: u8,
+        f: impl FnOnce(&mut RadioTimer, usize),
+    ) -> ResultByte {
+        match RadioTimer::available_channel(rat_ch) {
+            Some(idx) => {
+                f(&mut self.rat, idx);
+                ResultByte::Done
             }
-            RxEntryLengthSize::TwoByte => {
-                self.request_memory_write(ctx, offset, DataBus::Short(length.try_into().unwrap()));
-                offset = offset.offset(2);
+            None => {
+                warn!("RAT channel {rat_ch} is not available to the system CPU.");
+                ResultByte::ParError
             }
-            RxEntryLengthSize::Reserved => todo!("{entry_header:?}"),
-        }
-        while data.len() > 0 {
-            let chunk_size = data.len().min(4);
-            let chunk_size = if chunk_size.is_power_of_two() {
-                chunk_size
-            } else {
-                chunk_size.next_power_of_two() / 2
-            };
-            self.request_memory_write(ctx, offset, DataBus::from_slice(&data[0..chunk_size]));
-            offset = offset.offset(chunk_size.try_into().unwrap());
-            data = &data[chunk_size..];
         }
-        self.request_memory_write(
-            ctx,
-            entry_address.offset(4),
-            DataBus::Byte(RxEntryStatus::Finished.into()),
-        );
-        // self.write_back_status_field(ctx, Some(cmd_addr), CommandStatus::DoneOk);
-        // self.raise_cpe_interrupt_if_enabled(ctx, Interrupt::CommandDone);
-        // self.raise_cpe_interrupt_if_enabled(ctx, Interrupt::RxOk);
-        self.raise_cpe_interrupt_if_enabled(ctx, Interrupt::RxEntryDone);
     }
 
-    #[handler]
-    pub(crate) fn on_new_ahb_slave_input(
+    fn start_queue_operation(
         &mut self,
         ctx: &mut Context,
-        msg: MasterToSlaveWires<<RFCComponent as AHBPortConfig>::Data>,
-    ) {
-        <Self as AHBSlavePortInput>::on_ahb_input(self, ctx, msg);
-    }
-
-    pub(crate) fn set_interface(&mut self, interface: Option<ModemImpl>) {
-        self.modem_impl = interface;
+        command: QueueCommand,
+        pointer: Option<Address>,
+        p_queue: u32,
+    ) -> Option<CommandStatusHandle> {
+        let queue = Address::from_const(p_queue);
+        let Some(pointer) = pointer.filter(|_| is_memory(queue)) else {
+            return Some(ResultByte::ParError.into());
+        };
+        let in_use = self.rx_queue_pointer() == Some(queue)
+            || self
+                .ble
+                .as_ref()
+                .is_some_and(|ble| ble.tx_queue() == Some(queue));
+        self.queue_operation = Some(QueueOperation::new(command, pointer, queue, in_use));
+        let storage = self.queue_storage.take();
+        self.request_memory_read(
+            ctx,
+            queue,
+            storage,
+            QUEUE_SIZE,
+            RfcMemoryBypassReceiver::DataQueue,
+        );
+        None
     }
 
-    pub(crate) fn notify_radio_wakeup(&mut self) {
-        self.need_wakeup = true;
+    fn on_queue_step(&mut self, ctx: &mut Context, operation: QueueOperation, step: QueueStep) {
+        match step {
+            QueueStep::ReadEntry(entry) if is_memory(entry) => {
+                self.queue_operation = Some(operation);
+                let storage = self.queue_storage.take();
+                self.request_memory_read(
+                    ctx,
+                    entry,
+                    storage,
+                    ENTRY_HEADER_SIZE,
+                    RfcMemoryBypassReceiver::DataEntry,
+                );
+            }
+            QueueStep::ReadEntry(entry) => {
+                warn!(
+                    "The queue at {:?} points to {entry:?}, which is not memory.",
+                    operation.queue_pointer()
+                );
+                self.ack_command_with_status(ctx, ResultByte::QueueError);
+            }
+            QueueStep::Done(result) => self.ack_command_with_status(ctx, result),
+        }
     }
 }
 
@@ -862,7 +2653,7 @@ impl RFCComponent {
     ) {
         trace!("requesting memory bypass {:?}", start_address);
 
-        match storage {
+        let data = match storage {
             Some(MemoryBypassStorage { data, consumed, .. }) => {
                 if !consumed {
                     warn!(
@@ -870,23 +2661,27 @@ impl RFCComponent {
                         subreceiver
                     );
                 }
-
-                let receiver = MemoryBypassReceiver::Rfc(subreceiver);
-                address_match_range!(start_address,
-                    soc::FLASH::ADDR_SPACE => FlashProxy.request_memory_read_bypass(ctx, start_address, data, size, receiver),
-                    soc::GPRAM::ADDR_SPACE => GPRAMProxy.request_memory_read_bypass(ctx, start_address, data, size, receiver),
-                    soc::SRAM::ADDR_SPACE => SRAMProxy.request_memory_read_bypass(ctx, start_address, data, size, receiver),
-                    soc::BROM::ADDR_SPACE => ROMProxy.request_memory_read_bypass(ctx, start_address, data, size, receiver),
-                    _ => todo!("attempting to read memory from uhhh not memory? {start_address:?}"),
-                );
+                data
             }
             None => {
-                panic!(
+                warn!(
                     "There is read requested already for this receiver: {:?}.",
                     subreceiver
-                )
+                );
+                Vec::with_capacity(size)
             }
-        }
+        };
+        let receiver = MemoryBypassReceiver::Rfc(subreceiver);
+        address_match_range!(start_address,
+            soc::FLASH::ADDR_SPACE => { FlashProxy.request_memory_read_bypass(ctx, start_address, data, size, receiver); },
+            soc::GPRAM::ADDR_SPACE => { GPRAMProxy.request_memory_read_bypass(ctx, start_address, data, size, receiver); },
+            soc::SRAM::ADDR_SPACE => { SRAMProxy.request_memory_read_bypass(ctx, start_address, data, size, receiver); },
+            soc::BROM::ADDR_SPACE => { ROMProxy.request_memory_read_bypass(ctx, start_address, data, size, receiver); },
+            _ => {
+                warn!("Attempting to read memory from not memory {start_address:?}, reading zeros.");
+                RfcProxy.receive_memory_bypass(ctx, vec![0u8; size], start_address, subreceiver);
+            },
+        );
     }
 
     fn request_radio_command_read(&mut self, ctx: &mut Context, start_address: Address) {
@@ -923,14 +2718,29 @@ impl RFCComponent {
         );
 
         address_match_range!(start_address,
-            soc::FLASH::ADDR_SPACE => FlashProxy.request_memory_write_bypass(ctx, start_address, data),
-            soc::GPRAM::ADDR_SPACE => GPRAMProxy.request_memory_write_bypass(ctx, start_address, data),
-            soc::SRAM::ADDR_SPACE => SRAMProxy.request_memory_write_bypass(ctx, start_address, data),
-            soc::BROM::ADDR_SPACE => ROMProxy.request_memory_write_bypass(ctx, start_address, data),
-            _ => todo!("attempting to write memory to uhhh not memory? {start_address:?}"),
+            soc::FLASH::ADDR_SPACE => { FlashProxy.request_memory_write_bypass(ctx, start_address, data); },
+            soc::GPRAM::ADDR_SPACE => { GPRAMProxy.request_memory_write_bypass(ctx, start_address, data); },
+            soc::SRAM::ADDR_SPACE => { SRAMProxy.request_memory_write_bypass(ctx, start_address, data); },
+            soc::BROM::ADDR_SPACE => { ROMProxy.request_memory_write_bypass(ctx, start_address, data); },
+            _ => warn!("Attempting to write memory to not memory {start_address:?}, dropping {data:?}."),
         );
     }
 
+    fn write_bytes(&self, ctx: &mut Context, start_address: Address, mut data: &[u8]) {
+        let mut offset = start_address;
+        while !data.is_empty() {
+            let chunk_size = data.len().min(4);
+            let chunk_size = if chunk_size.is_power_of_two() {
+                chunk_size
+            } else {
+                chunk_size.next_power_of_two() / 2
+            };
+            self.request_memory_write(ctx, offset, DataBus::from_slice(&data[0..chunk_size]));
+            offset = offset.offset(u32::try_from(chunk_size).expect("at most 4"));
+            data = &data[chunk_size..];
+        }
+    }
+
     #[handler]
     pub(crate) fn receive_memory_bypass(
         &mut self,
@@ -950,113 +2760,269 @@ impl RFCComponent {
                 self.command_storage = Some(storage);
             }
             RfcMemoryBypassReceiver::Payload => {
+                storage.consumed = true;
+                let payload = std::mem::take(&mut storage.data);
+                self.transmit(ctx, &payload);
+                storage.data = payload;
                 self.payload_storage = Some(storage);
             }
             RfcMemoryBypassReceiver::RxQueue => {
-                // quick validation
-                assert_eq!(storage.data.len(), 8);
-                assert_eq!(
-                    &storage.data[4..8],
-                    &[0u8; 4],
-                    "we only support circular data entry queues for now"
-                );
-                assert_ne!(
-                    &storage.data[0..4],
-                    &[0u8; 4],
-                    "the data entry queue must not be empty"
-                );
-                let entry_address =
-                    Address::from(u32::from_le_bytes(storage.data[0..4].try_into().unwrap()));
+                let queue = DataQueue::from_bytes(&storage.data);
                 storage.consumed = true;
                 self.rx_queue_storage = Some(storage);
-                let storage = self.rx_entry_header_storage.take();
-                self.request_memory_read(
-                    ctx,
-                    entry_address,
-                    storage,
-                    8,
-                    RfcMemoryBypassReceiver::RxEntryHeader,
-                );
+                self.on_rx_queue(ctx, queue);
             }
             RfcMemoryBypassReceiver::RxEntryHeader => {
-                let data: [u8; 8] = storage.data[0..8].try_into().unwrap();
-                let entry_header = RxEntryHeader::from(data);
-                let entry_address = storage.address;
+                let entry_header = DataEntryHeader::from_bytes(&storage.data);
                 storage.consumed = true;
-                // dbg!(entry_address, entry_header);
                 self.rx_entry_header_storage = Some(storage);
-                self.write_rx_contents(ctx, entry_address, entry_header);
+                self.write_rx_contents(ctx, address, entry_header);
+            }
+            RfcMemoryBypassReceiver::DataQueue | RfcMemoryBypassReceiver::DataEntry => {
+                let Some(mut operation) = self.queue_operation.take() else {
+                    warn!("Received {receiver:?}, which no command waits for.");
+                    self.queue_storage = Some(storage);
+                    return;
+                };
+                let mut writes = Vec::new();
+                let step = if matches!(receiver, RfcMemoryBypassReceiver::DataQueue) {
+                    operation.on_queue(DataQueue::from_bytes(&storage.data), &mut writes)
+                } else {
+                    let entry_header = DataEntryHeader::from_bytes(&storage.data);
+                    operation.on_entry(address, entry_header, &mut writes)
+                };
+                storage.consumed = true;
+                self.queue_storage = Some(storage);
+                for (address, data) in writes {
+                    self.request_memory_write(ctx, address, data);
+                }
+                self.on_queue_step(ctx, operation, step);
+            }
+            RfcMemoryBypassReceiver::BleData => {
+                let data = storage.data.clone();
+                storage.consumed = true;
+                // The reads of the operations differ in size, so the storage keeps the biggest.
+                storage.data.reserve(BLE_MAX_READ_SIZE);
+                self.ble_storage = Some(storage);
+                if matches!(
+                    self.running[Level::Background],
+                    Some(RunningOperation {
+                        state: OperationState::FetchingBle,
+                        ..
+                    })
+                ) {
+                    self.set_state(Level::Background, OperationState::Running);
+                }
+                self.with_ble(ctx, |ble, _| ble.on_read(address, &data));
+            }
+            RfcMemoryBypassReceiver::PatternValue => {
+                let value = u32::from_le_bytes(
+                    storage.data[..PATTERN_VALUE_SIZE]
+                        .try_into()
+                        .expect("4 bytes"),
+                );
+                storage.consumed = true;
+                self.value_storage = Some(storage);
+                let level = [Level::Background, Level::Foreground]
+                    .into_iter()
+                    .find(|&level| {
+                        matches!(
+                            self.running[level],
+                            Some(RunningOperation {
+                                state: OperationState::FetchingValue,
+                                ..
+                            })
+                        )
+                    });
+                match level {
+                    Some(level) => self.check_pattern(ctx, level, value),
+                    None => warn!("Received a value, which no operation waits for."),
+                }
             }
         }
     }
 }
 
-/// [TI-TRM-I] 23.3.2.7.2 Data Entry
-/// [TI-TRM-I] Table 23-10. General Data Entry Structure
-#[derive(Clone, Copy, Debug)]
-struct RxEntryHeader {
-    p_next_entry: Address,
-    status: RxEntryStatus,
-    config: RxEntryConfig,
-    length: u16,
+/// Whether the CPE may access the address through the memory bypass.
+fn is_memory(address: Address) -> bool {
+    [
+        soc::FLASH::ADDR_SPACE,
+        soc::GPRAM::ADDR_SPACE,
+        soc::SRAM::ADDR_SPACE,
+        soc::BROM::ADDR_SPACE,
+    ]
+    .iter()
+    .any(|space| space.contains(&address))
 }
 
-impl From<[u8; 8]> for RxEntryHeader {
-    fn from(value: [u8; 8]) -> Self {
-        let p_next_entry = Address::from(u32::from_le_bytes(value[0..4].try_into().unwrap()));
-        let status = RxEntryStatus::try_from_primitive(value[4]).unwrap();
-        let config = RxEntryConfig::from_bytes(value[5..6].try_into().unwrap());
-        let length = u16::from_le_bytes(value[6..8].try_into().unwrap());
-        Self {
-            p_next_entry,
-            status,
-            config,
-            length,
-        }
+fn is_command_pointer(address: Address) -> bool {
+    is_memory(address) && address.is_aligned_to_4_bytes()
+}
+
+/// The end trigger and the end time of the operations, which have them.
+fn end_trigger(parameters: &RadioOperationCommandParameters) -> Option<(Trigger, u32)> {
+    let (RadioOperationCommandParameters::RxTest {
+        end_trigger,
+        end_time,
+        ..
     }
+    | RadioOperationCommandParameters::TxTest {
+        end_trigger,
+        end_time,
+        ..
+    }
+    | RadioOperationCommandParameters::IeeeRx {
+        end_trigger,
+        end_time,
+        ..
+    }
+    | RadioOperationCommandParameters::IeeeEdScan {
+        end_trigger,
+        end_time,
+        ..
+    }
+    | RadioOperationCommandParameters::IeeeCsma {
+        end_trigger,
+        end_time,
+        ..
+    }
+    | RadioOperationCommandParameters::IeeeRxAck {
+        end_trigger,
+        end_time,
+        ..
+    }) = *parameters
+    else {
+        return None;
+    };
+    Some((Trigger::from_bytes([end_trigger]), end_time))
 }
 
-/// [TI-TRM-I] 23.3.2.7.2 Data Entry
-#[derive(Clone, Copy, Debug, TryFromPrimitive, IntoPrimitive)]
-#[repr(u8)]
-enum RxEntryStatus {
-    Pending = 0,
-    Active = 1,
-    Busy = 2,
-    Finished = 3,
+/// The next operation and the operations to skip from it, according to the condition.
+fn next_operation(op: &RunningOperation, result: CommandResult) -> Option<(Address, u8)> {
+    let condition = op.preamble.condition;
+    let rule = ConditionRule::try_from(condition.rule()).ok()?;
+    let skip = match (rule, result) {
+        (_, CommandResult::Abort)
+        | (ConditionRule::Never, _)
+        | (ConditionRule::StopOnFalse, CommandResult::False)
+        | (ConditionRule::StopOnTrue, CommandResult::True) => return None,
+        (ConditionRule::SkipOnFalse, CommandResult::False)
+        | (ConditionRule::SkipOnTrue, CommandResult::True) => true,
+        _ => false,
+    };
+    match (skip, condition.n_skip()) {
+        (false, _) => Some((op.preamble.p_next_op, 0)),
+        // Run the same operation again.
+        (true, 0) => Some((op.pointer, 0)),
+        (true, n_skip) => Some((op.preamble.p_next_op, n_skip - 1)),
+    }
 }
 
-#[bitfield]
-#[derive(Clone, Copy, Debug)]
-struct RxEntryConfig {
-    r#type: RxEntryType,
-    len_sz: RxEntryLengthSize,
-    irq_intv: B4,
+/// The parameters of a direct command, which are in CMDR.
+fn direct_parameters(
+    id: CommandId,
+    param: u8,
+    extension: u8,
+) -> Option<ImmediateCommandParameters> {
+    // The 16-bit parameters take the lower half of CMDR, without its two lowest bits.
+    let wide_param = u16::from_le_bytes([extension << 2, param]);
+    Some(match id {
+        CommandId::Abort => ImmediateCommandParameters::Abort {},
+        CommandId::Stop => ImmediateCommandParameters::Stop {},
+        CommandId::GetRssi => ImmediateCommandParameters::GetRssi {},
+        CommandId::Trigger => ImmediateCommandParameters::Trigger { trigger_no: param },
+        CommandId::StartRat => ImmediateCommandParameters::StartRat {},
+        CommandId::Ping => ImmediateCommandParameters::Ping {},
+        CommandId::ReadRfreg => ImmediateCommandParameters::ReadRfreg {
+            address: wide_param,
+            value: 0,
+        },
+        CommandId::SetRatCpt => ImmediateCommandParameters::SetRatCpt { config: wide_param },
+        CommandId::DisableRatCh => ImmediateCommandParameters::DisableRatCh { rat_ch: param },
+        CommandId::SetRatOutput => ImmediateCommandParameters::SetRatOutput { config: wide_param },
+        CommandId::ArmRatCh => ImmediateCommandParameters::ArmRatCh { rat_ch: param },
+        CommandId::DisarmRatCh => ImmediateCommandParameters::DisarmRatCh { rat_ch: param },
+        CommandId::BusRequest => ImmediateCommandParameters::BusRequest {
+            b_sys_bus_needed: param,
+        },
+        CommandId::IeeeAbortFg => ImmediateCommandParameters::IeeeAbortFg {},
+        CommandId::IeeeStopFg => ImmediateCommandParameters::IeeeStopFg {},
+        _ => return None,
+    })
 }
 
-#[derive(BitfieldSpecifier, Clone, Copy, Debug)]
-#[bits = 2]
-enum RxEntryType {
-    General = 0,
-    Multielement = 1,
-    Pointer = 2,
+/// Formats the received frame as an element of the RX queue, according to `rxConfig`.
+fn rx_element(frame: &[u8], rx_config: IeeeRxConfig, length_size: u32, timestamp: u32) -> Vec<u8> {
+    let (&phy_header, payload) = frame.split_first().expect("the frames are validated");
+    let mut data = Vec::with_capacity(frame.len() + 8);
+    if rx_config.b_include_phy_hdr() {
+        data.push(phy_header);
+    }
+    // The modem passes the frames with their CRC.
+    if rx_config.b_include_crc() {
+        data.extend_from_slice(payload);
+    } else {
+        data.extend_from_slice(&payload[..payload.len().saturating_sub(IEEE_CRC_SIZE)]);
+    }
+    if rx_config.b_append_rssi() {
+        data.push(RX_FRAME_RSSI.to_le_bytes()[0]);
+    }
+    if rx_config.b_append_corr_crc() {
+        // TODO: the correlation is not modelled, and the CRC is always correct.
+        data.push(0);
+    }
+    if rx_config.b_append_src_ind() {
+        data.push(NO_SOURCE_MATCH);
+    }
+    if rx_config.b_append_timestamp() {
+        data.extend_from_slice(&timestamp.to_le_bytes());
+    }
+    with_length(data, length_size)
 }
 
-#[derive(BitfieldSpecifier, Clone, Copy, Debug)]
-#[bits = 2]
-enum RxEntryLengthSize {
-    NoIndicator = 0,
-    OneByte = 1,
-    TwoByte = 2,
-    Reserved = 3,
+/// Prefixes the element of the RX queue with its length, if the entry has the length indicator.
+fn with_length(data: Vec<u8>, length_size: u32) -> Vec<u8> {
+    let length = data.len();
+    let mut element = match length_size {
+        1 => vec![u8::try_from(length).unwrap_or(u8::MAX)],
+        2 => u16::try_from(length)
+            .unwrap_or(u16::MAX)
+            .to_le_bytes()
+            .to_vec(),
+        _ => Vec::new(),
+    };
+    element.extend(data);
+    element
 }
 
-#[derive(Clone, Copy, Debug)]
+/// The next state of the backoff generator of CSMA-CA.
+fn next_random(state: u16) -> u16 {
+    if state & 1 == 0 {
+        state >> 1
+    } else {
+        (state >> 1) ^ CSMA_RANDOM_TAPS
+    }
+}
+
+#[allow(clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+fn sign_extend(value: u32, bit: u8) -> i32 {
+    let shift = 31 - u32::from(bit);
+    ((value << shift) as i32) >> shift
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub(crate) enum RfcMemoryBypassReceiver {
     Command,
     Payload,
     RxQueue,
     RxEntryHeader,
+    /// The queue of a data queue command.
+    DataQueue,
+    /// An entry of the queue of a data queue command.
+    DataEntry,
+    PatternValue,
+    /// The memory read by a BLE operation.
+    BleData,
 }
 
 bridge_ports!(@slave RFCComponent => @auto_configured @slave BusDriver);
@@ -1090,11 +3056,11 @@ impl FakingHandler for RFCComponent {
 
     fn read(
         comp: &mut Self::Component,
-        _ctx: &mut Context,
+        ctx: &mut Context,
         address: Address,
         _size: Size,
     ) -> Self::Data {
-        DataBus::from(comp.get_data_for_address(address))
+        DataBus::from(comp.get_data_for_address(ctx, address))
     }
 
     fn pre_write(
@@ -1115,12 +3081,32 @@ impl FakingHandler for RFCComponent {
 // Talking with registers
 #[component_impl(rfc)]
 impl RFCComponent {
-    fn get_data_for_address(&mut self, addr: Address) -> u32 {
-        self.register_bank.get_data_for_address(addr)
+    fn get_data_for_address(&mut self, ctx: &mut Context, addr: Address) -> u32 {
+        let now = ctx.event_queue().get_current_time();
+        if addr == RFC::RAT::RATCNT::ADDR {
+            let value = self.rat.counter(now);
+            trace!("reading RATCNT: {:#x}", value);
+            value
+        } else if let Some(idx) = rat_channel_register(addr) {
+            let value = self.rat.channel_value(idx);
+            trace!("reading RATCH{idx}VAL: {:#x}", value);
+            value
+        } else {
+            self.register_bank.get_data_for_address(addr)
+        }
     }
 
     fn set_data_for_address(&mut self, ctx: &mut Context, addr: Address, data: u32) {
-        self.register_bank.set_data_for_address(ctx, addr, data);
+        let now = ctx.event_queue().get_current_time();
+        if addr == RFC::RAT::RATCNT::ADDR {
+            trace!("writing RATCNT => {:#x}", data);
+            self.rat.set_counter(now, data);
+        } else if let Some(idx) = rat_channel_register(addr) {
+            trace!("writing RATCH{idx}VAL => {:#x}", data);
+            self.rat.set_channel_value(idx, data);
+        } else {
+            self.register_bank.set_data_for_address(ctx, addr, data);
+        }
     }
 
     fn get_waitstates_for_address(&self, _addr: Address, _writing: bool) -> u32 {
@@ -1129,56 +3115,74 @@ impl RFCComponent {
 
     #[handler]
     pub fn on_non_zero_cmdr_write(&mut self, ctx: &mut Context, cmdr: u32) {
+        let [low, param, ..] = cmdr.to_le_bytes();
         let task = match cmdr & 0b11 {
             0b01 => {
-                let numeric_id = u16::try_from(cmdr >> 16).unwrap();
-                let Ok(id) = CommandId::try_from(numeric_id) else {
-                    todo!(
-                        "received invalid CMDR value {:08x} (unknown command id {:04x})",
-                        cmdr,
-                        numeric_id
-                    );
-                };
-                let param = u8::try_from((cmdr & 0xf0) >> 8).unwrap();
-                let extension = u8::try_from((cmdr & 0b11111100) >> 2).unwrap();
-                TaskHandle::Direct {
-                    id,
-                    param,
-                    extension,
+                let numeric_id = u16::try_from(cmdr >> 16).expect("the upper half of CMDR");
+                match CommandId::try_from(numeric_id) {
+                    Ok(id) if id.is_direct() => TaskHandle::Direct {
+                        id,
+                        param,
+                        extension: low >> 2,
+                    },
+                    _ => {
+                        warn!(
+                            "received CMDR value {cmdr:08x} with unknown direct command id {numeric_id:04x}"
+                        );
+                        TaskHandle::Invalid(ResultByte::UnknownDirCommand)
+                    }
                 }
             }
-            0b00 => TaskHandle::Pointer(Address::from_const(cmdr)),
-            0b10 | 0b11 => todo!("received invalid CMDR value: {:08x}", cmdr),
-            _ => unreachable!(),
+            0b00 if is_command_pointer(Address::from_const(cmdr)) => {
+                TaskHandle::Pointer(Address::from_const(cmdr))
+            }
+            _ => {
+                warn!("received CMDR value {cmdr:08x}, which is not a valid pointer");
+                TaskHandle::Invalid(ResultByte::IllegalPointer)
+            }
         };
-        self.current_task.set_next(task);
+        self.current_task = task;
         self.register_bank.set_command_status(ResultByte::Pending);
     }
 }
 
+/// Index of the channel of a `RATCHxVAL` register.
+fn rat_channel_register(addr: Address) -> Option<usize> {
+    let first = RFC::RAT::RATCH0VAL::ADDR;
+    let last = RFC::RAT::RATCH7VAL::ADDR;
+    (first..=last)
+        .contains(&addr)
+        .then(|| (addr.offset_from(first) / 4) as usize)
+}
+
 #[component_impl(rfc)]
 impl RFCComponent {
-    fn raise_cpe_interrupt_if_enabled(&mut self, ctx: &mut Context, interrupt: Interrupt) {
+    /// Sets the flag of the interrupt, and raises it if it's enabled.
+    fn raise_cpe_interrupt(&mut self, ctx: &mut Context, interrupt: Interrupt) {
+        self.register_bank.set_cpe_interrupt_pending(interrupt);
         if self.register_bank.is_cpe_interrupt_enabled(interrupt) {
-            self.register_bank.set_cpe_interrupt_pending(interrupt);
-            EventFabricProxy.notify(
-                ctx,
-                self.register_bank.get_event_for_cpe_interrupt(interrupt),
-            );
+            self.notify_event(self.register_bank.get_event_for_cpe_interrupt(interrupt));
+        }
+    }
+
+    /// Queues the event, which is sent at the end of the tick, at most once per cycle.
+    fn notify_event(&mut self, event: EventFabricEvent) {
+        if !self.pending_events.contains(&event) {
+            self.pending_events.push(event);
         }
     }
 
     /// Writes to CMDSTA register, zeros CMDR register sets interrupt flag and send interrupt event to EventFabric.
-    fn ack_command_with_status(&mut self, ctx: &mut Context, status: ResultByte) {
+    fn ack_command_with_status(
+        &mut self,
+        ctx: &mut Context,
+        status: impl Into<CommandStatusHandle>,
+    ) {
         self.register_bank.clean_cmdr();
         self.register_bank.set_command_status(status);
         self.register_bank.set_doorbell_interrupt_pending();
-        EventFabricProxy.notify(ctx, self.register_bank.get_event_for_doorbell_interrupt());
+        self.notify_event(self.register_bank.get_event_for_doorbell_interrupt());
     }
-
-    // fn raise_interrupt_if_enabled(&mut self, ) {
-    //
-    // }
 }
 
 #[component_impl(rfc)]
@@ -1186,21 +3190,25 @@ impl DisableableComponent for RFCComponent {
     fn can_be_disabled_now(&self) -> bool {
         // TODO: this is implementation of "can skip cycles", not whether can be disabled completely!
         let subs = self.driver.can_be_disabled_now() && self.register_bank.can_be_disabled_now();
-        subs && self.pending_command.is_empty()
-            && self.pending_command.is_none()
-            && self.current_task.is_empty()
-            && matches!(*self.current_task, TaskHandle::Nothing)
-            && self.address_for_pending_cca_req.is_empty()
-            && self.address_for_pending_cca_req.is_none()
-            && self.background_command.is_empty()
-            && self.tx_awaiting_done.is_none()
+        subs && self.pending_command.is_none()
+            && self.pending_events.is_empty()
+            && !self.register_bank.has_flag_updates()
+            && matches!(self.current_task, TaskHandle::Nothing)
+            && self.chain_links.values().all(Option::is_none)
+            && self.running.values().flatten().all(RunningOperation::allows_skipping)
+            && self.cca_users.is_empty()
+            && self.queue_operation.is_none()
             // storages are not pending
             && self.command_storage.is_some()
             && self.payload_storage.is_some()
             && self.rx_queue_storage.is_some()
             && self.rx_entry_header_storage.is_some()
+            && self.queue_storage.is_some()
+            && self.value_storage.is_some()
+            && self.ble_storage.is_some()
             // writing not pending
-            && self.rx_buffer_to_write.is_none()
+            && self.rx_frames.is_empty()
+            && !self.rx_storing
     }
 }
 
@@ -1208,19 +3216,21 @@ impl DisableableComponent for RFCComponent {
 impl SkippableClockTreeNode for RFCComponent {
     fn max_cycles_to_skip(
         comp: &mut Self::Component,
-        _ctx: &mut Context,
+        ctx: &mut Context,
         _parent: Self::IdSpace,
         _extra: &mut Self::Extra,
     ) -> u64 {
-        // TODO: triggers and timing
         if comp.need_wakeup || !comp.can_be_disabled_now() {
             debug!(
-                "RFC cannot be disabled now: bg: {:?}, pend: {:?}",
-                comp.background_command, comp.pending_command
+                "RFC cannot be disabled now: running: {:?}, pend: {:?}",
+                comp.running, comp.pending_command
             );
             0
         } else {
-            u64::MAX
+            // Stop a tick of the RAT before the event, to handle it on time.
+            comp.ticks_to_next_event(ctx).map_or(u64::MAX, |ticks| {
+                u64::from(ticks.saturating_sub(1)) * CYCLES_PER_TICK
+            })
         }
     }
 
@@ -1232,14 +3242,16 @@ impl SkippableClockTreeNode for RFCComponent {
         skipped_cycles: u64,
     ) {
         let this = comp;
-        // todo: fast forward internal tickers
+        // The RAT follows the emulation time, and the timed events wait for it.
         // this.cycles_clock
         //     .set_next(this.cycles_clock.wrapping_add(skipped_cycles as usize));
     }
 }
 
 /// [TI-TRM-I] Table 23-1. Values of the Result Byte in the CMDSTA Register
-#[derive(Debug, PartialEq, Clone, Copy, IntoPrimitive, TryFromPrimitive)]
+#[derive(
+    Debug, PartialEq, Eq, Clone, Copy, IntoPrimitive, TryFromPrimitive, Serialize, Deserialize,
+)]
 #[repr(u8)]
 pub enum ResultByte {
     Pending = 0x00,
@@ -1254,7 +3266,7 @@ pub enum ResultByte {
     QueueBusy = 0x89,
 }
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub struct CommandStatusHandle {
     result: ResultByte,
     return_byte_1: Option<u8>,
@@ -1262,6 +3274,15 @@ pub struct CommandStatusHandle {
     return_byte_3: Option<u8>,
 }
 
+impl CommandStatusHandle {
+    fn with_return_byte_2(self, byte: u8) -> Self {
+        Self {
+            return_byte_2: Some(byte),
+            ..self
+        }
+    }
+}
+
 impl From<ResultByte> for CommandStatusHandle {
     fn from(result: ResultByte) -> Self {
         CommandStatusHandle {
@@ -1282,41 +3303,17 @@ impl From<CommandStatusHandle> for u32 {
     }
 }
 
-impl TryFrom<TaskHandle> for Command {
-    type Error = (CommandStatusHandle, CommandStatus);
-
-    fn try_from(value: TaskHandle) -> Result<Self, Self::Error> {
-        let TaskHandle::Direct {
-            id,
-            param,
-            extension,
-        } = value
-        else {
-            unreachable!("Can't parse {:?} into Command.", value)
-        };
-        if !id.is_direct() {
-            return Result::Err((
-                CommandStatusHandle::from(ResultByte::UnknownCommand),
-                CommandStatus::ErrorCmdId,
-            ));
-        }
-        Ok(Command {
-            id,
-            data: CommandData::Direct { param, extension },
-        })
-    }
-}
-
 #[derive(Debug, PartialEq, Clone, Copy, IntoPrimitive, TryFromPrimitive)]
 #[repr(u8)]
 enum Strobe {
     RegSxoscon = 0x01,
     RegSrxon = 0x03,
     RegStxon = 0x04,
+    RegSrfoff = 0x06,
 }
 
 /// [TI-TRM-I] 23.8.2.5 Command and Packet Engine Generated Interrupts
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
 #[repr(u8)]
 enum Interrupt {
     InternalError = 31,
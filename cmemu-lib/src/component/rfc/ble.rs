@@ -0,0 +1,1923 @@
+//! The Bluetooth low energy radio operations.
+//!
+//! [TI-TRM-I] Bluetooth Low Energy
+//! The BLE operations keep their parameters in a separate structure (`pParams`) and count
+//! the packets in an output structure (`pOutput`), which the CPE updates as the operation runs.
+//! Every operation runs on the single channel of its command: the BLE stacks hop between
+//! the advertising channels and the data channels by chaining the operations, which start
+//! with the triggers at the RAT times of the events.
+//!
+//! A [`BleOperation`] is a state machine of the link layer. It's told about the memory it read,
+//! the packets of the modem and the passing time, and asks for [`BleAction`]s in return.
+
+use crate::component::rfc::command::{CommandId, CommandStatus, Trigger};
+use crate::component::rfc::data_queue::{
+    DataEntryHeader, DataEntryStatus, DataEntryType, DataQueue, ENTRY_HEADER_SIZE,
+    GENERAL_ENTRY_DATA_OFFSET, QUEUE_SIZE,
+};
+use crate::component::rfc::{BlePacket, CSMA_RANDOM_SEED, Interrupt, RSSI_UNKNOWN, next_random};
+use cmemu_common::Address;
+use enum_map::{Enum, EnumMap};
+use log::{trace, warn};
+use modular_bitfield::prelude::*;
+use num_enum::TryFromPrimitive;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::ops::RangeInclusive;
+
+/// The BLE channels: 0-36 are the data channels, 37-39 are the advertising channels.
+pub(super) const BLE_CHANNELS: RangeInclusive<u8> = 0..=39;
+/// Channels given as the frequency of `2300 + channel` MHz.
+pub(super) const BLE_CUSTOM_CHANNELS: RangeInclusive<u8> = 60..=207;
+/// `channel`, which keeps the frequency programmed by `CMD_FS`.
+pub(super) const BLE_CHANNEL_KEEP: u8 = 0xFF;
+/// The biggest read of an operation: a white list of 255 entries.
+pub(super) const BLE_MAX_READ_SIZE: usize = 255 * WHITE_LIST_ENTRY_SIZE;
+
+/// The access address and the CRC initialization of the advertising channel packets.
+const ADV_ACCESS_ADDRESS: u32 = 0x8E89_BED6;
+const ADV_CRC_INIT: u32 = 0x55_5555;
+/// The access address of the test packets, which use the CRC initialization of advertising.
+const TEST_ACCESS_ADDRESS: u32 = 0x7176_4129;
+/// The polynomial of the CRC, reflected (`x^24 + x^10 + x^9 + x^6 + x^4 + x^3 + x + 1`).
+const CRC_TAPS: u32 = 0x5A_6000;
+/// RAT ticks of sending a byte at 1 Mbps.
+const TICKS_PER_BYTE: u32 = 32;
+/// The preamble and the access address are sent before the PDU.
+const SYNC_SIZE: u32 = 5;
+const CRC_SIZE: usize = 3;
+/// The inter frame space of 150 us.
+const T_IFS_TICKS: u32 = 600;
+/// How long a response is listened for after a packet: the inter frame space with some margin,
+/// and the sync of the response.
+const RESPONSE_TIMEOUT_TICKS: u32 = T_IFS_TICKS + 8 + SYNC_SIZE * TICKS_PER_BYTE;
+/// The unit of the timing of connections (1.25 ms).
+const CONNECTION_UNIT_TICKS: u32 = 5000;
+const ADDRESS_SIZE: usize = 6;
+/// `LLData` of `CONNECT_IND`.
+const LL_DATA_SIZE: usize = 22;
+/// The advertising data, the scan response data, or the address of the peer of `ADV_DIRECT_IND`.
+const MAX_ADV_DATA_SIZE: usize = 31;
+/// A data packet: the header byte and the payload.
+const MAX_TX_ENTRY_SIZE: usize = 256;
+const WHITE_LIST_ENTRY_SIZE: usize = 8;
+/// Bits of `conf` of a white list entry.
+const WHITE_LIST_ENABLE: u8 = 1 << 0;
+const WHITE_LIST_RANDOM: u8 = 1 << 1;
+const WHITE_LIST_IGNORE: u8 = 1 << 2;
+/// Bits of the first byte of the header of the advertising channel PDUs.
+const ADV_PDU_TYPE_MASK: u8 = 0b1111;
+const ADV_TX_ADD: u8 = 1 << 6;
+const ADV_RX_ADD: u8 = 1 << 7;
+/// Bits of the first byte of the header of the data channel PDUs.
+const DATA_LLID_MASK: u8 = 0b11;
+const DATA_NESN: u8 = 1 << 2;
+const DATA_SN: u8 = 1 << 3;
+const DATA_MD: u8 = 1 << 4;
+/// LLID of an empty packet (a continuation of a message) and of the LL control packets.
+const LLID_CONTINUATION: u8 = 0b01;
+const LLID_CONTROL: u8 = 0b11;
+/// Consecutive packets with CRC errors, which end a connection event.
+const MAX_CRC_ERRORS: u8 = 2;
+/// The TX queue is read up to that many entries at the start of a connection event.
+const MAX_TX_ENTRIES: usize = 16;
+/// The upper limit of the backoff of the scanner is at most 256.
+const MAX_LOG_UPPER_LIMIT: u8 = 8;
+
+/// RAT ticks of sending a packet with the PDU.
+pub(super) fn air_time_ticks(pdu_len: usize) -> u32 {
+    let bytes = SYNC_SIZE + u32::try_from(pdu_len + CRC_SIZE).expect("the packets are short");
+    bytes * TICKS_PER_BYTE
+}
+
+/// The CRC of a packet, in the order it's sent in.
+fn crc24(crc_init: u32, pdu: &[u8]) -> [u8; CRC_SIZE] {
+    // The shift register is reflected, so the bits are sent from the least significant one.
+    let mut state = crc_init.reverse_bits() >> 8;
+    for &byte in pdu {
+        let mut byte = byte;
+        for _ in 0..8 {
+            let feedback = (state ^ u32::from(byte)) & 1;
+            byte >>= 1;
+            state >>= 1;
+            if feedback != 0 {
+                state |= 1 << 23;
+                state ^= CRC_TAPS;
+            }
+        }
+    }
+    let [b0, b1, b2, _] = state.to_le_bytes();
+    [b0, b1, b2]
+}
+
+/// The PDU types of the advertising channel packets (the lower bits of the header).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, TryFromPrimitive, Serialize, Deserialize)]
+#[repr(u8)]
+pub(super) enum AdvPduType {
+    AdvInd = 0,
+    AdvDirectInd = 1,
+    AdvNonconnInd = 2,
+    ScanReq = 3,
+    ScanRsp = 4,
+    ConnectInd = 5,
+    AdvScanInd = 6,
+}
+
+impl AdvPduType {
+    fn is_scannable(self) -> bool {
+        matches!(self, Self::AdvInd | Self::AdvScanInd)
+    }
+
+    fn is_connectable(self) -> bool {
+        matches!(self, Self::AdvInd | Self::AdvDirectInd)
+    }
+}
+
+/// The BLE radio operation commands.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) enum BleKind {
+    Slave,
+    Master,
+    /// The advertisers differ in the PDU they send.
+    Advertiser(AdvPduType),
+    Scanner,
+    Initiator,
+    GenericRx,
+    TxTest,
+}
+
+impl BleKind {
+    pub(super) fn from_id(id: CommandId) -> Option<Self> {
+        Some(match id {
+            CommandId::BleSlave => Self::Slave,
+            CommandId::BleMaster => Self::Master,
+            CommandId::BleAdv => Self::Advertiser(AdvPduType::AdvInd),
+            CommandId::BleAdvDir => Self::Advertiser(AdvPduType::AdvDirectInd),
+            CommandId::BleAdvNc => Self::Advertiser(AdvPduType::AdvNonconnInd),
+            CommandId::BleAdvScan => Self::Advertiser(AdvPduType::AdvScanInd),
+            CommandId::BleScanner => Self::Scanner,
+            CommandId::BleInitiator => Self::Initiator,
+            CommandId::BleGenericRx => Self::GenericRx,
+            CommandId::BleTxTest => Self::TxTest,
+            _ => return None,
+        })
+    }
+
+    /// Size of the parameter structure.
+    fn params_size(self) -> usize {
+        match self {
+            Self::Slave | Self::Advertiser(_) => 32,
+            Self::Master => 24,
+            Self::Scanner | Self::Initiator => 36,
+            Self::GenericRx => 20,
+            Self::TxTest => 16,
+        }
+    }
+
+    fn is_connection(self) -> bool {
+        matches!(self, Self::Master | Self::Slave)
+    }
+
+    fn output_layout(self) -> &'static OutputLayout {
+        match self {
+            Self::Advertiser(_) => &ADV_OUTPUT,
+            Self::Scanner => &SCANNER_OUTPUT,
+            Self::Initiator => &INITIATOR_OUTPUT,
+            Self::Master | Self::Slave => &CONNECTION_OUTPUT,
+            Self::GenericRx => &GENERIC_RX_OUTPUT,
+            Self::TxTest => &TX_TEST_OUTPUT,
+        }
+    }
+}
+
+/// The counters of the output structures.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Enum, Serialize, Deserialize)]
+enum Counter {
+    TxAdvInd,
+    TxScanRsp,
+    RxScanReq,
+    RxConnectInd,
+    TxScanReq,
+    BackedOffScanReq,
+    RxAdvOk,
+    RxAdvIgnored,
+    RxAdvNok,
+    RxAdvBufFull,
+    RxScanRspOk,
+    RxScanRspIgnored,
+    RxScanRspNok,
+    RxScanRspBufFull,
+    TxConnectReq,
+    Tx,
+    TxAck,
+    TxCtrl,
+    TxCtrlAck,
+    TxCtrlAckAck,
+    TxRetrans,
+    TxEntryDone,
+    RxOk,
+    RxCtrl,
+    RxCtrlAck,
+    RxNok,
+    RxIgnored,
+    RxEmpty,
+    RxBufFull,
+}
+
+/// Offsets of the fields of an output structure.
+struct OutputLayout {
+    size: usize,
+    /// The counters with their offsets and sizes.
+    counters: &'static [(usize, Counter, usize)],
+    last_rssi: Option<usize>,
+    pkt_status: Option<usize>,
+    time_stamp: Option<usize>,
+}
+
+/// [TI-TRM-I] Output Structure for Advertiser
+const ADV_OUTPUT: OutputLayout = OutputLayout {
+    size: 16,
+    counters: &[
+        (0, Counter::TxAdvInd, 2),
+        (2, Counter::TxScanRsp, 1),
+        (3, Counter::RxScanReq, 1),
+        (4, Counter::RxConnectInd, 1),
+        (6, Counter::RxNok, 2),
+        (8, Counter::RxIgnored, 1),
+        (9, Counter::RxBufFull, 1),
+    ],
+    last_rssi: Some(10),
+    pkt_status: None,
+    time_stamp: Some(12),
+};
+
+/// [TI-TRM-I] Output Structure for Scanner
+const SCANNER_OUTPUT: OutputLayout = OutputLayout {
+    size: 24,
+    counters: &[
+        (0, Counter::TxScanReq, 2),
+        (2, Counter::BackedOffScanReq, 2),
+        (4, Counter::RxAdvOk, 2),
+        (6, Counter::RxAdvIgnored, 2),
+        (8, Counter::RxAdvNok, 2),
+        (10, Counter::RxScanRspOk, 2),
+        (12, Counter::RxScanRspIgnored, 2),
+        (14, Counter::RxScanRspNok, 2),
+        (16, Counter::RxAdvBufFull, 1),
+        (17, Counter::RxScanRspBufFull, 1),
+    ],
+    last_rssi: Some(18),
+    pkt_status: None,
+    time_stamp: Some(20),
+};
+
+/// [TI-TRM-I] Output Structure for Initiator
+const INITIATOR_OUTPUT: OutputLayout = OutputLayout {
+    size: 12,
+    counters: &[
+        (0, Counter::TxConnectReq, 1),
+        (1, Counter::RxAdvOk, 1),
+        (2, Counter::RxAdvIgnored, 1),
+        (3, Counter::RxAdvNok, 1),
+        (4, Counter::RxAdvBufFull, 1),
+    ],
+    last_rssi: Some(5),
+    pkt_status: None,
+    time_stamp: Some(8),
+};
+
+/// [TI-TRM-I] Output Structure for Master and Slave
+const CONNECTION_OUTPUT: OutputLayout = OutputLayout {
+    size: 20,
+    counters: &[
+        (0, Counter::Tx, 1),
+        (1, Counter::TxAck, 1),
+        (2, Counter::TxCtrl, 1),
+        (3, Counter::TxCtrlAck, 1),
+        (4, Counter::TxCtrlAckAck, 1),
+        (5, Counter::TxRetrans, 1),
+        (6, Counter::TxEntryDone, 1),
+        (7, Counter::RxOk, 1),
+        (8, Counter::RxCtrl, 1),
+        (9, Counter::RxCtrlAck, 1),
+        (10, Counter::RxNok, 1),
+        (11, Counter::RxIgnored, 1),
+        (12, Counter::RxEmpty, 1),
+        (13, Counter::RxBufFull, 1),
+    ],
+    last_rssi: Some(14),
+    pkt_status: Some(15),
+    time_stamp: Some(16),
+};
+
+/// [TI-TRM-I] Output Structure for Generic Receiver
+const GENERIC_RX_OUTPUT: OutputLayout = OutputLayout {
+    size: 12,
+    counters: &[
+        (0, Counter::RxOk, 2),
+        (2, Counter::RxNok, 2),
+        (4, Counter::RxBufFull, 2),
+    ],
+    last_rssi: Some(6),
+    pkt_status: None,
+    time_stamp: Some(8),
+};
+
+/// [TI-TRM-I] Output Structure for Transmitter Test
+const TX_TEST_OUTPUT: OutputLayout = OutputLayout {
+    size: 2,
+    counters: &[(0, Counter::Tx, 2)],
+    last_rssi: None,
+    pkt_status: None,
+    time_stamp: None,
+};
+
+/// The parameter structures of all the operations, with the fields named after the first
+/// structure having them.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+struct BleParams {
+    p_rx_q: u32,
+    p_tx_q: u32,
+    rx_config: u8,
+    /// `advConfig`, `scanConfig` or `initConfig`.
+    config: u8,
+    /// `advLen`, `scanReqLen` or `connectReqLen`.
+    data_len: u8,
+    scan_rsp_len: u8,
+    /// `pAdvData`, `pScanReqData` or `pConnectReqData`.
+    p_data: u32,
+    p_scan_rsp_data: u32,
+    p_device_address: u32,
+    /// The white list, or the address of the peer of the initiator not using it.
+    p_white_list: u32,
+    random_state: u16,
+    backoff_count: u16,
+    backoff_par: u8,
+    connect_time: u32,
+    seq_stat: u8,
+    max_nack: u8,
+    max_pkt: u8,
+    access_address: u32,
+    crc_init: u32,
+    b_repeat: u8,
+    num_packets: u16,
+    payload_length: u8,
+    packet_type: u8,
+    period: u32,
+    test_config: u8,
+    byte_val: u8,
+    timeout: Option<(u8, u32)>,
+    end_trigger: u8,
+    end_time: u32,
+}
+
+impl BleParams {
+    fn from_bytes(kind: BleKind, bytes: &[u8]) -> Self {
+        let u16_at = |offset: usize| u16::from_le_bytes([bytes[offset], bytes[offset + 1]]);
+        let u32_at = |offset: usize| {
+            u32::from_le_bytes(bytes[offset..offset + 4].try_into().expect("4 bytes"))
+        };
+        let u24_at = |offset: usize| {
+            u32::from_le_bytes([bytes[offset], bytes[offset + 1], bytes[offset + 2], 0])
+        };
+        match kind {
+            BleKind::Advertiser(_) => Self {
+                p_rx_q: u32_at(0),
+                rx_config: bytes[4],
+                config: bytes[5],
+                data_len: bytes[6],
+                scan_rsp_len: bytes[7],
+                p_data: u32_at(8),
+                p_scan_rsp_data: u32_at(12),
+                p_device_address: u32_at(16),
+                p_white_list: u32_at(20),
+                end_trigger: bytes[27],
+                end_time: u32_at(28),
+                ..Self::default()
+            },
+            BleKind::Scanner => Self {
+                p_rx_q: u32_at(0),
+                rx_config: bytes[4],
+                config: bytes[5],
+                random_state: u16_at(6),
+                backoff_count: u16_at(8),
+                backoff_par: bytes[10],
+                data_len: bytes[11],
+                p_data: u32_at(12),
+                p_device_address: u32_at(16),
+                p_white_list: u32_at(20),
+                timeout: Some((bytes[26], u32_at(28))),
+                end_trigger: bytes[27],
+                end_time: u32_at(32),
+                ..Self::default()
+            },
+            BleKind::Initiator => Self {
+                p_rx_q: u32_at(0),
+                rx_config: bytes[4],
+                config: bytes[5],
+                data_len: bytes[7],
+                p_data: u32_at(8),
+                p_device_address: u32_at(12),
+                p_white_list: u32_at(16),
+                connect_time: u32_at(20),
+                timeout: Some((bytes[26], u32_at(28))),
+                end_trigger: bytes[27],
+                end_time: u32_at(32),
+                ..Self::default()
+            },
+            BleKind::Master | BleKind::Slave => Self {
+                p_rx_q: u32_at(0),
+                p_tx_q: u32_at(4),
+                rx_config: bytes[8],
+                seq_stat: bytes[9],
+                max_nack: bytes[10],
+                max_pkt: bytes[11],
+                access_address: u32_at(12),
+                crc_init: u24_at(16),
+                timeout: (kind == BleKind::Slave).then(|| (bytes[19], u32_at(20))),
+                end_trigger: if kind == BleKind::Slave {
+                    bytes[27]
+                } else {
+                    bytes[19]
+                },
+                end_time: if kind == BleKind::Slave {
+                    u32_at(28)
+                } else {
+                    u32_at(20)
+                },
+                ..Self::default()
+            },
+            BleKind::GenericRx => Self {
+                p_rx_q: u32_at(0),
+                rx_config: bytes[4],
+                b_repeat: bytes[5],
+                access_address: u32_at(8),
+                crc_init: u24_at(12),
+                end_trigger: bytes[15],
+                end_time: u32_at(16),
+                ..Self::default()
+            },
+            BleKind::TxTest => Self {
+                num_packets: u16_at(0),
+                payload_length: bytes[2],
+                packet_type: bytes[3],
+                period: u32_at(4),
+                test_config: bytes[8],
+                byte_val: bytes[9],
+                end_trigger: bytes[11],
+                end_time: u32_at(12),
+                ..Self::default()
+            },
+        }
+    }
+
+    fn rx_config(&self) -> BleRxConfig {
+        BleRxConfig::from_bytes([self.rx_config])
+    }
+
+    fn adv_config(&self) -> AdvConfig {
+        AdvConfig::from_bytes([self.config])
+    }
+
+    fn scan_config(&self) -> ScanConfig {
+        ScanConfig::from_bytes([self.config])
+    }
+
+    fn init_config(&self) -> InitConfig {
+        InitConfig::from_bytes([self.config])
+    }
+
+    fn test_config(&self) -> TxTestConfig {
+        TxTestConfig::from_bytes([self.test_config])
+    }
+}
+
+/// `rxConfig` of the BLE operations.
+#[bitfield]
+#[derive(Clone, Copy, Debug)]
+struct BleRxConfig {
+    b_auto_flush_ignored: bool,
+    b_auto_flush_crc_err: bool,
+    b_auto_flush_empty: bool,
+    b_include_len_byte: bool,
+    b_include_crc: bool,
+    b_append_rssi: bool,
+    b_append_status: bool,
+    b_append_timestamp: bool,
+}
+
+/// `advConfig` of the advertisers.
+#[bitfield]
+#[derive(Clone, Copy, Debug)]
+struct AdvConfig {
+    /// Bit 0: only the scanners in the white list, bit 1: only the initiators in it.
+    adv_filter_policy: B2,
+    device_addr_type: bool,
+    peer_addr_type: bool,
+    b_strict_len_filter: bool,
+    #[skip]
+    __: B3,
+}
+
+/// `scanConfig` of the scanner.
+#[bitfield]
+#[derive(Clone, Copy, Debug)]
+struct ScanConfig {
+    scan_filter_policy: bool,
+    b_active_scan: bool,
+    device_addr_type: bool,
+    #[skip]
+    __: B1,
+    b_strict_len_filter: bool,
+    b_auto_wl_ignore: bool,
+    b_end_on_rpt: bool,
+    #[skip]
+    __: B1,
+}
+
+/// `initConfig` of the initiator.
+#[bitfield]
+#[derive(Clone, Copy, Debug)]
+struct InitConfig {
+    b_use_white_list: bool,
+    b_dynamic_win_offset: bool,
+    device_addr_type: bool,
+    peer_addr_type: bool,
+    b_strict_len_filter: bool,
+    #[skip]
+    __: B3,
+}
+
+/// `backoffPar` of the scanner.
+#[bitfield]
+#[derive(Clone, Copy, Debug)]
+struct BackoffPar {
+    log_upper_limit: B4,
+    b_last_succeeded: bool,
+    b_last_failed: bool,
+    #[skip]
+    __: B2,
+}
+
+/// `seqStat` of the connections.
+#[bitfield]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+struct SeqStat {
+    last_rx_sn: bool,
+    last_tx_sn: bool,
+    next_tx_sn: bool,
+    /// Nothing was received in the connection yet.
+    b_first_pkt: bool,
+    /// The last packet sent was an empty one, not from the TX queue.
+    b_auto_empty: bool,
+    b_ll_ctrl_tx: bool,
+    b_ll_ctrl_ack_rx: bool,
+    b_ll_ctrl_ack_pending: bool,
+}
+
+/// `pktStatus` of the output of the connections.
+#[bitfield]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+struct PktStatus {
+    b_time_stamp_valid: bool,
+    b_last_crc_err: bool,
+    b_last_ignored: bool,
+    b_last_empty: bool,
+    b_last_ctrl: bool,
+    b_last_md: bool,
+    b_last_ack: bool,
+    #[skip]
+    __: B1,
+}
+
+/// `config` of `CMD_BLE_TX_TEST`.
+#[bitfield]
+#[derive(Clone, Copy, Debug)]
+struct TxTestConfig {
+    /// Send `byteVal` instead of the pattern of `packetType`.
+    b_override_default: bool,
+    b_use_prbs9: bool,
+    b_use_prbs15: bool,
+    #[skip]
+    __: B5,
+}
+
+/// How a packet handed to be stored is counted, once it's stored or dropped.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub(super) struct RxReport {
+    ok: Counter,
+    buf_full: Counter,
+    interrupt: Interrupt,
+}
+
+impl RxReport {
+    const fn new(ok: Counter, buf_full: Counter, interrupt: Interrupt) -> Self {
+        Self {
+            ok,
+            buf_full,
+            interrupt,
+        }
+    }
+}
+
+/// What the operation needs to be done.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(super) enum BleAction {
+    Read {
+        address: Address,
+        size: usize,
+    },
+    Write {
+        address: Address,
+        data: Vec<u8>,
+    },
+    /// The end trigger and the timeout trigger with their times, known from the parameters.
+    Triggers {
+        end: (Trigger, u32),
+        timeout: Option<(Trigger, u32)>,
+    },
+    /// Send the packet on the channel of the operation.
+    Transmit {
+        pdu: Vec<u8>,
+    },
+    /// Receive on the channel of the operation, for at most `timeout` RAT ticks.
+    Listen {
+        timeout: Option<u32>,
+    },
+    /// Let the RAT ticks pass.
+    Wait {
+        ticks: u32,
+    },
+    /// Store the element in the RX queue.
+    StoreRx {
+        data: Vec<u8>,
+        report: Option<RxReport>,
+    },
+    Interrupt(Interrupt),
+}
+
+/// Memory the operation reads.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+enum Fetch {
+    Params,
+    Output,
+    DeviceAddress,
+    Data,
+    ScanRspData,
+    PeerAddress,
+    /// The first entry of the white list, with its size.
+    WhiteListSize,
+    WhiteList,
+    TxQueue,
+    TxEntry(Address),
+    TxData(Address, DataEntryHeader),
+}
+
+/// A peer of the advertising channel packets.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct Peer {
+    address: [u8; ADDRESS_SIZE],
+    random: bool,
+}
+
+/// A packet the operation sends.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+enum Sent {
+    Adv,
+    ScanRsp,
+    ScanReq(Peer),
+    ConnectInd(Peer),
+    Data,
+    Test,
+}
+
+/// A packet the operation listens for.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+enum Expect {
+    /// `SCAN_REQ` or `CONNECT_IND` after advertising.
+    Request,
+    Adv,
+    ScanRsp(Peer),
+    Data,
+    Any,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+enum Phase {
+    Fetching,
+    /// Waits for the inter frame space, or the period of the test packets, before sending.
+    Waiting(Sent),
+    Transmitting(Sent),
+    Listening(Expect),
+    Ended,
+}
+
+/// An entry of the TX queue of a connection.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct TxEntry {
+    address: Address,
+    header: DataEntryHeader,
+    /// The header byte of the packet and its payload.
+    data: Vec<u8>,
+}
+
+/// A BLE radio operation in progress.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(super) struct BleOperation {
+    kind: BleKind,
+    channel: u8,
+    p_params: Address,
+    p_output: Address,
+    params: BleParams,
+    device_address: [u8; ADDRESS_SIZE],
+    /// The advertising data, or `LLData` of the initiator.
+    data: Vec<u8>,
+    scan_rsp_data: Vec<u8>,
+    /// The peer of the initiator, which doesn't use the white list.
+    peer_address: [u8; ADDRESS_SIZE],
+    white_list: Vec<[u8; WHITE_LIST_ENTRY_SIZE]>,
+    tx_queue: Option<DataQueue>,
+    tx_entries: VecDeque<TxEntry>,
+    fetches: VecDeque<(Fetch, Address, usize)>,
+    /// The memory being read and its address.
+    fetching: Option<(Fetch, Address)>,
+    phase: Phase,
+
+    counters: EnumMap<Counter, u16>,
+    last_rssi: i8,
+    pkt_status: PktStatus,
+    time_stamp: u32,
+
+    seq_stat: SeqStat,
+    /// The last packet sent wasn't acknowledged yet.
+    awaiting_ack: bool,
+    /// An acknowledged LL control packet, whose acknowledgement is acknowledged by the next packet.
+    ctrl_ack_ack_pending: bool,
+    nacks: u8,
+    crc_errors: u8,
+    sent_packets: u8,
+    peer_more_data: bool,
+    /// The slave received a packet in this event.
+    synced: bool,
+
+    actions: Vec<BleAction>,
+    status: Option<CommandStatus>,
+    closed: bool,
+}
+
+impl BleOperation {
+    pub(super) fn new(kind: BleKind, channel: u8, p_params: Address, p_output: Address) -> Self {
+        let mut operation = Self {
+            kind,
+            channel,
+            p_params,
+            p_output,
+            params: BleParams::default(),
+            device_address: [0; ADDRESS_SIZE],
+            data: Vec::new(),
+            scan_rsp_data: Vec::new(),
+            peer_address: [0; ADDRESS_SIZE],
+            white_list: Vec::new(),
+            tx_queue: None,
+            tx_entries: VecDeque::new(),
+            fetches: VecDeque::new(),
+            fetching: None,
+            phase: Phase::Fetching,
+            counters: EnumMap::default(),
+            last_rssi: RSSI_UNKNOWN,
+            pkt_status: PktStatus::new(),
+            time_stamp: 0,
+            seq_stat: SeqStat::new(),
+            awaiting_ack: false,
+            ctrl_ack_ack_pending: false,
+            nacks: 0,
+            crc_errors: 0,
+            sent_packets: 0,
+            peer_more_data: false,
+            synced: false,
+            actions: Vec::new(),
+            status: None,
+            closed: false,
+        };
+        operation.fetch(Fetch::Params, p_params, kind.params_size());
+        if p_output.to_const() != 0 {
+            operation.fetch(Fetch::Output, p_output, kind.output_layout().size);
+        }
+        operation.next_fetch();
+        operation
+    }
+
+    pub(super) fn take_actions(&mut self) -> Vec<BleAction> {
+        std::mem::take(&mut self.actions)
+    }
+
+    /// The status the operation ended with.
+    pub(super) fn status(&self) -> Option<CommandStatus> {
+        self.status
+    }
+
+    pub(super) fn channel(&self) -> u8 {
+        self.channel
+    }
+
+    /// The access address and the CRC initialization of the packets.
+    pub(super) fn link(&self) -> (u32, u32) {
+        match self.kind {
+            BleKind::Advertiser(_) | BleKind::Scanner | BleKind::Initiator => {
+                (ADV_ACCESS_ADDRESS, ADV_CRC_INIT)
+            }
+            BleKind::Master | BleKind::Slave | BleKind::GenericRx => {
+                (self.params.access_address, self.params.crc_init)
+            }
+            BleKind::TxTest => (TEST_ACCESS_ADDRESS, ADV_CRC_INIT),
+        }
+    }
+
+    /// The RX queue, once the parameters are read.
+    pub(super) fn rx_queue(&self) -> Option<Address> {
+        let receives = self.kind != BleKind::TxTest && !matches!(self.phase, Phase::Fetching);
+        receives.then(|| Address::from(self.params.p_rx_q))
+    }
+
+    /// The TX queue of a connection.
+    pub(super) fn tx_queue(&self) -> Option<Address> {
+        let p_tx_q = self.params.p_tx_q;
+        (self.kind.is_connection() && p_tx_q != 0).then(|| Address::from(p_tx_q))
+    }
+
+    fn fetch(&mut self, fetch: Fetch, address: Address, size: usize) {
+        self.fetches.push_back((fetch, address, size));
+    }
+
+    fn next_fetch(&mut self) {
+        match self.fetches.pop_front() {
+            Some((fetch, address, size)) => {
+                self.fetching = Some((fetch, address));
+                self.actions.push(BleAction::Read { address, size });
+            }
+            None => self.begin(),
+        }
+    }
+
+    pub(super) fn on_read(&mut self, address: Address, data: &[u8]) {
+        let Some(fetch) = self
+            .fetching
+            .take_if(|&mut (_, fetched)| fetched == address)
+            .map(|(fetch, _)| fetch)
+        else {
+            warn!("Received memory at {address:?}, which the BLE operation doesn't wait for.");
+            return;
+        };
+        match fetch {
+            Fetch::Params => {
+                self.params = BleParams::from_bytes(self.kind, data);
+                if !self.check_params() {
+                    self.end(CommandStatus::BleErrorPar);
+                    return;
+                }
+                let trigger = |(trigger, time): (u8, u32)| (Trigger::from_bytes([trigger]), time);
+                self.actions.push(BleAction::Triggers {
+                    end: trigger((self.params.end_trigger, self.params.end_time)),
+                    timeout: self.params.timeout.map(trigger),
+                });
+                self.seq_stat = SeqStat::from_bytes([self.params.seq_stat]);
+                self.awaiting_ack = !self.seq_stat.b_first_pkt()
+                    && self.seq_stat.last_tx_sn() == self.seq_stat.next_tx_sn();
+                self.plan_fetches();
+            }
+            Fetch::Output => self.read_output(data),
+            Fetch::DeviceAddress => self.device_address.copy_from_slice(&data[..ADDRESS_SIZE]),
+            Fetch::PeerAddress => self.peer_address.copy_from_slice(&data[..ADDRESS_SIZE]),
+            Fetch::Data => self.data = data.to_vec(),
+            Fetch::ScanRspData => self.scan_rsp_data = data.to_vec(),
+            Fetch::WhiteListSize => {
+                let size = usize::from(data[0]);
+                if size > 1 {
+                    let address = Address::from(self.params.p_white_list);
+                    self.fetches.push_front((
+                        Fetch::WhiteList,
+                        address,
+                        size * WHITE_LIST_ENTRY_SIZE,
+                    ));
+                } else {
+                    self.read_white_list(&data[..WHITE_LIST_ENTRY_SIZE * size]);
+                }
+            }
+            Fetch::WhiteList => self.read_white_list(data),
+            Fetch::TxQueue => {
+                let queue = DataQueue::from_bytes(data);
+                self.tx_queue = Some(queue);
+                if !queue.is_empty() {
+                    self.fetches.push_front((
+                        Fetch::TxEntry(queue.p_curr_entry),
+                        queue.p_curr_entry,
+                        ENTRY_HEADER_SIZE,
+                    ));
+                }
+            }
+            Fetch::TxEntry(address) => self.read_tx_entry(address, data),
+            Fetch::TxData(address, header) => {
+                self.tx_entries.push_back(TxEntry {
+                    address,
+                    header,
+                    data: data.to_vec(),
+                });
+                let queue = self.tx_queue.expect("the queue is read before its entries");
+                let is_last = address == queue.p_last_entry
+                    || header.p_next_entry.to_const() == 0
+                    || header.p_next_entry == queue.p_curr_entry;
+                if !is_last && self.tx_entries.len() < MAX_TX_ENTRIES {
+                    let next = header.p_next_entry;
+                    self.fetches
+                        .push_front((Fetch::TxEntry(next), next, ENTRY_HEADER_SIZE));
+                }
+            }
+        }
+        self.next_fetch();
+    }
+
+    fn check_params(&self) -> bool {
+        let data_len = usize::from(self.params.data_len);
+        match self.kind {
+            BleKind::Advertiser(AdvPduType::AdvDirectInd) => data_len == ADDRESS_SIZE,
+            BleKind::Advertiser(_) => {
+                data_len <= MAX_ADV_DATA_SIZE
+                    && usize::from(self.params.scan_rsp_len) <= MAX_ADV_DATA_SIZE
+            }
+            BleKind::Initiator => data_len == LL_DATA_SIZE,
+            _ => true,
+        }
+    }
+
+    /// Reads the memory the parameters point to.
+    fn plan_fetches(&mut self) {
+        let params = self.params;
+        let address = Address::from;
+        match self.kind {
+            BleKind::Advertiser(adv_type) => {
+                self.fetch(
+                    Fetch::DeviceAddress,
+                    address(params.p_device_address),
+                    ADDRESS_SIZE,
+                );
+                self.fetch(Fetch::Data, address(params.p_data), params.data_len.into());
+                if adv_type.is_scannable() {
+                    self.fetch(
+                        Fetch::ScanRspData,
+                        address(params.p_scan_rsp_data),
+                        params.scan_rsp_len.into(),
+                    );
+                }
+                if params.adv_config().adv_filter_policy() != 0 {
+                    self.fetch(
+                        Fetch::WhiteListSize,
+                        address(params.p_white_list),
+                        WHITE_LIST_ENTRY_SIZE,
+                    );
+                }
+            }
+            BleKind::Scanner => {
+                self.fetch(
+                    Fetch::DeviceAddress,
+                    address(params.p_device_address),
+                    ADDRESS_SIZE,
+                );
+                if params.scan_config().scan_filter_policy() {
+                    self.fetch(
+                        Fetch::WhiteListSize,
+                        address(params.p_white_list),
+                        WHITE_LIST_ENTRY_SIZE,
+                    );
+                }
+            }
+            BleKind::Initiator => {
+                self.fetch(
+                    Fetch::DeviceAddress,
+                    address(params.p_device_address),
+                    ADDRESS_SIZE,
+                );
+                self.fetch(Fetch::Data, address(params.p_data), LL_DATA_SIZE);
+                if params.init_config().b_use_white_list() {
+                    self.fetch(
+                        Fetch::WhiteListSize,
+                        address(params.p_white_list),
+                        WHITE_LIST_ENTRY_SIZE,
+                    );
+                } else {
+                    self.fetch(
+                        Fetch::PeerAddress,
+                        address(params.p_white_list),
+                        ADDRESS_SIZE,
+                    );
+                }
+            }
+            BleKind::Master | BleKind::Slave => {
+                if let Some(p_tx_q) = self.tx_queue() {
+                    self.fetch(Fetch::TxQueue, p_tx_q, QUEUE_SIZE);
+                }
+            }
+            BleKind::GenericRx | BleKind::TxTest => {}
+        }
+        // Nothing is read for the empty data.
+        self.fetches.retain(|&(_, _, size)| size > 0);
+    }
+
+    fn read_output(&mut self, data: &[u8]) {
+        let layout = self.kind.output_layout();
+        for &(offset, counter, size) in layout.counters {
+            self.counters[counter] = match size {
+                1 => u16::from(data[offset]),
+                _ => u16::from_le_bytes([data[offset], data[offset + 1]]),
+            };
+        }
+        if let Some(offset) = layout.last_rssi {
+            self.last_rssi = i8::from_le_bytes([data[offset]]);
+        }
+        if let Some(offset) = layout.pkt_status {
+            self.pkt_status = PktStatus::from_bytes([data[offset]]);
+        }
+        if let Some(offset) = layout.time_stamp {
+            self.time_stamp =
+                u32::from_le_bytes(data[offset..offset + 4].try_into().expect("4 bytes"));
+        }
+    }
+
+    fn read_white_list(&mut self, data: &[u8]) {
+        self.white_list = data
+            .chunks_exact(WHITE_LIST_ENTRY_SIZE)
+            .map(|entry| entry.try_into().expect("the size of an entry"))
+            .collect();
+    }
+
+    fn read_tx_entry(&mut self, address: Address, data: &[u8]) {
+        let Some(header) = DataEntryHeader::from_bytes(data) else {
+            warn!("The TX entry at {address:?} has an invalid status.");
+            return;
+        };
+        let length = usize::from(header.length);
+        let usable = matches!(
+            header.status,
+            DataEntryStatus::Pending | DataEntryStatus::Active
+        ) && (1..=MAX_TX_ENTRY_SIZE).contains(&length);
+        let data_address = match header.config.r#type() {
+            DataEntryType::General => address.offset(GENERAL_ENTRY_DATA_OFFSET),
+            DataEntryType::Pointer => header.p_data(),
+            DataEntryType::Multielement | DataEntryType::Reserved => {
+                warn!(
+                    "The TX entry at {address:?} has unsupported type {:?}",
+                    header.config.r#type()
+                );
+                return;
+            }
+        };
+        if usable {
+            self.fetches
+                .push_front((Fetch::TxData(address, header), data_address, length));
+        }
+    }
+
+    fn begin(&mut self) {
+        match self.kind {
+            BleKind::Advertiser(_) => self.transmit(Sent::Adv, 0),
+            BleKind::Scanner | BleKind::Initiator => self.listen(Expect::Adv, None),
+            BleKind::Slave => self.listen(Expect::Data, None),
+            BleKind::Master => self.transmit(Sent::Data, 0),
+            BleKind::GenericRx => self.listen(Expect::Any, None),
+            BleKind::TxTest => self.transmit(Sent::Test, 0),
+        }
+    }
+
+    fn listen(&mut self, expect: Expect, timeout: Option<u32>) {
+        self.phase = Phase::Listening(expect);
+        self.actions.push(BleAction::Listen { timeout });
+    }
+
+    fn wait(&mut self, sent: Sent, ticks: u32) {
+        self.phase = Phase::Waiting(sent);
+        self.actions.push(BleAction::Wait { ticks });
+    }
+
+    fn interrupt(&mut self, interrupt: Interrupt) {
+        self.actions.push(BleAction::Interrupt(interrupt));
+    }
+
+    fn count(&mut self, counter: Counter) {
+        self.counters[counter] = self.counters[counter].saturating_add(1);
+    }
+
+    /// Ends the operation, once its packets are stored.
+    fn end(&mut self, status: CommandStatus) {
+        trace!("{:?} ends with {status:?}", self.kind);
+        self.phase = Phase::Ended;
+        self.status = Some(status);
+        self.write_output();
+    }
+
+    /// Writes back the parameters updated by the operation, when it ends.
+    pub(super) fn close(&mut self) {
+        if std::mem::replace(&mut self.closed, true)
+            || matches!(self.fetching, Some((Fetch::Params, _)))
+        {
+            return;
+        }
+        let params = self.params;
+        let writes: Vec<(u32, Vec<u8>)> = match self.kind {
+            BleKind::Scanner => vec![
+                (6, params.random_state.to_le_bytes().to_vec()),
+                (8, params.backoff_count.to_le_bytes().to_vec()),
+                (10, vec![params.backoff_par]),
+            ],
+            BleKind::Initiator if params.init_config().b_dynamic_win_offset() => {
+                vec![(20, params.connect_time.to_le_bytes().to_vec())]
+            }
+            BleKind::Master | BleKind::Slave => vec![(9, self.seq_stat.into_bytes().to_vec())],
+            _ => Vec::new(),
+        };
+        for (offset, data) in writes {
+            let address = self.p_params.offset(offset);
+            self.actions.push(BleAction::Write { address, data });
+        }
+        self.write_output();
+    }
+
+    fn write_output(&mut self) {
+        if self.p_output.to_const() == 0 {
+            return;
+        }
+        let layout = self.kind.output_layout();
+        let mut data = vec![0u8; layout.size];
+        for &(offset, counter, size) in layout.counters {
+            let value = self.counters[counter];
+            match size {
+                1 => data[offset] = u8::try_from(value).unwrap_or(u8::MAX),
+                _ => data[offset..offset + 2].copy_from_slice(&value.to_le_bytes()),
+            }
+        }
+        if let Some(offset) = layout.last_rssi {
+            data[offset] = self.last_rssi.to_le_bytes()[0];
+        }
+        if let Some(offset) = layout.pkt_status {
+            data[offset] = self.pkt_status.into_bytes()[0];
+        }
+        if let Some(offset) = layout.time_stamp {
+            data[offset..offset + 4].copy_from_slice(&self.time_stamp.to_le_bytes());
+        }
+        let address = self.p_output;
+        self.actions.push(BleAction::Write { address, data });
+    }
+}
+
+// Sending the packets.
+impl BleOperation {
+    fn transmit(&mut self, sent: Sent, now: u32) {
+        let pdu = match sent {
+            Sent::Adv => {
+                let BleKind::Advertiser(adv_type) = self.kind else {
+                    unreachable!("only the advertisers advertise")
+                };
+                let config = self.params.adv_config();
+                let mut header =
+                    adv_type as u8 | address_type(config.device_addr_type(), ADV_TX_ADD);
+                if adv_type == AdvPduType::AdvDirectInd {
+                    header |= address_type(config.peer_addr_type(), ADV_RX_ADD);
+                }
+                adv_pdu(header, &[&self.device_address, &self.data])
+            }
+            Sent::ScanRsp => {
+                let device_addr_type = self.params.adv_config().device_addr_type();
+                let header = AdvPduType::ScanRsp as u8 | address_type(device_addr_type, ADV_TX_ADD);
+                adv_pdu(header, &[&self.device_address, &self.scan_rsp_data])
+            }
+            Sent::ScanReq(peer) => {
+                let device_addr_type = self.params.scan_config().device_addr_type();
+                let header = AdvPduType::ScanReq as u8
+                    | address_type(device_addr_type, ADV_TX_ADD)
+                    | address_type(peer.random, ADV_RX_ADD);
+                adv_pdu(header, &[&self.device_address, &peer.address])
+            }
+            Sent::ConnectInd(peer) => {
+                let device_addr_type = self.params.init_config().device_addr_type();
+                let header = AdvPduType::ConnectInd as u8
+                    | address_type(device_addr_type, ADV_TX_ADD)
+                    | address_type(peer.random, ADV_RX_ADD);
+                let ll_data = self.ll_data(now);
+                adv_pdu(header, &[&self.device_address, &peer.address, &ll_data])
+            }
+            Sent::Data => self.data_pdu(),
+            Sent::Test => self.test_pdu(),
+        };
+        trace!("{:?} sends {sent:?}: {pdu:02x?}", self.kind);
+        self.phase = Phase::Transmitting(sent);
+        self.actions.push(BleAction::Transmit { pdu });
+    }
+
+    /// `LLData` of `CONNECT_IND` sent at `now`, with the window offset moved to the next
+    /// connection event, which may still start after it, if it's dynamic.
+    fn ll_data(&mut self, now: u32) -> Vec<u8> {
+        let mut ll_data = self.data.clone();
+        ll_data.resize(LL_DATA_SIZE, 0);
+        if !self.params.init_config().b_dynamic_win_offset() {
+            return ll_data;
+        }
+        let pdu_len = 2 + 2 * ADDRESS_SIZE + LL_DATA_SIZE;
+        // The transmit window starts 1.25 ms after the end of the packet.
+        let window_start = now
+            .wrapping_add(air_time_ticks(pdu_len))
+            .wrapping_add(CONNECTION_UNIT_TICKS);
+        let interval =
+            u32::from(u16::from_le_bytes([ll_data[10], ll_data[11]])) * CONNECTION_UNIT_TICKS;
+        let mut anchor = self.params.connect_time;
+        let late = window_start.wrapping_sub(anchor);
+        // The anchor is at most half of the period of the RAT in the past.
+        #[allow(clippy::cast_possible_wrap)]
+        if late as i32 > 0 {
+            anchor = if interval == 0 {
+                window_start
+            } else {
+                anchor.wrapping_add(late.div_ceil(interval) * interval)
+            };
+        }
+        let win_offset = anchor.wrapping_sub(window_start) / CONNECTION_UNIT_TICKS;
+        let win_offset = u16::try_from(win_offset).unwrap_or(u16::MAX);
+        ll_data[8..10].copy_from_slice(&win_offset.to_le_bytes());
+        self.params.connect_time = anchor;
+        ll_data
+    }
+
+    /// The packet of a connection: the first entry of the TX queue, or an empty one.
+    fn data_pdu(&mut self) -> Vec<u8> {
+        let more_data = self.tx_entries.len() > 1;
+        let (llid, payload) = match self.tx_entries.front_mut() {
+            Some(entry) => {
+                if entry.header.status == DataEntryStatus::Pending {
+                    entry.header.status = DataEntryStatus::Active;
+                    let address = entry.address.offset(4);
+                    let data = vec![DataEntryStatus::Active.into()];
+                    self.actions.push(BleAction::Write { address, data });
+                }
+                (entry.data[0] & DATA_LLID_MASK, entry.data[1..].to_vec())
+            }
+            None => (LLID_CONTINUATION, Vec::new()),
+        };
+        let seq_stat = self.seq_stat;
+        let mut header = llid;
+        if !seq_stat.last_rx_sn() {
+            header |= DATA_NESN;
+        }
+        if seq_stat.next_tx_sn() {
+            header |= DATA_SN;
+        }
+        if more_data {
+            header |= DATA_MD;
+        }
+        let mut pdu = vec![
+            header,
+            u8::try_from(payload.len()).expect("checked when read"),
+        ];
+        pdu.extend(payload);
+        pdu
+    }
+
+    /// [TI-TRM-I] Bluetooth Low Energy Transmitter Test Command
+    fn test_pdu(&self) -> Vec<u8> {
+        let params = self.params;
+        let config = params.test_config();
+        let length = usize::from(params.payload_length);
+        let payload = if config.b_use_prbs9()
+            || (!config.b_override_default() && params.packet_type == 0)
+        {
+            prbs(9, 5, length)
+        } else if config.b_use_prbs15() || (!config.b_override_default() && params.packet_type == 3)
+        {
+            prbs(15, 14, length)
+        } else {
+            let byte = if config.b_override_default() {
+                params.byte_val
+            } else {
+                match params.packet_type {
+                    1 => 0x0F,
+                    2 => 0x55,
+                    4 => 0xFF,
+                    5 => 0x00,
+                    6 => 0xF0,
+                    _ => 0xAA,
+                }
+            };
+            vec![byte; length]
+        };
+        let mut pdu = vec![params.packet_type, params.payload_length];
+        pdu.extend(payload);
+        pdu
+    }
+
+    pub(super) fn on_wait_done(&mut self, now: u32) {
+        if let Phase::Waiting(sent) = self.phase {
+            self.transmit(sent, now);
+        }
+    }
+
+    pub(super) fn on_tx_done(&mut self) {
+        let Phase::Transmitting(sent) = self.phase else {
+            warn!("The BLE operation didn't send anything.");
+            return;
+        };
+        self.interrupt(Interrupt::TxDone);
+        match sent {
+            Sent::Adv => {
+                self.count(Counter::TxAdvInd);
+                self.write_output();
+                match self.kind {
+                    BleKind::Advertiser(AdvPduType::AdvNonconnInd) => {
+                        self.end(CommandStatus::BleDoneOk);
+                    }
+                    _ => self.listen(Expect::Request, Some(RESPONSE_TIMEOUT_TICKS)),
+                }
+            }
+            Sent::ScanRsp => {
+                self.count(Counter::TxScanRsp);
+                self.end(CommandStatus::BleDoneOk);
+            }
+            Sent::ScanReq(peer) => {
+                self.count(Counter::TxScanReq);
+                self.write_output();
+                self.listen(Expect::ScanRsp(peer), Some(RESPONSE_TIMEOUT_TICKS));
+            }
+            Sent::ConnectInd(_) => {
+                self.count(Counter::TxConnectReq);
+                self.end(CommandStatus::BleDoneConnect);
+            }
+            Sent::Data => self.on_data_sent(),
+            Sent::Test => {
+                self.count(Counter::Tx);
+                self.write_output();
+                let num_packets = self.params.num_packets;
+                if num_packets != 0 && self.counters[Counter::Tx] >= num_packets {
+                    self.end(CommandStatus::BleDoneOk);
+                } else {
+                    let air_time = air_time_ticks(2 + usize::from(self.params.payload_length));
+                    self.wait(Sent::Test, self.params.period.saturating_sub(air_time));
+                }
+            }
+        }
+    }
+
+    fn on_data_sent(&mut self) {
+        let auto_empty = self.tx_entries.is_empty();
+        let control = self
+            .tx_entries
+            .front()
+            .is_some_and(|entry| entry.data[0] & DATA_LLID_MASK == LLID_CONTROL);
+        if self.awaiting_ack {
+            self.count(Counter::TxRetrans);
+            self.interrupt(Interrupt::TxRetrans);
+        }
+        self.count(Counter::Tx);
+        if control {
+            self.count(Counter::TxCtrl);
+            self.interrupt(Interrupt::TxCtrl);
+        }
+        // The packet acknowledges the LL control packet received before.
+        if self.seq_stat.b_ll_ctrl_ack_pending() {
+            self.seq_stat.set_b_ll_ctrl_ack_pending(false);
+            self.count(Counter::RxCtrlAck);
+            self.interrupt(Interrupt::RxCtrlAck);
+        }
+        if std::mem::take(&mut self.ctrl_ack_ack_pending) {
+            self.count(Counter::TxCtrlAckAck);
+            self.interrupt(Interrupt::TxCtrlAckAck);
+        }
+        let next_tx_sn = self.seq_stat.next_tx_sn();
+        self.seq_stat.set_last_tx_sn(next_tx_sn);
+        self.seq_stat.set_b_auto_empty(auto_empty);
+        self.seq_stat.set_b_ll_ctrl_tx(control);
+        self.awaiting_ack = true;
+        self.sent_packets = self.sent_packets.saturating_add(1);
+        self.write_output();
+        match self.kind {
+            BleKind::Master => self.listen(Expect::Data, Some(RESPONSE_TIMEOUT_TICKS)),
+            _ if self.event_continues() => self.listen(Expect::Data, Some(RESPONSE_TIMEOUT_TICKS)),
+            _ => self.end(CommandStatus::BleDoneOk),
+        }
+    }
+
+    /// Whether another pair of packets is exchanged in the connection event.
+    fn event_continues(&self) -> bool {
+        let more_data = self.peer_more_data || !self.tx_entries.is_empty();
+        let max_pkt = self.params.max_pkt;
+        more_data && (max_pkt == 0 || self.sent_packets < max_pkt)
+    }
+}
+
+// Receiving the packets.
+impl BleOperation {
+    pub(super) fn on_packet(&mut self, packet: &BlePacket, now: u32) {
+        let Phase::Listening(expect) = self.phase else {
+            return;
+        };
+        if packet.access_address != self.link().0 {
+            trace!("Ignoring a packet to {:#x}", packet.access_address);
+            return;
+        }
+        self.last_rssi = packet.rssi;
+        let header = match packet.pdu.as_slice() {
+            [header, length, payload @ ..]
+                if packet.crc_ok && usize::from(*length) == payload.len() =>
+            {
+                Some(*header)
+            }
+            _ => None,
+        };
+        let Some(header) = header else {
+            self.on_rx_error(expect, packet, now);
+            self.write_output();
+            return;
+        };
+        match self.kind {
+            BleKind::Advertiser(adv_type) => self.on_request(adv_type, header, packet, now),
+            BleKind::Scanner => self.on_scanned(expect, header, packet, now),
+            BleKind::Initiator => self.on_initiated(header, packet, now),
+            BleKind::Master | BleKind::Slave => self.on_data_packet(header, packet, now),
+            BleKind::GenericRx => {
+                let report = RxReport::new(Counter::RxOk, Counter::RxBufFull, Interrupt::RxOk);
+                self.store(packet, now, false, Some(report));
+                if self.params.b_repeat == 0 {
+                    self.end(CommandStatus::BleDoneOk);
+                }
+            }
+            BleKind::TxTest => {}
+        }
+        self.write_output();
+    }
+
+    pub(super) fn on_listen_timeout(&mut self, _now: u32) {
+        let Phase::Listening(expect) = self.phase else {
+            return;
+        };
+        match (self.kind, expect) {
+            (BleKind::Scanner, Expect::ScanRsp(_)) => {
+                self.update_backoff(false);
+                self.after_report();
+            }
+            (BleKind::Master | BleKind::Slave, _) => self.end(CommandStatus::BleDoneNoSync),
+            _ => self.end(CommandStatus::BleDoneOk),
+        }
+    }
+
+    /// The timeout trigger ends the scanning, or the slave, which didn't hear the master.
+    pub(super) fn on_timeout_trigger(&mut self, _now: u32) {
+        if self.status.is_some() || (self.kind == BleKind::Slave && self.synced) {
+            return;
+        }
+        self.end(CommandStatus::BleDoneRxTimeout);
+    }
+
+    fn on_rx_error(&mut self, expect: Expect, packet: &BlePacket, now: u32) {
+        let counter = match (self.kind, expect) {
+            (BleKind::Scanner, Expect::ScanRsp(_)) => Counter::RxScanRspNok,
+            (BleKind::Scanner | BleKind::Initiator, _) => Counter::RxAdvNok,
+            _ => Counter::RxNok,
+        };
+        self.count(counter);
+        self.interrupt(Interrupt::RxNok);
+        self.store(packet, now, false, None);
+        match self.kind {
+            BleKind::Advertiser(_) => self.end(CommandStatus::BleDoneRxErr),
+            BleKind::Scanner if matches!(expect, Expect::ScanRsp(_)) => {
+                self.update_backoff(false);
+                self.after_report();
+            }
+            BleKind::Master | BleKind::Slave => {
+                self.pkt_status.set_b_last_crc_err(true);
+                self.crc_errors += 1;
+                if self.crc_errors >= MAX_CRC_ERRORS {
+                    self.end(CommandStatus::BleDoneRxErr);
+                } else if self.kind == BleKind::Master && !self.event_continues() {
+                    self.end(CommandStatus::BleDoneOk);
+                } else {
+                    self.synced |= self.kind == BleKind::Slave;
+                    self.wait(Sent::Data, T_IFS_TICKS);
+                }
+            }
+            BleKind::GenericRx if self.params.b_repeat == 0 => self.end(CommandStatus::BleDoneOk),
+            _ => {}
+        }
+    }
+
+    /// Counts and stores the packet, which the operation ignores.
+    fn ignore(&mut self, counter: Counter, packet: &BlePacket, now: u32) {
+        self.count(counter);
+        self.interrupt(Interrupt::RxIgnored);
+        self.store(packet, now, true, None);
+    }
+
+    /// Hands the packet to be stored in the RX queue, unless it's flushed.
+    fn store(&mut self, packet: &BlePacket, now: u32, ignored: bool, report: Option<RxReport>) {
+        let rx_config = self.params.rx_config();
+        let empty = self.kind.is_connection() && packet.pdu.get(1) == Some(&0);
+        let flushed = (ignored && rx_config.b_auto_flush_ignored())
+            || (!packet.crc_ok && rx_config.b_auto_flush_crc_err())
+            || (empty && rx_config.b_auto_flush_empty());
+        if report.is_some() && !(self.kind.is_connection() && self.pkt_status.b_time_stamp_valid())
+        {
+            self.time_stamp = now;
+            self.pkt_status.set_b_time_stamp_valid(true);
+        }
+        if flushed {
+            if let Some(report) = report {
+                self.on_rx_stored(report);
+            }
+            return;
+        }
+
+        let (header, rest) = packet.pdu.split_at(packet.pdu.len().min(1));
+        let mut data = header.to_vec();
+        let (length, payload) = rest.split_at(rest.len().min(1));
+        if rx_config.b_include_len_byte() {
+            data.extend_from_slice(length);
+        }
+        data.extend_from_slice(payload);
+        if rx_config.b_include_crc() {
+            let (_, crc_init) = self.link();
+            let mut crc = crc24(crc_init, &packet.pdu);
+            if !packet.crc_ok {
+                crc[0] ^= 1;
+            }
+            data.extend_from_slice(&crc);
+        }
+        if rx_config.b_append_rssi() {
+            data.push(packet.rssi.to_le_bytes()[0]);
+        }
+        if rx_config.b_append_status() {
+            let mut status = self.channel & 0x3F;
+            if ignored {
+                status |= 1 << 6;
+            }
+            if !packet.crc_ok {
+                status |= 1 << 7;
+            }
+            data.push(status);
+        }
+        if rx_config.b_append_timestamp() {
+            data.extend_from_slice(&now.to_le_bytes());
+        }
+        self.actions.push(BleAction::StoreRx { data, report });
+    }
+
+    pub(super) fn on_rx_stored(&mut self, report: RxReport) {
+        self.count(report.ok);
+        self.interrupt(report.interrupt);
+        self.write_output();
+    }
+
+    pub(super) fn on_rx_buffer_full(&mut self, report: Option<RxReport>) {
+        if let Some(report) = report {
+            self.count(report.buf_full);
+            self.write_output();
+        }
+    }
+
+    fn device_addr_type(&self) -> bool {
+        match self.kind {
+            BleKind::Advertiser(_) => self.params.adv_config().device_addr_type(),
+            BleKind::Scanner => self.params.scan_config().device_addr_type(),
+            BleKind::Initiator => self.params.init_config().device_addr_type(),
+            _ => false,
+        }
+    }
+
+    /// Whether the address of a received PDU is the one of the device.
+    fn is_own_address(&self, address: &[u8], random: bool) -> bool {
+        address == self.device_address && random == self.device_addr_type()
+    }
+
+    /// Index of the enabled entry of the white list with the address.
+    fn white_list_index(&self, peer: Peer) -> Option<usize> {
+        self.white_list.iter().position(|entry| {
+            let conf = entry[1];
+            conf & WHITE_LIST_ENABLE != 0
+                && conf & WHITE_LIST_IGNORE == 0
+                && (conf & WHITE_LIST_RANDOM != 0) == peer.random
+                && entry[2..] == peer.address
+        })
+    }
+
+    /// `SCAN_REQ` or `CONNECT_IND` sent to the advertiser.
+    fn on_request(&mut self, adv_type: AdvPduType, header: u8, packet: &BlePacket, now: u32) {
+        let payload = &packet.pdu[2..];
+        let config = self.params.adv_config();
+        let request = AdvPduType::try_from(header & ADV_PDU_TYPE_MASK).ok();
+        let (sender, target) = match payload {
+            [sender @ .., _] if payload.len() < 2 * ADDRESS_SIZE => (sender, &[][..]),
+            _ => payload.split_at(ADDRESS_SIZE),
+        };
+        let to_us = target.len() >= ADDRESS_SIZE
+            && self.is_own_address(&target[..ADDRESS_SIZE], header & ADV_RX_ADD != 0);
+        let peer = peer_at(sender, header & ADV_TX_ADD != 0);
+        let filtered = |policy_bit: u8| config.adv_filter_policy() & policy_bit != 0;
+        match request {
+            Some(AdvPduType::ScanReq)
+                if payload.len() == 2 * ADDRESS_SIZE
+                    && adv_type.is_scannable()
+                    && to_us
+                    && (!filtered(0b01)
+                        || peer.is_some_and(|peer| self.white_list_index(peer).is_some())) =>
+            {
+                let report = RxReport::new(Counter::RxScanReq, Counter::RxBufFull, Interrupt::RxOk);
+                self.store(packet, now, false, Some(report));
+                self.wait(Sent::ScanRsp, T_IFS_TICKS);
+            }
+            Some(AdvPduType::ConnectInd)
+                if payload.len() == 2 * ADDRESS_SIZE + LL_DATA_SIZE
+                    && adv_type.is_connectable()
+                    && to_us
+                    && peer.is_some_and(|peer| self.accepts_initiator(adv_type, peer)) =>
+            {
+                let report =
+                    RxReport::new(Counter::RxConnectInd, Counter::RxBufFull, Interrupt::RxOk);
+                self.store(packet, now, false, Some(report));
+                self.end(CommandStatus::BleDoneConnect);
+            }
+            _ => {
+                self.ignore(Counter::RxIgnored, packet, now);
+                self.end(CommandStatus::BleDoneOk);
+            }
+        }
+    }
+
+    fn accepts_initiator(&self, adv_type: AdvPduType, peer: Peer) -> bool {
+        let config = self.params.adv_config();
+        if adv_type == AdvPduType::AdvDirectInd {
+            // The advertising data of ADV_DIRECT_IND is the address of the peer.
+            peer.address == self.data[..] && peer.random == config.peer_addr_type()
+        } else {
+            config.adv_filter_policy() & 0b10 == 0 || self.white_list_index(peer).is_some()
+        }
+    }
+
+    /// An advertising PDU with its advertiser, if it's addressed to the device.
+    fn advertiser(&self, header: u8, payload: &[u8]) -> Option<(AdvPduType, Peer)> {
+        let adv_type = AdvPduType::try_from(header & ADV_PDU_TYPE_MASK).ok()?;
+        let peer = peer_at(payload, header & ADV_TX_ADD != 0)?;
+        let addressed = match adv_type {
+            AdvPduType::AdvDirectInd => {
+                payload.len() == 2 * ADDRESS_SIZE
+                    && self.is_own_address(&payload[ADDRESS_SIZE..], header & ADV_RX_ADD != 0)
+            }
+            AdvPduType::AdvInd | AdvPduType::AdvNonconnInd | AdvPduType::AdvScanInd => true,
+            _ => false,
+        };
+        addressed.then_some((adv_type, peer))
+    }
+
+    fn on_scanned(&mut self, expect: Expect, header: u8, packet: &BlePacket, now: u32) {
+        let payload = &packet.pdu[2..];
+        if let Expect::ScanRsp(advertiser) = expect {
+            let response = header & ADV_PDU_TYPE_MASK == AdvPduType::ScanRsp as u8
+                && peer_at(payload, header & ADV_TX_ADD != 0) == Some(advertiser);
+            if response {
+                let report = RxReport::new(
+                    Counter::RxScanRspOk,
+                    Counter::RxScanRspBufFull,
+                    Interrupt::RxOk,
+                );
+                self.store(packet, now, false, Some(report));
+                self.update_backoff(true);
+                if self.params.scan_config().b_auto_wl_ignore() {
+                    self.ignore_in_white_list(advertiser);
+                }
+            } else {
+                self.ignore(Counter::RxScanRspIgnored, packet, now);
+                self.update_backoff(false);
+            }
+            self.after_report();
+            return;
+        }
+
+        let config = self.params.scan_config();
+        let accepted = self.advertiser(header, payload).filter(|(_, peer)| {
+            !config.scan_filter_policy() || self.white_list_index(*peer).is_some()
+        });
+        let Some((adv_type, peer)) = accepted else {
+            self.ignore(Counter::RxAdvIgnored, packet, now);
+            return;
+        };
+        let report = RxReport::new(Counter::RxAdvOk, Counter::RxAdvBufFull, Interrupt::RxOk);
+        self.store(packet, now, false, Some(report));
+        if !(config.b_active_scan() && adv_type.is_scannable()) {
+            self.after_report();
+        } else if self.params.backoff_count > 1 {
+            self.params.backoff_count -= 1;
+            self.count(Counter::BackedOffScanReq);
+            self.after_report();
+        } else {
+            self.wait(Sent::ScanReq(peer), T_IFS_TICKS);
+        }
+    }
+
+    /// The scanner goes on, unless it ends after each report.
+    fn after_report(&mut self) {
+        if self.params.scan_config().b_end_on_rpt() {
+            self.end(CommandStatus::BleDoneOk);
+        } else {
+            self.listen(Expect::Adv, None);
+        }
+    }
+
+    /// The backoff of the scan requests, which the link layer of Bluetooth specifies.
+    fn update_backoff(&mut self, succeeded: bool) {
+        let params = &mut self.params;
+        let mut backoff = BackoffPar::from_bytes([params.backoff_par]);
+        let log = backoff.log_upper_limit();
+        if succeeded {
+            if backoff.b_last_succeeded() {
+                backoff.set_log_upper_limit(log.saturating_sub(1));
+                backoff.set_b_last_succeeded(false);
+            } else {
+                backoff.set_b_last_succeeded(true);
+            }
+            backoff.set_b_last_failed(false);
+        } else {
+            if backoff.b_last_failed() {
+                backoff.set_log_upper_limit((log + 1).min(MAX_LOG_UPPER_LIMIT));
+                backoff.set_b_last_failed(false);
+            } else {
+                backoff.set_b_last_failed(true);
+            }
+            backoff.set_b_last_succeeded(false);
+        }
+        if params.random_state == 0 {
+            params.random_state = CSMA_RANDOM_SEED;
+        }
+        params.random_state = next_random(params.random_state);
+        let upper_limit = 1u16 << backoff.log_upper_limit();
+        params.backoff_count = 1 + (params.random_state & (upper_limit - 1));
+        params.backoff_par = backoff.into_bytes()[0];
+    }
+
+    fn ignore_in_white_list(&mut self, peer: Peer) {
+        let Some(index) = self.white_list_index(peer) else {
+            return;
+        };
+        let conf = &mut self.white_list[index][1];
+        *conf |= WHITE_LIST_IGNORE;
+        let data = vec![*conf];
+        let offset = u32::try_from(index * WHITE_LIST_ENTRY_SIZE + 1).expect("a short list");
+        let address = Address::from(self.params.p_white_list).offset(offset);
+        self.actions.push(BleAction::Write { address, data });
+    }
+
+    fn on_initiated(&mut self, header: u8, packet: &BlePacket, now: u32) {
+        let payload = &packet.pdu[2..];
+        let config = self.params.init_config();
+        let accepted = self.advertiser(header, payload).filter(|(adv_type, peer)| {
+            adv_type.is_connectable()
+                && if config.b_use_white_list() {
+                    self.white_list_index(*peer).is_some()
+                } else {
+                    peer.address == self.peer_address && peer.random == config.peer_addr_type()
+                }
+        });
+        let Some((_, peer)) = accepted else {
+            self.ignore(Counter::RxAdvIgnored, packet, now);
+            return;
+        };
+        let report = RxReport::new(Counter::RxAdvOk, Counter::RxAdvBufFull, Interrupt::RxOk);
+        self.store(packet, now, false, Some(report));
+        self.wait(Sent::ConnectInd(peer), T_IFS_TICKS);
+    }
+
+    fn on_data_packet(&mut self, header: u8, packet: &BlePacket, now: u32) {
+        self.crc_errors = 0;
+        self.synced = true;
+        let llid = header & DATA_LLID_MASK;
+        let sn = header & DATA_SN != 0;
+        let nesn = header & DATA_NESN != 0;
+        self.peer_more_data = header & DATA_MD != 0;
+
+        // The peer expects the next packet, so the last one was received.
+        let acked = self.awaiting_ack && nesn != self.seq_stat.next_tx_sn();
+        if acked {
+            self.on_acked();
+        } else if self.awaiting_ack {
+            self.nacks = self.nacks.saturating_add(1);
+        }
+        let new = self.seq_stat.b_first_pkt() || sn != self.seq_stat.last_rx_sn();
+        self.seq_stat.set_b_first_pkt(false);
+        let empty = packet.pdu[1] == 0;
+        let control = llid == LLID_CONTROL && !empty;
+        self.pkt_status = self
+            .pkt_status
+            .with_b_last_crc_err(false)
+            .with_b_last_ignored(!new)
+            .with_b_last_empty(empty)
+            .with_b_last_ctrl(control)
+            .with_b_last_md(self.peer_more_data)
+            .with_b_last_ack(acked);
+        if new {
+            self.seq_stat.set_last_rx_sn(sn);
+            self.seq_stat.set_b_ll_ctrl_ack_pending(control);
+            let report = if empty {
+                RxReport::new(Counter::RxEmpty, Counter::RxBufFull, Interrupt::RxEmpty)
+            } else if control {
+                RxReport::new(Counter::RxCtrl, Counter::RxBufFull, Interrupt::RxCtrl)
+            } else {
+                RxReport::new(Counter::RxOk, Counter::RxBufFull, Interrupt::RxOk)
+            };
+            self.store(packet, now, false, Some(report));
+        } else {
+            self.ignore(Counter::RxIgnored, packet, now);
+        }
+
+        let max_nack = self.params.max_nack;
+        if max_nack != 0 && self.nacks >= max_nack {
+            self.end(CommandStatus::BleDoneMaxNack);
+        } else if self.kind == BleKind::Master && !self.event_continues() {
+            self.end(CommandStatus::BleDoneOk);
+        } else {
+            // The slave answers every packet of the master.
+            self.wait(Sent::Data, T_IFS_TICKS);
+        }
+    }
+
+    /// The last packet sent was acknowledged, so its entry is done.
+    fn on_acked(&mut self) {
+        self.awaiting_ack = false;
+        let next_tx_sn = self.seq_stat.next_tx_sn();
+        self.seq_stat.set_next_tx_sn(!next_tx_sn);
+        self.count(Counter::TxAck);
+        self.interrupt(Interrupt::TxAck);
+        let control = self.seq_stat.b_ll_ctrl_tx();
+        self.seq_stat.set_b_ll_ctrl_ack_rx(control);
+        if control {
+            self.count(Counter::TxCtrlAck);
+            self.interrupt(Interrupt::TxCtrlAck);
+            self.ctrl_ack_ack_pending = true;
+        }
+        if self.seq_stat.b_auto_empty() {
+            return;
+        }
+        let (Some(entry), Some(queue), Some(p_tx_q)) =
+            (self.tx_entries.pop_front(), self.tx_queue, self.tx_queue())
+        else {
+            return;
+        };
+        let next_entry = if entry.address == queue.p_last_entry {
+            Address::from(0)
+        } else {
+            entry.header.p_next_entry
+        };
+        self.actions.push(BleAction::Write {
+            address: entry.address.offset(4),
+            data: vec![DataEntryStatus::Finished.into()],
+        });
+        self.actions.push(BleAction::Write {
+            address: p_tx_q,
+            data: next_entry.to_const().to_le_bytes().to_vec(),
+        });
+        self.tx_queue = Some(DataQueue {
+            p_curr_entry: next_entry,
+            ..queue
+        });
+        self.count(Counter::TxEntryDone);
+        self.interrupt(Interrupt::TxEntryDone);
+    }
+}
+
+/// The flag of a random address in the header of an advertising PDU.
+fn address_type(random: bool, flag: u8) -> u8 {
+    if random { flag } else { 0 }
+}
+
+fn adv_pdu(header: u8, parts: &[&[u8]]) -> Vec<u8> {
+    let payload = parts.concat();
+    let mut pdu = vec![
+        header,
+        u8::try_from(payload.len()).expect("checked lengths"),
+    ];
+    pdu.extend(payload);
+    pdu
+}
+
+/// The address at the start of a payload.
+fn peer_at(payload: &[u8], random: bool) -> Option<Peer> {
+    let address = payload
+        .get(..ADDRESS_SIZE)?
+        .try_into()
+        .expect("the size of an address");
+    Some(Peer { address, random })
+}
+
+/// The pseudo-random sequence of the test packets, from a Fibonacci LFSR with the taps.
+fn prbs(bits: u32, tap: u32, length: usize) -> Vec<u8> {
+    let mut state: u32 = (1 << bits) - 1;
+    (0..length)
+        .map(|_| {
+            (0..8).fold(0u8, |byte, bit| {
+                let out = state & 1;
+                let feedback = (state ^ (state >> (bits - tap))) & 1;
+                state = (state >> 1) | (feedback << (bits - 1));
+                byte | (u8::try_from(out).expect("a bit") << bit)
+            })
+        })
+        .collect()
+}
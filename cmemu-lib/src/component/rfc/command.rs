@@ -2,15 +2,18 @@ use crate::component::rfc::{CommandStatusHandle, ResultByte};
 use cmemu_common::Address;
 use modular_bitfield::prelude::*;
 use num_enum::{IntoPrimitive, TryFromPrimitive};
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub(super) struct Command {
     pub(super) id: CommandId,
     pub(super) data: CommandData,
 }
 
 /// [TI-TRM-I] Table 23-2. Common Radio Operation Status Codes
-#[derive(Debug, PartialEq, Clone, Copy, IntoPrimitive, TryFromPrimitive)]
+#[derive(
+    Debug, PartialEq, Clone, Copy, IntoPrimitive, TryFromPrimitive, Serialize, Deserialize,
+)]
 #[repr(u16)]
 pub(super) enum CommandStatus {
     // Operation not finished
@@ -94,9 +97,74 @@ pub(super) enum CommandStatus {
     IeeeErrorRxovf = 0x2804,
     // TX underflow observed during operation
     IeeeErrorTxunf = 0x2805,
+
+    // [TI-TRM-I] BLE Radio Operation Status Codes
+    /// Operation ended normally.
+    BleDoneOk = 0x1400,
+    /// Timeout of first RX of slave operation or end of scan window.
+    BleDoneRxTimeout = 0x1401,
+    /// Timeout of subsequent RX.
+    BleDoneNoSync = 0x1402,
+    /// Operation ended because of receive error (CRC or other).
+    BleDoneRxErr = 0x1403,
+    /// `CONNECT_IND` received or transmitted.
+    BleDoneConnect = 0x1404,
+    /// Maximum number of retransmissions exceeded.
+    BleDoneMaxNack = 0x1405,
+    /// Operation stopped after end trigger.
+    BleDoneEnded = 0x1406,
+    /// Operation aborted by command.
+    BleDoneAbort = 0x1407,
+    /// Operation stopped after stop command.
+    BleDoneStopped = 0x1408,
+    /// Illegal parameter.
+    BleErrorPar = 0x1800,
+    /// No available RX buffer (advertiser, scanner, initiator).
+    BleErrorRxBuf = 0x1801,
+    /// Operation using RX or TX attempted when not in BLE mode.
+    BleErrorNoSetup = 0x1802,
+    /// Operation using RX or TX attempted without frequency synth configured.
+    BleErrorNoFs = 0x1803,
+    /// Synthesizer programming failed to complete on time.
+    BleErrorSynthProg = 0x1804,
+    /// RX overflow observed during operation.
+    BleErrorRxOvf = 0x1805,
+    /// TX underflow observed during operation.
+    BleErrorTxUnf = 0x1806,
 }
+impl CommandStatus {
+    /// The result of an operation, which finished with the status.
+    pub(super) fn result(self) -> CommandResult {
+        match self {
+            CommandStatus::DoneOk
+            | CommandStatus::IeeeDoneOk
+            | CommandStatus::IeeeDoneAck
+            | CommandStatus::IeeeDoneAckpend
+            | CommandStatus::BleDoneOk
+            | CommandStatus::BleDoneNoSync
+            | CommandStatus::BleDoneRxErr
+            | CommandStatus::BleDoneMaxNack => CommandResult::True,
+            CommandStatus::DoneCountdown
+            | CommandStatus::DoneRxErr
+            | CommandStatus::DoneTimeout
+            | CommandStatus::DoneStopped
+            | CommandStatus::IeeeDoneBusy
+            | CommandStatus::IeeeDoneStopped
+            | CommandStatus::IeeeDoneTimeout
+            | CommandStatus::IeeeDoneBgend
+            | CommandStatus::BleDoneRxTimeout
+            | CommandStatus::BleDoneConnect
+            | CommandStatus::BleDoneEnded
+            | CommandStatus::BleDoneStopped => CommandResult::False,
+            _ => CommandResult::Abort,
+        }
+    }
+}
+
 /// [TI-TRM-I] 23.3.3 Command Definitions
-#[derive(Debug, PartialEq, Clone, Copy, IntoPrimitive, TryFromPrimitive)]
+#[derive(
+    Debug, PartialEq, Clone, Copy, IntoPrimitive, TryFromPrimitive, Serialize, Deserialize,
+)]
 #[repr(u16)]
 pub(super) enum CommandId {
     Nop = 0x0801,
@@ -154,6 +222,19 @@ pub(super) enum CommandId {
     IeeeStopFg = 0x2402,
     IeeeCcaReq = 0x2403,
 
+    /// [TI-TRM-I] Bluetooth Low Energy
+    BleSlave = 0x1801,
+    BleMaster = 0x1802,
+    BleAdv = 0x1803,
+    BleAdvDir = 0x1804,
+    BleAdvNc = 0x1805,
+    BleAdvScan = 0x1806,
+    BleScanner = 0x1807,
+    BleInitiator = 0x1808,
+    BleGenericRx = 0x1809,
+    BleTxTest = 0x180A,
+    BleAdvPayload = 0x1001,
+
     /// <https://github.com/contiki-ng/contiki-ng/blob/482e65555a600df848a120ce3addeb4e8a7db126/arch/cpu/cc26x0-cc13x0/rf-core/rf-core.c#L142>
     MagicalSecretSauce = 0x0607,
 }
@@ -191,6 +272,7 @@ impl CommandId {
                 | CommandId::IeeeAbortFg
                 | CommandId::IeeeStopFg
                 | CommandId::IeeeCcaReq
+                | CommandId::BleAdvPayload
                 | CommandId::MagicalSecretSauce
         )
     }
@@ -224,6 +306,15 @@ impl CommandId {
         !self.is_direct() && !self.is_immediate()
     }
 
+    /// IEEE 802.15.4 radio operations, which run at the foreground level,
+    /// if there is a background level operation running.
+    pub(super) fn is_ieee_foreground(self) -> bool {
+        matches!(
+            self,
+            CommandId::IeeeTx | CommandId::IeeeCsma | CommandId::IeeeRxAck | CommandId::IeeeAbortBg
+        )
+    }
+
     /// Returns number of bytes occupied command struct.
     fn size(self) -> usize {
         match self {
@@ -281,6 +372,18 @@ impl CommandId {
             CommandId::IeeeStopFg => 2,
             CommandId::IeeeCcaReq => 5,
 
+            CommandId::BleSlave
+            | CommandId::BleMaster
+            | CommandId::BleAdv
+            | CommandId::BleAdvDir
+            | CommandId::BleAdvNc
+            | CommandId::BleAdvScan
+            | CommandId::BleScanner
+            | CommandId::BleInitiator
+            | CommandId::BleGenericRx
+            | CommandId::BleTxTest => 24,
+            CommandId::BleAdvPayload => 12,
+
             CommandId::MagicalSecretSauce => 2, // TODO
         }
     }
@@ -313,6 +416,7 @@ impl TryFrom<&[u8]> for Command {
                     free_ram_sz: parse_u16(&value[6..=7]),
                     avail_rat_ch: parse_u16(&value[8..=9]),
                 },
+                CommandId::GetRssi => ImmediateCommandParameters::GetRssi {},
                 CommandId::StartRat => ImmediateCommandParameters::StartRat {},
                 CommandId::Ping => ImmediateCommandParameters::Ping {},
                 CommandId::ReadRfreg => ImmediateCommandParameters::ReadRfreg {
@@ -333,6 +437,9 @@ impl TryFrom<&[u8]> for Command {
                     config: parse_u16(&value[2..=3]),
                 },
                 CommandId::ArmRatCh => ImmediateCommandParameters::ArmRatCh { rat_ch: value[2] },
+                CommandId::DisarmRatCh => {
+                    ImmediateCommandParameters::DisarmRatCh { rat_ch: value[2] }
+                }
                 CommandId::SetTxPower => ImmediateCommandParameters::SetTxPower {
                     tx_power: parse_u16(&value[2..=3]),
                 },
@@ -385,24 +492,27 @@ impl TryFrom<&[u8]> for Command {
                 CommandId::IeeeCcaReq => ImmediateCommandParameters::IeeeCcaReq(
                     CcaReq::from_bytes(value[2..][..3].try_into().unwrap()),
                 ),
+                CommandId::BleAdvPayload => ImmediateCommandParameters::BleAdvPayload {
+                    payload_type: value[2],
+                    new_len: value[3],
+                    p_new_data: parse_u32(&value[4..=7]),
+                    p_params: parse_u32(&value[8..=11]),
+                },
+                CommandId::MagicalSecretSauce => ImmediateCommandParameters::MagicalSecretSauce {},
                 _ => unreachable!("Unknown immediate command: {:?}", id),
             };
             CommandData::Immediate { parameters }
         } else {
-            let status_bytes: [u8; 2] = value[2..=3].try_into().unwrap();
-            let status: CommandStatus = u16::from_le_bytes(status_bytes)
-                .try_into()
-                .map_err(|_| todo!())?;
-            let p_next_op_bytes: [u8; 4] = value[4..=7].try_into().unwrap();
-            let p_next_op = u32::from_le_bytes(p_next_op_bytes)
-                .try_into()
-                .map_err(|_| todo!())
-                .map(|addr| Address::from_const(addr))?;
-            let start_time_bytes: [u8; 4] = value[8..=11].try_into().unwrap();
-            let start_time: u32 = u32::from_le_bytes(start_time_bytes)
-                .try_into()
-                .map_err(|_| todo!())?;
-            let start_trigger = StartTrigger::from_bytes(value[12..13].try_into().unwrap());
+            // The CPE overwrites the status, so the system CPU should have set it to IDLE.
+            let status: CommandStatus = parse_u16(&value[2..=3]).try_into().map_err(|_| {
+                (
+                    CommandStatusHandle::from(ResultByte::ParError),
+                    CommandStatus::ErrorPar,
+                )
+            })?;
+            let p_next_op = Address::from_const(parse_u32(&value[4..=7]));
+            let start_time = parse_u32(&value[8..=11]);
+            let start_trigger = Trigger::from_bytes(value[12..13].try_into().unwrap());
             let condition = Condition::from_bytes(value[13..14].try_into().unwrap());
             let preamble = RadioOperationCommandPreamble {
                 status,
@@ -513,7 +623,7 @@ impl TryFrom<&[u8]> for Command {
                     last_rssi: value[22],
                     end_trigger: value[23],
                     last_time_stamp: parse_u32(&value[24..=27]),
-                    end_time: parse_u32(&value[20..=23]),
+                    end_time: parse_u32(&value[28..=31]),
                 },
                 CommandId::IeeeRxAck => RadioOperationCommandParameters::IeeeRxAck {
                     seq_no: value[14],
@@ -521,6 +631,21 @@ impl TryFrom<&[u8]> for Command {
                     end_time: parse_u32(&value[16..=19]),
                 },
                 CommandId::IeeeAbortBg => RadioOperationCommandParameters::IeeeAbortBg {},
+                CommandId::BleSlave
+                | CommandId::BleMaster
+                | CommandId::BleAdv
+                | CommandId::BleAdvDir
+                | CommandId::BleAdvNc
+                | CommandId::BleAdvScan
+                | CommandId::BleScanner
+                | CommandId::BleInitiator
+                | CommandId::BleGenericRx
+                | CommandId::BleTxTest => RadioOperationCommandParameters::Ble {
+                    channel: value[14],
+                    whitening: value[15],
+                    p_params: Address::from(parse_u32(&value[16..=19])),
+                    p_output: Address::from(parse_u32(&value[20..=23])),
+                },
                 _ => unreachable!("Unknown radio operation command: {:?}", id),
             };
             CommandData::RadioOperation {
@@ -532,7 +657,7 @@ impl TryFrom<&[u8]> for Command {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub(super) enum CommandData {
     RadioOperation {
         preamble: RadioOperationCommandPreamble,
@@ -547,7 +672,7 @@ pub(super) enum CommandData {
     },
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub(super) enum RadioOperationCommandParameters {
     Nop {},
     RadioSetup {
@@ -657,6 +782,15 @@ pub(super) enum RadioOperationCommandParameters {
         end_time: u32,
     },
     IeeeAbortBg {},
+
+    /// All the BLE radio operations share the command structure, their parameters are
+    /// in a structure of their own.
+    Ble {
+        channel: u8,
+        whitening: u8,
+        p_params: Address,
+        p_output: Address,
+    },
 }
 
 impl RadioOperationCommandParameters {
@@ -668,26 +802,73 @@ impl RadioOperationCommandParameters {
             IeeeRx { .. } | IeeeEdScan { .. } | IeeeTx { .. } | IeeeCsma { .. } | IeeeRxAck { .. }
         )
     }
+
+    #[must_use]
+    pub(super) const fn is_ble(&self) -> bool {
+        matches!(self, RadioOperationCommandParameters::Ble { .. })
+    }
 }
 
+/// A trigger of a radio operation (`startTrigger` in the preamble).
+///
+/// Used both as the start trigger of the radio operations and as the end trigger of some of them.
 #[bitfield]
-#[derive(Clone, Copy, Debug)]
-pub(super) struct StartTrigger {
-    trigger_type: B4,
-    b_ena_cmd: bool,
-    trigger_no: B2,
-    past_trig: bool,
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub(super) struct Trigger {
+    pub(super) trigger_type: B4,
+    /// The trigger may also be given with `CMD_TRIGGER`.
+    pub(super) b_ena_cmd: bool,
+    pub(super) trigger_no: B2,
+    /// A trigger in the past fires at once, instead of ending the operation with an error.
+    pub(super) past_trig: bool,
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Copy, TryFromPrimitive)]
+#[repr(u8)]
+pub(super) enum TriggerType {
+    Now = 0,
+    Never = 1,
+    AbsTime = 2,
+    RelSubmit = 3,
+    RelStart = 4,
+    RelPrevStart = 5,
+    RelFirstStart = 6,
+    RelPrevEnd = 7,
+    RelEvt1 = 8,
+    RelEvt2 = 9,
+    External = 10,
+}
+
+/// The condition for running the next operation in the chain (`condition` in the preamble).
 #[bitfield]
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub(super) struct Condition {
-    rule: B4,
-    n_skip: B4,
+    pub(super) rule: B4,
+    /// Number of skips + 1, when the rule involves skipping: 0 runs the same operation again.
+    pub(super) n_skip: B4,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, TryFromPrimitive)]
+#[repr(u8)]
+pub(super) enum ConditionRule {
+    Always = 0,
+    Never = 1,
+    StopOnFalse = 2,
+    StopOnTrue = 3,
+    SkipOnFalse = 4,
+    SkipOnTrue = 5,
+}
+
+/// The result of a finished radio operation, deciding about the next one in the chain.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub(super) enum CommandResult {
+    True,
+    False,
+    Abort,
 }
 
 /// [TI-TRM] 23.3.2.6.1 Table 23-8
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub(super) struct RadioOperationCommandPreamble {
     /// Byte index: 2-3
     pub(super) status: CommandStatus,
@@ -696,12 +877,12 @@ pub(super) struct RadioOperationCommandPreamble {
     /// Byte index: 8-11
     pub(super) start_time: u32,
     /// Byte index: 12
-    pub(super) start_trigger: StartTrigger,
+    pub(super) start_trigger: Trigger,
     /// Byte index: 13
     pub(super) condition: Condition,
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub(super) enum ImmediateCommandParameters {
     Abort {},
     Stop {},
@@ -793,6 +974,16 @@ pub(super) enum ImmediateCommandParameters {
     IeeeAbortFg {},
     IeeeStopFg {},
     IeeeCcaReq(CcaReq),
+
+    /// `CMD_BLE_ADV_PAYLOAD`: changes the advertising or the scan response data of an advertiser.
+    BleAdvPayload {
+        /// 0: the advertising data, 1: the scan response data.
+        payload_type: u8,
+        new_len: u8,
+        p_new_data: u32,
+        p_params: u32,
+    },
+    MagicalSecretSauce {},
 }
 
 fn parse_u64(bytes: &[u8]) -> u64 {
@@ -808,7 +999,7 @@ fn parse_u16(bytes: &[u8]) -> u16 {
 }
 
 #[bitfield]
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct CcaReq {
     pub current_rssi: B8,
     pub max_rssi: B8,
@@ -843,11 +1034,21 @@ impl CcaReq {
             .with_cca_corr(0b00)
             .with_cca_sync(false)
     }
+    pub fn new_busy(rssi: i8) -> Self {
+        let rssi_stored = rssi.to_le_bytes()[0];
+        CcaReq::new()
+            .with_current_rssi(rssi_stored)
+            .with_max_rssi(rssi_stored)
+            .with_cca_state(0b01)
+            .with_cca_energy(0b01)
+            .with_cca_corr(0b00)
+            .with_cca_sync(false)
+    }
 }
 const CC26XX_DEFAULT_CHANNEL: u8 = 11;
 
 #[bitfield]
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub(super) struct IeeeRxConfig {
     pub(super) b_auto_flush_crc: bool,
     pub(super) b_auto_flush_ign: bool,
@@ -860,7 +1061,7 @@ pub(super) struct IeeeRxConfig {
 }
 
 #[bitfield]
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub(super) struct IeeeTxConfig {
     pub(super) b_include_phy_hdr: bool,
     pub(super) b_include_crc: bool,
@@ -869,9 +1070,52 @@ pub(super) struct IeeeTxConfig {
     pub(super) payload_len_msb: B5,
 }
 
-/// [TI-TRM-I] Table 23-71. Frame Filtering Configuration Bit Field
+/// `config` of `CMD_SET_RAT_CPT`.
 #[bitfield]
 #[derive(Clone, Copy, Debug)]
+pub(super) struct RatCaptureConfig {
+    #[skip]
+    __: B3,
+    pub(super) input_src: B5,
+    pub(super) rat_ch: B4,
+    pub(super) b_repeated: bool,
+    pub(super) input_mode: B2,
+    #[skip]
+    __: B1,
+}
+
+/// `config` of `CMD_SET_RAT_OUTPUT`.
+#[bitfield]
+#[derive(Clone, Copy, Debug)]
+pub(super) struct RatOutputConfig {
+    #[skip]
+    __: B2,
+    pub(super) output_sel: B3,
+    pub(super) output_mode: B3,
+    pub(super) rat_ch: B4,
+    #[skip]
+    __: B4,
+}
+
+/// `patternOpt` of `CMD_PATTERN_CHECK`.
+#[bitfield]
+#[derive(Clone, Copy, Debug)]
+pub(super) struct PatternOptions {
+    /// 0: equal, 1: less than, 2: greater than, 3: not equal.
+    pub(super) operation: B2,
+    pub(super) b_byte_rev: bool,
+    pub(super) b_bit_rev: bool,
+    /// 0: no sign extension, otherwise the bit to extend the sign from.
+    pub(super) sign_ext: B5,
+    /// `pValue` is an offset into the last received packet.
+    pub(super) b_rx_val: bool,
+    #[skip]
+    __: B6,
+}
+
+/// [TI-TRM-I] Table 23-71. Frame Filtering Configuration Bit Field
+#[bitfield]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub(super) struct FrameFilteringConfiguration {
     pub(super) frame_filt_en: bool,
     pub(super) frame_filt_stop: bool,
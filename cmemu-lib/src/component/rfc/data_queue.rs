@@ -0,0 +1,600 @@
+//! Data entry queues shared by the system CPU and the CPE, and the commands manipulating them.
+//!
+//! [TI-TRM-I] 23.3.2.7.2 Data Entry
+//! A queue (`dataQueue_t`) is a pair of pointers: to the current entry and to the last entry.
+//! The entries are linked with their `pNextEntry`. A queue without the last entry is circular.
+
+use crate::common::new_ahb::DataBus;
+use crate::component::rfc::ResultByte;
+use cmemu_common::Address;
+use modular_bitfield::specifiers::B4;
+use modular_bitfield::{BitfieldSpecifier, bitfield};
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+use serde::{Deserialize, Serialize};
+
+/// Size of the queue structure.
+pub(super) const QUEUE_SIZE: usize = 8;
+/// Size of the header of an entry, with the word following it
+/// (`pData` of a pointer entry, `numElements` and `nextIndex` of a multi-element entry).
+pub(super) const ENTRY_HEADER_SIZE: usize = 12;
+/// Offset of the data of a general entry.
+pub(super) const GENERAL_ENTRY_DATA_OFFSET: u32 = 8;
+/// Offset of the data of a multi-element entry.
+pub(super) const MULTI_ELEMENT_DATA_OFFSET: u32 = 12;
+/// The queues are walked at most that far, so a corrupted queue ends with an error.
+const MAX_ENTRIES_WALKED: usize = 256;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) struct DataQueue {
+    pub(super) p_curr_entry: Address,
+    pub(super) p_last_entry: Address,
+}
+
+impl DataQueue {
+    const NULL: Address = Address::from_const(0);
+
+    pub(super) fn from_bytes(bytes: &[u8]) -> Self {
+        Self {
+            p_curr_entry: Address::from(parse_u32(&bytes[0..4])),
+            p_last_entry: Address::from(parse_u32(&bytes[4..8])),
+        }
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.p_curr_entry == Self::NULL
+    }
+
+    pub(super) fn is_circular(&self) -> bool {
+        !self.is_empty() && self.p_last_entry == Self::NULL
+    }
+}
+
+/// [TI-TRM-I] Table 23-10. General Data Entry Structure
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub(super) struct DataEntryHeader {
+    pub(super) p_next_entry: Address,
+    pub(super) status: DataEntryStatus,
+    pub(super) config: DataEntryConfig,
+    pub(super) length: u16,
+    /// The word after the header, which is meaningful for some types of the entries only.
+    pub(super) extra: u32,
+}
+
+impl DataEntryHeader {
+    /// Parses the header, which is `None` if the status is invalid.
+    pub(super) fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        Some(Self {
+            p_next_entry: Address::from(parse_u32(&bytes[0..4])),
+            status: DataEntryStatus::try_from_primitive(bytes[4]).ok()?,
+            config: DataEntryConfig::from_bytes([bytes[5]]),
+            length: u16::from_le_bytes([bytes[6], bytes[7]]),
+            extra: bytes.get(8..12).map_or(0, parse_u32),
+        })
+    }
+
+    /// `pData` of a pointer entry.
+    pub(super) fn p_data(&self) -> Address {
+        Address::from(self.extra)
+    }
+
+    /// `numElements` of a multi-element entry.
+    #[allow(clippy::cast_possible_truncation, reason = "the lower half")]
+    pub(super) fn num_elements(&self) -> u16 {
+        self.extra as u16
+    }
+
+    /// `nextIndex` of a multi-element entry.
+    pub(super) fn next_index(&self) -> u16 {
+        (self.extra >> 16) as u16
+    }
+}
+
+/// [TI-TRM-I] 23.3.2.7.2 Data Entry
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, TryFromPrimitive, IntoPrimitive, Serialize, Deserialize,
+)]
+#[repr(u8)]
+pub(super) enum DataEntryStatus {
+    Pending = 0,
+    Active = 1,
+    Busy = 2,
+    Finished = 3,
+}
+
+#[bitfield]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub(super) struct DataEntryConfig {
+    pub(super) r#type: DataEntryType,
+    pub(super) len_sz: DataEntryLengthSize,
+    pub(super) irq_intv: B4,
+}
+
+#[derive(BitfieldSpecifier, Clone, Copy, Debug, PartialEq, Eq)]
+#[bits = 2]
+pub(super) enum DataEntryType {
+    General = 0,
+    Multielement = 1,
+    Pointer = 2,
+    Reserved = 3,
+}
+
+#[derive(BitfieldSpecifier, Clone, Copy, Debug, PartialEq, Eq)]
+#[bits = 2]
+pub(super) enum DataEntryLengthSize {
+    NoIndicator = 0,
+    OneByte = 1,
+    TwoByte = 2,
+    Reserved = 3,
+}
+
+impl DataEntryLengthSize {
+    /// Bytes of the length indicator, `None` if reserved.
+    pub(super) fn bytes(self) -> Option<u32> {
+        match self {
+            Self::NoIndicator => Some(0),
+            Self::OneByte => Some(1),
+            Self::TwoByte => Some(2),
+            Self::Reserved => None,
+        }
+    }
+}
+
+/// The data queue commands: `CMD_ADD_DATA_ENTRY`, `CMD_REMOVE_DATA_ENTRY`, `CMD_FLUSH_QUEUE`,
+/// `CMD_CLEAR_RX` and `CMD_REMOVE_PENDING_ENTRIES`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) enum QueueCommand {
+    AddEntry { entry: Address },
+    RemoveEntry,
+    Flush,
+    ClearRx,
+    RemovePendingEntries,
+}
+
+/// What the operation needs next.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum QueueStep {
+    /// Read the header of the entry.
+    ReadEntry(Address),
+    /// The command finished with the result.
+    Done(ResultByte),
+}
+
+/// A data queue command in progress: the queue and its entries are read one by one
+/// through the memory bypass, and the changes are returned as memory writes.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub(super) struct QueueOperation {
+    command: QueueCommand,
+    /// The command structure, which receives the removed entries.
+    command_pointer: Address,
+    queue_pointer: Address,
+    /// The queue is used by a running radio operation.
+    in_use: bool,
+    queue: Option<DataQueue>,
+    /// The last entry visited before the one being read.
+    previous: Option<Address>,
+    visited: usize,
+}
+
+impl QueueOperation {
+    pub(super) fn new(
+        command: QueueCommand,
+        command_pointer: Address,
+        queue_pointer: Address,
+        in_use: bool,
+    ) -> Self {
+        Self {
+            command,
+            command_pointer,
+            queue_pointer,
+            in_use,
+            queue: None,
+            previous: None,
+            visited: 0,
+        }
+    }
+
+    pub(super) fn queue_pointer(&self) -> Address {
+        self.queue_pointer
+    }
+
+    fn write_queue(&self, writes: &mut Vec<(Address, DataBus)>, curr: Address, last: Address) {
+        writes.push((self.queue_pointer, curr.to_const().into()));
+        writes.push((self.queue_pointer.offset(4), last.to_const().into()));
+    }
+
+    /// Writes the pointer to the removed entries to the command structure.
+    fn write_removed(&self, writes: &mut Vec<(Address, DataBus)>, entry: Address) {
+        writes.push((self.command_pointer.offset(8), entry.to_const().into()));
+    }
+
+    pub(super) fn on_queue(
+        &mut self,
+        queue: DataQueue,
+        writes: &mut Vec<(Address, DataBus)>,
+    ) -> QueueStep {
+        self.queue = Some(queue);
+        match self.command {
+            QueueCommand::AddEntry { entry } => {
+                if queue.is_circular() {
+                    return QueueStep::Done(ResultByte::QueueError);
+                }
+                if queue.is_empty() {
+                    self.write_queue(writes, entry, entry);
+                } else {
+                    writes.push((queue.p_last_entry, entry.to_const().into()));
+                    writes.push((self.queue_pointer.offset(4), entry.to_const().into()));
+                }
+                QueueStep::Done(ResultByte::Done)
+            }
+            QueueCommand::Flush => {
+                if self.in_use {
+                    return QueueStep::Done(ResultByte::QueueBusy);
+                }
+                self.write_removed(writes, queue.p_curr_entry);
+                self.write_queue(writes, DataQueue::NULL, DataQueue::NULL);
+                QueueStep::Done(ResultByte::Done)
+            }
+            QueueCommand::ClearRx if self.in_use => QueueStep::Done(ResultByte::QueueBusy),
+            QueueCommand::RemoveEntry | QueueCommand::RemovePendingEntries
+                if queue.is_circular() =>
+            {
+                QueueStep::Done(ResultByte::QueueError)
+            }
+            QueueCommand::RemoveEntry if queue.is_empty() => {
+                QueueStep::Done(ResultByte::QueueError)
+            }
+            QueueCommand::ClearRx | QueueCommand::RemovePendingEntries if queue.is_empty() => {
+                if self.command == QueueCommand::RemovePendingEntries {
+                    self.write_removed(writes, DataQueue::NULL);
+                }
+                QueueStep::Done(ResultByte::Done)
+            }
+            QueueCommand::RemoveEntry
+            | QueueCommand::ClearRx
+            | QueueCommand::RemovePendingEntries => QueueStep::ReadEntry(queue.p_curr_entry),
+        }
+    }
+
+    /// Handles the header of the entry, which is `None` if it is invalid.
+    pub(super) fn on_entry(
+        &mut self,
+        address: Address,
+        entry: Option<DataEntryHeader>,
+        writes: &mut Vec<(Address, DataBus)>,
+    ) -> QueueStep {
+        let (Some(queue), Some(entry)) = (self.queue, entry) else {
+            return QueueStep::Done(ResultByte::QueueError);
+        };
+        let is_last = address == queue.p_last_entry
+            || entry.p_next_entry == DataQueue::NULL
+            || entry.p_next_entry == queue.p_curr_entry;
+        match self.command {
+            QueueCommand::RemoveEntry => {
+                if self.in_use
+                    && matches!(
+                        entry.status,
+                        DataEntryStatus::Active | DataEntryStatus::Busy
+                    )
+                {
+                    return QueueStep::Done(ResultByte::QueueBusy);
+                }
+                self.write_removed(writes, address);
+                if address == queue.p_last_entry {
+                    self.write_queue(writes, DataQueue::NULL, DataQueue::NULL);
+                } else {
+                    writes.push((self.queue_pointer, entry.p_next_entry.to_const().into()));
+                }
+                return QueueStep::Done(ResultByte::Done);
+            }
+            QueueCommand::ClearRx => {
+                writes.push((address.offset(4), u8::from(DataEntryStatus::Pending).into()));
+                if entry.config.r#type() == DataEntryType::Multielement {
+                    writes.push((address.offset(8), 0u32.into()));
+                }
+            }
+            QueueCommand::RemovePendingEntries if entry.status == DataEntryStatus::Pending => {
+                // The entries are used in order, so the pending ones are at the end.
+                self.write_removed(writes, address);
+                match self.previous {
+                    Some(previous) => {
+                        writes.push((self.queue_pointer.offset(4), previous.to_const().into()));
+                    }
+                    None => self.write_queue(writes, DataQueue::NULL, DataQueue::NULL),
+                }
+                return QueueStep::Done(ResultByte::Done);
+            }
+            QueueCommand::RemovePendingEntries => {
+                if is_last {
+                    self.write_removed(writes, DataQueue::NULL);
+                }
+            }
+            QueueCommand::AddEntry { .. } | QueueCommand::Flush => {
+                unreachable!("{:?} doesn't read the entries", self.command)
+            }
+        }
+
+        self.visited += 1;
+        if is_last {
+            QueueStep::Done(ResultByte::Done)
+        } else if self.visited >= MAX_ENTRIES_WALKED {
+            QueueStep::Done(ResultByte::QueueError)
+        } else {
+            self.previous = Some(address);
+            QueueStep::ReadEntry(entry.p_next_entry)
+        }
+    }
+}
+
+fn parse_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes(bytes.try_into().expect("4 bytes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        DataEntryConfig, DataEntryHeader, DataEntryLengthSize, DataEntryStatus, DataEntryType,
+        DataQueue, MAX_ENTRIES_WALKED, QueueCommand, QueueOperation, QueueStep,
+    };
+    use crate::common::new_ahb::DataBus;
+    use crate::component::rfc::ResultByte;
+    use cmemu_common::Address;
+
+    const COMMAND: Address = Address::from_const(0x2000_0100);
+    const QUEUE: Address = Address::from_const(0x2000_0200);
+    const ENTRY_A: Address = Address::from_const(0x2000_0300);
+    const ENTRY_B: Address = Address::from_const(0x2000_0400);
+    const ENTRY_C: Address = Address::from_const(0x2000_0500);
+    const NULL: Address = Address::from_const(0);
+
+    type Writes = Vec<(Address, DataBus)>;
+
+    fn queue(curr: Address, last: Address) -> DataQueue {
+        DataQueue {
+            p_curr_entry: curr,
+            p_last_entry: last,
+        }
+    }
+
+    fn entry(next: Address, status: DataEntryStatus) -> DataEntryHeader {
+        DataEntryHeader {
+            p_next_entry: next,
+            status,
+            config: DataEntryConfig::new(),
+            length: 16,
+            extra: 0,
+        }
+    }
+
+    fn word(address: Address, value: Address) -> (Address, DataBus) {
+        (address, value.to_const().into())
+    }
+
+    /// Runs the command on the queue, whose entries are looked up in `entries`.
+    fn run(
+        command: QueueCommand,
+        in_use: bool,
+        q: DataQueue,
+        entries: &[(Address, DataEntryHeader)],
+    ) -> (ResultByte, Writes) {
+        let mut operation = QueueOperation::new(command, COMMAND, QUEUE, in_use);
+        let mut writes = Vec::new();
+        let mut step = operation.on_queue(q, &mut writes);
+        loop {
+            match step {
+                QueueStep::Done(result) => return (result, writes),
+                QueueStep::ReadEntry(address) => {
+                    let header = entries.iter().find(|(a, _)| *a == address).map(|(_, h)| *h);
+                    step = operation.on_entry(address, header, &mut writes);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn add_to_empty_queue() {
+        let command = QueueCommand::AddEntry { entry: ENTRY_A };
+        let (result, writes) = run(command, false, queue(NULL, NULL), &[]);
+        assert_eq!(result, ResultByte::Done);
+        assert_eq!(
+            writes,
+            [word(QUEUE, ENTRY_A), word(QUEUE.offset(4), ENTRY_A)]
+        );
+    }
+
+    #[test]
+    fn add_links_after_last_entry() {
+        let command = QueueCommand::AddEntry { entry: ENTRY_B };
+        let (result, writes) = run(command, true, queue(ENTRY_A, ENTRY_A), &[]);
+        assert_eq!(result, ResultByte::Done);
+        assert_eq!(
+            writes,
+            [word(ENTRY_A, ENTRY_B), word(QUEUE.offset(4), ENTRY_B)]
+        );
+    }
+
+    #[test]
+    fn add_to_circular_queue_fails() {
+        let command = QueueCommand::AddEntry { entry: ENTRY_B };
+        let (result, writes) = run(command, false, queue(ENTRY_A, NULL), &[]);
+        assert_eq!(result, ResultByte::QueueError);
+        assert!(writes.is_empty());
+    }
+
+    #[test]
+    fn remove_first_entry() {
+        let entries = [
+            (ENTRY_A, entry(ENTRY_B, DataEntryStatus::Finished)),
+            (ENTRY_B, entry(NULL, DataEntryStatus::Pending)),
+        ];
+        let (result, writes) = run(
+            QueueCommand::RemoveEntry,
+            false,
+            queue(ENTRY_A, ENTRY_B),
+            &entries,
+        );
+        assert_eq!(result, ResultByte::Done);
+        assert_eq!(
+            writes,
+            [word(COMMAND.offset(8), ENTRY_A), word(QUEUE, ENTRY_B)]
+        );
+    }
+
+    #[test]
+    fn remove_last_entry_empties_queue() {
+        let entries = [(ENTRY_A, entry(NULL, DataEntryStatus::Pending))];
+        let (result, writes) = run(
+            QueueCommand::RemoveEntry,
+            false,
+            queue(ENTRY_A, ENTRY_A),
+            &entries,
+        );
+        assert_eq!(result, ResultByte::Done);
+        assert_eq!(
+            writes,
+            [
+                word(COMMAND.offset(8), ENTRY_A),
+                word(QUEUE, NULL),
+                word(QUEUE.offset(4), NULL)
+            ]
+        );
+    }
+
+    #[test]
+    fn remove_fails_on_empty_and_busy_queue() {
+        let (result, _) = run(QueueCommand::RemoveEntry, false, queue(NULL, NULL), &[]);
+        assert_eq!(result, ResultByte::QueueError);
+
+        let entries = [(ENTRY_A, entry(NULL, DataEntryStatus::Active))];
+        let q = queue(ENTRY_A, ENTRY_A);
+        let (result, writes) = run(QueueCommand::RemoveEntry, true, q, &entries);
+        assert_eq!(result, ResultByte::QueueBusy);
+        assert!(writes.is_empty());
+        // The entry is not used if the queue is not.
+        let (result, _) = run(QueueCommand::RemoveEntry, false, q, &entries);
+        assert_eq!(result, ResultByte::Done);
+    }
+
+    #[test]
+    fn remove_fails_on_invalid_entry() {
+        let (result, writes) = run(
+            QueueCommand::RemoveEntry,
+            false,
+            queue(ENTRY_A, ENTRY_A),
+            &[],
+        );
+        assert_eq!(result, ResultByte::QueueError);
+        assert!(writes.is_empty());
+    }
+
+    #[test]
+    fn flush_returns_all_entries() {
+        let (result, writes) = run(QueueCommand::Flush, false, queue(ENTRY_A, ENTRY_B), &[]);
+        assert_eq!(result, ResultByte::Done);
+        assert_eq!(
+            writes,
+            [
+                word(COMMAND.offset(8), ENTRY_A),
+                word(QUEUE, NULL),
+                word(QUEUE.offset(4), NULL)
+            ]
+        );
+
+        let (result, writes) = run(QueueCommand::Flush, true, queue(ENTRY_A, ENTRY_B), &[]);
+        assert_eq!(result, ResultByte::QueueBusy);
+        assert!(writes.is_empty());
+    }
+
+    #[test]
+    fn remove_pending_entries_keeps_used_ones() {
+        let entries = [
+            (ENTRY_A, entry(ENTRY_B, DataEntryStatus::Finished)),
+            (ENTRY_B, entry(ENTRY_C, DataEntryStatus::Pending)),
+            (ENTRY_C, entry(NULL, DataEntryStatus::Pending)),
+        ];
+        let (result, writes) = run(
+            QueueCommand::RemovePendingEntries,
+            false,
+            queue(ENTRY_A, ENTRY_C),
+            &entries,
+        );
+        assert_eq!(result, ResultByte::Done);
+        assert_eq!(
+            writes,
+            [
+                word(COMMAND.offset(8), ENTRY_B),
+                word(QUEUE.offset(4), ENTRY_A)
+            ]
+        );
+    }
+
+    #[test]
+    fn clear_rx_resets_all_entries() {
+        let mut multi_element = entry(ENTRY_A, DataEntryStatus::Finished);
+        multi_element.config = DataEntryConfig::new().with_type(DataEntryType::Multielement);
+        let entries = [
+            (ENTRY_A, entry(ENTRY_B, DataEntryStatus::Finished)),
+            (ENTRY_B, multi_element),
+        ];
+        // A circular queue.
+        let (result, writes) = run(QueueCommand::ClearRx, false, queue(ENTRY_A, NULL), &entries);
+        assert_eq!(result, ResultByte::Done);
+        assert_eq!(
+            writes,
+            [
+                (ENTRY_A.offset(4), DataBus::Byte(0)),
+                (ENTRY_B.offset(4), DataBus::Byte(0)),
+                (ENTRY_B.offset(8), DataBus::Word(0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn walking_a_corrupted_queue_ends() {
+        // The entry B points to itself, so the last entry is never reached.
+        let entries = [
+            (ENTRY_A, entry(ENTRY_B, DataEntryStatus::Finished)),
+            (ENTRY_B, entry(ENTRY_B, DataEntryStatus::Finished)),
+        ];
+        let (result, writes) = run(
+            QueueCommand::ClearRx,
+            false,
+            queue(ENTRY_A, ENTRY_C),
+            &entries,
+        );
+        assert_eq!(result, ResultByte::QueueError);
+        assert_eq!(writes.len(), MAX_ENTRIES_WALKED);
+    }
+
+    #[test]
+    fn header_parsing() {
+        let bytes = [
+            0x00,
+            0x03,
+            0x00,
+            0x20,
+            0x03,
+            0b1101_0110,
+            0x34,
+            0x12,
+            0x02,
+            0x00,
+            0x08,
+            0x00,
+        ];
+        let header = DataEntryHeader::from_bytes(&bytes).unwrap();
+        assert_eq!(header.p_next_entry, Address::from_const(0x2000_0300));
+        assert_eq!(header.status, DataEntryStatus::Finished);
+        assert_eq!(header.config.r#type(), DataEntryType::Pointer);
+        assert_eq!(header.config.len_sz(), DataEntryLengthSize::OneByte);
+        assert_eq!(header.config.irq_intv(), 0b1101);
+        assert_eq!(header.length, 0x1234);
+        assert_eq!(header.num_elements(), 2);
+        assert_eq!(header.next_index(), 8);
+
+        let mut invalid = bytes;
+        invalid[4] = 4;
+        assert!(DataEntryHeader::from_bytes(&invalid).is_none());
+        assert_eq!(DataEntryLengthSize::Reserved.bytes(), None);
+        assert_eq!(DataEntryLengthSize::TwoByte.bytes(), Some(2));
+    }
+}
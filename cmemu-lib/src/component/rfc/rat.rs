@@ -0,0 +1,323 @@
+//! The Radio Timer (RAT) of the RF core.
+//!
+//! [TI-TRM-I] 23.8.1 RFC_RAT Registers: a 32-bit counter running at 4 MHz with 8 channels,
+//! which either capture the counter on an input event or compare it with their value.
+//! The channels are configured with the radio timer commands of the CPE, and the system CPU
+//! is given only some of them, the others are used by the CPE itself.
+//!
+//! The counter is derived from the emulation time, thus it keeps counting while the cycles
+//! of the RF core are skipped, and `CMD_SYNC_START_RAT` resumes it as if it was running meanwhile
+//! (like the real CPE does, which synchronizes the RAT with the RTC).
+
+use crate::engine::{Duration, PICOS_IN_SECOND, Timepoint};
+use log::trace;
+use serde::{Deserialize, Serialize};
+
+/// The RAT runs at 4 MHz.
+const TICKS_PER_SECOND: u64 = 4_000_000;
+/// A tick of the RAT.
+pub(super) const TICK: Duration = Duration::from_picos(PICOS_IN_SECOND / TICKS_PER_SECOND);
+/// Cycles of the RF core (clocked with 48 MHz) in a tick of the RAT.
+pub(super) const CYCLES_PER_TICK: u64 = 12;
+pub(super) const CHANNEL_COUNT: usize = 8;
+/// Channels, which the system CPU may use through the CPE API (`availRatCh` of `CMD_GET_FW_INFO`).
+pub(super) const AVAILABLE_CHANNELS: u16 = 0b1110_0000;
+/// The flag of the channel 0 in `RFC_DBELL:RFHWIFG`, the next channels follow it.
+const RFHWIFG_RATCH0_SHIFT: u32 = 12;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+enum ChannelMode {
+    Disabled,
+    Compare,
+    Capture { repeated: bool },
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+struct Channel {
+    mode: ChannelMode,
+    armed: bool,
+    /// `RATCHxVAL`: the compare time or the captured time.
+    value: u32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(super) struct RadioTimer {
+    running: bool,
+    /// The counter is the ticks since the start of the emulation plus `offset` when running,
+    /// and just `offset` when stopped.
+    offset: u32,
+    channels: [Channel; CHANNEL_COUNT],
+    /// The last configuration from `CMD_SET_RAT_OUTPUT`.
+    // TODO: the outputs (`RFC_GPO`) are not routed anywhere.
+    output_config: u16,
+}
+
+impl RadioTimer {
+    pub(super) fn new() -> Self {
+        Self {
+            running: false,
+            offset: 0,
+            channels: [Channel {
+                mode: ChannelMode::Disabled,
+                armed: false,
+                value: 0,
+            }; CHANNEL_COUNT],
+            output_config: 0,
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation, reason = "the counter wraps")]
+    fn ticks_since_start(now: Timepoint) -> u32 {
+        (now.as_picos() / TICK.as_picos()) as u32
+    }
+
+    /// Duration of `ticks` of the RAT, which is counted regardless of whether it runs.
+    pub(super) fn ticks_duration(ticks: u32) -> Duration {
+        TICK * u64::from(ticks)
+    }
+
+    pub(super) fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// `RATCNT` at `now`.
+    pub(super) fn counter(&self, now: Timepoint) -> u32 {
+        if self.running {
+            self.offset.wrapping_add(Self::ticks_since_start(now))
+        } else {
+            self.offset
+        }
+    }
+
+    pub(super) fn set_counter(&mut self, now: Timepoint, value: u32) {
+        self.offset = if self.running {
+            value.wrapping_sub(Self::ticks_since_start(now))
+        } else {
+            value
+        };
+    }
+
+    /// `CMD_START_RAT`: the counter resumes from its last value.
+    pub(super) fn start(&mut self, now: Timepoint) {
+        let counter = self.counter(now);
+        self.running = true;
+        self.set_counter(now, counter);
+    }
+
+    /// `CMD_SYNC_START_RAT` with `rat0` returned by the last `CMD_SYNC_STOP_RAT`.
+    pub(super) fn sync_start(&mut self, rat0: u32) {
+        self.running = true;
+        self.offset = rat0;
+    }
+
+    /// `CMD_SYNC_STOP_RAT`, returns `rat0` for the next `CMD_SYNC_START_RAT`.
+    pub(super) fn sync_stop(&mut self, now: Timepoint) -> u32 {
+        let counter = self.counter(now);
+        let rat0 = counter.wrapping_sub(Self::ticks_since_start(now));
+        self.running = false;
+        self.offset = counter;
+        rat0
+    }
+
+    /// Whether the counter reached `time`, which is at most half of the period in the past.
+    pub(super) fn has_reached(&self, now: Timepoint, time: u32) -> bool {
+        self.ticks_until(now, time) == 0
+    }
+
+    /// Ticks left until the counter reaches `time`, 0 if it is in the past.
+    #[allow(clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+    pub(super) fn ticks_until(&self, now: Timepoint, time: u32) -> u32 {
+        let delta = time.wrapping_sub(self.counter(now)) as i32;
+        delta.max(0) as u32
+    }
+
+    /// Index of a channel given to the system CPU.
+    pub(super) fn available_channel(channel: u8) -> Option<usize> {
+        let index = usize::from(channel);
+        (index < CHANNEL_COUNT && AVAILABLE_CHANNELS & (1 << index) != 0).then_some(index)
+    }
+
+    /// `CMD_SET_RAT_CMP`: the channel raises its interrupt once, when the counter reaches `time`.
+    pub(super) fn set_compare(&mut self, index: usize, time: u32) {
+        self.channels[index] = Channel {
+            mode: ChannelMode::Compare,
+            armed: true,
+            value: time,
+        };
+    }
+
+    /// `CMD_SET_RAT_CPT`.
+    // TODO: the capture inputs are not connected, so the channels never capture.
+    pub(super) fn set_capture(&mut self, index: usize, repeated: bool) {
+        let channel = &mut self.channels[index];
+        channel.mode = ChannelMode::Capture { repeated };
+        channel.armed = true;
+    }
+
+    pub(super) fn disable(&mut self, index: usize) {
+        let channel = &mut self.channels[index];
+        channel.mode = ChannelMode::Disabled;
+        channel.armed = false;
+    }
+
+    pub(super) fn arm(&mut self, index: usize) {
+        self.channels[index].armed = true;
+    }
+
+    pub(super) fn disarm(&mut self, index: usize) {
+        self.channels[index].armed = false;
+    }
+
+    pub(super) fn set_output(&mut self, config: u16) {
+        self.output_config = config;
+    }
+
+    pub(super) fn channel_value(&self, index: usize) -> u32 {
+        self.channels[index].value
+    }
+
+    pub(super) fn set_channel_value(&mut self, index: usize, value: u32) {
+        self.channels[index].value = value;
+    }
+
+    fn armed_compare_channels(&self) -> impl Iterator<Item = (usize, &Channel)> {
+        self.channels
+            .iter()
+            .enumerate()
+            .filter(|(_, ch)| ch.armed && ch.mode == ChannelMode::Compare)
+    }
+
+    /// Disarms the compare channels, which reached their time, and returns their `RFHWIFG` flags.
+    pub(super) fn take_due_compare_flags(&mut self, now: Timepoint) -> u32 {
+        if !self.running {
+            return 0;
+        }
+        let due = self
+            .armed_compare_channels()
+            .filter(|(_, ch)| self.has_reached(now, ch.value))
+            .fold(0u32, |mask, (idx, _)| mask | (1 << idx));
+        for idx in 0..CHANNEL_COUNT {
+            if due & (1 << idx) != 0 {
+                trace!("RAT channel {idx} reached {:#x}", self.channels[idx].value);
+                self.disable(idx);
+            }
+        }
+        due << RFHWIFG_RATCH0_SHIFT
+    }
+
+    /// Ticks until the earliest compare event, if there is any.
+    pub(super) fn ticks_until_compare(&self, now: Timepoint) -> Option<u32> {
+        if !self.running {
+            return None;
+        }
+        self.armed_compare_channels()
+            .map(|(_, ch)| self.ticks_until(now, ch.value))
+            .min()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CHANNEL_COUNT, RadioTimer, TICK};
+    use crate::engine::Timepoint;
+
+    fn at_tick(ticks: u64) -> Timepoint {
+        Timepoint::ZERO + TICK * ticks
+    }
+
+    #[test]
+    fn counter_runs_only_after_start() {
+        let mut rat = RadioTimer::new();
+        assert_eq!(rat.counter(at_tick(100)), 0);
+
+        rat.start(at_tick(100));
+        assert!(rat.is_running());
+        assert_eq!(rat.counter(at_tick(100)), 0);
+        assert_eq!(rat.counter(at_tick(110)), 10);
+    }
+
+    #[test]
+    fn start_resumes_from_the_written_counter() {
+        let mut rat = RadioTimer::new();
+        rat.set_counter(at_tick(5), 1000);
+        assert_eq!(rat.counter(at_tick(50)), 1000);
+
+        rat.start(at_tick(50));
+        assert_eq!(rat.counter(at_tick(55)), 1005);
+        rat.set_counter(at_tick(60), 0);
+        assert_eq!(rat.counter(at_tick(70)), 10);
+    }
+
+    #[test]
+    fn sync_start_counts_the_time_while_stopped() {
+        let mut rat = RadioTimer::new();
+        rat.start(at_tick(10));
+        let rat0 = rat.sync_stop(at_tick(30));
+        assert!(!rat.is_running());
+        assert_eq!(rat.counter(at_tick(80)), 20);
+
+        rat.sync_start(rat0);
+        assert_eq!(rat.counter(at_tick(80)), 70);
+    }
+
+    #[test]
+    fn counter_wraps() {
+        let mut rat = RadioTimer::new();
+        rat.start(Timepoint::ZERO);
+        rat.set_counter(at_tick(0), u32::MAX - 1);
+        assert_eq!(rat.counter(at_tick(3)), 1);
+        assert_eq!(rat.ticks_until(at_tick(0), 0x10), 0x12);
+        assert!(!rat.has_reached(at_tick(0), 0x10));
+        assert!(rat.has_reached(at_tick(0x12), 0x10));
+        // Half of the period in the past is still reached.
+        assert!(rat.has_reached(at_tick(0x12), 0x10u32.wrapping_sub(1 << 30)));
+    }
+
+    #[test]
+    fn only_some_channels_are_available() {
+        let available: Vec<_> = (0..=u8::try_from(CHANNEL_COUNT).unwrap())
+            .filter_map(RadioTimer::available_channel)
+            .collect();
+        assert_eq!(available, [5, 6, 7]);
+    }
+
+    #[test]
+    fn compare_fires_once_at_its_time() {
+        let mut rat = RadioTimer::new();
+        rat.start(Timepoint::ZERO);
+        rat.set_compare(5, 100);
+        assert_eq!(rat.ticks_until_compare(at_tick(40)), Some(60));
+
+        assert_eq!(rat.take_due_compare_flags(at_tick(99)), 0);
+        assert_eq!(rat.take_due_compare_flags(at_tick(100)), 1 << 17);
+        assert_eq!(rat.take_due_compare_flags(at_tick(101)), 0);
+        assert_eq!(rat.ticks_until_compare(at_tick(101)), None);
+    }
+
+    #[test]
+    fn compare_waits_for_the_counter_to_run() {
+        let mut rat = RadioTimer::new();
+        rat.set_compare(6, 10);
+        assert_eq!(rat.ticks_until_compare(at_tick(20)), None);
+        assert_eq!(rat.take_due_compare_flags(at_tick(20)), 0);
+
+        rat.start(at_tick(20));
+        assert_eq!(rat.ticks_until_compare(at_tick(20)), Some(10));
+        assert_eq!(rat.take_due_compare_flags(at_tick(30)), 1 << 18);
+    }
+
+    #[test]
+    fn disarmed_compare_doesnt_fire() {
+        let mut rat = RadioTimer::new();
+        rat.start(Timepoint::ZERO);
+        rat.set_compare(5, 10);
+        rat.set_compare(7, 20);
+        rat.disarm(5);
+        assert_eq!(rat.ticks_until_compare(at_tick(0)), Some(20));
+        assert_eq!(rat.take_due_compare_flags(at_tick(30)), 1 << 19);
+
+        rat.arm(5);
+        assert_eq!(rat.take_due_compare_flags(at_tick(30)), 1 << 17);
+        assert_eq!(rat.channel_value(5), 10);
+    }
+}
@@ -1,5 +1,5 @@
 use crate::component::event_fabric::EventFabricEvent;
-use crate::component::rfc::{Interrupt, ResultByte};
+use crate::component::rfc::{CommandStatusHandle, Interrupt, ResultByte};
 use crate::engine::{Context, SeqFlopMemoryBankSimple};
 use crate::engine::{
     DisableableComponent, SeqFlopMemoryBank, Subcomponent, TickComponent, TickComponentExtra,
@@ -9,56 +9,50 @@ use cc2650_constants::RFC;
 use cmemu_common::Address;
 use cmemu_common::HwRegister;
 use log::{trace, warn};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, PartialEq, Clone, Copy)]
-pub struct CommandStatusHandle {
-    result: ResultByte,
-    return_byte_1: Option<u8>,
-    return_byte_2: Option<u8>,
-    return_byte_3: Option<u8>,
-}
-impl From<ResultByte> for CommandStatusHandle {
-    fn from(result: ResultByte) -> Self {
-        CommandStatusHandle {
-            result,
-            return_byte_1: None,
-            return_byte_2: None,
-            return_byte_3: None,
-        }
-    }
-}
-
-impl From<CommandStatusHandle> for u32 {
-    fn from(status: CommandStatusHandle) -> Self {
-        u32::from(u8::from(status.result))
-            | (u32::from(u8::from(status.return_byte_1.unwrap_or(0))) << 8)
-            | (u32::from(u8::from(status.return_byte_2.unwrap_or(0))) << 16)
-            | (u32::from(u8::from(status.return_byte_3.unwrap_or(0))) << 24)
-    }
-}
-
-#[derive(Subcomponent, TickComponent, TickComponentExtra, DisableableComponent)]
+#[derive(
+    Clone,
+    Subcomponent,
+    TickComponent,
+    TickComponentExtra,
+    DisableableComponent,
+    Serialize,
+    Deserialize,
+)]
 pub(super) struct RfcRegisterBank<SC>
 where
     SC: Subcomponent<Member = Self>,
 {
     #[flop]
+    #[serde(with = "crate::engine::serde_hw_register_bank")]
     cmdr: SeqFlopMemoryBank<RFC::DBELL::CMDR::Register, u32>,
     #[flop]
     // This can't be RFC::DBELL::CMDR::Register, as this register is RO
     cmdsta: SeqFlopMemoryBankSimple<CommandStatusHandle>,
     #[flop]
+    #[serde(with = "crate::engine::serde_hw_register_bank")]
     rfackifg: SeqFlopMemoryBank<RFC::DBELL::RFACKIFG::Register, u32>,
     #[flop]
+    #[serde(with = "crate::engine::serde_hw_register_bank")]
     rfcpeifg: SeqFlopMemoryBank<RFC::DBELL::RFCPEIFG::Register, u32>,
     #[flop]
+    #[serde(with = "crate::engine::serde_hw_register_bank")]
     rfcpeien: SeqFlopMemoryBank<RFC::DBELL::RFCPEIEN::Register, u32>,
     #[flop]
+    #[serde(with = "crate::engine::serde_hw_register_bank")]
     rfcpeisl: SeqFlopMemoryBank<RFC::DBELL::RFCPEISL::Register, u32>,
     #[flop]
-    ratcnt: SeqFlopMemoryBank<RFC::RAT::RATCNT::Register, u32>,
+    #[serde(with = "crate::engine::serde_hw_register_bank")]
+    rfhwifg: SeqFlopMemoryBank<RFC::DBELL::RFHWIFG::Register, u32>,
+    #[flop]
+    #[serde(with = "crate::engine::serde_hw_register_bank")]
+    rfhwien: SeqFlopMemoryBank<RFC::DBELL::RFHWIEN::Register, u32>,
     #[flop]
+    #[serde(with = "crate::engine::serde_hw_register_bank")]
     pwmclken: SeqFlopMemoryBank<RFC::PWR::PWMCLKEN::Register, u32>,
+    /// Changes of the interrupt flags in this cycle, committed at once.
+    flag_updates: FlagUpdates,
     phantom_subcomponent: std::marker::PhantomData<SC>,
 }
 
@@ -74,8 +68,10 @@ where
             rfcpeifg: SeqFlopMemoryBank::new(RFC::DBELL::RFCPEIFG::Register::new()),
             rfcpeien: SeqFlopMemoryBank::new(RFC::DBELL::RFCPEIEN::Register::new()),
             rfcpeisl: SeqFlopMemoryBank::new(RFC::DBELL::RFCPEISL::Register::new()),
-            ratcnt: SeqFlopMemoryBank::new(RFC::RAT::RATCNT::Register::new()),
+            rfhwifg: SeqFlopMemoryBank::new(RFC::DBELL::RFHWIFG::Register::new()),
+            rfhwien: SeqFlopMemoryBank::new(RFC::DBELL::RFHWIEN::Register::new()),
             pwmclken: SeqFlopMemoryBank::new(RFC::PWR::PWMCLKEN::Register::new()),
+            flag_updates: FlagUpdates::new(),
             phantom_subcomponent: std::marker::PhantomData,
         }
     }
@@ -117,9 +113,14 @@ where
                 trace!("reading PWMCLKEN: {:#x}", value);
                 value
             }
-            RFC::RAT::RATCNT::ADDR => {
-                let value = self.ratcnt.read();
-                trace!("reading RATCNT: {:#x}", value);
+            RFC::DBELL::RFHWIFG::ADDR => {
+                let value = self.rfhwifg.read();
+                trace!("reading RFHWIFG: {:#x}", value);
+                value
+            }
+            RFC::DBELL::RFHWIEN::ADDR => {
+                let value = self.rfhwien.read();
+                trace!("reading RFHWIEN: {:#x}", value);
                 value
             }
             RFC::DBELL::CMDSTA::ADDR => {
@@ -127,7 +128,10 @@ where
                 trace!("reading CMDSTA: {:#x} ({:?})", u32::from(value), value);
                 u32::from(value)
             }
-            _ => todo!("reading unknown register {addr:?}"),
+            _ => {
+                warn!("Reading unknown register {addr:?}, returning 0.");
+                0
+            }
         }
     }
 
@@ -147,18 +151,24 @@ where
                 }
             }
             RFC::DBELL::CMDSTA::ADDR => {
-                panic!(
-                    "Tried to write {:#x} to CMDSTA, but CMDSTA register is readonly.",
-                    data
-                );
+                warn!("Ignoring write of {data:#x} to the read-only CMDSTA.");
             }
+            // The flags are cleared by writing 0, while writing 1 has no effect.
             RFC::DBELL::RFACKIFG::ADDR => {
                 trace!("writing RFACKIFG => {:#x}", data);
-                self.rfackifg.mutate_next(data, |reg, val| reg.mutate(val));
+                self.flag_updates.ack_kept &= data;
             }
             RFC::DBELL::RFCPEIFG::ADDR => {
                 trace!("writing RFCPEIFG => {:#x}", data);
-                self.rfcpeifg.mutate_next(data, |reg, val| reg.mutate(val));
+                self.flag_updates.cpe_kept &= data;
+            }
+            RFC::DBELL::RFHWIFG::ADDR => {
+                trace!("writing RFHWIFG => {:#x}", data);
+                self.flag_updates.hw_kept &= data;
+            }
+            RFC::DBELL::RFHWIEN::ADDR => {
+                trace!("writing RFHWIEN => {:#x}", data);
+                self.rfhwien.mutate_next(data, |reg, val| reg.mutate(val));
             }
             RFC::DBELL::RFCPEIEN::ADDR => {
                 trace!("writing RFCPEIEN => {:#x}", data);
@@ -172,11 +182,7 @@ where
                 trace!("writing PWMCLKEN => {:#x}", data);
                 self.pwmclken.mutate_next(data, |reg, val| reg.mutate(val));
             }
-            RFC::RAT::RATCNT::ADDR => {
-                trace!("writing RATCNT => {:#x}", data);
-                self.ratcnt.mutate_next(data, |reg, val| reg.mutate(val));
-            }
-            _ => todo!("writing {data:08x} to unknown register {addr:?}"),
+            _ => warn!("Ignoring write of {data:#x} to unknown register {addr:?}."),
         }
     }
 }
@@ -199,19 +205,49 @@ where
     }
 
     pub(super) fn set_cpe_interrupt_pending(&mut self, interrupt: Interrupt) {
-        debug_assert!(self.is_cpe_interrupt_enabled(interrupt));
-        let bitmask: u32 = 1u32 << interrupt as u8;
-        self.rfcpeifg
-            .mutate_next(bitmask, |reg, bitmask| reg.mutate(reg.read() | bitmask));
+        self.flag_updates.cpe_set |= 1u32 << interrupt as u8;
     }
 
-    pub(super) fn set_command_status(&mut self, value: ResultByte) {
-        self.cmdsta.set_next(CommandStatusHandle::from(value));
+    /// Sets the `RFHWIFG` flags, returns whether any of them is enabled.
+    pub(super) fn set_hw_interrupts_pending(&mut self, flags: u32) -> bool {
+        self.flag_updates.hw_set |= flags;
+        self.rfhwien.read() & flags != 0
+    }
+
+    pub(super) const fn get_event_for_hw_interrupt(&self) -> EventFabricEvent {
+        EventFabricEvent::RFC_HW_COMB
+    }
+
+    pub(super) fn set_command_status(&mut self, value: impl Into<CommandStatusHandle>) {
+        self.cmdsta.set_next(value.into());
     }
 
     pub(super) fn set_doorbell_interrupt_pending(&mut self) {
-        self.rfackifg
-            .mutate_next(1, |reg, val| reg.mut_bitfields().set_ACKFLAG(val as u8));
+        self.flag_updates.ack_set |= 1;
+    }
+
+    /// Applies the flags set and cleared in this cycle.
+    pub(super) fn commit_interrupt_flags(&mut self) {
+        let updates = std::mem::replace(&mut self.flag_updates, FlagUpdates::new());
+        if let Some(value) = updates.cpe_value(self.rfcpeifg.read()) {
+            self.rfcpeifg
+                .mutate_next(value, |reg, val| *reg = val.into());
+        }
+        if let Some(value) = updates.hw_value(self.rfhwifg.read()) {
+            self.rfhwifg
+                .mutate_next(value, |reg, val| *reg = val.into());
+        }
+        if let Some(value) = updates.ack_value(self.rfackifg.read()) {
+            self.rfackifg
+                .mutate_next(value, |reg, val| *reg = val.into());
+        }
+    }
+
+    /// Whether there are flags to commit.
+    pub(super) fn has_flag_updates(&self) -> bool {
+        let updates = self.flag_updates;
+        updates.cpe_set | updates.hw_set | updates.ack_set != 0
+            || updates.cpe_kept & updates.hw_kept & updates.ack_kept != u32::MAX
     }
 
     pub(super) const fn get_event_for_doorbell_interrupt(&self) -> EventFabricEvent {
@@ -222,3 +258,45 @@ where
         self.cmdr.mutate_next(0, |reg, data| *reg = data.into());
     }
 }
+
+/// The interrupt flags may be set by the CPE and cleared by the system CPU in the same cycle,
+/// while their registers may be mutated only once.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+struct FlagUpdates {
+    cpe_set: u32,
+    cpe_kept: u32,
+    hw_set: u32,
+    hw_kept: u32,
+    ack_set: u32,
+    ack_kept: u32,
+}
+
+impl FlagUpdates {
+    const fn new() -> Self {
+        Self {
+            cpe_set: 0,
+            cpe_kept: u32::MAX,
+            hw_set: 0,
+            hw_kept: u32::MAX,
+            ack_set: 0,
+            ack_kept: u32::MAX,
+        }
+    }
+
+    fn updated(current: u32, kept: u32, set: u32) -> Option<u32> {
+        let value = (current & kept) | set;
+        (value != current).then_some(value)
+    }
+
+    fn cpe_value(&self, current: u32) -> Option<u32> {
+        Self::updated(current, self.cpe_kept, self.cpe_set)
+    }
+
+    fn hw_value(&self, current: u32) -> Option<u32> {
+        Self::updated(current, self.hw_kept, self.hw_set)
+    }
+
+    fn ack_value(&self, current: u32) -> Option<u32> {
+        Self::updated(current, self.ack_kept, self.ack_set)
+    }
+}
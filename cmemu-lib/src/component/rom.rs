@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use std::ops::Range;
 
 use log::trace;
@@ -33,7 +34,15 @@ impl MemoryConfiguration for ROMMemory {
 }
 type ROMMemory = Memory<MemorySC>;
 
-#[derive(MainComponent, SkippableClockTreeNode, TickComponent, TickComponentExtra)]
+#[derive(
+    Clone,
+    MainComponent,
+    SkippableClockTreeNode,
+    TickComponent,
+    TickComponentExtra,
+    Serialize,
+    Deserialize,
+)]
 #[skippable_if_disableable]
 pub(crate) struct ROMComponent {
     #[subcomponent(MemorySC)]
@@ -72,6 +81,11 @@ impl ROMComponent {
         }
     }
 
+    /// The memory keeps its contents across a reset, only its bus interface is reset.
+    pub(crate) fn reset_keeping_contents(&mut self) {
+        self.memory.reset_interface();
+    }
+
     pub(crate) fn tick(&mut self, ctx: &mut Context) {
         ROMMemory::run_driver(self, ctx);
     }
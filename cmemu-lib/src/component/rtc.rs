@@ -1,34 +1,19 @@
-pub const RTC_ROUTE_INJECTION: Range<Address> = RTC::ADDR_SPACE;
-
-use crate::bridge_ports;
 #[proxy_use]
 use crate::common::Address;
-#[proxy_use]
-use crate::common::new_ahb::databus::DataBus;
-use crate::common::new_ahb::ports::AHBSlavePortProxiedInput;
-#[proxy_use]
-use crate::common::new_ahb::ports::{AHBPortConfig, AHBSlavePortInput};
-#[proxy_use]
-use crate::common::new_ahb::signals::{MasterToSlaveWires, Size};
-use crate::common::new_ahb::slave_driver::stateless_simplifiers::AlignedHandler;
-use crate::common::new_ahb::slave_driver::{
-    SimpleResponse, SimpleSynchronousSlaveInterface, SimpleWriteResponse, WriteMode,
-};
-use crate::common::utils::MaybeMut;
 use crate::component::aon_event::AonEvent;
-use crate::engine::{CombRegister, SeqRegister};
 #[proxy_use]
 use crate::engine::{
     Context, DisableableComponent, MainComponent, SkippableClockTreeNode, TickComponent,
     TickComponentExtra,
 };
-use crate::proxy::{AONEventProxy, RTCBypassProxy, RTCProxy};
+use crate::proxy::{AONEventProxy, RTCShadowProxy};
 use cc2650_constants::AON_RTC as RTC;
 use cmemu_common::HwRegister;
 use cmemu_proc_macros::{component_impl, handler, proxy_use};
 use log::{debug, log_enabled, trace, warn};
-use std::cmp::min;
-use std::ops::Range;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::mem;
 
 // TODO: implement correctness and timing tests
 // Some adjustment of timing is possible using AON_WUC, not implemented yet.
@@ -38,53 +23,92 @@ use std::ops::Range;
 // Events come from 3 channels, may be delayed and combined here.
 //
 // The RTC has an MCU-AON interface: the MCU side is clocked with HF, but the AON side with LF.
-// The RTC registers are clocked with LF and interact with the MCU-shadow-interface on LF's tick.
-// That means, writes by the MCU side are effectively delayed to be consumed later.
-// The same goes for reads: MCU has copies which are updated on LF ticks.
-// AON_RTC::SYNC addr is used to wait for the propagation (docs say 1-2 LF cycles)
-// TODO: implement the MCU-AON interface (rtc_bypass is part of a trick right now)
-// The interface should operate with the AHB clock and have effects on the slow clock.
-// Maybe we don't even need shadow registers: just using the 'next' part of flops may be enough.
-
-#[derive(MainComponent, TickComponent, TickComponentExtra, DisableableComponent)]
+// The MCU side is `RTCShadowComponent`: it answers the reads with shadow copies of the registers,
+// which are sent to it on every tick of the RTC, and passes the writes to `on_mcu_write`.
+// The writes go through a two-stage synchronizer clocked with LF, thus they take effect
+// 1-2 cycles of `SCLK_LF` after the MCU issued them (docs say 1-2 LF cycles for AON_RTC::SYNC).
+// A write to `SYNC` has only one stage to pass: it just waits for the next LF edge.
+//
+// Each channel raises its event (`RTC_CHx`) and sets its flag in `EVFLAGS` on a match.
+// Then, after `CTL.EV_DELAY` cycles, the channel raises its delayed event (`RTC_CHx_DLY`),
+// and the combined event (`RTC_COMB_DLY`) if it is selected in `CTL.COMB_EV_MASK`.
+// A new event of a channel, while its delayed event is pending, is merged with the latter.
+
+/// Cycles of `SCLK_LF` of the delays selected by `CTL.EV_DELAY`.
+const EV_DELAY_CYCLES: [u8; 14] = [0, 1, 2, 4, 8, 16, 32, 48, 64, 80, 96, 112, 128, 144];
+
+#[derive(
+    Clone,
+    MainComponent,
+    TickComponent,
+    TickComponentExtra,
+    DisableableComponent,
+    Serialize,
+    Deserialize,
+)]
 pub(crate) struct RTCComponent {
-    #[subcomponent(DriverSC)]
-    driver: BusDriver,
-
     counter: RtcCounter,
 
-    latched_subsec: Option<u32>,
-
-    #[flop]
-    subsecinc: SeqRegister<u32>,
-
-    #[flop]
-    ctl: SeqRegister<RTC::CTL::Register>,
-
-    #[flop]
-    chctl: SeqRegister<RTC::CHCTL::Register>,
-
-    // CombRegister so we can possibly mutate it both in Write and in Update
-    #[flop]
-    evflags: CombRegister<RTC::EVFLAGS::Register>,
+    subsecinc: u32,
+    #[serde(with = "crate::common::utils::serde_with::hw_register")]
+    ctl: RTC::CTL::Register,
+    #[serde(with = "crate::common::utils::serde_with::hw_register")]
+    chctl: RTC::CHCTL::Register,
+    evflags: u32,
+    /// `CH0CMP`, `CH1CMP` and `CH2CMP`.
+    cmp: [u32; RtcChannel::COUNT],
+    ch2cmpinc: u32,
+    ch1capt: u32,
+
+    /// Writes of the MCU in the synchronizer, in order.
+    mcu_writes: VecDeque<McuWrite>,
+    /// Cycles left until the delayed event of each channel.
+    delayed_events: [Option<u8>; RtcChannel::COUNT],
+    /// A capture event of the channel 1 arrived since the last tick.
+    capture_pending: bool,
+}
 
-    #[flop]
-    ch0cmp: SeqRegister<RTC::CH0CMP::Register>,
-    #[flop]
-    ch1cmp: SeqRegister<RTC::CH1CMP::Register>,
-    #[flop]
-    ch2cmp: SeqRegister<RTC::CH2CMP::Register>,
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+struct McuWrite {
+    addr: Address,
+    data: u32,
+    /// Passed the first stage of the synchronizer.
+    synchronized: bool,
+}
 
-    #[flop]
-    ch2cmpinc: SeqRegister<RTC::CH2CMPINC::Register>,
-    #[flop]
-    ch1capt: SeqRegister<RTC::CH1CAPT::Register>,
+/// The values of the registers of the RTC, mirrored by the MCU side of the MCU-AON interface.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub(crate) struct RtcRegisters {
+    pub(crate) ctl: u32,
+    pub(crate) evflags: u32,
+    pub(crate) sec: u32,
+    pub(crate) subsec: u32,
+    pub(crate) subsecinc: u32,
+    pub(crate) chctl: u32,
+    pub(crate) ch0cmp: u32,
+    pub(crate) ch1cmp: u32,
+    pub(crate) ch2cmp: u32,
+    pub(crate) ch2cmpinc: u32,
+    pub(crate) ch1capt: u32,
+}
 
-    // Right now, we have a mismatch in timings for the registers and need to fully implement
-    // the MCU-AON interface as described in [TI-TRM] 14.3 RTC Registers
-    any_write_waiting: bool,
+impl RtcRegisters {
+    pub(crate) fn new() -> Self {
+        Self {
+            ctl: RTC::CTL::RESET_VALUE,
+            evflags: RTC::EVFLAGS::RESET_VALUE,
+            sec: RTC::SEC::RESET_VALUE,
+            subsec: RTC::SUBSEC::RESET_VALUE,
+            subsecinc: RTC::SUBSECINC::RESET_VALUE,
+            chctl: RTC::CHCTL::RESET_VALUE,
+            ch0cmp: RTC::CH0CMP::RESET_VALUE,
+            ch1cmp: RTC::CH1CMP::RESET_VALUE,
+            ch2cmp: RTC::CH2CMP::RESET_VALUE,
+            ch2cmpinc: RTC::CH2CMPINC::RESET_VALUE,
+            ch1capt: RTC::CH1CAPT::RESET_VALUE,
+        }
+    }
 }
-type BusDriver = SimpleSynchronousSlaveInterface<DriverSC, RTCComponent>;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 enum RtcChannel {
@@ -93,264 +117,309 @@ enum RtcChannel {
     Ch2,
 }
 
+impl RtcChannel {
+    const COUNT: usize = 3;
+    const ALL: [Self; Self::COUNT] = [Self::Ch0, Self::Ch1, Self::Ch2];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    fn evflag(self) -> u32 {
+        match self {
+            Self::Ch0 => RTC::EVFLAGS::CH0::BIT_MASK,
+            Self::Ch1 => RTC::EVFLAGS::CH1::BIT_MASK,
+            Self::Ch2 => RTC::EVFLAGS::CH2::BIT_MASK,
+        }
+    }
+
+    fn comb_ev_mask(self) -> u32 {
+        match self {
+            Self::Ch0 => RTC::CTL::COMB_EV_MASK::E::CH0,
+            Self::Ch1 => RTC::CTL::COMB_EV_MASK::E::CH1,
+            Self::Ch2 => RTC::CTL::COMB_EV_MASK::E::CH2,
+        }
+    }
+
+    fn event(self) -> AonEvent {
+        match self {
+            Self::Ch0 => AonEvent::RTC_CH0,
+            Self::Ch1 => AonEvent::RTC_CH1,
+            Self::Ch2 => AonEvent::RTC_CH2,
+        }
+    }
+
+    fn delayed_event(self) -> AonEvent {
+        match self {
+            Self::Ch0 => AonEvent::RTC_CH0_DLY,
+            Self::Ch1 => AonEvent::RTC_CH1_DLY,
+            Self::Ch2 => AonEvent::RTC_CH2_DLY,
+        }
+    }
+}
+
+/// Events of the channels, indexed with `RtcChannel::index`.
+type ChannelEvents = [bool; RtcChannel::COUNT];
+
 #[component_impl(rtc)]
 impl RTCComponent {
     pub fn new() -> Self {
         Self {
-            driver: Default::default(),
-
             counter: RtcCounter::new(),
-            latched_subsec: None,
-
-            subsecinc: SeqRegister::new(RTC::SUBSECINC::RESET_VALUE),
 
-            ctl: SeqRegister::new(RTC::CTL::Register::new()),
-            chctl: SeqRegister::new(RTC::CHCTL::Register::new()),
-            evflags: CombRegister::new(RTC::EVFLAGS::Register::new()),
-            ch0cmp: SeqRegister::new(RTC::CH0CMP::Register::new()),
-            ch1cmp: SeqRegister::new(RTC::CH1CMP::Register::new()),
-            ch2cmp: SeqRegister::new(RTC::CH2CMP::Register::new()),
-
-            ch2cmpinc: SeqRegister::new(RTC::CH2CMPINC::Register::new()),
-            ch1capt: SeqRegister::new(RTC::CH1CAPT::Register::new()),
-
-            any_write_waiting: false,
+            subsecinc: RTC::SUBSECINC::RESET_VALUE,
+            ctl: RTC::CTL::Register::new(),
+            chctl: RTC::CHCTL::Register::new(),
+            evflags: RTC::EVFLAGS::RESET_VALUE,
+            cmp: [
+                RTC::CH0CMP::RESET_VALUE,
+                RTC::CH1CMP::RESET_VALUE,
+                RTC::CH2CMP::RESET_VALUE,
+            ],
+            ch2cmpinc: RTC::CH2CMPINC::RESET_VALUE,
+            ch1capt: RTC::CH1CAPT::RESET_VALUE,
+
+            mcu_writes: VecDeque::new(),
+            delayed_events: [None; RtcChannel::COUNT],
+            capture_pending: false,
         }
     }
 
     pub fn tick(&mut self, ctx: &mut Context) {
-        self.any_write_waiting = false;
-        BusDriver::run_driver(self, ctx);
+        let (consumed_writes, mut events) = self.consume_mcu_writes(ctx);
+        self.tick_delayed_events(ctx);
+
+        let counter_events = self.tick_counter(u128::from(self.subsecinc));
+        if mem::take(&mut self.capture_pending) && self.capture() {
+            events[RtcChannel::Ch1.index()] = true;
+        }
+        for (event, counter_event) in events.iter_mut().zip(counter_events) {
+            *event |= counter_event;
+        }
+        self.trigger_events(ctx, events);
+
+        RTCShadowProxy.on_rtc_update(ctx, self.registers(), consumed_writes);
+    }
+
+    pub fn tock(&mut self, _ctx: &mut Context) {}
+
+    fn registers(&self) -> RtcRegisters {
+        RtcRegisters {
+            ctl: self.ctl.read(),
+            evflags: self.evflags,
+            sec: self.counter.get_sec(),
+            subsec: self.counter.get_subsec(),
+            subsecinc: self.subsecinc,
+            chctl: self.chctl.read(),
+            ch0cmp: self.cmp[RtcChannel::Ch0.index()],
+            ch1cmp: self.cmp[RtcChannel::Ch1.index()],
+            ch2cmp: self.cmp[RtcChannel::Ch2.index()],
+            ch2cmpinc: self.ch2cmpinc,
+            ch1capt: self.ch1capt,
+        }
+    }
 
-        self.tick_counter(ctx, u128::from(*self.subsecinc));
+    /// Is the channel enabled in the compare mode?
+    fn is_comparing(&self, ch: RtcChannel) -> bool {
+        let chctl = self.chctl.bitfields();
+        match ch {
+            RtcChannel::Ch0 => chctl.CH0_EN() != 0,
+            RtcChannel::Ch1 => chctl.CH1_EN() != 0 && chctl.CH1_CAPT_EN() == 0,
+            RtcChannel::Ch2 => chctl.CH2_EN() != 0,
+        }
     }
 
-    pub fn tock(&mut self, ctx: &mut Context) {
-        BusDriver::tock(self, ctx);
+    /// Cycles of `SCLK_LF` from an event to its delayed event.
+    fn event_delay(&self) -> u8 {
+        let ev_delay = self.ctl.bitfields().EV_DELAY();
+        EV_DELAY_CYCLES
+            .get(usize::from(ev_delay))
+            .copied()
+            .unwrap_or_else(|| {
+                paranoid!(
+                    warn,
+                    "Reserved RTC EV_DELAY {ev_delay}, using the longest delay"
+                );
+                EV_DELAY_CYCLES[EV_DELAY_CYCLES.len() - 1]
+            })
     }
 
-    // Returns true iff this call caused an interrupt to be raised.
-    #[allow(clippy::similar_names)]
-    fn tick_counter(&mut self, ctx: &mut Context, counter_delta: u128) -> bool {
-        let ctl = self.ctl.bitfields();
-        if ctl.EN() == 0 {
-            return false;
+    /// Advances the counter and returns the compare events.
+    fn tick_counter(&mut self, counter_delta: u128) -> ChannelEvents {
+        let mut events = [false; RtcChannel::COUNT];
+        if self.ctl.bitfields().EN() == 0 {
+            return events;
         }
-        let chctl = self.chctl.bitfields();
         let compare_before = self.counter.get_compare_value();
         self.counter.tick(counter_delta);
         let compare_after = self.counter.get_compare_value();
         let compare_not_wrapped = compare_before < compare_after;
 
-        let ch0_event = if chctl.CH0_EN() != 0 {
-            let ch0cmp = self.ch0cmp.read();
-            if compare_not_wrapped {
-                compare_before < ch0cmp && ch0cmp <= compare_after
-            } else {
-                compare_before < ch0cmp || ch0cmp <= compare_after
-            }
-        } else {
-            false
-        };
-        let ch1_event = if chctl.CH1_EN() != 0 && chctl.CH1_CAPT_EN() == 0 {
-            let ch1cmp = self.ch1cmp.read();
-            if compare_not_wrapped {
-                compare_before < ch1cmp && ch1cmp <= compare_after
-            } else {
-                compare_before < ch1cmp || ch1cmp <= compare_after
-            }
-        } else {
-            false
-        };
-        let ch2_event = if chctl.CH2_EN() != 0 {
-            let ch2cmp = self.ch2cmp.read();
-            if compare_not_wrapped {
-                compare_before < ch2cmp && ch2cmp <= compare_after
-            } else {
-                compare_before < ch2cmp || ch2cmp <= compare_after
-            }
-        } else {
-            false
-        };
-        if ch2_event && chctl.CH2_CONT_EN() != 0 {
-            self.ch2cmp
-                .set_next_mutated_reg(self.ch2cmp.read().wrapping_add(self.ch2cmpinc.read()));
+        for ch in RtcChannel::ALL {
+            let cmp = self.cmp[ch.index()];
+            events[ch.index()] = self.is_comparing(ch)
+                && if compare_not_wrapped {
+                    compare_before < cmp && cmp <= compare_after
+                } else {
+                    compare_before < cmp || cmp <= compare_after
+                };
+        }
+        // [TI-TRM] 14.4.1.10 CH2CMPINC Register: the auto-increment of the channel 2.
+        if events[RtcChannel::Ch2.index()] && self.chctl.bitfields().CH2_CONT_EN() != 0 {
+            let ch2cmp = &mut self.cmp[RtcChannel::Ch2.index()];
+            *ch2cmp = ch2cmp.wrapping_add(self.ch2cmpinc);
         }
 
-        if ch1_event && self.evflags.bitfields().CH1() != 0 {
+        if events[RtcChannel::Ch1.index()] && self.evflags & RtcChannel::Ch1.evflag() != 0 {
             warn!(
                 "We allowed RTC interrupt despite bit being set in EVFLAGS.\
                  Write a test for that. Contiki seems to rely on this!"
             );
         }
-        self.trigger_events(ctx, (ch0_event, ch1_event, ch2_event))
+        events
+    }
+
+    /// Sets the flags and raises the events of the channels, and starts their delayed events.
+    fn trigger_events(&mut self, ctx: &mut Context, events: ChannelEvents) {
+        let delay = self.event_delay();
+        let mut delayed_events = [false; RtcChannel::COUNT];
+        for ch in RtcChannel::ALL.into_iter().filter(|ch| events[ch.index()]) {
+            self.evflags |= ch.evflag();
+            AONEventProxy.notify(ctx, ch.event());
+            if delay == 0 {
+                delayed_events[ch.index()] = true;
+            } else {
+                self.delayed_events[ch.index()].get_or_insert(delay);
+            }
+        }
+        self.raise_delayed_events(ctx, delayed_events);
     }
 
-    // TODO: Should not be called more than once per cycle I guess. This impl allows for that!
-    #[allow(clippy::similar_names)]
-    fn trigger_events(&mut self, ctx: &mut Context, ch_events: (bool, bool, bool)) -> bool {
-        let (ch0_event, ch1_event, ch2_event) = ch_events;
-        let ctl = self.ctl.bitfields();
-
-        if (ch0_event || ch1_event || ch2_event) && ctl.EV_DELAY() != 0 {
-            unimplemented!("delaying RTC events is unimplemented");
+    fn tick_delayed_events(&mut self, ctx: &mut Context) {
+        let mut delayed_events = [false; RtcChannel::COUNT];
+        for (cycles_left, event) in self.delayed_events.iter_mut().zip(&mut delayed_events) {
+            match cycles_left {
+                Some(1) => {
+                    *cycles_left = None;
+                    *event = true;
+                }
+                Some(cycles) => *cycles -= 1,
+                None => {}
+            }
         }
+        self.raise_delayed_events(ctx, delayed_events);
+    }
 
-        if ch0_event {
-            self.evflags.next_builder().mut_bitfields().set_CH0(1);
-            AONEventProxy.notify(ctx, AonEvent::RTC_CH0);
-        }
-        if ch1_event {
-            self.evflags.next_builder().mut_bitfields().set_CH1(1);
-            AONEventProxy.notify(ctx, AonEvent::RTC_CH1);
-        }
-        if ch2_event {
-            self.evflags.next_builder().mut_bitfields().set_CH2(1);
-            AONEventProxy.notify(ctx, AonEvent::RTC_CH2);
+    fn raise_delayed_events(&mut self, ctx: &mut Context, delayed_events: ChannelEvents) {
+        let comb_ev_mask = u32::from(self.ctl.bitfields().COMB_EV_MASK());
+        let mut combined_event = false;
+        for ch in RtcChannel::ALL
+            .into_iter()
+            .filter(|ch| delayed_events[ch.index()])
+        {
+            AONEventProxy.notify(ctx, ch.delayed_event());
+            combined_event |= comb_ev_mask & ch.comb_ev_mask() != 0;
         }
 
-        let combined_event = {
-            let comb_ev_mask = u32::from(ctl.COMB_EV_MASK());
-            let mut combined_event = false;
-            if comb_ev_mask & RTC::CTL::COMB_EV_MASK::E::CH0 != 0 {
-                combined_event |= ch0_event;
-            }
-            if comb_ev_mask & RTC::CTL::COMB_EV_MASK::E::CH1 != 0 {
-                combined_event |= ch1_event;
-            }
-            if comb_ev_mask & RTC::CTL::COMB_EV_MASK::E::CH2 != 0 {
-                combined_event |= ch2_event;
-            }
-            combined_event
-        };
-
         if combined_event {
-            trace!("RTC Combined Event: ch0:{ch0_event:?}, ch1:{ch1_event:?}, ch2:{ch2_event:?}");
+            trace!("RTC Combined Event: {delayed_events:?}");
             AONEventProxy.notify(ctx, AonEvent::RTC_COMB_DLY);
         }
-        ch0_event | ch1_event | ch2_event
     }
 
-    #[handler]
-    pub fn on_ch1capt_event(&mut self, ctx: &mut Context) {
-        // FIXME: this should be delayed to a tick, so it is correctly handled while skipping cycles
-
+    /// Captures the counter in the channel 1, returns true if it is enabled in the capture mode.
+    fn capture(&mut self) -> bool {
         // [TI-TRM] 14.4.1.11 CH1CAPT Register
         // NOTE: This is captured only on pos-edge (matters if the event is held up)
         let chctl = self.chctl.bitfields();
         if chctl.CH1_EN() == 1 && chctl.CH1_CAPT_EN() == 1 {
-            // TODO: write tests
-            self.ch1capt
-                .set_next_mutated_reg(self.counter.get_compare_value());
-
-            // Trigger event of CH1
-            self.trigger_events(ctx, (false, true, false));
-            if self.ctl.bitfields().EV_DELAY() != 0 {
-                unimplemented!("delaying RTC events is unimplemented");
-            }
+            self.ch1capt = self.counter.get_compare_value();
+            trace!("RTC captured {:#x}", self.ch1capt);
+            true
+        } else {
+            false
         }
     }
 
     #[handler]
-    pub fn bypass_write(&mut self, ctx: &mut Context, address: Address, mut data: DataBus) {
-        let aligned_address = <Self as AlignedHandler>::ALIGN.align_addr(address);
-        if data.size() != <Self as AlignedHandler>::ALIGN {
-            let fill: DataBus =
-                Self::get_data_for_address(MaybeMut::Ref(self), ctx, aligned_address)
-                    .unwrap()
-                    .into();
-            data = fill.emplace_in_aligned(address, data);
-        }
-        self.set_data_for_address(
-            ctx,
-            aligned_address,
-            <Self as AlignedHandler>::Native::from(data),
-        );
+    pub fn on_ch1capt_event(&mut self, _ctx: &mut Context) {
+        // The event is sampled on the next tick of the slow clock.
+        self.capture_pending = true;
     }
 
+    /// A write from the MCU side of the MCU-AON interface enters the synchronizer.
     #[handler]
-    pub fn request_bypass_read(&mut self, ctx: &mut Context, address: Address, size: Size) {
-        let data = Self::get_data_for_address(
-            MaybeMut::Mut(self),
-            ctx,
-            <Self as AlignedHandler>::ALIGN.align_addr(address),
-        )
-        .unwrap();
-        RTCBypassProxy.bypass_read(
-            ctx,
-            address,
-            DataBus::from(data).extract_from_aligned(address, size),
-        );
-    }
-
-    fn ch1_is_cmp_mode(&self) -> bool {
-        let chctl = self.chctl.bitfields();
-        chctl.CH1_EN() == 1 && chctl.CH1_CAPT_EN() == 0
+    pub fn on_mcu_write(&mut self, _ctx: &mut Context, addr: Address, data: u32) {
+        self.mcu_writes.push_back(McuWrite {
+            addr,
+            data,
+            synchronized: false,
+        });
+    }
+
+    /// Applies the writes which went through the synchronizer,
+    /// returns their number and the immediate compare events.
+    fn consume_mcu_writes(&mut self, ctx: &mut Context) -> (usize, ChannelEvents) {
+        let mut consumed_writes = 0;
+        let mut events = [false; RtcChannel::COUNT];
+        while let Some(&write) = self.mcu_writes.front() {
+            if !write.synchronized && write.addr != RTC::SYNC::ADDR {
+                break;
+            }
+            self.mcu_writes.pop_front();
+            consumed_writes += 1;
+            if let Some(ch) = self.set_data_for_address(ctx, write.addr, write.data) {
+                events[ch.index()] = true;
+            }
+        }
+        for write in &mut self.mcu_writes {
+            write.synchronized = true;
+        }
+        (consumed_writes, events)
     }
 
     /// How many cycles till the RTC generates any new event? `None` if never/unbounded.
     fn cycles_until_event(&self) -> Option<u64> {
-        let chctl = self.chctl.bitfields();
-        let ctl = self.ctl.bitfields();
-
-        if ctl.EN() == 0 || (chctl.CH0_EN() == 0 && !self.ch1_is_cmp_mode() && chctl.CH2_EN() == 0)
+        let delayed_event = self
+            .delayed_events
+            .iter()
+            .flatten()
+            .min()
+            .map(|&cycles| u64::from(cycles));
+
+        if self.ctl.bitfields().EN() == 0
+            || !RtcChannel::ALL.iter().any(|&ch| self.is_comparing(ch))
         {
-            return None;
+            return delayed_event;
         }
 
         if log_enabled!(log::Level::Debug) {
             // Gate against side effects
             debug!(
-                "cycles_until_interrupt(): CH1CMP value: {:} next: {:?}, current cmp: {:}",
-                self.ch1cmp.read(),
-                self.ch1cmp.peek_next(),
+                "cycles_until_interrupt(): CH1CMP value: {:}, current cmp: {:}",
+                self.cmp[RtcChannel::Ch1.index()],
                 self.counter.get_compare_value()
             );
         }
 
-        if self.evflags.bitfields().CH1() != 0 {
+        if self.evflags & RtcChannel::Ch1.evflag() != 0 {
             warn!(
                 "evflags.CH1 not cleared. Should we generate an event? But Contiki seems to rely on it."
             );
         }
 
-        if self.ctl.bitfields().EV_DELAY() != 0 {
-            unimplemented!("EV delay may give additional results in RTC::cycles_until_event");
-        }
-
-        // Use next value if written but not yet flopped
-        let mut result = u64::MAX;
-        if chctl.CH0_EN() == 1 {
-            result = min(
-                result,
-                self.counter.ticks_until(
-                    *self.subsecinc,
-                    self.ch0cmp.peek_next().unwrap_or(&*self.ch0cmp).read(),
-                ),
-            );
-        }
-        if self.ch1_is_cmp_mode() {
-            result = min(
-                result,
-                self.counter.ticks_until(
-                    *self.subsecinc,
-                    self.ch1cmp.peek_next().unwrap_or(&*self.ch1cmp).read(),
-                ),
-            );
-        }
-        if chctl.CH2_EN() == 1 {
-            result = min(
-                result,
-                self.counter.ticks_until(
-                    *self.subsecinc,
-                    self.ch2cmp.peek_next().unwrap_or(&*self.ch2cmp).read(),
-                ),
-            );
-        }
-        Some(result)
+        RtcChannel::ALL
+            .into_iter()
+            .filter(|&ch| self.is_comparing(ch))
+            .map(|ch| {
+                self.counter
+                    .ticks_until(self.subsecinc, self.cmp[ch.index()])
+            })
+            .chain(delayed_event)
+            .min()
     }
 
-    fn check_for_immediate_event(&mut self, ctx: &mut Context, ch: RtcChannel, compare_value: u32) {
+    fn check_for_immediate_event(&self, ch: RtcChannel) -> bool {
         // [TI-TRM] 14.2.3.1 Capture and Compare:
         // "If a compare value is set so that the compare value minus current value is larger than the
         // seconds wrap-around time minus one second (2^32 × SCLK_LFperiod – 1), an immediate
@@ -364,51 +433,43 @@ impl RTCComponent {
         // to synchronization."
 
         // TODO: check for exactness
-        if self.counter.get_compare_value().wrapping_sub(compare_value)
-            <= RtcCounter::COMPARE_VALUE_ONE_SECOND
-        {
-            self.trigger_events(
-                ctx,
-                (
-                    ch == RtcChannel::Ch0,
-                    ch == RtcChannel::Ch1,
-                    ch == RtcChannel::Ch2,
-                ),
-            );
-        }
-    }
-
-    #[handler]
-    pub fn on_new_ahb_slave_input(
-        &mut self,
-        ctx: &mut Context,
-        msg: MasterToSlaveWires<<RTCComponent as AHBPortConfig>::Data>,
-    ) {
-        <Self as AHBSlavePortInput>::on_ahb_input(self, ctx, msg);
+        self.ctl.bitfields().EN() != 0
+            && self.is_comparing(ch)
+            && self
+                .counter
+                .get_compare_value()
+                .wrapping_sub(self.cmp[ch.index()])
+                <= RtcCounter::COMPARE_VALUE_ONE_SECOND
     }
 
+    /// Applies a write of the MCU, returns the channel with an immediate compare event.
     fn set_data_for_address(
         &mut self,
         ctx: &mut Context,
         addr: Address,
-        data: <RTCComponent as AlignedHandler>::Native,
-    ) {
+        data: u32,
+    ) -> Option<RtcChannel> {
         trace!(
             "RTC write {addr:?}(\"{}\") = {data:x?} [t={:?}, cmp={:?}]",
             ctx.display_named_address(addr),
             ctx.event_queue().get_current_time(),
             self.counter.get_compare_value(),
         );
+        let compare_channel = match addr {
+            RTC::CH0CMP::ADDR => Some(RtcChannel::Ch0),
+            RTC::CH1CMP::ADDR => Some(RtcChannel::Ch1),
+            RTC::CH2CMP::ADDR => Some(RtcChannel::Ch2),
+            _ => None,
+        };
         match addr {
             RTC::CTL::ADDR => {
-                let mut next = *self.ctl;
-                next.mutate(data);
+                self.ctl.mutate(data);
 
-                if next.bitfields().RESET() != 0 {
+                if self.ctl.bitfields().RESET() != 0 {
                     self.counter = RtcCounter::new();
-                    next.mut_bitfields().set_RESET(0);
+                    self.ctl.mut_bitfields().set_RESET(0);
                 }
-                if next.bitfields().RTC_UPD_EN() == 0 {
+                if self.ctl.bitfields().RTC_UPD_EN() == 0 {
                     // paranoid! as Contiki sets this up for some reason (it is also needed by the RFC)
                     paranoid!(
                         warn,
@@ -416,31 +477,19 @@ impl RTCComponent {
                     );
                 }
                 debug_assert!(
-                    next.bitfields().RTC_4KHZ_EN() == 0,
+                    self.ctl.bitfields().RTC_4KHZ_EN() == 0,
                     "RTC_4KHZ unimplemented"
                 );
-                self.ctl.set_next(next);
             }
             RTC::EVFLAGS::ADDR => {
                 // EVFlag is Write-1-Clears
-                let reg = RTC::EVFLAGS::Register::from(data).bitfields();
-
-                let next = self.evflags.next_builder().mut_bitfields();
                 // "Channel 0 event flag, set when CHCTL.CH0_EN = 1 and the RTC
                 // value matches or passes the CH0CMP value.
                 // Writing 1 clears this flag."
                 // TODO: "Note that a new event can not occur on this
                 //        channel in first 2 SCLK_LF cycles after a clearance."
                 // Whatever it means!
-                if reg.CH0() != 0 {
-                    next.set_CH0(0);
-                }
-                if reg.CH1() != 0 {
-                    next.set_CH1(0);
-                }
-                if reg.CH2() != 0 {
-                    next.set_CH2(0);
-                }
+                self.evflags &= !data;
             }
             RTC::SEC::ADDR => {
                 self.counter.set_sec(data);
@@ -448,23 +497,18 @@ impl RTCComponent {
             RTC::SUBSEC::ADDR => {
                 self.counter.set_subsec(data);
             }
-            RTC::CHCTL::ADDR => {
-                self.chctl.set_next_mutated_reg(data);
-            }
-            RTC::CH0CMP::ADDR => {
-                let next = self.ch0cmp.set_next_mutated_reg(data);
-                self.check_for_immediate_event(ctx, RtcChannel::Ch0, next.read());
+            RTC::SUBSECINC::ADDR => {
+                self.subsecinc = data & RTC::SUBSECINC::VALUEINC::BIT_MASK;
             }
-            RTC::CH1CMP::ADDR => {
-                let next = self.ch1cmp.set_next_mutated_reg(data);
-                self.check_for_immediate_event(ctx, RtcChannel::Ch1, next.read());
+            RTC::CHCTL::ADDR => {
+                self.chctl.mutate(data);
             }
-            RTC::CH2CMP::ADDR => {
-                let next = self.ch2cmp.set_next_mutated_reg(data);
-                self.check_for_immediate_event(ctx, RtcChannel::Ch2, next.read());
+            RTC::CH0CMP::ADDR | RTC::CH1CMP::ADDR | RTC::CH2CMP::ADDR => {
+                let ch = compare_channel.expect("a compare register");
+                self.cmp[ch.index()] = data;
             }
             RTC::CH2CMPINC::ADDR => {
-                self.ch2cmpinc.set_next_mutated_reg(data);
+                self.ch2cmpinc = data;
             }
             RTC::CH1CAPT::ADDR => {
                 paranoid!(
@@ -472,134 +516,11 @@ impl RTCComponent {
                     "Writing to a read-only RTC::CH1CAPT register: {data:}"
                 );
             }
-            RTC::SYNC::ADDR => {
-                // This just sets the "any write pending" flag
-                // TODO: implement MCU-AON interface
-            }
+            // Only waits for an edge of the slow clock in the synchronizer.
+            RTC::SYNC::ADDR => {}
             a => unimplemented!("Requested RTC data write {:x?} for address {:?}", data, a),
         }
-        self.any_write_waiting = true;
-    }
-
-    // Mutability of MaybeMut may be confusing, but it indicates if side effects are allowed.
-    // We need a mut smart pointer to get a mut reference from it.
-    // Returns None of pending
-    fn get_data_for_address(
-        mut this: MaybeMut<Self>,
-        ctx: &Context,
-        addr: Address,
-    ) -> Option<<RTCComponent as AlignedHandler>::Native> {
-        let res = match addr {
-            RTC::CTL::ADDR => Some(this.ctl.read()),
-            RTC::EVFLAGS::ADDR => Some(this.evflags.read()),
-            RTC::SEC::ADDR => {
-                let sec = this.counter.get_sec();
-                let subsec = this.counter.get_subsec();
-                if let Some(mut_self) = this.get_mut() {
-                    mut_self.latched_subsec = Some(subsec);
-                }
-                Some(sec)
-            }
-            RTC::SUBSEC::ADDR => this.get_mut().and_then(|t| t.latched_subsec.take()).or_else(
-                || {
-                    paranoid!(
-                        warn,
-                        "You should've read SEC register first. Real hardware's output may be unreliable."
-                    );
-                    Some(this.counter.get_subsec())
-                }
-            ),
-            RTC::SUBSECINC::ADDR => Some(*this.subsecinc),
-            RTC::CHCTL::ADDR => Some(this.chctl.read()),
-            RTC::CH0CMP::ADDR => Some(this.ch0cmp.read()),
-            RTC::CH1CMP::ADDR => Some(this.ch1cmp.read()),
-            RTC::CH2CMP::ADDR => Some(this.ch2cmp.read()),
-            RTC::CH2CMPINC::ADDR => Some(this.ch2cmpinc.read()),
-            RTC::CH1CAPT::ADDR => Some(this.ch1capt.read()),
-            RTC::SYNC::ADDR if this.any_write_waiting && this.get_mut().is_some() => {
-                // TODO: wait until there're no outstanding write requests
-                None
-            }
-            // The register always returns 0
-            RTC::SYNC::ADDR => Some(0),
-            a => unimplemented!(
-                "Requested RTC data read for address {:?}: {}",
-                a,
-                ctx.display_named_address(a)
-            ),
-        };
-        trace!(
-            "RTC read {addr:?}(\"{}\") = {res:x?} [t={:?}, cmp={:?}]",
-            ctx.display_named_address(addr),
-            ctx.event_queue().get_current_time(),
-            this.counter.get_compare_value(),
-        );
-        res
-    }
-}
-
-bridge_ports!(@slave RTCComponent => @slave BusDriver);
-
-#[component_impl(rtc)]
-impl AHBPortConfig for RTCComponent {
-    type Data = DataBus;
-    type Component = Self;
-    const TAG: &'static str = "RTC";
-}
-#[component_impl(rtc)]
-impl AHBSlavePortProxiedInput for RTCComponent {
-    fn proxy_ahb_input(ctx: &mut Context, msg: MasterToSlaveWires<Self::Data>) {
-        RTCProxy.on_new_ahb_slave_input(ctx, msg);
-    }
-}
-
-#[component_impl(rtc)]
-impl AlignedHandler for RTCComponent {
-    const WRITE_MODE: WriteMode = WriteMode::Combinatorial;
-    const ALIGN: Size = Size::Word;
-    type Native = u32;
-
-    fn read_for_write_filler(
-        slave: &Self::Component,
-        ctx: &Context,
-        address: Address,
-    ) -> Self::Native {
-        Self::get_data_for_address(MaybeMut::Ref(slave), ctx, address).unwrap()
-    }
-
-    fn pre_write(
-        _slave: &mut Self::Component,
-        _ctx: &mut Context,
-        _address: Address,
-    ) -> SimpleWriteResponse {
-        // TODO: why SUCCESS causes an error?
-        SimpleWriteResponse::Pending
-    }
-
-    fn read_data(
-        slave: &mut Self::Component,
-        ctx: &mut Context,
-        address: Address,
-    ) -> SimpleResponse<Self::Native> {
-        let data = Self::get_data_for_address(MaybeMut::Mut(slave), ctx, address);
-        if let Some(data) = data {
-            SimpleResponse::Success(data)
-        } else {
-            SimpleResponse::Pending
-        }
-    }
-
-    fn write_data(
-        slave: &mut Self::Component,
-        ctx: &mut Context,
-        address: Address,
-        data: Self::Native,
-        post_success: bool,
-    ) -> SimpleWriteResponse {
-        if post_success {
-            slave.set_data_for_address(ctx, address, data);
-        }
-        SimpleWriteResponse::SUCCESS
+        compare_channel.filter(|&ch| self.check_for_immediate_event(ch))
     }
 }
 
@@ -611,7 +532,7 @@ impl SkippableClockTreeNode for RTCComponent {
         _parent: Self::IdSpace,
         _extra: &mut Self::Extra,
     ) -> u64 {
-        if !comp.can_be_disabled_now() {
+        if !comp.can_be_disabled_now() || !comp.mcu_writes.is_empty() || comp.capture_pending {
             0
         } else if let Some(cycs) = comp.cycles_until_event() {
             // -1 because we don't want to raise the interrupt in skip
@@ -630,25 +551,27 @@ impl SkippableClockTreeNode for RTCComponent {
     ) {
         let this = comp;
         let pre_compare_value = this.counter.get_compare_value();
-        let caused_interrupt = this.tick_counter(
-            ctx,
-            u128::from(*this.subsecinc) * u128::from(skipped_cycles),
-        );
+        let events = this.tick_counter(u128::from(this.subsecinc) * u128::from(skipped_cycles));
         let post_compare_value = this.counter.get_compare_value();
         trace!(
-            "RTC t={:#?} cnt={:?} sleepcycles={skipped_cycles} pre={pre_compare_value} post={post_compare_value} ch1cmp={} caused={caused_interrupt}",
+            "RTC t={:#?} cnt={:?} sleepcycles={skipped_cycles} pre={pre_compare_value} post={post_compare_value} ch1cmp={} events={events:?}",
             ctx.event_queue().get_current_time(),
             this.counter,
-            this.ch1cmp.read()
+            this.cmp[RtcChannel::Ch1.index()]
         );
 
         assert!(
-            !caused_interrupt,
+            !events.contains(&true),
             "Emulate_skipped_cycles caused an interrupt from RTC! \
             pre_compare_value: {pre_compare_value:x}, CH1CMP: {:x} \
             post_compare_value: {post_compare_value:x}",
-            this.ch1cmp.read()
+            this.cmp[RtcChannel::Ch1.index()]
         );
+        for cycles_left in this.delayed_events.iter_mut().flatten() {
+            *cycles_left = u8::try_from(u64::from(*cycles_left) - skipped_cycles)
+                .expect("skipped less cycles than left");
+        }
+        RTCShadowProxy.on_rtc_update(ctx, this.registers(), 0);
     }
 }
 
@@ -660,7 +583,7 @@ impl SkippableClockTreeNode for RTCComponent {
 /// - `reg[37:6]` is readable as SUBSEC
 /// - `reg[69:38]` is readable as SEC
 /// - `reg[53:22]` is used for comparators to trigger events
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
 struct RtcCounter {
     counter: u128,
 }
@@ -0,0 +1,263 @@
+//! The MCU side of the MCU-AON interface of the RTC.
+//!
+//! [TI-TRM] 14.3 RTC Registers: the registers of the RTC are in the AON domain, clocked with
+//! `SCLK_LF`, while the MCU accesses them on its own clock through an interface holding
+//! shadow copies of them.
+//! Reads return the shadow copies, which the RTC refreshes on every `SCLK_LF` tick, so they
+//! are fast, but may be out of date by up to one cycle of `SCLK_LF`.
+//! Writes are posted: the MCU continues, while the RTC consumes them through a synchronizer,
+//! 1-2 cycles of `SCLK_LF` later (see `RTCComponent::on_mcu_write`).
+//! Reading `SYNC` stalls until all the writes reached the RTC, and writing `SYNC`
+//! makes the next read of it wait for an `SCLK_LF` edge.
+//!
+//! TODO: the timings of the writes on the MCU side and of the reads of the registers other than
+//!       `SEC`, `SUBSEC` and `SYNC` are not measured.
+
+use crate::bridge_ports;
+#[proxy_use]
+use crate::common::Address;
+#[proxy_use]
+use crate::common::new_ahb::databus::DataBus;
+use crate::common::new_ahb::ports::AHBSlavePortProxiedInput;
+#[proxy_use]
+use crate::common::new_ahb::ports::{AHBPortConfig, AHBSlavePortInput};
+#[proxy_use]
+use crate::common::new_ahb::signals::{MasterToSlaveWires, Size};
+use crate::common::new_ahb::slave_driver::stateless_simplifiers::AlignedHandler;
+use crate::common::new_ahb::slave_driver::{
+    SimpleResponse, SimpleSynchronousSlaveInterface, SimpleWriteResponse, WriteMode,
+};
+#[proxy_use]
+use crate::component::rtc::RtcRegisters;
+#[proxy_use]
+use crate::engine::{
+    Context, DisableableComponent, MainComponent, SkippableClockTreeNode, TickComponent,
+    TickComponentExtra,
+};
+use crate::proxy::{RTCProxy, RTCShadowProxy};
+use cc2650_constants::AON_RTC as RTC;
+use cmemu_proc_macros::{component_impl, handler, proxy_use};
+use core::ops::Range;
+use log::trace;
+use serde::{Deserialize, Serialize};
+
+pub const RTC_SHADOW_ROUTE_INJECTION: Range<Address> = RTC::ADDR_SPACE;
+
+/// Waitstates of the reads of the shadow registers.
+///
+/// Measured on the hardware for `SEC` (see the `need_review/rtc` flash test), where `SUBSEC`
+/// took one cycle more. The other registers are assumed to behave like `SEC`.
+const SHADOW_READ_WAITSTATES: u8 = 11;
+/// Waitstates of a read of `SYNC` without outstanding writes, measured on the hardware.
+const SYNC_READ_WAITSTATES: u8 = 14;
+
+#[derive(
+    Clone,
+    MainComponent,
+    SkippableClockTreeNode,
+    TickComponent,
+    TickComponentExtra,
+    DisableableComponent,
+)]
+#[skippable_if_disableable]
+#[derive(Serialize, Deserialize)]
+pub(crate) struct RTCShadowComponent {
+    #[subcomponent(DriverSC)]
+    driver: BusDriver,
+
+    /// The values of the registers at the last tick of the RTC.
+    registers: RtcRegisters,
+    /// `SUBSEC` latched by the last read of `SEC`, returned by the next read of `SUBSEC`.
+    latched_subsec: Option<u32>,
+
+    /// Writes sent to the RTC, but not consumed by it yet.
+    outstanding_writes: usize,
+    /// Waitstates left of the current read, if it started.
+    read_waitstates: Option<u8>,
+}
+type BusDriver = SimpleSynchronousSlaveInterface<DriverSC, RTCShadowComponent>;
+
+#[component_impl(rtc_shadow)]
+impl RTCShadowComponent {
+    pub(crate) fn new() -> Self {
+        Self {
+            driver: Default::default(),
+
+            registers: RtcRegisters::new(),
+            latched_subsec: None,
+
+            outstanding_writes: 0,
+            read_waitstates: None,
+        }
+    }
+
+    /// Resets the bus interface, but keeps the shadow registers in sync with the RTC,
+    /// which survives a warm reset.
+    pub(crate) fn reset_keeping_registers(&mut self) {
+        self.driver = Default::default();
+        self.read_waitstates = None;
+    }
+
+    pub(crate) fn tick(&mut self, ctx: &mut Context) {
+        BusDriver::run_driver(self, ctx);
+    }
+
+    pub(crate) fn tock(&mut self, ctx: &mut Context) {
+        BusDriver::tock(self, ctx);
+    }
+
+    #[handler]
+    pub(crate) fn on_new_ahb_slave_input(
+        &mut self,
+        ctx: &mut Context,
+        msg: MasterToSlaveWires<<RTCShadowComponent as AHBPortConfig>::Data>,
+    ) {
+        <Self as AHBSlavePortInput>::on_ahb_input(self, ctx, msg);
+    }
+
+    /// Refreshes the shadow registers on a tick of the RTC, which consumed `consumed_writes`.
+    #[handler]
+    pub(crate) fn on_rtc_update(
+        &mut self,
+        _ctx: &mut Context,
+        registers: RtcRegisters,
+        consumed_writes: usize,
+    ) {
+        self.registers = registers;
+        self.outstanding_writes = self
+            .outstanding_writes
+            .checked_sub(consumed_writes)
+            .expect("the RTC consumed more writes than were sent");
+    }
+
+    fn read_latency(&self, addr: Address) -> Option<u8> {
+        match addr {
+            RTC::SYNC::ADDR if self.outstanding_writes > 0 => None,
+            RTC::SYNC::ADDR => Some(SYNC_READ_WAITSTATES),
+            _ => Some(SHADOW_READ_WAITSTATES),
+        }
+    }
+
+    fn get_data_for_address(&self, addr: Address) -> u32 {
+        let registers = &self.registers;
+        match addr {
+            RTC::CTL::ADDR => registers.ctl,
+            RTC::EVFLAGS::ADDR => registers.evflags,
+            RTC::SEC::ADDR => registers.sec,
+            RTC::SUBSEC::ADDR => registers.subsec,
+            RTC::SUBSECINC::ADDR => registers.subsecinc,
+            RTC::CHCTL::ADDR => registers.chctl,
+            RTC::CH0CMP::ADDR => registers.ch0cmp,
+            RTC::CH1CMP::ADDR => registers.ch1cmp,
+            RTC::CH2CMP::ADDR => registers.ch2cmp,
+            RTC::CH2CMPINC::ADDR => registers.ch2cmpinc,
+            RTC::CH1CAPT::ADDR => registers.ch1capt,
+            // The register always returns 0
+            RTC::SYNC::ADDR => 0,
+            a => unimplemented!("Requested RTC data read for address {a:?}"),
+        }
+    }
+
+    /// Reads a register with the side effects.
+    fn read_register(&mut self, ctx: &Context, addr: Address) -> u32 {
+        let data = match addr {
+            RTC::SEC::ADDR => {
+                self.latched_subsec = Some(self.registers.subsec);
+                self.registers.sec
+            }
+            RTC::SUBSEC::ADDR => self.latched_subsec.take().unwrap_or_else(|| {
+                paranoid!(
+                    warn,
+                    "You should've read SEC register first. Real hardware's output may be unreliable."
+                );
+                self.registers.subsec
+            }),
+            a => self.get_data_for_address(a),
+        };
+        trace!(
+            "RTC read {addr:?}(\"{}\") = {data:#x} [t={:?}]",
+            ctx.display_named_address(addr),
+            ctx.event_queue().get_current_time(),
+        );
+        data
+    }
+}
+
+bridge_ports!(@slave RTCShadowComponent => @slave BusDriver);
+
+#[component_impl(rtc_shadow)]
+impl AHBPortConfig for RTCShadowComponent {
+    type Data = DataBus;
+    type Component = Self;
+    const TAG: &'static str = "RTCShadow";
+}
+#[component_impl(rtc_shadow)]
+impl AHBSlavePortProxiedInput for RTCShadowComponent {
+    fn proxy_ahb_input(ctx: &mut Context, msg: MasterToSlaveWires<Self::Data>) {
+        RTCShadowProxy.on_new_ahb_slave_input(ctx, msg);
+    }
+}
+
+#[component_impl(rtc_shadow)]
+impl AlignedHandler for RTCShadowComponent {
+    const WRITE_MODE: WriteMode = WriteMode::Combinatorial;
+    const ALIGN: Size = Size::Word;
+    type Native = u32;
+
+    fn read_for_write_filler(
+        slave: &Self::Component,
+        _ctx: &Context,
+        address: Address,
+    ) -> Self::Native {
+        slave.get_data_for_address(address)
+    }
+
+    fn pre_write(
+        _slave: &mut Self::Component,
+        _ctx: &mut Context,
+        _address: Address,
+    ) -> SimpleWriteResponse {
+        SimpleWriteResponse::SUCCESS
+    }
+
+    fn read_data(
+        slave: &mut Self::Component,
+        ctx: &mut Context,
+        address: Address,
+    ) -> SimpleResponse<Self::Native> {
+        // The waitstates are counted after the outstanding writes are consumed.
+        let waitstates = match slave.read_waitstates {
+            Some(waitstates) => waitstates,
+            None => match slave.read_latency(address) {
+                Some(latency) => latency,
+                None => return SimpleResponse::Pending,
+            },
+        };
+        if waitstates > 0 {
+            slave.read_waitstates = Some(waitstates - 1);
+            SimpleResponse::Pending
+        } else {
+            slave.read_waitstates = None;
+            SimpleResponse::Success(slave.read_register(ctx, address))
+        }
+    }
+
+    fn write_data(
+        slave: &mut Self::Component,
+        ctx: &mut Context,
+        address: Address,
+        data: Self::Native,
+        post_success: bool,
+    ) -> SimpleWriteResponse {
+        if post_success {
+            trace!(
+                "RTC write {address:?}(\"{}\") = {data:#x} [t={:?}]",
+                ctx.display_named_address(address),
+                ctx.event_queue().get_current_time(),
+            );
+            slave.outstanding_writes += 1;
+            RTCProxy.on_mcu_write(ctx, address, data);
+        }
+        SimpleWriteResponse::SUCCESS
+    }
+}
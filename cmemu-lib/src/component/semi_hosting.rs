@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use std::error::Error;
 use std::fmt::{Display, Formatter};
 use std::io::{ErrorKind, Read, Write, stderr, stdin, stdout};
@@ -134,9 +135,10 @@ impl TryFrom<Address> for SpecialRegisters {
     }
 }
 
-#[derive(Subcomponent, TickComponent, DisableableComponent)]
+#[derive(Clone, Subcomponent, TickComponent, DisableableComponent, Serialize, Deserialize)]
 #[subcomponent_1to1]
 pub(crate) struct SemiHosting {
+    #[serde(skip, default = "Instant::now")]
     creation_time: Instant,
     #[subcomponent(DriverSC)]
     driver: BusDriver,
@@ -0,0 +1,450 @@
+use crate::bridge_ports;
+use crate::common::new_ahb::databus::DataBus;
+use crate::common::new_ahb::ports::AHBSlavePortProxiedInput;
+#[proxy_use]
+use crate::common::new_ahb::ports::{AHBPortConfig, AHBSlavePortInput};
+#[proxy_use]
+use crate::common::new_ahb::signals::{MasterToSlaveWires, Size};
+use crate::common::new_ahb::slave_driver::WriteMode;
+use crate::common::new_ahb::slave_driver::faking_slave_driver::{
+    AlignedFakingHandler, FakingIface, WaitstatesOrErr,
+};
+use crate::component::aon_event::AonEvent;
+use crate::component::event_fabric::EventFabricEvent;
+use crate::component::uart_lite::{UARTLite, UARTLiteInterface};
+#[proxy_use]
+use crate::engine::Context;
+use crate::engine::{
+    DisableableComponent, MainComponent, SkippableClockTreeNode, TickComponent, TickComponentExtra,
+};
+use crate::proxy::{AONEventProxy, EventFabricProxy, SensorControllerProxy, WUCProxy};
+use cc2650_constants::AUX_EVCTL::{EVTOAONFLAGS, SWEVSET};
+use cc2650_constants::AUX_SCE::{
+    CPUSTAT, CTL, FETCHSTAT, LOOPADDR, LOOPCNT, REG1_0, REG3_2, REG5_4, REG7_6, WUSTAT,
+};
+use cc2650_constants::AUX_WUC::WUEVFLAGS;
+use cc2650_constants::{AUX, AUX_RAM};
+use cmemu_common::Address;
+use cmemu_common::address::RangeUnion;
+use cmemu_proc_macros::{component_impl, handler, proxy_use};
+use log::{debug, trace, warn};
+use serde::{Deserialize, Serialize};
+use std::mem;
+use std::ops::Range;
+use std::panic::UnwindSafe;
+
+mod adc;
+mod peripherals;
+mod sce;
+mod timer;
+
+pub(crate) use adc::{FCFG1_SOC_ADC_ABS_GAIN, FCFG1_SOC_ADC_OFFSET_INT, FCFG1_SOC_ADC_REL_GAIN};
+
+use peripherals::AuxPeripherals;
+use sce::Sce;
+
+// [TI-TRM] 17 Sensor Controller
+// The Sensor Controller Engine (AUX_SCE) is a 16-bit CPU in the AUX domain, which executes
+// from the AUX RAM and accesses the AUX peripherals with its I/O instructions, see `sce`.
+// Once enabled with `AON_WUC:AUXCTL.SCE_RUN_EN` or `CTL.CLK_EN`, it runs from `CTL.RESET_VECTOR`
+// (set with `CTL.RESTART`) until it goes to sleep. A sleeping engine is woken up
+// by the lowest vector flagged in `AUX_EVCTL:VECFLAGS`, the vectors are triggered by
+// the edges of the AUX event bus configured in `AUX_EVCTL:VECCFG0` and `VECCFG1`.
+// The engine and the MCU signal each other with the flags of `AUX_EVCTL:EVTOMCUFLAGS`
+// (the `AUX_COMB` interrupt), the software events of `AUX_EVCTL:SWEVSET` (the `AUX_SWEV0` and
+// `AUX_SWEV1` interrupts and the AON events), and `AON_WUC:AUXCTL.SWEV` (`AUX_WUC:WUEVFLAGS`).
+// The engine executes an instruction per cycle of the AUX clock.
+// Not implemented: the forcing of the events and the wakeup signal (`CTL.FORCE_*`),
+// freezing for the debugger, AUX DMA requests, and the other AUX peripherals (e.g., the comparators).
+//
+// When a `UARTLiteInterface` is attached, the AUX RAM is assumed to hold the UART Lite program,
+// which is emulated by forwarding its ring buffer to the interface instead of executing it.
+
+/// The AUX peripherals from `AUX_EVCTL` to `AUX_ANAIF` and `AUX_ADI4`,
+/// and the AUX RAM followed by `AUX_SCE`.
+const AUX_CONSECUTIVE_PERIPHERALS_RANGE: Range<Address> =
+    AUX::EVCTL::ADDR_SPACE.start..AUX::ANAIF::ADDR_SPACE.end;
+const AUX_PERIPHERALS_RANGE: RangeUnion<Range<Address>, Range<Address>> =
+    RangeUnion(&AUX_CONSECUTIVE_PERIPHERALS_RANGE, &AUX::ADI4::ADDR_SPACE);
+const AUX_RAM_AND_SCE_RANGE: Range<Address> = AUX_RAM::ADDR_SPACE.start..AUX::SCE::ADDR_SPACE.end;
+
+pub const SENSOR_CONTROLLER_ROUTE_INJECTION: RangeUnion<
+    RangeUnion<Range<Address>, Range<Address>>,
+    Range<Address>,
+> = RangeUnion(&AUX_PERIPHERALS_RANGE, &AUX_RAM_AND_SCE_RANGE);
+
+/// The AON events of the flags of `AUX_EVCTL:EVTOAONFLAGS` start from `AUX_SWEV0`.
+const EVTOAON_FLAGS: u8 = 9;
+
+/// Bits of `CTL` which are not stored, but trigger an action.
+const CTL_STROBES: u32 = CTL::RESTART::BIT_MASK | CTL::SINGLE_STEP::BIT_MASK;
+
+type BusDriver = FakingIface<DriverSC, SensorControllerComponent>;
+
+#[derive(
+    Clone,
+    MainComponent,
+    TickComponent,
+    TickComponentExtra,
+    DisableableComponent,
+    Serialize,
+    Deserialize,
+)]
+pub(crate) struct SensorControllerComponent {
+    #[subcomponent(DriverSC)]
+    driver: BusDriver,
+
+    sce: Sce,
+    aux: AuxPeripherals,
+    /// `CTL` without the strobes.
+    ctl: u32,
+    /// `CTL.SINGLE_STEP` was written while suspended.
+    single_step: bool,
+
+    uart_lite: UARTLite,
+}
+
+#[component_impl(sensor_controller)]
+impl SensorControllerComponent {
+    pub(crate) fn new() -> Self {
+        Self {
+            driver: Default::default(),
+
+            sce: Sce::new(),
+            aux: AuxPeripherals::new(),
+            ctl: 0,
+            single_step: false,
+
+            uart_lite: UARTLite::new(),
+        }
+    }
+
+    pub(crate) fn tick(&mut self, ctx: &mut Context) {
+        BusDriver::run_driver(self, ctx);
+        self.advance(ctx);
+    }
+
+    pub(crate) fn tock(&mut self, ctx: &mut Context) {
+        BusDriver::tock(self, ctx);
+    }
+
+    #[handler]
+    pub(crate) fn on_new_ahb_slave_input(
+        &mut self,
+        ctx: &mut Context,
+        msg: MasterToSlaveWires<<SensorControllerComponent as AHBPortConfig>::Data>,
+    ) {
+        <Self as AHBSlavePortInput>::on_ahb_input(self, ctx, msg);
+    }
+
+    /// A change of `AON_WUC:AUXCTL`.
+    #[handler]
+    pub(crate) fn on_aon_control(
+        &mut self,
+        _ctx: &mut Context,
+        sce_run_en: bool,
+        software_event: bool,
+        force_on: bool,
+    ) {
+        trace!("AUXCTL: SCE_RUN_EN={sce_run_en} SWEV={software_event} AUX_FORCE_ON={force_on}");
+        self.aux
+            .set_aon_control(sce_run_en, software_event, force_on);
+    }
+
+    /// An event selected in `AON_EVENT:AUXWUSEL` woke up AUX.
+    #[handler]
+    pub(crate) fn on_aon_wakeup(&mut self, _ctx: &mut Context) {
+        debug!("AUX woken up by AON_EVENT:AUXWUSEL");
+        self.aux.set_wakeup_flag(WUEVFLAGS::AON_PROG_WU::BIT_MASK);
+    }
+
+    #[handler]
+    pub(crate) fn on_rtc_ch2_event(&mut self, _ctx: &mut Context) {
+        self.aux.set_wakeup_flag(WUEVFLAGS::AON_RTC_CH2::BIT_MASK);
+    }
+
+    pub(crate) fn set_uart_lite_interface(
+        &mut self,
+        interface: Option<Box<dyn UARTLiteInterface + Send + Sync + UnwindSafe>>,
+    ) {
+        self.uart_lite.set_interface(interface);
+    }
+
+    pub(crate) fn take_uart_lite_interface(
+        &mut self,
+    ) -> Option<Box<dyn UARTLiteInterface + Send + Sync + UnwindSafe>> {
+        self.uart_lite.take_interface()
+    }
+
+    /// Resets the bus interface, but keeps the state of AUX, as in a warm reset.
+    pub(crate) fn reset_keeping_registers(&mut self) {
+        self.driver = Default::default();
+    }
+
+    fn is_clock_enabled(&self) -> bool {
+        self.aux.aon_sce_run_en() || self.ctl & CTL::CLK_EN::BIT_MASK != 0
+    }
+
+    /// Advances AUX by a cycle of its clock.
+    fn advance(&mut self, ctx: &mut Context) {
+        let raised = self.aux.update_events(ctx);
+        for flag in 0..EVTOAON_FLAGS {
+            if raised.aon_flags & (1 << flag) != 0 {
+                let event = AonEvent::try_from(u8::from(AonEvent::AUX_SWEV0) + flag)
+                    .expect("the AON events of AUX are consecutive");
+                AONEventProxy.notify(ctx, event);
+            }
+        }
+        if raised.aon_flags & EVTOAONFLAGS::SWEV0::BIT_MASK != 0 {
+            EventFabricProxy.notify(ctx, EventFabricEvent::AUX_SWEV0);
+        }
+        if raised.aon_flags & EVTOAONFLAGS::SWEV1::BIT_MASK != 0 {
+            EventFabricProxy.notify(ctx, EventFabricEvent::AUX_SWEV1);
+        }
+        if raised.combined {
+            EventFabricProxy.notify(ctx, EventFabricEvent::AUX_COMB);
+        }
+        if mem::take(&mut self.aux.software_event_cleared) {
+            WUCProxy.clear_aux_software_event(ctx);
+        }
+
+        if self.uart_lite.is_attached() {
+            self.uart_lite.drain(&mut self.aux.ram);
+            return;
+        }
+        if !self.is_clock_enabled() {
+            return;
+        }
+        if self.sce.is_sleeping()
+            && let Some(vector) = self.aux.triggered_vector()
+        {
+            self.sce.wake_up(vector);
+        }
+        if self.ctl & CTL::SUSPEND::BIT_MASK == 0 || mem::take(&mut self.single_step) {
+            self.sce.step(&mut self.aux);
+        }
+    }
+
+    /// Whether AUX has to be ticked, because its state changes on its own.
+    fn is_busy(&self) -> bool {
+        if self.aux.is_busy() || self.single_step || self.aux.software_event_cleared {
+            return true;
+        }
+        self.is_clock_enabled()
+            && self.ctl & CTL::SUSPEND::BIT_MASK == 0
+            && !self.uart_lite.is_attached()
+            && !(self.sce.is_idle(&self.aux)
+                && !(self.sce.is_sleeping() && self.aux.triggered_vector().is_some()))
+    }
+
+    fn wustat(&self) -> u32 {
+        let mut wustat =
+            u32::from(self.sce.exception_vector()) << WUSTAT::EXC_VECTOR::BIT_RANGE.start();
+        if self.aux.wev7_event() {
+            wustat |= 1 << WUSTAT::EV_SIGNALS::BIT_RANGE.end();
+        }
+        if self.aux.triggered_vector().is_some() {
+            wustat |= WUSTAT::WU_SIGNAL::BIT_MASK;
+        }
+        wustat
+    }
+
+    fn get_data_for_address(&self, ctx: &Context, addr: Address) -> u32 {
+        if addr.is_in_range(&AUX_RAM::ADDR_SPACE) {
+            let word = ram_index(addr);
+            return u32::from(self.aux.ram[word]) | (u32::from(self.aux.ram[word + 1]) << 16);
+        }
+        match addr {
+            CTL::ADDR => self.ctl,
+            FETCHSTAT::ADDR => self.sce.fetchstat(),
+            CPUSTAT::ADDR => self.sce.cpustat(),
+            WUSTAT::ADDR => self.wustat(),
+            REG1_0::ADDR => self.sce.register_pair(0),
+            REG3_2::ADDR => self.sce.register_pair(2),
+            REG5_4::ADDR => self.sce.register_pair(4),
+            REG7_6::ADDR => self.sce.register_pair(6),
+            LOOPADDR::ADDR => self.sce.loopaddr(),
+            LOOPCNT::ADDR => self.sce.loopcnt(),
+            _ => self.aux.get_register(addr).unwrap_or_else(|| {
+                unimplemented!(
+                    "Requested AUX data read from address {:?}: {}",
+                    addr,
+                    ctx.display_named_address(addr)
+                )
+            }),
+        }
+    }
+
+    fn read_data_for_address(&mut self, ctx: &Context, addr: Address) -> u32 {
+        if is_aux_peripheral(addr) {
+            self.aux.read_register(addr).unwrap_or_else(|| {
+                unimplemented!(
+                    "Requested AUX data read from address {:?}: {}",
+                    addr,
+                    ctx.display_named_address(addr)
+                )
+            })
+        } else {
+            self.get_data_for_address(ctx, addr)
+        }
+    }
+
+    fn set_data_for_address(&mut self, ctx: &mut Context, addr: Address, data: u32) {
+        if addr.is_in_range(&AUX_RAM::ADDR_SPACE) {
+            let word = ram_index(addr);
+            #[allow(clippy::cast_possible_truncation)]
+            let halves = [data as u16, (data >> 16) as u16];
+            self.aux.ram[word..word + 2].copy_from_slice(&halves);
+            return;
+        }
+        match addr {
+            CTL::ADDR => {
+                if data & CTL::RESTART::BIT_MASK != 0 {
+                    let reset_vector = (data & CTL::RESET_VECTOR::BIT_MASK)
+                        >> CTL::RESET_VECTOR::BIT_RANGE.start();
+                    debug!("SCE restarted at vector {reset_vector}");
+                    self.sce
+                        .restart(u8::try_from(reset_vector).expect("4-bit vector"));
+                }
+                if data & CTL::SINGLE_STEP::BIT_MASK != 0 {
+                    self.single_step = true;
+                }
+                self.ctl = data & !CTL_STROBES;
+            }
+            FETCHSTAT::ADDR
+            | CPUSTAT::ADDR
+            | WUSTAT::ADDR
+            | REG1_0::ADDR
+            | REG3_2::ADDR
+            | REG5_4::ADDR
+            | REG7_6::ADDR
+            | LOOPADDR::ADDR
+            | LOOPCNT::ADDR => warn!(
+                "Ignoring a write {data:#x} to the read-only {}",
+                ctx.display_named_address(addr)
+            ),
+            _ => {
+                if self.aux.write_register(addr, data).is_none() {
+                    unimplemented!(
+                        "Requested AUX data write {:#x} to address {:?}: {}",
+                        data,
+                        addr,
+                        ctx.display_named_address(addr)
+                    )
+                }
+                if addr == SWEVSET::ADDR {
+                    trace!("AUX software events set by the MCU: {data:#x}");
+                }
+            }
+        }
+    }
+}
+
+fn is_aux_peripheral(addr: Address) -> bool {
+    addr.is_in_range(&AUX_CONSECUTIVE_PERIPHERALS_RANGE) || addr.is_in_range(&AUX::ADI4::ADDR_SPACE)
+}
+
+/// Index of the first 16-bit word of the AUX RAM accessed at the word address `addr`.
+fn ram_index(addr: Address) -> usize {
+    (addr.offset_from(AUX_RAM::ADDR) / 2) as usize
+}
+
+bridge_ports!(@slave SensorControllerComponent => @auto_configured @slave BusDriver);
+
+#[component_impl(sensor_controller)]
+impl AHBPortConfig for SensorControllerComponent {
+    type Data = DataBus;
+    type Component = Self;
+    const TAG: &'static str = "SensorController";
+}
+
+#[component_impl(sensor_controller)]
+impl AHBSlavePortProxiedInput for SensorControllerComponent {
+    fn proxy_ahb_input(ctx: &mut Context, msg: MasterToSlaveWires<Self::Data>) {
+        SensorControllerProxy.on_new_ahb_slave_input(ctx, msg);
+    }
+}
+
+#[component_impl(sensor_controller)]
+impl AlignedFakingHandler for SensorControllerComponent {
+    const WRITE_MODE: WriteMode = WriteMode::Combinatorial;
+    const ALIGN: Size = Size::Word;
+    type Native = u32;
+
+    fn read_for_write_filler(
+        slave: &Self::Component,
+        ctx: &Context,
+        address: Address,
+    ) -> Self::Native {
+        if is_aux_peripheral(address) {
+            slave.aux.write_filler(address).unwrap_or_else(|| {
+                unimplemented!(
+                    "Requested AUX data write to address {:?}: {}",
+                    address,
+                    ctx.display_named_address(address)
+                )
+            })
+        } else {
+            slave.get_data_for_address(ctx, address)
+        }
+    }
+
+    fn pre_read(
+        _slave: &mut Self::Component,
+        _ctx: &mut Context,
+        _address: Address,
+    ) -> WaitstatesOrErr {
+        Ok(0)
+    }
+
+    fn read(slave: &mut Self::Component, ctx: &mut Context, address: Address) -> Self::Native {
+        let data = slave.read_data_for_address(ctx, address);
+        trace!(
+            "read {address:?} (\"{}\") = {data:#x}",
+            ctx.display_named_address(address)
+        );
+        data
+    }
+
+    fn pre_write(
+        _slave: &mut Self::Component,
+        _ctx: &mut Context,
+        _address: Address,
+    ) -> WaitstatesOrErr {
+        Ok(0)
+    }
+
+    fn write(slave: &mut Self::Component, ctx: &mut Context, address: Address, data: Self::Native) {
+        trace!(
+            "write {address:?} (\"{}\") {data:#x}",
+            ctx.display_named_address(address)
+        );
+        slave.set_data_for_address(ctx, address, data);
+    }
+}
+
+#[component_impl(sensor_controller)]
+impl SkippableClockTreeNode for SensorControllerComponent {
+    fn max_cycles_to_skip(
+        comp: &mut Self::Component,
+        _ctx: &mut Context,
+        _parent: Self::IdSpace,
+        _extra: &mut Self::Extra,
+    ) -> u64 {
+        if !comp.can_be_disabled_now() || comp.is_busy() {
+            0
+        } else {
+            u64::MAX
+        }
+    }
+
+    fn emulate_skipped_cycles(
+        _comp: &mut Self::Component,
+        _ctx: &mut Context,
+        _parent: Self::IdSpace,
+        _extra: &mut Self::Extra,
+        _skipped_cycles: u64,
+    ) {
+        // Nothing changes in AUX, while it is not busy.
+    }
+}
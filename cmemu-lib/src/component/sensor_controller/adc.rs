@@ -0,0 +1,458 @@
+//! The ADC of AUX: its analog part controlled through `AUX_ADI4`, and its digital interface
+//! with the sample FIFO in `AUX_ANAIF`.
+//!
+//! A conversion is triggered by an edge of the AUX event bus line selected with
+//! `AUX_ANAIF:ADCCTL.START_SRC`, or by `AUX_ANAIF:ADCTRIG.START`. In the synchronous mode
+//! (`AUX_ADI4:ADC0.SMPL_MODE`) the input is sampled for the time of `ADC0.SMPL_CYCLE_EXP`
+//! after the trigger, in the asynchronous mode it has been sampled since the previous
+//! conversion, so the conversion starts immediately. A conversion takes 50 cycles of
+//! the 24 MHz ADC clock, as in the datasheet. Its 12-bit result is pushed to the 4-sample FIFO.
+//!
+//! The input is the voltage of the node measured at the start of the conversion,
+//! see [`AnalogInput`]. It is converted with the gain and the offset error, which are
+//! compensated by the trims of `FCFG1` in the software. The reference is the fixed 4.3 V one
+//! (1.48 V with `ADC1.SCALE_DIS`) or VDDS, as selected with `ADCREF0.SRC`.
+//! Not implemented: the comparators, the current source, the reference trimming
+//! (`ADCREF1`), the settling of the reference, and the DMA interface of the FIFO.
+
+use cc2650_constants::AUX_ADI4::{ADC0, ADC1, ADCREF0, MUX2, MUX3};
+use cc2650_constants::AUX_ANAIF::{ADCCTL, ADCFIFO, ADCFIFOSTAT, ADCTRIG, ISRCCTL};
+use cc2650_constants::{AUX_ADI4, FCFG1};
+use cmemu_common::Address;
+use log::{debug, trace, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+use crate::engine::{AnalogInput, Context};
+
+/// `FCFG1:SOC_ADC_ABS_GAIN`, the gain error with the fixed reference.
+pub(crate) const FCFG1_SOC_ADC_ABS_GAIN: u32 = 0xFFFF_8213;
+/// `FCFG1:SOC_ADC_REL_GAIN`, the gain error with the VDDS reference.
+pub(crate) const FCFG1_SOC_ADC_REL_GAIN: u32 = 0xFFFF_8180;
+/// `FCFG1:SOC_ADC_OFFSET_INT`, the offset errors with both references.
+pub(crate) const FCFG1_SOC_ADC_OFFSET_INT: u32 = 0xFFFE_FF05;
+
+/// The unity gain of the trims, as they are applied in the software.
+const UNITY_GAIN: f64 = 32768.0;
+
+/// The full scale of the fixed reference with the input scaling, in volts.
+const FIXED_REFERENCE: f64 = 4.3;
+/// The internal reference, the full scale without the input scaling, in volts.
+const INTERNAL_REFERENCE: f64 = 1.48;
+/// The voltage of `DCOUPL`, the output of the internal regulator.
+const DCOUPL_VOLTAGE: f64 = 1.27;
+
+const SAMPLE_MASK: u16 = 0xFFF;
+const FIFO_SIZE: usize = 4;
+
+/// AUX clock cycles (48 MHz) per a cycle of the 6 MHz sampling clock.
+const SAMPLING_CLOCK_DIVIDER: u32 = 8;
+/// AUX clock cycles of a conversion: 50 cycles of the 24 MHz ADC clock.
+const CONVERSION_CYCLES: u32 = 100;
+
+/// Number of the 8-bit registers in the direct access region of `AUX_ADI4`.
+const ADI_REGISTERS: usize = 16;
+
+/// Offsets of the access regions of an ADI, see `hw_adi.h` of the driverlib.
+mod region {
+    pub(super) const SET: u32 = 0x10;
+    pub(super) const CLR: u32 = 0x20;
+    pub(super) const MASK4B: u32 = 0x40;
+    pub(super) const MASK8B: u32 = 0x60;
+    pub(super) const MASK16B: u32 = 0x80;
+    pub(super) const END: u32 = 0xA0;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+enum State {
+    Idle,
+    /// The input is sampled for `left` more cycles (the synchronous mode).
+    Sampling {
+        left: u32,
+    },
+    /// The `sample` is converted for `left` more cycles.
+    Converting {
+        left: u32,
+        sample: u16,
+    },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(super) struct AuxAdc {
+    /// The 8-bit registers of `AUX_ADI4`.
+    adi: [u8; ADI_REGISTERS],
+    adcctl: u32,
+    isrcctl: u32,
+
+    fifo: VecDeque<u16>,
+    /// The sample returned by reading an empty FIFO.
+    last_read: u16,
+    underflow: bool,
+    overflow: bool,
+
+    state: State,
+    /// The level of the trigger line (with `START_POL` applied) in the previous cycle.
+    trigger_level: bool,
+    /// `ADCTRIG.START` was written.
+    manual_trigger: bool,
+    /// The `ADC_DONE` event, high for a single AUX clock cycle.
+    done: bool,
+}
+
+impl AuxAdc {
+    pub(super) fn new() -> Self {
+        Self {
+            adi: [0; ADI_REGISTERS],
+            adcctl: ADCCTL::RESET_VALUE,
+            isrcctl: ISRCCTL::RESET_VALUE,
+            fifo: VecDeque::with_capacity(FIFO_SIZE),
+            last_read: 0,
+            underflow: false,
+            overflow: false,
+            state: State::Idle,
+            trigger_level: false,
+            manual_trigger: false,
+            done: false,
+        }
+    }
+
+    fn adi(&self, register: Address) -> u8 {
+        self.adi[register.offset_from(AUX_ADI4::ADDR) as usize]
+    }
+
+    fn command(&self) -> u32 {
+        self.adcctl & ADCCTL::CMD::BIT_MASK
+    }
+
+    /// Whether the ADC is powered, released from reset, enabled and clocked.
+    fn is_enabled(&self, clock_requested: bool) -> bool {
+        let adc0 = u32::from(self.adi(ADC0::ADDR));
+        adc0 & ADC0::EN::BIT_MASK != 0
+            && adc0 & ADC0::RESET_N::BIT_MASK != 0
+            && self.command() == ADCCTL::CMD::Named::EN
+            && clock_requested
+    }
+
+    /// The AUX event bus line triggering the conversions, with its active edge.
+    pub(super) fn start_source(&self) -> (u8, bool) {
+        let line =
+            (self.adcctl & ADCCTL::START_SRC::BIT_MASK) >> ADCCTL::START_SRC::BIT_RANGE.start();
+        let rising = self.adcctl & ADCCTL::START_POL::BIT_MASK == 0;
+        (u8::try_from(line).expect("5-bit line"), rising)
+    }
+
+    /// The `ADC_DONE` event line.
+    pub(super) fn done_event(&self) -> bool {
+        self.done
+    }
+
+    /// The `ADC_FIFO_ALMOST_FULL` event line.
+    pub(super) fn almost_full_event(&self) -> bool {
+        self.fifo.len() == FIFO_SIZE - 1
+    }
+
+    /// The `ADC_IRQ` event line, without the DMA done event if `dma_enabled`.
+    pub(super) fn irq_event(&self, dma_enabled: bool) -> bool {
+        (!dma_enabled && !self.fifo.is_empty()) || self.overflow || self.underflow
+    }
+
+    /// Whether the ADC changes its state without the accesses to the registers.
+    pub(super) fn is_busy(&self) -> bool {
+        self.state != State::Idle || self.manual_trigger || self.done
+    }
+
+    /// Advances the ADC by a cycle of the AUX clock, with the level of its trigger line.
+    pub(super) fn tick(&mut self, ctx: &mut Context, trigger_line: bool, clock_requested: bool) {
+        self.done = false;
+        let (_, rising) = self.start_source();
+        let level = trigger_line == rising;
+        let triggered = std::mem::take(&mut self.manual_trigger) || (level && !self.trigger_level);
+        self.trigger_level = level;
+
+        if !self.is_enabled(clock_requested) {
+            if self.state != State::Idle {
+                debug!("ADC conversion aborted");
+                self.state = State::Idle;
+            }
+            return;
+        }
+
+        self.state = match self.state {
+            State::Idle if triggered => {
+                trace!("ADC triggered");
+                let adc0 = u32::from(self.adi(ADC0::ADDR));
+                if adc0 & ADC0::SMPL_MODE::BIT_MASK == 0 {
+                    let exp = (adc0 & ADC0::SMPL_CYCLE_EXP::BIT_MASK)
+                        >> ADC0::SMPL_CYCLE_EXP::BIT_RANGE.start();
+                    State::Sampling {
+                        left: (1 << (exp + 1)) * SAMPLING_CLOCK_DIVIDER,
+                    }
+                } else {
+                    self.start_conversion(ctx)
+                }
+            }
+            State::Idle => State::Idle,
+            State::Sampling { left } => {
+                if triggered {
+                    debug!("Ignoring an ADC trigger during sampling");
+                }
+                if left > 1 {
+                    State::Sampling { left: left - 1 }
+                } else {
+                    self.start_conversion(ctx)
+                }
+            }
+            State::Converting { left, sample } => {
+                if triggered {
+                    debug!("Ignoring an ADC trigger during a conversion");
+                }
+                if left > 1 {
+                    State::Converting {
+                        left: left - 1,
+                        sample,
+                    }
+                } else {
+                    self.push(sample);
+                    self.done = true;
+                    State::Idle
+                }
+            }
+        };
+    }
+
+    fn start_conversion(&mut self, ctx: &mut Context) -> State {
+        State::Converting {
+            left: CONVERSION_CYCLES,
+            sample: self.convert(ctx),
+        }
+    }
+
+    fn push(&mut self, sample: u16) {
+        if self.overflow || self.fifo.len() == FIFO_SIZE {
+            debug!("ADC FIFO overflow, sample {sample:#x} lost");
+            self.overflow = true;
+        } else {
+            trace!("ADC sample {sample:#x}");
+            self.fifo.push_back(sample);
+        }
+    }
+
+    fn pop(&mut self) -> u16 {
+        if self.underflow {
+            return self.last_read;
+        }
+        if let Some(sample) = self.fifo.pop_front() {
+            self.last_read = sample;
+        } else {
+            debug!("ADC FIFO underflow");
+            self.underflow = true;
+        }
+        self.last_read
+    }
+
+    /// The 12-bit sample of the selected input.
+    fn convert(&self, ctx: &mut Context) -> u16 {
+        let vdds = ctx.sample_analog(AnalogInput::SupplyVoltage);
+        let mux2 = u32::from(self.adi(MUX2::ADDR)) & MUX2::ADCCOMPB_IN::BIT_MASK;
+        let mux3 = u32::from(self.adi(MUX3::ADDR));
+        let voltage = if mux3 != 0 {
+            if mux2 != 0 || !mux3.is_power_of_two() {
+                warn!("Several inputs of the ADC are selected: {mux2:#x} and {mux3:#x}");
+            }
+            // AUXIO0 is the highest bit.
+            let pin = u8::try_from(mux3.leading_zeros() - 24).expect("8-bit multiplexer");
+            ctx.sample_analog(AnalogInput::AuxIo(pin))
+        } else {
+            match mux2 >> MUX2::ADCCOMPB_IN::BIT_RANGE.start() {
+                MUX2::ADCCOMPB_IN::Named::VDDS => vdds,
+                MUX2::ADCCOMPB_IN::Named::VSS => 0.0,
+                MUX2::ADCCOMPB_IN::Named::DCOUPL => DCOUPL_VOLTAGE,
+                input => {
+                    warn!("Unmodeled input {input:#x} of the ADC selected, sampling 0 V");
+                    0.0
+                }
+            }
+        };
+
+        let fixed = u32::from(self.adi(ADCREF0::ADDR)) & ADCREF0::SRC::BIT_MASK == 0;
+        let mut full_scale = if fixed { FIXED_REFERENCE } else { vdds };
+        if u32::from(self.adi(ADC1::ADDR)) & ADC1::SCALE_DIS::BIT_MASK != 0 {
+            full_scale *= INTERNAL_REFERENCE / FIXED_REFERENCE;
+        }
+        let ideal = (voltage / full_scale * 4096.0)
+            .round()
+            .clamp(0.0, f64::from(SAMPLE_MASK));
+
+        // The inverse of the adjustment of the driverlib: ((raw + offset) * gain) >> 15.
+        let (gain, offset) = if fixed {
+            (
+                FCFG1_SOC_ADC_ABS_GAIN & FCFG1::SOC_ADC_ABS_GAIN::SOC_ADC_ABS_GAIN_TEMP1::BIT_MASK,
+                FCFG1_SOC_ADC_OFFSET_INT
+                    & FCFG1::SOC_ADC_OFFSET_INT::SOC_ADC_ABS_OFFSET_TEMP1::BIT_MASK,
+            )
+        } else {
+            (
+                FCFG1_SOC_ADC_REL_GAIN & FCFG1::SOC_ADC_REL_GAIN::SOC_ADC_REL_GAIN_TEMP1::BIT_MASK,
+                (FCFG1_SOC_ADC_OFFSET_INT
+                    & FCFG1::SOC_ADC_OFFSET_INT::SOC_ADC_REL_OFFSET_TEMP1::BIT_MASK)
+                    >> FCFG1::SOC_ADC_OFFSET_INT::SOC_ADC_REL_OFFSET_TEMP1::BIT_RANGE.start(),
+            )
+        };
+        #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+        let offset = f64::from(offset as u8 as i8);
+        let raw = (ideal * UNITY_GAIN / f64::from(gain)).round() - offset;
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let sample = raw.clamp(0.0, f64::from(SAMPLE_MASK)) as u16;
+        sample
+    }
+
+    fn fifostat(&self) -> u32 {
+        let mut stat = 0;
+        let mut set = |mask: u32, flag: bool| {
+            if flag {
+                stat |= mask;
+            }
+        };
+        set(ADCFIFOSTAT::EMPTY::BIT_MASK, self.fifo.is_empty());
+        set(ADCFIFOSTAT::ALMOST_FULL::BIT_MASK, self.almost_full_event());
+        set(ADCFIFOSTAT::FULL::BIT_MASK, self.fifo.len() == FIFO_SIZE);
+        set(ADCFIFOSTAT::UNDERFLOW::BIT_MASK, self.underflow);
+        set(ADCFIFOSTAT::OVERFLOW::BIT_MASK, self.overflow);
+        stat
+    }
+
+    /// Returns the value of the register at `addr`, without the side effects of reading it.
+    ///
+    /// The word of `AUX_ADI4` holds four 8-bit registers. The set and clear aliases read
+    /// the registers, and the masked aliases read as 0.
+    pub(super) fn get_register(&self, addr: Address) -> Option<u32> {
+        Some(match addr {
+            ADCCTL::ADDR => self.adcctl,
+            ADCFIFOSTAT::ADDR => self.fifostat(),
+            ADCFIFO::ADDR => u32::from(self.fifo.front().copied().unwrap_or(self.last_read)),
+            ADCTRIG::ADDR => 0,
+            ISRCCTL::ADDR => self.isrcctl,
+            a if a.is_in_range(&AUX_ADI4::ADDR_SPACE) => {
+                let offset = a.offset_from(AUX_ADI4::ADDR);
+                if offset < region::MASK4B {
+                    let first = (offset % region::SET) as usize;
+                    u32::from_le_bytes(
+                        self.adi[first..first + 4]
+                            .try_into()
+                            .expect("4 registers in a word"),
+                    )
+                } else {
+                    0
+                }
+            }
+            _ => return None,
+        })
+    }
+
+    /// Reads the register at `addr`: reading `ADCFIFO` pops a sample.
+    pub(super) fn read_register(&mut self, addr: Address) -> Option<u32> {
+        if addr == ADCFIFO::ADDR {
+            return Some(u32::from(self.pop()));
+        }
+        self.get_register(addr)
+    }
+
+    /// Writes the register at `addr`, returns `None` if there is no such register.
+    pub(super) fn write_register(&mut self, addr: Address, data: u32) -> Option<()> {
+        match addr {
+            ADCCTL::ADDR => {
+                self.adcctl = data
+                    & (ADCCTL::CMD::BIT_MASK
+                        | ADCCTL::START_SRC::BIT_MASK
+                        | ADCCTL::START_POL::BIT_MASK);
+                if self.command() == ADCCTL::CMD::Named::FLUSH {
+                    trace!("ADC FIFO flushed");
+                    self.fifo.clear();
+                    self.underflow = false;
+                    self.overflow = false;
+                }
+            }
+            // Writing the FIFO pushes a sample, e.g., for testing the DMA.
+            #[allow(clippy::cast_possible_truncation)]
+            ADCFIFO::ADDR => self.push(data as u16 & SAMPLE_MASK),
+            ADCTRIG::ADDR => {
+                if data & ADCTRIG::START::BIT_MASK != 0 {
+                    self.manual_trigger = true;
+                }
+            }
+            ISRCCTL::ADDR => self.isrcctl = data & ISRCCTL::RESET_N::BIT_MASK,
+            ADCFIFOSTAT::ADDR => {
+                warn!("Ignoring a write {data:#x} to the read-only AUX register at {addr:?}");
+            }
+            a if a.is_in_range(&AUX_ADI4::ADDR_SPACE) => self.write_adi(a, data),
+            _ => return None,
+        }
+        Some(())
+    }
+
+    /// Writes a word of `AUX_ADI4`. Its bytes, which were not written by the bus, are filled
+    /// with 0 in the aliases (see [`Self::write_filler`]), so they do not change the registers.
+    fn write_adi(&mut self, addr: Address, data: u32) {
+        let offset = addr.offset_from(AUX_ADI4::ADDR);
+        let bytes = data.to_le_bytes();
+        match offset {
+            o if o < region::SET => {
+                let first = o as usize;
+                self.adi[first..first + 4].copy_from_slice(&bytes);
+            }
+            o if o < region::CLR => {
+                let first = (o - region::SET) as usize;
+                for (register, byte) in self.adi[first..first + 4].iter_mut().zip(bytes) {
+                    *register |= byte;
+                }
+            }
+            o if o < region::MASK4B => {
+                let first = (o - region::CLR) as usize;
+                for (register, byte) in self.adi[first..first + 4].iter_mut().zip(bytes) {
+                    *register &= !byte;
+                }
+            }
+            o if o < region::MASK8B => {
+                // A byte per a half of a register: the mask in the upper nibble.
+                let first = ((o - region::MASK4B) / 2) as usize;
+                for (nibble, byte) in bytes.into_iter().enumerate() {
+                    let shift = 4 * (nibble % 2);
+                    let mask = (byte >> 4) << shift;
+                    let value = (byte & 0xF) << shift;
+                    let register = &mut self.adi[first + nibble / 2];
+                    *register = (*register & !mask) | (value & mask);
+                }
+            }
+            o if o < region::MASK16B => {
+                // A halfword per a register: the mask in the upper byte.
+                let first = ((o - region::MASK8B) / 2) as usize;
+                for (i, half) in bytes.chunks_exact(2).enumerate() {
+                    let (value, mask) = (half[0], half[1]);
+                    let register = &mut self.adi[first + i];
+                    *register = (*register & !mask) | (value & mask);
+                }
+            }
+            o if o < region::END => {
+                // A word per two registers: the mask in the upper halfword.
+                let first = ((o - region::MASK16B) / 2) as usize;
+                for i in 0..2 {
+                    let (value, mask) = (bytes[i], bytes[i + 2]);
+                    let register = &mut self.adi[first + i];
+                    *register = (*register & !mask) | (value & mask);
+                }
+            }
+            _ => warn!("Ignoring a write {data:#x} to the unmapped AUX_ADI4 offset {offset:#x}"),
+        }
+    }
+
+    /// The data of the bytes of the word at `addr` not written by a narrower write.
+    pub(super) fn write_filler(&self, addr: Address) -> Option<u32> {
+        if addr.is_in_range(&AUX_ADI4::ADDR_SPACE)
+            && addr.offset_from(AUX_ADI4::ADDR) >= region::SET
+        {
+            Some(0)
+        } else {
+            self.get_register(addr)
+        }
+    }
+}
@@ -0,0 +1,604 @@
+//! The AUX RAM and the AUX peripherals shared by the MCU and the Sensor Controller Engine:
+//! the event control (`AUX_EVCTL`), the wakeup controller (`AUX_WUC`), the timers (`AUX_TIMER`),
+//! the semaphores (`AUX_SMPH`), and the ADC (`AUX_ANAIF` and `AUX_ADI4`, see `adc`).
+//!
+//! The event bus of AUX has 32 lines, which are listed in `AUX_EVCTL:EVSTAT0` and `EVSTAT1`.
+//! Only the lines of the modeled peripherals ever go high, the others (e.g., the comparators
+//! or the AUXIO pins) are constantly low.
+
+use cc2650_constants::AUX_EVCTL::{
+    COMBEVTOMCUMASK, DMACTL, EVSTAT0, EVSTAT1, EVTOAONFLAGS, EVTOAONFLAGSCLR, EVTOAONPOL,
+    EVTOMCUFLAGS, EVTOMCUFLAGSCLR, EVTOMCUPOL, SCEWEVSEL, SWEVSET, VECCFG0, VECCFG1, VECFLAGS,
+    VECFLAGSCLR,
+};
+use cc2650_constants::AUX_SMPH::{AUTOTAKE, SMPH0, SMPH7};
+use cc2650_constants::AUX_TIMER::{T0CFG, T0CTL, T0TARGET, T1CFG, T1CTL, T1TARGET};
+use cc2650_constants::AUX_WUC::{
+    ADCCLKCTL, AONCTLSTAT, AUXIOLATCH, CLKLFACK, CLKLFREQ, MCUBUSCTL, MCUBUSSTAT, MODCLKEN0,
+    MODCLKEN1, PWRDWNACK, PWRDWNREQ, PWROFFREQ, REFCLKCTL, RTCSUBSECINC0, RTCSUBSECINC1,
+    RTCSUBSECINCCTL, TDCCLKCTL, WUEVCLR, WUEVFLAGS,
+};
+use cc2650_constants::{AUX_ADI4, AUX_ANAIF};
+use cmemu_common::Address;
+use log::{trace, warn};
+use serde::{Deserialize, Serialize};
+
+use super::adc::AuxAdc;
+use super::sce::{RAM_WORDS, SceBus};
+use super::timer::AuxTimer;
+use crate::engine::Context;
+
+/// Lines of the AUX event bus, see `AUX_EVCTL:EVSTAT0` and `AUX_EVCTL:EVSTAT1`.
+#[allow(dead_code)]
+mod line {
+    pub(super) const AON_RTC_CH2: u8 = 0;
+    pub(super) const AUX_COMPA: u8 = 1;
+    pub(super) const AUX_COMPB: u8 = 2;
+    pub(super) const TDC_DONE: u8 = 3;
+    pub(super) const TIMER0_EV: u8 = 4;
+    pub(super) const TIMER1_EV: u8 = 5;
+    pub(super) const SMPH_AUTOTAKE_DONE: u8 = 6;
+    pub(super) const ADC_DONE: u8 = 7;
+    pub(super) const ADC_FIFO_ALMOST_FULL: u8 = 8;
+    pub(super) const OBSMUX0: u8 = 9;
+    pub(super) const OBSMUX1: u8 = 10;
+    pub(super) const AON_SW: u8 = 11;
+    pub(super) const AON_PROG_WU: u8 = 12;
+    pub(super) const ADC_IRQ: u8 = 31;
+}
+
+/// Number of the vectors of the engine, configured in `VECCFG0` and `VECCFG1`.
+const VECTORS: u8 = 4;
+
+/// The event lines, which set the flags of `EVTOAONFLAGS` from `AUX_COMPA` on.
+const EVTOAON_LINES: [(u32, u8); 6] = [
+    (EVTOAONFLAGS::AUX_COMPA::BIT_MASK, line::AUX_COMPA),
+    (EVTOAONFLAGS::AUX_COMPB::BIT_MASK, line::AUX_COMPB),
+    (EVTOAONFLAGS::ADC_DONE::BIT_MASK, line::ADC_DONE),
+    (EVTOAONFLAGS::TDC_DONE::BIT_MASK, line::TDC_DONE),
+    (EVTOAONFLAGS::TIMER0_EV::BIT_MASK, line::TIMER0_EV),
+    (EVTOAONFLAGS::TIMER1_EV::BIT_MASK, line::TIMER1_EV),
+];
+
+const EVTOAON_LINES_MASK: u32 = EVTOAONFLAGS::AUX_COMPA::BIT_MASK
+    | EVTOAONFLAGS::AUX_COMPB::BIT_MASK
+    | EVTOAONFLAGS::ADC_DONE::BIT_MASK
+    | EVTOAONFLAGS::TDC_DONE::BIT_MASK
+    | EVTOAONFLAGS::TIMER0_EV::BIT_MASK
+    | EVTOAONFLAGS::TIMER1_EV::BIT_MASK;
+
+/// All the flags of `EVTOMCUFLAGS`.
+const EVTOMCU_MASK: u32 =
+    EVTOMCUFLAGS::AON_WU_EV::BIT_MASK | EVTOMCU_LINES_MASK | EVTOMCUFLAGS::ADC_IRQ::BIT_MASK;
+
+/// The flags of `EVTOMCUFLAGS` set by the event lines of the same numbers.
+const EVTOMCU_LINES_MASK: u32 = EVTOMCUFLAGS::AUX_COMPA::BIT_MASK
+    | EVTOMCUFLAGS::AUX_COMPB::BIT_MASK
+    | EVTOMCUFLAGS::TDC_DONE::BIT_MASK
+    | EVTOMCUFLAGS::TIMER0_EV::BIT_MASK
+    | EVTOMCUFLAGS::TIMER1_EV::BIT_MASK
+    | EVTOMCUFLAGS::SMPH_AUTOTAKE_DONE::BIT_MASK
+    | EVTOMCUFLAGS::ADC_DONE::BIT_MASK
+    | EVTOMCUFLAGS::ADC_FIFO_ALMOST_FULL::BIT_MASK
+    | EVTOMCUFLAGS::OBSMUX0::BIT_MASK;
+
+const SWEV_MASK: u32 =
+    SWEVSET::SWEV0::BIT_MASK | SWEVSET::SWEV1::BIT_MASK | SWEVSET::SWEV2::BIT_MASK;
+
+/// Flags raised in a cycle, to be reported outside of AUX.
+#[derive(Clone, Copy, Debug, Default)]
+pub(super) struct RaisedEvents {
+    /// Newly set flags of `EVTOAONFLAGS`.
+    pub(super) aon_flags: u32,
+    /// The rising edge of the `AUX_COMB` event to the MCU.
+    pub(super) combined: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[allow(clippy::struct_excessive_bools)]
+pub(super) struct AuxPeripherals {
+    pub(super) ram: Box<[u16]>,
+
+    // AUX_EVCTL
+    veccfg: [u32; 2],
+    scewevsel: u32,
+    evtoaonflags: u32,
+    evtoaonpol: u32,
+    dmactl: u32,
+    evtomcupol: u32,
+    evtomcuflags: u32,
+    combevtomcumask: u32,
+    vecflags: u32,
+    /// The event bus in the current and the previous cycle.
+    events: u32,
+    previous_events: u32,
+    /// `EVTOAONFLAGS` already reported as AON events.
+    reported_aon_flags: u32,
+    combined_event: bool,
+
+    // AUX_WUC
+    modclken0: u32,
+    modclken1: u32,
+    pwroffreq: u32,
+    pwrdwnreq: u32,
+    clklfreq: u32,
+    adcclkctl: u32,
+    tdcclkctl: u32,
+    refclkctl: u32,
+    rtcsubsecinc: [u32; 2],
+    rtcsubsecincctl: u32,
+    mcubusctl: u32,
+    auxiolatch: u32,
+    /// `WUEVFLAGS.AON_PROG_WU` and `WUEVFLAGS.AON_RTC_CH2`, the `AON_SW` flag is kept by AON.
+    wuevflags: u32,
+    /// `AON_WUC:AUXCTL.SWEV`, `AON_WUC:AUXCTL.SCE_RUN_EN` and `AON_WUC:AUXCTL.AUX_FORCE_ON`.
+    aon_software_event: bool,
+    aon_sce_run_en: bool,
+    aon_force_on: bool,
+    /// `WUEVCLR.AON_SW` was written, so `AON_WUC:AUXCTL.SWEV` has to be cleared.
+    pub(super) software_event_cleared: bool,
+
+    // AUX_TIMER
+    timers: [AuxTimer; 2],
+
+    // AUX_SMPH
+    /// Bit `n` is set if `SMPHn` is free.
+    free_semaphores: u8,
+    autotake: u32,
+    autotake_pending: bool,
+    autotake_done: bool,
+
+    // AUX_ANAIF and AUX_ADI4
+    adc: AuxAdc,
+}
+
+impl AuxPeripherals {
+    pub(super) fn new() -> Self {
+        Self {
+            ram: vec![0; usize::from(RAM_WORDS)].into_boxed_slice(),
+
+            veccfg: [VECCFG0::RESET_VALUE, VECCFG1::RESET_VALUE],
+            scewevsel: SCEWEVSEL::RESET_VALUE,
+            evtoaonflags: EVTOAONFLAGS::RESET_VALUE,
+            evtoaonpol: EVTOAONPOL::RESET_VALUE,
+            dmactl: DMACTL::RESET_VALUE,
+            evtomcupol: EVTOMCUPOL::RESET_VALUE,
+            evtomcuflags: EVTOMCUFLAGS::RESET_VALUE,
+            combevtomcumask: COMBEVTOMCUMASK::RESET_VALUE,
+            vecflags: VECFLAGS::RESET_VALUE,
+            events: 0,
+            previous_events: 0,
+            reported_aon_flags: 0,
+            combined_event: false,
+
+            modclken0: MODCLKEN0::RESET_VALUE,
+            modclken1: MODCLKEN1::RESET_VALUE,
+            pwroffreq: PWROFFREQ::RESET_VALUE,
+            pwrdwnreq: PWRDWNREQ::RESET_VALUE,
+            clklfreq: CLKLFREQ::RESET_VALUE,
+            adcclkctl: ADCCLKCTL::RESET_VALUE,
+            tdcclkctl: TDCCLKCTL::RESET_VALUE,
+            refclkctl: REFCLKCTL::RESET_VALUE,
+            rtcsubsecinc: [RTCSUBSECINC0::RESET_VALUE, RTCSUBSECINC1::RESET_VALUE],
+            rtcsubsecincctl: RTCSUBSECINCCTL::RESET_VALUE,
+            mcubusctl: MCUBUSCTL::RESET_VALUE,
+            auxiolatch: AUXIOLATCH::RESET_VALUE,
+            wuevflags: WUEVFLAGS::RESET_VALUE,
+            aon_software_event: false,
+            aon_sce_run_en: false,
+            aon_force_on: false,
+            software_event_cleared: false,
+
+            timers: [
+                AuxTimer::new(T0TARGET::VALUE::BIT_MASK),
+                AuxTimer::new(T1TARGET::VALUE::BIT_MASK),
+            ],
+
+            free_semaphores: 0xFF,
+            autotake: AUTOTAKE::RESET_VALUE,
+            autotake_pending: false,
+            autotake_done: false,
+
+            adc: AuxAdc::new(),
+        }
+    }
+
+    /// `AON_WUC:AUXCTL.SCE_RUN_EN`
+    pub(super) fn aon_sce_run_en(&self) -> bool {
+        self.aon_sce_run_en
+    }
+
+    /// Updates the copy of the `AON_WUC:AUXCTL` bits, which are used by AUX.
+    pub(super) fn set_aon_control(
+        &mut self,
+        sce_run_en: bool,
+        software_event: bool,
+        force_on: bool,
+    ) {
+        self.aon_sce_run_en = sce_run_en;
+        self.aon_software_event = software_event;
+        self.aon_force_on = force_on;
+    }
+
+    /// Sets a flag of `WUEVFLAGS` set by an event from AON.
+    pub(super) fn set_wakeup_flag(&mut self, mask: u32) {
+        self.wuevflags |= mask;
+    }
+
+    /// The lowest vector with its flag set in `VECFLAGS`.
+    pub(super) fn triggered_vector(&self) -> Option<u8> {
+        #[allow(clippy::cast_possible_truncation)]
+        (self.vecflags != 0).then(|| self.vecflags.trailing_zeros() as u8)
+    }
+
+    /// The event bus line selected with `SCEWEVSEL.WEV7_EV`, as in `AUX_SCE:WUSTAT.EV_SIGNALS`.
+    pub(super) fn wev7_event(&self) -> bool {
+        self.event_line(
+            u8::try_from(self.scewevsel & SCEWEVSEL::WEV7_EV::BIT_MASK).expect("5-bit line"),
+        )
+    }
+
+    /// The level of the event bus line `line`.
+    pub(super) fn event_line(&self, line: u8) -> bool {
+        self.events & (1 << line) != 0
+    }
+
+    /// Advances the timers, the semaphores and the ADC by a cycle of the AUX clock and updates
+    /// the event bus, with the flags set by its events.
+    pub(super) fn update_events(&mut self, ctx: &mut Context) -> RaisedEvents {
+        for timer in &mut self.timers {
+            let tick_source = timer
+                .tick_source()
+                .map(|(line, rising)| (self.events & (1 << line) != 0) == rising);
+            timer.tick(tick_source);
+        }
+        if self.autotake_pending {
+            let semaphore = 1 << (self.autotake & AUTOTAKE::SMPH_ID::BIT_MASK);
+            if self.free_semaphores & semaphore != 0 {
+                self.free_semaphores &= !semaphore;
+                self.autotake_pending = false;
+                self.autotake_done = true;
+            }
+        }
+        let (adc_line, _) = self.adc.start_source();
+        let adc_clock = self.adcclkctl & ADCCLKCTL::REQ::BIT_MASK != 0;
+        self.adc.tick(ctx, self.event_line(adc_line), adc_clock);
+
+        self.previous_events = self.events;
+        self.events = self.event_bus();
+        let rising = self.events & !self.previous_events;
+        let falling = !self.events & self.previous_events;
+
+        for vector in 0..VECTORS {
+            let cfg = self.veccfg[usize::from(vector / 2)] >> (8 * (vector % 2));
+            if cfg & VECCFG0::VEC0_EN::BIT_MASK == 0 {
+                continue;
+            }
+            let edges = if cfg & VECCFG0::VEC0_POL::BIT_MASK == 0 {
+                rising
+            } else {
+                falling
+            };
+            if edges & (1 << (cfg & VECCFG0::VEC0_EV::BIT_MASK)) != 0 {
+                trace!("AUX vector {vector} triggered");
+                self.vecflags |= 1 << vector;
+            }
+        }
+
+        // The flags to MCU and AON are set as long as their sources are at the active level.
+        let mcu_sources = self.evtomcu_sources() ^ self.evtomcupol;
+        self.evtomcuflags |= mcu_sources;
+        self.evtoaonflags |= self.evtoaon_sources();
+
+        let combined = self.evtomcuflags & self.combevtomcumask != 0;
+        let raised = RaisedEvents {
+            aon_flags: self.evtoaonflags & !self.reported_aon_flags,
+            combined: combined && !self.combined_event,
+        };
+        self.combined_event = combined;
+        self.reported_aon_flags = self.evtoaonflags;
+        raised
+    }
+
+    fn event_bus(&self) -> u32 {
+        let wuevflags = self.wuevflags();
+        let mut events = 0;
+        let mut set = |line: u8, level: bool| {
+            if level {
+                events |= 1 << line;
+            }
+        };
+        set(
+            line::AON_RTC_CH2,
+            wuevflags & WUEVFLAGS::AON_RTC_CH2::BIT_MASK != 0,
+        );
+        set(line::TIMER0_EV, self.timers[0].event());
+        set(line::TIMER1_EV, self.timers[1].event());
+        set(line::SMPH_AUTOTAKE_DONE, self.autotake_done);
+        set(line::ADC_DONE, self.adc.done_event());
+        set(line::ADC_FIFO_ALMOST_FULL, self.adc.almost_full_event());
+        set(
+            line::ADC_IRQ,
+            self.adc.irq_event(self.dmactl & DMACTL::EN::BIT_MASK != 0),
+        );
+        set(line::AON_SW, wuevflags & WUEVFLAGS::AON_SW::BIT_MASK != 0);
+        set(
+            line::AON_PROG_WU,
+            wuevflags & WUEVFLAGS::AON_PROG_WU::BIT_MASK != 0,
+        );
+        events
+    }
+
+    /// The sources of `EVTOMCUFLAGS`: `AON_WU_EV` combines the wakeup events from AON,
+    /// the next flags are the event lines of the same numbers, and `ADC_IRQ` is the last one.
+    fn evtomcu_sources(&self) -> u32 {
+        let aon_wakeup = [line::AON_RTC_CH2, line::AON_SW, line::AON_PROG_WU]
+            .into_iter()
+            .any(|line| self.event_line(line));
+        let mut sources = self.events & EVTOMCU_LINES_MASK;
+        if aon_wakeup {
+            sources |= EVTOMCUFLAGS::AON_WU_EV::BIT_MASK;
+        }
+        if self.event_line(line::ADC_IRQ) {
+            sources |= EVTOMCUFLAGS::ADC_IRQ::BIT_MASK;
+        }
+        sources
+    }
+
+    /// The `EVTOAONFLAGS` flags, whose sources are at the active level.
+    fn evtoaon_sources(&self) -> u32 {
+        let mut sources = 0;
+        for (mask, line) in EVTOAON_LINES {
+            if self.event_line(line) {
+                sources |= mask;
+            }
+        }
+        (sources ^ self.evtoaonpol) & EVTOAON_LINES_MASK
+    }
+
+    fn wuevflags(&self) -> u32 {
+        if self.aon_software_event {
+            self.wuevflags | WUEVFLAGS::AON_SW::BIT_MASK
+        } else {
+            self.wuevflags
+        }
+    }
+
+    /// Whether the state changes without accesses to the registers.
+    pub(super) fn is_busy(&self) -> bool {
+        let mcu_sources = self.evtomcu_sources() ^ self.evtomcupol;
+        self.timers.iter().any(AuxTimer::is_enabled)
+            || self.autotake_pending
+            || self.adc.is_busy()
+            || self.events != self.previous_events
+            || self.event_bus() != self.events
+            || self.evtomcuflags & mcu_sources != mcu_sources
+            || self.evtoaonflags & self.evtoaon_sources() != self.evtoaon_sources()
+            || self.evtoaonflags != self.reported_aon_flags
+            || (self.evtomcuflags & self.combevtomcumask != 0) != self.combined_event
+    }
+
+    /// Returns the value of the register at `addr`, without the side effects of reading it.
+    pub(super) fn get_register(&self, addr: Address) -> Option<u32> {
+        Some(match addr {
+            VECCFG0::ADDR => self.veccfg[0],
+            VECCFG1::ADDR => self.veccfg[1],
+            SCEWEVSEL::ADDR => self.scewevsel,
+            EVTOAONFLAGS::ADDR => self.evtoaonflags,
+            EVTOAONPOL::ADDR => self.evtoaonpol,
+            DMACTL::ADDR => self.dmactl,
+            EVSTAT0::ADDR => self.events & 0xFFFF,
+            EVSTAT1::ADDR => self.events >> 16,
+            EVTOMCUPOL::ADDR => self.evtomcupol,
+            EVTOMCUFLAGS::ADDR => self.evtomcuflags,
+            COMBEVTOMCUMASK::ADDR => self.combevtomcumask,
+            VECFLAGS::ADDR => self.vecflags,
+
+            MODCLKEN0::ADDR => self.modclken0,
+            MODCLKEN1::ADDR => self.modclken1,
+            PWROFFREQ::ADDR => self.pwroffreq,
+            // The requests are acknowledged immediately.
+            PWRDWNREQ::ADDR | PWRDWNACK::ADDR => self.pwrdwnreq,
+            CLKLFREQ::ADDR | CLKLFACK::ADDR => self.clklfreq,
+            ADCCLKCTL::ADDR => acknowledged(self.adcclkctl),
+            TDCCLKCTL::ADDR => acknowledged(self.tdcclkctl),
+            REFCLKCTL::ADDR => acknowledged(self.refclkctl),
+            RTCSUBSECINC0::ADDR => self.rtcsubsecinc[0],
+            RTCSUBSECINC1::ADDR => self.rtcsubsecinc[1],
+            RTCSUBSECINCCTL::ADDR => acknowledged(self.rtcsubsecincctl),
+            MCUBUSCTL::ADDR => self.mcubusctl,
+            MCUBUSSTAT::ADDR => {
+                if self.mcubusctl & MCUBUSCTL::DISCONNECT_REQ::BIT_MASK != 0 {
+                    MCUBUSSTAT::DISCONNECT_ACK::BIT_MASK | MCUBUSSTAT::DISCONNECTED::BIT_MASK
+                } else {
+                    0
+                }
+            }
+            AONCTLSTAT::ADDR => {
+                let mut stat = 0;
+                if self.aon_sce_run_en {
+                    stat |= AONCTLSTAT::SCE_RUN_EN::BIT_MASK;
+                }
+                if self.aon_force_on {
+                    stat |= AONCTLSTAT::AUX_FORCE_ON::BIT_MASK;
+                }
+                stat
+            }
+            AUXIOLATCH::ADDR => self.auxiolatch,
+            WUEVFLAGS::ADDR => self.wuevflags(),
+
+            T0CFG::ADDR => self.timers[0].cfg(),
+            T1CFG::ADDR => self.timers[1].cfg(),
+            T0CTL::ADDR => self.timers[0].ctl(),
+            T1CTL::ADDR => self.timers[1].ctl(),
+            T0TARGET::ADDR => self.timers[0].target(),
+            T1TARGET::ADDR => self.timers[1].target(),
+
+            // Write-only
+            SWEVSET::ADDR
+            | EVTOMCUFLAGSCLR::ADDR
+            | EVTOAONFLAGSCLR::ADDR
+            | VECFLAGSCLR::ADDR
+            | WUEVCLR::ADDR => 0,
+
+            a if a.is_in_range(&(SMPH0::ADDR..AUTOTAKE::ADDR)) => {
+                u32::from(self.free_semaphores & semaphore_of(a) != 0)
+            }
+            AUTOTAKE::ADDR => self.autotake,
+            _ => return self.adc.get_register(addr),
+        })
+    }
+
+    /// The data of the bytes of the word at `addr` not written by a narrower write.
+    pub(super) fn write_filler(&self, addr: Address) -> Option<u32> {
+        if is_adc_register(addr) {
+            self.adc.write_filler(addr)
+        } else {
+            self.get_register(addr)
+        }
+    }
+
+    /// Reads the register at `addr`: reading a semaphore takes it.
+    pub(super) fn read_register(&mut self, addr: Address) -> Option<u32> {
+        if is_adc_register(addr) {
+            return self.adc.read_register(addr);
+        }
+        let data = self.get_register(addr)?;
+        if addr.is_in_range(&(SMPH0::ADDR..AUTOTAKE::ADDR)) {
+            self.free_semaphores &= !semaphore_of(addr);
+        }
+        Some(data)
+    }
+
+    /// Writes the register at `addr`, returns `None` if there is no such register.
+    pub(super) fn write_register(&mut self, addr: Address, data: u32) -> Option<()> {
+        match addr {
+            VECCFG0::ADDR => self.veccfg[0] = data,
+            VECCFG1::ADDR => self.veccfg[1] = data,
+            SCEWEVSEL::ADDR => self.scewevsel = data,
+            // Writing 0 to a flag clears it.
+            EVTOAONFLAGS::ADDR => self.evtoaonflags &= data,
+            EVTOAONPOL::ADDR => self.evtoaonpol = data & EVTOAON_LINES_MASK,
+            DMACTL::ADDR => self.dmactl = data,
+            SWEVSET::ADDR => self.evtoaonflags |= data & SWEV_MASK,
+            EVTOMCUPOL::ADDR => self.evtomcupol = data & EVTOMCU_MASK,
+            EVTOMCUFLAGS::ADDR => self.evtomcuflags &= data,
+            COMBEVTOMCUMASK::ADDR => self.combevtomcumask = data,
+            EVTOMCUFLAGSCLR::ADDR => self.evtomcuflags &= !data,
+            EVTOAONFLAGSCLR::ADDR => self.evtoaonflags &= !data,
+            VECFLAGSCLR::ADDR => self.vecflags &= !data,
+
+            MODCLKEN0::ADDR => self.modclken0 = data,
+            MODCLKEN1::ADDR => self.modclken1 = data,
+            PWROFFREQ::ADDR => self.pwroffreq = data,
+            PWRDWNREQ::ADDR => self.pwrdwnreq = data,
+            CLKLFREQ::ADDR => self.clklfreq = data,
+            ADCCLKCTL::ADDR => self.adcclkctl = data,
+            TDCCLKCTL::ADDR => self.tdcclkctl = data,
+            REFCLKCTL::ADDR => self.refclkctl = data,
+            // Not implemented: updating the sub-second increment of the RTC.
+            RTCSUBSECINC0::ADDR => self.rtcsubsecinc[0] = data,
+            RTCSUBSECINC1::ADDR => self.rtcsubsecinc[1] = data,
+            RTCSUBSECINCCTL::ADDR => self.rtcsubsecincctl = data,
+            MCUBUSCTL::ADDR => self.mcubusctl = data,
+            AUXIOLATCH::ADDR => self.auxiolatch = data,
+            WUEVCLR::ADDR => {
+                self.wuevflags &= !data;
+                if data & WUEVCLR::AON_SW::BIT_MASK != 0 {
+                    self.aon_software_event = false;
+                    self.software_event_cleared = true;
+                }
+            }
+
+            T0CFG::ADDR => self.timers[0].set_cfg(data),
+            T1CFG::ADDR => self.timers[1].set_cfg(data),
+            T0CTL::ADDR => self.timers[0].set_ctl(data),
+            T1CTL::ADDR => self.timers[1].set_ctl(data),
+            T0TARGET::ADDR => self.timers[0].set_target(data),
+            T1TARGET::ADDR => self.timers[1].set_target(data),
+
+            // Writing 1 releases the semaphore.
+            a if a.is_in_range(&(SMPH0::ADDR..AUTOTAKE::ADDR)) => {
+                if data & SMPH0::STAT::BIT_MASK != 0 {
+                    let semaphore = semaphore_of(a);
+                    self.free_semaphores |= semaphore;
+                    if self.autotake_done
+                        && semaphore == 1 << (self.autotake & AUTOTAKE::SMPH_ID::BIT_MASK)
+                    {
+                        self.autotake_done = false;
+                    }
+                }
+            }
+            AUTOTAKE::ADDR => {
+                self.autotake = data;
+                self.autotake_pending = true;
+                self.autotake_done = false;
+            }
+
+            EVSTAT0::ADDR
+            | EVSTAT1::ADDR
+            | VECFLAGS::ADDR
+            | PWRDWNACK::ADDR
+            | CLKLFACK::ADDR
+            | MCUBUSSTAT::ADDR
+            | AONCTLSTAT::ADDR
+            | WUEVFLAGS::ADDR => {
+                warn!("Ignoring a write {data:#x} to the read-only AUX register at {addr:?}");
+            }
+            _ => return self.adc.write_register(addr, data),
+        }
+        Some(())
+    }
+}
+
+/// Registers with a `REQ` bit 0 and an `ACK` bit 1, which follows the request immediately.
+fn acknowledged(request: u32) -> u32 {
+    request | ((request & 1) << 1)
+}
+
+fn is_adc_register(addr: Address) -> bool {
+    addr.is_in_range(&AUX_ANAIF::ADDR_SPACE) || addr.is_in_range(&AUX_ADI4::ADDR_SPACE)
+}
+
+fn semaphore_of(addr: Address) -> u8 {
+    debug_assert!(addr <= SMPH7::ADDR);
+    1 << (addr.offset_from(SMPH0::ADDR) / 4)
+}
+
+/// Address of the AUX register accessed by the engine at the I/O address `io`.
+fn io_address(io: u16) -> Address {
+    cc2650_constants::AUX::START_ADDR.offset(u32::from(io) * 4)
+}
+
+impl SceBus for AuxPeripherals {
+    fn read_ram(&self, addr: u16) -> u16 {
+        self.ram[usize::from(addr)]
+    }
+
+    fn write_ram(&mut self, addr: u16, data: u16) {
+        self.ram[usize::from(addr)] = data;
+    }
+
+    fn read_io(&mut self, addr: u16) -> u16 {
+        let address = io_address(addr);
+        if let Some(data) = self.read_register(address) {
+            #[allow(clippy::cast_possible_truncation)]
+            let data = data as u16;
+            data
+        } else {
+            warn!("SCE read of an unmodeled AUX register at {address:?}, returning 0");
+            0
+        }
+    }
+
+    fn write_io(&mut self, addr: u16, data: u16) {
+        let address = io_address(addr);
+        if self.write_register(address, u32::from(data)).is_none() {
+            warn!("Ignoring an SCE write {data:#x} to an unmodeled AUX register at {address:?}");
+        }
+    }
+
+    fn event(&self, line: u8) -> bool {
+        self.event_line(line)
+    }
+}
@@ -0,0 +1,544 @@
+//! The Sensor Controller Engine (`AUX_SCE`): a 16-bit CPU executing from the AUX RAM.
+//!
+//! The engine has eight 16-bit registers `R0`-`R7`, the `Z`, `N`, `C` and `V` flags,
+//! a hardware call stack and a single hardware loop (`LOOPADDR` and `LOOPCNT`).
+//! Code and data addresses are word addresses in the AUX RAM. The I/O instructions address
+//! the AUX peripherals by the word index from the start of the AUX bus (`AUX_AIODIO0`).
+//!
+//! The TRM documents the engine only through its internal registers, so the instruction
+//! encoding below is our own model of it. `d` and `s` are register numbers,
+//! `i` are immediates, `a` absolute addresses, `o` signed branch offsets from the next
+//! instruction, `c` branch conditions and `e` lines of the AUX event bus:
+//!
+//! ```text
+//! 0000 0000 0000 0000  nop
+//! 0000 0001 0000 0000  rts
+//! 0000 0010 0000 0000  sleep             wait for a vector in AUX_EVCTL:VECFLAGS
+//! 0000 0011 0000 0000  halt              stop until restarted by the MCU
+//! 0000 0100 000e eeee  wev0 #e           wait for the event line to be low
+//! 0000 0101 000e eeee  wev1 #e           wait for the event line to be high
+//! 0000 0110 0000 0sss  jmp Rs
+//! 0000 0111 0000 0sss  jsr Rs
+//! 0001 0aaa aaaa aaaa  jmp #a
+//! 0010 0aaa aaaa aaaa  jsr #a
+//! 0011 cccc oooo oooo  b<c> #o           see `Condition`
+//! 0100 0ddd iiii iiii  ld Rd, #i         sign-extended
+//! 0100 1ddd iiii iiii  ldh Rd, #i        sets the high byte only
+//! 0101 0ddd sssi iiii  ld Rd, [Rs+#i]
+//! 0101 1ddd sssi iiii  st Rd, [Rs+#i]
+//! 0110 0ddd sss0 0000  ld Rd, [Rs++]
+//! 0110 1ddd sss0 0000  st Rd, [Rs++]
+//! 0111 pppp 0ddd 0sss  <p> Rd, Rs        see `AluOp`
+//! 1000 pppp dddi iiii  <p> Rd, #i        zero-extended
+//! 1001 0ddd 0000 0sss  in Rd, [Rs]
+//! 1001 1ddd 0000 0sss  out Rd, [Rs]
+//! 1010 00bb bb00 0sss  iobset #b, [Rs]
+//! 1010 01bb bb00 0sss  iobclr #b, [Rs]
+//! 1010 10bb bb00 0sss  iobtst #b, [Rs]   Z is set if the bit is clear
+//! 1011 0sss iiii iiii  loop Rs, #i       repeats the next i instructions Rs[7:0] times
+//! ```
+//!
+//! Every instruction takes a single AUX clock cycle. An undefined instruction, an access
+//! outside of the AUX RAM or a misuse of the call stack stop the engine with
+//! `CPUSTAT.BUS_ERROR` set.
+
+use cc2650_constants::AUX_SCE::{CPUSTAT, FETCHSTAT, LOOPADDR};
+use log::{trace, warn};
+use serde::{Deserialize, Serialize};
+
+/// Number of the words of the AUX RAM.
+pub(super) const RAM_WORDS: u16 = 0x800;
+
+/// Depth of the hardware call stack.
+const STACK_DEPTH: usize = 8;
+
+/// Vectors occupy two words each at the start of the AUX RAM.
+const VECTOR_SIZE: u16 = 2;
+
+/// Accesses of the engine to the rest of the AUX domain.
+pub(super) trait SceBus {
+    fn read_ram(&self, addr: u16) -> u16;
+    fn write_ram(&mut self, addr: u16, data: u16);
+    fn read_io(&mut self, addr: u16) -> u16;
+    fn write_io(&mut self, addr: u16, data: u16);
+    /// The level of the line `line` of the AUX event bus.
+    fn event(&self, line: u8) -> bool;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+enum State {
+    Running,
+    /// After `sleep`, until a vector is triggered.
+    Sleeping,
+    /// After `wev0` or `wev1`, until the event line has the level.
+    WaitingForEvent {
+        line: u8,
+        level: bool,
+    },
+    /// After `halt` or an error, until the engine is restarted.
+    Stopped,
+}
+
+/// Branch conditions, following the `C` flag convention of a "no borrow" for subtraction.
+#[derive(Clone, Copy, Debug)]
+enum Condition {
+    Always,
+    Eq,
+    Ne,
+    Cs,
+    Cc,
+    Mi,
+    Pl,
+    Vs,
+    Vc,
+    Hi,
+    Ls,
+    Ge,
+    Lt,
+    Gt,
+    Le,
+}
+
+impl Condition {
+    fn decode(bits: u16) -> Option<Self> {
+        Some(match bits {
+            0x0 => Self::Always,
+            0x1 => Self::Eq,
+            0x2 => Self::Ne,
+            0x3 => Self::Cs,
+            0x4 => Self::Cc,
+            0x5 => Self::Mi,
+            0x6 => Self::Pl,
+            0x7 => Self::Vs,
+            0x8 => Self::Vc,
+            0x9 => Self::Hi,
+            0xA => Self::Ls,
+            0xB => Self::Ge,
+            0xC => Self::Lt,
+            0xD => Self::Gt,
+            0xE => Self::Le,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum AluOp {
+    Mov,
+    Add,
+    Adc,
+    Sub,
+    Sbc,
+    Cmp,
+    And,
+    Or,
+    Xor,
+    Tst,
+    Lsl,
+    Lsr,
+    Asr,
+    Neg,
+    Not,
+}
+
+impl AluOp {
+    fn decode(bits: u16) -> Option<Self> {
+        Some(match bits {
+            0x0 => Self::Mov,
+            0x1 => Self::Add,
+            0x2 => Self::Adc,
+            0x3 => Self::Sub,
+            0x4 => Self::Sbc,
+            0x5 => Self::Cmp,
+            0x6 => Self::And,
+            0x7 => Self::Or,
+            0x8 => Self::Xor,
+            0x9 => Self::Tst,
+            0xA => Self::Lsl,
+            0xB => Self::Lsr,
+            0xC => Self::Asr,
+            0xD => Self::Neg,
+            0xE => Self::Not,
+            _ => return None,
+        })
+    }
+}
+
+/// A reason for stopping the engine with `CPUSTAT.BUS_ERROR`.
+#[derive(Clone, Copy, Debug)]
+enum Fault {
+    UndefinedInstruction,
+    #[allow(dead_code)] // printed with `Debug`
+    OutsideOfRam(u16),
+    StackOverflow,
+    StackUnderflow,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(super) struct Sce {
+    regs: [u16; 8],
+    pc: u16,
+    /// `CPUSTAT` flags in their register layout.
+    flags: u32,
+    stack: [u16; STACK_DEPTH],
+    stack_len: usize,
+    /// `LOOPADDR.START`, `LOOPADDR.STOP` and `LOOPCNT.ITER_LEFT` of the active loop.
+    loop_start: u16,
+    loop_stop: u16,
+    loop_left: u8,
+    state: State,
+    bus_error: bool,
+    /// The last fetched instruction, as in `FETCHSTAT.OPCODE`.
+    opcode: u16,
+    /// The vector of the last wakeup, as in `WUSTAT.EXC_VECTOR`.
+    vector: u8,
+}
+
+impl Sce {
+    pub(super) fn new() -> Self {
+        Self {
+            regs: [0; 8],
+            pc: 0,
+            flags: 0,
+            stack: [0; STACK_DEPTH],
+            stack_len: 0,
+            loop_start: 0,
+            loop_stop: 0,
+            loop_left: 0,
+            state: State::Stopped,
+            bus_error: false,
+            opcode: 0,
+            vector: 0,
+        }
+    }
+
+    /// Restarts the engine at the vector `reset_vector`, as by `CTL.RESTART`.
+    pub(super) fn restart(&mut self, reset_vector: u8) {
+        *self = Self::new();
+        self.pc = u16::from(reset_vector) * VECTOR_SIZE;
+        self.state = State::Running;
+    }
+
+    pub(super) fn is_sleeping(&self) -> bool {
+        self.state == State::Sleeping
+    }
+
+    /// Whether the engine makes no progress until an awaited event or vector comes.
+    pub(super) fn is_idle(&self, bus: &impl SceBus) -> bool {
+        match self.state {
+            State::Running => false,
+            State::WaitingForEvent { line, level } => bus.event(line) != level,
+            State::Sleeping | State::Stopped => true,
+        }
+    }
+
+    /// Jumps to the vector `vector` if sleeping.
+    pub(super) fn wake_up(&mut self, vector: u8) {
+        debug_assert!(self.is_sleeping());
+        trace!("SCE wakes up to vector {vector}");
+        self.vector = vector;
+        self.pc = u16::from(vector) * VECTOR_SIZE;
+        self.state = State::Running;
+    }
+
+    pub(super) fn cpustat(&self) -> u32 {
+        let mut cpustat = self.flags;
+        if self.bus_error {
+            cpustat |= CPUSTAT::BUS_ERROR::BIT_MASK;
+        }
+        match self.state {
+            State::Running => {}
+            State::Sleeping => cpustat |= CPUSTAT::SLEEP::BIT_MASK,
+            State::WaitingForEvent { .. } => cpustat |= CPUSTAT::WEV::BIT_MASK,
+            State::Stopped => cpustat |= CPUSTAT::SELF_STOP::BIT_MASK,
+        }
+        cpustat
+    }
+
+    pub(super) fn fetchstat(&self) -> u32 {
+        (u32::from(self.opcode) << FETCHSTAT::OPCODE::BIT_RANGE.start()) | u32::from(self.pc)
+    }
+
+    pub(super) fn loopaddr(&self) -> u32 {
+        (u32::from(self.loop_stop) << LOOPADDR::STOP::BIT_RANGE.start())
+            | u32::from(self.loop_start)
+    }
+
+    pub(super) fn loopcnt(&self) -> u32 {
+        u32::from(self.loop_left)
+    }
+
+    pub(super) fn exception_vector(&self) -> u8 {
+        self.vector
+    }
+
+    /// `REGn_m` registers: the pair of `R(2n)` and `R(2n+1)`.
+    pub(super) fn register_pair(&self, first: usize) -> u32 {
+        u32::from(self.regs[first]) | (u32::from(self.regs[first + 1]) << 16)
+    }
+
+    /// Executes a single instruction, if the engine is running or its awaited event came.
+    pub(super) fn step(&mut self, bus: &mut impl SceBus) {
+        match self.state {
+            State::Running => {}
+            State::WaitingForEvent { line, level } if bus.event(line) == level => {
+                self.state = State::Running;
+            }
+            State::WaitingForEvent { .. } | State::Sleeping | State::Stopped => return,
+        }
+        if let Err(fault) = self.execute(bus) {
+            warn!(
+                "SCE stopped by {fault:?} at {:#05x} (opcode {:#06x})",
+                self.pc, self.opcode
+            );
+            self.bus_error = true;
+            self.state = State::Stopped;
+        }
+    }
+
+    fn execute(&mut self, bus: &mut impl SceBus) -> Result<(), Fault> {
+        let pc = self.pc;
+        let op = bus.read_ram(check_ram(pc)?);
+        self.opcode = op;
+        let mut next = pc.wrapping_add(1);
+
+        let d = usize::from((op >> 8) & 7);
+        let s = usize::from(op & 7);
+        match op >> 12 {
+            0x0 => match (op >> 8) & 0xF {
+                0x0 if op == 0 => {}
+                0x1 if op == 0x0100 => next = self.pop()?,
+                0x2 if op == 0x0200 => self.state = State::Sleeping,
+                0x3 if op == 0x0300 => self.state = State::Stopped,
+                sub @ (0x4 | 0x5) if op & 0xE0 == 0 => {
+                    #[allow(clippy::cast_possible_truncation)]
+                    let line = (op & 0x1F) as u8;
+                    self.state = State::WaitingForEvent {
+                        line,
+                        level: sub == 0x5,
+                    };
+                }
+                0x6 if op & 0xF8 == 0 => next = self.regs[s],
+                0x7 if op & 0xF8 == 0 => {
+                    self.push(next)?;
+                    next = self.regs[s];
+                }
+                _ => return Err(Fault::UndefinedInstruction),
+            },
+            0x1 if op & 0x0800 == 0 => next = op & 0x7FF,
+            0x2 if op & 0x0800 == 0 => {
+                self.push(next)?;
+                next = op & 0x7FF;
+            }
+            0x3 => {
+                let condition =
+                    Condition::decode((op >> 8) & 0xF).ok_or(Fault::UndefinedInstruction)?;
+                if self.holds(condition) {
+                    #[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
+                    let offset = i16::from(op as u8 as i8);
+                    next = next.wrapping_add_signed(offset);
+                }
+            }
+            0x4 if op & 0x0800 == 0 => {
+                #[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
+                let value = i16::from(op as u8 as i8);
+                #[allow(clippy::cast_sign_loss)]
+                let value = value as u16;
+                self.regs[d] = value;
+            }
+            0x4 => self.regs[d] = (self.regs[d] & 0xFF) | (op << 8),
+            0x5 => {
+                let base = usize::from((op >> 5) & 7);
+                let addr = check_ram(self.regs[base].wrapping_add(op & 0x1F))?;
+                if op & 0x0800 == 0 {
+                    self.regs[d] = bus.read_ram(addr);
+                } else {
+                    bus.write_ram(addr, self.regs[d]);
+                }
+            }
+            0x6 if op.trailing_zeros() >= 5 => {
+                let base = usize::from((op >> 5) & 7);
+                let addr = check_ram(self.regs[base])?;
+                if op & 0x0800 == 0 {
+                    self.regs[d] = bus.read_ram(addr);
+                } else {
+                    bus.write_ram(addr, self.regs[d]);
+                }
+                self.regs[base] = self.regs[base].wrapping_add(1);
+            }
+            0x7 if op & 0x88 == 0 => {
+                let alu_op = AluOp::decode((op >> 8) & 0xF).ok_or(Fault::UndefinedInstruction)?;
+                let d = usize::from((op >> 4) & 7);
+                self.alu(alu_op, d, self.regs[s]);
+            }
+            0x8 => {
+                let alu_op = AluOp::decode((op >> 8) & 0xF).ok_or(Fault::UndefinedInstruction)?;
+                let d = usize::from((op >> 5) & 7);
+                self.alu(alu_op, d, op & 0x1F);
+            }
+            0x9 if op & 0xF8 == 0 => {
+                if op & 0x0800 == 0 {
+                    self.regs[d] = bus.read_io(self.regs[s]);
+                } else {
+                    bus.write_io(self.regs[s], self.regs[d]);
+                }
+            }
+            0xA if op & 0x38 == 0 => {
+                let mask = 1 << ((op >> 6) & 0xF);
+                let addr = self.regs[s];
+                match (op >> 10) & 3 {
+                    0 => {
+                        let value = bus.read_io(addr);
+                        bus.write_io(addr, value | mask);
+                    }
+                    1 => {
+                        let value = bus.read_io(addr);
+                        bus.write_io(addr, value & !mask);
+                    }
+                    2 => self.set_flag(CPUSTAT::Z_FLAG::BIT_MASK, bus.read_io(addr) & mask == 0),
+                    _ => return Err(Fault::UndefinedInstruction),
+                }
+            }
+            0xB if op & 0x0800 == 0 => {
+                let length = op & 0xFF;
+                #[allow(clippy::cast_possible_truncation)]
+                let count = self.regs[d] as u8;
+                if length == 0 || count == 0 {
+                    next = next.wrapping_add(length);
+                } else {
+                    self.loop_start = next;
+                    self.loop_stop = pc.wrapping_add(length);
+                    self.loop_left = count;
+                }
+                self.pc = next;
+                return Ok(());
+            }
+            _ => return Err(Fault::UndefinedInstruction),
+        }
+
+        // The hardware loop jumps back after its last instruction, unless it was a taken jump.
+        if self.loop_left > 0 && pc == self.loop_stop && next == pc.wrapping_add(1) {
+            self.loop_left -= 1;
+            if self.loop_left > 0 {
+                next = self.loop_start;
+            }
+        }
+        self.pc = next;
+        Ok(())
+    }
+
+    fn push(&mut self, addr: u16) -> Result<(), Fault> {
+        let slot = self
+            .stack
+            .get_mut(self.stack_len)
+            .ok_or(Fault::StackOverflow)?;
+        *slot = addr;
+        self.stack_len += 1;
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Result<u16, Fault> {
+        self.stack_len = self.stack_len.checked_sub(1).ok_or(Fault::StackUnderflow)?;
+        Ok(self.stack[self.stack_len])
+    }
+
+    fn flag(&self, mask: u32) -> bool {
+        self.flags & mask != 0
+    }
+
+    fn set_flag(&mut self, mask: u32, value: bool) {
+        if value {
+            self.flags |= mask;
+        } else {
+            self.flags &= !mask;
+        }
+    }
+
+    fn holds(&self, condition: Condition) -> bool {
+        let z = self.flag(CPUSTAT::Z_FLAG::BIT_MASK);
+        let n = self.flag(CPUSTAT::N_FLAG::BIT_MASK);
+        let c = self.flag(CPUSTAT::C_FLAG::BIT_MASK);
+        let v = self.flag(CPUSTAT::V_FLAG::BIT_MASK);
+        match condition {
+            Condition::Always => true,
+            Condition::Eq => z,
+            Condition::Ne => !z,
+            Condition::Cs => c,
+            Condition::Cc => !c,
+            Condition::Mi => n,
+            Condition::Pl => !n,
+            Condition::Vs => v,
+            Condition::Vc => !v,
+            Condition::Hi => c && !z,
+            Condition::Ls => !c || z,
+            Condition::Ge => n == v,
+            Condition::Lt => n != v,
+            Condition::Gt => !z && n == v,
+            Condition::Le => z || n != v,
+        }
+    }
+
+    fn alu(&mut self, op: AluOp, d: usize, operand: u16) {
+        let value = self.regs[d];
+        let carry = self.flag(CPUSTAT::C_FLAG::BIT_MASK);
+        let result = match op {
+            AluOp::Mov => operand,
+            AluOp::Add => self.add_with_carry(value, operand, false),
+            AluOp::Adc => self.add_with_carry(value, operand, carry),
+            AluOp::Sub | AluOp::Cmp => self.add_with_carry(value, !operand, true),
+            AluOp::Sbc => self.add_with_carry(value, !operand, carry),
+            AluOp::Neg => self.add_with_carry(0, !operand, true),
+            AluOp::And | AluOp::Tst => value & operand,
+            AluOp::Or => value | operand,
+            AluOp::Xor => value ^ operand,
+            AluOp::Not => !operand,
+            AluOp::Lsl | AluOp::Lsr | AluOp::Asr => {
+                let amount = u32::from(operand.min(16));
+                if amount > 0 {
+                    let (result, carry_out) = match op {
+                        AluOp::Lsl => (
+                            value.checked_shl(amount).unwrap_or(0),
+                            (u32::from(value) << amount) & 0x1_0000 != 0,
+                        ),
+                        AluOp::Lsr => (
+                            value.checked_shr(amount).unwrap_or(0),
+                            (u32::from(value) >> (amount - 1)) & 1 != 0,
+                        ),
+                        _ => {
+                            let signed = value.cast_signed();
+                            let result = (signed >> amount.min(15)).cast_unsigned();
+                            (result, (i32::from(signed) >> (amount - 1)) & 1 != 0)
+                        }
+                    };
+                    self.set_flag(CPUSTAT::C_FLAG::BIT_MASK, carry_out);
+                    result
+                } else {
+                    value
+                }
+            }
+        };
+        self.set_flag(CPUSTAT::Z_FLAG::BIT_MASK, result == 0);
+        self.set_flag(CPUSTAT::N_FLAG::BIT_MASK, result & 0x8000 != 0);
+        if !matches!(op, AluOp::Cmp | AluOp::Tst) {
+            self.regs[d] = result;
+        }
+    }
+
+    fn add_with_carry(&mut self, a: u16, b: u16, carry: bool) -> u16 {
+        let sum = u32::from(a) + u32::from(b) + u32::from(carry);
+        #[allow(clippy::cast_possible_truncation)]
+        let result = sum as u16;
+        let overflow = (a ^ result) & (b ^ result) & 0x8000 != 0;
+        self.set_flag(CPUSTAT::C_FLAG::BIT_MASK, sum > 0xFFFF);
+        self.set_flag(CPUSTAT::V_FLAG::BIT_MASK, overflow);
+        result
+    }
+}
+
+fn check_ram(addr: u16) -> Result<u16, Fault> {
+    if addr < RAM_WORDS {
+        Ok(addr)
+    } else {
+        Err(Fault::OutsideOfRam(addr))
+    }
+}
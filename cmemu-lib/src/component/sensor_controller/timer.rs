@@ -0,0 +1,145 @@
+//! A timer of `AUX_TIMER`: the 16-bit Timer 0 or the 8-bit Timer 1.
+//!
+//! Both timers share the layout of their registers, so Timer 0 names are used for both.
+
+use cc2650_constants::AUX_TIMER::T0CFG;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(super) struct AuxTimer {
+    /// `TnCFG`
+    cfg: u32,
+    /// `TnCTL.EN`
+    enabled: bool,
+    /// `TnTARGET.VALUE`
+    target: u16,
+    target_mask: u16,
+
+    counter: u16,
+    prescaler_counter: u16,
+    /// The level of the tick source at the last tick, for detecting its edges.
+    tick_source_level: bool,
+    /// The timer event (`AUX_TIMERn_EV`), high for a single AUX clock cycle.
+    event: bool,
+}
+
+impl AuxTimer {
+    pub(super) fn new(target_mask: u32) -> Self {
+        Self {
+            cfg: T0CFG::RESET_VALUE,
+            enabled: false,
+            target: 0,
+            target_mask: u16::try_from(target_mask).expect("the target has at most 16 bits"),
+            counter: 0,
+            prescaler_counter: 0,
+            tick_source_level: false,
+            event: false,
+        }
+    }
+
+    pub(super) fn cfg(&self) -> u32 {
+        self.cfg
+    }
+
+    pub(super) fn set_cfg(&mut self, cfg: u32) {
+        self.cfg = cfg;
+    }
+
+    pub(super) fn ctl(&self) -> u32 {
+        u32::from(self.enabled)
+    }
+
+    /// [TI-TRM] `AUX_TIMER:T0CTL.EN`: "The counter restarts from 0 when you enable Timer 0."
+    pub(super) fn set_ctl(&mut self, ctl: u32) {
+        let enable = ctl & 1 != 0;
+        if enable && !self.enabled {
+            self.counter = 0;
+            self.prescaler_counter = 0;
+        }
+        self.enabled = enable;
+    }
+
+    pub(super) fn target(&self) -> u32 {
+        u32::from(self.target)
+    }
+
+    pub(super) fn set_target(&mut self, target: u32) {
+        #[allow(clippy::cast_possible_truncation)]
+        let target = target as u16;
+        self.target = target & self.target_mask;
+    }
+
+    pub(super) fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// The level of `AUX_TIMERn_EV` in this cycle.
+    pub(super) fn event(&self) -> bool {
+        self.event
+    }
+
+    /// The event bus line selected as the tick source and its counted level.
+    pub(super) fn tick_source(&self) -> Option<(u8, bool)> {
+        (self.cfg & T0CFG::MODE::BIT_MASK != 0).then(|| {
+            #[allow(clippy::cast_possible_truncation)]
+            let line = ((self.cfg & T0CFG::TICK_SRC::BIT_MASK)
+                >> T0CFG::TICK_SRC::BIT_RANGE.start()) as u8;
+            (line, self.cfg & T0CFG::TICK_SRC_POL::BIT_MASK == 0)
+        })
+    }
+
+    /// Advances the timer by a cycle of the AUX clock. `tick_source` is the level of the event
+    /// bus line selected with `TnCFG.TICK_SRC`, if the timer counts its edges.
+    pub(super) fn tick(&mut self, tick_source: Option<bool>) {
+        self.event = false;
+        if !self.enabled {
+            return;
+        }
+        if self.is_continuous() && self.target < 2 {
+            // The counter stays at 0 and the event stays high.
+            self.event = true;
+            return;
+        }
+        let counted = match tick_source {
+            Some(level) => {
+                let edge = level && !self.tick_source_level;
+                self.tick_source_level = level;
+                edge
+            }
+            None => true,
+        };
+        if !counted {
+            return;
+        }
+        let division = 1u32 << ((self.cfg & T0CFG::PRE::BIT_MASK) >> T0CFG::PRE::BIT_RANGE.start());
+        self.prescaler_counter += 1;
+        if u32::from(self.prescaler_counter) < division {
+            return;
+        }
+        self.prescaler_counter = 0;
+        self.count();
+    }
+
+    /// [TI-TRM] `AUX_TIMER:T0TARGET.VALUE` describes the two reload modes.
+    fn count(&mut self) {
+        if self.is_continuous() {
+            self.counter = if self.counter >= self.target - 1 {
+                0
+            } else {
+                self.counter + 1
+            };
+            self.event = self.counter == 0;
+        } else {
+            // Manual mode: counts up to the target (at least to 1) and stops.
+            self.counter += 1;
+            if self.counter >= self.target.max(1) {
+                self.event = true;
+                self.enabled = false;
+            }
+        }
+    }
+
+    fn is_continuous(&self) -> bool {
+        self.cfg & T0CFG::RELOAD::BIT_MASK != 0
+    }
+}
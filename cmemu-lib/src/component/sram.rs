@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use std::ops::Range;
 
 use cc2650_constants as soc;
@@ -22,6 +23,7 @@ use crate::engine::{
     DisableableComponent, MainComponent, SkippableClockTreeNode, TickComponent, TickComponentExtra,
 };
 use crate::proxy::SRAMProxy;
+use log::info;
 
 impl MemoryConfiguration for SRAMMemory {
     const IS_WRITABLE: bool = true;
@@ -32,10 +34,18 @@ impl MemoryConfiguration for SRAMMemory {
 
 type SRAMMemory = Memory<MemorySC>;
 
+const SRAM_BANKS: u32 = 4;
+
 #[derive(
-    MainComponent, SkippableClockTreeNode, TickComponent, TickComponentExtra, DisableableComponent,
+    Clone,
+    MainComponent,
+    SkippableClockTreeNode,
+    TickComponent,
+    TickComponentExtra,
+    DisableableComponent,
 )]
 #[skippable_if_disableable]
+#[derive(Serialize, Deserialize)]
 pub(crate) struct SRAMComponent {
     #[subcomponent(MemorySC)]
     memory: SRAMMemory,
@@ -49,6 +59,28 @@ impl SRAMComponent {
         }
     }
 
+    /// The memory keeps its contents across a reset, only its bus interface is reset.
+    pub(crate) fn reset_keeping_contents(&mut self) {
+        self.memory.reset_interface();
+    }
+
+    /// Corrupts the banks of SRAM without retention, as by powering off `MCU_VD`.
+    /// Bit `n` of `retained_banks` enables the retention of `SRAM:BANKn`
+    /// (as in `AON_WUC:MCUCFG.SRAM_RET_EN`).
+    // NOTE: the banks are assumed to be of equal size.
+    #[handler]
+    pub(crate) fn lose_unretained_banks(&mut self, _ctx: &mut Context, retained_banks: u8) {
+        let space = soc::SRAM::ADDR_SPACE;
+        let bank_size = space.end.offset_from(space.start) / SRAM_BANKS;
+        for bank in 0..SRAM_BANKS {
+            if retained_banks & (1 << bank) == 0 {
+                let start = space.start.offset(bank * bank_size);
+                info!("SRAM:BANK{bank} is not retained, its contents are lost");
+                self.memory.lose_contents(start..start.offset(bank_size));
+            }
+        }
+    }
+
     pub(crate) fn tick(&mut self, ctx: &mut Context) {
         SRAMMemory::run_driver(self, ctx);
     }
@@ -0,0 +1,670 @@
+pub const SSI_ROUTE_INJECTION: RangeUnion<Range<Address>, Range<Address>> =
+    RangeUnion(&SSI0::ADDR_SPACE, &SSI1::ADDR_SPACE);
+
+use crate::bridge_ports;
+#[proxy_use]
+use crate::build_data::{ClockTreeNodes, EnergyEntity};
+use crate::common::new_ahb::databus::DataBus;
+use crate::common::new_ahb::ports::AHBSlavePortProxiedInput;
+#[proxy_use]
+use crate::common::new_ahb::ports::{AHBPortConfig, AHBSlavePortInput};
+#[proxy_use]
+use crate::common::new_ahb::signals::{MasterToSlaveWires, Size};
+use crate::common::new_ahb::slave_driver::WriteMode;
+use crate::common::new_ahb::slave_driver::faking_slave_driver::{
+    AlignedFakingHandler, FakingIface, WaitstatesOrErr,
+};
+use crate::common::utils::HostHandle;
+use crate::component::event_fabric::EventFabricEvent;
+#[proxy_use]
+use crate::engine::Context;
+use crate::engine::{
+    DisableableComponent, MainComponent, SkippableClockTreeNode, TickComponent, TickComponentExtra,
+};
+use crate::proxy::{EventFabricProxy, SSIProxy};
+use cc2650_constants::{SSI0, SSI1};
+use cmemu_common::Address;
+use cmemu_common::address::RangeUnion;
+use cmemu_proc_macros::{component_impl, handler, proxy_use};
+use log::{trace, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::ops::Range;
+use std::panic::UnwindSafe;
+
+// [TI-TRM] 20 Synchronous Serial Interface (SSI)
+// SSI0 and SSI1 are ARM PL022-like modules clocked with the MCU clock through the per-module
+// clock gates of the PRCM. Only the master mode is emulated: the serial bus of each module is
+// connected to the emulator-attached `SpiDeviceInterface`, if any, which is selected with the
+// frame signal (`SSIFss`) and exchanges a frame at the end of each frame time.
+// The frame time is counted in whole bit periods: the half-period setup and hold times
+// of the frame signal are not emulated.
+// Not implemented: the slave mode (`CR1.MS`), thus `CR1.SOD` has no effect.
+
+/// `SpiDeviceInterface` is a virtual device attached to the serial bus of an SSI module.
+pub trait SpiDeviceInterface {
+    /// Called when the SSI asserts the frame signal, i.e., selects the device.
+    fn select(&mut self);
+    /// Called with each frame of `bits` bits shifted out by the SSI.
+    /// Returns the frame shifted in at the same time (only the `bits` lowest bits are used).
+    fn transfer(&mut self, data: u16, bits: u8) -> u16;
+    /// Called when the SSI deasserts the frame signal.
+    fn deselect(&mut self);
+}
+
+pub type SpiDeviceInterfaceImpl = Box<dyn SpiDeviceInterface + Send + Sync + UnwindSafe>;
+
+/// The SSI module a `SpiDeviceInterface` is attached to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SsiModule {
+    Ssi0,
+    Ssi1,
+}
+
+const MODULES_COUNT: usize = 2;
+
+const MODULE_BASES: [Address; MODULES_COUNT] = [SSI0::ADDR, SSI1::ADDR];
+
+const CLOCK_GATES: [ClockTreeNodes; MODULES_COUNT] =
+    [ClockTreeNodes::Ssi0Gate, ClockTreeNodes::Ssi1Gate];
+
+const INTERRUPT_EVENTS: [EventFabricEvent; MODULES_COUNT] =
+    [EventFabricEvent::SSI0_COMB, EventFabricEvent::SSI1_COMB];
+
+/// The DMA request lines of the receiver and the transmitter of each module.
+const DMA_REQUESTS: [[EventFabricEvent; 4]; MODULES_COUNT] = [
+    [
+        EventFabricEvent::SSI0_RX_DMASREQ,
+        EventFabricEvent::SSI0_RX_DMABREQ,
+        EventFabricEvent::SSI0_TX_DMASREQ,
+        EventFabricEvent::SSI0_TX_DMABREQ,
+    ],
+    [
+        EventFabricEvent::SSI1_RX_DMASREQ,
+        EventFabricEvent::SSI1_RX_DMABREQ,
+        EventFabricEvent::SSI1_TX_DMASREQ,
+        EventFabricEvent::SSI1_TX_DMABREQ,
+    ],
+];
+
+/// [TI-TRM] 20.4.3 FIFO Operation: both FIFOs are 8 entries deep.
+const FIFO_DEPTH: usize = 8;
+
+/// `TXRIS` and `RXRIS` are asserted at a half-full FIFO, what is also the burst DMA level.
+const FIFO_HALF: usize = FIFO_DEPTH / 2;
+
+/// [TI-TRM] 20.7.1.7 RIS Register: the receive time-out is 32 bit periods.
+const RECEIVE_TIMEOUT_BITS: u64 = 32;
+
+/// The National Microwire control word is always 8 bits long.
+const MICROWIRE_CONTROL_BITS: u8 = 8;
+
+/// Interrupts latched until cleared with `ICR`, the others follow the FIFO levels.
+const LATCHED_INTERRUPTS: u32 = SSI0::RIS::RTRIS::BIT_MASK | SSI0::RIS::RORRIS::BIT_MASK;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum FrameFormat {
+    MotorolaSpi,
+    TiSyncSerial,
+    NationalMicrowire,
+}
+
+#[derive(
+    Clone,
+    MainComponent,
+    TickComponent,
+    TickComponentExtra,
+    DisableableComponent,
+    Serialize,
+    Deserialize,
+)]
+pub(crate) struct SSIComponent {
+    #[subcomponent(DriverSC)]
+    driver: BusDriver,
+
+    modules: [SsiPort; MODULES_COUNT],
+}
+type BusDriver = FakingIface<DriverSC, SSIComponent>;
+
+#[derive(Clone, Serialize, Deserialize)]
+struct SsiPort {
+    cr0: u32,
+    cr1: u32,
+    cpsr: u32,
+    imsc: u32,
+    /// Only the `LATCHED_INTERRUPTS` bits of `RIS`.
+    ris: u32,
+    dmacr: u32,
+    /// The DMA request lines, indexed as `DMA_REQUESTS`.
+    dma_requests: u8,
+    /// Masked interrupts already signalled to the event fabric.
+    raised: u32,
+
+    tx_fifo: VecDeque<u16>,
+    rx_fifo: VecDeque<u16>,
+    /// The frame being transferred and its remaining time in cycles.
+    shifter: Option<(u16, u64)>,
+    /// Whether the frame signal is asserted.
+    selected: bool,
+    /// Time in cycles the SSI has been idle with a non-empty receive FIFO.
+    rx_idle: u64,
+
+    device: HostHandle<SpiDeviceInterfaceImpl>,
+}
+
+#[component_impl(ssi)]
+impl SSIComponent {
+    pub(crate) fn new() -> Self {
+        Self {
+            driver: Default::default(),
+            modules: [SsiPort::new(), SsiPort::new()],
+        }
+    }
+
+    pub(crate) fn tick(&mut self, ctx: &mut Context) {
+        BusDriver::run_driver(self, ctx);
+
+        for (index, module) in self.modules.iter_mut().enumerate() {
+            if module.is_running() && Self::is_clock_enabled(ctx, index) {
+                module.run(index);
+                module.update_dma_requests(ctx, index);
+                module.update_interrupts(ctx, index);
+            }
+        }
+    }
+
+    pub(crate) fn tock(&mut self, ctx: &mut Context) {
+        BusDriver::tock(self, ctx);
+    }
+
+    #[handler]
+    pub(crate) fn on_new_ahb_slave_input(
+        &mut self,
+        ctx: &mut Context,
+        msg: MasterToSlaveWires<<SSIComponent as AHBPortConfig>::Data>,
+    ) {
+        <Self as AHBSlavePortInput>::on_ahb_input(self, ctx, msg);
+    }
+
+    pub(crate) fn set_device(&mut self, module: SsiModule, device: Option<SpiDeviceInterfaceImpl>) {
+        *self.modules[module as usize].device = device;
+    }
+
+    pub(crate) fn take_device(&mut self, module: SsiModule) -> Option<SpiDeviceInterfaceImpl> {
+        self.modules[module as usize].device.take()
+    }
+
+    fn is_clock_enabled(ctx: &Context, index: usize) -> bool {
+        ctx.get_energy_state_of(EnergyEntity::ClockTree(CLOCK_GATES[index]))
+            .is_active()
+    }
+
+    fn decode_address(addr: Address) -> (usize, u32) {
+        let index = usize::from(!SSI0::ADDR_SPACE.contains(&addr));
+        (index, addr.offset_from(MODULE_BASES[index]))
+    }
+
+    fn get_data_for_address(&self, ctx: &Context, addr: Address) -> u32 {
+        let (index, offset) = Self::decode_address(addr);
+        let module = &self.modules[index];
+        match offset {
+            // Reading `DR` pops the receive FIFO, what is done by the bus handler.
+            SSI0::DR::OFFSET | SSI0::ICR::OFFSET => 0,
+            SSI0::CR0::OFFSET => module.cr0,
+            SSI0::CR1::OFFSET => module.cr1,
+            SSI0::SR::OFFSET => module.status(),
+            SSI0::CPSR::OFFSET => module.cpsr,
+            SSI0::IMSC::OFFSET => module.imsc,
+            SSI0::RIS::OFFSET => module.raw_interrupts(),
+            SSI0::MIS::OFFSET => module.raw_interrupts() & module.imsc,
+            SSI0::DMACR::OFFSET => module.dmacr,
+            _ => unimplemented!(
+                "Requested SSI{index} data read from address {:?}: {}",
+                addr,
+                ctx.display_named_address(addr)
+            ),
+        }
+    }
+
+    fn set_data_for_address(&mut self, ctx: &mut Context, addr: Address, data: u32) {
+        let (index, offset) = Self::decode_address(addr);
+        let module = &mut self.modules[index];
+        match offset {
+            SSI0::DR::OFFSET => module.push_data(index, data),
+            SSI0::CR0::OFFSET => module.cr0 = data & 0xffff,
+            SSI0::CR1::OFFSET => module.set_cr1(index, data),
+            SSI0::CPSR::OFFSET => module.cpsr = data & SSI0::CPSR::CPSDVSR::BIT_MASK & !1,
+            SSI0::IMSC::OFFSET => module.imsc = data & 0xf,
+            SSI0::ICR::OFFSET => module.ris &= !(data & LATCHED_INTERRUPTS),
+            SSI0::DMACR::OFFSET => module.dmacr = data & 0x3,
+            SSI0::SR::OFFSET | SSI0::RIS::OFFSET | SSI0::MIS::OFFSET => {
+                warn!(
+                    "Ignoring write {:#x} to read-only {}",
+                    data,
+                    ctx.display_named_address(addr)
+                );
+            }
+            _ => unimplemented!(
+                "Requested SSI{index} data write {:#x} to address {:?}: {}",
+                data,
+                addr,
+                ctx.display_named_address(addr)
+            ),
+        }
+    }
+
+    fn update_lines(&mut self, ctx: &mut Context, addr: Address) {
+        let (index, _) = Self::decode_address(addr);
+        let module = &mut self.modules[index];
+        module.update_dma_requests(ctx, index);
+        module.update_interrupts(ctx, index);
+    }
+
+    fn cycles_until_event(&self, ctx: &Context) -> Option<u64> {
+        self.modules
+            .iter()
+            .enumerate()
+            .filter(|&(index, _)| Self::is_clock_enabled(ctx, index))
+            .filter_map(|(_, module)| module.cycles_until_event())
+            .min()
+    }
+
+    fn warn_if_gated(ctx: &Context, addr: Address) {
+        let (index, _) = Self::decode_address(addr);
+        if !Self::is_clock_enabled(ctx, index) {
+            warn!(
+                "Accessing {} while the clock of SSI{index} is gated.",
+                ctx.display_named_address(addr)
+            );
+        }
+    }
+}
+
+impl SsiPort {
+    fn new() -> Self {
+        Self {
+            cr0: SSI0::CR0::RESET_VALUE,
+            cr1: SSI0::CR1::RESET_VALUE,
+            cpsr: SSI0::CPSR::RESET_VALUE,
+            imsc: SSI0::IMSC::RESET_VALUE,
+            ris: 0,
+            dmacr: SSI0::DMACR::RESET_VALUE,
+            dma_requests: 0,
+            raised: 0,
+
+            tx_fifo: VecDeque::with_capacity(FIFO_DEPTH),
+            rx_fifo: VecDeque::with_capacity(FIFO_DEPTH),
+            shifter: None,
+            selected: false,
+            rx_idle: 0,
+
+            device: HostHandle::new(None),
+        }
+    }
+
+    fn is_running(&self) -> bool {
+        self.cr1 & SSI0::CR1::SSE::BIT_MASK != 0 && self.cr1 & SSI0::CR1::MS::BIT_MASK == 0
+    }
+
+    fn frame_format(&self) -> FrameFormat {
+        match (self.cr0 & SSI0::CR0::FRF::BIT_MASK) >> 4 {
+            SSI0::CR0::FRF::Named::MOTOROLA_SPI => FrameFormat::MotorolaSpi,
+            SSI0::CR0::FRF::Named::TI_SYNC_SERIAL => FrameFormat::TiSyncSerial,
+            SSI0::CR0::FRF::Named::NATIONAL_MICROWIRE => FrameFormat::NationalMicrowire,
+            _ => {
+                warn!("Reserved SSI frame format, assuming Motorola SPI.");
+                FrameFormat::MotorolaSpi
+            }
+        }
+    }
+
+    /// `CR0.DSS` is the data size minus one, the values below 4 bits are reserved.
+    #[allow(clippy::cast_possible_truncation)]
+    fn data_bits(&self) -> u8 {
+        (self.cr0 & SSI0::CR0::DSS::BIT_MASK).max(3) as u8 + 1
+    }
+
+    fn data_mask(&self) -> u16 {
+        u16::MAX >> (16 - self.data_bits())
+    }
+
+    /// Motorola SPI frames with `SPH = 1` are sent back-to-back with the frame signal held.
+    fn holds_frame_signal(&self) -> bool {
+        self.frame_format() == FrameFormat::MotorolaSpi && self.cr0 & SSI0::CR0::SPH::BIT_MASK != 0
+    }
+
+    /// [TI-TRM] 20.4.1 Bit Rate Generation: `SSIClk = PERDMACLK / (CPSDVSR * (1 + SCR))`.
+    fn bit_time(&self) -> u64 {
+        let scr = u64::from((self.cr0 & SSI0::CR0::SCR::BIT_MASK) >> 8);
+        u64::from(self.cpsr) * (1 + scr)
+    }
+
+    /// [TI-TRM] 20.4.4 Frame Formats.
+    fn frame_time(&self) -> u64 {
+        let data_bits = u64::from(self.data_bits());
+        let bits = match self.frame_format() {
+            // The frame signal is pulsed for a bit period before each frame.
+            FrameFormat::TiSyncSerial => 1 + data_bits,
+            // The control word and a bit period of turnaround precede the response.
+            FrameFormat::NationalMicrowire => u64::from(MICROWIRE_CONTROL_BITS) + 1 + data_bits,
+            FrameFormat::MotorolaSpi if self.holds_frame_signal() => data_bits,
+            // The frame signal is deasserted for a bit period between the frames.
+            FrameFormat::MotorolaSpi => data_bits + 1,
+        };
+        bits * self.bit_time()
+    }
+
+    fn set_cr1(&mut self, index: usize, data: u32) {
+        let was_running = self.is_running();
+        self.cr1 = data & 0xf;
+        if !was_running && self.is_running() && self.bit_time() == 0 {
+            warn!("SSI{index} enabled with a zero clock prescale divisor.");
+        }
+        if self.cr1 & (SSI0::CR1::SSE::BIT_MASK | SSI0::CR1::MS::BIT_MASK)
+            == SSI0::CR1::SSE::BIT_MASK | SSI0::CR1::MS::BIT_MASK
+        {
+            warn!("SSI{index} slave mode is not implemented, the module stays idle.");
+        }
+        if was_running && !self.is_running() {
+            // The frame in progress is aborted.
+            self.shifter = None;
+            self.set_selected(false);
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn push_data(&mut self, index: usize, data: u32) {
+        if self.tx_fifo.len() < FIFO_DEPTH {
+            self.tx_fifo.push_back(data as u16);
+        } else {
+            warn!("SSI{index} transmit FIFO overflow, {data:#06x} is lost.");
+        }
+    }
+
+    fn read_data(&mut self) -> u32 {
+        let Some(data) = self.rx_fifo.pop_front() else {
+            return 0;
+        };
+        self.rx_idle = 0;
+        if self.rx_fifo.is_empty() {
+            self.ris &= !SSI0::RIS::RTRIS::BIT_MASK;
+        }
+        u32::from(data)
+    }
+
+    fn set_selected(&mut self, selected: bool) {
+        if self.selected == selected {
+            return;
+        }
+        self.selected = selected;
+        if let Some(device) = &mut *self.device {
+            if selected {
+                device.select();
+            } else {
+                device.deselect();
+            }
+        }
+    }
+
+    fn run(&mut self, index: usize) {
+        if let Some((data, remaining)) = self.shifter.as_mut() {
+            *remaining = remaining.saturating_sub(1);
+            if *remaining == 0 {
+                let data = *data;
+                self.shifter = None;
+                self.finish_frame(index, data);
+            }
+        }
+        if self.shifter.is_none() && self.bit_time() != 0 {
+            if let Some(data) = self.tx_fifo.pop_front() {
+                self.set_selected(true);
+                self.shifter = Some((data, self.frame_time()));
+            } else if !self.rx_fifo.is_empty() {
+                let timeout = RECEIVE_TIMEOUT_BITS * self.bit_time();
+                if self.rx_idle < timeout {
+                    self.rx_idle += 1;
+                    if self.rx_idle >= timeout {
+                        self.ris |= SSI0::RIS::RTRIS::BIT_MASK;
+                    }
+                }
+            }
+        }
+    }
+
+    fn finish_frame(&mut self, index: usize, data: u16) {
+        let bits = self.data_bits();
+        let loopback = self.cr1 & SSI0::CR1::LBM::BIT_MASK != 0;
+        let received = if self.frame_format() == FrameFormat::NationalMicrowire {
+            // Half-duplex: the control word is sent, then the response is received.
+            let control = data & 0xff;
+            trace!("SSI{index} transmitted control word {control:#04x}");
+            match &mut *self.device {
+                _ if loopback => control,
+                Some(device) => {
+                    device.transfer(control, MICROWIRE_CONTROL_BITS);
+                    device.transfer(0, bits)
+                }
+                None => 0,
+            }
+        } else {
+            let data = data & self.data_mask();
+            trace!("SSI{index} transmitted {data:#06x}");
+            match &mut *self.device {
+                _ if loopback => data,
+                Some(device) => device.transfer(data, bits),
+                None => 0,
+            }
+        } & self.data_mask();
+        trace!("SSI{index} received {received:#06x}");
+
+        if self.rx_fifo.len() < FIFO_DEPTH {
+            self.rx_fifo.push_back(received);
+            self.ris &= !SSI0::RIS::RTRIS::BIT_MASK;
+        } else {
+            // [TI-TRM] 20.7.1.7 RIS Register: the FIFO contents stay valid.
+            self.ris |= SSI0::RIS::RORRIS::BIT_MASK;
+        }
+        self.rx_idle = 0;
+
+        if !self.holds_frame_signal() || self.tx_fifo.is_empty() {
+            self.set_selected(false);
+        }
+    }
+
+    fn raw_interrupts(&self) -> u32 {
+        let mut ris = self.ris;
+        if self.tx_fifo.len() <= FIFO_HALF {
+            ris |= SSI0::RIS::TXRIS::BIT_MASK;
+        }
+        if self.rx_fifo.len() >= FIFO_HALF {
+            ris |= SSI0::RIS::RXRIS::BIT_MASK;
+        }
+        ris
+    }
+
+    fn update_interrupts(&mut self, ctx: &mut Context, index: usize) {
+        let masked = self.raw_interrupts() & self.imsc;
+        if masked & !self.raised != 0 {
+            EventFabricProxy.notify(ctx, INTERRUPT_EVENTS[index]);
+        }
+        self.raised = masked;
+    }
+
+    /// [TI-TRM] 20.4.6 DMA Operation: a single request is held while there is a frame
+    /// to receive or space to transmit, and a burst request while the FIFO is at least half
+    /// full (receive) or at most half full (transmit).
+    fn update_dma_requests(&mut self, ctx: &mut Context, index: usize) {
+        let rx = self.dmacr & SSI0::DMACR::RXDMAE::BIT_MASK != 0;
+        let tx = self.dmacr & SSI0::DMACR::TXDMAE::BIT_MASK != 0;
+        let levels = [
+            rx && !self.rx_fifo.is_empty(),
+            rx && self.rx_fifo.len() >= FIFO_HALF,
+            tx && self.tx_fifo.len() < FIFO_DEPTH,
+            tx && self.tx_fifo.len() <= FIFO_HALF,
+        ];
+        for (bit_index, (asserted, event)) in
+            levels.into_iter().zip(DMA_REQUESTS[index]).enumerate()
+        {
+            let bit = 1 << bit_index;
+            if asserted != (self.dma_requests & bit != 0) {
+                self.dma_requests ^= bit;
+                EventFabricProxy.set_dma_request(ctx, event, asserted);
+            }
+        }
+    }
+
+    fn status(&self) -> u32 {
+        let mut sr = 0;
+        if self.shifter.is_some() || !self.tx_fifo.is_empty() {
+            sr |= SSI0::SR::BSY::BIT_MASK;
+        }
+        if self.rx_fifo.len() >= FIFO_DEPTH {
+            sr |= SSI0::SR::RFF::BIT_MASK;
+        }
+        if !self.rx_fifo.is_empty() {
+            sr |= SSI0::SR::RNE::BIT_MASK;
+        }
+        if self.tx_fifo.len() < FIFO_DEPTH {
+            sr |= SSI0::SR::TNF::BIT_MASK;
+        }
+        if self.tx_fifo.is_empty() {
+            sr |= SSI0::SR::TFE::BIT_MASK;
+        }
+        sr
+    }
+
+    fn cycles_until_event(&self) -> Option<u64> {
+        if !self.is_running() || self.bit_time() == 0 {
+            return None;
+        }
+        if let Some((_, remaining)) = self.shifter {
+            Some(remaining.max(1))
+        } else if !self.tx_fifo.is_empty() {
+            Some(1)
+        } else {
+            (!self.rx_fifo.is_empty())
+                .then(|| RECEIVE_TIMEOUT_BITS * self.bit_time())
+                .filter(|&timeout| self.rx_idle < timeout)
+                .map(|timeout| timeout - self.rx_idle)
+        }
+    }
+
+    fn skip_cycles(&mut self, cycles: u64) {
+        if !self.is_running() || self.bit_time() == 0 {
+            return;
+        }
+        if let Some((_, remaining)) = self.shifter.as_mut() {
+            *remaining = remaining.saturating_sub(cycles);
+        } else if !self.rx_fifo.is_empty() {
+            self.rx_idle += cycles;
+        }
+    }
+}
+
+bridge_ports!(@slave SSIComponent => @auto_configured @slave BusDriver);
+
+#[component_impl(ssi)]
+impl AHBPortConfig for SSIComponent {
+    type Data = DataBus;
+    type Component = Self;
+    const TAG: &'static str = "SSI";
+}
+
+#[component_impl(ssi)]
+impl AHBSlavePortProxiedInput for SSIComponent {
+    fn proxy_ahb_input(ctx: &mut Context, msg: MasterToSlaveWires<Self::Data>) {
+        SSIProxy.on_new_ahb_slave_input(ctx, msg);
+    }
+}
+
+#[component_impl(ssi)]
+impl AlignedFakingHandler for SSIComponent {
+    const WRITE_MODE: WriteMode = WriteMode::Combinatorial;
+    const ALIGN: Size = Size::Word;
+    type Native = u32;
+
+    fn read_for_write_filler(
+        slave: &Self::Component,
+        ctx: &Context,
+        address: Address,
+    ) -> Self::Native {
+        slave.get_data_for_address(ctx, address)
+    }
+
+    fn pre_read(
+        _slave: &mut Self::Component,
+        ctx: &mut Context,
+        address: Address,
+    ) -> WaitstatesOrErr {
+        Self::warn_if_gated(ctx, address);
+        Ok(0)
+    }
+
+    fn read(slave: &mut Self::Component, ctx: &mut Context, address: Address) -> Self::Native {
+        let (index, offset) = Self::decode_address(address);
+        let data = if offset == SSI0::DR::OFFSET {
+            let data = slave.modules[index].read_data();
+            slave.update_lines(ctx, address);
+            data
+        } else {
+            slave.get_data_for_address(ctx, address)
+        };
+        trace!(
+            "read {address:?} (\"{}\") = {data:#x}",
+            ctx.display_named_address(address)
+        );
+        data
+    }
+
+    fn pre_write(
+        _slave: &mut Self::Component,
+        ctx: &mut Context,
+        address: Address,
+    ) -> WaitstatesOrErr {
+        Self::warn_if_gated(ctx, address);
+        Ok(0)
+    }
+
+    fn write(slave: &mut Self::Component, ctx: &mut Context, address: Address, data: Self::Native) {
+        trace!(
+            "write {address:?} (\"{}\") {data:#x}",
+            ctx.display_named_address(address)
+        );
+        slave.set_data_for_address(ctx, address, data);
+        slave.update_lines(ctx, address);
+    }
+}
+
+#[component_impl(ssi)]
+impl SkippableClockTreeNode for SSIComponent {
+    fn max_cycles_to_skip(
+        comp: &mut Self::Component,
+        ctx: &mut Context,
+        _parent: Self::IdSpace,
+        _extra: &mut Self::Extra,
+    ) -> u64 {
+        if !comp.can_be_disabled_now() {
+            0
+        } else if let Some(cycles) = comp.cycles_until_event(ctx) {
+            // -1 because the events happen only in ticks
+            cycles - 1
+        } else {
+            u64::MAX
+        }
+    }
+
+    fn emulate_skipped_cycles(
+        comp: &mut Self::Component,
+        ctx: &mut Context,
+        _parent: Self::IdSpace,
+        _extra: &mut Self::Extra,
+        skipped_cycles: u64,
+    ) {
+        for (index, module) in comp.modules.iter_mut().enumerate() {
+            if Self::is_clock_enabled(ctx, index) {
+                module.skip_cycles(skipped_cycles);
+            }
+        }
+    }
+}
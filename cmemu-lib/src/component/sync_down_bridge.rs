@@ -1,4 +1,5 @@
 use cmemu_proc_macros::proxy_use;
+use serde::{Deserialize, Serialize};
 
 use crate::bridge_ports;
 use crate::common::Address;
@@ -27,7 +28,7 @@ use log::debug;
 // TODO: is it right?
 /// Subcomponent that allows HF-clocked masters to access LF-clocked slaves.
 
-#[derive(Subcomponent, TickComponent, DisableableComponent)]
+#[derive(Clone, Subcomponent, TickComponent, DisableableComponent, Serialize, Deserialize)]
 #[subcomponent_1to1]
 pub(crate) struct SyncDownBridge {
     #[subcomponent(pub(crate) DriverSC)]
@@ -51,11 +52,13 @@ pub(crate) struct SyncDownBridge {
 pub(crate) type BusDriver = SimpleSynchronousSlaveInterface<DriverSC, SyncDownBridge>;
 pub(crate) type DBusDriver = MasterDriver<DBusDriverSC, SyncDownBridge>;
 
+#[derive(Clone, Serialize, Deserialize)]
 enum DataRequest {
     Read(Address, Size),
     Write(Address, DataBus),
 }
 
+#[derive(Clone, Serialize, Deserialize)]
 enum DataResponse {
     Read(DataBus),
     Write,
@@ -319,6 +322,7 @@ where
 }
 
 // Autoderived subcomponent copied from `cargo expand` output.
+#[derive(Clone)]
 pub(crate) struct DBusDriverSC;
 #[automatically_derived]
 impl Default for DBusDriverSC {
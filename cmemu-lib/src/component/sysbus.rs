@@ -1,4 +1,5 @@
 use cmemu_proc_macros::{component_impl, handler, proxy_use};
+use serde::{Deserialize, Serialize};
 
 use crate::common::new_ahb::databus::DataBus;
 #[proxy_use]
@@ -20,9 +21,15 @@ use crate::proxy::SystemBusProxy;
 use crate::{expose_ports, make_port_struct};
 
 #[derive(
-    MainComponent, SkippableClockTreeNode, TickComponent, TickComponentExtra, DisableableComponent,
+    Clone,
+    MainComponent,
+    SkippableClockTreeNode,
+    TickComponent,
+    TickComponentExtra,
+    DisableableComponent,
 )]
 #[skippable_if_disableable]
+#[derive(Serialize, Deserialize)]
 pub(crate) struct SystemBusComponent {
     #[subcomponent(Interconnect)]
     interconnect: Interconnect,
@@ -73,6 +80,32 @@ impl SystemBusComponent {
         <CoreSPort as AHBSlavePortInput>::on_ahb_input(self, ctx, msg);
     }
 
+    #[handler]
+    pub fn on_new_ahb_dma_input(
+        &mut self,
+        ctx: &mut Context,
+        msg: MasterToSlaveWires<<SystemBusComponent as AHBPortConfig>::Data>,
+    ) {
+        if !<Self as PowerNode>::is_active(self, ctx) {
+            debug_assert!(msg.is_inert());
+            return;
+        }
+        <DmaSPort as AHBSlavePortInput>::on_ahb_input(self, ctx, msg);
+    }
+
+    #[handler]
+    pub fn on_new_ahb_crypto_input(
+        &mut self,
+        ctx: &mut Context,
+        msg: MasterToSlaveWires<<SystemBusComponent as AHBPortConfig>::Data>,
+    ) {
+        if !<Self as PowerNode>::is_active(self, ctx) {
+            debug_assert!(msg.is_inert());
+            return;
+        }
+        <CryptoSPort as AHBSlavePortInput>::on_ahb_input(self, ctx, msg);
+    }
+
     #[handler]
     pub fn on_new_ahb_master_tagged_input(
         &mut self,
@@ -103,17 +136,28 @@ expose_ports! {
         SramMPort,
         VimsMPort,
         PrcmMPort,
-        RTCBypassMPort,
+        RTCShadowMPort,
         AonBusMPort,
         MemMockMPort,
-        UartLiteMPort,
+        SensorControllerMPort,
         GpioMPort,
         RfcMPort,
         EventFabricMPort,
+        FlashControllerMPort,
+        GptMPort,
+        UartMPort,
+        UdmaMPort,
+        SsiMPort,
+        I2cMPort,
+        WdtMPort,
+        CryptoMPort,
+        TrngMPort,
     }
 }
 // Public ports
 make_port_struct!(pub(crate) CoreSPort);
+make_port_struct!(pub(crate) DmaSPort);
+make_port_struct!(pub(crate) CryptoSPort);
 
 mod interconnect {
     //! For general overview of busses in ARM Cortex-M3 see [ARM-TDG] 6.2
@@ -142,8 +186,11 @@ mod interconnect {
     };
     use crate::common::utils::{FromMarker, SubcomponentProxyMut, iter_enum};
     use crate::component::semi_hosting;
-    use crate::component::sysbus::CoreSPort;
-    use crate::component::{aon_bus, bitband, gpio, prcm, rtc_bypass};
+    use crate::component::sysbus::{CoreSPort, CryptoSPort, DmaSPort};
+    use crate::component::{
+        aon_bus, bitband, crypto, flash_controller, gpio, gpt, i2c, prcm, rtc_shadow,
+        sensor_controller, ssi, trng, uart, udma, wdt,
+    };
     use crate::engine::{
         CombFlop, Context, DisableableComponent, Subcomponent, TickComponent, TickComponentExtra,
     };
@@ -158,14 +205,19 @@ mod interconnect {
     use cmemu_common::address_match_range;
 
     use super::{
-        AonBusMPort, EventFabricMPort, GpioMPort, MemMockMPort, PrcmMPort, RTCBypassMPort,
-        RfcMPort, SramMPort, SystemBusComponent, UartLiteMPort, VimsMPort,
+        AonBusMPort, CryptoMPort, EventFabricMPort, FlashControllerMPort, GpioMPort, GptMPort,
+        I2cMPort, MemMockMPort, PrcmMPort, RTCShadowMPort, RfcMPort, SensorControllerMPort,
+        SramMPort, SsiMPort, SystemBusComponent, TrngMPort, UartMPort, UdmaMPort, VimsMPort,
+        WdtMPort,
     };
 
     decoder_tags_and_markers!(@with_markers
     pub(crate) enum SlavePorts {
         Core,
         BitbandS,
+        // The last ones have the lowest priority in the fixed arbiters.
+        Dma,
+        CryptoDma,
     });
 
     // Bitband memory region is not continuous. The true region is used in Decoder<CoreDecoderSC>::decode().
@@ -174,6 +226,11 @@ mod interconnect {
         &bitband::PERIPH_BITBAND_ALIAS_REGION,
     );
 
+    const DMA_FLASH_RANGES: RangeUnion<Range<Address>, Range<Address>> = RangeUnion(
+        &soc::FLASHMEM::ADDR_SPACE,
+        &soc::FLASHMEM::SYSTEM_ALIAS_ADDR_SPACE,
+    );
+
     decoder_tags_and_markers!(@with_dispatcher
     pub(crate) enum MasterPorts {
         VIMS = soc::VIMS::ADDR_SPACE,
@@ -184,21 +241,30 @@ mod interconnect {
 
         // Routing is enabled only if they are implemented on this branch.
         PRCM = prcm::PRCM_ROUTE_INJECTION,
-        RTCBypass = rtc_bypass::ROUTE_INJECTION,
+        RTCShadow = rtc_shadow::RTC_SHADOW_ROUTE_INJECTION,
         AonBus = aon_bus::AON_BUS_ROUTE_INJECTION,
-        // TODO: UartLite should be under AonBus
-        UartLite = soc::AUX_RAM::ADDR_SPACE,
+        // TODO: SensorController should be under AonBus
+        SensorController = sensor_controller::SENSOR_CONTROLLER_ROUTE_INJECTION,
         BitbandM = BITBAND_REGION,
         GPIO = gpio::GPIO_ROUTE_INJECTION,
         RFC = soc::RFC::ADDR_SPACE,
         EventFabric = soc::EVENT::ADDR_SPACE,
+        FlashController = flash_controller::FLASH_CONTROLLER_ROUTE_INJECTION,
+        GPT = gpt::GPT_ROUTE_INJECTION,
+        UART0 = uart::UART_ROUTE_INJECTION,
+        UDMA0 = udma::UDMA_ROUTE_INJECTION,
+        SSI = ssi::SSI_ROUTE_INJECTION,
+        I2C0 = i2c::I2C_ROUTE_INJECTION,
+        WDT = wdt::WDT_ROUTE_INJECTION,
+        CRYPTO = crypto::CRYPTO_ROUTE_INJECTION,
+        TRNG = trng::TRNG_ROUTE_INJECTION,
         MemMock = RangeFull,
     });
 
     build_interconnect!(
         SysbusInterconnect
-        masters SlavePorts => [Core, BitbandS]
-        slaves MasterPorts => [VIMS, SRAM, SemiOsData, Semi, PRCM, RTCBypass, AonBus, UartLite, BitbandM, GPIO, MemMock, RFC, EventFabric]
+        masters SlavePorts => [Core, BitbandS, Dma, CryptoDma]
+        slaves MasterPorts => [VIMS, SRAM, SemiOsData, Semi, PRCM, RTCShadow, AonBus, SensorController, BitbandM, GPIO, MemMock, RFC, EventFabric, FlashController, GPT, UART0, UDMA0, SSI, I2C0, WDT, CRYPTO, TRNG]
         using InputStage as input, Decoder=>DPort as decoder, and OPort=>OutputStage as output
     );
 
@@ -243,6 +309,37 @@ mod interconnect {
         const REFLECTS_HREADY: bool = true;
     }
 
+    impl AhbDecoderTag for Decoder<DmaDecoderSC> {
+        type Enum = Option<MasterPorts>;
+        const REFLECTS_HREADY: bool = true;
+
+        fn decode(addr: Address) -> Self::Enum {
+            decode_dma(addr)
+        }
+    }
+
+    impl AhbDecoderTag for Decoder<CryptoDmaDecoderSC> {
+        type Enum = Option<MasterPorts>;
+        const REFLECTS_HREADY: bool = true;
+
+        fn decode(addr: Address) -> Self::Enum {
+            decode_dma(addr)
+        }
+    }
+
+    /// The decoding for the DMA masters: the uDMA and the DMA of CRYPTO.
+    fn decode_dma(addr: Address) -> Option<MasterPorts> {
+        // The bit-band aliases are a feature of the Cortex-M3 and the semihosting is ours.
+        // The DMA reaches the Flash through the VIMS, as the CPU does with its System bus.
+        address_match_range! {addr,
+            BITBAND_REGION => None,
+            semi_hosting::OS_DATA_RANGE => None,
+            semi_hosting::SEMI_HOSTING_ADDR_SPACE => None,
+            DMA_FLASH_RANGES => Some(MasterPorts::VIMS),
+            _ => Option::<MasterPorts>::decode(addr)
+        }
+    }
+
     impl AhbMultiMasterConfig for OutputStage<VIMSOutputSC> {
         type MastersEnum = SlavePorts;
         type Arbiter = FixedArbiter<SlavePorts>;
@@ -260,7 +357,7 @@ mod interconnect {
         type MastersEnum = SlavePorts;
         type Arbiter = FixedArbiter<SlavePorts>;
     }
-    impl AhbMultiMasterConfig for OutputStage<RTCBypassOutputSC> {
+    impl AhbMultiMasterConfig for OutputStage<RTCShadowOutputSC> {
         type MastersEnum = SlavePorts;
         type Arbiter = FixedArbiter<SlavePorts>;
     }
@@ -276,7 +373,7 @@ mod interconnect {
         type MastersEnum = SlavePorts;
         type Arbiter = NoArbiter<SlavePorts, Core>;
     }
-    impl AhbMultiMasterConfig for OutputStage<UartLiteOutputSC> {
+    impl AhbMultiMasterConfig for OutputStage<SensorControllerOutputSC> {
         type MastersEnum = SlavePorts;
         type Arbiter = FixedArbiter<SlavePorts>;
     }
@@ -288,6 +385,42 @@ mod interconnect {
         type MastersEnum = SlavePorts;
         type Arbiter = FixedArbiter<SlavePorts>;
     }
+    impl AhbMultiMasterConfig for OutputStage<FlashControllerOutputSC> {
+        type MastersEnum = SlavePorts;
+        type Arbiter = FixedArbiter<SlavePorts>;
+    }
+    impl AhbMultiMasterConfig for OutputStage<GPTOutputSC> {
+        type MastersEnum = SlavePorts;
+        type Arbiter = FixedArbiter<SlavePorts>;
+    }
+    impl AhbMultiMasterConfig for OutputStage<UART0OutputSC> {
+        type MastersEnum = SlavePorts;
+        type Arbiter = FixedArbiter<SlavePorts>;
+    }
+    impl AhbMultiMasterConfig for OutputStage<UDMA0OutputSC> {
+        type MastersEnum = SlavePorts;
+        type Arbiter = FixedArbiter<SlavePorts>;
+    }
+    impl AhbMultiMasterConfig for OutputStage<SSIOutputSC> {
+        type MastersEnum = SlavePorts;
+        type Arbiter = FixedArbiter<SlavePorts>;
+    }
+    impl AhbMultiMasterConfig for OutputStage<I2C0OutputSC> {
+        type MastersEnum = SlavePorts;
+        type Arbiter = FixedArbiter<SlavePorts>;
+    }
+    impl AhbMultiMasterConfig for OutputStage<WDTOutputSC> {
+        type MastersEnum = SlavePorts;
+        type Arbiter = FixedArbiter<SlavePorts>;
+    }
+    impl AhbMultiMasterConfig for OutputStage<CRYPTOOutputSC> {
+        type MastersEnum = SlavePorts;
+        type Arbiter = FixedArbiter<SlavePorts>;
+    }
+    impl AhbMultiMasterConfig for OutputStage<TRNGOutputSC> {
+        type MastersEnum = SlavePorts;
+        type Arbiter = FixedArbiter<SlavePorts>;
+    }
 
     impl AhbMultiMasterConfig for OutputStage<MemMockOutputSC> {
         type MastersEnum = SlavePorts;
@@ -317,6 +450,30 @@ mod interconnect {
         }
     }
 
+    impl AHBPortConfig for DmaSPort {
+        type Data = DataBus;
+        type Component = SystemBusComponent;
+        const TAG: &'static str = "DmaSPort";
+    }
+
+    impl AHBSlavePortProxiedInput for DmaSPort {
+        fn proxy_ahb_input(ctx: &mut Context, msg: MasterToSlaveWires<Self::Data>) {
+            SystemBusProxy.on_new_ahb_dma_input(ctx, msg);
+        }
+    }
+
+    impl AHBPortConfig for CryptoSPort {
+        type Data = DataBus;
+        type Component = SystemBusComponent;
+        const TAG: &'static str = "CryptoSPort";
+    }
+
+    impl AHBSlavePortProxiedInput for CryptoSPort {
+        fn proxy_ahb_input(ctx: &mut Context, msg: MasterToSlaveWires<Self::Data>) {
+            SystemBusProxy.on_new_ahb_crypto_input(ctx, msg);
+        }
+    }
+
     pub(crate) struct StatelessTap;
 
     bridge_ports!(@auto_configured @master LiteOutput<VIMS> => @master VimsMPort);
@@ -324,15 +481,26 @@ mod interconnect {
     bridge_ports!(@auto_configured @master LiteOutput<Semi> => @slave semi_hosting::SemiHosting);
     bridge_ports!(@auto_configured @master LiteOutput<SemiOsData> => @slave semi_hosting::OsDataMemory);
     bridge_ports!(@auto_configured @master LiteOutput<PRCM> => @master PrcmMPort);
-    bridge_ports!(@auto_configured @master LiteOutput<RTCBypass> => @master RTCBypassMPort);
+    bridge_ports!(@auto_configured @master LiteOutput<RTCShadow> => @master RTCShadowMPort);
     bridge_ports!(@auto_configured @master LiteOutput<AonBus> => @master AonBusMPort);
     bridge_ports!(@auto_configured @master LiteOutput<MemMock> => @master MemMockMPort);
-    bridge_ports!(@auto_configured @master LiteOutput<UartLite> => @master UartLiteMPort);
+    bridge_ports!(@auto_configured @master LiteOutput<SensorController> => @master SensorControllerMPort);
     bridge_ports!(@auto_configured @master LiteOutput<RFC> => @master RfcMPort);
     bridge_ports!(@auto_configured @master LiteOutput<EventFabric> => @master EventFabricMPort);
+    bridge_ports!(@auto_configured @master LiteOutput<FlashController> => @master FlashControllerMPort);
+    bridge_ports!(@auto_configured @master LiteOutput<GPT> => @master GptMPort);
+    bridge_ports!(@auto_configured @master LiteOutput<UART0> => @master UartMPort);
+    bridge_ports!(@auto_configured @master LiteOutput<UDMA0> => @master UdmaMPort);
+    bridge_ports!(@auto_configured @master LiteOutput<SSI> => @master SsiMPort);
+    bridge_ports!(@auto_configured @master LiteOutput<I2C0> => @master I2cMPort);
+    bridge_ports!(@auto_configured @master LiteOutput<WDT> => @master WdtMPort);
+    bridge_ports!(@auto_configured @master LiteOutput<CRYPTO> => @master CryptoMPort);
+    bridge_ports!(@auto_configured @master LiteOutput<TRNG> => @master TrngMPort);
     bridge_ports!(@auto_configured @master LiteOutput<GPIO> => @master GpioMPort);
     bridge_ports!(@slave CoreSPort => @auto_configured @slave StatelessTap);
     bridge_ports!(@master StatelessTap => @auto_configured @slave LiteInput<Core>);
+    bridge_ports!(@slave DmaSPort => @auto_configured @slave LiteInput<Dma>);
+    bridge_ports!(@slave CryptoSPort => @auto_configured @slave LiteInput<CryptoDma>);
 
     bridge_ports!(@auto_configured @master LiteOutput<BitbandM> => @slave bitband::Bitband);
     bridge_ports!(@master bitband::Bitband => @auto_configured @slave LiteInput<BitbandS>);
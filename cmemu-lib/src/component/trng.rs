@@ -0,0 +1,564 @@
+pub const TRNG_ROUTE_INJECTION: Range<Address> = TRNG::ADDR_SPACE;
+
+use crate::bridge_ports;
+use crate::common::new_ahb::databus::DataBus;
+use crate::common::new_ahb::ports::AHBSlavePortProxiedInput;
+#[proxy_use]
+use crate::common::new_ahb::ports::{AHBPortConfig, AHBSlavePortInput};
+#[proxy_use]
+use crate::common::new_ahb::signals::{MasterToSlaveWires, Size};
+use crate::common::new_ahb::slave_driver::WriteMode;
+use crate::common::new_ahb::slave_driver::faking_slave_driver::{
+    AlignedFakingHandler, FakingIface, WaitstatesOrErr,
+};
+use crate::component::event_fabric::EventFabricEvent;
+#[proxy_use]
+use crate::engine::Context;
+use crate::engine::{
+    DisableableComponent, MainComponent, SkippableClockTreeNode, TickComponent, TickComponentExtra,
+};
+use crate::proxy::{EventFabricProxy, TRNGProxy};
+use cc2650_constants::TRNG;
+use cmemu_common::{Address, HwRegister};
+use cmemu_proc_macros::{component_impl, handler, proxy_use};
+use fastrand::Rng;
+use log::{debug, trace, warn};
+use serde::{Deserialize, Serialize};
+use std::mem;
+use std::ops::Range;
+
+// [TI-TRM] 16 True Random Number Generator
+// The TRNG gathers entropy from 24 free-running oscillators (FROs), which are sampled every
+// `CFG0.SMPL_DIV + 1` cycles of its clock. After `CTL.TRNG_EN` is set, the first 64-bit number
+// is made of `CTL.STARTUP_CYCLES` samples, and every next one of a refill of `CFG0` samples.
+// A number waits in an internal register until the previous one is acknowledged with
+// `IRQFLAGCLR.RDY`, then it is moved to `OUT0`/`OUT1` and the next refill starts.
+// The numbers are drawn from a generator seeded with the node id, so the runs are reproducible.
+// The emulated FROs never detect a repeating pattern on their own: the alarm events are injected
+// by the host. The second alarm of an FRO shuts it down, which is logged in `ALARMSTOP`
+// and raises `IRQFLAGSTAT.SHUTDOWN_OVF` above `ALARMCNT.SHUTDOWN_THR`. No entropy is gathered
+// while all the FROs are off.
+// Not implemented: the test mode with the LFSR registers, the effect of `FRODETUNE`,
+// and the varying length of a refill: it always takes the minimal number of samples.
+
+/// Number of the FROs, see `FROEN.FRO_MASK`.
+pub(crate) const FRO_COUNT: u8 = 24;
+
+/// Cycles for which `SWRESET.RESET` reads as set after a software reset.
+const SWRESET_CYCLES: u8 = 4;
+
+/// Salt of the generator seed, so that it differs from the other generators seeded with the node id.
+const TRNG_RNG_SALT: u64 = 0x7267_1e5e;
+
+const CTL_WRITABLE_BITS: u32 = TRNG::CTL::STARTUP_CYCLES::BIT_MASK
+    | TRNG::CTL::TRNG_EN::BIT_MASK
+    | TRNG::CTL::NO_LFSR_FB::BIT_MASK
+    | TRNG::CTL::TEST_MODE::BIT_MASK;
+const FLAGS: u32 = TRNG::IRQFLAGSTAT::RDY::BIT_MASK | TRNG::IRQFLAGSTAT::SHUTDOWN_OVF::BIT_MASK;
+const ALARMCNT_THRESHOLDS: u32 =
+    TRNG::ALARMCNT::SHUTDOWN_THR::BIT_MASK | TRNG::ALARMCNT::ALARM_THR::BIT_MASK;
+
+type BusDriver = FakingIface<DriverSC, TRNGComponent>;
+
+#[derive(
+    Clone,
+    MainComponent,
+    TickComponent,
+    TickComponentExtra,
+    DisableableComponent,
+    Serialize,
+    Deserialize,
+)]
+pub(crate) struct TRNGComponent {
+    #[subcomponent(DriverSC)]
+    driver: BusDriver,
+
+    /// The source of the numbers, which survives the resets.
+    #[serde(with = "crate::common::utils::serde_with::rng")]
+    rng: Rng,
+
+    ctl: u32,
+    cfg0: u32,
+    /// Only the thresholds, as the count of the FROs shut down is derived from `alarmstop`.
+    alarmcnt: u32,
+    froen: u32,
+    frodetune: u32,
+    alarmmask: u32,
+    alarmstop: u32,
+    irqflagmask: u32,
+    /// The `RDY` and `SHUTDOWN_OVF` flags of `IRQFLAGSTAT`.
+    flags: u32,
+    out: u64,
+
+    /// The number waiting for the result registers to be acknowledged.
+    internal: Option<u64>,
+    /// Cycles until a number is gathered, while the FROs are sampled.
+    generation_left: Option<u64>,
+    /// The next number is the first one since the TRNG was enabled.
+    starting_up: bool,
+    /// The internal number is moved to the result registers on the next cycle.
+    move_pending: bool,
+    /// Cycles until the software reset is done.
+    reset_left: u8,
+}
+
+#[component_impl(trng)]
+impl TRNGComponent {
+    pub(crate) fn new() -> Self {
+        Self {
+            driver: Default::default(),
+
+            rng: Rng::with_seed(TRNG_RNG_SALT),
+
+            ctl: TRNG::CTL::RESET_VALUE,
+            cfg0: TRNG::CFG0::RESET_VALUE,
+            alarmcnt: TRNG::ALARMCNT::RESET_VALUE,
+            froen: TRNG::FROEN::RESET_VALUE,
+            frodetune: TRNG::FRODETUNE::RESET_VALUE,
+            alarmmask: TRNG::ALARMMASK::RESET_VALUE,
+            alarmstop: TRNG::ALARMSTOP::RESET_VALUE,
+            irqflagmask: TRNG::IRQFLAGMASK::RESET_VALUE,
+            flags: 0,
+            out: 0,
+
+            internal: None,
+            generation_left: None,
+            starting_up: false,
+            move_pending: false,
+            reset_left: 0,
+        }
+    }
+
+    pub(crate) fn tick(&mut self, ctx: &mut Context) {
+        // The number is moved a cycle after the acknowledgement.
+        if mem::take(&mut self.move_pending) {
+            self.move_to_output(ctx);
+        }
+        self.reset_left = self.reset_left.saturating_sub(1);
+
+        BusDriver::run_driver(self, ctx);
+
+        self.advance(ctx, 1);
+    }
+
+    pub(crate) fn tock(&mut self, ctx: &mut Context) {
+        BusDriver::tock(self, ctx);
+    }
+
+    #[handler]
+    pub(crate) fn on_new_ahb_slave_input(
+        &mut self,
+        ctx: &mut Context,
+        msg: MasterToSlaveWires<<TRNGComponent as AHBPortConfig>::Data>,
+    ) {
+        <Self as AHBSlavePortInput>::on_ahb_input(self, ctx, msg);
+    }
+
+    pub(crate) fn set_node_id(&mut self, id: u64) {
+        self.rng.seed(id ^ TRNG_RNG_SALT);
+    }
+
+    /// Takes over the generator of the `old` instance, so that the numbers do not repeat
+    /// after a reset.
+    pub(crate) fn take_generator(&mut self, old: &Self) {
+        self.rng = old.rng.clone();
+    }
+
+    /// An alarm event of the FRO `fro`, that is a repeating pattern detected in its samples.
+    pub(crate) fn fro_alarm(&mut self, ctx: &mut Context, fro: u8) {
+        let bit = 1 << fro;
+        if !self.is_enabled() || self.running_fros() & bit == 0 {
+            debug!("TRNG: ignoring an alarm of the FRO {fro}, which is not running");
+        } else if self.alarmmask & bit == 0 {
+            debug!("TRNG: an alarm of the FRO {fro}");
+            self.alarmmask |= bit;
+        } else {
+            debug!("TRNG: a repeated alarm of the FRO {fro}, shutting it down");
+            self.alarmstop |= bit;
+            self.check_fros(ctx);
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.ctl & TRNG::CTL::TRNG_EN::BIT_MASK != 0
+    }
+
+    fn running_fros(&self) -> u32 {
+        self.froen & !self.alarmstop
+    }
+
+    fn irq_line(&self) -> bool {
+        self.flags & self.irqflagmask != 0
+    }
+
+    /// Advances the sampling of the FROs by `cycles`.
+    fn advance(&mut self, ctx: &mut Context, cycles: u64) {
+        let Some(left) = self.generation_left else {
+            return;
+        };
+        if cycles < left {
+            self.generation_left = Some(left - cycles);
+            return;
+        }
+        self.generation_left = None;
+        self.starting_up = false;
+        trace!("TRNG: a random number is gathered");
+        self.internal = Some(self.rng.u64(..));
+        if self.flags & TRNG::IRQFLAGSTAT::RDY::BIT_MASK == 0 {
+            self.move_to_output(ctx);
+        }
+    }
+
+    /// [TI-TRM-I] 16.7.1.3 IRQFLAGSTAT Register: the internal number goes to the result registers.
+    fn move_to_output(&mut self, ctx: &mut Context) {
+        let Some(number) = self.internal.take() else {
+            return;
+        };
+        self.out = number;
+        self.raise(ctx, TRNG::IRQFLAGSTAT::RDY::BIT_MASK);
+        self.start_generation();
+    }
+
+    /// Starts gathering the next number, if there is room for it and the FROs run.
+    fn start_generation(&mut self) {
+        if !self.is_enabled()
+            || self.internal.is_some()
+            || self.generation_left.is_some()
+            || self.running_fros() == 0
+        {
+            return;
+        }
+        let samples = if self.starting_up {
+            Some(self.startup_samples())
+        } else {
+            self.refill_samples()
+        };
+        let Some(samples) = samples else {
+            warn!("TRNG: no number is created with CFG0.MIN_REFILL_CYCLES above the maximum");
+            return;
+        };
+        let cfg0 = TRNG::CFG0::Register::from(self.cfg0).bitfields();
+        let cycles = samples * (u64::from(cfg0.SMPL_DIV()) + 1);
+        trace!("TRNG: gathering a number of {samples} samples in {cycles} cycles");
+        self.generation_left = Some(cycles);
+    }
+
+    /// [TI-TRM-I] 16.7.1.6 CTL Register: `STARTUP_CYCLES` in units of 2^8 samples,
+    /// with 0 meaning 2^24.
+    fn startup_samples(&self) -> u64 {
+        match TRNG::CTL::Register::from(self.ctl)
+            .bitfields()
+            .STARTUP_CYCLES()
+        {
+            0 => 1 << 24,
+            cycles => u64::from(cycles) << 8,
+        }
+    }
+
+    /// [TI-TRM-I] 16.7.1.7 CFG0 Register: the minimal number of samples of a refill,
+    /// if it is not above the maximal one.
+    fn refill_samples(&self) -> Option<u64> {
+        let cfg0 = TRNG::CFG0::Register::from(self.cfg0).bitfields();
+        let max = match cfg0.MAX_REFILL_CYCLES() {
+            0 => 1 << 24,
+            cycles => u64::from(cycles) << 8,
+        };
+        match cfg0.MIN_REFILL_CYCLES() {
+            0 => Some(max),
+            cycles => {
+                let min = u64::from(cycles) << 6;
+                (min <= max).then_some(min)
+            }
+        }
+    }
+
+    /// Applies a change of the FROs: raises `SHUTDOWN_OVF` and stops or resumes the sampling.
+    fn check_fros(&mut self, ctx: &mut Context) {
+        self.froen &= !self.alarmstop;
+        let threshold = TRNG::ALARMCNT::Register::from(self.alarmcnt)
+            .bitfields()
+            .SHUTDOWN_THR();
+        if self.alarmstop.count_ones() > u32::from(threshold) {
+            self.raise(ctx, TRNG::IRQFLAGSTAT::SHUTDOWN_OVF::BIT_MASK);
+        }
+        if self.running_fros() == 0 {
+            if self.generation_left.take().is_some() {
+                warn!("TRNG: all the FROs are off, no entropy is gathered");
+            }
+        } else {
+            self.start_generation();
+        }
+    }
+
+    /// [TI-TRM-I] 16.7.1.4 IRQFLAGMASK Register: the masked flags make the interrupt.
+    fn raise(&mut self, ctx: &mut Context, flags: u32) {
+        let was_raised = self.irq_line();
+        self.flags |= flags;
+        self.notify_if_raised(ctx, was_raised);
+    }
+
+    fn notify_if_raised(&mut self, ctx: &mut Context, was_raised: bool) {
+        if !was_raised && self.irq_line() {
+            trace!("TRNG: raising the interrupt");
+            EventFabricProxy.notify(ctx, EventFabricEvent::TRNG_IRQ);
+        }
+    }
+
+    fn write_ctl(&mut self, data: u32) {
+        let was_enabled = self.is_enabled();
+        let mut ctl = data & CTL_WRITABLE_BITS;
+        if was_enabled {
+            // `STARTUP_CYCLES` is kept while the TRNG is enabled.
+            ctl = (ctl & !TRNG::CTL::STARTUP_CYCLES::BIT_MASK)
+                | (self.ctl & TRNG::CTL::STARTUP_CYCLES::BIT_MASK);
+        }
+        if ctl & (TRNG::CTL::TEST_MODE::BIT_MASK | TRNG::CTL::NO_LFSR_FB::BIT_MASK) != 0 {
+            warn!("TRNG: the test mode is not implemented");
+        }
+        self.ctl = ctl;
+        match (was_enabled, self.is_enabled()) {
+            (false, true) => {
+                debug!("TRNG: enabled");
+                self.starting_up = true;
+                self.start_generation();
+            }
+            (true, false) => {
+                // All the logic goes to the idle state immediately.
+                debug!("TRNG: disabled");
+                self.internal = None;
+                self.generation_left = None;
+                self.move_pending = false;
+            }
+            _ => {}
+        }
+    }
+
+    /// [TI-TRM-I] 16.7.1.21 SWRESET Register: resets all the registers, while the generator
+    /// and the bus interface are kept.
+    fn soft_reset(&mut self) {
+        debug!("TRNG: software reset");
+        let driver = mem::take(&mut self.driver);
+        let rng = mem::replace(&mut self.rng, Rng::with_seed(0));
+        *self = Self {
+            driver,
+            rng,
+            reset_left: SWRESET_CYCLES,
+            ..Self::new()
+        };
+    }
+
+    /// Cycles until the next change of the state.
+    fn cycles_until_event(&self) -> Option<u64> {
+        if self.move_pending || self.reset_left > 0 {
+            Some(1)
+        } else {
+            self.generation_left
+        }
+    }
+
+    fn need_clock(&self) -> bool {
+        self.is_enabled()
+            && (self.generation_left.is_some() || self.ctl & TRNG::CTL::TEST_MODE::BIT_MASK != 0)
+    }
+
+    fn get_data_for_address(&self, ctx: &Context, addr: Address) -> u32 {
+        use TRNG as T;
+        match addr {
+            #[allow(clippy::cast_possible_truncation, reason = "the lower word")]
+            T::OUT0::ADDR => self.out as u32,
+            T::OUT1::ADDR => (self.out >> 32) as u32,
+            T::IRQFLAGSTAT::ADDR => {
+                let need_clock = if self.need_clock() {
+                    T::IRQFLAGSTAT::NEED_CLOCK::BIT_MASK
+                } else {
+                    0
+                };
+                self.flags | need_clock
+            }
+            T::IRQFLAGMASK::ADDR => self.irqflagmask,
+            // The write-only registers, and the LFSR, which is visible only in the test mode.
+            T::IRQFLAGCLR::ADDR
+            | T::IRQSET::ADDR
+            | T::LFSR0::ADDR
+            | T::LFSR1::ADDR
+            | T::LFSR2::ADDR => 0,
+            T::CTL::ADDR => self.ctl,
+            T::CFG0::ADDR => self.cfg0,
+            T::ALARMCNT::ADDR => {
+                (self.alarmstop.count_ones() << T::ALARMCNT::SHUTDOWN_CNT::BIT_RANGE.start())
+                    | self.alarmcnt
+            }
+            T::FROEN::ADDR => self.froen,
+            T::FRODETUNE::ADDR => self.frodetune,
+            T::ALARMMASK::ADDR => self.alarmmask,
+            T::ALARMSTOP::ADDR => self.alarmstop,
+            T::HWOPT::ADDR => T::HWOPT::RESET_VALUE,
+            T::HWVER0::ADDR => T::HWVER0::RESET_VALUE,
+            T::HWVER1::ADDR => T::HWVER1::RESET_VALUE,
+            T::IRQSTATMASK::ADDR => self.flags & self.irqflagmask,
+            T::IRQSTAT::ADDR => u32::from(self.flags != 0),
+            T::SWRESET::ADDR => u32::from(self.reset_left > 0),
+            _ => unimplemented!(
+                "Requested TRNG data read from address {:?}: {}",
+                addr,
+                ctx.display_named_address(addr)
+            ),
+        }
+    }
+
+    fn set_data_for_address(&mut self, ctx: &mut Context, addr: Address, data: u32) {
+        use TRNG as T;
+        match addr {
+            T::IRQFLAGMASK::ADDR => {
+                let was_raised = self.irq_line();
+                self.irqflagmask = data & FLAGS;
+                self.notify_if_raised(ctx, was_raised);
+            }
+            T::IRQFLAGCLR::ADDR => {
+                self.flags &= !(data & FLAGS);
+                if data & T::IRQFLAGCLR::RDY::BIT_MASK != 0 && self.internal.is_some() {
+                    self.move_pending = true;
+                }
+            }
+            T::CTL::ADDR => self.write_ctl(data),
+            T::CFG0::ADDR if self.is_enabled() => warn!(
+                "Ignoring a write {data:#x} to {} while the TRNG is enabled.",
+                ctx.display_named_address(addr)
+            ),
+            T::CFG0::ADDR => self.cfg0 = T::CFG0::Register::from(data).read(),
+            T::ALARMCNT::ADDR => {
+                self.alarmcnt = data & ALARMCNT_THRESHOLDS;
+                self.check_fros(ctx);
+            }
+            T::FROEN::ADDR => {
+                self.froen = data & T::FROEN::FRO_MASK::BIT_MASK;
+                self.check_fros(ctx);
+            }
+            T::FRODETUNE::ADDR => self.frodetune = data & T::FRODETUNE::FRO_MASK::BIT_MASK,
+            T::ALARMMASK::ADDR => self.alarmmask = data & T::ALARMMASK::FRO_MASK::BIT_MASK,
+            T::ALARMSTOP::ADDR => {
+                self.alarmstop = data & T::ALARMSTOP::FRO_FLAGS::BIT_MASK;
+                self.check_fros(ctx);
+            }
+            T::SWRESET::ADDR => {
+                if data & T::SWRESET::RESET::BIT_MASK != 0 {
+                    self.soft_reset();
+                }
+            }
+            T::LFSR0::ADDR | T::LFSR1::ADDR | T::LFSR2::ADDR | T::IRQSET::ADDR => warn!(
+                "Ignoring a write {data:#x} to {}, the test mode is not implemented",
+                ctx.display_named_address(addr)
+            ),
+            T::OUT0::ADDR
+            | T::OUT1::ADDR
+            | T::IRQFLAGSTAT::ADDR
+            | T::HWOPT::ADDR
+            | T::HWVER0::ADDR
+            | T::HWVER1::ADDR
+            | T::IRQSTATMASK::ADDR
+            | T::IRQSTAT::ADDR => warn!(
+                "Ignoring a write {data:#x} to the read-only {}",
+                ctx.display_named_address(addr)
+            ),
+            _ => unimplemented!(
+                "Requested TRNG data write {:#x} to address {:?}: {}",
+                data,
+                addr,
+                ctx.display_named_address(addr)
+            ),
+        }
+    }
+}
+
+bridge_ports!(@slave TRNGComponent => @auto_configured @slave BusDriver);
+
+#[component_impl(trng)]
+impl AHBPortConfig for TRNGComponent {
+    type Data = DataBus;
+    type Component = Self;
+    const TAG: &'static str = "TRNG";
+}
+
+#[component_impl(trng)]
+impl AHBSlavePortProxiedInput for TRNGComponent {
+    fn proxy_ahb_input(ctx: &mut Context, msg: MasterToSlaveWires<Self::Data>) {
+        TRNGProxy.on_new_ahb_slave_input(ctx, msg);
+    }
+}
+
+#[component_impl(trng)]
+impl AlignedFakingHandler for TRNGComponent {
+    const WRITE_MODE: WriteMode = WriteMode::Combinatorial;
+    const ALIGN: Size = Size::Word;
+    type Native = u32;
+
+    fn read_for_write_filler(
+        slave: &Self::Component,
+        ctx: &Context,
+        address: Address,
+    ) -> Self::Native {
+        slave.get_data_for_address(ctx, address)
+    }
+
+    fn pre_read(
+        _slave: &mut Self::Component,
+        _ctx: &mut Context,
+        _address: Address,
+    ) -> WaitstatesOrErr {
+        Ok(0)
+    }
+
+    fn read(slave: &mut Self::Component, ctx: &mut Context, address: Address) -> Self::Native {
+        let data = slave.get_data_for_address(ctx, address);
+        trace!(
+            "read {address:?} (\"{}\") = {data:#x}",
+            ctx.display_named_address(address)
+        );
+        data
+    }
+
+    fn pre_write(
+        _slave: &mut Self::Component,
+        _ctx: &mut Context,
+        _address: Address,
+    ) -> WaitstatesOrErr {
+        Ok(0)
+    }
+
+    fn write(slave: &mut Self::Component, ctx: &mut Context, address: Address, data: Self::Native) {
+        trace!(
+            "write {address:?} (\"{}\") {data:#x}",
+            ctx.display_named_address(address)
+        );
+        slave.set_data_for_address(ctx, address, data);
+    }
+}
+
+#[component_impl(trng)]
+impl SkippableClockTreeNode for TRNGComponent {
+    fn max_cycles_to_skip(
+        comp: &mut Self::Component,
+        _ctx: &mut Context,
+        _parent: Self::IdSpace,
+        _extra: &mut Self::Extra,
+    ) -> u64 {
+        if !comp.can_be_disabled_now() {
+            0
+        } else if let Some(cycles) = comp.cycles_until_event() {
+            // -1 because the numbers are gathered only on ticks
+            cycles.saturating_sub(1)
+        } else {
+            u64::MAX
+        }
+    }
+
+    fn emulate_skipped_cycles(
+        comp: &mut Self::Component,
+        ctx: &mut Context,
+        _parent: Self::IdSpace,
+        _extra: &mut Self::Extra,
+        skipped_cycles: u64,
+    ) {
+        comp.advance(ctx, skipped_cycles);
+    }
+}
@@ -0,0 +1,603 @@
+pub const UART_ROUTE_INJECTION: Range<Address> = UART0::ADDR_SPACE;
+
+use crate::bridge_ports;
+use crate::build_data::{ClockTreeNodes, EnergyEntity};
+use crate::common::new_ahb::databus::DataBus;
+use crate::common::new_ahb::ports::AHBSlavePortProxiedInput;
+#[proxy_use]
+use crate::common::new_ahb::ports::{AHBPortConfig, AHBSlavePortInput};
+#[proxy_use]
+use crate::common::new_ahb::signals::{MasterToSlaveWires, Size};
+use crate::common::new_ahb::slave_driver::WriteMode;
+use crate::common::new_ahb::slave_driver::faking_slave_driver::{
+    AlignedFakingHandler, FakingIface, WaitstatesOrErr,
+};
+use crate::common::utils::HostHandle;
+use crate::component::event_fabric::EventFabricEvent;
+#[proxy_use]
+use crate::engine::Context;
+use crate::engine::{
+    DisableableComponent, MainComponent, SkippableClockTreeNode, TickComponent, TickComponentExtra,
+};
+use crate::proxy::{EventFabricProxy, UartProxy};
+use cc2650_constants::UART0;
+use cmemu_common::Address;
+use cmemu_proc_macros::{component_impl, handler, proxy_use};
+use log::{trace, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::ops::Range;
+use std::panic::UnwindSafe;
+
+// [TI-TRM] 19 Universal Asynchronous Receivers and Transmitters (UARTS)
+// The UART0 is an ARM PL011-like module clocked with the MCU clock through the UART clock gate.
+// The serial line is connected to the emulator-attached `UARTInterface`, if any:
+// the transmitted frames are forwarded to it and it is polled for a received byte
+// once per frame time.
+// Not implemented: the break condition (`LCRH.BRK`), framing and parity errors (the host
+// stream is error-free), and the CTS modem interrupt (the host is always clear to send).
+
+/// `UARTInterface` connects the serial line of UART0 to the host.
+pub trait UARTInterface {
+    /// Called with each frame transmitted by the UART.
+    fn send_byte(&mut self, byte: u8);
+    /// Called once per frame time of the enabled receiver to check for a byte sent by the host.
+    fn receive_byte(&mut self) -> Option<u8>;
+}
+
+pub type UARTInterfaceImpl = Box<dyn UARTInterface + Send + Sync + UnwindSafe>;
+
+/// [TI-TRM] 19.4.6 FIFO Operation: both FIFOs are 32 entries deep.
+const FIFO_DEPTH: usize = 32;
+
+/// The timing is tracked in fractions of a cycle, as the baud-rate divisor has
+/// a 6-bit fractional part.
+const SUBCYCLES: u64 = 64;
+
+/// [TI-TRM] 19.4.7 Interrupts: the receive time-out is 32 bit periods.
+const RECEIVE_TIMEOUT_BITS: u64 = 32;
+
+/// The DMA request lines of the receiver and the transmitter.
+const DMA_REQUESTS: [EventFabricEvent; 4] = [
+    EventFabricEvent::UART0_RX_DMASREQ,
+    EventFabricEvent::UART0_RX_DMABREQ,
+    EventFabricEvent::UART0_TX_DMASREQ,
+    EventFabricEvent::UART0_TX_DMABREQ,
+];
+
+/// `DR` bits above the data byte: `FE`, `PE`, `BE` and `OE`.
+const DR_OE: u16 = 1 << 11;
+
+#[derive(
+    Clone,
+    MainComponent,
+    TickComponent,
+    TickComponentExtra,
+    DisableableComponent,
+    Serialize,
+    Deserialize,
+)]
+pub(crate) struct UARTComponent {
+    #[subcomponent(DriverSC)]
+    driver: BusDriver,
+
+    ctl: u32,
+    lcrh: u32,
+    ibrd: u32,
+    fbrd: u32,
+    ifls: u32,
+    imsc: u32,
+    ris: u32,
+    rsr: u32,
+    dmactl: u32,
+    /// The DMA request lines, indexed as `DMA_REQUESTS`.
+    dma_requests: u8,
+
+    tx_fifo: VecDeque<u8>,
+    /// Received bytes with the error bits of `DR`.
+    rx_fifo: VecDeque<u16>,
+    /// The frame being transmitted and its remaining time in subcycles.
+    tx_shifter: Option<(u8, u64)>,
+    /// Remaining time in subcycles until the receiver polls the host for the next frame.
+    rx_remaining: u64,
+    /// Time in subcycles since the last activity of the receive FIFO.
+    rx_idle: u64,
+
+    interface_impl: HostHandle<UARTInterfaceImpl>,
+}
+type BusDriver = FakingIface<DriverSC, UARTComponent>;
+
+#[component_impl(uart)]
+impl UARTComponent {
+    pub(crate) fn new() -> Self {
+        Self {
+            driver: Default::default(),
+
+            ctl: UART0::CTL::RESET_VALUE,
+            lcrh: UART0::LCRH::RESET_VALUE,
+            ibrd: UART0::IBRD::RESET_VALUE,
+            fbrd: UART0::FBRD::RESET_VALUE,
+            ifls: UART0::IFLS::RESET_VALUE,
+            imsc: UART0::IMSC::RESET_VALUE,
+            ris: 0,
+            rsr: UART0::RSR::RESET_VALUE,
+            dmactl: UART0::DMACTL::RESET_VALUE,
+            dma_requests: 0,
+
+            tx_fifo: VecDeque::with_capacity(FIFO_DEPTH),
+            rx_fifo: VecDeque::with_capacity(FIFO_DEPTH),
+            tx_shifter: None,
+            rx_remaining: 0,
+            rx_idle: 0,
+
+            interface_impl: HostHandle::new(None),
+        }
+    }
+
+    pub(crate) fn tick(&mut self, ctx: &mut Context) {
+        BusDriver::run_driver(self, ctx);
+
+        if self.is_enabled(UART0::CTL::UARTEN::BIT_MASK) && Self::is_clock_enabled(ctx) {
+            self.run_transmitter(ctx);
+            self.run_receiver(ctx);
+            self.update_dma_requests(ctx);
+        }
+    }
+
+    pub(crate) fn tock(&mut self, ctx: &mut Context) {
+        BusDriver::tock(self, ctx);
+    }
+
+    #[handler]
+    pub(crate) fn on_new_ahb_slave_input(
+        &mut self,
+        ctx: &mut Context,
+        msg: MasterToSlaveWires<<UARTComponent as AHBPortConfig>::Data>,
+    ) {
+        <Self as AHBSlavePortInput>::on_ahb_input(self, ctx, msg);
+    }
+
+    pub(crate) fn set_interface(&mut self, interface: Option<UARTInterfaceImpl>) {
+        *self.interface_impl = interface;
+    }
+
+    pub(crate) fn take_interface(&mut self) -> Option<UARTInterfaceImpl> {
+        self.interface_impl.take()
+    }
+
+    fn is_clock_enabled(ctx: &Context) -> bool {
+        ctx.get_energy_state_of(EnergyEntity::ClockTree(ClockTreeNodes::UartGate))
+            .is_active()
+    }
+
+    fn is_enabled(&self, ctl_mask: u32) -> bool {
+        self.ctl & ctl_mask == ctl_mask
+    }
+
+    fn fifo_depth(&self) -> usize {
+        if self.lcrh & UART0::LCRH::FEN::BIT_MASK == 0 {
+            1
+        } else {
+            FIFO_DEPTH
+        }
+    }
+
+    /// [TI-TRM] 19.8.1.9 IFLS Register: the levels are in eighths of the FIFO depth.
+    fn fifo_level(&self, select: u32) -> usize {
+        const EIGHTHS: [usize; 5] = [1, 2, 4, 6, 7];
+        if self.lcrh & UART0::LCRH::FEN::BIT_MASK == 0 {
+            return 0;
+        }
+        FIFO_DEPTH * EIGHTHS.get(select as usize).copied().unwrap_or(7) / 8
+    }
+
+    /// The transmit interrupt is asserted when the FIFO drops to this level.
+    fn tx_trigger_level(&self) -> usize {
+        self.fifo_level(self.ifls & UART0::IFLS::TXSEL::BIT_MASK)
+    }
+
+    /// The receive interrupt is asserted when the FIFO fills up to this level.
+    fn rx_trigger_level(&self) -> usize {
+        let select = (self.ifls & UART0::IFLS::RXSEL::BIT_MASK) >> 3;
+        self.fifo_level(select).max(1)
+    }
+
+    /// [TI-TRM] 19.4.2 Baud-Rate Generation: `BRD = IBRD + FBRD / 64` and a bit lasts
+    /// `16 * BRD` UART clocks.
+    fn bit_time(&self) -> u64 {
+        16 * (u64::from(self.ibrd) * SUBCYCLES + u64::from(self.fbrd))
+    }
+
+    /// The start bit, data bits, the optional parity bit and the stop bits.
+    fn frame_time(&self) -> u64 {
+        let data_bits = 5 + u64::from((self.lcrh & UART0::LCRH::WLEN::BIT_MASK) >> 5);
+        let parity_bits = u64::from(self.lcrh & UART0::LCRH::PEN::BIT_MASK != 0);
+        let stop_bits = 1 + u64::from(self.lcrh & UART0::LCRH::STP2::BIT_MASK != 0);
+        (1 + data_bits + parity_bits + stop_bits) * self.bit_time()
+    }
+
+    fn data_mask(&self) -> u8 {
+        let data_bits = 5 + ((self.lcrh & UART0::LCRH::WLEN::BIT_MASK) >> 5);
+        u8::MAX >> (8 - data_bits)
+    }
+
+    fn run_transmitter(&mut self, ctx: &mut Context) {
+        if !self.is_enabled(UART0::CTL::TXE::BIT_MASK) {
+            return;
+        }
+        if let Some((byte, remaining)) = self.tx_shifter.as_mut() {
+            *remaining = remaining.saturating_sub(SUBCYCLES);
+            if *remaining == 0 {
+                let byte = *byte & self.data_mask();
+                self.tx_shifter = None;
+                self.transmit(ctx, byte);
+            }
+        }
+        if self.tx_shifter.is_none()
+            && self.bit_time() != 0
+            && let Some(byte) = self.tx_fifo.pop_front()
+        {
+            self.tx_shifter = Some((byte, self.frame_time()));
+            if self.tx_fifo.len() == self.tx_trigger_level() {
+                self.raise_interrupts(ctx, UART0::RIS::TXRIS::BIT_MASK);
+            }
+        }
+    }
+
+    fn transmit(&mut self, ctx: &mut Context, byte: u8) {
+        trace!("UART0 transmitted {byte:#04x} {:?}", char::from(byte));
+        if self.is_enabled(UART0::CTL::LBE::BIT_MASK) {
+            self.receive(ctx, byte);
+        } else if let Some(interface) = &mut *self.interface_impl {
+            interface.send_byte(byte);
+        }
+    }
+
+    fn run_receiver(&mut self, ctx: &mut Context) {
+        if !self.rx_fifo.is_empty() && self.imsc & UART0::IMSC::RTIM::BIT_MASK != 0 {
+            let timeout = RECEIVE_TIMEOUT_BITS * self.bit_time();
+            if self.rx_idle < timeout {
+                self.rx_idle += SUBCYCLES;
+                if self.rx_idle >= timeout {
+                    self.raise_interrupts(ctx, UART0::RIS::RTRIS::BIT_MASK);
+                }
+            }
+        }
+
+        if !self.is_polling_host() {
+            return;
+        }
+        self.rx_remaining = self.rx_remaining.saturating_sub(SUBCYCLES);
+        if self.rx_remaining == 0 {
+            self.rx_remaining = self.frame_time();
+            // [TI-TRM] 19.4.8 Flow control: with RTS enabled, the host is stopped
+            // when the receive FIFO is full.
+            let stopped = self.is_enabled(UART0::CTL::RTSEN::BIT_MASK)
+                && self.rx_fifo.len() >= self.fifo_depth();
+            let received = (!stopped)
+                .then(|| self.interface_impl.as_mut()?.receive_byte())
+                .flatten();
+            if let Some(byte) = received {
+                self.receive(ctx, byte);
+            }
+        }
+    }
+
+    fn is_polling_host(&self) -> bool {
+        self.is_enabled(UART0::CTL::RXE::BIT_MASK)
+            && !self.is_enabled(UART0::CTL::LBE::BIT_MASK)
+            && self.interface_impl.is_some()
+            && self.bit_time() != 0
+    }
+
+    fn receive(&mut self, ctx: &mut Context, byte: u8) {
+        if !self.is_enabled(UART0::CTL::RXE::BIT_MASK) {
+            return;
+        }
+        let byte = byte & self.data_mask();
+        trace!("UART0 received {byte:#04x} {:?}", char::from(byte));
+        if self.rx_fifo.len() >= self.fifo_depth() {
+            // [TI-TRM] 19.8.1.2 RSR Register: the FIFO contents remain valid
+            // and the next character is lost.
+            self.rsr |= UART0::RSR::OE::BIT_MASK;
+            if let Some(last) = self.rx_fifo.back_mut() {
+                *last |= DR_OE;
+            }
+            self.raise_interrupts(ctx, UART0::RIS::OERIS::BIT_MASK);
+            return;
+        }
+        self.rx_fifo.push_back(u16::from(byte));
+        self.rx_idle = 0;
+        if self.rx_fifo.len() == self.rx_trigger_level() {
+            self.raise_interrupts(ctx, UART0::RIS::RXRIS::BIT_MASK);
+        }
+    }
+
+    fn raise_interrupts(&mut self, ctx: &mut Context, bits: u32) {
+        let newly_raised = bits & !self.ris;
+        self.ris |= bits;
+        if newly_raised & self.imsc != 0 {
+            EventFabricProxy.notify(ctx, EventFabricEvent::UART0_COMB);
+        }
+    }
+
+    /// [TI-TRM] 19.4.10 DMA Operation: a single request is held while there is a character
+    /// to receive or space to transmit, and a burst request while the FIFO level is past
+    /// the trigger level. There are no burst requests with the FIFOs disabled.
+    fn update_dma_requests(&mut self, ctx: &mut Context) {
+        let rx = self.dmactl & UART0::DMACTL::RXDMAE::BIT_MASK != 0;
+        let tx = self.dmactl & UART0::DMACTL::TXDMAE::BIT_MASK != 0;
+        let fifos = self.fifo_depth() > 1;
+        let levels = [
+            rx && !self.rx_fifo.is_empty(),
+            rx && fifos && self.rx_fifo.len() >= self.rx_trigger_level(),
+            tx && self.tx_fifo.len() < self.fifo_depth(),
+            tx && fifos && self.tx_fifo.len() <= self.tx_trigger_level(),
+        ];
+        for (index, (asserted, event)) in levels.into_iter().zip(DMA_REQUESTS).enumerate() {
+            let bit = 1 << index;
+            if asserted != (self.dma_requests & bit != 0) {
+                self.dma_requests ^= bit;
+                EventFabricProxy.set_dma_request(ctx, event, asserted);
+            }
+        }
+    }
+
+    fn flags(&self) -> u32 {
+        let mut fr = UART0::FR::CTS::BIT_MASK;
+        if self.tx_shifter.is_some() || !self.tx_fifo.is_empty() {
+            fr |= UART0::FR::BUSY::BIT_MASK;
+        }
+        if self.rx_fifo.is_empty() {
+            fr |= UART0::FR::RXFE::BIT_MASK;
+        }
+        if self.rx_fifo.len() >= self.fifo_depth() {
+            fr |= UART0::FR::RXFF::BIT_MASK;
+        }
+        if self.tx_fifo.is_empty() {
+            fr |= UART0::FR::TXFE::BIT_MASK;
+        }
+        if self.tx_fifo.len() >= self.fifo_depth() {
+            fr |= UART0::FR::TXFF::BIT_MASK;
+        }
+        fr
+    }
+
+    fn cycles_until_event(&self) -> Option<u64> {
+        if !self.is_enabled(UART0::CTL::UARTEN::BIT_MASK) {
+            return None;
+        }
+        let to_cycles = |subcycles: u64| subcycles.div_ceil(SUBCYCLES).max(1);
+        let transmitter = if !self.is_enabled(UART0::CTL::TXE::BIT_MASK) {
+            None
+        } else if let Some((_, remaining)) = self.tx_shifter {
+            Some(to_cycles(remaining))
+        } else {
+            (!self.tx_fifo.is_empty() && self.bit_time() != 0).then_some(1)
+        };
+        let receiver = self.is_polling_host().then(|| to_cycles(self.rx_remaining));
+        let timeout = (!self.rx_fifo.is_empty() && self.imsc & UART0::IMSC::RTIM::BIT_MASK != 0)
+            .then(|| RECEIVE_TIMEOUT_BITS * self.bit_time())
+            .filter(|&timeout| self.rx_idle < timeout)
+            .map(|timeout| to_cycles(timeout - self.rx_idle));
+        [transmitter, receiver, timeout].into_iter().flatten().min()
+    }
+
+    fn skip_cycles(&mut self, cycles: u64) {
+        let subcycles = cycles * SUBCYCLES;
+        if let Some((_, remaining)) = self.tx_shifter.as_mut() {
+            *remaining = remaining.saturating_sub(subcycles);
+        }
+        if self.is_polling_host() {
+            self.rx_remaining = self.rx_remaining.saturating_sub(subcycles);
+        }
+        if !self.rx_fifo.is_empty() && self.imsc & UART0::IMSC::RTIM::BIT_MASK != 0 {
+            self.rx_idle += subcycles;
+        }
+    }
+
+    fn get_data_for_address(&self, ctx: &Context, addr: Address) -> u32 {
+        match addr {
+            // Reading `DR` pops the receive FIFO, what is done by the bus handler.
+            UART0::DR::ADDR | UART0::ICR::ADDR => 0,
+            UART0::RSR::ADDR => self.rsr,
+            UART0::FR::ADDR => self.flags(),
+            UART0::IBRD::ADDR => self.ibrd,
+            UART0::FBRD::ADDR => self.fbrd,
+            UART0::LCRH::ADDR => self.lcrh,
+            UART0::CTL::ADDR => self.ctl,
+            UART0::IFLS::ADDR => self.ifls,
+            UART0::IMSC::ADDR => self.imsc,
+            UART0::RIS::ADDR => self.ris,
+            UART0::MIS::ADDR => self.ris & self.imsc,
+            UART0::DMACTL::ADDR => self.dmactl,
+            _ => unimplemented!(
+                "Requested UART0 data read from address {:?}: {}",
+                addr,
+                ctx.display_named_address(addr)
+            ),
+        }
+    }
+
+    fn read_data(&mut self) -> u32 {
+        let Some(data) = self.rx_fifo.pop_front() else {
+            return 0;
+        };
+        self.rx_idle = 0;
+        if self.rx_fifo.len() < self.rx_trigger_level() {
+            self.ris &= !UART0::RIS::RXRIS::BIT_MASK;
+        }
+        if self.rx_fifo.is_empty() {
+            self.ris &= !UART0::RIS::RTRIS::BIT_MASK;
+        }
+        u32::from(data)
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn set_data_for_address(&mut self, ctx: &mut Context, addr: Address, data: u32) {
+        match addr {
+            UART0::DR::ADDR => {
+                if self.tx_fifo.len() < self.fifo_depth() {
+                    self.tx_fifo.push_back(data as u8);
+                } else {
+                    warn!("UART0 transmit FIFO overflow, {data:#04x} is lost.");
+                }
+                if self.tx_fifo.len() > self.tx_trigger_level() {
+                    self.ris &= !UART0::RIS::TXRIS::BIT_MASK;
+                }
+            }
+            UART0::ECR::ADDR => self.rsr = 0,
+            UART0::IBRD::ADDR => self.ibrd = data & UART0::IBRD::DIVINT::BIT_MASK,
+            UART0::FBRD::ADDR => self.fbrd = data & UART0::FBRD::DIVFRAC::BIT_MASK,
+            UART0::LCRH::ADDR => self.lcrh = data,
+            UART0::CTL::ADDR => {
+                let was_enabled = self.is_enabled(UART0::CTL::UARTEN::BIT_MASK);
+                self.ctl = data;
+                if !was_enabled && self.is_enabled(UART0::CTL::UARTEN::BIT_MASK) {
+                    if self.bit_time() == 0 {
+                        warn!("UART0 enabled with a zero baud-rate divisor.");
+                    }
+                    self.rx_remaining = self.frame_time();
+                }
+            }
+            UART0::IFLS::ADDR => self.ifls = data,
+            UART0::IMSC::ADDR => {
+                let newly_unmasked = data & !self.imsc;
+                self.imsc = data;
+                if self.ris & newly_unmasked != 0 {
+                    EventFabricProxy.notify(ctx, EventFabricEvent::UART0_COMB);
+                }
+            }
+            UART0::ICR::ADDR => self.ris &= !data,
+            UART0::DMACTL::ADDR => self.dmactl = data,
+            UART0::FR::ADDR | UART0::RIS::ADDR | UART0::MIS::ADDR => {
+                warn!(
+                    "Ignoring write {:#x} to read-only {}",
+                    data,
+                    ctx.display_named_address(addr)
+                );
+            }
+            _ => unimplemented!(
+                "Requested UART0 data write {:#x} to address {:?}: {}",
+                data,
+                addr,
+                ctx.display_named_address(addr)
+            ),
+        }
+    }
+
+    fn warn_if_gated(ctx: &Context, addr: Address) {
+        if !Self::is_clock_enabled(ctx) {
+            warn!(
+                "Accessing {} while the UART clock is gated.",
+                ctx.display_named_address(addr)
+            );
+        }
+    }
+}
+
+bridge_ports!(@slave UARTComponent => @auto_configured @slave BusDriver);
+
+#[component_impl(uart)]
+impl AHBPortConfig for UARTComponent {
+    type Data = DataBus;
+    type Component = Self;
+    const TAG: &'static str = "UART0";
+}
+
+#[component_impl(uart)]
+impl AHBSlavePortProxiedInput for UARTComponent {
+    fn proxy_ahb_input(ctx: &mut Context, msg: MasterToSlaveWires<Self::Data>) {
+        UartProxy.on_new_ahb_slave_input(ctx, msg);
+    }
+}
+
+#[component_impl(uart)]
+impl AlignedFakingHandler for UARTComponent {
+    const WRITE_MODE: WriteMode = WriteMode::Combinatorial;
+    const ALIGN: Size = Size::Word;
+    type Native = u32;
+
+    fn read_for_write_filler(
+        slave: &Self::Component,
+        ctx: &Context,
+        address: Address,
+    ) -> Self::Native {
+        slave.get_data_for_address(ctx, address)
+    }
+
+    fn pre_read(
+        _slave: &mut Self::Component,
+        ctx: &mut Context,
+        address: Address,
+    ) -> WaitstatesOrErr {
+        Self::warn_if_gated(ctx, address);
+        Ok(0)
+    }
+
+    fn read(slave: &mut Self::Component, ctx: &mut Context, address: Address) -> Self::Native {
+        let data = if address == UART0::DR::ADDR {
+            let data = slave.read_data();
+            slave.update_dma_requests(ctx);
+            data
+        } else {
+            slave.get_data_for_address(ctx, address)
+        };
+        trace!(
+            "read {address:?} (\"{}\") = {data:#x}",
+            ctx.display_named_address(address)
+        );
+        data
+    }
+
+    fn pre_write(
+        _slave: &mut Self::Component,
+        ctx: &mut Context,
+        address: Address,
+    ) -> WaitstatesOrErr {
+        Self::warn_if_gated(ctx, address);
+        Ok(0)
+    }
+
+    fn write(slave: &mut Self::Component, ctx: &mut Context, address: Address, data: Self::Native) {
+        trace!(
+            "write {address:?} (\"{}\") {data:#x}",
+            ctx.display_named_address(address)
+        );
+        slave.set_data_for_address(ctx, address, data);
+        slave.update_dma_requests(ctx);
+    }
+}
+
+#[component_impl(uart)]
+impl SkippableClockTreeNode for UARTComponent {
+    fn max_cycles_to_skip(
+        comp: &mut Self::Component,
+        ctx: &mut Context,
+        _parent: Self::IdSpace,
+        _extra: &mut Self::Extra,
+    ) -> u64 {
+        if !comp.can_be_disabled_now() {
+            0
+        } else if !Self::is_clock_enabled(ctx) {
+            u64::MAX
+        } else if let Some(cycles) = comp.cycles_until_event() {
+            // -1 because the events happen only in ticks
+            cycles - 1
+        } else {
+            u64::MAX
+        }
+    }
+
+    fn emulate_skipped_cycles(
+        comp: &mut Self::Component,
+        ctx: &mut Context,
+        _parent: Self::IdSpace,
+        _extra: &mut Self::Extra,
+        skipped_cycles: u64,
+    ) {
+        if Self::is_clock_enabled(ctx) && comp.is_enabled(UART0::CTL::UARTEN::BIT_MASK) {
+            comp.skip_cycles(skipped_cycles);
+        }
+    }
+}
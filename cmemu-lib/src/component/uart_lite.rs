@@ -5,82 +5,49 @@
 /// The main program interfaces the SC program by writing pairs of bytes
 /// to the ring buffer in SC RAM and advancing the head index.
 ///
-/// This module handles the reads and writes to SC RAM under the assumption
-/// that it is running the UART Lite program, forwarding written bytes to
-/// the emulator-attached `UARTLiteInterface`, if any.
+/// When a `UARTLiteInterface` is attached, the Sensor Controller assumes that
+/// the AUX RAM holds the UART Lite program. Instead of executing it, the bytes written
+/// to the ring buffer are forwarded to the interface by this module.
 ///
 /// Original C Source: `scif_uart_emulator.h` and `scif_uart_emulator.c`
 /// in `platforms/boards/cc26xxbased/cherry-v5/scif_uart/` in WHIP6-PUB
-use std::ops::Range;
+use serde::{Deserialize, Serialize};
 use std::panic::UnwindSafe;
 
 use log::trace;
 
-use cmemu_proc_macros::{component_impl, handler, proxy_use};
-
-use crate::bridge_ports;
 use crate::common::Address;
-use crate::common::new_ahb::databus::DataBus;
-#[proxy_use]
-use crate::common::new_ahb::ports::{AHBPortConfig, AHBSlavePortInput, AHBSlavePortProxiedInput};
-#[proxy_use]
-use crate::common::new_ahb::signals::{MasterToSlaveWires, Size};
-use crate::common::new_memory::{InvalidAddressError, Memory, MemoryConfiguration};
-#[proxy_use]
-use crate::engine::Context;
-use crate::engine::{
-    DisableableComponent, MainComponent, SkippableClockTreeNode, TickComponent, TickComponentExtra,
-};
-use crate::proxy::UartLiteProxy;
+use crate::common::utils::HostHandle;
 
 /// `UARTLiteInterface` defines behavior of UART Lite.
 pub trait UARTLiteInterface {
     fn send_byte(&mut self, byte: u8);
 }
 
-impl MemoryConfiguration for AUXMemory {
-    const IS_WRITABLE: bool = true;
-    const ADDRESS_SPACE: Range<Address> = cc2650_constants::AUX_RAM::ADDR_SPACE;
-    const BUS_WIDTH: Size = Size::Word;
-    const WAIT_STATES: u8 = 0;
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct UARTLite {
+    interface_impl: HostHandle<Box<dyn UARTLiteInterface + Send + Sync + UnwindSafe>>,
 }
-type AUXMemory = Memory<MemorySC>;
-
-#[derive(MainComponent, SkippableClockTreeNode, TickComponent, TickComponentExtra)]
-#[skippable_if_disableable]
-pub(crate) struct UARTLiteComponent {
-    #[subcomponent(MemorySC)]
-    memory: AUXMemory,
 
-    interface_impl: Option<Box<dyn UARTLiteInterface + Send + Sync + UnwindSafe>>,
-}
-
-#[component_impl(uart_lite)]
-impl UARTLiteComponent {
+impl UARTLite {
     pub(crate) fn new() -> Self {
         Self {
-            memory: AUXMemory::new_zeroed(),
-            interface_impl: None,
+            interface_impl: HostHandle::new(None),
         }
     }
 
-    pub(crate) fn tick(&mut self, ctx: &mut Context) {
-        AUXMemory::run_driver(self, ctx);
+    /// Whether an interface is attached, and thus the AUX RAM is assumed to run UART Lite.
+    pub(crate) fn is_attached(&self) -> bool {
+        self.interface_impl.is_some()
     }
 
-    pub(crate) fn tock(&mut self, ctx: &mut Context) {
-        AUXMemory::tock(self, ctx);
-
-        let head = self
-            .read_memory_u16(SCIF::TASK_DATA::STATE::HEAD_ADDR)
-            .unwrap();
-        let tail = self
-            .read_memory_u16(SCIF::TASK_DATA::STATE::TAIL_ADDR)
-            .unwrap();
+    /// Sends the pair of bytes at the tail of the ring buffer in `ram` (the AUX RAM words),
+    /// if there is any.
+    pub(crate) fn drain(&mut self, ram: &mut [u16]) {
+        let head = ram[word_index(SCIF::TASK_DATA::STATE::HEAD_ADDR)];
+        let tail = ram[word_index(SCIF::TASK_DATA::STATE::TAIL_ADDR)];
         if head != tail {
-            let mut data = [0xAAu8; 2];
-            self.read_memory(SCIF::TASK_DATA::BUFF::nth_slot_addr(tail), &mut data)
-                .unwrap();
+            let data = ram[word_index(SCIF::TASK_DATA::BUFF::nth_slot_addr(tail))].to_le_bytes();
             let as_char = char::from;
             trace!(
                 "Data printed on UART: {:02X?}, {:?} {:?}",
@@ -88,82 +55,34 @@ impl UARTLiteComponent {
                 as_char(data[0]),
                 as_char(data[1])
             );
-            if let Some(i) = &mut self.interface_impl {
+            if let Some(i) = &mut *self.interface_impl {
                 i.send_byte(data[0]);
                 i.send_byte(data[1]);
             }
             let new_tail = (tail + 1) % SCIF::TASK_DATA::BUFF::SLOT_COUNT;
             // SAFETY: (wrt write-write hazards) node program code is only supposed to write to BUFF and HEAD_ADDR while SC execution is enabled
             // `scif_uart_emulator.c` just treats head as a u16 field behind a volatile pointer, so it should stick to 16-bit writes to just head
-            self.write_memory(SCIF::TASK_DATA::STATE::TAIL_ADDR, &new_tail.to_le_bytes())
-                .unwrap();
+            ram[word_index(SCIF::TASK_DATA::STATE::TAIL_ADDR)] = new_tail;
         }
     }
 
-    #[handler]
-    pub(crate) fn on_new_ahb_slave_input(
-        &mut self,
-        ctx: &mut Context,
-        msg: MasterToSlaveWires<<UARTLiteComponent as AHBPortConfig>::Data>,
-    ) {
-        <Self as AHBSlavePortInput>::on_ahb_input(self, ctx, msg);
-    }
-
     pub(crate) fn set_interface(
         &mut self,
         interface: Option<Box<dyn UARTLiteInterface + Send + Sync + UnwindSafe>>,
     ) {
-        self.interface_impl = interface;
+        *self.interface_impl = interface;
     }
 
-    pub(crate) fn write_memory(
+    pub(crate) fn take_interface(
         &mut self,
-        start_address: Address,
-        memory: &[u8],
-    ) -> Result<(), InvalidAddressError> {
-        self.memory
-            .write_memory(start_address, memory)
-            .or(Err(InvalidAddressError))
-    }
-
-    pub(crate) fn read_memory(
-        &self,
-        start_address: Address,
-        memory: &mut [u8],
-    ) -> Result<(), InvalidAddressError> {
-        self.memory
-            .read_memory(start_address, memory)
-            .or(Err(InvalidAddressError))
-    }
-
-    pub(crate) fn read_memory_u16(&self, address: Address) -> Result<u16, InvalidAddressError> {
-        let mut buf = [0xAAu8; 2];
-        self.read_memory(address, &mut buf)?;
-        Ok(u16::from_le_bytes(buf))
-    }
-}
-
-bridge_ports!(@slave UARTLiteComponent => @auto_configured @slave AUXMemory);
-
-#[component_impl(uart_lite)]
-impl AHBPortConfig for UARTLiteComponent {
-    type Data = DataBus;
-    type Component = Self;
-    const TAG: &'static str = "UARTLite";
-}
-
-#[component_impl(uart_lite)]
-impl AHBSlavePortProxiedInput for UARTLiteComponent {
-    fn proxy_ahb_input(ctx: &mut Context, msg: MasterToSlaveWires<Self::Data>) {
-        UartLiteProxy.on_new_ahb_slave_input(ctx, msg);
+    ) -> Option<Box<dyn UARTLiteInterface + Send + Sync + UnwindSafe>> {
+        self.interface_impl.take()
     }
 }
 
-#[component_impl(uart_lite)]
-impl DisableableComponent for UARTLiteComponent {
-    fn can_be_disabled_now(&self) -> bool {
-        true
-    }
+/// Index of the 16-bit word of the AUX RAM at `addr`.
+fn word_index(addr: Address) -> usize {
+    (addr.offset_from(cc2650_constants::AUX_RAM::ADDR) / 2) as usize
 }
 
 /// Printf-related data and addresses
@@ -0,0 +1,836 @@
+pub const UDMA_ROUTE_INJECTION: Range<Address> = UDMA0::ADDR_SPACE;
+
+use crate::bridge_ports;
+use crate::build_data::{ClockTreeNodes, EnergyEntity};
+use crate::common::new_ahb::databus::DataBus;
+use crate::common::new_ahb::master_driver::MasterDriver;
+use crate::common::new_ahb::master_driver::stateless_helpers::SimplerHandler as MasterDriverSimplerHandler;
+#[proxy_use]
+use crate::common::new_ahb::ports::{AHBMasterPortInput, AHBPortConfig, AHBSlavePortInput};
+use crate::common::new_ahb::ports::{AHBMasterPortProxiedInput, AHBSlavePortProxiedInput};
+#[proxy_use]
+use crate::common::new_ahb::signals::{MasterToSlaveWires, Size, SlaveToMasterWires};
+use crate::common::new_ahb::signals::{Protection, TransferMeta};
+use crate::common::new_ahb::slave_driver::WriteMode;
+use crate::common::new_ahb::slave_driver::faking_slave_driver::{
+    AlignedFakingHandler, FakingIface, WaitstatesOrErr,
+};
+use crate::component::event_fabric::EventFabricEvent;
+#[proxy_use(proxy_only)]
+use crate::component::udma::{RequestKind, SysbusMPort};
+#[proxy_use]
+use crate::engine::Context;
+use crate::engine::{
+    DisableableComponent, MainComponent, SkippableClockTreeNode, TickComponent, TickComponentExtra,
+};
+use crate::make_port_struct;
+use crate::proxy::{EventFabricProxy, UDMAProxy};
+use cc2650_constants::UDMA0;
+use cmemu_common::Address;
+use cmemu_proc_macros::{component_impl, handler, proxy_use};
+use log::{debug, trace, warn};
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+// [TI-TRM] 12 Micro Direct Memory Access (µDMA)
+// The uDMA is an ARM PL230-like controller with 32 channels, clocked by the MCU clock through
+// the DMA clock gate of the PRCM. It is a master of the system bus with a lower priority than
+// the CPU. The channel control structures are placed in the system memory at `CTRL.BASEPTR`
+// (primary) and `ALTCTRL` (alternate). Each structure is read and written back by the
+// controller for every arbitration cycle, and every data item is read and then written with
+// separate, non-pipelined bus transfers.
+// The peripheral requests are routed by the event fabric (`EVENT:UDMACHnSSEL/BSEL`).
+// The flash is accessed through the system bus port of VIMS, where the fetches of the core
+// have a fixed priority.
+// Not implemented: `NXTUSEBURST`, waiting for the requests to clear (`WAITONREQ`),
+// and propagating the done state of the peripheral channels to the peripherals.
+
+/// [TI-TRM] 12.2.5 Channel Configuration: a control structure consists of the source end pointer,
+/// the destination end pointer and the control word.
+const SRC_END_OFFSET: u32 = 0x0;
+const DST_END_OFFSET: u32 = 0x4;
+const CHCTL_OFFSET: u32 = 0x8;
+const STRUCTURE_SIZE: u32 = 0x10;
+/// The alternate control structures follow the primary ones.
+const ALTERNATE_OFFSET: u32 = UDMA0::ALTCTRL::RESET_VALUE;
+
+/// Kind of a peripheral uDMA request, as selected by `EVENT:UDMACHnSSEL` and `EVENT:UDMACHnBSEL`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum RequestKind {
+    Single,
+    Burst,
+}
+
+/// [TI-TRM] 12.3.3 Transfer Modes, the `XFERMODE` field of the control word.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TransferMode {
+    Stop,
+    Basic,
+    Auto,
+    PingPong,
+    MemScatterGather,
+    AltMemScatterGather,
+    PeriphScatterGather,
+    AltPeriphScatterGather,
+}
+
+/// The channel control word, `DMA_CHCTL` in TI driverlib.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+struct ChannelControl(u32);
+
+impl ChannelControl {
+    const XFERSIZE_SHIFT: u32 = 4;
+    const XFERSIZE_MASK: u32 = 0x3ff << Self::XFERSIZE_SHIFT;
+    const XFERMODE_MASK: u32 = 0x7;
+
+    fn mode(self) -> TransferMode {
+        match self.0 & Self::XFERMODE_MASK {
+            0 => TransferMode::Stop,
+            1 => TransferMode::Basic,
+            2 => TransferMode::Auto,
+            3 => TransferMode::PingPong,
+            4 => TransferMode::MemScatterGather,
+            5 => TransferMode::AltMemScatterGather,
+            6 => TransferMode::PeriphScatterGather,
+            _ => TransferMode::AltPeriphScatterGather,
+        }
+    }
+
+    /// `XFERSIZE` holds the number of items minus one.
+    fn items_left(self) -> u32 {
+        ((self.0 & Self::XFERSIZE_MASK) >> Self::XFERSIZE_SHIFT) + 1
+    }
+
+    /// `ARBSIZE` is the base-2 logarithm of the items transferred before rearbitration.
+    fn arbitration_size(self) -> u32 {
+        1 << ((self.0 >> 14) & 0xf).min(10)
+    }
+
+    fn src_prot(self) -> u32 {
+        (self.0 >> 18) & 0x7
+    }
+
+    fn dst_prot(self) -> u32 {
+        (self.0 >> 21) & 0x7
+    }
+
+    fn src_size(self) -> Size {
+        Self::size((self.0 >> 24) & 0x3)
+    }
+
+    fn src_inc(self) -> u32 {
+        (self.0 >> 26) & 0x3
+    }
+
+    fn dst_size(self) -> Size {
+        Self::size((self.0 >> 28) & 0x3)
+    }
+
+    fn dst_inc(self) -> u32 {
+        (self.0 >> 30) & 0x3
+    }
+
+    fn size(bits: u32) -> Size {
+        match bits {
+            0 => Size::Byte,
+            1 => Size::Halfword,
+            _ => Size::Word,
+        }
+    }
+
+    /// The address of an item counted from the end, as the increment of 3 means no increment.
+    fn item_address(end: u32, items_left: u32, inc: u32) -> Address {
+        let back = if inc == 3 { 0 } else { (items_left - 1) << inc };
+        Address::from(end.wrapping_sub(back))
+    }
+
+    /// The control word written back after an arbitration cycle. When all the items are
+    /// transferred, the mode is set to stop.
+    fn written_back(self, items_left: u32) -> Self {
+        let mut word = self.0 & !Self::XFERSIZE_MASK;
+        if items_left == 0 {
+            word &= !Self::XFERMODE_MASK;
+        } else {
+            word |= (items_left - 1) << Self::XFERSIZE_SHIFT;
+        }
+        Self(word)
+    }
+}
+
+/// [TI-TRM] 12.5.1.1 STATUS Register: the states of the control state machine.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+enum State {
+    Idle,
+    ReadChannelControl,
+    ReadSrcEnd,
+    ReadDstEnd,
+    ReadSrcData,
+    WriteDstData,
+    WriteChannelControl,
+}
+
+impl State {
+    fn status(self) -> u32 {
+        match self {
+            Self::Idle => 0x0,
+            Self::ReadChannelControl => 0x1,
+            Self::ReadSrcEnd => 0x2,
+            Self::ReadDstEnd => 0x3,
+            Self::ReadSrcData => 0x4,
+            Self::WriteDstData => 0x5,
+            Self::WriteChannelControl => 0x7,
+        }
+    }
+}
+
+/// An arbitration cycle of the channel being served.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Cycle {
+    channel: usize,
+    /// Address of the control structure.
+    structure: u32,
+    /// Items transferred in this arbitration cycle.
+    items_to_arbitration: u32,
+    control: ChannelControl,
+    items_left: u32,
+    src_end: u32,
+    dst_end: u32,
+    data: DataBus,
+}
+
+type BusDriver = FakingIface<DriverSC, UDMAComponent>;
+type SysbusDriver = MasterDriver<SysbusDriverSC, SysbusMPort>;
+
+#[derive(
+    Clone,
+    MainComponent,
+    SkippableClockTreeNode,
+    TickComponent,
+    TickComponentExtra,
+    Serialize,
+    Deserialize,
+)]
+#[skippable_if_disableable]
+pub(crate) struct UDMAComponent {
+    #[subcomponent(DriverSC)]
+    driver: BusDriver,
+
+    #[subcomponent(pub(crate) SysbusDriverSC)]
+    sysbus_driver: SysbusDriver,
+
+    cfg: u32,
+    ctrl: u32,
+    useburst: u32,
+    reqmask: u32,
+    enabled: u32,
+    alternate: u32,
+    priority: u32,
+    error: u32,
+    reqdone: u32,
+    donemask: u32,
+
+    /// Requests of `SOFTREQ` and pulsed events, consumed by an arbitration cycle.
+    burst_pulses: u32,
+    single_pulses: u32,
+    /// Request lines held by the peripherals.
+    burst_levels: u32,
+    single_levels: u32,
+    /// Channels continuing without a request, as in the auto-request and scatter-gather modes.
+    continuing: u32,
+
+    state: State,
+    cycle: Option<Cycle>,
+    /// A bus transfer of the current state is in progress.
+    in_flight: bool,
+}
+
+#[component_impl(udma)]
+impl UDMAComponent {
+    pub(crate) fn new() -> Self {
+        Self {
+            driver: Default::default(),
+            sysbus_driver: SysbusDriver::new(),
+
+            cfg: UDMA0::CFG::RESET_VALUE,
+            ctrl: UDMA0::CTRL::RESET_VALUE,
+            useburst: UDMA0::SETBURST::RESET_VALUE,
+            reqmask: UDMA0::SETREQMASK::RESET_VALUE,
+            enabled: UDMA0::SETCHANNELEN::RESET_VALUE,
+            alternate: UDMA0::SETCHNLPRIALT::RESET_VALUE,
+            priority: UDMA0::SETCHNLPRIORITY::RESET_VALUE,
+            error: UDMA0::ERROR::RESET_VALUE,
+            reqdone: UDMA0::REQDONE::RESET_VALUE,
+            donemask: UDMA0::DONEMASK::RESET_VALUE,
+
+            burst_pulses: 0,
+            single_pulses: 0,
+            burst_levels: 0,
+            single_levels: 0,
+            continuing: 0,
+
+            state: State::Idle,
+            cycle: None,
+            in_flight: false,
+        }
+    }
+
+    pub(crate) fn tick(&mut self, ctx: &mut Context) {
+        BusDriver::run_driver(self, ctx);
+        SysbusDriver::run_driver(self, ctx);
+
+        // The state machine is stopped while the clock is gated.
+        if self.in_flight || !self.sysbus_driver.is_free() || !Self::is_clock_enabled(ctx) {
+            return;
+        }
+        if self.state == State::Idle {
+            self.start_cycle();
+        }
+        self.issue_transfer();
+    }
+
+    pub(crate) fn tock(&mut self, ctx: &mut Context) {
+        BusDriver::tock(self, ctx);
+        SysbusDriver::tock(self, ctx);
+    }
+
+    #[handler]
+    pub(crate) fn on_new_ahb_slave_input(
+        &mut self,
+        ctx: &mut Context,
+        msg: MasterToSlaveWires<<UDMAComponent as AHBPortConfig>::Data>,
+    ) {
+        <Self as AHBSlavePortInput>::on_ahb_input(self, ctx, msg);
+    }
+
+    #[handler]
+    pub(crate) fn on_new_ahb_master_input(
+        &mut self,
+        ctx: &mut Context,
+        msg: SlaveToMasterWires<<SysbusMPort as AHBPortConfig>::Data>,
+    ) {
+        <SysbusMPort as AHBMasterPortInput>::on_ahb_input(self, ctx, msg);
+    }
+
+    /// A pulsed request on the `channels`, latched until served.
+    #[handler]
+    pub(crate) fn request(&mut self, _ctx: &mut Context, channels: u32, kind: RequestKind) {
+        let channels = channels & !self.reqmask;
+        match kind {
+            RequestKind::Single => self.single_pulses |= channels,
+            RequestKind::Burst => self.burst_pulses |= channels,
+        }
+    }
+
+    /// A change of the request lines of the `channels` held by a peripheral.
+    #[handler]
+    pub(crate) fn set_request_level(
+        &mut self,
+        _ctx: &mut Context,
+        channels: u32,
+        kind: RequestKind,
+        asserted: bool,
+    ) {
+        let levels = match kind {
+            RequestKind::Single => &mut self.single_levels,
+            RequestKind::Burst => &mut self.burst_levels,
+        };
+        if asserted {
+            *levels |= channels;
+        } else {
+            *levels &= !channels;
+        }
+    }
+
+    fn is_clock_enabled(ctx: &Context) -> bool {
+        ctx.get_energy_state_of(EnergyEntity::ClockTree(ClockTreeNodes::DmaGate))
+            .is_active()
+    }
+
+    fn is_master_enabled(&self) -> bool {
+        self.cfg & UDMA0::CFG::MASTERENABLE::BIT_MASK != 0
+    }
+
+    /// Channels with a burst request and with only a single request.
+    /// `REQMASK` masks the peripheral requests, and `USEBURST` ignores the single ones.
+    fn requests(&self) -> (u32, u32) {
+        let burst = (self.burst_pulses | self.continuing | (self.burst_levels & !self.reqmask))
+            & self.enabled;
+        let single = (self.single_pulses | self.single_levels)
+            & !self.reqmask
+            & !self.useburst
+            & self.enabled
+            & !burst;
+        (burst, single)
+    }
+
+    /// [TI-TRM] 12.2.3 Priority: the channels with the high priority come first,
+    /// then the lower channel number wins.
+    fn next_channel(&self) -> Option<(usize, RequestKind)> {
+        if !self.is_master_enabled() {
+            return None;
+        }
+        let (burst, single) = self.requests();
+        let requested = burst | single;
+        let high = requested & self.priority;
+        let candidates = if high != 0 { high } else { requested };
+        if candidates == 0 {
+            return None;
+        }
+        let channel = candidates.trailing_zeros() as usize;
+        let kind = if burst & (1 << channel) != 0 {
+            RequestKind::Burst
+        } else {
+            RequestKind::Single
+        };
+        Some((channel, kind))
+    }
+
+    fn start_cycle(&mut self) {
+        let Some((channel, kind)) = self.next_channel() else {
+            return;
+        };
+        let bit = 1 << channel;
+        self.burst_pulses &= !bit;
+        self.single_pulses &= !bit;
+        self.continuing &= !bit;
+
+        #[allow(clippy::cast_possible_truncation)]
+        let mut structure =
+            (self.ctrl & UDMA0::CTRL::BASEPTR::BIT_MASK) + STRUCTURE_SIZE * channel as u32;
+        if self.alternate & bit != 0 {
+            structure += ALTERNATE_OFFSET;
+        }
+        trace!("uDMA channel {channel} arbitrated with a {kind:?} request");
+        self.cycle = Some(Cycle {
+            channel,
+            structure,
+            items_to_arbitration: match kind {
+                RequestKind::Single => 1,
+                RequestKind::Burst => u32::MAX,
+            },
+            control: ChannelControl(0),
+            items_left: 0,
+            src_end: 0,
+            dst_end: 0,
+            data: DataBus::HighZ,
+        });
+        self.state = State::ReadChannelControl;
+    }
+
+    /// [TI-TRM] 12.5.1.2 CFG Register: `PRTOCTRL` drives `HPROT[3:1]` of the control accesses.
+    fn control_prot(&self) -> Protection {
+        Self::protection((self.cfg & UDMA0::CFG::PRTOCTRL::BIT_MASK) >> 5)
+    }
+
+    /// The privileged, bufferable and cacheable bits of `HPROT[3:1]`.
+    fn protection(bits: u32) -> Protection {
+        Protection::new_data()
+            .with_privileged(bits & 0b001 != 0)
+            .with_bufferable(bits & 0b010 != 0)
+            .with_cacheable(bits & 0b100 != 0)
+    }
+
+    fn issue_transfer(&mut self) {
+        let Some(cycle) = &self.cycle else {
+            return;
+        };
+        let control = cycle.control;
+        let control_prot = self.control_prot();
+        let driver = &mut self.sysbus_driver;
+        let structure = Address::from(cycle.structure);
+        let issued = match self.state {
+            State::Idle => return,
+            State::ReadChannelControl => driver.try_read_data_with_prot(
+                structure.offset(CHCTL_OFFSET),
+                Size::Word,
+                control_prot,
+                (),
+            ),
+            State::ReadSrcEnd => driver.try_read_data_with_prot(
+                structure.offset(SRC_END_OFFSET),
+                Size::Word,
+                control_prot,
+                (),
+            ),
+            State::ReadDstEnd => driver.try_read_data_with_prot(
+                structure.offset(DST_END_OFFSET),
+                Size::Word,
+                control_prot,
+                (),
+            ),
+            State::ReadSrcData => driver.try_read_data_with_prot(
+                ChannelControl::item_address(cycle.src_end, cycle.items_left, control.src_inc()),
+                control.src_size(),
+                Self::protection(control.src_prot()),
+                (),
+            ),
+            State::WriteDstData => driver.try_write_latched_data_with_prot(
+                ChannelControl::item_address(cycle.dst_end, cycle.items_left, control.dst_inc()),
+                cycle.data.size(),
+                cycle.data.clone(),
+                Self::protection(control.dst_prot()),
+                (),
+            ),
+            State::WriteChannelControl => driver.try_write_latched_data_with_prot(
+                structure.offset(CHCTL_OFFSET),
+                Size::Word,
+                DataBus::Word(control.written_back(cycle.items_left).0),
+                control_prot,
+                (),
+            ),
+        };
+        debug_assert!(issued, "uDMA transfer not accepted by a free driver");
+        self.in_flight = issued;
+    }
+
+    fn transfer_done(&mut self, ctx: &mut Context, data: Option<DataBus>) {
+        self.in_flight = false;
+        let cycle = self.cycle.as_mut().expect("uDMA transfer without a cycle");
+        let word = || data.clone().map_or(0, u32::from);
+        self.state = match self.state {
+            State::ReadChannelControl => {
+                cycle.control = ChannelControl(word());
+                if cycle.control.mode() == TransferMode::Stop {
+                    // [TI-TRM] 12.3.3.1 Stop Mode: the channel is disabled without a transfer.
+                    debug!(
+                        "uDMA channel {} has a stopped control structure",
+                        cycle.channel
+                    );
+                    let channel = cycle.channel;
+                    self.disable_channel(channel);
+                    self.cycle = None;
+                    State::Idle
+                } else {
+                    if cycle.control.src_size() != cycle.control.dst_size() {
+                        warn!(
+                            "uDMA channel {} has different source and destination sizes",
+                            cycle.channel
+                        );
+                    }
+                    cycle.items_left = cycle.control.items_left();
+                    cycle.items_to_arbitration = cycle
+                        .items_to_arbitration
+                        .min(cycle.control.arbitration_size());
+                    State::ReadSrcEnd
+                }
+            }
+            State::ReadSrcEnd => {
+                cycle.src_end = word();
+                State::ReadDstEnd
+            }
+            State::ReadDstEnd => {
+                cycle.dst_end = word();
+                State::ReadSrcData
+            }
+            State::ReadSrcData => {
+                cycle.data = data.expect("uDMA read without data");
+                State::WriteDstData
+            }
+            State::WriteDstData => {
+                cycle.items_left -= 1;
+                cycle.items_to_arbitration -= 1;
+                if cycle.items_left > 0 && cycle.items_to_arbitration > 0 {
+                    State::ReadSrcData
+                } else {
+                    State::WriteChannelControl
+                }
+            }
+            State::WriteChannelControl => {
+                self.finish_cycle(ctx);
+                State::Idle
+            }
+            State::Idle => unreachable!("uDMA transfer in the idle state"),
+        };
+    }
+
+    /// [TI-TRM] 12.3.3 Transfer Modes: the actions after an arbitration cycle.
+    fn finish_cycle(&mut self, ctx: &mut Context) {
+        let cycle = self.cycle.take().expect("uDMA cycle to finish");
+        let channel = cycle.channel;
+        let bit = 1 << channel;
+        let complete = cycle.items_left == 0;
+        match cycle.control.mode() {
+            TransferMode::Stop => unreachable!("Transfer with a stopped control structure"),
+            TransferMode::Basic | TransferMode::Auto if complete => {
+                self.disable_channel(channel);
+                self.signal_done(ctx, channel);
+            }
+            TransferMode::Auto => self.continuing |= bit,
+            // The alternate structure is used on the next request.
+            TransferMode::PingPong if complete => {
+                self.alternate ^= bit;
+                self.signal_done(ctx, channel);
+            }
+            // A task is copied to the alternate structure and is performed right away.
+            TransferMode::MemScatterGather | TransferMode::PeriphScatterGather => {
+                self.alternate |= bit;
+                self.continuing |= bit;
+            }
+            TransferMode::AltMemScatterGather => {
+                if complete {
+                    self.alternate &= !bit;
+                }
+                self.continuing |= bit;
+            }
+            TransferMode::AltPeriphScatterGather if complete => {
+                self.alternate &= !bit;
+                self.continuing |= bit;
+            }
+            // The basic, ping-pong and peripheral scatter-gather modes wait for the next request.
+            _ => {}
+        }
+    }
+
+    fn disable_channel(&mut self, channel: usize) {
+        let bit = 1 << channel;
+        self.enabled &= !bit;
+        self.continuing &= !bit;
+    }
+
+    /// [TI-TRM] 12.5.1.19 DONEMASK Register: the masked channels contribute to the combined done
+    /// event, and the others signal the done state to their peripherals.
+    fn signal_done(&mut self, ctx: &mut Context, channel: usize) {
+        let bit = 1 << channel;
+        trace!("uDMA channel {channel} done");
+        self.reqdone |= bit;
+        if self.donemask & bit != 0 {
+            EventFabricProxy.notify(ctx, EventFabricEvent::DMA_DONE_COMB);
+        } else if channel == 0 {
+            EventFabricProxy.notify(ctx, EventFabricEvent::DMA_CH0_DONE);
+        } else if channel == 18 {
+            EventFabricProxy.notify(ctx, EventFabricEvent::DMA_CH18_DONE);
+        }
+    }
+
+    /// [TI-TRM] 12.2.8 Error Handling: the channel causing a bus error is disabled.
+    fn bus_error(&mut self, ctx: &mut Context) {
+        self.in_flight = false;
+        self.state = State::Idle;
+        if let Some(cycle) = self.cycle.take() {
+            warn!("uDMA bus error on channel {}", cycle.channel);
+            self.disable_channel(cycle.channel);
+        }
+        self.error |= UDMA0::ERROR::STATUS::BIT_MASK;
+        EventFabricProxy.notify(ctx, EventFabricEvent::DMA_ERR);
+    }
+
+    fn status(&self) -> u32 {
+        let mut status =
+            (UDMA0::STATUS::TOTALCHANNELS::RESET_VALUE << 16) | (self.state.status() << 4);
+        if self.is_master_enabled() {
+            status |= UDMA0::STATUS::MASTERENABLE::BIT_MASK;
+        }
+        status
+    }
+
+    fn get_data_for_address(&self, ctx: &Context, addr: Address) -> u32 {
+        match addr {
+            UDMA0::STATUS::ADDR => self.status(),
+            UDMA0::CTRL::ADDR => self.ctrl,
+            UDMA0::ALTCTRL::ADDR => self.ctrl + ALTERNATE_OFFSET,
+            UDMA0::WAITONREQ::ADDR => UDMA0::WAITONREQ::RESET_VALUE,
+            UDMA0::SETBURST::ADDR => self.useburst,
+            UDMA0::SETREQMASK::ADDR => self.reqmask,
+            UDMA0::SETCHANNELEN::ADDR => self.enabled,
+            UDMA0::SETCHNLPRIALT::ADDR => self.alternate,
+            UDMA0::SETCHNLPRIORITY::ADDR => self.priority,
+            UDMA0::ERROR::ADDR => self.error,
+            UDMA0::REQDONE::ADDR => self.reqdone,
+            UDMA0::DONEMASK::ADDR => self.donemask,
+            // Write-only registers
+            UDMA0::CFG::ADDR
+            | UDMA0::SOFTREQ::ADDR
+            | UDMA0::CLEARBURST::ADDR
+            | UDMA0::CLEARREQMASK::ADDR
+            | UDMA0::CLEARCHANNELEN::ADDR
+            | UDMA0::CLEARCHNLPRIALT::ADDR
+            | UDMA0::CLEARCHNLPRIORITY::ADDR => 0,
+            _ => unimplemented!(
+                "Requested UDMA0 data read from address {:?}: {}",
+                addr,
+                ctx.display_named_address(addr)
+            ),
+        }
+    }
+
+    fn set_data_for_address(&mut self, ctx: &mut Context, addr: Address, data: u32) {
+        match addr {
+            UDMA0::CFG::ADDR => self.cfg = data,
+            UDMA0::CTRL::ADDR => self.ctrl = data & UDMA0::CTRL::BASEPTR::BIT_MASK,
+            UDMA0::SOFTREQ::ADDR => self.burst_pulses |= data,
+            UDMA0::SETBURST::ADDR => self.useburst |= data,
+            UDMA0::CLEARBURST::ADDR => self.useburst &= !data,
+            UDMA0::SETREQMASK::ADDR => self.reqmask |= data,
+            UDMA0::CLEARREQMASK::ADDR => self.reqmask &= !data,
+            UDMA0::SETCHANNELEN::ADDR => self.enabled |= data,
+            UDMA0::CLEARCHANNELEN::ADDR => {
+                self.enabled &= !data;
+                self.continuing &= !data;
+            }
+            UDMA0::SETCHNLPRIALT::ADDR => self.alternate |= data,
+            UDMA0::CLEARCHNLPRIALT::ADDR => self.alternate &= !data,
+            UDMA0::SETCHNLPRIORITY::ADDR => self.priority |= data,
+            UDMA0::CLEARCHNLPRIORITY::ADDR => self.priority &= !data,
+            UDMA0::ERROR::ADDR => self.error &= !data,
+            UDMA0::REQDONE::ADDR => self.reqdone &= !data,
+            UDMA0::DONEMASK::ADDR => {
+                let newly_unmasked = data & !self.donemask;
+                self.donemask = data;
+                if self.reqdone & newly_unmasked != 0 {
+                    EventFabricProxy.notify(ctx, EventFabricEvent::DMA_DONE_COMB);
+                }
+            }
+            UDMA0::STATUS::ADDR | UDMA0::ALTCTRL::ADDR | UDMA0::WAITONREQ::ADDR => {
+                warn!(
+                    "Ignoring write {:#x} to read-only {}",
+                    data,
+                    ctx.display_named_address(addr)
+                );
+            }
+            _ => unimplemented!(
+                "Requested UDMA0 data write {:#x} to address {:?}: {}",
+                data,
+                addr,
+                ctx.display_named_address(addr)
+            ),
+        }
+    }
+
+    fn warn_if_gated(ctx: &Context, addr: Address) {
+        if !Self::is_clock_enabled(ctx) {
+            warn!(
+                "Accessing {} while the uDMA clock is gated.",
+                ctx.display_named_address(addr)
+            );
+        }
+    }
+}
+
+bridge_ports!(@slave UDMAComponent => @auto_configured @slave BusDriver);
+
+#[component_impl(udma)]
+impl AHBPortConfig for UDMAComponent {
+    type Data = DataBus;
+    type Component = Self;
+    const TAG: &'static str = "UDMA0";
+}
+
+#[component_impl(udma)]
+impl AHBSlavePortProxiedInput for UDMAComponent {
+    fn proxy_ahb_input(ctx: &mut Context, msg: MasterToSlaveWires<Self::Data>) {
+        UDMAProxy.on_new_ahb_slave_input(ctx, msg);
+    }
+}
+
+#[component_impl(udma)]
+impl AlignedFakingHandler for UDMAComponent {
+    const WRITE_MODE: WriteMode = WriteMode::Combinatorial;
+    const ALIGN: Size = Size::Word;
+    type Native = u32;
+
+    fn read_for_write_filler(
+        slave: &Self::Component,
+        ctx: &Context,
+        address: Address,
+    ) -> Self::Native {
+        slave.get_data_for_address(ctx, address)
+    }
+
+    fn pre_read(
+        _slave: &mut Self::Component,
+        ctx: &mut Context,
+        address: Address,
+    ) -> WaitstatesOrErr {
+        Self::warn_if_gated(ctx, address);
+        Ok(0)
+    }
+
+    fn read(slave: &mut Self::Component, ctx: &mut Context, address: Address) -> Self::Native {
+        let data = slave.get_data_for_address(ctx, address);
+        trace!(
+            "read {address:?} (\"{}\") = {data:#x}",
+            ctx.display_named_address(address)
+        );
+        data
+    }
+
+    fn pre_write(
+        _slave: &mut Self::Component,
+        ctx: &mut Context,
+        address: Address,
+    ) -> WaitstatesOrErr {
+        Self::warn_if_gated(ctx, address);
+        Ok(0)
+    }
+
+    fn write(slave: &mut Self::Component, ctx: &mut Context, address: Address, data: Self::Native) {
+        trace!(
+            "write {address:?} (\"{}\") {data:#x}",
+            ctx.display_named_address(address)
+        );
+        slave.set_data_for_address(ctx, address, data);
+    }
+}
+
+#[component_impl(udma)]
+impl DisableableComponent for UDMAComponent {
+    fn can_be_disabled_now(&self) -> bool {
+        // Requests are arbitrated on ticks.
+        self.driver.can_be_disabled_now()
+            && self.sysbus_driver.can_be_disabled_now()
+            && self.state == State::Idle
+            && self.next_channel().is_none()
+    }
+}
+
+// The port of the uDMA on the system bus
+make_port_struct!(pub(crate) SysbusMPort);
+
+impl AHBPortConfig for SysbusMPort {
+    type Data = DataBus;
+    type Component = UDMAComponent;
+    const TAG: &'static str = "UDMA0:Master";
+}
+
+impl AHBMasterPortProxiedInput for SysbusMPort {
+    fn proxy_ahb_input(ctx: &mut Context, msg: SlaveToMasterWires<Self::Data>) {
+        UDMAProxy.on_new_ahb_master_input(ctx, msg);
+    }
+}
+
+bridge_ports!(@auto_configured @master SysbusDriver => @master SysbusMPort);
+
+impl MasterDriverSimplerHandler for SysbusMPort {
+    type UserData = ();
+    type MasterDriverSC = SysbusDriverSC;
+    const AHB_LITE_COMPAT: bool = true;
+    const DEFAULT_PROT: Protection = Protection::new_data();
+
+    fn read_done(
+        comp: &mut <Self as AHBPortConfig>::Component,
+        ctx: &mut Context,
+        _addr: Address,
+        data: DataBus,
+        _user: <Self as MasterDriverSimplerHandler>::UserData,
+    ) {
+        comp.transfer_done(ctx, Some(data));
+    }
+
+    fn write_done(
+        comp: &mut <Self as AHBPortConfig>::Component,
+        ctx: &mut Context,
+        _addr: Address,
+        _user: <Self as MasterDriverSimplerHandler>::UserData,
+    ) {
+        comp.transfer_done(ctx, None);
+    }
+
+    fn transfers_aborted(
+        comp: &mut <Self as AHBPortConfig>::Component,
+        ctx: &mut Context,
+        _addr_phase: Option<(TransferMeta, <Self as MasterDriverSimplerHandler>::UserData)>,
+        _data_phase: Option<(TransferMeta, <Self as MasterDriverSimplerHandler>::UserData)>,
+    ) {
+        comp.bus_error(ctx);
+    }
+}
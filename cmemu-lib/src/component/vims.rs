@@ -1,4 +1,5 @@
 use cmemu_proc_macros::{component_impl, handler, proxy_use};
+use serde::{Deserialize, Serialize};
 
 use crate::common::new_ahb::databus::DataBus;
 #[proxy_use]
@@ -15,8 +16,8 @@ pub(crate) use crate::component::vims::{PublicMasters, PublicSlaves};
 #[proxy_use]
 use crate::engine::Context;
 use crate::engine::{
-    DisableableComponent, MainComponent, PowerNode, SkippableClockTreeNode, TickComponent,
-    TickComponentExtra,
+    DisableableComponent, MainComponent, MemoryAccess, PowerNode, SkippableClockTreeNode,
+    TickComponent, TickComponentExtra,
 };
 use crate::expose_ports;
 use crate::proxy::VIMSProxy;
@@ -35,7 +36,7 @@ mod registers;
 // VIMSComponent
 // ===========================================================================
 
-#[derive(MainComponent, SkippableClockTreeNode, TickComponent)]
+#[derive(Clone, MainComponent, SkippableClockTreeNode, TickComponent, Serialize, Deserialize)]
 #[skippable_if_disableable]
 pub(crate) struct VIMSComponent {
     #[subcomponent(Interconnect)]
@@ -116,6 +117,9 @@ impl VIMSComponent {
             debug_assert!(msg.is_inert());
             return;
         }
+        if matches!(tag, PublicMasters::FlashMPort) && msg.meta.is_done() && msg.data.is_present() {
+            ctx.record_memory_access(MemoryAccess::FlashRead);
+        }
         <Self as AHBSoftVlanMasterPortInput<PublicMasters>>::on_ahb_soft_tagged_input(
             self, ctx, tag, msg,
         );
@@ -15,7 +15,7 @@ use crate::component::vims::cache_ram::CacheRAMComponent;
 use crate::component::vims::internal_routing::CodeCacheLineBuffer;
 use crate::engine::Context;
 use crate::engine::{
-    CombFlop, DisableableComponent, SeqFlopMemoryBank, Subcomponent, TickComponent,
+    CombFlop, DisableableComponent, MemoryAccess, SeqFlopMemoryBank, Subcomponent, TickComponent,
     TickComponentExtra,
 };
 use crate::make_port_struct;
@@ -26,10 +26,11 @@ use fixedbitset::FixedBitSet;
 #[cfg_attr(not(debug_assertions), allow(unused_imports))]
 use log::{debug, info, trace};
 use owo_colors::OwoColorize;
+use serde::{Deserialize, Serialize};
 
 use crate::confeature;
 
-#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
 pub(crate) enum Mode {
     GPRAM = 0,
     Cache = 1,
@@ -52,7 +53,7 @@ const POLY: [u8; 255] = [
     0, 0, 1, 2, 0, 1, 3, 3, 3, 3, 3, 3, 3, 2, 0, 0, 0, 1, 2, 1, 3, 3, 3, 2, 0, 0, 1, 3, 2, 1, 2,
 ];
 
-#[derive(Eq, PartialEq, Debug)]
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
 enum PendingRequestType {
     None,
     Delayed,
@@ -60,7 +61,7 @@ enum PendingRequestType {
     Cache,
 }
 
-#[derive(Eq, PartialEq, Debug)]
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
 enum RequestThisTick {
     None,
     Delayed,
@@ -68,7 +69,7 @@ enum RequestThisTick {
     Cache,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 struct PendingTagUpdate {
     addr: Address,
     data: [u8; 8],
@@ -76,7 +77,7 @@ struct PendingTagUpdate {
     set_was_full_according_to_tag_ram_when_tag_ram_was_read: bool,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 struct PendingFlashRead {
     is_prefetched: bool,
     set_was_full_according_to_tag_ram_when_tag_ram_was_read: bool,
@@ -84,13 +85,13 @@ struct PendingFlashRead {
 
 /// The form of TAG RAM entries reflects the description from a cache user's guide from TI:
 /// <https://www.ti.com/lit/ug/spru656a/spru656a.pdf>.
-#[derive(Default, Copy, Clone)]
+#[derive(Default, Copy, Clone, Serialize, Deserialize)]
 struct TagRamEntry {
     valid: bool,
     tag: u32,
 }
 
-#[derive(Subcomponent, TickComponent, DisableableComponent)]
+#[derive(Clone, Subcomponent, TickComponent, DisableableComponent, Serialize, Deserialize)]
 #[subcomponent_1to1]
 pub(crate) struct CacheComponent {
     // Partly-manual flop that has a transition period
@@ -106,6 +107,7 @@ pub(crate) struct CacheComponent {
     /// However, because of pipelining it is not trivial how many loads consider the set to not be full. Additionally,
     /// due to various edge cases these loads may use and modify `next_set_pos` in a non-trivial manner.
     #[flop]
+    #[serde(with = "crate::engine::serde_array_bank")]
     next_set_pos: SeqFlopMemoryBank<[u8; 256], u8>,
 
     /// A TAG RAM update is considered to treat its set as full if the following two conditions hold:
@@ -116,6 +118,7 @@ pub(crate) struct CacheComponent {
 
     /// The memory that contains metadata about which addresses are cached in which ways.
     #[flop]
+    #[serde(with = "crate::engine::serde_array_bank")]
     tag_ram: SeqFlopMemoryBank<[TagRamEntry; 1024], (u32, u32)>,
 
     /// Used for storing the request when a simulated wait state for TAG lookup is added.
@@ -949,6 +952,9 @@ impl AHBMasterPortInput for CacheRAMPort {
                 PendingRequestType::Cache | PendingRequestType::None
             )
         {
+            if *this.pending_request_type == PendingRequestType::Cache && msg.meta.is_done() {
+                ctx.record_memory_access(MemoryAccess::CacheHit);
+            }
             #[cfg(debug_assertions)]
             if *this.pending_request_type == PendingRequestType::Cache {
                 let DataBus::Quad(ref data) = msg.data else {
@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use std::ops::Range;
 
 use cmemu_proc_macros::proxy_use;
@@ -28,7 +29,7 @@ impl MemoryConfiguration for CacheRAMMemory {
 }
 type CacheRAMMemory = Memory<MemorySC>;
 
-#[derive(Subcomponent, TickComponent, DisableableComponent)]
+#[derive(Clone, Subcomponent, TickComponent, DisableableComponent, Serialize, Deserialize)]
 #[subcomponent_1to1]
 pub(crate) struct CacheRAMComponent {
     #[flop]
@@ -29,6 +29,7 @@ pub(super) type VIMSRegistersDriver = new_ahb::slave_driver::SimpleSynchronousSl
     RegistersPort,
 >;
 
+#[derive(Clone)]
 pub(crate) struct RegistersPort;
 bridge_ports!(@auto_configured Output<Internal> => RegistersPort);
 bridge_ports!(@slave RegistersPort => @slave VIMSRegistersDriver);
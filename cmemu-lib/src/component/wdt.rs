@@ -0,0 +1,325 @@
+pub const WDT_ROUTE_INJECTION: Range<Address> = WDT::ADDR_SPACE;
+
+use crate::bridge_ports;
+use crate::common::new_ahb::databus::DataBus;
+use crate::common::new_ahb::ports::AHBSlavePortProxiedInput;
+#[proxy_use]
+use crate::common::new_ahb::ports::{AHBPortConfig, AHBSlavePortInput};
+#[proxy_use]
+use crate::common::new_ahb::signals::{MasterToSlaveWires, Size};
+use crate::common::new_ahb::slave_driver::WriteMode;
+use crate::common::new_ahb::slave_driver::faking_slave_driver::{
+    AlignedFakingHandler, FakingIface, WaitstatesOrErr,
+};
+use crate::component::event_fabric::EventFabricEvent;
+#[proxy_use]
+use crate::engine::Context;
+use crate::engine::{
+    DisableableComponent, MainComponent, ResetRequest, SkippableClockTreeNode, TickComponent,
+    TickComponentExtra, WarmResetSource,
+};
+use crate::proxy::{EventFabricProxy, WDTProxy};
+use cc2650_constants::WDT;
+use cmemu_common::Address;
+use cmemu_proc_macros::{component_impl, handler, proxy_use};
+use log::{info, trace, warn};
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+// [TI-TRM] 15 Watchdog Timer
+// The WDT is a 32-bit down-counter, which runs at the MCU clock divided by 32.
+// Setting `CTL.INTEN` starts the counter, which then can be stopped only by a reset.
+// On a time-out, the counter is reloaded from `LOAD` and the interrupt is raised: a maskable one
+// or an NMI, as selected by `CTL.INTTYPE`. If the interrupt is still pending on the next time-out
+// and `CTL.RESEN` is set, the WDT requests a warm reset.
+// Writing the magic key to `LOCK` unlocks the other registers, while writing any other value
+// locks them.
+// Not implemented: the test mode (`TEST.TEST_EN`) and stalling while the CPU is halted by
+// a debugger (`TEST.STALL`).
+
+/// Number of MCU clock cycles per a count of the WDT.
+const CLOCK_DIVIDER: u64 = 32;
+
+/// The value that unlocks the register writes, see `WDT:LOCK`.
+const UNLOCK_KEY: u32 = 0x1ACC_E551;
+
+const CTL_WRITABLE_BITS: u32 =
+    WDT::CTL::INTEN::BIT_MASK | WDT::CTL::RESEN::BIT_MASK | WDT::CTL::INTTYPE::BIT_MASK;
+
+type BusDriver = FakingIface<DriverSC, WDTComponent>;
+
+#[derive(
+    Clone,
+    MainComponent,
+    TickComponent,
+    TickComponentExtra,
+    DisableableComponent,
+    Serialize,
+    Deserialize,
+)]
+pub(crate) struct WDTComponent {
+    #[subcomponent(DriverSC)]
+    driver: BusDriver,
+
+    load: u32,
+    value: u32,
+    /// MCU cycles since the last count of the counter.
+    prescaler: u64,
+    ctl: u32,
+    /// The interrupt status, `RIS.WDTRIS`.
+    interrupt: bool,
+    test: u32,
+    locked: bool,
+}
+
+#[component_impl(wdt)]
+impl WDTComponent {
+    pub(crate) fn new() -> Self {
+        Self {
+            driver: Default::default(),
+
+            load: WDT::LOAD::RESET_VALUE,
+            value: WDT::VALUE::RESET_VALUE,
+            prescaler: 0,
+            ctl: WDT::CTL::RESET_VALUE,
+            interrupt: false,
+            test: WDT::TEST::RESET_VALUE,
+            locked: false,
+        }
+    }
+
+    pub(crate) fn tick(&mut self, ctx: &mut Context) {
+        BusDriver::run_driver(self, ctx);
+
+        if self.is_running() {
+            self.advance(ctx, 1);
+        }
+    }
+
+    pub(crate) fn tock(&mut self, ctx: &mut Context) {
+        BusDriver::tock(self, ctx);
+    }
+
+    #[handler]
+    pub(crate) fn on_new_ahb_slave_input(
+        &mut self,
+        ctx: &mut Context,
+        msg: MasterToSlaveWires<<WDTComponent as AHBPortConfig>::Data>,
+    ) {
+        <Self as AHBSlavePortInput>::on_ahb_input(self, ctx, msg);
+    }
+
+    fn is_running(&self) -> bool {
+        self.ctl & WDT::CTL::INTEN::BIT_MASK != 0
+    }
+
+    /// Advances the WDT by `cycles` of the MCU clock.
+    fn advance(&mut self, ctx: &mut Context, cycles: u64) {
+        let cycles = self.prescaler + cycles;
+        self.prescaler = cycles % CLOCK_DIVIDER;
+        let mut counts = cycles / CLOCK_DIVIDER;
+        while counts > 0 {
+            if counts < u64::from(self.value) {
+                self.value -= u32::try_from(counts).expect("counts are less than the value");
+                return;
+            }
+            // A zero loaded to the counter times out on the next count.
+            counts -= u64::from(self.value.max(1));
+            self.value = self.load;
+            if self.time_out(ctx) {
+                return;
+            }
+        }
+    }
+
+    /// Handles a time-out of the counter, returns whether a reset was requested.
+    fn time_out(&mut self, ctx: &mut Context) -> bool {
+        if !self.interrupt {
+            trace!("WDT time-out, raising the interrupt");
+            self.interrupt = true;
+            let event = if self.ctl & WDT::CTL::INTTYPE::BIT_MASK == 0 {
+                EventFabricEvent::WDT_IRQ
+            } else {
+                EventFabricEvent::WDT_NMI
+            };
+            EventFabricProxy.notify(ctx, event);
+            false
+        } else if self.ctl & WDT::CTL::RESEN::BIT_MASK != 0 {
+            info!("Second WDT time-out with the interrupt pending, requesting a reset");
+            ctx.request_reset(ResetRequest::Warm(WarmResetSource::Watchdog));
+            true
+        } else {
+            false
+        }
+    }
+
+    /// MCU cycles until the next time-out.
+    fn cycles_until_event(&self) -> Option<u64> {
+        self.is_running()
+            .then(|| u64::from(self.value.max(1)) * CLOCK_DIVIDER - self.prescaler)
+    }
+
+    fn get_data_for_address(&self, ctx: &Context, addr: Address) -> u32 {
+        match addr {
+            WDT::LOAD::ADDR => self.load,
+            WDT::VALUE::ADDR => self.value,
+            WDT::CTL::ADDR => self.ctl,
+            WDT::ICR::ADDR => 0,
+            WDT::RIS::ADDR => u32::from(self.interrupt),
+            WDT::MIS::ADDR => u32::from(self.interrupt && self.is_running()),
+            WDT::TEST::ADDR => self.test,
+            WDT::INT_CAUS::ADDR => {
+                if self.interrupt {
+                    WDT::INT_CAUS::CAUSE_INTR::BIT_MASK
+                } else {
+                    0
+                }
+            }
+            WDT::LOCK::ADDR => u32::from(self.locked),
+            _ => unimplemented!(
+                "Requested WDT data read from address {:?}: {}",
+                addr,
+                ctx.display_named_address(addr)
+            ),
+        }
+    }
+
+    fn set_data_for_address(&mut self, ctx: &mut Context, addr: Address, data: u32) {
+        if self.locked && addr != WDT::LOCK::ADDR {
+            warn!(
+                "Ignoring a write {data:#x} to {} while the WDT is locked.",
+                ctx.display_named_address(addr)
+            );
+            return;
+        }
+        match addr {
+            WDT::LOAD::ADDR => {
+                self.load = data;
+                self.value = data;
+                self.prescaler = 0;
+            }
+            WDT::CTL::ADDR => {
+                // `CTL.INTEN` can be cleared only by a reset.
+                let started = !self.is_running() && data & WDT::CTL::INTEN::BIT_MASK != 0;
+                self.ctl = (self.ctl & WDT::CTL::INTEN::BIT_MASK) | (data & CTL_WRITABLE_BITS);
+                if started {
+                    self.value = self.load;
+                    self.prescaler = 0;
+                }
+            }
+            // Writing any value clears the interrupt and reloads the counter.
+            WDT::ICR::ADDR => {
+                self.interrupt = false;
+                self.value = self.load;
+                self.prescaler = 0;
+            }
+            WDT::TEST::ADDR => self.test = data,
+            WDT::LOCK::ADDR => self.locked = data != UNLOCK_KEY,
+            WDT::VALUE::ADDR | WDT::RIS::ADDR | WDT::MIS::ADDR | WDT::INT_CAUS::ADDR => warn!(
+                "Ignoring a write {data:#x} to the read-only {}",
+                ctx.display_named_address(addr)
+            ),
+            _ => unimplemented!(
+                "Requested WDT data write {:#x} to address {:?}: {}",
+                data,
+                addr,
+                ctx.display_named_address(addr)
+            ),
+        }
+    }
+}
+
+bridge_ports!(@slave WDTComponent => @auto_configured @slave BusDriver);
+
+#[component_impl(wdt)]
+impl AHBPortConfig for WDTComponent {
+    type Data = DataBus;
+    type Component = Self;
+    const TAG: &'static str = "WDT";
+}
+
+#[component_impl(wdt)]
+impl AHBSlavePortProxiedInput for WDTComponent {
+    fn proxy_ahb_input(ctx: &mut Context, msg: MasterToSlaveWires<Self::Data>) {
+        WDTProxy.on_new_ahb_slave_input(ctx, msg);
+    }
+}
+
+#[component_impl(wdt)]
+impl AlignedFakingHandler for WDTComponent {
+    const WRITE_MODE: WriteMode = WriteMode::Combinatorial;
+    const ALIGN: Size = Size::Word;
+    type Native = u32;
+
+    fn read_for_write_filler(
+        slave: &Self::Component,
+        ctx: &Context,
+        address: Address,
+    ) -> Self::Native {
+        slave.get_data_for_address(ctx, address)
+    }
+
+    fn pre_read(
+        _slave: &mut Self::Component,
+        _ctx: &mut Context,
+        _address: Address,
+    ) -> WaitstatesOrErr {
+        Ok(0)
+    }
+
+    fn read(slave: &mut Self::Component, ctx: &mut Context, address: Address) -> Self::Native {
+        let data = slave.get_data_for_address(ctx, address);
+        trace!(
+            "read {address:?} (\"{}\") = {data:#x}",
+            ctx.display_named_address(address)
+        );
+        data
+    }
+
+    fn pre_write(
+        _slave: &mut Self::Component,
+        _ctx: &mut Context,
+        _address: Address,
+    ) -> WaitstatesOrErr {
+        Ok(0)
+    }
+
+    fn write(slave: &mut Self::Component, ctx: &mut Context, address: Address, data: Self::Native) {
+        trace!(
+            "write {address:?} (\"{}\") {data:#x}",
+            ctx.display_named_address(address)
+        );
+        slave.set_data_for_address(ctx, address, data);
+    }
+}
+
+#[component_impl(wdt)]
+impl SkippableClockTreeNode for WDTComponent {
+    fn max_cycles_to_skip(
+        comp: &mut Self::Component,
+        _ctx: &mut Context,
+        _parent: Self::IdSpace,
+        _extra: &mut Self::Extra,
+    ) -> u64 {
+        if !comp.can_be_disabled_now() {
+            0
+        } else if let Some(cycles) = comp.cycles_until_event() {
+            // -1 because the time-outs happen only on ticks
+            cycles.saturating_sub(1)
+        } else {
+            u64::MAX
+        }
+    }
+
+    fn emulate_skipped_cycles(
+        comp: &mut Self::Component,
+        ctx: &mut Context,
+        _parent: Self::IdSpace,
+        _extra: &mut Self::Extra,
+        skipped_cycles: u64,
+    ) {
+        if comp.is_running() {
+            comp.advance(ctx, skipped_cycles);
+        }
+    }
+}
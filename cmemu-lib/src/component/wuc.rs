@@ -18,17 +18,26 @@ use crate::engine::{
     Context, DisableableComponent, MainComponent, PowerMode, SeqFlopMemoryBank,
     SkippableClockTreeNode, Subcomponent, TickComponent, TickComponentExtra,
 };
-use crate::proxy::{ClockTreeProxy, PRCMProxy, WUCProxy};
+use crate::proxy::{
+    ClockTreeProxy, GPIOProxy, PRCMProxy, SRAMProxy, SensorControllerProxy, WUCProxy,
+};
 use cc2650_constants::AON_WUC as WUC;
 use cmemu_common::{Address, HwRegister};
 use cmemu_proc_macros::{component_impl, handler, proxy_use};
-use log::debug;
+use log::{debug, info};
+use serde::{Deserialize, Serialize};
 use std::ops::Range;
 
 #[derive(
-    MainComponent, SkippableClockTreeNode, TickComponent, TickComponentExtra, DisableableComponent,
+    Clone,
+    MainComponent,
+    SkippableClockTreeNode,
+    TickComponent,
+    TickComponentExtra,
+    DisableableComponent,
 )]
 #[skippable_if_disableable]
+#[derive(Serialize, Deserialize)]
 pub(crate) struct WUCComponent {
     #[subcomponent(DriverSC)]
     driver: BusDriver,
@@ -37,24 +46,40 @@ pub(crate) struct WUCComponent {
     // Aux is not simulated
     // aux_state: DomainState,
     #[flop]
+    #[serde(with = "crate::engine::serde_hw_register_bank")]
     ctl0: SeqFlopMemoryBank<WUC::CTL0::Register, u32>,
     #[flop]
+    #[serde(with = "crate::engine::serde_hw_register_bank")]
+    ctl1: SeqFlopMemoryBank<WUC::CTL1::Register, u32>,
+    #[flop]
+    #[serde(with = "crate::engine::serde_hw_register_bank")]
+    shutdown: SeqFlopMemoryBank<WUC::SHUTDOWN::Register, u32>,
+    #[flop]
+    #[serde(with = "crate::engine::serde_hw_register_bank")]
     rechargecfg: SeqFlopMemoryBank<WUC::RECHARGECFG::Register, u32>,
     #[flop]
+    #[serde(with = "crate::engine::serde_hw_register_bank")]
     rechargestat: SeqFlopMemoryBank<WUC::RECHARGESTAT::Register, u32>,
     #[flop]
+    #[serde(with = "crate::engine::serde_hw_register_bank")]
     pwrstat: SeqFlopMemoryBank<WUC::PWRSTAT::Register, u32>,
     #[flop]
+    #[serde(with = "crate::engine::serde_hw_register_bank")]
     mcuclk: SeqFlopMemoryBank<WUC::MCUCLK::Register, u32>,
     #[flop]
+    #[serde(with = "crate::engine::serde_hw_register_bank")]
     mcucfg: SeqFlopMemoryBank<WUC::MCUCFG::Register, u32>,
     #[flop]
+    #[serde(with = "crate::engine::serde_hw_register_bank")]
     auxctl: SeqFlopMemoryBank<WUC::AUXCTL::Register, u32>,
     #[flop]
+    #[serde(with = "crate::engine::serde_hw_register_bank")]
     auxcfg: SeqFlopMemoryBank<WUC::AUXCFG::Register, u32>,
     #[flop]
+    #[serde(with = "crate::engine::serde_hw_register_bank")]
     auxclk: SeqFlopMemoryBank<WUC::AUXCLK::Register, u32>,
     #[flop]
+    #[serde(with = "crate::engine::serde_hw_register_bank")]
     jtagcfg: SeqFlopMemoryBank<WUC::JTAGCFG::Register, u32>,
 }
 type BusDriver = SimpleSynchronousSlaveInterface<DriverSC, WUCComponent>;
@@ -64,7 +89,7 @@ type BusDriver = SimpleSynchronousSlaveInterface<DriverSC, WUCComponent>;
 /// [TI-TRM] 4.4.2.1 Wake-Up Controller (WUC)
 /// "The WUC receives output signals from the WUC subscriber in the AON event fabric where power-on
 /// sequences can be triggered by configured input events from JTAG, AUX, or the MCU"
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[allow(non_camel_case_types, dead_code)]
 pub(crate) enum WUCWakeupEvent {
     JTAG,
@@ -77,7 +102,7 @@ pub(crate) enum WUCWakeupEvent {
 /// [TI-TRM] 4.7.1.1 MCUWUSEL Register
 /// "A wakeup sequence will guarantee that the MCU power switches are turned on, LDO resources
 /// are available and `SCLK_HF` is available and selected as clock source for MCU."
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[allow(non_camel_case_types, dead_code)]
 pub(crate) enum DomainState {
     Off,
@@ -94,6 +119,8 @@ impl WUCComponent {
             mcu_vd_state: DomainState::Active,
 
             ctl0: SeqFlopMemoryBank::new(WUC::CTL0::Register::new()),
+            ctl1: SeqFlopMemoryBank::new(WUC::CTL1::Register::new()),
+            shutdown: SeqFlopMemoryBank::new(WUC::SHUTDOWN::Register::new()),
             rechargecfg: SeqFlopMemoryBank::new(WUC::RECHARGECFG::Register::new()),
             rechargestat: SeqFlopMemoryBank::new(WUC::RECHARGESTAT::Register::new()),
             pwrstat: SeqFlopMemoryBank::new(WUC::PWRSTAT::Register::new()),
@@ -133,36 +160,66 @@ impl WUCComponent {
         // Let's map it as:
         // ClockGated: Idle Mode (i.e. nothing special by WUC)
         // Retention: ULDO, MCU_PD is having power, but may be clock-less
-        // Off: MCU_VD is completely disconnected
+        // Off: MCU_VD is powered off, only the logic and SRAM banks with retention keep the state
         // debug_assert!(mcu_mode != PowerMode::Active);
 
+        // [TI-TRM] 6.8.2.3.7 SHUTDOWN Register
+        // "A registered shutdown request takes effect the next time power down conditions exists."
+        if mcu_mode != PowerMode::Active && self.shutdown.bitfields().EN() != 0 {
+            info!("Entering the shutdown mode, only a pad can wake up the device");
+            self.mcu_vd_state = DomainState::Off;
+            GPIOProxy.enter_shutdown(ctx);
+            ClockTreeProxy.start_sleep(ctx);
+            return;
+        }
+
         self.mcu_vd_state = match mcu_mode {
             PowerMode::Active | PowerMode::ClockGated => {
                 // HF clock is driving mcu_vd (e.g. PRCM)
                 DomainState::ShallowSleep
             }
-            PowerMode::Retention => {
-                let osc = match u32::from(self.mcuclk.bitfields().PWR_DWN_SRC()) {
-                    WUC::MCUCLK::PWR_DWN_SRC::E::SCLK_LF => {
-                        EnergyEntity::ClockTree(ClockTreeNodes::SclkLf)
-                    }
-                    WUC::MCUCLK::PWR_DWN_SRC::E::NONE => {
-                        EnergyEntity::ClockTree(ClockTreeNodes::NullClk)
-                    }
-                    _ => unreachable!(),
-                };
-                // TODO: implement actually cutting off the clock (we need proper buses first)
-                // ClockTreeProxy.want_switch_parent(ctx, ClockTreeNodes::McuClk, osc);
-                // TODO: implement non-retention states
-                DomainState::DeepSleep { clk: osc }
+            PowerMode::Retention => DomainState::DeepSleep {
+                clk: self.power_down_clock(),
+            },
+            PowerMode::Off => {
+                // The power domains of MCU_VD are already down, and the ones with retention
+                // keep their state. Of SRAM, only the banks selected in MCUCFG are retained.
+                SRAMProxy.lose_unretained_banks(ctx, self.mcucfg.bitfields().SRAM_RET_EN());
+                DomainState::DeepSleep {
+                    clk: self.power_down_clock(),
+                }
             }
-            PowerMode::Off => todo!("Full shutdown not implemented!"),
         };
 
         // TODO: we should clock-down and implement RTC per-channel events to support proper wakeup
         ClockTreeProxy.start_sleep(ctx);
     }
 
+    fn power_down_clock(&self) -> EnergyEntity {
+        let osc = match u32::from(self.mcuclk.bitfields().PWR_DWN_SRC()) {
+            WUC::MCUCLK::PWR_DWN_SRC::E::SCLK_LF => EnergyEntity::ClockTree(ClockTreeNodes::SclkLf),
+            WUC::MCUCLK::PWR_DWN_SRC::E::NONE => EnergyEntity::ClockTree(ClockTreeNodes::NullClk),
+            _ => unreachable!(),
+        };
+        // TODO: implement actually cutting off the clock (we need proper buses first)
+        // ClockTreeProxy.want_switch_parent(ctx, ClockTreeNodes::McuClk, osc);
+        osc
+    }
+
+    /// Resets the bus interface, but keeps the registers, as in a warm reset.
+    pub(crate) fn reset_keeping_registers(&mut self) {
+        self.driver = Default::default();
+        self.mcu_vd_state = DomainState::Active;
+    }
+
+    /// Records a warm reset in `CTL1`.
+    pub(crate) fn record_warm_reset(&mut self) {
+        self.ctl1
+            .unsafe_as_mut()
+            .mut_bitfields()
+            .set_MCU_WARM_RESET(1);
+    }
+
     /// Output signals from AON Event Fabric subscriber
     #[handler]
     pub fn on_wake_up_event(&mut self, ctx: &mut Context, ev: WUCWakeupEvent) {
@@ -174,7 +231,13 @@ impl WUCComponent {
                 // [TI-TRM]4.7.1.2 AUXWUSEL Register
                 // "A wakeup sequence will guarantee that the AUX power switches are turned on, LDO resources
                 // are available and SCLK_HF is available and selected as clock source for AUX."
-                todo!("wakeup aux impl");
+                // AUX is never powered off here, so the wakeup only sets `AUX_WUC:WUEVFLAGS.AON_PROG_WU`.
+                debug!("WUC wakeup for AUX");
+                SensorControllerProxy.on_aon_wakeup(ctx);
+            }
+            WUCWakeupEvent::MCU if self.mcu_vd_state == DomainState::Off => {
+                // In the shutdown, the wakeup from a pad is a reset (see `GPIOComponent`).
+                debug!("Ignoring the WUC wakeup for MCU in the shutdown mode");
             }
             WUCWakeupEvent::MCU => {
                 debug!("WUC wakeup for MCU from {:?}", self.mcu_vd_state);
@@ -193,15 +256,32 @@ impl WUCComponent {
         }
     }
 
+    /// `AUX_WUC:WUEVCLR.AON_SW` was written by AUX.
+    #[handler]
+    pub fn clear_aux_software_event(&mut self, _ctx: &mut Context) {
+        self.auxctl
+            .mutate_next(WUC::AUXCTL::SWEV::BIT_MASK, |reg, val| {
+                reg.mutate(reg.read() & !val);
+            });
+    }
+
     fn set_data_for_address(
         &mut self,
-        _ctx: &mut Context,
+        ctx: &mut Context,
         addr: Address,
         data: <WUCComponent as AlignedHandler>::Native,
     ) {
         debug!("wuc write: {:?} {:?}", addr, data);
         match addr {
             WUC::CTL0::ADDR => self.ctl0.mutate_next(data, |reg, val| reg.mutate(val)),
+            // The bits are cleared by writing 1.
+            WUC::CTL1::ADDR => self
+                .ctl1
+                .mutate_next(data, |reg, val| reg.mutate(reg.read() & !val)),
+            WUC::SHUTDOWN::ADDR => {
+                // Not implemented: latching of the pads until `AON_IOC:IOCLATCH.EN` is set.
+                self.shutdown.mutate_next(data, |reg, val| reg.mutate(val));
+            }
             WUC::RECHARGECFG::ADDR => self
                 .rechargecfg
                 .mutate_next(data, |reg, val| reg.mutate(val)),
@@ -236,6 +316,15 @@ impl WUCComponent {
                 //     panic!("aux_ctrl_power_up");
                 // }
                 self.auxctl.mutate_next(data, |reg, val| reg.mutate(val));
+                // Not implemented: resetting AUX with `RESET_REQ`.
+                let auxctl = WUC::AUXCTL::Register::from(data);
+                let auxctl = auxctl.bitfields();
+                SensorControllerProxy.on_aon_control(
+                    ctx,
+                    auxctl.SCE_RUN_EN() != 0,
+                    auxctl.SWEV() != 0,
+                    auxctl.AUX_FORCE_ON() != 0,
+                );
             }
             WUC::AUXCFG::ADDR => {
                 // Contiki:
@@ -265,6 +354,8 @@ impl WUCComponent {
         debug!("wuc read: {:?}", addr);
         match addr {
             WUC::CTL0::ADDR => self.ctl0.read(),
+            WUC::CTL1::ADDR => self.ctl1.read(),
+            WUC::SHUTDOWN::ADDR => self.shutdown.read(),
             WUC::RECHARGECFG::ADDR => self.rechargecfg.read(),
             WUC::RECHARGESTAT::ADDR => self.rechargestat.read(),
             WUC::PWRSTAT::ADDR => {
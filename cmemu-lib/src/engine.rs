@@ -5,27 +5,36 @@
 //! emulator is aware of in opposite to the contents of the `crate::common`.
 //! Highly connected with `build.rs` and `cmemu-proc-macros/component.rs`.
 
+mod analog;
 mod component_traits;
 mod context;
 mod emulator;
+mod energy;
 mod event_queue;
 mod flop;
 mod stm;
 mod time;
 
+pub use analog::{ANALOG_AUXIO_COUNT, AnalogInput, AnalogSource, AnalogSourceImpl};
+pub use component_traits::PowerMode;
 pub(crate) use component_traits::{
-    ClockTreeNode, CpuMode, DisableableComponent, EnergyNode, MainComponent, PowerMode, PowerNode,
+    ClockTreeNode, CpuMode, DisableableComponent, EnergyNode, MainComponent, PowerNode,
     PureSubcomponentMarker, SkippableClockTreeNode, Subcomponent, TickComponent,
     TickComponentExtra,
 };
 pub(crate) use context::Context;
 pub use context::SymbolsService;
-pub use emulator::{Emulator, EmulatorError};
+pub use emulator::{Emulator, EmulatorError, EmulatorSnapshot, SnapshotError};
+pub(crate) use emulator::{ResetRequest, WarmResetSource};
+pub use energy::{
+    CurrentTable, EnergyConfigError, EnergyReport, EnergySample, MemoryAccess, RadioActivity,
+};
 use event_queue::EventQueue;
 pub(crate) use event_queue::EventRevokeToken;
 pub(crate) use flop::{
     BufferFlop, CombFlop, CombFlopMemoryBank, CombFlopMemoryBankSimple, CombRegister, LatchFlop,
     SeqFlop, SeqFlopMemoryBank, SeqFlopMemoryBankSimple, SeqFlopMemoryBankSimpleLarge, SeqRegister,
+    serde_array_bank, serde_hw_register, serde_hw_register_bank,
 };
 #[cfg(debug_assertions)]
 pub(crate) use stm::TransitionValidator;
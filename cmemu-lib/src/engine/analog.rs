@@ -0,0 +1,103 @@
+//! Analog quantities measured by the emulated node, supplied by the host.
+//!
+//! The battery monitor (`AON_BATMON`) measures the supply voltage and the temperature of the chip,
+//! and the ADC of AUX measures the supply voltage and the voltages of the analog AUXIO pins.
+//! The host attaches an [`AnalogSource`] to an [`AnalogInput`] to make it change in time,
+//! otherwise it has a constant default value.
+
+use crate::engine::Timepoint;
+use std::panic::UnwindSafe;
+
+/// The supply voltage of the typical conditions of the datasheet, in volts.
+const DEFAULT_SUPPLY_VOLTAGE: f64 = 3.0;
+
+/// The temperature of the typical conditions of the datasheet, in °C.
+const DEFAULT_TEMPERATURE: f64 = 25.0;
+
+/// Number of the AUXIO pins connected to the input multiplexer of the ADC.
+pub const ANALOG_AUXIO_COUNT: u8 = 8;
+
+/// An analog quantity measured by the node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AnalogInput {
+    /// The supply voltage (VDDS), in volts. 3.0 V by default.
+    SupplyVoltage,
+    /// The temperature of the chip, in °C. 25 °C by default.
+    Temperature,
+    /// The voltage of an analog AUXIO pin (below [`ANALOG_AUXIO_COUNT`]), in volts.
+    /// 0 V by default.
+    AuxIo(u8),
+}
+
+/// A source of the values of an [`AnalogInput`] in time.
+pub trait AnalogSource {
+    /// Returns the value at the time `now`.
+    ///
+    /// The calls are irregular: the value is sampled only when the emulated node measures it.
+    /// Time may go back between the calls: the sources are kept across `Emulator::restore`,
+    /// so a restored or reverse-stepped emulation samples again at earlier times.
+    /// To make such a replay identical to the original run, the value must be a pure function
+    /// of `now`.
+    fn sample(&mut self, now: Timepoint) -> f64;
+}
+
+pub type AnalogSourceImpl = Box<dyn AnalogSource + Send + Sync + UnwindSafe>;
+
+/// A constant value.
+impl AnalogSource for f64 {
+    fn sample(&mut self, _now: Timepoint) -> f64 {
+        *self
+    }
+}
+
+/// A value computed by a callback.
+impl<F: FnMut(Timepoint) -> f64> AnalogSource for F {
+    fn sample(&mut self, now: Timepoint) -> f64 {
+        self(now)
+    }
+}
+
+/// The sources attached to the analog inputs, a host-side service of the context.
+#[derive(Default)]
+pub(crate) struct AnalogInputs {
+    supply_voltage: Option<AnalogSourceImpl>,
+    temperature: Option<AnalogSourceImpl>,
+    auxio: [Option<AnalogSourceImpl>; ANALOG_AUXIO_COUNT as usize],
+}
+
+impl AnalogInputs {
+    /// Attaches the `source` to the `input`, returns the previous one.
+    pub(crate) fn set_source(
+        &mut self,
+        input: AnalogInput,
+        source: Option<AnalogSourceImpl>,
+    ) -> Option<AnalogSourceImpl> {
+        std::mem::replace(self.source_mut(input), source)
+    }
+
+    pub(crate) fn sample(&mut self, input: AnalogInput, now: Timepoint) -> f64 {
+        let default = match input {
+            AnalogInput::SupplyVoltage => DEFAULT_SUPPLY_VOLTAGE,
+            AnalogInput::Temperature => DEFAULT_TEMPERATURE,
+            AnalogInput::AuxIo(_) => 0.0,
+        };
+        self.source_mut(input)
+            .as_mut()
+            .map_or(default, |source| source.sample(now))
+    }
+
+    fn source_mut(&mut self, input: AnalogInput) -> &mut Option<AnalogSourceImpl> {
+        match input {
+            AnalogInput::SupplyVoltage => &mut self.supply_voltage,
+            AnalogInput::Temperature => &mut self.temperature,
+            AnalogInput::AuxIo(pin) => {
+                assert!(
+                    pin < ANALOG_AUXIO_COUNT,
+                    "AUXIO{pin} is not an input of the ADC."
+                );
+                &mut self.auxio[usize::from(pin)]
+            }
+        }
+    }
+}
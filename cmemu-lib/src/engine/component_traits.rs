@@ -4,6 +4,8 @@ pub(crate) use cmemu_proc_macros::{
     DisableableComponent, MainComponent, SkippableClockTreeNode, Subcomponent, TickComponent,
     TickComponentExtra,
 };
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 use std::hash::Hash;
 // export both derive macros and the traits
@@ -156,7 +158,15 @@ pub(crate) trait EnergyNode {
     ///
     /// These values come as `parent` in most methods, which are mostly useful for switches,
     /// but also for debugging purposes.
-    type IdSpace: Debug + Clone + Copy + PartialEq + Eq + Hash + enum_map::Enum;
+    type IdSpace: Debug
+        + Clone
+        + Copy
+        + PartialEq
+        + Eq
+        + Hash
+        + enum_map::Enum
+        + Serialize
+        + DeserializeOwned;
     /// A human-understandable name of this node (used only for logs).
     const NAME: &'static str;
 
@@ -192,7 +202,20 @@ pub(crate) trait ClockTreeNode: EnergyNode + Subcomponent {
 ///
 /// This enum is abstracted from states described in [TI-TRM] 6. Power, Reset, and Clock Management
 /// as well as sample docs for other uControllers.
-#[derive(Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, enum_map::Enum)]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Ord,
+    PartialOrd,
+    Eq,
+    PartialEq,
+    enum_map::Enum,
+    strum::EnumString,
+    Serialize,
+    Deserialize,
+)]
+#[non_exhaustive]
 pub enum PowerMode {
     /// The node is cut off from any voltage lines.
     /// It is going through the reset sequence when powered on again.
@@ -233,7 +256,9 @@ impl PowerMode {
 /// See also:
 /// - [ARM-TRM-G] Figure 7-4 Power down timing sequence
 /// - [ARM-TRM-G] A.5 Low power interface
-#[derive(Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, enum_map::Enum)]
+#[derive(
+    Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, enum_map::Enum, Serialize, Deserialize,
+)]
 pub(crate) enum CpuMode {
     /// Indicates that all the clock to the Cortex-M3 may be gated.
     /// For systems with WIC (such as cc2650), FCLK is gated as well,
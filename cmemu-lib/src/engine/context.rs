@@ -1,9 +1,15 @@
 use crate::build_data::EnergyEntity;
 use crate::common::utils::FromMarker;
-use crate::engine::{EventQueue, PowerMode};
+use crate::engine::analog::AnalogInputs;
+use crate::engine::energy::EnergyMeter;
+use crate::engine::{
+    AnalogInput, EventQueue, MemoryAccess, PowerMode, RadioActivity, ResetRequest,
+};
 use cmemu_common::Address;
 use enum_map::{EnumMap, enum_map};
+use serde::{Deserialize, Serialize};
 use std::fmt::Display;
+use std::mem;
 #[cfg(feature = "pretty_log")]
 use std::panic::UnwindSafe;
 
@@ -24,11 +30,35 @@ pub(crate) struct Context {
     cycle_no: u64,
     /// Ground truth state (nodes in transition report TODO state)
     pub(in crate::engine) energy_state: EnumMap<EnergyEntity, PowerMode>,
+    /// Reset requested by a component, applied by the emulator at the end of the phase.
+    reset_request: Option<ResetRequest>,
+    /// Components, which were powered off in this phase, thus lost their state.
+    powered_off: Vec<EnergyEntity>,
+    /// Energy accounting, enabled by the host.
+    pub(super) energy_meter: Option<EnergyMeter>,
+    /// Sources of the analog quantities, attached by the host.
+    pub(super) analog_inputs: AnalogInputs,
 
     #[cfg(feature = "pretty_log")]
     pub(super) symbols_service: Option<Box<dyn SymbolsService + Send + Sync + UnwindSafe>>,
 }
 
+/// The emulated part of the [`Context`], that is without the host-side services.
+/// Used for snapshots of the emulator.
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct ContextState {
+    queue: EventQueue,
+    node_id: u64,
+    cycle_no: u64,
+    energy_state: EnumMap<EnergyEntity, PowerMode>,
+}
+
+impl ContextState {
+    pub(crate) fn cycle_no(&self) -> u64 {
+        self.cycle_no
+    }
+}
+
 // TODO:
 //   slab would be better(?) without extra copies:
 //   - create without taking new elem
@@ -43,6 +73,10 @@ impl Context {
             cycle_no: 0,
             // TODO: call generated code with initial state
             energy_state: enum_map! {_ => PowerMode::Active},
+            reset_request: None,
+            powered_off: Vec::new(),
+            energy_meter: None,
+            analog_inputs: AnalogInputs::default(),
             #[cfg(feature = "pretty_log")]
             symbols_service: None,
         }
@@ -71,6 +105,58 @@ impl Context {
         self.cycle_no = cycle;
     }
 
+    pub(crate) fn save_state(&self) -> ContextState {
+        ContextState {
+            queue: self.queue.clone(),
+            node_id: self.node_id,
+            cycle_no: self.cycle_no,
+            energy_state: self.energy_state,
+        }
+    }
+
+    pub(crate) fn restore_state(&mut self, state: &ContextState) {
+        self.queue = state.queue.clone();
+        self.node_id = state.node_id;
+        self.cycle_no = state.cycle_no;
+        self.energy_state = state.energy_state;
+        self.sync_energy_meter();
+    }
+
+    /// Brings the emulated part of the context to the power-on state, but keeps the time
+    /// and the events scheduled by the host.
+    pub(super) fn reset(&mut self) {
+        self.queue.reset();
+        self.energy_state = enum_map! {_ => PowerMode::Active};
+        self.reset_request = None;
+        self.powered_off.clear();
+        self.sync_energy_meter();
+        self.set_radio_activity(RadioActivity::Idle);
+    }
+
+    /// Brings the energy meter in line with the energy state set without it.
+    fn sync_energy_meter(&mut self) {
+        let now = self.queue.get_current_time();
+        if let Some(ref mut meter) = self.energy_meter {
+            for (ent, &mode) in &self.energy_state {
+                meter.set_mode(now, ent, mode);
+            }
+        }
+    }
+
+    /// Requests a reset of the system, which happens at the end of the current phase.
+    /// Of the resets requested in the same phase, the most invasive one is applied.
+    pub(crate) fn request_reset(&mut self, request: ResetRequest) {
+        self.reset_request = Some(self.reset_request.map_or(request, |r| r.max(request)));
+    }
+
+    pub(super) fn take_reset_request(&mut self) -> Option<ResetRequest> {
+        self.reset_request.take()
+    }
+
+    pub(super) fn take_powered_off_components(&mut self) -> Vec<EnergyEntity> {
+        mem::take(&mut self.powered_off)
+    }
+
     pub(crate) fn event_queue(&self) -> &EventQueue {
         &self.queue
     }
@@ -91,7 +177,36 @@ impl Context {
     }
     // FIXME: this should be visible only from the ClockTree
     pub(crate) fn set_energy_state_of(&mut self, ent: EnergyEntity, state: PowerMode) {
+        if state == PowerMode::Off
+            && self.energy_state[ent] != PowerMode::Off
+            && matches!(ent, EnergyEntity::Component(_))
+        {
+            self.powered_off.push(ent);
+        }
         self.energy_state[ent] = state;
+        let now = self.queue.get_current_time();
+        if let Some(ref mut meter) = self.energy_meter {
+            meter.set_mode(now, ent, state);
+        }
+    }
+
+    pub(crate) fn set_radio_activity(&mut self, activity: RadioActivity) {
+        let now = self.queue.get_current_time();
+        if let Some(ref mut meter) = self.energy_meter {
+            meter.set_radio_activity(now, activity);
+        }
+    }
+
+    pub(crate) fn record_memory_access(&mut self, access: MemoryAccess) {
+        if let Some(ref mut meter) = self.energy_meter {
+            meter.record_access(access);
+        }
+    }
+
+    /// Returns the current value of the analog quantity `input`, as measured by a component.
+    pub(crate) fn sample_analog(&mut self, input: AnalogInput) -> f64 {
+        let now = self.queue.get_current_time();
+        self.analog_inputs.sample(input, now)
     }
 
     // TODO: decide if we display the raw address or only pretty name (then the name is misleading)
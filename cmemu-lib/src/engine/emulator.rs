@@ -6,8 +6,12 @@ use crate::component::{Components, PowerClockManager, WakeupEvent};
 use crate::proxy::{ClockTreeProxy, event_data::EventData};
 pub use component_api::EmulatorError;
 use log::{debug, info, log_enabled, trace};
+pub(crate) use reset::{ResetRequest, WarmResetSource};
+pub use snapshot::{EmulatorSnapshot, SnapshotError};
 
 mod component_api;
+mod reset;
+mod snapshot;
 
 #[allow(missing_debug_implementations)]
 pub struct Emulator {
@@ -79,6 +83,11 @@ impl Emulator {
                     self.context.event_queue().get_current_time(),
                     self.context.node_id()
                 );
+                if let WakeupEvent::Gpio = event {
+                    self.components
+                        .gpio
+                        .apply_scheduled_inputs(&mut self.context);
+                }
                 self.clock_tree
                     .external_wake_up(&mut self.context, &mut self.components);
             }
@@ -93,6 +102,11 @@ impl Emulator {
             }
             self.dispatch_event(payload);
         }
+
+        self.reset_powered_off_components();
+        if let Some(request) = self.context.take_reset_request() {
+            self.apply_reset(request);
+        }
     }
 
     pub fn trigger_radio_wakeup(&mut self, timepoint: Timepoint) {
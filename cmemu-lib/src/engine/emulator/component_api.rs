@@ -1,15 +1,22 @@
 //! Emulator public API (that is aware of the internal components).
 
 use super::Emulator;
-use crate::build_data::EnergyEntity;
 #[cfg(feature = "cycle-debug-logger")]
 use crate::common::new_ahb::signals::{TransferMeta, TransferType};
 use crate::component::core::CoreCoupledRegisterId;
-use crate::engine::PowerMode;
+use crate::component::gpio::{DIO_COUNT, GPIOComponent};
+use crate::component::trng::FRO_COUNT;
 #[cfg(feature = "pretty_log")]
 use crate::engine::context::SymbolsService;
+use crate::engine::energy::{EnergyMeter, entity_name};
+use crate::engine::{
+    AnalogInput, AnalogSourceImpl, CurrentTable, EnergyReport, EnergySample, PowerMode, Timepoint,
+};
 use crate::{
-    common::{Address, RegisterID, UARTLiteInterface, Word},
+    common::{
+        Address, GpioInterfaceImpl, I2cBusInterfaceImpl, RegisterID, SpiDeviceInterfaceImpl,
+        SsiModule, UARTInterfaceImpl, UARTLiteInterface, Word,
+    },
     component::rfc::ModemImpl,
 };
 use std::borrow::Borrow;
@@ -200,6 +207,13 @@ impl Emulator {
         }
     }
 
+    /// Returns the image of the whole Flash memory, including the changes made by
+    /// the emulated program through the Flash controller.
+    #[must_use]
+    pub fn get_flash_contents(&self) -> Vec<u8> {
+        self.components.flash.get_contents()
+    }
+
     // Send + Sync enforcement makes Emulator shared between threads safely.
     // return old value if possible, safe to drop
     #[cfg(feature = "pretty_log")]
@@ -218,7 +232,56 @@ impl Emulator {
         &mut self,
         interface: Option<Box<dyn UARTLiteInterface + Send + Sync + UnwindSafe>>,
     ) {
-        self.components.uart_lite.set_interface(interface);
+        self.components
+            .sensor_controller
+            .set_uart_lite_interface(interface);
+    }
+
+    /// Attach the host side of the UART0 serial line.
+    pub fn set_uart_interface(&mut self, interface: Option<UARTInterfaceImpl>) {
+        self.components.uart.set_interface(interface);
+    }
+
+    /// Attach a virtual device to the serial bus of an SSI module.
+    pub fn set_spi_device(&mut self, module: SsiModule, device: Option<SpiDeviceInterfaceImpl>) {
+        self.components.ssi.set_device(module, device);
+    }
+
+    /// Attach a virtual slave to the I2C bus of I2C0.
+    pub fn attach_i2c_device(&mut self, device: I2cBusInterfaceImpl) {
+        self.components.i2c.attach_device(device);
+    }
+
+    /// Attach the host observer of the DIO pads driven by the MCU.
+    pub fn set_gpio_interface(&mut self, interface: Option<GpioInterfaceImpl>) {
+        self.components.gpio.set_interface(interface);
+    }
+
+    /// Drive the `dio` pad to `level` from `time` on, or release it with `None`.
+    ///
+    /// A `time` in the past takes effect as soon as possible.
+    pub fn drive_pin(&mut self, time: Timepoint, dio: u8, level: Option<bool>) {
+        assert!(usize::from(dio) < DIO_COUNT, "There is no DIO{dio}.");
+        self.components
+            .gpio
+            .schedule_input(&mut self.context, time, dio, level);
+    }
+
+    /// Inject an alarm event of the free-running oscillator `fro` of the TRNG,
+    /// as if a repeating pattern was detected in its samples.
+    pub fn trigger_trng_fro_alarm(&mut self, fro: u8) {
+        assert!(fro < FRO_COUNT, "There is no FRO{fro} in the TRNG.");
+        self.components.trng.fro_alarm(&mut self.context, fro);
+    }
+
+    /// Attach the host source of the analog quantity `input`, or restore its default value
+    /// with `None`. Returns the previous source.
+    pub fn set_analog_source(
+        &mut self,
+        input: AnalogInput,
+        source: Option<AnalogSourceImpl>,
+    ) -> Option<AnalogSourceImpl> {
+        self.context.analog_inputs.set_source(input, source)
     }
 
     pub fn set_radio_interface(&mut self, interface: Option<ModemImpl>) {
@@ -279,10 +342,11 @@ impl Emulator {
     }
 
     pub fn get_leds_state(&self) -> LedsState {
+        let dout = self.components.gpio.dout();
         LedsState {
-            yellow: *self.components.gpio.yellow_led_state,
-            vled1: *self.components.gpio.v1_led_state,
-            vled2: *self.components.gpio.v2_led_state,
+            yellow: dout & (1 << GPIOComponent::YELLOW_LED_IO_ID) != 0,
+            vled1: dout & (1 << GPIOComponent::V1_LED_IO_ID) != 0,
+            vled2: dout & (1 << GPIOComponent::V2_LED_IO_ID) != 0,
         }
     }
 
@@ -304,7 +368,7 @@ impl Emulator {
             .automock
             .fcfg1_mac_15_4_0
             .unsafe_as_mut() = u32::from_le_bytes(mac_address[0..4].try_into().unwrap()).into();
-        self.components.mem_mock.set_rng_seed(id);
+        self.components.trng.set_node_id(id);
         #[cfg(feature = "cycle-debug-logger")]
         self.components
             .cycle_debug_logger
@@ -315,17 +379,42 @@ impl Emulator {
     pub fn unstable_get_components_energy_state(
         &self,
     ) -> impl Iterator<Item = (impl Borrow<str>, impl Borrow<PowerMode>)> {
-        fn render_ent(ent: EnergyEntity) -> &'static str {
-            match ent {
-                EnergyEntity::Component(c) => <&'static str as From<_>>::from(c),
-                EnergyEntity::ClockTree(c) => <&'static str as From<_>>::from(c),
-                EnergyEntity::Oscillator(o) => <&'static str as From<_>>::from(o),
-            }
-        }
         self.context
             .energy_state
             .iter()
-            .map(|(entity, mode)| (render_ent(entity), *mode))
+            .map(|(entity, mode)| (entity_name(entity), *mode))
+    }
+
+    /// Starts estimating the consumed energy with the given currents from now on.
+    ///
+    /// With `record_samples`, the consumption is also recorded as a time series,
+    /// see [`Emulator::take_energy_samples`].
+    pub fn enable_energy_meter(&mut self, table: CurrentTable, record_samples: bool) {
+        let now = self.context.event_queue().get_current_time();
+        self.context.energy_meter = Some(EnergyMeter::new(
+            table,
+            now,
+            self.context.energy_state,
+            record_samples,
+        ));
+    }
+
+    /// Returns the energy consumed since the energy meter was enabled.
+    pub fn energy_report(&self) -> Option<EnergyReport> {
+        let now = self.context.event_queue().get_current_time();
+        self.context
+            .energy_meter
+            .as_ref()
+            .map(|meter| meter.report(now))
+    }
+
+    /// Takes the samples of the consumption recorded since the last call.
+    pub fn take_energy_samples(&mut self) -> Vec<EnergySample> {
+        self.context
+            .energy_meter
+            .as_mut()
+            .map(EnergyMeter::take_samples)
+            .unwrap_or_default()
     }
 }
 
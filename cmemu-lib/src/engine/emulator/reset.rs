@@ -0,0 +1,201 @@
+//! Resets of the emulated system, requested by the components or by the host.
+//!
+//! There are three kinds of resets, which differ in the state they retain:
+//! - a warm reset resets the MCU, but retains the AON domain: the RTC, `AON_EVENT`, `AON_WUC`,
+//!   `AON_BATMON`, the Sensor Controller, and the mocked `AON_SYSCTL` and `AON_IOC` registers,
+//! - a system reset goes through a full power-up sequence, thus it resets also the AON domain,
+//!   and reports its source in `AON_SYSCTL:RESETCTL.RESET_SRC`,
+//! - a power-on reset clears also the contents of SRAM.
+//!
+//! A warm reset is converted to an emulated pin reset (a system reset reported as `WARMRESET`),
+//! if `PRCM:WARMRESET.WR_TO_PINRESET` is set. A wakeup from the shutdown is a system reset too.
+//!
+//! All the resets retain:
+//! - the contents of flash and ROM,
+//! - the emulation time, the cycle number and the node id,
+//! - the host-side objects and the pads driven by the host,
+//! - the wakeups scheduled by the host,
+//! - the generator of the TRNG numbers, so that they do not repeat.
+//!
+//! Moreover, components in a power domain lose their state when the domain is powered off,
+//! and come back in their reset state, when the power is restored.
+
+use super::Emulator;
+use crate::build_data::{Components as ComponentId, EnergyEntity};
+use crate::component::crypto::CryptoComponent;
+use crate::component::gpt::GPTComponent;
+use crate::component::i2c::I2CComponent;
+use crate::component::rfc::RFCComponent;
+use crate::component::trng::TRNGComponent;
+use crate::component::uart::UARTComponent;
+use crate::component::udma::UDMAComponent;
+use crate::component::{Components, PowerClockManager};
+use crate::engine::RadioActivity;
+use crate::proxy::ClockTreeProxy;
+use cc2650_constants::AON_SYSCTL::RESETCTL::RESET_SRC;
+use log::{debug, info};
+use std::mem;
+
+/// A reset requested by a component. The variants are ordered from the least invasive one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum ResetRequest {
+    Warm(WarmResetSource),
+    /// Requested with `AON_SYSCTL:RESETCTL.SYSRESET`.
+    SystemReset,
+    /// A pad configured with `IOC:IOCFGn.WU_CFG` changed in the shutdown mode.
+    ShutdownWakeup,
+}
+
+/// Sources of a warm reset, as listed in `PRCM:WARMRESET`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum WarmResetSource {
+    /// `AIRCR.SYSRESETREQ` written by the CPU.
+    SystemResetRequest,
+    /// The lockup of the CPU.
+    Lockup,
+    /// The second time-out of the WDT with the interrupt still pending.
+    Watchdog,
+}
+
+impl Emulator {
+    /// Resets the emulated system as by the reset pin.
+    pub fn pin_reset(&mut self) {
+        info!("Pin reset requested by the host");
+        self.system_reset(RESET_SRC::Named::PIN_RESET, false);
+    }
+
+    /// Resets the emulated system as by turning the power off and on again.
+    pub fn power_on_reset(&mut self) {
+        info!("Power-on reset requested by the host");
+        self.rebuild_components(false);
+    }
+
+    pub(super) fn apply_reset(&mut self, request: ResetRequest) {
+        info!(
+            "Reset requested: {request:?}, t={:#?} n={}",
+            self.context.event_queue().get_current_time(),
+            self.context.node_id()
+        );
+        match request {
+            ResetRequest::Warm(source) if self.components.prcm.is_warm_reset_to_pin_reset() => {
+                info!("The warm reset is converted to a pin reset");
+                self.system_reset(RESET_SRC::Named::WARMRESET, false);
+                self.components.prcm.record_warm_reset(source);
+            }
+            ResetRequest::Warm(source) => self.warm_reset(source),
+            ResetRequest::SystemReset => self.system_reset(RESET_SRC::Named::SYSRESET, false),
+            // The wakeup goes through the sequence of a pin reset and sets the `*WU_FROM_SD` flags.
+            ResetRequest::ShutdownWakeup => self.system_reset(RESET_SRC::Named::PIN_RESET, true),
+        }
+    }
+
+    /// Brings the components, which were powered off, to their power-on state.
+    /// The host-side objects attached to them are taken over.
+    pub(super) fn reset_powered_off_components(&mut self) {
+        let components = &mut self.components;
+        for entity in self.context.take_powered_off_components() {
+            let EnergyEntity::Component(component) = entity else {
+                unreachable!("Only components lose their state")
+            };
+            match component {
+                ComponentId::GPTComponent => components.gpt = GPTComponent::new(),
+                ComponentId::UDMAComponent => components.udma = UDMAComponent::new(),
+                ComponentId::CryptoComponent => components.crypto = CryptoComponent::new(),
+                ComponentId::TRNGComponent => {
+                    let old = mem::replace(&mut components.trng, TRNGComponent::new());
+                    components.trng.take_generator(&old);
+                }
+                ComponentId::UARTComponent => {
+                    let interface = components.uart.take_interface();
+                    components.uart = UARTComponent::new();
+                    components.uart.set_interface(interface);
+                }
+                ComponentId::I2CComponent => {
+                    let devices = components.i2c.take_devices();
+                    components.i2c = I2CComponent::new();
+                    components.i2c.set_devices(devices);
+                }
+                ComponentId::RFCComponent => {
+                    let interface = components.rfc.take_interface();
+                    components.rfc = RFCComponent::new();
+                    components.rfc.set_interface(interface);
+                    self.context.set_radio_activity(RadioActivity::Idle);
+                }
+                // Not implemented: the GPIO registers should be lost, but the component models
+                // the pads as well.
+                _ => {
+                    debug!("Losing the state of {component:?} is not implemented");
+                    continue;
+                }
+            }
+            info!("{component:?} was powered off, so it is reset");
+        }
+    }
+
+    fn warm_reset(&mut self, source: WarmResetSource) {
+        let old = self.rebuild_components(true);
+        let components = &mut self.components;
+
+        components.rtc = old.rtc;
+        components.rtc_shadow = old.rtc_shadow;
+        components.rtc_shadow.reset_keeping_registers();
+        components.aon_event = old.aon_event;
+        components.aon_event.reset_keeping_registers();
+        components.wuc = old.wuc;
+        components.wuc.reset_keeping_registers();
+        components.batmon = old.batmon;
+        components.batmon.reset_keeping_registers();
+        let uart_lite = components.sensor_controller.take_uart_lite_interface();
+        components.sensor_controller = old.sensor_controller;
+        components.sensor_controller.reset_keeping_registers();
+        components
+            .sensor_controller
+            .set_uart_lite_interface(uart_lite);
+        components.mem_mock.take_aon_registers(&old.mem_mock);
+
+        components.wuc.record_warm_reset();
+        components.prcm.record_warm_reset(source);
+    }
+
+    fn system_reset(&mut self, reset_src: u32, from_shutdown: bool) {
+        self.rebuild_components(true);
+        self.components
+            .mem_mock
+            .record_system_reset(reset_src, from_shutdown);
+    }
+
+    /// Replaces the components and the clock tree with the power-on ones,
+    /// and returns the old components for taking over the state retained by the reset.
+    /// The host-side state is always taken over, while the contents of SRAM only if `keep_sram`.
+    fn rebuild_components(&mut self, keep_sram: bool) -> Components {
+        let mut components = Components::new(&[], None);
+        self.move_host_interfaces_to(&mut components);
+        let mut old = mem::replace(&mut self.components, components);
+
+        self.context.reset();
+        self.clock_tree = PowerClockManager::new();
+        ClockTreeProxy.power_on_reset(&mut self.context);
+
+        let components = &mut self.components;
+        mem::swap(&mut components.flash, &mut old.flash);
+        components.flash.reset_keeping_contents();
+        mem::swap(&mut components.rom, &mut old.rom);
+        components.rom.reset_keeping_contents();
+        if keep_sram {
+            mem::swap(&mut components.sram, &mut old.sram);
+            components.sram.reset_keeping_contents();
+        }
+        components
+            .gpio
+            .take_host_inputs(&mut self.context, &mut old.gpio);
+        #[cfg(feature = "cycle-debug-logger")]
+        mem::swap(
+            &mut components.cycle_debug_logger,
+            &mut old.cycle_debug_logger,
+        );
+
+        self.set_node_id(self.context.node_id());
+        self.components.trng.take_generator(&old.trng);
+        old
+    }
+}
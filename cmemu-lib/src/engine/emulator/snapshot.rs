@@ -0,0 +1,126 @@
+//! Snapshots of the whole emulated state.
+//!
+//! A snapshot contains every component, the clock tree, the event queue and thus all the flops.
+//! Restoring it makes the emulation cycle-identical to the one that the snapshot was taken from.
+//!
+//! Host-side objects (the modem, the UART interface, the SPI and I2C devices, the GPIO
+//! observer, the analog sources, the symbols service) are not a part of the emulated state: they are never
+//! copied into a snapshot, and they stay attached
+//! to the emulator across ``Emulator::restore``.
+//! It is the user's responsibility to keep the state of such objects consistent.
+//!
+//! A snapshot may be saved to a file with [`EmulatorSnapshot::save`] and loaded back with
+//! [`EmulatorSnapshot::load`]. The format is private to the build of the emulator,
+//! so a file should be loaded only by the same version of the emulator that saved it.
+//! The Cycle Debug Logger is not emulated hardware, thus it is not a part of a saved snapshot:
+//! it starts afresh in a loaded one.
+
+use super::Emulator;
+use crate::common::SsiModule;
+use crate::component::{Components, PowerClockManager};
+use crate::engine::context::ContextState;
+use std::io::{Read, Write};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[non_exhaustive]
+pub enum SnapshotError {
+    #[error("snapshot I/O failed: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("invalid snapshot: {0}")]
+    Format(#[from] postcard::Error),
+}
+
+/// A frozen copy of the whole emulated state of an [`Emulator`].
+///
+/// It is cheap to restore many times, so it may serve as a common starting point
+/// for a number of experiments (e.g., after booting an OS).
+#[allow(missing_debug_implementations)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct EmulatorSnapshot {
+    components: Components,
+    clock_tree: PowerClockManager,
+    context: ContextState,
+}
+
+impl EmulatorSnapshot {
+    /// Number of the cycle at which the snapshot was taken.
+    pub fn cycle_no(&self) -> u64 {
+        self.context.cycle_no()
+    }
+
+    /// Write the snapshot to the `writer`, e.g., a file.
+    pub fn save(&self, mut writer: impl Write) -> Result<(), SnapshotError> {
+        writer.write_all(&postcard::to_stdvec(self)?)?;
+        Ok(())
+    }
+
+    /// Read a snapshot written by [`EmulatorSnapshot::save`] from the `reader`.
+    pub fn load(mut reader: impl Read) -> Result<Self, SnapshotError> {
+        // Some parts of the state (e.g., `FixedBitSet`) may only be decoded from borrowed bytes.
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        Ok(postcard::from_bytes(&data)?)
+    }
+}
+
+impl Emulator {
+    /// Take a snapshot of the whole emulated state.
+    ///
+    /// It should be called between steps, that is not in the middle of a phase.
+    pub fn snapshot(&self) -> EmulatorSnapshot {
+        EmulatorSnapshot {
+            components: self.components.clone(),
+            clock_tree: self.clock_tree.clone(),
+            context: self.context.save_state(),
+        }
+    }
+
+    /// Bring the emulator to the state captured in the `snapshot`.
+    ///
+    /// The snapshot doesn't need to come from this very emulator instance,
+    /// but the attached host interfaces are kept.
+    pub fn restore(&mut self, snapshot: &EmulatorSnapshot) {
+        let mut components = snapshot.components.clone();
+        self.move_host_interfaces_to(&mut components);
+
+        self.components = components;
+        self.clock_tree = snapshot.clock_tree.clone();
+        self.context.restore_state(&snapshot.context);
+    }
+
+    /// Move the host interfaces attached to the current components to the new `components`.
+    pub(super) fn move_host_interfaces_to(&mut self, components: &mut Components) {
+        components
+            .sensor_controller
+            .set_uart_lite_interface(self.components.sensor_controller.take_uart_lite_interface());
+        components
+            .rfc
+            .set_interface(self.components.rfc.take_interface());
+        components
+            .uart
+            .set_interface(self.components.uart.take_interface());
+        components
+            .i2c
+            .set_devices(self.components.i2c.take_devices());
+        components
+            .gpio
+            .set_interface(self.components.gpio.take_interface());
+        for module in [SsiModule::Ssi0, SsiModule::Ssi1] {
+            components
+                .ssi
+                .set_device(module, self.components.ssi.take_device(module));
+        }
+    }
+
+    /// Create a new emulator from a snapshot. There are no host interfaces attached.
+    pub fn from_snapshot(snapshot: &EmulatorSnapshot) -> Self {
+        let mut context = super::Context::new();
+        context.restore_state(&snapshot.context);
+        Self {
+            components: snapshot.components.clone(),
+            clock_tree: snapshot.clock_tree.clone(),
+            context,
+        }
+    }
+}
@@ -0,0 +1,410 @@
+//! Estimation of the energy consumed by the emulated node.
+//!
+//! The estimation is based on the power modes of the energy entities ([`EnergyEntity`]):
+//! every entity draws a supply current depending on its [`PowerMode`],
+//! which is integrated over the virtual time. On top of that, the radio draws a current
+//! depending on its [`RadioActivity`], and memory accesses ([`MemoryAccess`]) draw a fixed
+//! charge each.
+//!
+//! The default currents come from the Power Consumption tables of the CC2650 datasheet,
+//! which give a current of the whole chip in a given system state and the delta currents
+//! of the peripherals. We attribute them to the entities as follows:
+//! - the idle current (supply systems and RAM powered) to the `BusPowerDomain`,
+//!   with the standby and shutdown currents as its retention and off currents,
+//! - the rest of the active current of the MCU (at 48 MHz) to the `CoreGate`,
+//! - the cache retention part of the standby current to the `VimsPowerDomain`,
+//! - the peripheral delta currents to the power domains and clock gates of the peripherals.
+//!
+//! The datasheet doesn't give any figures of the Flash and cache accesses,
+//! so they are included in the active current of the MCU and the access charges are zero
+//! by default. Set them to split the active current into the core and the memory parts.
+
+use crate::build_data::{ClockTreeNodes, EnergyEntity};
+use crate::engine::{Duration, PICOS_IN_SECOND, PowerMode, Timepoint};
+use enum_map::{Enum, EnumMap, enum_map};
+use std::fmt::{Display, Formatter};
+use thiserror::Error;
+
+/// The supply voltage of the typical conditions of the datasheet.
+const DEFAULT_SUPPLY_VOLTAGE: f64 = 3.0;
+
+/// The state of the radio as seen by the energy accounting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum, strum::EnumString, strum::IntoStaticStr)]
+#[non_exhaustive]
+pub enum RadioActivity {
+    Idle,
+    /// Receiving or listening.
+    Rx,
+    /// Transmitting.
+    Tx,
+}
+
+/// A memory access drawing a fixed charge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum, strum::EnumString, strum::IntoStaticStr)]
+#[non_exhaustive]
+pub enum MemoryAccess {
+    /// A read of the Flash memory (a line fetch of the cache or a read bypassing it).
+    FlashRead,
+    /// A read served from the cache RAM.
+    CacheHit,
+}
+
+#[derive(Error, Debug)]
+#[non_exhaustive]
+pub enum EnergyConfigError {
+    #[error("unknown energy entity `{0}`")]
+    UnknownEntity(String),
+}
+
+/// Currents drawn by the parts of the node, used by the energy estimation.
+#[derive(Clone, Debug)]
+pub struct CurrentTable {
+    /// The current drawn by each entity in each power mode, in µA.
+    entities: EnumMap<EnergyEntity, EnumMap<PowerMode, f64>>,
+    /// The current drawn by the radio on top of the RF core, in µA.
+    radio: EnumMap<RadioActivity, f64>,
+    /// The charge drawn by a single access, in pC.
+    accesses: EnumMap<MemoryAccess, f64>,
+    supply_voltage: f64,
+}
+
+impl Default for CurrentTable {
+    /// The currents of the CC2650 datasheet, see the module documentation.
+    fn default() -> Self {
+        use ClockTreeNodes::*;
+
+        let mut entities = enum_map! {_ => enum_map! {_ => 0.0}};
+        let mut powered = |node, micro_amps| {
+            entities[EnergyEntity::ClockTree(node)][PowerMode::Active] = micro_amps;
+            entities[EnergyEntity::ClockTree(node)][PowerMode::ClockGated] = micro_amps;
+        };
+        powered(BusPowerDomain, 550.0);
+        powered(PeriphPowerDomain, 20.0);
+        powered(SerialPowerDomain, 13.0);
+        powered(RfcorePowerDomain, 237.0);
+        entities[EnergyEntity::ClockTree(BusPowerDomain)][PowerMode::Retention] = 1.0;
+        entities[EnergyEntity::ClockTree(BusPowerDomain)][PowerMode::Off] = 0.15;
+        entities[EnergyEntity::ClockTree(VimsPowerDomain)][PowerMode::Retention] = 1.5;
+
+        // Clock gates draw the current only with a ticking clock.
+        let clocked = [
+            // 1.45 mA + 31 µA/MHz at 48 MHz, less the idle current.
+            (CoreGate, 2388.0),
+            (DmaGate, 130.0),
+            (Gpt0Gate, 113.0),
+            (Gpt1Gate, 113.0),
+            (Gpt2Gate, 113.0),
+            (Gpt3Gate, 113.0),
+            (I2cGate, 12.0),
+            (Ssi0Gate, 93.0),
+            (Ssi1Gate, 93.0),
+            (UartGate, 164.0),
+        ];
+        for (node, micro_amps) in clocked {
+            entities[EnergyEntity::ClockTree(node)][PowerMode::Active] = micro_amps;
+        }
+
+        Self {
+            entities,
+            // At 0 dBm of the output power.
+            radio: enum_map! {
+                RadioActivity::Idle => 0.0,
+                RadioActivity::Rx => 5900.0,
+                RadioActivity::Tx => 6100.0,
+            },
+            accesses: enum_map! {_ => 0.0},
+            supply_voltage: DEFAULT_SUPPLY_VOLTAGE,
+        }
+    }
+}
+
+impl CurrentTable {
+    /// Sets the current drawn by the entity named as in
+    /// [`Emulator::unstable_get_components_energy_state`](crate::engine::Emulator::unstable_get_components_energy_state).
+    pub fn set_entity_current(
+        &mut self,
+        entity: &str,
+        mode: PowerMode,
+        micro_amps: f64,
+    ) -> Result<(), EnergyConfigError> {
+        let entity = (0..EnergyEntity::LENGTH)
+            .map(EnergyEntity::from_usize)
+            .find(|&ent| entity_name(ent) == entity)
+            .ok_or_else(|| EnergyConfigError::UnknownEntity(entity.to_owned()))?;
+        self.entities[entity][mode] = micro_amps;
+        Ok(())
+    }
+
+    pub fn set_radio_current(&mut self, activity: RadioActivity, micro_amps: f64) {
+        self.radio[activity] = micro_amps;
+    }
+
+    pub fn set_access_charge(&mut self, access: MemoryAccess, pico_coulombs: f64) {
+        self.accesses[access] = pico_coulombs;
+    }
+
+    pub fn set_supply_voltage(&mut self, volts: f64) {
+        self.supply_voltage = volts;
+    }
+}
+
+/// A point of the time series of the consumption, taken when the current changes.
+#[derive(Clone, Copy, Debug)]
+#[non_exhaustive]
+pub struct EnergySample {
+    pub time: Timepoint,
+    /// The current drawn from this point on, in µA.
+    pub current: f64,
+    /// The energy consumed until this point, in µJ.
+    pub energy: f64,
+}
+
+/// The energy consumed since the energy meter was enabled.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct EnergyReport {
+    pub duration: Duration,
+    /// The total energy, in µJ.
+    pub energy: f64,
+    /// The energy of each consumer with a non-zero consumption in the descending order, in µJ.
+    pub breakdown: Vec<(&'static str, f64)>,
+    pub flash_reads: u64,
+    pub cache_hits: u64,
+    pub supply_voltage: f64,
+}
+
+impl EnergyReport {
+    /// The average current, in µA.
+    pub fn average_current(&self) -> f64 {
+        self.energy / self.supply_voltage / seconds(self.duration)
+    }
+}
+
+impl Display for EnergyReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Consumed {:.3} uJ in {:.6} s, {:.3} uA on average ({} Flash reads, {} cache hits).",
+            self.energy,
+            seconds(self.duration),
+            self.average_current(),
+            self.flash_reads,
+            self.cache_hits
+        )?;
+        for (consumer, energy) in &self.breakdown {
+            writeln!(f, "{consumer:>24}: {energy:12.3} uJ")?;
+        }
+        Ok(())
+    }
+}
+
+/// Integrates the currents of the [`CurrentTable`] over the virtual time.
+pub(crate) struct EnergyMeter {
+    table: CurrentTable,
+    start: Timepoint,
+    modes: EnumMap<EnergyEntity, PowerMode>,
+    /// Charge drawn by each entity until its `since` timepoint, in µC.
+    entity_charge: EnumMap<EnergyEntity, f64>,
+    since: EnumMap<EnergyEntity, Timepoint>,
+    radio: RadioActivity,
+    radio_charge: EnumMap<RadioActivity, f64>,
+    radio_since: Timepoint,
+    accesses: EnumMap<MemoryAccess, u64>,
+    /// Charge drawn by the whole node until `last_change`, in µC.
+    total_charge: f64,
+    /// Current drawn by the whole node since `last_change`, in µA.
+    current: f64,
+    last_change: Timepoint,
+    samples: Option<Vec<EnergySample>>,
+}
+
+impl EnergyMeter {
+    pub(crate) fn new(
+        table: CurrentTable,
+        now: Timepoint,
+        modes: EnumMap<EnergyEntity, PowerMode>,
+        record_samples: bool,
+    ) -> Self {
+        let current = modes
+            .iter()
+            .map(|(ent, &mode)| table.entities[ent][mode])
+            .sum::<f64>()
+            + table.radio[RadioActivity::Idle];
+        let mut meter = Self {
+            table,
+            start: now,
+            modes,
+            entity_charge: enum_map! {_ => 0.0},
+            since: enum_map! {_ => now},
+            radio: RadioActivity::Idle,
+            radio_charge: enum_map! {_ => 0.0},
+            radio_since: now,
+            accesses: enum_map! {_ => 0},
+            total_charge: 0.0,
+            current,
+            last_change: now,
+            samples: record_samples.then(Vec::new),
+        };
+        meter.record_sample(now);
+        meter
+    }
+
+    pub(crate) fn set_mode(&mut self, now: Timepoint, ent: EnergyEntity, mode: PowerMode) {
+        let old_mode = std::mem::replace(&mut self.modes[ent], mode);
+        let old_current = self.table.entities[ent][old_mode];
+        self.entity_charge[ent] += old_current * seconds(now - self.since[ent]);
+        self.since[ent] = now;
+        self.change_current(now, self.table.entities[ent][mode] - old_current);
+    }
+
+    pub(crate) fn set_radio_activity(&mut self, now: Timepoint, activity: RadioActivity) {
+        let old_activity = std::mem::replace(&mut self.radio, activity);
+        let old_current = self.table.radio[old_activity];
+        self.radio_charge[old_activity] += old_current * seconds(now - self.radio_since);
+        self.radio_since = now;
+        self.change_current(now, self.table.radio[activity] - old_current);
+    }
+
+    pub(crate) fn record_access(&mut self, access: MemoryAccess) {
+        self.accesses[access] += 1;
+        self.total_charge += self.table.accesses[access] * 1e-6;
+    }
+
+    fn change_current(&mut self, now: Timepoint, delta: f64) {
+        if delta == 0.0 {
+            return;
+        }
+        self.total_charge += self.current * seconds(now - self.last_change);
+        self.current += delta;
+        self.last_change = now;
+        self.record_sample(now);
+    }
+
+    fn record_sample(&mut self, now: Timepoint) {
+        let energy = self.total_charge * self.table.supply_voltage;
+        if let Some(ref mut samples) = self.samples {
+            // Changes at the same timepoint leave only the last current.
+            if samples.last().is_some_and(|s| s.time == now) {
+                samples.pop();
+            }
+            samples.push(EnergySample {
+                time: now,
+                current: self.current,
+                energy,
+            });
+        }
+    }
+
+    pub(crate) fn take_samples(&mut self) -> Vec<EnergySample> {
+        self.samples
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn report(&self, now: Timepoint) -> EnergyReport {
+        let voltage = self.table.supply_voltage;
+        let entities = self.modes.iter().map(|(ent, &mode)| {
+            let pending = self.table.entities[ent][mode] * seconds(now - self.since[ent]);
+            (entity_name(ent), self.entity_charge[ent] + pending)
+        });
+        let radio = self.radio_charge.iter().map(|(activity, &charge)| {
+            let pending = if activity == self.radio {
+                self.table.radio[activity] * seconds(now - self.radio_since)
+            } else {
+                0.0
+            };
+            let name = match activity {
+                RadioActivity::Idle => "Radio idle",
+                RadioActivity::Rx => "Radio RX",
+                RadioActivity::Tx => "Radio TX",
+            };
+            (name, charge + pending)
+        });
+        let accesses = self.accesses.iter().map(|(access, &count)| {
+            #[allow(clippy::cast_precision_loss, reason = "We're fine with an estimation")]
+            let charge = self.table.accesses[access] * 1e-6 * count as f64;
+            (<&'static str>::from(access), charge)
+        });
+        let mut breakdown: Vec<_> = entities
+            .chain(radio)
+            .chain(accesses)
+            .filter(|&(_, charge)| charge > 0.0)
+            .map(|(name, charge)| (name, charge * voltage))
+            .collect();
+        breakdown.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+
+        EnergyReport {
+            duration: now - self.start,
+            energy: (self.total_charge + self.current * seconds(now - self.last_change)) * voltage,
+            breakdown,
+            flash_reads: self.accesses[MemoryAccess::FlashRead],
+            cache_hits: self.accesses[MemoryAccess::CacheHit],
+            supply_voltage: voltage,
+        }
+    }
+}
+
+pub(crate) fn entity_name(ent: EnergyEntity) -> &'static str {
+    match ent {
+        EnergyEntity::Component(c) => <&'static str as From<_>>::from(c),
+        EnergyEntity::ClockTree(c) => <&'static str as From<_>>::from(c),
+        EnergyEntity::Oscillator(o) => <&'static str as From<_>>::from(o),
+    }
+}
+
+#[allow(clippy::cast_precision_loss, reason = "We're fine with an estimation")]
+fn seconds(duration: Duration) -> f64 {
+    duration.as_picos() as f64 / PICOS_IN_SECOND as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integrates_currents() {
+        let core = EnergyEntity::ClockTree(ClockTreeNodes::CoreGate);
+        let mut table = CurrentTable {
+            entities: enum_map! {_ => enum_map! {_ => 0.0}},
+            ..Default::default()
+        };
+        table.entities[core][PowerMode::Active] = 1000.0;
+        table.set_radio_current(RadioActivity::Tx, 2000.0);
+        table.set_access_charge(MemoryAccess::FlashRead, 1e6);
+        table.set_supply_voltage(2.0);
+
+        let ms = |t| Timepoint::from_picos(t * PICOS_IN_SECOND / 1000);
+        let mut meter = EnergyMeter::new(table, ms(1), enum_map! {_ => PowerMode::Off}, true);
+        meter.set_mode(ms(1), core, PowerMode::Active);
+        meter.set_radio_activity(ms(3), RadioActivity::Tx);
+        meter.set_mode(ms(4), core, PowerMode::ClockGated);
+        meter.record_access(MemoryAccess::FlashRead);
+        meter.set_radio_activity(ms(5), RadioActivity::Idle);
+
+        // 3 ms of 1 mA, 2 ms of 2 mA and 1 uC at 2 V.
+        let report = meter.report(ms(11));
+        assert!((report.energy - 16.0).abs() < 1e-9, "{report}");
+        assert_eq!(
+            report.duration,
+            Duration::from_picos(10 * PICOS_IN_SECOND / 1000)
+        );
+        let names: Vec<_> = report.breakdown.iter().map(|&(name, _)| name).collect();
+        assert_eq!(names, ["Radio TX", "CoreGate", "FlashRead"]);
+
+        let samples: Vec<_> = meter
+            .take_samples()
+            .iter()
+            .map(|s| (s.time, s.current))
+            .collect();
+        assert_eq!(
+            samples,
+            [
+                (ms(1), 1000.0),
+                (ms(3), 3000.0),
+                (ms(4), 2000.0),
+                (ms(5), 0.0)
+            ]
+        );
+    }
+}
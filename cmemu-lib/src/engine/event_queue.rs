@@ -5,11 +5,12 @@ use crate::proxy::event_data::{COMPONENTS_COUNT, EventData, HANDLERS_COUNT};
 use enum_map::Enum;
 use heapless::Deque as ArrayDeque;
 use heapless::sorted_linked_list::{Min, SortedLinkedList};
+use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::mem::swap;
 use std::ops::Not;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub(super) struct Event {
     timepoint: Timepoint,
     // unique-ish ID used for revocations
@@ -36,7 +37,7 @@ impl Ord for Event {
 impl Eq for Event {}
 
 /// Revocation token for future-scheduled events
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct EventRevokeToken {
     timepoint: Timepoint,
     // unique-ish ID used for revocations
@@ -81,15 +82,73 @@ type FutureQueue = SortedLinkedList<Event, Min, QUEUE_FUTURE_MAX_EVENTS, u8>;
 // Intended use:
 // events in the same tick should go with delay = 0; their order is preserved
 // only ticks should have delay != 0; their order in same future timepoint is not preserved
+#[derive(Serialize, Deserialize)]
 pub(crate) struct EventQueue {
     current_time: Timepoint,
     queue_now: ArrayDeque<EventData, QUEUE_NOW_MAX_EVENTS>,
+    #[serde(with = "future_queue")]
     queue_future: FutureQueue,
+    #[serde(with = "future_queue")]
     overflow_buffer: FutureQueue,
     // Used for revocations
     events_counter: u32,
 }
 
+impl Clone for EventQueue {
+    fn clone(&self) -> Self {
+        Self {
+            current_time: self.current_time,
+            queue_now: self.queue_now.clone(),
+            queue_future: clone_future_queue(&self.queue_future),
+            overflow_buffer: clone_future_queue(&self.overflow_buffer),
+            events_counter: self.events_counter,
+        }
+    }
+}
+
+/// `SortedLinkedList` is not `Clone`, so rebuild it by pushing the events.
+fn clone_future_queue(queue: &FutureQueue) -> FutureQueue {
+    let events: Vec<&Event> = queue.iter().collect();
+    future_queue_from_sorted(events.into_iter().cloned())
+        .expect("Cloned queue has the same capacity")
+}
+
+/// An event is inserted before the ones with an equal timepoint, thus we push
+/// in the reverse order to keep the order of the same-timepoint events intact.
+fn future_queue_from_sorted(
+    events: impl DoubleEndedIterator<Item = Event>,
+) -> Result<FutureQueue, Event> {
+    let mut new_queue = FutureQueue::new_u8();
+    for ev in events.rev() {
+        new_queue.push(ev)?;
+    }
+    Ok(new_queue)
+}
+
+/// `SortedLinkedList` doesn't implement `serde` traits, so it is stored as a sorted sequence.
+mod future_queue {
+    use super::{Event, FutureQueue, future_queue_from_sorted};
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(
+        queue: &FutureQueue,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        // The length has to be known upfront by some formats.
+        queue.iter().collect::<Vec<_>>().serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<FutureQueue, D::Error> {
+        let events = Vec::<Event>::deserialize(deserializer)?;
+        let len = events.len();
+        future_queue_from_sorted(events.into_iter())
+            .map_err(|_| D::Error::invalid_length(len, &"a queue within its capacity"))
+    }
+}
+
 impl EventQueue {
     pub(super) fn new() -> Self {
         Self {
@@ -101,6 +160,20 @@ impl EventQueue {
         }
     }
 
+    /// Drops all the events, except for the wakeups scheduled by the host, keeping the time.
+    pub(super) fn reset(&mut self) {
+        debug_assert!(self.queue_now.is_empty(), "Reset in the middle of a phase");
+        for queue in [&mut self.queue_future, &mut self.overflow_buffer] {
+            let mut kept = FutureQueue::new_u8();
+            while let Some(ev) = queue.pop() {
+                if matches!(ev.payload, EventData::Wakeup(_)) {
+                    kept.push(ev).expect("Kept queue has the same capacity");
+                }
+            }
+            *queue = kept;
+        }
+    }
+
     pub(crate) fn add(&mut self, delay: Duration, payload: EventData) -> Option<EventRevokeToken> {
         // TODO: verify that it inlines enough or should we have a dedicated method?
         if delay == Duration::ZERO {
@@ -1,4 +1,6 @@
 use owo_colors::OwoColorize;
+use serde::ser::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 #[cfg(debug_assertions)]
 use std::cell::Cell;
 use std::marker::PhantomData;
@@ -39,6 +41,7 @@ use std::{fmt, mem};
 /// `Flop` is meant for temporary variables that must be used in a cycle later
 /// after setting the value, then the value is erased.
 /// `FlopMemoryBank`, on the other hand, is a memory - it always has a value.
+#[derive(Clone, Serialize, Deserialize)]
 pub(crate) struct Flop<T, M> {
     // note: big fields earlier, small fields later - save memory on padding
     // TODO: it would be way efficient if we did the flipping globally instead of locally.
@@ -54,9 +57,13 @@ pub(crate) struct Flop<T, M> {
     was_ignored: Cell<bool>,
 }
 
+#[derive(Clone)]
 pub(crate) struct SeqFlopMarker;
+#[derive(Clone)]
 pub(crate) struct BufferFlopMarker;
+#[derive(Clone)]
 pub(crate) struct CombFlopMarker;
+#[derive(Clone)]
 pub(crate) struct LatchFlopMarker;
 
 pub(crate) type SeqFlop<T> = Flop<T, SeqFlopMarker>;
@@ -488,6 +495,7 @@ where
 /// Transparent latch either returns value from the previous cycle (if latched)
 /// or is a pass-through otherwise.
 #[allow(dead_code)]
+#[derive(Clone)]
 pub(crate) struct TransparentLatch<T: Clone> {
     data: Option<T>,
     // Handles safety checks
@@ -602,6 +610,10 @@ where
 /// is the same as in case of `Flop`.
 ///
 /// Note: see `Flop` doc-string for the difference between it and this type.
+///
+/// Only the current value is serialized: a pending mutator is a function pointer,
+/// so a bank cannot be serialized in the middle of a cycle.
+#[derive(Clone)]
 pub(crate) struct FlopMemoryBank<T, D, M> {
     data: T,
     next_mutator: Option<(D, MutatorFunction<T, D>)>,
@@ -627,7 +639,94 @@ where
         )
     }
 }
+impl<T, D, M> Serialize for FlopMemoryBank<T, D, M>
+where
+    T: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.serializable_data::<S>()?.serialize(serializer)
+    }
+}
+
+impl<'de, T, D, M> Deserialize<'de> for FlopMemoryBank<T, D, M>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+        T::deserialize(deserializer).map(Self::new)
+    }
+}
+
+/// Serialization of a `FlopMemoryBank` of an array, for `#[serde(with)]`.
+///
+/// `serde` implements its traits only for arrays of up to 32 elements.
+pub(crate) mod serde_array_bank {
+    use super::FlopMemoryBank;
+    use crate::common::utils::serde_with::array;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub(crate) fn serialize<S, T, D, M, const N: usize>(
+        bank: &FlopMemoryBank<[T; N], D, M>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: Serialize,
+    {
+        array::serialize(bank.serializable_data::<S>()?, serializer)
+    }
+
+    pub(crate) fn deserialize<'de, De, T, D, M, const N: usize>(
+        deserializer: De,
+    ) -> Result<FlopMemoryBank<[T; N], D, M>, De::Error>
+    where
+        De: Deserializer<'de>,
+        T: Deserialize<'de>,
+    {
+        array::deserialize(deserializer).map(FlopMemoryBank::new)
+    }
+}
+
+/// Serialization of a `FlopMemoryBank` of a generated hardware register (which doesn't
+/// implement `serde` traits) as its raw value, for `#[serde(with)]`.
+pub(crate) mod serde_hw_register_bank {
+    use super::FlopMemoryBank;
+    use crate::common::utils::serde_with::hw_register;
+    use serde::{Deserializer, Serializer};
+
+    pub(crate) fn serialize<S, T, D, M>(
+        bank: &FlopMemoryBank<T, D, M>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: Copy + Into<u32>,
+    {
+        hw_register::serialize(bank.serializable_data::<S>()?, serializer)
+    }
+
+    pub(crate) fn deserialize<'de, De, T, D, M>(
+        deserializer: De,
+    ) -> Result<FlopMemoryBank<T, D, M>, De::Error>
+    where
+        De: Deserializer<'de>,
+        T: From<u32>,
+    {
+        hw_register::deserialize(deserializer).map(FlopMemoryBank::new)
+    }
+}
+
 impl<T, D, M> FlopMemoryBank<T, D, M> {
+    fn serializable_data<S: Serializer>(&self) -> Result<&T, S::Error> {
+        if self.next_mutator.is_some() {
+            Err(S::Error::custom(
+                "FlopMemoryBank with a pending mutator cannot be serialized",
+            ))
+        } else {
+            Ok(&self.data)
+        }
+    }
+
     pub(crate) fn new(value: T) -> Self {
         Self {
             data: value,
@@ -720,11 +819,44 @@ impl<T, D, M> Deref for FlopMemoryBank<T, D, M> {
 /// In contrast to the former, the compiler can optimize `tick` method, because there is
 /// no user-supplied function pointer.
 /// It is designed to be used with small types (that are copy) or move types that change very rarely.
+#[derive(Clone, Serialize, Deserialize)]
 pub(crate) struct Register<T, M> {
     data: T,
     next: Option<T>,
     type_marker: PhantomData<M>,
 }
+/// Serialization of a `Register` of a generated hardware register (which doesn't implement
+/// `serde` traits) as its raw value, for `#[serde(with)]`.
+pub(crate) mod serde_hw_register {
+    use super::Register;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::marker::PhantomData;
+
+    pub(crate) fn serialize<S, T, M>(
+        register: &Register<T, M>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: Copy + Into<u32>,
+    {
+        (register.data.into(), register.next.map(Into::into)).serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D, T, M>(deserializer: D) -> Result<Register<T, M>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: From<u32>,
+    {
+        let (data, next) = <(u32, Option<u32>)>::deserialize(deserializer)?;
+        Ok(Register {
+            data: data.into(),
+            next: next.map(Into::into),
+            type_marker: PhantomData,
+        })
+    }
+}
+
 pub(crate) type SeqFlopMemoryBankSimple<T> = Register<T, SeqFlopMarker>;
 pub(crate) type SeqRegister<T> = Register<T, SeqFlopMarker>;
 /// The use is discouraged.
@@ -766,6 +898,7 @@ impl<T, M> Register<T, M> {
         self.next.is_none()
     }
 
+    #[allow(dead_code)]
     #[track_caller]
     pub(crate) fn peek_next(&self) -> Option<&T> {
         self.next.as_ref()
@@ -1,10 +1,14 @@
 use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign};
 
+use serde::{Deserialize, Serialize};
+
 pub const PICOS_IN_SECOND: u64 = 1_000_000_000_000;
 
 macro_rules! def_time_type {
     ($name:ident) => {
-        #[derive(Clone, Copy, Default, PartialOrd, Ord, PartialEq, Eq, Hash)]
+        #[derive(
+            Clone, Copy, Default, PartialOrd, Ord, PartialEq, Eq, Hash, Serialize, Deserialize,
+        )]
         pub struct $name {
             picoseconds: u64,
         }
@@ -0,0 +1,212 @@
+//! IEEE 802.15.4 (2.4 GHz O-QPSK PHY) frames as seen by the medium.
+//!
+//! A frame is kept in the format exchanged with the RFC through the `ModemInterface`:
+//! the PHY header (length) byte followed by the PSDU (MAC frame) including the FCS.
+
+use cmemu_lib::engine::Duration;
+
+/// 250 kbps, i.e., two 16 µs symbols per byte.
+const BYTE_DURATION: Duration = Duration::from_picos(32_000_000);
+/// Preamble (4 bytes), SFD (1 byte) and PHR (1 byte)
+const SYNC_HEADER_AND_PHR_LEN: u64 = 6;
+/// `aMaxPHYPacketSize`
+const MAX_PSDU_LEN: usize = 127;
+const FCS_LEN: usize = 2;
+/// Frame control, sequence number and FCS
+const ACK_PSDU_LEN: u8 = 5;
+
+/// `aTurnaroundTime`: 12 symbols, the RX-to-TX (and TX-to-RX) switching time.
+pub(crate) const TURNAROUND_TIME: Duration = Duration::from_picos(192_000_000);
+/// The on-air duration of the longest possible frame.
+pub(crate) const MAX_FRAME_DURATION: Duration = Duration::from_picos(
+    BYTE_DURATION.as_picos() * (SYNC_HEADER_AND_PHR_LEN + MAX_PSDU_LEN as u64),
+);
+
+const FRAME_TYPE_ACK: u16 = 0b010;
+const FCF_FRAME_TYPE_MASK: u16 = 0b111;
+const FCF_ACK_REQUEST: u16 = 1 << 5;
+const FCF_DST_ADDR_MODE_SHIFT: u16 = 10;
+const ADDR_MODE_SHORT: u16 = 0b10;
+const ADDR_MODE_EXT: u16 = 0b11;
+const BROADCAST_PAN_ID: u16 = 0xFFFF;
+const BROADCAST_SHORT_ADDR: u16 = 0xFFFF;
+
+/// Addresses of a node, as configured with `ModemOp::SetAddr`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct NodeAddress {
+    pub(crate) pan_id: u16,
+    pub(crate) short: u16,
+    pub(crate) ext: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Frame(Vec<u8>);
+
+impl Frame {
+    /// Build a frame from bytes pushed to the TX FIFO, that is the PHY header and the payload.
+    ///
+    /// The RFC doesn't compute the FCS, so it is appended here if missing.
+    pub(crate) fn from_tx_fifo(mut fifo: Vec<u8>) -> Option<Self> {
+        let psdu_len = usize::from(*fifo.first()?);
+        if !(FCS_LEN..=MAX_PSDU_LEN).contains(&psdu_len) {
+            return None;
+        }
+        if fifo.len() == psdu_len + 1 - FCS_LEN {
+            let fcs = fcs(&fifo[1..]);
+            fifo.extend_from_slice(&fcs.to_le_bytes());
+        }
+        (fifo.len() == psdu_len + 1).then_some(Self(fifo))
+    }
+
+    /// An immediate acknowledgment frame for a frame with the sequence number `seq`.
+    pub(crate) fn ack(seq: u8) -> Self {
+        let mut bytes = vec![ACK_PSDU_LEN];
+        bytes.extend_from_slice(&FRAME_TYPE_ACK.to_le_bytes());
+        bytes.push(seq);
+        let fcs = fcs(&bytes[1..]);
+        bytes.extend_from_slice(&fcs.to_le_bytes());
+        Self(bytes)
+    }
+
+    pub(crate) fn bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// How long the frame is on air.
+    pub(crate) fn duration(&self) -> Duration {
+        BYTE_DURATION * (SYNC_HEADER_AND_PHR_LEN + self.0.len() as u64 - 1)
+    }
+
+    fn psdu(&self) -> &[u8] {
+        &self.0[1..]
+    }
+
+    fn frame_control(&self) -> Option<u16> {
+        let psdu = self.psdu();
+        (psdu.len() >= 3 + FCS_LEN).then(|| u16::from_le_bytes([psdu[0], psdu[1]]))
+    }
+
+    /// Returns the sequence number if the frame should be acknowledged by a node with `address`.
+    ///
+    /// Broadcast frames are never acknowledged.
+    pub(crate) fn ack_request_for(&self, address: &NodeAddress) -> Option<u8> {
+        let fcf = self.frame_control()?;
+        if fcf & FCF_ACK_REQUEST == 0 || fcf & FCF_FRAME_TYPE_MASK == FRAME_TYPE_ACK {
+            return None;
+        }
+        let psdu = self.psdu();
+        let seq = psdu[2];
+        let field = |offset: usize, len: usize| psdu.get(offset..offset + len);
+        let pan_id = u16::from_le_bytes(field(3, 2)?.try_into().ok()?);
+        if pan_id != address.pan_id && pan_id != BROADCAST_PAN_ID {
+            return None;
+        }
+        let addressed = match (fcf >> FCF_DST_ADDR_MODE_SHIFT) & 0b11 {
+            ADDR_MODE_SHORT => {
+                let short = u16::from_le_bytes(field(5, 2)?.try_into().ok()?);
+                short == address.short && short != BROADCAST_SHORT_ADDR
+            }
+            ADDR_MODE_EXT => u64::from_le_bytes(field(5, 8)?.try_into().ok()?) == address.ext,
+            _ => false,
+        };
+        addressed.then_some(seq)
+    }
+}
+
+/// The Frame Check Sequence, i.e., CRC-16 ITU-T with reflected bits (also known as KERMIT).
+fn fcs(data: &[u8]) -> u16 {
+    data.iter().fold(0, |mut crc: u16, byte| {
+        crc ^= u16::from(*byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0x8408
+            } else {
+                crc >> 1
+            };
+        }
+        crc
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDR: NodeAddress = NodeAddress {
+        pan_id: 0xABCD,
+        short: 0x0102,
+        ext: 0x0012_4B00_0102_0304,
+    };
+
+    fn data_frame(dst_pan: u16, dst_short: u16, ack_request: bool) -> Frame {
+        let fcf: u16 = 0b001
+            | if ack_request { FCF_ACK_REQUEST } else { 0 }
+            | (1 << 6) // PAN ID compression
+            | (ADDR_MODE_SHORT << FCF_DST_ADDR_MODE_SHIFT)
+            | (ADDR_MODE_SHORT << 14);
+        let mut psdu = fcf.to_le_bytes().to_vec();
+        psdu.push(42);
+        psdu.extend_from_slice(&dst_pan.to_le_bytes());
+        psdu.extend_from_slice(&dst_short.to_le_bytes());
+        psdu.extend_from_slice(&[0x05, 0x06, b'h', b'i']);
+        let mut fifo = vec![u8::try_from(psdu.len() + FCS_LEN).unwrap()];
+        fifo.extend(psdu);
+        Frame::from_tx_fifo(fifo).expect("valid frame")
+    }
+
+    #[test]
+    fn fcs_check_value() {
+        assert_eq!(fcs(b"123456789"), 0x2189);
+    }
+
+    #[test]
+    fn fcs_is_appended() {
+        let frame = data_frame(ADDR.pan_id, ADDR.short, true);
+        let bytes = frame.bytes();
+        assert_eq!(usize::from(bytes[0]) + 1, bytes.len());
+        // The FCS over the PSDU with the FCS is a constant, as the CRC has no final XOR.
+        assert_eq!(fcs(&bytes[1..]), 0);
+        // Already complete frames are passed as-is.
+        assert_eq!(Frame::from_tx_fifo(bytes.to_vec()).as_ref(), Some(&frame));
+        assert_eq!(Frame::from_tx_fifo(bytes[..4].to_vec()), None);
+    }
+
+    #[test]
+    fn frame_duration() {
+        let ack = Frame::ack(7);
+        assert_eq!(ack.bytes().len(), 6);
+        assert_eq!(ack.bytes()[..4], [5, 0x02, 0x00, 7]);
+        assert_eq!(fcs(&ack.bytes()[1..]), 0);
+        // 6 bytes of headers + 5 bytes of PSDU
+        assert_eq!(ack.duration(), Duration::from_picos(352_000_000));
+    }
+
+    #[test]
+    fn ack_requests() {
+        assert_eq!(
+            data_frame(ADDR.pan_id, ADDR.short, true).ack_request_for(&ADDR),
+            Some(42)
+        );
+        assert_eq!(
+            data_frame(BROADCAST_PAN_ID, ADDR.short, true).ack_request_for(&ADDR),
+            Some(42)
+        );
+        assert_eq!(
+            data_frame(ADDR.pan_id, ADDR.short, false).ack_request_for(&ADDR),
+            None
+        );
+        assert_eq!(
+            data_frame(ADDR.pan_id, ADDR.short + 1, true).ack_request_for(&ADDR),
+            None
+        );
+        assert_eq!(
+            data_frame(ADDR.pan_id + 1, ADDR.short, true).ack_request_for(&ADDR),
+            None
+        );
+        assert_eq!(
+            data_frame(ADDR.pan_id, BROADCAST_SHORT_ADDR, true).ack_request_for(&ADDR),
+            None
+        );
+        assert_eq!(Frame::ack(42).ack_request_for(&ADDR), None);
+    }
+}
@@ -0,0 +1,221 @@
+//! Runs a network of emulated nodes connected through a simulated IEEE 802.15.4 radio medium.
+//!
+//! Each node is a separate [`Emulator`] with its own firmware. Their RF Cores (RFC) are connected
+//! to a shared medium through the `ModemInterface`, which models:
+//! - the time on air of frames (250 kbps) and the RX/TX turnaround time,
+//! - lossy links (see [`Link`]) with a configurable propagation delay,
+//! - collisions of overlapping transmissions and half-duplex radios,
+//! - CCA (the channel is busy during any in-range transmission),
+//! - automatic acknowledgments (when enabled by the firmware).
+//!
+//! Radio channels are not modeled: all the nodes are assumed to use the same one.
+//!
+//! # Synchronization
+//!
+//! The nodes are stepped in lockstep with a quantum not longer than the turnaround time:
+//! each node is run up to the end of the quantum before any node goes further.
+//! Since a transmission starts a turnaround time after it was requested, it is always known
+//! to the medium before any other node may hear it. Therefore, the simulation is exact
+//! with respect to the radio (and deterministic for a given seed).
+
+mod frame;
+mod medium;
+mod modem;
+
+pub use medium::{Link, MediumStats};
+
+use crate::frame::TURNAROUND_TIME;
+use crate::medium::Medium;
+use crate::modem::{NodeClock, NodeModem};
+use cmemu_lib::engine::{Duration, Emulator, Timepoint};
+use log::debug;
+use std::sync::{Arc, Mutex};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum NetworkError {
+    #[error("Node id {0} is already used in the network")]
+    DuplicateNodeId(u64),
+    #[error("The node is at {node:?}, which is before the network time {network:?}")]
+    NodeBehind { node: Timepoint, network: Timepoint },
+    #[error("Invalid quantum {0:?}: it must be positive and at most the turnaround time")]
+    InvalidQuantum(Duration),
+}
+
+struct Node {
+    id: u64,
+    emulator: Emulator,
+    clock: Arc<NodeClock>,
+}
+
+/// A set of emulators connected through a shared radio medium.
+///
+/// Nodes are identified by the index returned from [`Network::add_node`].
+pub struct Network {
+    nodes: Vec<Node>,
+    medium: Arc<Mutex<Medium>>,
+    quantum: Duration,
+    now: Timepoint,
+}
+
+impl std::fmt::Debug for Network {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Network")
+            .field(
+                "nodes",
+                &self.nodes.iter().map(|n| n.id).collect::<Vec<_>>(),
+            )
+            .field("quantum", &self.quantum)
+            .field("now", &self.now)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Network {
+    /// Creates an empty network. The `seed` determines which frames are lost on lossy links.
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Self {
+            nodes: Vec::new(),
+            medium: Arc::new(Mutex::new(Medium::new(seed))),
+            quantum: TURNAROUND_TIME,
+            now: Timepoint::ZERO,
+        }
+    }
+
+    /// Connects the `emulator` to the network as the node `node_id`.
+    ///
+    /// The node id is used to derive the MAC address (see [`Emulator::set_node_id`]),
+    /// any radio interface attached previously is replaced.
+    /// Links to and from the new node are set to the default link.
+    pub fn add_node(
+        &mut self,
+        mut emulator: Emulator,
+        node_id: u64,
+    ) -> Result<usize, NetworkError> {
+        if self.nodes.iter().any(|n| n.id == node_id) {
+            return Err(NetworkError::DuplicateNodeId(node_id));
+        }
+        let node_time = emulator.get_emulation_time();
+        if node_time < self.now {
+            return Err(NetworkError::NodeBehind {
+                node: node_time,
+                network: self.now,
+            });
+        }
+
+        let index = self.medium().add_radio();
+        let clock = Arc::new(NodeClock::default());
+        emulator.set_node_id(node_id);
+        emulator.set_radio_interface(Some(Box::new(NodeModem::new(
+            index,
+            Arc::clone(&clock),
+            Arc::clone(&self.medium),
+        ))));
+        self.nodes.push(Node {
+            id: node_id,
+            emulator,
+            clock,
+        });
+        debug!("Added node {node_id} to the network as #{index}");
+        Ok(index)
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    #[must_use]
+    pub fn node(&self, index: usize) -> &Emulator {
+        &self.nodes[index].emulator
+    }
+
+    /// Note: the emulator must not be stepped directly, as the network would lose track of its time.
+    pub fn node_mut(&mut self, index: usize) -> &mut Emulator {
+        &mut self.nodes[index].emulator
+    }
+
+    /// Sets the link used for frames sent from the node `from` to the node `to`.
+    pub fn set_link(&mut self, from: usize, to: usize, link: Link) {
+        assert!(from < self.len() && to < self.len(), "Invalid node index");
+        self.medium().set_link(from, to, link);
+    }
+
+    /// Sets the link in both directions between the nodes `a` and `b`.
+    pub fn set_symmetric_link(&mut self, a: usize, b: usize, link: Link) {
+        self.set_link(a, b, link);
+        self.set_link(b, a, link);
+    }
+
+    /// Sets the link used between nodes added from now on (all connected without loss by default).
+    pub fn set_default_link(&mut self, link: Link) {
+        self.medium().set_default_link(link);
+    }
+
+    /// Sets how far the nodes may get ahead of each other. It is the turnaround time by default.
+    ///
+    /// Shorter quanta make sense only when the nodes are inspected between [`Network::run_until`] calls.
+    pub fn set_quantum(&mut self, quantum: Duration) -> Result<(), NetworkError> {
+        if quantum == Duration::ZERO || quantum > TURNAROUND_TIME {
+            return Err(NetworkError::InvalidQuantum(quantum));
+        }
+        self.quantum = quantum;
+        Ok(())
+    }
+
+    /// The time that all the nodes have reached.
+    #[must_use]
+    pub fn now(&self) -> Timepoint {
+        self.now
+    }
+
+    #[must_use]
+    pub fn stats(&self) -> MediumStats {
+        self.medium().stats().clone()
+    }
+
+    /// Runs all the nodes until `end`, i.e., executes all the events before `end`.
+    ///
+    /// # Panics
+    ///
+    /// Panics from the emulators are propagated, notably the `RequestedExit` of semihosting.
+    /// The index of the panicking node may be checked with the log, or by running
+    /// the network with [`std::panic::catch_unwind`] for shorter periods.
+    pub fn run_until(&mut self, end: Timepoint) {
+        while self.now < end {
+            let quantum_end = end.min(self.now + self.quantum);
+            for node in &mut self.nodes {
+                Self::run_node(node, quantum_end);
+            }
+            self.medium().resolve_until(quantum_end + TURNAROUND_TIME);
+            self.now = quantum_end;
+        }
+    }
+
+    pub fn run_for(&mut self, duration: Duration) {
+        self.run_until(self.now + duration);
+    }
+
+    /// Runs the node phase by phase, so that its modem knows the time of every operation.
+    fn run_node(node: &mut Node, end: Timepoint) {
+        loop {
+            let next = node.emulator.get_next_event_time();
+            if next >= end {
+                break;
+            }
+            node.clock.set(next);
+            node.emulator.step_until(next + Duration::MIN_DELAY);
+        }
+    }
+
+    fn medium(&self) -> std::sync::MutexGuard<'_, Medium> {
+        self.medium.lock().expect("Medium lock is poisoned")
+    }
+}
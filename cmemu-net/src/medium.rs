@@ -0,0 +1,561 @@
+//! The shared radio medium connecting the modems of all nodes.
+//!
+//! All the methods take the current time of the calling node, which may be ahead of the other nodes
+//! by at most one quantum (see [`crate::Network`]).
+//! A transmission starts [`TURNAROUND_TIME`] after the TX strobe, so it is always registered before
+//! any other node may observe it. Similarly, the fate of a reception (delivered, collided, lost)
+//! is resolved at the end of a quantum, when all transmissions overlapping it are already known.
+
+use crate::frame::{Frame, MAX_FRAME_DURATION, NodeAddress, TURNAROUND_TIME};
+use cmemu_lib::common::{CcaReq, ModemOp};
+use cmemu_lib::engine::{Duration, Timepoint};
+use log::{debug, trace, warn};
+use std::collections::VecDeque;
+
+/// [TI-TRM] 23.3.3.2: strobes are a legacy of CC2420-like radios, only these are issued by the RFC.
+const STROBE_RXON: u8 = 0x03;
+const STROBE_TXON: u8 = 0x04;
+const STROBE_RFOFF: u8 = 0x06;
+/// RSSI reported when the channel is busy, in dBm.
+const BUSY_RSSI: i8 = -50;
+/// By default, the nodes are close to each other (about 30 m).
+const DEFAULT_PROPAGATION_DELAY: Duration = Duration::from_picos(100_000);
+
+/// A one-directional radio link between two nodes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct Link {
+    /// Probability of losing a frame sent over the link.
+    ///
+    /// With `1.0`, the nodes are out of range: they cannot even sense each other's transmissions.
+    pub loss: f64,
+    /// Propagation delay of the signal.
+    pub delay: Duration,
+}
+
+impl Link {
+    /// A link losing frames with probability `loss` and a default propagation delay.
+    #[must_use]
+    pub fn new(loss: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&loss),
+            "Invalid loss probability {loss}"
+        );
+        Self {
+            loss,
+            delay: DEFAULT_PROPAGATION_DELAY,
+        }
+    }
+
+    /// Nodes out of range of each other.
+    #[must_use]
+    pub fn disconnected() -> Self {
+        Self::new(1.0)
+    }
+
+    #[must_use]
+    pub fn with_delay(self, delay: Duration) -> Self {
+        Self { delay, ..self }
+    }
+
+    fn in_range(&self) -> bool {
+        self.loss < 1.0
+    }
+}
+
+impl Default for Link {
+    fn default() -> Self {
+        Self::new(0.0)
+    }
+}
+
+/// Counters of what happened in the medium.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MediumStats {
+    /// Frames sent, including acknowledgments.
+    pub transmitted: u64,
+    /// Automatically sent acknowledgments.
+    pub acks: u64,
+    /// Frames received by a node.
+    pub delivered: u64,
+    /// Frames not received due to overlapping transmissions.
+    pub collided: u64,
+    /// Frames not received due to the link loss.
+    pub lost: u64,
+    /// Frames not received as the receiver was not listening or was transmitting itself.
+    pub missed: u64,
+}
+
+#[derive(Debug, Default)]
+struct Radio {
+    tx_fifo: Vec<u8>,
+    tx_done_at: Option<Timepoint>,
+    /// The periods of listening, the last one may be open.
+    ///
+    /// The receiver is turned off by `SRFOFF` and for the time of own transmissions,
+    /// after which it returns to RX (like in CC2420).
+    rx_windows: Vec<(Timepoint, Option<Timepoint>)>,
+    address: NodeAddress,
+    auto_ack: bool,
+    /// Received frames with the time they may be taken by the RFC.
+    inbox: VecDeque<(Timepoint, Frame)>,
+}
+
+impl Radio {
+    fn is_listening(&self) -> bool {
+        self.rx_windows
+            .last()
+            .is_some_and(|(_, until)| until.is_none())
+    }
+
+    fn stop_listening(&mut self, at: Timepoint) {
+        if let Some((_, until @ None)) = self.rx_windows.last_mut() {
+            *until = Some(at);
+        }
+    }
+
+    /// Turns the receiver off for `from..to`, if it is listening at `from`.
+    fn pause_listening(&mut self, from: Timepoint, to: Timepoint) {
+        let Some(i) = self
+            .rx_windows
+            .iter()
+            .position(|&(since, until)| since <= from && until.is_none_or(|until| from < until))
+        else {
+            return;
+        };
+        let until = self.rx_windows[i].1.replace(from);
+        if until.is_none_or(|until| to < until) {
+            self.rx_windows.insert(i + 1, (to, until));
+        }
+    }
+
+    /// Whether the receiver was on for the whole `start..end`.
+    fn listened(&self, start: Timepoint, end: Timepoint) -> bool {
+        self.rx_windows
+            .iter()
+            .any(|&(since, until)| since <= start && until.is_none_or(|until| end <= until))
+    }
+}
+
+#[derive(Debug)]
+struct Transmission {
+    id: u64,
+    src: usize,
+    start: Timepoint,
+    end: Timepoint,
+    frame: Frame,
+}
+
+/// A transmission reaching a node, in the time frame of the receiver.
+#[derive(Clone, Copy, Debug)]
+struct Reception {
+    tx_id: u64,
+    receiver: usize,
+    start: Timepoint,
+    end: Timepoint,
+}
+
+#[derive(Debug)]
+pub(crate) struct Medium {
+    radios: Vec<Radio>,
+    /// `links[from][to]`
+    links: Vec<Vec<Link>>,
+    default_link: Link,
+    max_delay: Duration,
+    transmissions: Vec<Transmission>,
+    receptions: Vec<Reception>,
+    next_tx_id: u64,
+    seed: u64,
+    stats: MediumStats,
+}
+
+impl Medium {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self {
+            radios: Vec::new(),
+            links: Vec::new(),
+            default_link: Link::default(),
+            max_delay: DEFAULT_PROPAGATION_DELAY,
+            transmissions: Vec::new(),
+            receptions: Vec::new(),
+            next_tx_id: 0,
+            seed,
+            stats: MediumStats::default(),
+        }
+    }
+
+    /// Returns the index of the new radio.
+    pub(crate) fn add_radio(&mut self) -> usize {
+        for links in &mut self.links {
+            links.push(self.default_link);
+        }
+        self.radios.push(Radio::default());
+        self.links.push(vec![self.default_link; self.radios.len()]);
+        self.radios.len() - 1
+    }
+
+    pub(crate) fn set_link(&mut self, from: usize, to: usize, link: Link) {
+        self.max_delay = self.max_delay.max(link.delay);
+        self.links[from][to] = link;
+    }
+
+    /// The link used between new nodes.
+    pub(crate) fn set_default_link(&mut self, link: Link) {
+        self.max_delay = self.max_delay.max(link.delay);
+        self.default_link = link;
+    }
+
+    pub(crate) fn stats(&self) -> &MediumStats {
+        &self.stats
+    }
+
+    pub(crate) fn send_op(&mut self, node: usize, now: Timepoint, op: &ModemOp) {
+        let radio = &mut self.radios[node];
+        match *op {
+            ModemOp::PushTx(byte) => radio.tx_fifo.push(byte),
+            ModemOp::Strobe(STROBE_RXON) => {
+                if !radio.is_listening() {
+                    radio.rx_windows.push((now, None));
+                }
+            }
+            ModemOp::Strobe(STROBE_RFOFF) => radio.stop_listening(now),
+            ModemOp::Strobe(STROBE_TXON) => {
+                let fifo = std::mem::take(&mut radio.tx_fifo);
+                if let Some(frame) = Frame::from_tx_fifo(fifo) {
+                    let end = self.start_transmission(node, now, now + TURNAROUND_TIME, frame);
+                    self.radios[node].tx_done_at = Some(end);
+                } else {
+                    warn!("Node #{node} requested sending a malformed frame, ignoring.");
+                    radio.tx_done_at = Some(now);
+                }
+            }
+            ModemOp::Strobe(strobe) => trace!("Ignoring strobe {strobe:#x} of node #{node}"),
+            // CCA is computed on read
+            ModemOp::RequestCca => {}
+            ModemOp::SetAddr { panid, short, ext } => {
+                radio.address = NodeAddress {
+                    pan_id: panid,
+                    short,
+                    ext,
+                };
+            }
+            ModemOp::SetAutoAck(enabled) => radio.auto_ack = enabled,
+        }
+    }
+
+    pub(crate) fn take_rx(&mut self, node: usize, now: Timepoint) -> Option<Vec<u8>> {
+        let inbox = &mut self.radios[node].inbox;
+        inbox
+            .front()
+            .is_some_and(|(at, _)| *at <= now)
+            .then(|| inbox.pop_front().expect("checked above").1.bytes().to_vec())
+    }
+
+    pub(crate) fn take_tx_finished(&mut self, node: usize, now: Timepoint) -> Option<()> {
+        let radio = &mut self.radios[node];
+        radio
+            .tx_done_at
+            .is_some_and(|at| at <= now)
+            .then(|| radio.tx_done_at = None)
+    }
+
+    pub(crate) fn cca_read(&self, node: usize, now: Timepoint) -> CcaReq {
+        let busy = self.transmissions.iter().any(|tx| {
+            let link = &self.links[tx.src][node];
+            tx.src != node
+                && link.in_range()
+                && tx.start + link.delay <= now
+                && now < tx.end + link.delay
+        });
+        if busy {
+            CcaReq::new_busy(BUSY_RSSI)
+        } else {
+            CcaReq::new_clear()
+        }
+    }
+
+    /// Resolve all receptions ending before `horizon`.
+    ///
+    /// All nodes must have reached `horizon - TURNAROUND_TIME`,
+    /// so that every transmission that may interfere is already known.
+    pub(crate) fn resolve_until(&mut self, horizon: Timepoint) {
+        self.receptions
+            .sort_unstable_by_key(|rx| (rx.end, rx.tx_id, rx.receiver));
+        let ready = self.receptions.partition_point(|rx| rx.end < horizon);
+        let ready: Vec<_> = self.receptions.drain(..ready).collect();
+        for reception in ready {
+            self.resolve(reception);
+        }
+
+        // Pending receptions end no earlier than `horizon`, and so might overlap only these.
+        let max_delay = self.max_delay;
+        self.transmissions
+            .retain(|tx| tx.end + max_delay + MAX_FRAME_DURATION >= horizon);
+        for radio in &mut self.radios {
+            radio.rx_windows.retain(|(_, until)| {
+                until.is_none_or(|until| until + max_delay + MAX_FRAME_DURATION >= horizon)
+            });
+        }
+    }
+
+    /// Returns the time when the transmission ends.
+    /// The receiver of `src` is off since `turned_at`, when the radio switches to TX.
+    fn start_transmission(
+        &mut self,
+        src: usize,
+        turned_at: Timepoint,
+        start: Timepoint,
+        frame: Frame,
+    ) -> Timepoint {
+        let id = self.next_tx_id;
+        self.next_tx_id += 1;
+        let end = start + frame.duration();
+        debug!("Node #{src} transmits {frame:x?} in {start:?}..{end:?}");
+        self.radios[src].pause_listening(turned_at, end);
+
+        for (receiver, link) in self.links[src].iter().enumerate() {
+            if receiver != src && link.in_range() {
+                self.receptions.push(Reception {
+                    tx_id: id,
+                    receiver,
+                    start: start + link.delay,
+                    end: end + link.delay,
+                });
+            }
+        }
+        self.transmissions.push(Transmission {
+            id,
+            src,
+            start,
+            end,
+            frame,
+        });
+        self.stats.transmitted += 1;
+        end
+    }
+
+    fn resolve(&mut self, rx: Reception) {
+        let tx = self
+            .transmissions
+            .iter()
+            .find(|tx| tx.id == rx.tx_id)
+            .expect("Transmissions are kept until receptions are resolved");
+        let radio = &self.radios[rx.receiver];
+        let overlaps = |start: Timepoint, end: Timepoint| start < rx.end && rx.start < end;
+
+        let listening = radio.listened(rx.start, rx.end);
+        let collided = self.transmissions.iter().any(|other| {
+            let link = &self.links[other.src][rx.receiver];
+            other.id != tx.id
+                && other.src != rx.receiver
+                && link.in_range()
+                && overlaps(other.start + link.delay, other.end + link.delay)
+        });
+        let lost =
+            uniform_sample(self.seed, tx.id, rx.receiver) < self.links[tx.src][rx.receiver].loss;
+
+        let (src, receiver) = (tx.src, rx.receiver);
+        if !listening {
+            trace!("Node #{receiver} missed a frame from #{src}");
+            self.stats.missed += 1;
+        } else if collided {
+            trace!("Node #{receiver} got a collided frame from #{src}");
+            self.stats.collided += 1;
+        } else if lost {
+            trace!("Node #{receiver} lost a frame from #{src}");
+            self.stats.lost += 1;
+        } else {
+            trace!("Node #{receiver} received a frame from #{src}");
+            self.stats.delivered += 1;
+            let frame = tx.frame.clone();
+            let ack_seq = radio
+                .auto_ack
+                .then(|| frame.ack_request_for(&radio.address))
+                .flatten();
+            self.radios[receiver].inbox.push_back((rx.end, frame));
+            if let Some(seq) = ack_seq {
+                self.start_transmission(
+                    receiver,
+                    rx.end,
+                    rx.end + TURNAROUND_TIME,
+                    Frame::ack(seq),
+                );
+                self.stats.acks += 1;
+            }
+        }
+    }
+}
+
+/// A deterministic pseudo-random number in `[0, 1)` for the given reception.
+///
+/// It doesn't depend on the order of calls, so the simulation is reproducible.
+#[allow(clippy::cast_precision_loss)]
+fn uniform_sample(seed: u64, tx_id: u64, receiver: usize) -> f64 {
+    // SplitMix64 finalizer
+    let mut z = seed ^ tx_id.wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ ((receiver as u64) << 48);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^= z >> 31;
+    (z >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const US: u64 = 1_000_000;
+
+    fn at_us(us: u64) -> Timepoint {
+        Timepoint::from_picos(us * US)
+    }
+
+    /// A data frame with 10 bytes of payload to `short` requesting an ACK (560 µs on air).
+    fn send(medium: &mut Medium, node: usize, now: Timepoint, short: u16) {
+        let mut psdu = vec![0x61, 0x88, 0x01, 0xCD, 0xAB];
+        psdu.extend_from_slice(&short.to_le_bytes());
+        psdu.extend_from_slice(&[0x01, 0x00, 0x42]);
+        medium.send_op(node, now, &ModemOp::PushTx(12));
+        for byte in psdu {
+            medium.send_op(node, now, &ModemOp::PushTx(byte));
+        }
+        medium.send_op(node, now, &ModemOp::Strobe(STROBE_TXON));
+    }
+
+    fn medium_with_listeners(nodes: usize) -> Medium {
+        let mut medium = Medium::new(0);
+        for node in 0..nodes {
+            assert_eq!(medium.add_radio(), node);
+            medium.send_op(node, Timepoint::ZERO, &ModemOp::Strobe(STROBE_RXON));
+            medium.send_op(
+                node,
+                Timepoint::ZERO,
+                &ModemOp::SetAddr {
+                    panid: 0xABCD,
+                    short: u16::try_from(node).unwrap(),
+                    ext: 0,
+                },
+            );
+        }
+        medium
+    }
+
+    /// Emulate the network stepping with the maximal quantum.
+    fn run(medium: &mut Medium, until: Timepoint) {
+        let mut now = Timepoint::ZERO;
+        while now < until {
+            now += TURNAROUND_TIME;
+            medium.resolve_until(now + TURNAROUND_TIME);
+        }
+    }
+
+    #[test]
+    fn delivery_and_timing() {
+        let mut medium = medium_with_listeners(2);
+        send(&mut medium, 0, at_us(100), 1);
+        run(&mut medium, at_us(2000));
+
+        // 192 µs turnaround + 18 bytes (with headers) * 32 µs + 0.1 µs of propagation
+        let rx_end = Timepoint::from_picos(868 * US + 100_000);
+        assert_eq!(medium.take_rx(1, rx_end - Duration::MIN_DELAY), None);
+        let frame = medium.take_rx(1, rx_end).expect("frame received");
+        assert_eq!(frame.len(), 13);
+        assert_eq!(frame[0], 12);
+        assert_eq!(medium.take_rx(1, at_us(2000)), None);
+
+        assert_eq!(medium.take_tx_finished(0, at_us(867)), None);
+        assert_eq!(medium.take_tx_finished(0, at_us(868)), Some(()));
+        assert_eq!(medium.take_tx_finished(0, at_us(2000)), None);
+        assert_eq!(medium.stats().delivered, 1);
+    }
+
+    #[test]
+    fn cca_and_auto_ack() {
+        let mut medium = medium_with_listeners(2);
+        medium.send_op(1, Timepoint::ZERO, &ModemOp::SetAutoAck(true));
+        send(&mut medium, 0, Timepoint::ZERO, 1);
+        run(&mut medium, at_us(3000));
+
+        let busy = |medium: &Medium, node, us| medium.cca_read(node, at_us(us)).cca_state() == 1;
+        assert!(!busy(&medium, 1, 192));
+        assert!(busy(&medium, 1, 193));
+        assert!(busy(&medium, 1, 768));
+        assert!(!busy(&medium, 1, 769));
+        // The node doesn't sense its own transmission.
+        assert!(!busy(&medium, 0, 500));
+
+        assert_eq!(medium.stats().acks, 1);
+        assert!(medium.take_rx(1, at_us(769)).is_some());
+        // ACK is sent after a turnaround, and lasts 11 bytes
+        let ack = medium.take_rx(0, at_us(769 + 192 + 352)).expect("ACK");
+        assert_eq!(ack[..4], [5, 0x02, 0x00, 0x01]);
+        assert!(busy(&medium, 0, 1000));
+    }
+
+    #[test]
+    fn collisions_and_half_duplex() {
+        let mut medium = medium_with_listeners(3);
+        send(&mut medium, 0, at_us(0), 2);
+        send(&mut medium, 1, at_us(500), 2);
+        run(&mut medium, at_us(3000));
+
+        assert_eq!(medium.take_rx(2, at_us(3000)), None);
+        assert_eq!(medium.stats().collided, 2);
+        // Node 0 was transmitting when the frame of node 1 started, and vice versa.
+        assert_eq!(medium.stats().missed, 2);
+    }
+
+    #[test]
+    fn link_loss_and_range() {
+        let mut medium = medium_with_listeners(3);
+        medium.set_link(0, 1, Link::disconnected());
+        medium.set_link(0, 2, Link::new(0.5));
+        for i in 0..100 {
+            send(&mut medium, 0, at_us(i * 1000), 3);
+        }
+        run(&mut medium, at_us(101_000));
+
+        assert!(medium.take_rx(1, at_us(101_000)).is_none());
+        let received = (0..100)
+            .filter(|_| medium.take_rx(2, at_us(101_000)).is_some())
+            .count();
+        assert!((30..70).contains(&received), "received {received}");
+        assert_eq!(medium.stats().lost, 100 - received as u64);
+        assert_eq!(medium.stats().transmitted, 100);
+        assert!(medium.transmissions.len() < 10);
+    }
+
+    #[test]
+    fn not_listening() {
+        let mut medium = Medium::new(0);
+        medium.add_radio();
+        medium.add_radio();
+        send(&mut medium, 0, at_us(0), 1);
+        medium.send_op(1, at_us(300), &ModemOp::Strobe(STROBE_RXON));
+        run(&mut medium, at_us(2000));
+
+        assert_eq!(medium.take_rx(1, at_us(2000)), None);
+        assert_eq!(medium.stats().missed, 1);
+    }
+
+    #[test]
+    fn stops_listening() {
+        let mut medium = medium_with_listeners(2);
+        medium.send_op(1, Timepoint::ZERO, &ModemOp::SetAutoAck(true));
+        send(&mut medium, 0, at_us(0), 1);
+        medium.send_op(1, at_us(1500), &ModemOp::Strobe(STROBE_RFOFF));
+        send(&mut medium, 0, at_us(2000), 1);
+        // Turned off in the middle of the frame (4192..4768).
+        medium.send_op(1, at_us(3000), &ModemOp::Strobe(STROBE_RXON));
+        send(&mut medium, 0, at_us(4000), 1);
+        medium.send_op(1, at_us(4500), &ModemOp::Strobe(STROBE_RFOFF));
+        run(&mut medium, at_us(6000));
+
+        assert!(medium.take_rx(1, at_us(6000)).is_some());
+        assert_eq!(medium.take_rx(1, at_us(6000)), None);
+        assert_eq!(medium.stats().missed, 2);
+        assert_eq!(medium.stats().acks, 1);
+        // The frame and its ACK.
+        assert_eq!(medium.stats().delivered, 2);
+    }
+}
@@ -0,0 +1,61 @@
+//! The `ModemInterface` attached to each emulated node.
+
+use crate::medium::Medium;
+use cmemu_lib::common::{CcaReq, ModemInterface, ModemOp};
+use cmemu_lib::engine::Timepoint;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// The emulation time of a node, updated by the [`crate::Network`] before every step.
+///
+/// The modem has no access to the emulator, so it needs to be told what time it is.
+#[derive(Debug, Default)]
+pub(crate) struct NodeClock(AtomicU64);
+
+impl NodeClock {
+    pub(crate) fn set(&self, time: Timepoint) {
+        self.0.store(time.as_picos(), Ordering::Relaxed);
+    }
+
+    pub(crate) fn get(&self) -> Timepoint {
+        Timepoint::from_picos(self.0.load(Ordering::Relaxed))
+    }
+}
+
+pub(crate) struct NodeModem {
+    node: usize,
+    clock: Arc<NodeClock>,
+    medium: Arc<Mutex<Medium>>,
+}
+
+impl NodeModem {
+    pub(crate) fn new(node: usize, clock: Arc<NodeClock>, medium: Arc<Mutex<Medium>>) -> Self {
+        Self {
+            node,
+            clock,
+            medium,
+        }
+    }
+
+    fn medium(&self) -> MutexGuard<'_, Medium> {
+        self.medium.lock().expect("Medium lock is poisoned")
+    }
+}
+
+impl ModemInterface for NodeModem {
+    fn send_op(&self, op: ModemOp) {
+        self.medium().send_op(self.node, self.clock.get(), &op);
+    }
+
+    fn take_rx(&self) -> Option<Vec<u8>> {
+        self.medium().take_rx(self.node, self.clock.get())
+    }
+
+    fn take_tx_finished(&self) -> Option<()> {
+        self.medium().take_tx_finished(self.node, self.clock.get())
+    }
+
+    fn cca_read(&self) -> Option<CcaReq> {
+        Some(self.medium().cca_read(self.node, self.clock.get()))
+    }
+}
@@ -0,0 +1,65 @@
+// Checks stepping of a network of real emulators.
+//
+// The firmware doesn't use the radio, so this is only about keeping the nodes in lockstep.
+
+use cmemu_elf_loader::{ElfArgs, ElfLoader};
+use cmemu_lib::engine::{Duration, Emulator, Timepoint};
+use cmemu_net::{Link, Network, NetworkError};
+
+fn make_emulator() -> Emulator {
+    let flash_mem = std::fs::read(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../cmemu-elf-loader/tests/test.elf"
+    ))
+    .expect("test ELF is present");
+    let elf_params = ElfArgs::default();
+    let elf = ElfLoader::new(&flash_mem, None, &elf_params);
+    let mut emulator = Emulator::new(elf.flash_base(), elf.rom_base());
+    elf.load(&mut emulator);
+    emulator
+}
+
+#[test]
+fn nodes_run_in_lockstep() {
+    let mut network = Network::new(0);
+    let a = network.add_node(make_emulator(), 1).unwrap();
+    let b = network.add_node(make_emulator(), 2).unwrap();
+    network.set_symmetric_link(a, b, Link::new(0.1));
+
+    // The firmware exits after about 400 µs.
+    let end = Timepoint::from_picos(100_000_000);
+    network.run_until(end);
+    assert_eq!(network.now(), end);
+    for node in [a, b] {
+        let emulator = network.node(node);
+        assert!(emulator.get_emulation_time() < end);
+        assert!(emulator.get_next_event_time() >= end);
+    }
+    // Both run the same firmware from the same state.
+    assert_eq!(
+        network.node(a).get_emulation_time(),
+        network.node(b).get_emulation_time()
+    );
+    assert_eq!(network.stats().transmitted, 0);
+}
+
+#[test]
+fn invalid_configuration() {
+    let mut network = Network::new(0);
+    network.add_node(make_emulator(), 1).unwrap();
+    assert!(matches!(
+        network.add_node(make_emulator(), 1),
+        Err(NetworkError::DuplicateNodeId(1))
+    ));
+    assert!(matches!(
+        network.set_quantum(Duration::ONE_SECOND),
+        Err(NetworkError::InvalidQuantum(_))
+    ));
+
+    network.run_for(Duration::from_picos(1_000_000));
+    assert!(matches!(
+        network.add_node(make_emulator(), 2),
+        Err(NetworkError::NodeBehind { .. })
+    ));
+    assert_eq!(network.len(), 1);
+}
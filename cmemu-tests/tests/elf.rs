@@ -12,6 +12,7 @@ use cmemu::{App, configure, run_capture_semihosting};
 use cmemu_lib::common::{CcaReq, ModemInterface, ModemOp, UARTLiteInterface};
 use cmemu_lib::engine::Emulator;
 use log::{debug, trace};
+use std::collections::VecDeque;
 use std::error::Error;
 use std::ffi::OsString;
 use std::mem;
@@ -124,7 +125,7 @@ fn run_emulator(
     let duration = args.duration.clone();
     let mut emu = configure(args)?;
     config_cb(&mut emu);
-    run_capture_semihosting(emu, duration)
+    run_capture_semihosting(&mut emu, duration)
 }
 
 /// Struct for lock-less/atomic-less collection of the data.
@@ -178,7 +179,7 @@ impl UARTLiteInterface for SharedBufUartLiteBackend {
 
 // no lock-less version as the interface gives us only a shared reference anyway!
 /// Struct for collection of logs of the radio operations.
-/// This mock will always return ok/done/no-input on requests to the modem,
+/// This mock will always return ok/done on requests to the modem, and the queued frames as the input,
 /// what could hang the application.
 struct SharedLogModemInterface(Arc<Mutex<MockModem>>);
 
@@ -186,8 +187,10 @@ struct SharedLogModemInterface(Arc<Mutex<MockModem>>);
 struct MockModem {
     log: Vec<ModemRequest>,
     wanted_tx: bool,
+    /// Frames returned by `take_rx`, in order.
+    rx_frames: VecDeque<Vec<u8>>,
 }
-// TODO: implement mock support for replies
+// TODO: implement mock support for other replies
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 enum ModemRequest {
@@ -207,6 +210,11 @@ impl SharedLogModemInterface {
     fn get_arc(&self) -> Arc<Mutex<MockModem>> {
         Arc::clone(&self.0)
     }
+
+    /// Queues a frame (with its PHY header) to be received.
+    fn push_rx_frame(&self, frame: Vec<u8>) {
+        self.0.lock().unwrap().rx_frames.push_back(frame);
+    }
 }
 
 impl ModemInterface for SharedLogModemInterface {
@@ -220,9 +228,11 @@ impl ModemInterface for SharedLogModemInterface {
     }
 
     fn take_rx(&self) -> Option<Vec<u8>> {
-        self.0.lock().unwrap().log.push(ModemRequest::TakeRx);
-        trace!(target: "cmemu_tests::elf", "MockModem::take_rx() = None");
-        None
+        let mut modem = self.0.lock().unwrap();
+        modem.log.push(ModemRequest::TakeRx);
+        let res = modem.rx_frames.pop_front();
+        trace!(target: "cmemu_tests::elf", "MockModem::take_rx() = {res:?}");
+        res
     }
 
     fn take_tx_finished(&self) -> Option<()> {
@@ -1,6 +1,10 @@
 use predicates::prelude::*;
 
-use crate::{Timeout, cmemu_bin_run};
+use crate::{SharedLogModemInterface, Timeout, cmemu_bin_run, run_emulator};
+use std::fs;
+use std::mem;
+use std::ops::Range;
+use std::process::ExitCode;
 
 #[test]
 fn minimal() {
@@ -150,6 +154,52 @@ fn min_max_example_from_paper() {
     .stdout(predicate::str::is_empty());
 }
 
+#[test]
+#[cfg_attr(
+    not(any(cmemu_has_rom = "driverlib", cmemu_has_rom = "full")),
+    ignore = "No ROM"
+)]
+fn flash_program() {
+    // Erases and programs the FLASH_FLOG sector with `FlashSectorErase` and `FlashProgram` of ROM.
+    const SECTOR: Range<usize> = 0x1D000..0x1E000;
+    const DATA: [u32; 4] = [0x0123_4567, 0x89ab_cdef, 0xdead_beef, 0x0000_0000];
+
+    let dir = tempfile::tempdir().unwrap();
+    let image_path = dir.path().join("flash.bin");
+    cmemu_bin_run(
+        test_path!("hosted/flash_program.elf"),
+        Timeout::Cycles(1_000_000),
+        true,
+    )
+    .arg("--flash-write-back")
+    .arg(&image_path)
+    .assert()
+    .failure()
+    .code(42);
+
+    let image = fs::read(&image_path).unwrap();
+    let sector = &image[SECTOR];
+    let (programmed, erased) = sector.split_at(mem::size_of_val(&DATA));
+    let data: Vec<u8> = DATA.iter().flat_map(|w| w.to_le_bytes()).collect();
+    assert_eq!(programmed, data);
+    assert!(erased.iter().all(|&b| b == 0xFF));
+}
+
+#[test]
+fn rfc_rx_reserved_length() {
+    let modem = SharedLogModemInterface::new();
+    // A data frame: the PHY header, the frame control, the sequence number and the payload.
+    modem.push_rx_frame(vec![5, 0x01, 0x00, 0x2A, 0xBE, 0xEF]);
+    let code = run_emulator(
+        test_path!("hosted/rfc_rx_reserved_length.elf"),
+        Timeout::Cycles(20_000),
+        false,
+        |emu| emu.set_radio_interface(Some(Box::new(modem))),
+    )
+    .unwrap();
+    assert_eq!(code, ExitCode::from(42));
+}
+
 // Those should be auto-generated TBH
 mod bugs {
     use crate::{Timeout, cmemu_bin_run};
@@ -159,6 +209,19 @@ mod bugs {
     #[rstest]
     fn all_progs_return_42(
         #[files("*.elf")]
+        #[exclude("^mpu_")]
+        #[base_dir = "tests/elf/hosted/bugs_mini_tests/"]
+        path: PathBuf,
+    ) {
+        cmemu_bin_run(path, Timeout::Default, false)
+            .assert()
+            .code(42);
+    }
+
+    #[rstest]
+    #[cfg_attr(not(feature = "soc-has-mpu"), ignore = "No MPU regions")]
+    fn mpu_progs_return_42(
+        #[files("mpu_*.elf")]
         #[base_dir = "tests/elf/hosted/bugs_mini_tests/"]
         path: PathBuf,
     ) {
@@ -0,0 +1,126 @@
+# vim:ft=arm
+@ Common definitions for fault handling tests.
+@ A failed check exits cmemu with its (non-42) code.
+#ifndef CODE
+#define CODE .text.exec
+#endif
+#include "asm_test_prelude.asm"
+
+#define SCB_ICSR 0xE000ED04
+#define SCB_CCR 0xE000ED14
+#define SCB_SHPR2 0xE000ED1C
+#define SCB_SHPR3 0xE000ED20
+#define SCB_SHCSR 0xE000ED24
+#define SCB_CFSR 0xE000ED28
+#define SCB_HFSR 0xE000ED2C
+#define SCB_MMFAR 0xE000ED34
+#define SCB_BFAR 0xE000ED38
+#define MPU_CTRL 0xE000ED94
+#define MPU_RBAR 0xE000ED9C
+#define MPU_RASR 0xE000EDA0
+
+#define ICSR_PENDSVSET (1 << 28)
+#define CCR_NONBASETHRDENA (1 << 0)
+#define CCR_DIV_0_TRP (1 << 4)
+#define SHCSR_SVCALLACT (1 << 7)
+#define SHCSR_PENDSVACT (1 << 10)
+#define SHCSR_MEMFAULTENA (1 << 16)
+#define SHCSR_BUSFAULTENA (1 << 17)
+#define SHCSR_USGFAULTENA (1 << 18)
+
+#define CFSR_IACCVIOL (1 << 0)
+#define CFSR_DACCVIOL (1 << 1)
+#define CFSR_MUNSTKERR (1 << 3)
+#define CFSR_MSTKERR (1 << 4)
+#define CFSR_MMARVALID (1 << 7)
+#define CFSR_IBUSERR (1 << 8)
+#define CFSR_PRECISERR (1 << 9)
+#define CFSR_IMPRECISERR (1 << 10)
+#define CFSR_UNSTKERR (1 << 11)
+#define CFSR_STKERR (1 << 12)
+#define CFSR_BFARVALID (1 << 15)
+#define CFSR_INVSTATE (1 << 17)
+#define CFSR_INVPC (1 << 18)
+#define CFSR_DIVBYZERO (1 << 25)
+#define HFSR_FORCED (1 << 30)
+
+#define MPU_CTRL_ENABLE (1 << 0)
+#define MPU_CTRL_HFNMIENA (1 << 1)
+#define MPU_CTRL_PRIVDEFENA (1 << 2)
+@ MPU_RASR of an enabled region of 2^size_log2 bytes
+#define MPU_RASR_REGION(size_log2, ap, srd, xn) \
+    (1 | (((size_log2) - 1) << 1) | ((srd) << 8) | ((ap) << 24) | ((xn) << 28))
+#define MPU_AP_NO_ACCESS 0
+#define MPU_AP_PRIV_RW 1
+#define MPU_AP_FULL_ACCESS 3
+#define MPU_AP_READ_ONLY 6
+
+@ An address in the Code region, that no slave answers
+#define UNMAPPED_CODE_ADDR 0x40000
+
+.section CODE, "xa" @progbits
+
+@ Set bits in a register: clobbers r0, r1
+.macro set_bits addr, bits
+    ldr r0, =\addr
+    ldr r1, [r0]
+    orr r1, r1, \bits
+    str r1, [r0]
+.endm
+
+@ Configure an MPU region: clobbers r0, r1
+.macro mpu_region number, base, rasr
+    ldr r0, =MPU_RBAR
+    ldr r1, =(\base + (1 << 4) + \number)
+    str r1, [r0]
+    ldr r1, =\rasr
+    str r1, [r0, 4]
+.endm
+
+@ Fail with `code` if a register is not equal to `value`: clobbers r0, r1
+.macro expect_reg reg, value, code
+    mov r0, \reg
+    ldr r1, =\value
+    cmp r0, r1
+    beq .Lok\@
+    movs r0, \code
+    b fail
+.Lok\@:
+.endm
+
+@ Fail with `code` if two registers are not equal: clobbers r0, r1
+.macro expect_same reg, other, code
+    mov r0, \reg
+    mov r1, \other
+    cmp r0, r1
+    beq .Lok\@
+    movs r0, \code
+    b fail
+.Lok\@:
+.endm
+
+@ Fail with `code` if a memory word is not equal to `value`: clobbers r0, r1
+.macro expect_mem addr, value, code
+    ldr r0, =\addr
+    ldr r0, [r0]
+    ldr r1, =\value
+    cmp r0, r1
+    beq .Lok\@
+    movs r0, \code
+    b fail
+.Lok\@:
+.endm
+
+@ Fail with `code` if the exception being handled is not `number`: clobbers r0, r1
+.macro expect_ipsr number, code
+    mrs r2, ipsr
+    expect_reg r2, \number, \code
+.endm
+
+.thumb_func
+fail:
+    ldr r1, =EXIT_ADDR
+    str r0, [r1]
+    b .
+
+.ltorg
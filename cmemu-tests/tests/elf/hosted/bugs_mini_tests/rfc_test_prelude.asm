@@ -0,0 +1,90 @@
+# vim:ft=arm
+@ Common definitions for the RF core tests, which send the commands through the doorbell.
+@ A failed check exits cmemu with its (non-42) code.
+#include "fault_test_prelude.asm"
+
+#define PRCM_CLKLOADCTL 0x40082028
+#define PRCM_RFCCLKG 0x4008202C
+#define PRCM_PDCTL0RFC 0x40082130
+#define PRCM_PDSTAT0RFC 0x40082144
+#define RFC_PWR_PWMCLKEN 0x40040000
+#define RFC_DBELL_CMDR 0x40041000
+#define RFC_DBELL_CMDSTA 0x40041004
+#define RFC_DBELL_RFHWIFG 0x40041008
+#define RFC_DBELL_RFCPEIFG 0x40041010
+#define RFC_DBELL_RFACKIFG 0x4004101C
+#define RFC_RAT_RATCNT 0x40043004
+#define RFC_RAT_RATCH0VAL 0x40043080
+
+#define RFHWIFG_RATCH(n) (1 << (12 + (n)))
+
+@ The result byte of CMDSTA
+#define CMDSTA_DONE 0x01
+#define CMDSTA_CONTEXT_ERROR 0x85
+#define CMDSTA_PAR_ERROR 0x87
+#define CMDSTA_QUEUE_ERROR 0x88
+
+@ The status of the radio operations
+#define STATUS_PENDING 0x0001
+#define STATUS_ACTIVE 0x0002
+#define STATUS_DONE_OK 0x0400
+#define STATUS_IEEE_ERROR_PAR 0x2800
+
+@ The start triggers and the conditions of the radio operations
+#define TRIG_NOW 0
+#define COND_NEVER 1
+
+@ The value of CMDR for a direct command
+#define DIRECT_CMD(id, param) (((id) << 16) | ((param) << 8) | 1)
+
+.section CODE, "xa" @progbits
+
+@ Power the RF core on and enable its clocks: clobbers r0, r1
+.macro rfc_power_on
+    ldr r0, =PRCM_PDCTL0RFC
+    movs r1, 1
+    str r1, [r0]
+    ldr r0, =PRCM_PDSTAT0RFC
+.Lwait\@:
+    ldr r1, [r0]
+    cmp r1, 1
+    bne .Lwait\@
+    ldr r0, =PRCM_RFCCLKG
+    movs r1, 1
+    str r1, [r0]
+    ldr r0, =PRCM_CLKLOADCTL
+    str r1, [r0]
+.Lload\@:
+    ldr r1, [r0]
+    cmp r1, 2
+    bne .Lload\@
+    ldr r0, =RFC_PWR_PWMCLKEN
+    ldr r1, =0x7FF
+    str r1, [r0]
+.endm
+
+@ Send the command with CMDR and wait for its acknowledgement: r0 is CMDSTA, clobbers r1, r2
+.macro rfc_send cmdr
+    ldr r1, =RFC_DBELL_CMDR
+    movs r2, 0
+    str r2, [r1, (RFC_DBELL_RFACKIFG - RFC_DBELL_CMDR)]
+    ldr r0, =\cmdr
+    str r0, [r1]
+.Lwait\@:
+    ldr r2, [r1, (RFC_DBELL_RFACKIFG - RFC_DBELL_CMDR)]
+    cmp r2, 0
+    beq .Lwait\@
+    ldr r0, [r1, (RFC_DBELL_CMDSTA - RFC_DBELL_CMDR)]
+.endm
+
+@ Wait until the status of the radio operation at `cmd` is neither pending nor active:
+@ r0 is the status, clobbers r1
+.macro rfc_wait_op cmd
+    ldr r1, =\cmd
+.Lwait\@:
+    ldrh r0, [r1, 2]
+    cmp r0, STATUS_PENDING
+    beq .Lwait\@
+    cmp r0, STATUS_ACTIVE
+    beq .Lwait\@
+.endm
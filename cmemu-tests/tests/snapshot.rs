@@ -0,0 +1,141 @@
+// Checks that a run restored from an `EmulatorSnapshot` is cycle-identical to the original one.
+//
+// The emulator is snapshotted early in a flash test, and after a while it is rewound to the snapshot.
+// The addresses of executed instructions and the emulation time are compared cycle-by-cycle,
+// then the test continues to its end and its memory is checked as usual.
+// The snapshot may also be saved and loaded back before the rewind.
+
+use anyhow::Context;
+use cmemu_flash_test_lib::{CallbackType, FlashTestCase, TestRunner};
+use cmemu_lib::common::Address;
+use cmemu_lib::engine::{Emulator, EmulatorSnapshot, Timepoint};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const SNAPSHOT_AT_CYCLE: u64 = 100;
+const REWIND_AFTER_CYCLES: u64 = 2000;
+
+#[test]
+fn dijkstra() -> anyhow::Result<()> {
+    const TEST_PATH: &str = "tests/flash/misc/dijkstra.tzst";
+    const TEST_CASE: u32 = 4;
+    let rewound = run_single_test(TEST_PATH, TEST_CASE, false)?;
+    assert!(rewound, "The test is too short to be rewound.");
+    Ok(())
+}
+
+#[test]
+fn dijkstra_saved_snapshot() -> anyhow::Result<()> {
+    const TEST_PATH: &str = "tests/flash/misc/dijkstra.tzst";
+    const TEST_CASE: u32 = 4;
+    let rewound = run_single_test(TEST_PATH, TEST_CASE, true)?;
+    assert!(rewound, "The test is too short to be rewound.");
+    Ok(())
+}
+
+#[test]
+fn random_tests() -> anyhow::Result<()> {
+    fn search_for_tests(
+        path: &dyn AsRef<Path>,
+        tests_accumulator: &mut Vec<PathBuf>,
+    ) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        for dir_entry in fs::read_dir(path)
+            .with_context(|| format!("failed to read {}", path.display()))?
+            .map(|r| r.expect("reading testsuite directory entry"))
+        {
+            let p = dir_entry.path();
+            if dir_entry.metadata()?.is_dir() {
+                search_for_tests(&p, tests_accumulator)?;
+            } else if p.extension().is_some_and(|ext| ext == "tzst")
+                && !p.with_extension("ignore").exists()
+            {
+                tests_accumulator.push(p);
+            }
+        }
+        Ok(())
+    }
+
+    use rand::seq::IteratorRandom;
+
+    let mut tests = vec![];
+    search_for_tests(&"tests/flash/cache", &mut tests)?;
+    search_for_tests(&"tests/flash/instructions", &mut tests)?;
+    search_for_tests(&"tests/flash/interrupts", &mut tests)?;
+    search_for_tests(&"tests/flash/misc", &mut tests)?;
+
+    let tests_cnt = 5;
+    let rng = &mut rand::rng();
+    for file in tests.iter().choose_multiple(rng, tests_cnt) {
+        run_single_test(file, 0, true).with_context(|| format!("Test {}", file.display()))?;
+    }
+
+    Ok(())
+}
+
+/// Returns whether the test was long enough to be rewound.
+fn run_single_test(
+    test_path: impl AsRef<Path>,
+    test_case: u32,
+    save_snapshot: bool,
+) -> anyhow::Result<bool> {
+    let mut cycle = 0;
+    let mut snapshot: Option<EmulatorSnapshot> = None;
+    let mut rewound = false;
+    let mut original_trace: Vec<(Address, Timepoint)> = vec![];
+    let mut replayed_trace: Vec<(Address, Timepoint)> = vec![];
+
+    let mut callback = |emu: &mut Emulator, event: CallbackType| {
+        if !matches!(event, CallbackType::OnStepCycle) {
+            return;
+        }
+        if cycle == SNAPSHOT_AT_CYCLE && !rewound {
+            let mut taken = emu.snapshot();
+            if save_snapshot {
+                let mut file = vec![];
+                taken.save(&mut file).expect("Snapshot is saved");
+                taken = EmulatorSnapshot::load(file.as_slice()).expect("Snapshot is loaded");
+            }
+            snapshot = Some(taken);
+        }
+        if cycle == SNAPSHOT_AT_CYCLE + REWIND_AFTER_CYCLES && !rewound {
+            emu.restore(snapshot.as_ref().expect("Snapshot was taken"));
+            cycle = SNAPSHOT_AT_CYCLE;
+            rewound = true;
+        }
+        if (SNAPSHOT_AT_CYCLE..SNAPSHOT_AT_CYCLE + REWIND_AFTER_CYCLES).contains(&cycle) {
+            let trace = if rewound {
+                &mut replayed_trace
+            } else {
+                &mut original_trace
+            };
+            trace.push((
+                emu.get_current_instruction_address(),
+                emu.get_emulation_time(),
+            ));
+        }
+        cycle += 1;
+    };
+
+    let test = FlashTestCase::load_from_test_file_and_case(test_path, test_case)?;
+    let mut test_runner = test.get_new_test_runner()?;
+    test_runner.set_callback(Some(&mut callback));
+    test_runner.run()?;
+
+    if rewound {
+        assert_eq!(
+            original_trace.len(),
+            replayed_trace.len(),
+            "The restored run ended at a different cycle."
+        );
+        for (i, (original, replayed)) in original_trace.iter().zip(&replayed_trace).enumerate() {
+            assert_eq!(
+                original,
+                replayed,
+                "The restored run diverged at cycle {}.",
+                SNAPSHOT_AT_CYCLE + i as u64
+            );
+        }
+    }
+    Ok(rewound)
+}
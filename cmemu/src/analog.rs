@@ -0,0 +1,86 @@
+//! Analog quantities measured by the emulated node: the supply voltage, the temperature
+//! and the voltages of the AUXIO pins.
+
+use crate::ConfigError;
+use crate::trace::Trace;
+use cmemu_lib::engine::{ANALOG_AUXIO_COUNT, AnalogInput, AnalogSourceImpl, Timepoint};
+use log::info;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// A source of the values of an analog input of the node.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct AnalogSetting {
+    pub input: AnalogInput,
+    pub source: AnalogValue,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum AnalogValue {
+    /// A constant value.
+    Constant(f64),
+    /// A CSV trace of `TIME_S,VALUE` lines.
+    Trace(PathBuf),
+}
+
+impl FromStr for AnalogSetting {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((input, source)) = s.split_once('=') else {
+            return Err(format!(
+                "unknown analog input setting `{s}`, expected `INPUT=VALUE` or `INPUT=csv:FILE`"
+            ));
+        };
+        let input = match input {
+            "vdds" => AnalogInput::SupplyVoltage,
+            "temperature" => AnalogInput::Temperature,
+            _ => {
+                let pin = input
+                    .strip_prefix("auxio")
+                    .and_then(|pin| pin.parse().ok())
+                    .filter(|&pin| pin < ANALOG_AUXIO_COUNT)
+                    .ok_or_else(|| {
+                        format!(
+                            "unknown analog input `{input}`, expected `vdds`, `temperature` \
+                             or `auxio0` to `auxio{}`",
+                            ANALOG_AUXIO_COUNT - 1
+                        )
+                    })?;
+                AnalogInput::AuxIo(pin)
+            }
+        };
+        let source = match source.strip_prefix("csv:") {
+            Some("") => return Err(format!("missing the trace file of `{s}`")),
+            Some(path) => AnalogValue::Trace(path.into()),
+            None => AnalogValue::Constant(
+                source
+                    .parse()
+                    .map_err(|err| format!("bad analog value `{source}`: {err}"))?,
+            ),
+        };
+        Ok(Self { input, source })
+    }
+}
+
+impl AnalogSetting {
+    pub fn open(&self) -> Result<AnalogSourceImpl, ConfigError> {
+        Ok(match &self.source {
+            AnalogValue::Constant(value) => Box::new(*value),
+            AnalogValue::Trace(path) => {
+                let map_err = |err| ConfigError("Failed to load analog input trace", Some(err));
+                let csv = fs::read_to_string(path).map_err(map_err)?;
+                let trace = Trace::<1>::parse(&csv).map_err(map_err)?;
+                info!(
+                    "Analog input {:?} replays {} samples.",
+                    self.input,
+                    trace.len()
+                );
+                Box::new(move |now: Timepoint| trace.sample(now)[0])
+            }
+        })
+    }
+}
@@ -0,0 +1,91 @@
+//! Configuration and export of the energy estimation.
+
+use crate::ConfigError;
+use cmemu_lib::engine::{CurrentTable, EnergySample, PICOS_IN_SECOND};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, BufWriter, ErrorKind, Write};
+use std::path::Path;
+
+/// Overrides of the default currents, with the names of the entities, the power modes,
+/// the radio activities and the memory accesses as the keys, e.g.:
+/// ```yaml
+/// supply_voltage: 3.3
+/// currents:   # uA
+///   CoreGate: { Active: 2000 }
+///   PeriphPowerDomain: { Active: 20, ClockGated: 20 }
+/// radio:      # uA
+///   Tx: 9100
+/// access_charges:  # pC
+///   FlashRead: 20
+/// ```
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct EnergyConfig {
+    supply_voltage: Option<f64>,
+    #[serde(default)]
+    currents: BTreeMap<String, BTreeMap<String, f64>>,
+    #[serde(default)]
+    radio: BTreeMap<String, f64>,
+    #[serde(default)]
+    access_charges: BTreeMap<String, f64>,
+}
+
+impl EnergyConfig {
+    fn apply(self, table: &mut CurrentTable) -> Result<(), String> {
+        fn parse<T: std::str::FromStr>(what: &str, name: &str) -> Result<T, String> {
+            name.parse().map_err(|_| format!("unknown {what} `{name}`"))
+        }
+
+        if let Some(volts) = self.supply_voltage {
+            table.set_supply_voltage(volts);
+        }
+        for (entity, modes) in self.currents {
+            for (mode, micro_amps) in modes {
+                table
+                    .set_entity_current(&entity, parse("power mode", &mode)?, micro_amps)
+                    .map_err(|err| err.to_string())?;
+            }
+        }
+        for (activity, micro_amps) in self.radio {
+            table.set_radio_current(parse("radio activity", &activity)?, micro_amps);
+        }
+        for (access, pico_coulombs) in self.access_charges {
+            table.set_access_charge(parse("memory access", &access)?, pico_coulombs);
+        }
+        Ok(())
+    }
+}
+
+/// Returns the default currents, overridden by the YAML configuration, if any.
+pub(crate) fn load_current_table(path: Option<&Path>) -> Result<CurrentTable, ConfigError> {
+    let mut table = CurrentTable::default();
+    if let Some(path) = path {
+        let map_err = |err| ConfigError("Failed to load the energy configuration", Some(err));
+        let config = fs::read_to_string(path).map_err(map_err)?;
+        serde_yaml::from_str::<EnergyConfig>(&config)
+            .map_err(|err| err.to_string())
+            .and_then(|config| config.apply(&mut table))
+            .map_err(|err| map_err(io::Error::new(ErrorKind::InvalidData, err)))?;
+    }
+    Ok(table)
+}
+
+/// Writes the samples as `TIME_S,CURRENT_UA,ENERGY_UJ` lines of a CSV file.
+pub(crate) fn write_energy_trace(path: &Path, samples: &[EnergySample]) -> io::Result<()> {
+    let mut file = BufWriter::new(fs::File::create(path)?);
+    writeln!(file, "time_s,current_ua,energy_uj")?;
+    for sample in samples {
+        let picos = sample.time.as_picos();
+        writeln!(
+            file,
+            "{}.{:012},{},{}",
+            picos / PICOS_IN_SECOND,
+            picos % PICOS_IN_SECOND,
+            sample.current,
+            sample.energy
+        )?;
+    }
+    file.flush()
+}
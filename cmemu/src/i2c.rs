@@ -0,0 +1,342 @@
+//! Virtual slaves attachable to the I2C bus of the emulated node.
+
+use crate::ConfigError;
+use crate::trace::Trace;
+use cmemu_lib::common::{I2cBusInterface, I2cBusInterfaceImpl};
+use cmemu_lib::engine::Timepoint;
+use log::{info, warn};
+use std::fs;
+use std::io::{self, ErrorKind, Seek, SeekFrom, Write};
+use std::panic::UnwindSafe;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// The size of a newly created EEPROM image, as of the 24C02.
+const EEPROM_DEFAULT_SIZE: usize = 256;
+
+/// The largest EEPROM addressable with two address bytes.
+const EEPROM_MAX_SIZE: usize = 1 << 16;
+
+/// The value read from an undriven data line.
+const IDLE_BYTE: u8 = 0xff;
+
+mod hdc1000_register {
+    pub(super) const TEMPERATURE: u8 = 0x00;
+    pub(super) const HUMIDITY: u8 = 0x01;
+    pub(super) const CONFIGURATION: u8 = 0x02;
+    pub(super) const MANUFACTURER_ID: u8 = 0xfe;
+    pub(super) const DEVICE_ID: u8 = 0xff;
+}
+
+/// Texas Instruments.
+const HDC1000_MANUFACTURER_ID: u16 = 0x5449;
+const HDC1000_DEVICE_ID: u16 = 0x1000;
+/// The `MODE` bit of the configuration: acquisition of both temperature and humidity.
+const HDC1000_CONFIGURATION_MODE: u16 = 1 << 12;
+const HDC1000_DEFAULT_CONFIGURATION: u16 = HDC1000_CONFIGURATION_MODE;
+
+/// A virtual slave on the I2C bus.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum I2cDevice {
+    /// A 24Cxx-style EEPROM backed by a file, created erased if it doesn't exist.
+    Eeprom { address: u8, path: PathBuf },
+    /// An HDC1000 temperature and humidity sensor replaying a CSV trace.
+    Sensor { address: u8, path: PathBuf },
+}
+
+impl FromStr for I2cDevice {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let usage = || {
+            format!(
+                "unknown I2C device `{s}`, expected `eeprom:ADDRESS:FILE` or `sensor:ADDRESS:CSV`"
+            )
+        };
+        let mut parts = s.splitn(3, ':');
+        let (Some(kind), Some(address), Some(path)) = (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(usage());
+        };
+        let address = match address.strip_prefix("0x") {
+            Some(hex) => u8::from_str_radix(hex, 16),
+            None => address.parse(),
+        }
+        .map_err(|err| format!("bad I2C address `{address}`: {err}"))?;
+        if address > 0x7f {
+            return Err(format!("I2C address {address:#x} is not a 7-bit address"));
+        }
+        if path.is_empty() {
+            return Err(usage());
+        }
+        let path = path.into();
+        match kind {
+            "eeprom" => Ok(Self::Eeprom { address, path }),
+            "sensor" | "hdc1000" => Ok(Self::Sensor { address, path }),
+            _ => Err(usage()),
+        }
+    }
+}
+
+impl I2cDevice {
+    pub fn open(&self) -> Result<I2cBusInterfaceImpl, ConfigError> {
+        Ok(match self {
+            Self::Eeprom { address, path } => Box::new(
+                Eeprom::open(*address, path)
+                    .map_err(|err| ConfigError("Failed to open I2C EEPROM image", Some(err)))?,
+            ),
+            Self::Sensor { address, path } => {
+                let map_err = |err| ConfigError("Failed to load I2C sensor trace", Some(err));
+                let trace = fs::read_to_string(path).map_err(map_err)?;
+                Box::new(Hdc1000::new(
+                    *address,
+                    Trace::parse(&trace).map_err(map_err)?,
+                ))
+            }
+        })
+    }
+}
+
+/// An EEPROM with the protocol of the 24Cxx series.
+///
+/// A write transaction starts with the memory address (one byte for images up to 256 bytes,
+/// two bytes otherwise) followed by the data, and reads continue from the current address.
+/// Writes complete immediately (the device never ignores its address while busy) and are
+/// written through to the backing file at the STOP condition.
+struct Eeprom {
+    address: u8,
+    file: fs::File,
+    memory: Vec<u8>,
+    /// The current memory address, incremented by every byte written or read.
+    pointer: usize,
+    /// Number of bytes received in the current write transaction.
+    received: usize,
+    /// The range of the memory modified in the current transaction.
+    dirty: Option<(usize, usize)>,
+}
+
+impl Eeprom {
+    fn open(address: u8, path: &Path) -> io::Result<Self> {
+        let mut file = fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        let mut memory = fs::read(path)?;
+        if memory.is_empty() {
+            memory = vec![0xff; EEPROM_DEFAULT_SIZE];
+            file.write_all(&memory)?;
+        }
+        if memory.len() > EEPROM_MAX_SIZE {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!("the image is larger than {EEPROM_MAX_SIZE} bytes"),
+            ));
+        }
+        info!(
+            "I2C EEPROM {} at {address:#04x} has {} bytes.",
+            path.display(),
+            memory.len()
+        );
+        Ok(Self {
+            address,
+            file,
+            memory,
+            pointer: 0,
+            received: 0,
+            dirty: None,
+        })
+    }
+
+    fn address_bytes(&self) -> usize {
+        if self.memory.len() > EEPROM_DEFAULT_SIZE {
+            2
+        } else {
+            1
+        }
+    }
+
+    fn write_back(&mut self) -> io::Result<()> {
+        let Some((first, last)) = self.dirty.take() else {
+            return Ok(());
+        };
+        // The modified range may wrap around the end of the memory.
+        let ranges = if first <= last {
+            [first..last + 1, 0..0]
+        } else {
+            [first..self.memory.len(), 0..last + 1]
+        };
+        for range in ranges.into_iter().filter(|range| !range.is_empty()) {
+            self.file.seek(SeekFrom::Start(range.start as u64))?;
+            self.file.write_all(&self.memory[range])?;
+        }
+        self.file.flush()
+    }
+}
+
+impl UnwindSafe for Eeprom {}
+
+impl I2cBusInterface for Eeprom {
+    fn start(&mut self, _now: Timepoint, address: u8, read: bool) -> bool {
+        if address != self.address {
+            return false;
+        }
+        if !read {
+            self.received = 0;
+        }
+        true
+    }
+
+    fn write(&mut self, byte: u8) -> bool {
+        let address_bytes = self.address_bytes();
+        if self.received < address_bytes {
+            if self.received == 0 {
+                self.pointer = 0;
+            }
+            self.pointer = ((self.pointer << 8) | usize::from(byte)) % self.memory.len();
+        } else {
+            self.memory[self.pointer] = byte;
+            self.dirty = Some(match self.dirty {
+                Some((first, _)) => (first, self.pointer),
+                None => (self.pointer, self.pointer),
+            });
+            self.pointer = (self.pointer + 1) % self.memory.len();
+        }
+        self.received += 1;
+        true
+    }
+
+    fn read(&mut self, _ack: bool) -> u8 {
+        let byte = self.memory[self.pointer];
+        self.pointer = (self.pointer + 1) % self.memory.len();
+        byte
+    }
+
+    fn stop(&mut self) {
+        if let Err(err) = self.write_back() {
+            warn!("Failed to write back the I2C EEPROM image: {err}");
+        }
+    }
+}
+
+/// The HDC1000 temperature and humidity sensor, as on the `SensorTag`.
+///
+/// Writing the register pointer starts a measurement, which completes immediately with the
+/// sample of the trace at the time of the transaction. Reading the temperature register
+/// in the acquisition mode returns both the temperature and the humidity.
+struct Hdc1000 {
+    address: u8,
+    /// The temperature and the humidity in time.
+    trace: Trace<2>,
+    configuration: u16,
+    pointer: u8,
+    /// The raw temperature and humidity of the last measurement.
+    measurement: (u16, u16),
+    /// The time of the current transaction.
+    now: Timepoint,
+    /// Number of bytes transferred in the current transaction.
+    transferred: usize,
+}
+
+impl Hdc1000 {
+    fn new(address: u8, trace: Trace<2>) -> Self {
+        info!(
+            "I2C HDC1000 at {address:#04x} replays {} samples.",
+            trace.len()
+        );
+        let mut sensor = Self {
+            address,
+            trace,
+            configuration: HDC1000_DEFAULT_CONFIGURATION,
+            pointer: hdc1000_register::TEMPERATURE,
+            measurement: (0, 0),
+            now: Timepoint::ZERO,
+            transferred: 0,
+        };
+        sensor.measure();
+        sensor
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn measure(&mut self) {
+        // [HDC1000] 8.6.1, 8.6.2: the results are fractions of 2^16 of the measurement range.
+        let to_raw = |fraction: f64| (fraction.clamp(0.0, 1.0) * 65535.0) as u16;
+        let [temperature, humidity] = self.trace.sample(self.now);
+        self.measurement = (
+            to_raw((temperature + 40.0) / 165.0),
+            to_raw(humidity / 100.0),
+        );
+    }
+
+    fn is_acquisition_mode(&self) -> bool {
+        self.configuration & HDC1000_CONFIGURATION_MODE != 0
+    }
+
+    /// Returns the bytes read from the register selected by the pointer.
+    // The registers are transferred with the most significant byte first.
+    #[allow(clippy::big_endian_bytes)]
+    fn register_bytes(&self) -> Vec<u8> {
+        let (temperature, humidity) = self.measurement;
+        match self.pointer {
+            hdc1000_register::TEMPERATURE if self.is_acquisition_mode() => {
+                [temperature.to_be_bytes(), humidity.to_be_bytes()].concat()
+            }
+            hdc1000_register::TEMPERATURE => temperature.to_be_bytes().to_vec(),
+            hdc1000_register::HUMIDITY => humidity.to_be_bytes().to_vec(),
+            hdc1000_register::CONFIGURATION => self.configuration.to_be_bytes().to_vec(),
+            hdc1000_register::MANUFACTURER_ID => HDC1000_MANUFACTURER_ID.to_be_bytes().to_vec(),
+            hdc1000_register::DEVICE_ID => HDC1000_DEVICE_ID.to_be_bytes().to_vec(),
+            _ => vec![],
+        }
+    }
+}
+
+impl I2cBusInterface for Hdc1000 {
+    fn start(&mut self, now: Timepoint, address: u8, _read: bool) -> bool {
+        if address != self.address {
+            return false;
+        }
+        self.now = now;
+        self.transferred = 0;
+        true
+    }
+
+    fn write(&mut self, byte: u8) -> bool {
+        match self.transferred {
+            0 => {
+                self.pointer = byte;
+                if matches!(
+                    byte,
+                    hdc1000_register::TEMPERATURE | hdc1000_register::HUMIDITY
+                ) {
+                    self.measure();
+                }
+            }
+            1 | 2 if self.pointer == hdc1000_register::CONFIGURATION => {
+                let shift = if self.transferred == 1 { 8 } else { 0 };
+                self.configuration =
+                    (self.configuration & !(0xff << shift)) | (u16::from(byte) << shift);
+            }
+            _ => warn!(
+                "I2C HDC1000 ignored {byte:#04x} written to register {:#04x}.",
+                self.pointer
+            ),
+        }
+        self.transferred += 1;
+        true
+    }
+
+    fn read(&mut self, _ack: bool) -> u8 {
+        let byte = self
+            .register_bytes()
+            .get(self.transferred)
+            .copied()
+            .unwrap_or(IDLE_BYTE);
+        self.transferred += 1;
+        byte
+    }
+
+    fn stop(&mut self) {}
+}
@@ -1,16 +1,27 @@
 use clap::{Args, Parser};
-use cmemu_lib::common::{RequestedExit, UARTLiteInterface};
+use cmemu_lib::common::{RequestedExit, SsiModule, UARTLiteInterface};
 use cmemu_lib::engine::{Emulator, Timepoint};
 use flexi_logger::LoggerHandle;
 use log::{error, info, warn};
 use std::error::Error;
 use std::fmt::{Display, Formatter};
 use std::io::Write;
-use std::panic::UnwindSafe;
+use std::panic::{AssertUnwindSafe, UnwindSafe};
 use std::path::PathBuf;
 use std::process::{ExitCode, Termination};
 use std::{fs, io};
 
+mod analog;
+mod energy;
+mod i2c;
+mod spi;
+mod trace;
+mod uart;
+pub use analog::{AnalogSetting, AnalogValue};
+pub use i2c::I2cDevice;
+pub use spi::SpiDevice;
+pub use uart::UartBackend;
+
 #[derive(Debug)]
 pub struct TimeoutError(Duration);
 impl Display for TimeoutError {
@@ -49,6 +60,38 @@ pub struct App {
     /// path to dump data sent to UART Lite (scif) to, hint: try `/dev/stdout`
     pub uart_lite_dump: Option<PathBuf>,
 
+    #[arg(long, value_name = "BACKEND")]
+    /// connect the UART0 serial line to `stdio`, `file:OUTPUT[,INPUT]`, `pty` or `tcp:ADDRESS`
+    ///
+    /// The TCP server waits for a single client before the emulation starts.
+    pub uart0: Option<UartBackend>,
+
+    #[arg(long, value_name = "DEVICE")]
+    /// attach a virtual `nor:FILE` flash, `loopback` or `script:FILE` device to the SSI0 bus
+    ///
+    /// A missing NOR flash image is created erased. The script has a line of hexadecimal
+    /// `REPLY` or `EXPECTED:REPLY` frames per transaction.
+    pub ssi0: Option<SpiDevice>,
+
+    #[arg(long, value_name = "DEVICE")]
+    /// attach a virtual device to the SSI1 bus, see `--ssi0`
+    pub ssi1: Option<SpiDevice>,
+
+    #[arg(long, value_name = "DEVICE")]
+    /// attach a virtual `eeprom:ADDRESS:FILE` or `sensor:ADDRESS:CSV` slave to the I2C bus
+    ///
+    /// May be repeated. A missing EEPROM image is created erased. The sensor is an HDC1000
+    /// replaying the `TIME_S,TEMPERATURE_C,HUMIDITY_PCT` lines of the CSV trace.
+    pub i2c: Vec<I2cDevice>,
+
+    #[arg(long, value_name = "INPUT=SOURCE")]
+    /// set an analog input of the node: `vdds`, `temperature` or `auxio0`-`auxio7`
+    ///
+    /// May be repeated. The source is a constant (in volts or °C) or `csv:FILE` replaying
+    /// the `TIME_S,VALUE` lines of the trace. By default, VDDS is 3 V, the temperature is 25 °C
+    /// and the AUXIO pins are at 0 V.
+    pub analog: Vec<AnalogSetting>,
+
     #[arg(long, alias("mocked-mem-os"))]
     /// name of OS for which memory accesses should be mocked (DEPRECATED)
     pub mocked_memory_os: Option<String>,
@@ -64,6 +107,15 @@ pub struct App {
     /// (use `.d` extension).
     pub cycle_debug_log_file: Option<PathBuf>,
 
+    #[command(flatten, next_help_heading = "Energy estimation")]
+    pub energy: EnergyArgs,
+
+    #[arg(long, num_args = 0..=1, value_name = "PATH")]
+    /// write the Flash memory image back at exit, to PATH or to the flash file if omitted
+    ///
+    /// The image is written in the raw binary format, so PATH is required for ELF inputs.
+    pub flash_write_back: Option<Option<PathBuf>>,
+
     #[cfg(feature = "elf")]
     #[command(flatten, next_help_heading = "Elf options")]
     pub elf_params: cmemu_elf_loader::ElfArgs,
@@ -86,6 +138,28 @@ pub struct Duration {
     pub seconds: Option<f64>,
 }
 
+#[derive(Args, Debug, Clone)]
+#[non_exhaustive]
+pub struct EnergyArgs {
+    #[arg(long, value_name = "YAML")]
+    /// estimate the energy with the currents of YAML overriding the CC2650 datasheet ones
+    pub energy_config: Option<PathBuf>,
+
+    #[arg(long)]
+    /// print the energy consumed by the emulated node at exit
+    pub energy_report: bool,
+
+    #[arg(long, value_name = "CSV")]
+    /// write the time series of the current and the consumed energy to CSV at exit
+    pub energy_trace: Option<PathBuf>,
+}
+
+impl EnergyArgs {
+    fn is_enabled(&self) -> bool {
+        self.energy_config.is_some() || self.energy_report || self.energy_trace.is_some()
+    }
+}
+
 impl Display for Duration {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match (self.cycles, self.seconds) {
@@ -109,11 +183,58 @@ pub fn run(
         // FIXME: this interface of passing some params down is not very scalable,
         //        but we cannot let cmemu_gdb know the App type as that introduces circ deps.
         gdb_params.flash_file = Some(args.flash_file.clone());
+        if args.flash_write_back.is_some() {
+            warn!("Writing the Flash image back is not supported when running with gdb.");
+        }
+        if args.energy.energy_report || args.energy.energy_trace.is_some() {
+            warn!("Exporting the energy estimation is not supported when running with gdb.");
+        }
         let emulator = configure(args)?;
         return gdb_params.run_emulator(emulator, duration.cycles, logger);
     }
-    let emulator = configure(args)?;
-    run_capture_semihosting(emulator, duration)
+    let write_back_path = flash_write_back_path(&args)?;
+    let energy = args.energy.clone();
+    let mut emulator = configure(args)?;
+    let result = run_capture_semihosting(&mut emulator, duration);
+    if let Some(path) = write_back_path {
+        info!("Writing the Flash image back to {}.", path.display());
+        fs::write(&path, emulator.get_flash_contents())
+            .map_err(|err| ConfigError("Failed to write the Flash image back", Some(err)))?;
+    }
+    if energy.energy_report
+        && let Some(report) = emulator.energy_report()
+    {
+        eprint!("{report}");
+    }
+    if let Some(ref path) = energy.energy_trace {
+        info!("Writing the energy trace to {}.", path.display());
+        energy::write_energy_trace(path, &emulator.take_energy_samples())
+            .map_err(|err| ConfigError("Failed to write the energy trace", Some(err)))?;
+    }
+    result
+}
+
+/// Resolves the `--flash-write-back` option to the path of the written image.
+fn flash_write_back_path(args: &App) -> Result<Option<PathBuf>, Box<dyn Error>> {
+    match args.flash_write_back {
+        None => Ok(None),
+        Some(Some(ref path)) => Ok(Some(path.clone())),
+        Some(None) => {
+            let mut header = [0u8; 4];
+            let is_elf = fs::File::open(&args.flash_file)
+                .and_then(|mut f| io::Read::read_exact(&mut f, &mut header))
+                .is_ok_and(|()| header == *b"\x7fELF");
+            if is_elf {
+                Err(ConfigError(
+                    "Writing the Flash image back to an ELF file is not supported, give a PATH",
+                    None,
+                )
+                .into())
+            } else {
+                Ok(Some(args.flash_file.clone()))
+            }
+        }
+    }
 }
 
 #[allow(clippy::needless_pass_by_value, reason = "false positive")]
@@ -141,6 +262,21 @@ pub fn configure(args: App) -> Result<Emulator, Box<dyn Error>> {
         None
     };
 
+    let uart0 = args.uart0.as_ref().map(UartBackend::open).transpose()?;
+    let ssi0 = args.ssi0.as_ref().map(SpiDevice::open).transpose()?;
+    let ssi1 = args.ssi1.as_ref().map(SpiDevice::open).transpose()?;
+    let i2c = args
+        .i2c
+        .iter()
+        .map(I2cDevice::open)
+        .collect::<Result<Vec<_>, _>>()?;
+    let analog = args
+        .analog
+        .iter()
+        .map(|setting| Ok((setting.input, setting.open()?)))
+        .collect::<Result<Vec<_>, ConfigError>>()?;
+    let current_table = energy::load_current_table(args.energy.energy_config.as_deref())?;
+
     // construct emulator, configure it & run it
     let mut emulator = {
         #[cfg(feature = "elf")]
@@ -164,6 +300,24 @@ pub fn configure(args: App) -> Result<Emulator, Box<dyn Error>> {
     if let Some(scif_dumper) = uart_lite_dump {
         emulator.set_uart_lite_interface(Some(Box::new(scif_dumper)));
     }
+    if uart0.is_some() {
+        emulator.set_uart_interface(uart0);
+    }
+    if ssi0.is_some() {
+        emulator.set_spi_device(SsiModule::Ssi0, ssi0);
+    }
+    if ssi1.is_some() {
+        emulator.set_spi_device(SsiModule::Ssi1, ssi1);
+    }
+    for device in i2c {
+        emulator.attach_i2c_device(device);
+    }
+    for (input, source) in analog {
+        emulator.set_analog_source(input, Some(source));
+    }
+    if args.energy.is_enabled() {
+        emulator.enable_energy_meter(current_table, args.energy.energy_trace.is_some());
+    }
 
     #[cfg(feature = "cycle-debug-logger")]
     if let log_file @ Some(_) = args.cycle_debug_log_file {
@@ -186,18 +340,19 @@ pub fn configure(args: App) -> Result<Emulator, Box<dyn Error>> {
 }
 
 pub fn run_capture_semihosting(
-    emulator: Emulator,
+    emulator: &mut Emulator,
     duration: Duration,
 ) -> Result<ExitCode, Box<dyn Error>> {
     info!("Executing {}.", duration);
-    let runner = || run_emulator(emulator, duration);
+    // The emulator is not used after a panic other than the requested exit.
+    let runner = AssertUnwindSafe(|| run_emulator(emulator, duration));
     std::panic::catch_unwind(runner).unwrap_or_else(|p| match p.downcast::<RequestedExit>() {
         Ok(code) => Ok(ExitCode::from(code.code())),
         Err(p) => std::panic::resume_unwind(p),
     })
 }
 
-fn run_emulator(mut emulator: Emulator, duration: Duration) -> Result<ExitCode, Box<dyn Error>> {
+fn run_emulator(emulator: &mut Emulator, duration: Duration) -> Result<ExitCode, Box<dyn Error>> {
     // TODO: reintroduce siginfo while accounting for step_until?
     match (duration.cycles, duration.seconds) {
         (Some(cycles), None) => {
@@ -0,0 +1,367 @@
+//! Virtual devices attachable to the SSI (SPI) buses of the emulated node.
+
+use crate::ConfigError;
+use cmemu_lib::common::{SpiDeviceInterface, SpiDeviceInterfaceImpl};
+use log::{info, warn};
+use std::collections::VecDeque;
+use std::fs;
+use std::io::{self, ErrorKind, Seek, SeekFrom, Write};
+use std::panic::UnwindSafe;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// The size of a newly created NOR flash image, as of the MX25R8035F on the `LaunchPads`.
+const NOR_DEFAULT_SIZE: usize = 1 << 20;
+
+/// JEDEC manufacturer and device ID of the MX25R8035F.
+const NOR_JEDEC_ID: [u8; 3] = [0xc2, 0x28, 0x14];
+
+/// The electronic signature returned by the release from deep power-down command.
+const NOR_ELECTRONIC_ID: u8 = 0x14;
+
+const NOR_PAGE_SIZE: usize = 256;
+
+/// The value read from an undriven data line.
+const IDLE_FRAME: u16 = 0xff;
+
+mod nor_command {
+    pub(super) const WRITE_STATUS: u8 = 0x01;
+    pub(super) const PAGE_PROGRAM: u8 = 0x02;
+    pub(super) const READ: u8 = 0x03;
+    pub(super) const WRITE_DISABLE: u8 = 0x04;
+    pub(super) const READ_STATUS: u8 = 0x05;
+    pub(super) const WRITE_ENABLE: u8 = 0x06;
+    pub(super) const FAST_READ: u8 = 0x0b;
+    pub(super) const SECTOR_ERASE: u8 = 0x20;
+    pub(super) const BLOCK_ERASE_32K: u8 = 0x52;
+    pub(super) const CHIP_ERASE: u8 = 0x60;
+    pub(super) const READ_ID: u8 = 0x9f;
+    pub(super) const RELEASE_POWER_DOWN: u8 = 0xab;
+    pub(super) const DEEP_POWER_DOWN: u8 = 0xb9;
+    pub(super) const CHIP_ERASE_ALT: u8 = 0xc7;
+    pub(super) const BLOCK_ERASE_64K: u8 = 0xd8;
+}
+
+/// A virtual device on the serial bus of an SSI module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SpiDevice {
+    /// A SPI NOR flash backed by a file, created erased if it doesn't exist.
+    NorFlash(PathBuf),
+    /// Replies with the received frames, as if the data lines were connected.
+    Loopback,
+    /// Replies with the frames of a script file, a line per transaction.
+    Script(PathBuf),
+}
+
+impl FromStr for SpiDevice {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            None if s == "loopback" || s == "echo" => Ok(Self::Loopback),
+            Some(("nor", path)) if !path.is_empty() => Ok(Self::NorFlash(path.into())),
+            Some(("script", path)) if !path.is_empty() => Ok(Self::Script(path.into())),
+            _ => Err(format!(
+                "unknown SPI device `{s}`, expected `nor:FILE`, `loopback` or `script:FILE`"
+            )),
+        }
+    }
+}
+
+impl SpiDevice {
+    pub fn open(&self) -> Result<SpiDeviceInterfaceImpl, ConfigError> {
+        Ok(match self {
+            Self::NorFlash(path) => Box::new(
+                NorFlash::open(path)
+                    .map_err(|err| ConfigError("Failed to open SPI flash image", Some(err)))?,
+            ),
+            Self::Loopback => Box::new(Loopback),
+            Self::Script(path) => {
+                let map_err = |err| ConfigError("Failed to load SPI device script", Some(err));
+                let script = fs::read_to_string(path).map_err(map_err)?;
+                Box::new(ScriptedDevice::parse(&script).map_err(map_err)?)
+            }
+        })
+    }
+}
+
+/// A SPI NOR flash with the common command set of the Macronix MX25R series.
+///
+/// Programming and erasing complete immediately (the status never reports a write in progress)
+/// and are written through to the backing file.
+struct NorFlash {
+    file: fs::File,
+    memory: Vec<u8>,
+    write_enabled: bool,
+    powered_down: bool,
+    /// The opcode and the address bytes of the current command.
+    header: Vec<u8>,
+    /// Number of bytes received in the current transaction.
+    received: usize,
+    /// Data of the page program command, by offset in the page.
+    page: Vec<Option<u8>>,
+}
+
+impl NorFlash {
+    fn open(path: &Path) -> io::Result<Self> {
+        let mut file = fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        let mut memory = fs::read(path)?;
+        if memory.is_empty() {
+            memory = vec![0xff; NOR_DEFAULT_SIZE];
+            file.write_all(&memory)?;
+        }
+        info!("SPI flash {} has {} bytes.", path.display(), memory.len());
+        Ok(Self {
+            file,
+            memory,
+            write_enabled: false,
+            powered_down: false,
+            header: Vec::with_capacity(4),
+            received: 0,
+            page: vec![None; NOR_PAGE_SIZE],
+        })
+    }
+
+    fn address(&self) -> usize {
+        let address = self.header[1..4]
+            .iter()
+            .fold(0, |address, &byte| (address << 8) | usize::from(byte));
+        address % self.memory.len()
+    }
+
+    fn status(&self) -> u8 {
+        u8::from(self.write_enabled) << 1
+    }
+
+    /// Returns the byte shifted out by the flash for the `index`-th byte of a transaction.
+    fn reply(&mut self, index: usize, byte: u8) -> u8 {
+        let command = self.header[0];
+        match command {
+            nor_command::READ_ID => NOR_JEDEC_ID.get(index - 1).copied().unwrap_or(0),
+            nor_command::READ_STATUS => self.status(),
+            nor_command::READ if index >= 4 => {
+                self.memory[(self.address() + index - 4) % self.memory.len()]
+            }
+            // There is a dummy byte after the address.
+            nor_command::FAST_READ if index >= 5 => {
+                self.memory[(self.address() + index - 5) % self.memory.len()]
+            }
+            nor_command::RELEASE_POWER_DOWN if index >= 4 => NOR_ELECTRONIC_ID,
+            nor_command::PAGE_PROGRAM if index >= 4 => {
+                // The data wraps around within the page.
+                let offset = (self.address() + index - 4) % NOR_PAGE_SIZE;
+                self.page[offset] = Some(byte);
+                0xff
+            }
+            _ => 0xff,
+        }
+    }
+
+    /// Executes the command of the finished transaction.
+    fn execute(&mut self) {
+        let command = self.header[0];
+        let has_address = self.header.len() == 4;
+        if self.powered_down {
+            if command == nor_command::RELEASE_POWER_DOWN {
+                self.powered_down = false;
+            }
+            return;
+        }
+        match command {
+            nor_command::WRITE_ENABLE => self.write_enabled = true,
+            nor_command::WRITE_DISABLE => self.write_enabled = false,
+            nor_command::DEEP_POWER_DOWN => self.powered_down = true,
+            nor_command::WRITE_STATUS => {
+                warn!("SPI flash status register write is ignored.");
+                self.write_enabled = false;
+            }
+            nor_command::PAGE_PROGRAM if has_address && self.write_enabled => {
+                let page = self.address() / NOR_PAGE_SIZE * NOR_PAGE_SIZE;
+                for (offset, byte) in self.page.iter().enumerate() {
+                    if let Some(byte) = byte {
+                        // Programming can only clear bits.
+                        self.memory[page + offset] &= byte;
+                    }
+                }
+                self.write_back(page..page + NOR_PAGE_SIZE);
+                self.write_enabled = false;
+            }
+            nor_command::SECTOR_ERASE
+            | nor_command::BLOCK_ERASE_32K
+            | nor_command::BLOCK_ERASE_64K
+                if has_address && self.write_enabled =>
+            {
+                let size = match command {
+                    nor_command::SECTOR_ERASE => 4 << 10,
+                    nor_command::BLOCK_ERASE_32K => 32 << 10,
+                    _ => 64 << 10,
+                };
+                let start = self.address() / size * size;
+                let range = start..(start + size).min(self.memory.len());
+                self.memory[range.clone()].fill(0xff);
+                self.write_back(range);
+                self.write_enabled = false;
+            }
+            nor_command::CHIP_ERASE | nor_command::CHIP_ERASE_ALT if self.write_enabled => {
+                self.memory.fill(0xff);
+                self.write_back(0..self.memory.len());
+                self.write_enabled = false;
+            }
+            nor_command::PAGE_PROGRAM
+            | nor_command::SECTOR_ERASE
+            | nor_command::BLOCK_ERASE_32K
+            | nor_command::BLOCK_ERASE_64K
+            | nor_command::CHIP_ERASE
+            | nor_command::CHIP_ERASE_ALT => {
+                warn!("SPI flash command {command:#04x} ignored: write is not enabled.");
+            }
+            _ => (),
+        }
+    }
+
+    fn write_back(&mut self, range: std::ops::Range<usize>) {
+        let result = self
+            .file
+            .seek(SeekFrom::Start(range.start as u64))
+            .and_then(|_| self.file.write_all(&self.memory[range]));
+        if let Err(err) = result {
+            warn!("Failed to write the SPI flash image back: {err}.");
+        }
+    }
+}
+
+impl UnwindSafe for NorFlash {}
+
+impl SpiDeviceInterface for NorFlash {
+    fn select(&mut self) {
+        self.header.clear();
+        self.received = 0;
+        self.page.fill(None);
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn transfer(&mut self, data: u16, bits: u8) -> u16 {
+        if bits != 8 {
+            warn!("SPI flash received a {bits}-bit frame, only the low byte is used.");
+        }
+        let byte = data as u8;
+        let index = self.received;
+        self.received += 1;
+        if self.header.len() < 4 {
+            self.header.push(byte);
+        }
+        if index == 0 || (self.powered_down && self.header[0] != nor_command::RELEASE_POWER_DOWN) {
+            return IDLE_FRAME;
+        }
+        u16::from(self.reply(index, byte))
+    }
+
+    fn deselect(&mut self) {
+        if !self.header.is_empty() {
+            self.execute();
+        }
+    }
+}
+
+struct Loopback;
+
+impl SpiDeviceInterface for Loopback {
+    fn select(&mut self) {}
+
+    fn transfer(&mut self, data: u16, _bits: u8) -> u16 {
+        data
+    }
+
+    fn deselect(&mut self) {}
+}
+
+/// One frame of a script: the expected received frame, if checked, and the reply.
+type ScriptFrame = (Option<u16>, u16);
+
+/// A device replying with the frames of a script, a line per transaction.
+///
+/// The frames of a line are hexadecimal numbers separated by whitespace: `REPLY` or
+/// `EXPECTED:REPLY`, where a mismatch with the expected frame is reported with a warning.
+/// The device replies with zeros past the end of a line or of the script.
+/// Empty lines and lines starting with `#` are skipped.
+struct ScriptedDevice {
+    transactions: VecDeque<VecDeque<ScriptFrame>>,
+    current: VecDeque<ScriptFrame>,
+    /// Number of the current transaction, for diagnostics.
+    transaction: usize,
+}
+
+impl ScriptedDevice {
+    fn parse(script: &str) -> io::Result<Self> {
+        let parse_frame = |frame: &str| {
+            u16::from_str_radix(frame, 16).map_err(|err| {
+                io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("bad frame `{frame}`: {err}"),
+                )
+            })
+        };
+        let transactions = script
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| {
+                line.split_whitespace()
+                    .map(|token| match token.split_once(':') {
+                        Some((expected, reply)) => {
+                            Ok((Some(parse_frame(expected)?), parse_frame(reply)?))
+                        }
+                        None => Ok((None, parse_frame(token)?)),
+                    })
+                    .collect()
+            })
+            .collect::<io::Result<_>>()?;
+        Ok(Self {
+            transactions,
+            current: VecDeque::new(),
+            transaction: 0,
+        })
+    }
+}
+
+impl SpiDeviceInterface for ScriptedDevice {
+    fn select(&mut self) {
+        self.transaction += 1;
+        self.current = self.transactions.pop_front().unwrap_or_default();
+    }
+
+    fn transfer(&mut self, data: u16, _bits: u8) -> u16 {
+        let Some((expected, reply)) = self.current.pop_front() else {
+            info!(
+                "SPI script transaction {} received unscripted {data:#06x}.",
+                self.transaction
+            );
+            return 0;
+        };
+        if let Some(expected) = expected
+            && expected != data
+        {
+            warn!(
+                "SPI script transaction {} expected {expected:#06x}, received {data:#06x}.",
+                self.transaction
+            );
+        }
+        reply
+    }
+
+    fn deselect(&mut self) {
+        if !self.current.is_empty() {
+            warn!(
+                "SPI script transaction {} ended {} frames early.",
+                self.transaction,
+                self.current.len()
+            );
+        }
+    }
+}
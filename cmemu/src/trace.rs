@@ -0,0 +1,70 @@
+//! Traces of the environment of the emulated node, replayed by the host-side devices.
+
+use cmemu_lib::engine::{PICOS_IN_SECOND, Timepoint};
+use std::io::{self, ErrorKind};
+
+/// A trace of `N` quantities in time.
+///
+/// Each line of the CSV is `TIME_S` followed by the `N` values, with the time of the emulation
+/// in seconds, sorted by time. A sample holds until the next one.
+/// Empty lines, lines starting with `#` and a header line are skipped.
+pub(crate) struct Trace<const N: usize> {
+    /// The samples: time in picoseconds and the values.
+    samples: Vec<(u64, [f64; N])>,
+}
+
+impl<const N: usize> Trace<N> {
+    pub(crate) fn parse(csv: &str) -> io::Result<Self> {
+        let invalid = |line: &str, why: &str| {
+            io::Error::new(ErrorKind::InvalidData, format!("bad line `{line}`: {why}"))
+        };
+        let mut samples: Vec<(u64, [f64; N])> = Vec::new();
+        for (index, line) in csv.lines().map(str::trim).enumerate() {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<f64> = match line.split(',').map(|f| f.trim().parse()).collect() {
+                Ok(fields) => fields,
+                Err(_) if index == 0 => continue,
+                Err(err) => return Err(invalid(line, &err.to_string())),
+            };
+            let Some((&time, values)) = fields.split_first() else {
+                unreachable!("splitting yields at least one field")
+            };
+            let Ok(values) = <[f64; N]>::try_from(values) else {
+                return Err(invalid(line, &format!("expected {} fields", N + 1)));
+            };
+            if time.is_nan() || time < 0.0 {
+                return Err(invalid(line, "negative time"));
+            }
+            #[allow(
+                clippy::cast_possible_truncation,
+                clippy::cast_sign_loss,
+                clippy::cast_precision_loss
+            )]
+            let time = (time * PICOS_IN_SECOND as f64) as u64;
+            if samples.last().is_some_and(|&(last, _)| last > time) {
+                return Err(invalid(line, "the samples are not sorted by time"));
+            }
+            samples.push((time, values));
+        }
+        if samples.is_empty() {
+            return Err(io::Error::new(ErrorKind::InvalidData, "the trace is empty"));
+        }
+        Ok(Self { samples })
+    }
+
+    /// Number of the samples.
+    pub(crate) fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Returns the values at the time `now`.
+    pub(crate) fn sample(&self, now: Timepoint) -> [f64; N] {
+        let index = self
+            .samples
+            .partition_point(|&(time, _)| time <= now.as_picos())
+            .saturating_sub(1);
+        self.samples[index].1
+    }
+}
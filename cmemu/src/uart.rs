@@ -0,0 +1,250 @@
+//! Host-side backends of the UART0 serial line of the emulated node.
+
+use crate::ConfigError;
+use cmemu_lib::common::{UARTInterface, UARTInterfaceImpl};
+use log::{info, warn};
+use std::io::{self, ErrorKind, Read, Write};
+use std::net::TcpListener;
+use std::panic::UnwindSafe;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::sync::mpsc::{self, Receiver};
+use std::time::Duration;
+use std::{fs, thread};
+
+/// How long to wait before retrying a nonblocking read.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Where the UART0 serial line is connected to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum UartBackend {
+    /// The standard output and input of the emulator.
+    Stdio,
+    /// Transmitted data is written to `output`, received data is read from `input`.
+    File {
+        output: PathBuf,
+        input: Option<PathBuf>,
+    },
+    /// A new pseudo-terminal, its path is printed at startup.
+    #[cfg(target_os = "linux")]
+    Pty,
+    /// A TCP server on the given address, accepting a single connection before the emulation.
+    Tcp(String),
+}
+
+impl FromStr for UartBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            None if s == "stdio" => Ok(Self::Stdio),
+            #[cfg(target_os = "linux")]
+            None if s == "pty" => Ok(Self::Pty),
+            Some(("file", paths)) if !paths.is_empty() => {
+                let (output, input) = match paths.split_once(',') {
+                    Some((output, input)) => (output, Some(input.into())),
+                    None => (paths, None),
+                };
+                Ok(Self::File {
+                    output: output.into(),
+                    input,
+                })
+            }
+            Some(("tcp", address)) if !address.is_empty() => Ok(Self::Tcp(address.to_owned())),
+            _ => Err(format!(
+                "unknown UART backend `{s}`, expected `stdio`, `file:OUTPUT[,INPUT]`, `pty` or `tcp:ADDRESS`"
+            )),
+        }
+    }
+}
+
+impl UartBackend {
+    /// Opens the backend, waiting for the client in the case of a TCP server.
+    pub fn open(&self) -> Result<UARTInterfaceImpl, ConfigError> {
+        let backend = match self {
+            Self::Stdio => StreamBackend::new(io::stdout(), Some(spawn_reader(io::stdin()))),
+            Self::File { output, input } => {
+                let output = fs::File::create(output)
+                    .map_err(|err| ConfigError("Failed to create UART output file", Some(err)))?;
+                let input = input
+                    .as_ref()
+                    .map(fs::File::open)
+                    .transpose()
+                    .map_err(|err| ConfigError("Failed to open UART input file", Some(err)))?;
+                StreamBackend::new(output, input.map(spawn_reader))
+            }
+            #[cfg(target_os = "linux")]
+            Self::Pty => {
+                let (master, path) = pty::open()
+                    .map_err(|err| ConfigError("Failed to open a pseudo-terminal", Some(err)))?;
+                eprintln!("UART0 is connected to {}.", path.display());
+                let reader = master
+                    .try_clone()
+                    .map_err(|err| ConfigError("Failed to open a pseudo-terminal", Some(err)))?;
+                StreamBackend::new(pty::Master(master), Some(spawn_reader(pty::Master(reader))))
+            }
+            Self::Tcp(address) => {
+                let map_err = |err| ConfigError("Failed to set up UART TCP server", Some(err));
+                let listener = TcpListener::bind(address).map_err(map_err)?;
+                eprintln!(
+                    "Waiting for UART0 connection on {}.",
+                    listener.local_addr().map_err(map_err)?
+                );
+                let (stream, peer) = listener.accept().map_err(map_err)?;
+                info!("UART0 is connected to {peer}.");
+                let reader = stream.try_clone().map_err(map_err)?;
+                StreamBackend::new(stream, Some(spawn_reader(reader)))
+            }
+        };
+        Ok(Box::new(backend))
+    }
+}
+
+struct StreamBackend {
+    output: Box<dyn Write + Send + Sync>,
+    // Mutex is only for `Sync`, the receiver is accessed through `get_mut`.
+    input: Option<Mutex<Receiver<u8>>>,
+}
+
+impl StreamBackend {
+    fn new(output: impl Write + Send + Sync + 'static, input: Option<Receiver<u8>>) -> Self {
+        Self {
+            output: Box::new(output),
+            input: input.map(Mutex::new),
+        }
+    }
+}
+
+impl UnwindSafe for StreamBackend {}
+
+impl UARTInterface for StreamBackend {
+    fn send_byte(&mut self, byte: u8) {
+        if let Err(err) = self
+            .output
+            .write_all(&[byte])
+            .and_then(|()| self.output.flush())
+        {
+            warn!("UART0 output is closed: {err}.");
+            self.output = Box::new(io::sink());
+        }
+    }
+
+    fn receive_byte(&mut self) -> Option<u8> {
+        let input = self.input.as_mut()?;
+        input
+            .get_mut()
+            .expect("UART input is never shared")
+            .try_recv()
+            .ok()
+    }
+}
+
+/// Reads the `input` in a background thread, as the reads may block.
+fn spawn_reader(mut input: impl Read + Send + 'static) -> Receiver<u8> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut buf = [0; 256];
+        loop {
+            match input.read(&mut buf) {
+                Ok(0) => break,
+                Ok(len) => {
+                    if buf[..len].iter().try_for_each(|&b| sender.send(b)).is_err() {
+                        break;
+                    }
+                }
+                Err(err) if err.kind() == ErrorKind::Interrupted => {}
+                Err(err) if err.kind() == ErrorKind::WouldBlock => thread::sleep(POLL_INTERVAL),
+                Err(err) => {
+                    warn!("UART0 input is closed: {err}.");
+                    break;
+                }
+            }
+        }
+    });
+    receiver
+}
+
+#[cfg(target_os = "linux")]
+mod pty {
+    use std::ffi::{CStr, OsStr};
+    use std::fs::File;
+    use std::io::{self, ErrorKind, Read, Write};
+    use std::os::fd::{AsRawFd, FromRawFd};
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::PathBuf;
+
+    /// The master side of a pseudo-terminal.
+    ///
+    /// The terminal may have no slave side open: reading it fails with `EIO` then,
+    /// what is reported as `WouldBlock`, and writes are dropped.
+    pub(super) struct Master(pub(super) File);
+
+    impl Read for Master {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.0.read(buf).map_err(|err| {
+                if err.raw_os_error() == Some(libc::EIO) {
+                    ErrorKind::WouldBlock.into()
+                } else {
+                    err
+                }
+            })
+        }
+    }
+
+    impl Write for Master {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            match self.0.write(buf) {
+                Err(err)
+                    if err.kind() == ErrorKind::WouldBlock
+                        || err.raw_os_error() == Some(libc::EIO) =>
+                {
+                    Ok(buf.len())
+                }
+                res => res,
+            }
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            self.0.flush()
+        }
+    }
+
+    /// Opens a new nonblocking pseudo-terminal in the raw mode.
+    /// Returns its master side and the path of its slave side.
+    #[allow(unsafe_code, reason = "There are no wrappers in std.")]
+    pub(super) fn open() -> io::Result<(File, PathBuf)> {
+        let check = |res: libc::c_int| {
+            if res == 0 {
+                Ok(())
+            } else {
+                Err(io::Error::last_os_error())
+            }
+        };
+        // SAFETY: the call doesn't touch memory and the returned descriptor is checked.
+        let fd = unsafe { libc::posix_openpt(libc::O_RDWR | libc::O_NOCTTY | libc::O_NONBLOCK) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: the descriptor is valid and owned only by the file.
+        let master = unsafe { File::from_raw_fd(fd) };
+        let fd = master.as_raw_fd();
+
+        let mut name = [0; 64];
+        // SAFETY: `fd` is an open pseudo-terminal master, the buffer length is passed along,
+        // and `termios` is a plain C struct filled by `tcgetattr`.
+        unsafe {
+            check(libc::grantpt(fd))?;
+            check(libc::unlockpt(fd))?;
+            check(libc::ptsname_r(fd, name.as_mut_ptr(), name.len()))?;
+            let mut termios = std::mem::zeroed::<libc::termios>();
+            check(libc::tcgetattr(fd, &raw mut termios))?;
+            libc::cfmakeraw(&raw mut termios);
+            check(libc::tcsetattr(fd, libc::TCSANOW, &raw const termios))?;
+        }
+        // SAFETY: `ptsname_r` succeeded, so the name is NUL-terminated.
+        let name = unsafe { CStr::from_ptr(name.as_ptr()) };
+        Ok((master, OsStr::from_bytes(name.to_bytes()).into()))
+    }
+}